@@ -1,11 +1,40 @@
-interface AgentDef {
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+import { subscribeFile } from './watcher.js';
+
+export interface AgentDef {
   id: string;
   name: string;
   command: string;
+  /** May use {worktree}, {branch} and {task_name}; see the renderer's expandAgentArgs. */
   args: string[];
   resume_args: string[];
   skip_permissions_args: string[];
   description: string;
+  /** Extra environment for the agent process, under the task's own env. */
+  env?: Record<string, string>;
+  /** Short label (e.g. an emoji) shown next to the name. */
+  icon?: string;
+  /** Globs, relative to the worktree, for the agent's plan files; see plans.ts for defaults. */
+  plan_patterns?: string[];
+  /** Environment profile (see shell-env.ts) used over the project's. */
+  env_profile?: string;
+  /** Added by the user; built-in agents can't be changed or deleted. */
+  custom?: boolean;
+  /** Named flag sets a spawn can pick by id; filled in by listAgents. */
+  profiles?: AgentProfile[];
+}
+
+/** A named set of extra flags and env for an agent, e.g. a model or permission mode. */
+export interface AgentProfile {
+  id: string;
+  name: string;
+  /** Appended to the agent's arguments. */
+  args: string[];
+  env?: Record<string, string>;
+  /** Shipped with the app; can't be changed or deleted. */
+  builtin?: boolean;
 }
 
 const DEFAULT_AGENTS: AgentDef[] = [
@@ -38,6 +67,259 @@ const DEFAULT_AGENTS: AgentDef[] = [
   },
 ];
 
+const DEFAULT_PROFILES: Record<string, AgentProfile[]> = {
+  'claude-code': [
+    { id: 'plan', name: 'Plan mode', args: ['--permission-mode', 'plan'], builtin: true },
+    {
+      id: 'accept-edits',
+      name: 'Auto-accept edits',
+      args: ['--permission-mode', 'acceptEdits'],
+      builtin: true,
+    },
+  ],
+  codex: [{ id: 'read-only', name: 'Read-only', args: ['--sandbox', 'read-only'], builtin: true }],
+  gemini: [
+    {
+      id: 'auto-edit',
+      name: 'Auto-accept edits',
+      args: ['--approval-mode', 'auto_edit'],
+      builtin: true,
+    },
+  ],
+};
+
+let cached: AgentDef[] | null = null;
+let cachedProfiles: Record<string, AgentProfile[]> | null = null;
+
+function getCustomAgentsPath(): string {
+  return path.join(getStateDir(), 'custom-agents.json');
+}
+
+function readCustomAgents(): AgentDef[] {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getCustomAgentsPath(), 'utf8')) as AgentDef[];
+  } catch {
+    cached = [];
+  }
+  return cached;
+}
+
+function writeCustomAgents(agents: AgentDef[]): void {
+  const agentsPath = getCustomAgentsPath();
+  fs.mkdirSync(path.dirname(agentsPath), { recursive: true });
+  const tmpPath = agentsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(agents, null, 2), 'utf8');
+  fs.renameSync(tmpPath, agentsPath);
+  cached = agents;
+}
+
+/** Forget the cached files when custom-agents.json or agent-profiles.json is edited by hand. */
+export function watchAgentConfig(): void {
+  try {
+    fs.mkdirSync(getStateDir(), { recursive: true });
+    subscribeFile(getCustomAgentsPath(), {}, () => {
+      cached = null;
+    });
+    subscribeFile(getProfilesPath(), {}, () => {
+      cachedProfiles = null;
+    });
+  } catch (e) {
+    console.warn('Could not watch agent config:', e);
+  }
+}
+
+function getProfilesPath(): string {
+  return path.join(getStateDir(), 'agent-profiles.json');
+}
+
+/** User profiles per agent id; built-in ones come from DEFAULT_PROFILES. */
+function readUserProfiles(): Record<string, AgentProfile[]> {
+  if (cachedProfiles) return cachedProfiles;
+  try {
+    cachedProfiles = JSON.parse(fs.readFileSync(getProfilesPath(), 'utf8')) as Record<
+      string,
+      AgentProfile[]
+    >;
+  } catch {
+    cachedProfiles = {};
+  }
+  return cachedProfiles;
+}
+
+function writeUserProfiles(profiles: Record<string, AgentProfile[]>): void {
+  const profilesPath = getProfilesPath();
+  fs.mkdirSync(path.dirname(profilesPath), { recursive: true });
+  const tmpPath = profilesPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(profiles, null, 2), 'utf8');
+  fs.renameSync(tmpPath, profilesPath);
+  cachedProfiles = profiles;
+}
+
+function stringList(v: unknown, label: string): string[] {
+  if (v === undefined) return [];
+  if (!Array.isArray(v) || !v.every((a) => typeof a === 'string')) {
+    throw new Error(`${label} must be an array of strings`);
+  }
+  return v;
+}
+
+function validateEnv(value: unknown): Record<string, string> {
+  const env = value ?? {};
+  const isEnvEntry = ([k, v]: [string, unknown]) =>
+    /^[A-Za-z_][A-Za-z0-9_]*$/.test(k) && typeof v === 'string';
+  if (typeof env !== 'object' || Array.isArray(env) || !Object.entries(env).every(isEnvEntry)) {
+    throw new Error('env must map variable names to strings');
+  }
+  return env as Record<string, string>;
+}
+
+function validateAgentDef(value: unknown): AgentDef {
+  const d = value as Partial<Record<keyof AgentDef, unknown>> | null;
+  if (typeof d?.id !== 'string' || !/^[a-z0-9][a-z0-9-]*$/.test(d.id)) {
+    throw new Error('id must be lowercase letters, digits and dashes');
+  }
+  if (typeof d.name !== 'string' || !d.name.trim()) throw new Error('name must not be empty');
+  if (typeof d.command !== 'string' || !d.command.trim()) {
+    throw new Error('command must not be empty');
+  }
+  if (d.icon !== undefined && typeof d.icon !== 'string') throw new Error('icon must be a string');
+  if (d.env_profile !== undefined && typeof d.env_profile !== 'string') {
+    throw new Error('env_profile must be a string');
+  }
+  const planPatterns = stringList(d.plan_patterns, 'plan_patterns');
+  if (planPatterns.some((p) => path.isAbsolute(p) || p.split('/').includes('..'))) {
+    throw new Error('plan_patterns must stay inside the worktree');
+  }
+  return {
+    id: d.id,
+    name: d.name.trim(),
+    command: d.command.trim(),
+    args: stringList(d.args, 'args'),
+    resume_args: stringList(d.resume_args, 'resume_args'),
+    skip_permissions_args: stringList(d.skip_permissions_args, 'skip_permissions_args'),
+    description: typeof d.description === 'string' ? d.description : '',
+    env: validateEnv(d.env),
+    icon: d.icon?.trim() || undefined,
+    plan_patterns: planPatterns.length > 0 ? planPatterns : undefined,
+    env_profile: d.env_profile || undefined,
+    custom: true,
+  };
+}
+
+function assertCustom(id: string): void {
+  if (DEFAULT_AGENTS.some((a) => a.id === id)) {
+    throw new Error(`${id} is a built-in agent and can't be changed`);
+  }
+}
+
+function validateProfile(value: unknown): AgentProfile {
+  const p = value as Partial<Record<keyof AgentProfile, unknown>> | null;
+  if (typeof p?.id !== 'string' || !/^[a-z0-9][a-z0-9-]*$/.test(p.id)) {
+    throw new Error('Profile id must be lowercase letters, digits and dashes');
+  }
+  if (typeof p.name !== 'string' || !p.name.trim()) {
+    throw new Error('Profile name must not be empty');
+  }
+  return {
+    id: p.id,
+    name: p.name.trim(),
+    args: stringList(p.args, 'args'),
+    env: validateEnv(p.env),
+  };
+}
+
+function profilesFor(agentId: string): AgentProfile[] {
+  return [...(DEFAULT_PROFILES[agentId] ?? []), ...(readUserProfiles()[agentId] ?? [])];
+}
+
+function assertKnownAgent(agentId: string): void {
+  if (!listAgents().some((a) => a.id === agentId)) throw new Error(`Agent not found: ${agentId}`);
+}
+
 export function listAgents(): AgentDef[] {
-  return DEFAULT_AGENTS;
+  return [...DEFAULT_AGENTS, ...readCustomAgents()].map((a) => ({
+    ...a,
+    profiles: profilesFor(a.id),
+  }));
+}
+
+/** Agents and profiles the user added, without the built-in ones. */
+export function listUserAgentConfig(): {
+  agents: AgentDef[];
+  profiles: Record<string, AgentProfile[]>;
+} {
+  return { agents: readCustomAgents(), profiles: readUserProfiles() };
+}
+
+/** The profile a spawn asked for; throws if the agent has no such profile. */
+export function getAgentProfile(agentId: string, profileId: string): AgentProfile {
+  const profile = profilesFor(agentId).find((p) => p.id === profileId);
+  if (!profile) throw new Error(`Agent ${agentId} has no profile ${profileId}`);
+  return profile;
+}
+
+export function addAgentProfile(agentId: string, value: unknown): AgentProfile {
+  assertKnownAgent(agentId);
+  const profile = validateProfile(value);
+  if (profilesFor(agentId).some((p) => p.id === profile.id)) {
+    throw new Error(`Agent ${agentId} already has a profile ${profile.id}`);
+  }
+  const all = readUserProfiles();
+  writeUserProfiles({ ...all, [agentId]: [...(all[agentId] ?? []), profile] });
+  return profile;
+}
+
+export function updateAgentProfile(
+  agentId: string,
+  profileId: string,
+  value: unknown,
+): AgentProfile {
+  const all = readUserProfiles();
+  if (!(all[agentId] ?? []).some((p) => p.id === profileId)) {
+    throw new Error(`Profile not found or built in: ${profileId}`);
+  }
+  const profile = validateProfile({ ...(value as object), id: profileId });
+  writeUserProfiles({
+    ...all,
+    [agentId]: all[agentId].map((p) => (p.id === profileId ? profile : p)),
+  });
+  return profile;
+}
+
+export function deleteAgentProfile(agentId: string, profileId: string): void {
+  if ((DEFAULT_PROFILES[agentId] ?? []).some((p) => p.id === profileId)) {
+    throw new Error(`${profileId} is a built-in profile and can't be deleted`);
+  }
+  const all = readUserProfiles();
+  writeUserProfiles({
+    ...all,
+    [agentId]: (all[agentId] ?? []).filter((p) => p.id !== profileId),
+  });
+}
+
+export function addAgentDef(value: unknown): AgentDef {
+  const def = validateAgentDef(value);
+  assertCustom(def.id);
+  if (readCustomAgents().some((a) => a.id === def.id)) {
+    throw new Error(`An agent with id ${def.id} already exists`);
+  }
+  writeCustomAgents([...readCustomAgents(), def]);
+  return def;
+}
+
+export function updateAgentDef(id: string, value: unknown): AgentDef {
+  assertCustom(id);
+  const def = validateAgentDef({ ...(value as object), id });
+  const agents = readCustomAgents();
+  if (!agents.some((a) => a.id === id)) throw new Error(`Agent not found: ${id}`);
+  writeCustomAgents(agents.map((a) => (a.id === id ? def : a)));
+  return def;
+}
+
+export function deleteAgentDef(id: string): void {
+  assertCustom(id);
+  writeCustomAgents(readCustomAgents().filter((a) => a.id !== id));
+  const { [id]: _removed, ...profiles } = readUserProfiles();
+  writeUserProfiles(profiles);
 }
@@ -0,0 +1,196 @@
+import fs from 'fs';
+import path from 'path';
+import { expandCopyPatterns, restoreTaskBranch, snapshotTaskBranch } from './git.js';
+import { getStateDir } from './persistence.js';
+import { getRecordingsDir } from './recordings.js';
+import { deleteTask } from './tasks.js';
+
+// Archived tasks: the worktree is removed, but the branch (as a git bundle),
+// its final diff, terminal recordings and plan files are kept in app data so
+// finished work stays inspectable and can be checked out again.
+
+/** Agent plan files worth keeping, even when they are untracked or ignored. */
+export const PLAN_PATTERNS = [
+  'PLAN.md',
+  'plan.md',
+  '*.plan.md',
+  'plans/**/*.md',
+  '.claude/plans/**/*.md',
+];
+
+const BUNDLE_FILE = 'branch.bundle';
+const DIFF_FILE = 'final.diff';
+const PATCH_FILE = 'uncommitted.patch';
+
+export interface ArchivedTask {
+  /** Id of the archived task, which is also the archive's id. */
+  id: string;
+  name: string;
+  project_root: string;
+  branch_name: string;
+  main_branch: string;
+  head: string;
+  merge_base: string;
+  /** Agent the task ran, so a restore can start the same one. */
+  agent_id: string | null;
+  archived_at: number;
+  /** Absolute path of the archive directory, for opening the files it holds. */
+  path: string;
+  has_bundle: boolean;
+  has_uncommitted_changes: boolean;
+  recordings: string[];
+  plan_files: string[];
+}
+
+export interface ArchiveTaskParams {
+  taskId: string;
+  name: string;
+  projectRoot: string;
+  worktreePath: string;
+  branchName: string;
+  agentIds: string[];
+  agentId: string | null;
+}
+
+function validateId(id: string): void {
+  if (!/^[\w-]+$/.test(id)) throw new Error('taskId contains invalid characters');
+}
+
+function getArchivesDir(): string {
+  return path.join(getStateDir(), 'archives');
+}
+
+function getArchiveDir(taskId: string): string {
+  validateId(taskId);
+  return path.join(getArchivesDir(), taskId);
+}
+
+function readArchive(dir: string): ArchivedTask | null {
+  try {
+    const meta = JSON.parse(fs.readFileSync(path.join(dir, 'meta.json'), 'utf8')) as ArchivedTask;
+    return { ...meta, path: dir };
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Archive a task: snapshot its branch and files, then stop its agents and
+ * remove its worktree and branch. Nothing is removed if the snapshot fails.
+ */
+export async function archiveTask(params: ArchiveTaskParams): Promise<ArchivedTask> {
+  const dir = getArchiveDir(params.taskId);
+  // Assemble next to the final location so a failed archive leaves nothing behind
+  const partial = `${dir}.partial`;
+  fs.rmSync(partial, { recursive: true, force: true });
+  fs.mkdirSync(partial, { recursive: true });
+
+  let archived: ArchivedTask;
+  try {
+    const snapshot = await snapshotTaskBranch(
+      params.projectRoot,
+      params.worktreePath,
+      params.branchName,
+      path.join(partial, BUNDLE_FILE),
+    );
+    fs.writeFileSync(path.join(partial, DIFF_FILE), snapshot.diff);
+    if (snapshot.uncommitted) {
+      fs.writeFileSync(path.join(partial, PATCH_FILE), snapshot.uncommitted);
+    }
+
+    const planFiles = fs.existsSync(params.worktreePath)
+      ? await expandCopyPatterns(params.worktreePath, PLAN_PATTERNS)
+      : [];
+    for (const rel of planFiles) {
+      const target = path.join(partial, 'plans', rel);
+      fs.mkdirSync(path.dirname(target), { recursive: true });
+      fs.copyFileSync(path.join(params.worktreePath, rel), target);
+    }
+
+    archived = {
+      id: params.taskId,
+      name: params.name,
+      project_root: params.projectRoot,
+      branch_name: params.branchName,
+      main_branch: snapshot.main_branch,
+      head: snapshot.head,
+      merge_base: snapshot.merge_base,
+      agent_id: params.agentId,
+      archived_at: Date.now(),
+      path: dir,
+      has_bundle: snapshot.bundled,
+      has_uncommitted_changes: !!snapshot.uncommitted,
+      recordings: [],
+      plan_files: planFiles,
+    };
+  } catch (e) {
+    fs.rmSync(partial, { recursive: true, force: true });
+    throw new Error(`Could not archive task: ${e instanceof Error ? e.message : e}`);
+  }
+
+  await deleteTask(params.agentIds, params.branchName, true, params.projectRoot, params.taskId);
+
+  // Recordings are closed once the agents are gone; move them along
+  const recordingsDir = getRecordingsDir(params.taskId);
+  if (fs.existsSync(recordingsDir)) {
+    fs.renameSync(recordingsDir, path.join(partial, 'recordings'));
+    archived.recordings = fs.readdirSync(path.join(partial, 'recordings')).sort();
+  }
+
+  fs.writeFileSync(path.join(partial, 'meta.json'), JSON.stringify(archived, null, 2), 'utf8');
+  fs.rmSync(dir, { recursive: true, force: true });
+  fs.renameSync(partial, dir);
+  return archived;
+}
+
+/** Archived tasks, newest first. */
+export function listArchivedTasks(): ArchivedTask[] {
+  const root = getArchivesDir();
+  if (!fs.existsSync(root)) return [];
+  const result: ArchivedTask[] = [];
+  for (const name of fs.readdirSync(root)) {
+    if (name.endsWith('.partial')) continue;
+    const archived = readArchive(path.join(root, name));
+    if (archived) result.push(archived);
+  }
+  return result.sort((a, b) => b.archived_at - a.archived_at);
+}
+
+/**
+ * Bring an archived task back: recreate its branch and worktree, re-apply
+ * uncommitted changes and move its recordings back. The archive is removed
+ * unless those changes failed to apply.
+ */
+export async function restoreTask(
+  taskId: string,
+  symlinkDirs: string[] = [],
+): Promise<ArchivedTask & { worktree_path: string; patch_applied: boolean }> {
+  const dir = getArchiveDir(taskId);
+  const archived = readArchive(dir);
+  if (!archived) throw new Error(`Archived task not found: ${taskId}`);
+
+  const patchPath = path.join(dir, PATCH_FILE);
+  const worktree = await restoreTaskBranch(
+    archived.project_root,
+    archived.branch_name,
+    archived.head,
+    archived.has_bundle ? path.join(dir, BUNDLE_FILE) : null,
+    fs.existsSync(patchPath) ? patchPath : null,
+    symlinkDirs,
+  );
+
+  const recordingsDir = getRecordingsDir(taskId);
+  if (fs.existsSync(path.join(dir, 'recordings')) && !fs.existsSync(recordingsDir)) {
+    fs.mkdirSync(path.dirname(recordingsDir), { recursive: true });
+    fs.renameSync(path.join(dir, 'recordings'), recordingsDir);
+  }
+  // Without the patch applied, the archive is the only copy of those changes
+  if (worktree.patch_applied) fs.rmSync(dir, { recursive: true, force: true });
+
+  return {
+    ...archived,
+    branch_name: worktree.branch,
+    worktree_path: worktree.path,
+    patch_applied: worktree.patch_applied,
+  };
+}
@@ -0,0 +1,32 @@
+// GIT_ASKPASS / SSH_ASKPASS program for app-driven git commands.
+//
+// Git and ssh run this (through the wrapper script askpass.ts writes) with the
+// prompt as the only argument and read the answer from stdout. It runs under
+// ELECTRON_RUN_AS_NODE, forwards the prompt to the main process over the
+// socket in PCODE_ASKPASS_SOCKET and prints what the user typed. Exiting
+// non-zero tells git the prompt was cancelled.
+
+import net from 'net';
+import { createLineParser } from './session-protocol.js';
+
+const socketPath = process.env.PCODE_ASKPASS_SOCKET;
+if (!socketPath) process.exit(1);
+
+let answered = false;
+const sock = net.connect(socketPath, () => {
+  sock.write(JSON.stringify({ prompt: process.argv[2] ?? '' }) + '\n');
+});
+
+sock.on(
+  'data',
+  createLineParser<{ answer: string | null }>(({ answer }) => {
+    answered = true;
+    sock.end();
+    if (answer === null) process.exit(1);
+    process.stdout.write(answer + '\n', () => process.exit(0));
+  }),
+);
+sock.on('error', () => process.exit(1));
+sock.on('close', () => {
+  if (!answered) process.exit(1);
+});
@@ -0,0 +1,115 @@
+import { randomUUID } from 'crypto';
+import net from 'net';
+import fs from 'fs';
+import path from 'path';
+import { fileURLToPath } from 'url';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getStateDir } from './persistence.js';
+import { createLineParser } from './session-protocol.js';
+
+// Credential prompts for git and ssh. Commands that talk to a remote run with
+// GIT_ASKPASS/SSH_ASKPASS pointing at askpass-helper; the helper relays each
+// prompt here and the renderer answers it in a dialog. Terminal prompts are
+// disabled, since there is no terminal to type into.
+
+export interface AskpassPrompt {
+  id: string;
+  prompt: string;
+  /** Whether the answer should be masked (passwords, passphrases, tokens). */
+  secret: boolean;
+}
+
+const pending = new Map<string, (answer: string | null) => void>();
+let socketPath: string | null = null;
+let scriptPath: string | null = null;
+
+function getAskpassDir(): string {
+  const dir = path.join(getStateDir(), 'askpass');
+  fs.mkdirSync(dir, { recursive: true, mode: 0o700 });
+  return dir;
+}
+
+/** git and ssh only accept a program path, so wrap the helper in a script. */
+function writeWrapperScript(dir: string): string {
+  const helper = path.join(path.dirname(fileURLToPath(import.meta.url)), 'askpass-helper.js');
+  if (process.platform === 'win32') {
+    const script = path.join(dir, 'askpass.cmd');
+    const command = `"${process.execPath}" "${helper}" %*`;
+    fs.writeFileSync(script, `@echo off\r\nset ELECTRON_RUN_AS_NODE=1\r\n${command}\r\n`);
+    return script;
+  }
+  const quote = (s: string) => `'${s.replace(/'/g, "'\\''")}'`;
+  const script = path.join(dir, 'askpass.sh');
+  const command = `exec ${quote(process.execPath)} ${quote(helper)} "$@"`;
+  fs.writeFileSync(script, `#!/bin/sh\nELECTRON_RUN_AS_NODE=1 ${command}\n`, { mode: 0o700 });
+  return script;
+}
+
+/** Listen for helper connections and forward their prompts to `win`. */
+export function startAskpassServer(win: BrowserWindow): void {
+  if (socketPath) return;
+  const dir = getAskpassDir();
+  const listenPath =
+    process.platform === 'win32'
+      ? `\\\\.\\pipe\\parallel-code-askpass-${process.pid}`
+      : path.join(dir, `${process.pid}.sock`);
+
+  const server = net.createServer((sock) => {
+    const id = randomUUID();
+    sock.on(
+      'data',
+      createLineParser<{ prompt: string }>(({ prompt }) => {
+        if (pending.has(id)) return;
+        pending.set(id, (answer) => {
+          pending.delete(id);
+          if (!sock.destroyed) sock.end(JSON.stringify({ answer }) + '\n');
+        });
+        if (win.isDestroyed()) {
+          answerAskpass(id, null);
+          return;
+        }
+        const request: AskpassPrompt = {
+          id,
+          prompt,
+          secret: /password|passphrase|token|PIN/i.test(prompt),
+        };
+        win.webContents.send(IPC.AskpassPrompt, request);
+      }),
+    );
+    // git gave up (e.g. timed out) — close the dialog
+    sock.on('close', () => {
+      if (!pending.has(id)) return;
+      pending.delete(id);
+      if (!win.isDestroyed()) win.webContents.send(IPC.AskpassCancelled, { id });
+    });
+    sock.on('error', () => {});
+  });
+
+  if (process.platform !== 'win32') fs.rmSync(listenPath, { force: true });
+  server.listen(listenPath);
+  socketPath = listenPath;
+  scriptPath = writeWrapperScript(dir);
+}
+
+/** Reply to a prompt; null cancels it, which fails the git command. */
+export function answerAskpass(id: string, answer: string | null): void {
+  pending.get(id)?.(answer);
+}
+
+/**
+ * Environment for git commands that may need credentials. Without a running
+ * askpass server, prompts fail fast instead of hanging on a missing terminal.
+ */
+export function getAskpassEnv(): NodeJS.ProcessEnv {
+  const env: NodeJS.ProcessEnv = { ...process.env, GIT_TERMINAL_PROMPT: '0' };
+  if (!socketPath || !scriptPath) return env;
+  return {
+    ...env,
+    GIT_ASKPASS: scriptPath,
+    SSH_ASKPASS: scriptPath,
+    // OpenSSH otherwise only uses SSH_ASKPASS without a terminal and with DISPLAY set
+    SSH_ASKPASS_REQUIRE: 'force',
+    PCODE_ASKPASS_SOCKET: socketPath,
+  };
+}
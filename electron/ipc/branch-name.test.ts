@@ -0,0 +1,84 @@
+import { describe, it, expect, vi } from 'vitest';
+
+// branch-name.ts reaches persistence.ts, which reads Electron's app paths lazily.
+vi.mock('electron', () => ({ app: {} }));
+
+import {
+  renderBranchTemplate,
+  sanitizeBranchPrefix,
+  sanitizeRefName,
+  slug,
+} from './branch-name.js';
+
+// ---------------------------------------------------------------------------
+// slug
+// ---------------------------------------------------------------------------
+describe('slug', () => {
+  it.each([
+    ['Fix login bug', 'fix-login-bug'],
+    ['  Add   OAuth2 support!  ', 'add-oauth2-support'],
+    ['feat/API: v2', 'feat-api-v2'],
+    ['Ünïcödé names', 'n-c-d-names'],
+    ['---', ''],
+    ['a'.repeat(80), 'a'.repeat(72)],
+  ])('turns %j into %j', (name, expected) => {
+    expect(slug(name)).toBe(expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// sanitizeBranchPrefix
+// ---------------------------------------------------------------------------
+describe('sanitizeBranchPrefix', () => {
+  it.each([
+    ['task', 'task'],
+    ['Team A/Feature', 'team-a/feature'],
+    ['//', 'task'],
+    ['', 'task'],
+  ])('turns %j into %j', (prefix, expected) => {
+    expect(sanitizeBranchPrefix(prefix)).toBe(expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// sanitizeRefName
+// ---------------------------------------------------------------------------
+describe('sanitizeRefName', () => {
+  it.each([
+    ['feature/login', 'feature/login'],
+    ['has space', 'has-space'],
+    ['a..b', 'a.b'],
+    ['x~1^2:y?*[z]', 'x-1-2-y-z'],
+    ['ref@{1}', 'ref-1}'],
+    ['.hidden/name.lock', 'hidden/name'],
+    ['trailing./-dash-', 'trailing/dash'],
+    ['a//b\\c', 'a/b/c'],
+    ['@', ''],
+    ['tab\there', 'tab-here'],
+  ])('turns %j into %j', (name, expected) => {
+    expect(sanitizeRefName(name)).toBe(expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// renderBranchTemplate
+// ---------------------------------------------------------------------------
+describe('renderBranchTemplate', () => {
+  const values = {
+    title: 'Fix the Login Page',
+    prefix: 'Task',
+    user: 'Jane Doe',
+    date: new Date(2024, 0, 5),
+  };
+
+  it.each([
+    ['{prefix}/{slug}', 'task/fix-the-login-page'],
+    ['{user}/{task-slug}', 'jane-doe/fix-the-login-page'],
+    ['{date}-{slug}', '2024-01-05-fix-the-login-page'],
+    ['{prefix}/{unknown}', 'task/{unknown}'],
+    ['wip {slug}', 'wip-fix-the-login-page'],
+    ['{prefix}/..{slug}', 'task/fix-the-login-page'],
+  ])('renders %j as %j', (template, expected) => {
+    expect(renderBranchTemplate(template, values)).toBe(expected);
+  });
+});
@@ -0,0 +1,135 @@
+import fs from 'fs';
+import os from 'os';
+import { branchExists, getGitUserName, isValidBranchName } from './git.js';
+import { getWorktreeRoot } from './worktree-location.js';
+
+const MAX_SLUG_LEN = 72;
+const MAX_COLLISION_SUFFIX = 100;
+
+/** Placeholders: {prefix}, {slug} (alias {task-slug}), {user}, {date} (YYYY-MM-DD). */
+export const DEFAULT_BRANCH_TEMPLATE = '{prefix}/{slug}';
+
+export function slug(name: string): string {
+  let result = '';
+  let prevWasHyphen = false;
+  for (const c of name.toLowerCase()) {
+    if (result.length >= MAX_SLUG_LEN) break;
+    if (/[a-z0-9]/.test(c)) {
+      result += c;
+      prevWasHyphen = false;
+    } else if (!prevWasHyphen) {
+      result += '-';
+      prevWasHyphen = true;
+    }
+  }
+  return result.replace(/^-+|-+$/g, '');
+}
+
+export function sanitizeBranchPrefix(prefix: string): string {
+  const parts = prefix
+    .split('/')
+    .map(slug)
+    .filter((p) => p.length > 0);
+  return parts.length === 0 ? 'task' : parts.join('/');
+}
+
+/**
+ * Make an arbitrary string a valid branch name per git-check-ref-format:
+ * no control characters, spaces, `~^:?*[\`, `..` or `@{`; no component that
+ * starts with `.` or ends with `.lock`; no empty components.
+ */
+export function sanitizeRefName(name: string): string {
+  return name
+    .replace(/\\/g, '/')
+    .split('/')
+    .map((part) =>
+      part
+        // eslint-disable-next-line no-control-regex
+        .replace(/[\x00-\x20\x7f~^:?*[\]]+/g, '-')
+        .replace(/\.{2,}/g, '.')
+        .replace(/@\{/g, '-')
+        .replace(/^[.-]+/, '')
+        .replace(/(\.lock)+$/i, '')
+        .replace(/[.-]+$/, ''),
+    )
+    .filter((part) => part.length > 0 && part !== '@')
+    .join('/');
+}
+
+export interface BranchTemplateValues {
+  title: string;
+  prefix: string;
+  user: string;
+  date: Date;
+}
+
+export function renderBranchTemplate(template: string, values: BranchTemplateValues): string {
+  const d = values.date;
+  const pad = (n: number) => String(n).padStart(2, '0');
+  const vars: Record<string, string> = {
+    prefix: sanitizeBranchPrefix(values.prefix),
+    slug: slug(values.title),
+    'task-slug': slug(values.title),
+    user: slug(values.user),
+    date: `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())}`,
+  };
+  const rendered = template.replace(/\{([a-z-]+)\}/g, (m, key: string) => vars[key] ?? m);
+  return sanitizeRefName(rendered);
+}
+
+async function currentUser(repoRoot: string): Promise<string> {
+  const configured = await getGitUserName(repoRoot).catch(() => '');
+  if (configured) return configured;
+  try {
+    return os.userInfo().username;
+  } catch {
+    return 'user';
+  }
+}
+
+/** Render the branch name for a task title; throws if the result isn't a valid ref. */
+export async function buildBranchName(
+  repoRoot: string,
+  title: string,
+  prefix: string,
+  template = DEFAULT_BRANCH_TEMPLATE,
+): Promise<string> {
+  const name = renderBranchTemplate(template || DEFAULT_BRANCH_TEMPLATE, {
+    title,
+    prefix,
+    user: template.includes('{user}') ? await currentUser(repoRoot) : '',
+    date: new Date(),
+  });
+  if (!name || !(await isValidBranchName(repoRoot, name))) {
+    throw new Error(`Cannot derive a valid branch name from "${title}" with "${template}"`);
+  }
+  return name;
+}
+
+function isTaken(repoRoot: string, name: string): Promise<boolean> {
+  if (fs.existsSync(`${getWorktreeRoot(repoRoot)}/${name}`)) return Promise.resolve(true);
+  return branchExists(repoRoot, name);
+}
+
+/**
+ * Suggest a branch for a new task. If the templated name is already a branch
+ * (or a worktree directory), a numeric suffix is appended until it's free.
+ */
+export async function suggestBranchName(
+  repoRoot: string,
+  title: string,
+  prefix: string,
+  template?: string,
+): Promise<{ branch_name: string; base_name: string; collision: boolean }> {
+  const base = await buildBranchName(repoRoot, title, prefix, template);
+  if (!(await isTaken(repoRoot, base))) {
+    return { branch_name: base, base_name: base, collision: false };
+  }
+  for (let n = 2; n <= MAX_COLLISION_SUFFIX; n++) {
+    const candidate = `${base}-${n}`;
+    if (!(await isTaken(repoRoot, candidate))) {
+      return { branch_name: candidate, base_name: base, collision: true };
+    }
+  }
+  throw new Error(`No free branch name for "${base}"`);
+}
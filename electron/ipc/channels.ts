@@ -3,16 +3,112 @@ export enum IPC {
   SpawnAgent = 'spawn_agent',
   WriteToAgent = 'write_to_agent',
   ResizeAgent = 'resize_agent',
+  PauseAgentOutput = 'pause_agent_output',
+  ResumeAgentOutput = 'resume_agent_output',
   PauseAgent = 'pause_agent',
   ResumeAgent = 'resume_agent',
   KillAgent = 'kill_agent',
   CountRunningAgents = 'count_running_agents',
   KillAllAgents = 'kill_all_agents',
   ListAgents = 'list_agents',
+  GetAgentScrollback = 'get_agent_scrollback',
+  ListDetachedSessions = 'list_detached_sessions',
+  ReattachAgent = 'reattach_agent',
+  GetAgentStats = 'get_agent_stats',
+  AgentStats = 'agent-stats',
+  ListRecordings = 'list_recordings',
+  ExportRecording = 'export_recording',
+  AgentIdle = 'agent-idle',
+  SubscribeOutputPattern = 'subscribe_output_pattern',
+  UnsubscribeOutputPattern = 'unsubscribe_output_pattern',
+  PatternMatched = 'pattern-matched',
+  SetAgentLimit = 'set_agent_limit',
+  GetSpawnQueue = 'get_spawn_queue',
+  AgentQueued = 'agent-queued',
+  AgentDequeued = 'agent-dequeued',
+  WriteBytesToAgent = 'write_bytes_to_agent',
+  RestartAgent = 'restart_agent',
+  GetAgentEnv = 'get_agent_env',
+  SetOutputThrottle = 'set_output_throttle',
+  AckAgentOutput = 'ack_agent_output',
+  Backpressure = 'backpressure',
+  AttachOutputChannel = 'attach_output_channel',
+  DetachOutputChannel = 'detach_output_channel',
+  SendKey = 'send_key',
+  AgentExited = 'agent-exited',
+  GetSpawnPolicy = 'get_spawn_policy',
+  SetSpawnPolicy = 'set_spawn_policy',
+  GetAgentCwd = 'get_agent_cwd',
+  AgentCwdChanged = 'agent-cwd-changed',
+  AgentTitleChanged = 'agent-title-changed',
+  AgentNotification = 'agent-notification',
+  AgentTimeout = 'agent-timeout',
+  ListRunningAgents = 'list_running_agents',
+  TuneAgentOutput = 'tune_agent_output',
+  AddAgentDef = 'add_agent_def',
+  UpdateAgentDef = 'update_agent_def',
+  DeleteAgentDef = 'delete_agent_def',
+  AddAgentProfile = 'add_agent_profile',
+  UpdateAgentProfile = 'update_agent_profile',
+  DeleteAgentProfile = 'delete_agent_profile',
+  RunAgentHeadless = 'run_agent_headless',
+  CancelHeadlessRun = 'cancel_headless_run',
+  AgentEvent = 'agent-event',
+  RespondToPermission = 'respond_to_permission',
+  AgentPermissionRequest = 'agent-permission-request',
+  AgentPermissionResolved = 'agent-permission-resolved',
+  RefreshLoginPath = 'refresh_login_path',
+  ListEnvProfiles = 'list_env_profiles',
+  SaveEnvProfile = 'save_env_profile',
+  DeleteEnvProfile = 'delete_env_profile',
 
   // Task
   CreateTask = 'create_task',
   DeleteTask = 'delete_task',
+  ListTaskTemplates = 'list_task_templates',
+  SaveTaskTemplate = 'save_task_template',
+  DeleteTaskTemplate = 'delete_task_template',
+  CreateTaskFromTemplate = 'create_task_from_template',
+  SetTaskDependencies = 'set_task_dependencies',
+  GetTaskDependencies = 'get_task_dependencies',
+  TaskUnblocked = 'task-unblocked',
+  ArchiveTask = 'archive_task',
+  ListArchivedTasks = 'list_archived_tasks',
+  RestoreTask = 'restore_task',
+  GetTaskStatuses = 'get_task_statuses',
+  SetTaskStatus = 'set_task_status',
+  TaskStatusChanged = 'task-status-changed',
+  SuggestTaskName = 'suggest_task_name',
+  GetTaskMetrics = 'get_task_metrics',
+  GetMetricsReport = 'get_metrics_report',
+  UpdateTaskMetadata = 'update_task_metadata',
+  QueryTasks = 'query_tasks',
+  ListTaskLabels = 'list_task_labels',
+  ForkTask = 'fork_task',
+  GetTaskNotes = 'get_task_notes',
+  UpdateTaskNotes = 'update_task_notes',
+  ListTaskAgents = 'list_task_agents',
+  StartReviewAgent = 'start_review_agent',
+  ReviewCompleted = 'review-completed',
+  HandoffTask = 'handoff_task',
+  QueryTaskHistory = 'query_task_history',
+  ListTaskSessions = 'list_task_sessions',
+  QueryEvents = 'query_events',
+  WatchPath = 'watch_path',
+  Unwatch = 'unwatch',
+  PathChanged = 'path-changed',
+  WatchForPlans = 'watch_for_plans',
+  UnwatchPlans = 'unwatch_plans',
+  PlanChanged = 'plan-changed',
+  GetPlanHistory = 'get_plan_history',
+  GetPlanDiff = 'get_plan_diff',
+  WritePlanFile = 'write_plan_file',
+  ParsePlan = 'parse_plan',
+  TogglePlanItem = 'toggle_plan_item',
+  ApprovePlan = 'approve_plan',
+  RequestPlanChanges = 'request_plan_changes',
+  GetPlanApprovals = 'get_plan_approvals',
+  PlanApprovalChanged = 'plan-approval-changed',
 
   // Git
   GetChangedFiles = 'get_changed_files',
@@ -26,10 +122,77 @@ export enum IPC {
   RebaseTask = 'rebase_task',
   GetMainBranch = 'get_main_branch',
   GetCurrentBranch = 'get_current_branch',
+  CreateTaskWorktree = 'create_task_worktree',
+  RemoveTaskWorktree = 'remove_task_worktree',
+  ListWorktrees = 'list_worktrees',
+  PruneWorktrees = 'prune_worktrees',
+  ResolveConflict = 'resolve_conflict',
+  ContinueMerge = 'continue_merge',
+  AbortMerge = 'abort_merge',
+  CommitTask = 'commit_task',
+  GetFileHunks = 'get_file_hunks',
+  StageHunk = 'stage_hunk',
+  UnstageHunk = 'unstage_hunk',
+  DiscardFileChanges = 'discard_file_changes',
+  DiscardAllChanges = 'discard_all_changes',
+  StashChanges = 'stash_changes',
+  ListStashes = 'list_stashes',
+  ApplyStash = 'apply_stash',
+  DropStash = 'drop_stash',
+  GetFileDiffBetween = 'get_file_diff_between',
+  GetTaskDiffVsBase = 'get_task_diff_vs_base',
+  WatchWorktreeChanges = 'watch_worktree_changes',
+  UnwatchWorktreeChanges = 'unwatch_worktree_changes',
+  ChangedFilesUpdated = 'changed-files-updated',
+  PreviewMerge = 'preview_merge',
+  GetRebasePlan = 'get_rebase_plan',
+  ExecuteRebasePlan = 'execute_rebase_plan',
+  GetCommit = 'get_commit',
+  GetCommitFileDiff = 'get_commit_file_diff',
+  FetchAndCompare = 'fetch_and_compare',
+  SetBackgroundFetch = 'set_background_fetch',
+  BranchDiverged = 'branch-diverged',
+  GetWorktreesDiskUsage = 'get_worktrees_disk_usage',
+  CleanupStaleWorktrees = 'cleanup_stale_worktrees',
+  GetWorktreeLocation = 'get_worktree_location',
+  SetWorktreeLocation = 'set_worktree_location',
+  SuggestBranchName = 'suggest_branch_name',
+  GetMainBranchInfo = 'get_main_branch_info',
+  SetTaskBaseBranch = 'set_task_base_branch',
+  GetRunGitHooks = 'get_run_git_hooks',
+  SetRunGitHooks = 'set_run_git_hooks',
+  GitHookOutput = 'git-hook-output',
+  GetFileBlame = 'get_file_blame',
+  SearchWorktree = 'search_worktree',
+  CancelSearch = 'cancel_search',
+  SearchResults = 'search-results',
+  ListWorktreeTree = 'list_worktree_tree',
+  ReadWorktreeFile = 'read_worktree_file',
+  WriteWorktreeFile = 'write_worktree_file',
+  GetLinkedChangedFiles = 'get_linked_changed_files',
+  MergeLinkedTasks = 'merge_linked_tasks',
+  GetWorktreeCopyFiles = 'get_worktree_copy_files',
+  SetWorktreeCopyFiles = 'set_worktree_copy_files',
+  GetWorktreeSetupCommand = 'get_worktree_setup_command',
+  SetWorktreeSetupCommand = 'set_worktree_setup_command',
+  GetSigningConfig = 'get_signing_config',
+  GetCredentialStatus = 'get_credential_status',
+  AnswerAskpass = 'answer_askpass',
+  AskpassPrompt = 'askpass-prompt',
+  AskpassCancelled = 'askpass-cancelled',
+  ContinueWorktreeOperation = 'continue_worktree_operation',
+  AbortWorktreeOperation = 'abort_worktree_operation',
 
   // Persistence
   SaveAppState = 'save_app_state',
   LoadAppState = 'load_app_state',
+  GetSettings = 'get_settings',
+  UpdateSettings = 'update_settings',
+  SettingsChanged = 'settings-changed',
+  ListStateBackups = 'list_state_backups',
+  RestoreStateBackup = 'restore_state_backup',
+  ExportWorkspace = 'export_workspace',
+  ImportWorkspace = 'import_workspace',
 
   // Window
   WindowIsFocused = '__window_is_focused',
@@ -50,10 +213,15 @@ export enum IPC {
   WindowResized = '__window_resized',
   WindowMoved = '__window_moved',
   WindowCloseRequested = '__window_close_requested',
+  GetNotificationSettings = 'get_notification_settings',
+  SetNotificationSettings = 'set_notification_settings',
+  TestNotification = 'test_notification',
+  NotificationClicked = 'notification-clicked',
 
   // Dialog
   DialogConfirm = '__dialog_confirm',
   DialogOpen = '__dialog_open',
+  DialogSave = '__dialog_save',
 
   // Shell
   ShellReveal = '__shell_reveal',
@@ -62,4 +230,41 @@ export enum IPC {
   StartRemoteServer = 'start_remote_server',
   StopRemoteServer = 'stop_remote_server',
   GetRemoteStatus = 'get_remote_status',
+
+  // Integrations
+  CreatePullRequest = 'create_pull_request',
+  SetIntegrationToken = 'set_integration_token',
+  HasIntegrationToken = 'has_integration_token',
+  GetPullRequestStatus = 'get_pull_request_status',
+  PrStatusChanged = 'pr-status-changed',
+  OpenInEditor = 'open_in_editor',
+  DetectEditors = 'detect_editors',
+  GetEditorSettings = 'get_editor_settings',
+  SetEditorSettings = 'set_editor_settings',
+  SetSecret = 'set_secret',
+  GetSecret = 'get_secret',
+  DeleteSecret = 'delete_secret',
+  ListSecrets = 'list_secrets',
+
+  // Projects
+  AddProject = 'add_project',
+  ListProjects = 'list_projects',
+  RemoveProject = 'remove_project',
+  ListMcpServers = 'list_mcp_servers',
+  AddMcpServer = 'add_mcp_server',
+  ToggleMcpServer = 'toggle_mcp_server',
+  GetProjectEnvProfile = 'get_project_env_profile',
+  SetProjectEnvProfile = 'set_project_env_profile',
+
+  // Prompts
+  ListPromptSnippets = 'list_prompt_snippets',
+  SavePromptSnippet = 'save_prompt_snippet',
+  DeletePromptSnippet = 'delete_prompt_snippet',
+  SendPrompt = 'send_prompt',
+
+  // Diagnostics
+  GetBackendLogs = 'get_backend_logs',
+  GetLogLevel = 'get_log_level',
+  SetLogLevel = 'set_log_level',
+  ListActiveWatchers = 'list_active_watchers',
 }
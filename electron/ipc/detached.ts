@@ -0,0 +1,224 @@
+import { spawn } from 'child_process';
+import { createHash } from 'crypto';
+import net from 'net';
+import fs from 'fs';
+import path from 'path';
+import { fileURLToPath } from 'url';
+import type * as pty from 'node-pty';
+import { getStateDir } from './persistence.js';
+import {
+  createLineParser,
+  type DetachedSessionMeta,
+  type HostCommand,
+  type HostMessage,
+  type SessionHostSpec,
+} from './session-protocol.js';
+
+const HOST_READY_TIMEOUT = 5_000; // ms
+const HOST_READY_POLL = 50; // ms
+
+/** PTY-like handle backed by a session host process instead of a local PTY. */
+export interface DetachedPty {
+  pid: number;
+  cols: number;
+  write: (data: string | Buffer) => void;
+  resize: (cols: number, rows: number) => void;
+  pause: () => void;
+  resume: () => void;
+  kill: (signal?: string) => void;
+  onData: pty.IEvent<string>;
+  onExit: pty.IEvent<{ exitCode: number; signal?: number }>;
+  /** Scrollback the host replays on connect; kept apart from live output. */
+  onReplay: pty.IEvent<Buffer>;
+  /** Close the connection but leave the session running in the host. */
+  detach: () => void;
+}
+
+function getSessionsDir(): string {
+  const dir = path.join(getStateDir(), 'sessions');
+  fs.mkdirSync(dir, { recursive: true, mode: 0o700 });
+  return dir;
+}
+
+function validateAgentId(agentId: string): void {
+  // The ID names files under the sessions dir, so it must not contain path separators
+  if (!/^[\w-]+$/.test(agentId)) throw new Error('agentId contains invalid characters');
+}
+
+function sessionFiles(agentId: string): { metaPath: string; socketPath: string } {
+  validateAgentId(agentId);
+  const dir = getSessionsDir();
+  // Unix socket paths are limited to ~104 bytes on macOS — keep the name short
+  const short = createHash('sha1').update(agentId).digest('hex').slice(0, 12);
+  return {
+    metaPath: path.join(dir, `${agentId}.json`),
+    socketPath: path.join(dir, `${short}.sock`),
+  };
+}
+
+function isProcessAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+function connectOnce(socketPath: string): Promise<net.Socket> {
+  return new Promise((resolve, reject) => {
+    const sock = net.createConnection(socketPath);
+    sock.once('connect', () => {
+      sock.removeAllListeners('error');
+      resolve(sock);
+    });
+    sock.once('error', reject);
+  });
+}
+
+async function connectWithRetry(socketPath: string, timeoutMs: number): Promise<net.Socket> {
+  const deadline = Date.now() + timeoutMs;
+  let lastErr: unknown;
+  while (Date.now() <= deadline) {
+    try {
+      return await connectOnce(socketPath);
+    } catch (e) {
+      lastErr = e;
+      await new Promise((r) => setTimeout(r, HOST_READY_POLL));
+    }
+  }
+  throw new Error(`Session host did not become ready: ${lastErr}`);
+}
+
+function wrapSocket(sock: net.Socket, pid: number, initialCols: number): DetachedPty {
+  const dataListeners = new Set<(data: string) => void>();
+  const replayListeners = new Set<(data: Buffer) => void>();
+  const exitListeners = new Set<(e: { exitCode: number; signal?: number }) => void>();
+  let exited = false;
+  let detached = false;
+  let cols = initialCols;
+
+  const emitExit = (e: { exitCode: number; signal?: number }) => {
+    if (exited) return;
+    exited = true;
+    exitListeners.forEach((fn) => fn(e));
+  };
+
+  sock.on(
+    'data',
+    createLineParser<HostMessage>((msg) => {
+      switch (msg.type) {
+        case 'scrollback': {
+          const replay = Buffer.from(msg.data, 'base64');
+          if (replay.length > 0) replayListeners.forEach((fn) => fn(replay));
+          break;
+        }
+        case 'data':
+          dataListeners.forEach((fn) => fn(msg.data));
+          break;
+        case 'exit':
+          emitExit({ exitCode: msg.exitCode, signal: msg.signal ?? undefined });
+          break;
+      }
+    }),
+  );
+  sock.on('error', () => {});
+  sock.on('close', () => {
+    // Host vanished without reporting an exit (crash or external kill)
+    if (!detached) emitExit({ exitCode: -1 });
+  });
+
+  const sendCmd = (cmd: HostCommand) => {
+    if (!sock.destroyed) sock.write(JSON.stringify(cmd) + '\n');
+  };
+
+  return {
+    pid,
+    get cols() {
+      return cols;
+    },
+    write: (data) =>
+      typeof data === 'string'
+        ? sendCmd({ type: 'write', data })
+        : sendCmd({ type: 'write_bytes', data: data.toString('base64') }),
+    resize: (c, rows) => {
+      cols = c;
+      sendCmd({ type: 'resize', cols: c, rows });
+    },
+    pause: () => sendCmd({ type: 'pause' }),
+    resume: () => sendCmd({ type: 'resume' }),
+    kill: (signal) => sendCmd({ type: 'kill', signal }),
+    onData: (listener) => {
+      dataListeners.add(listener);
+      return { dispose: () => dataListeners.delete(listener) };
+    },
+    onExit: (listener) => {
+      exitListeners.add(listener);
+      return { dispose: () => exitListeners.delete(listener) };
+    },
+    onReplay: (listener) => {
+      replayListeners.add(listener);
+      return { dispose: () => replayListeners.delete(listener) };
+    },
+    detach: () => {
+      detached = true;
+      sock.end();
+    },
+  };
+}
+
+/** Launch a session host process for a detachable agent and connect to it. */
+export async function spawnDetachedPty(
+  spec: Omit<SessionHostSpec, 'socketPath' | 'metaPath'>,
+): Promise<DetachedPty> {
+  const { metaPath, socketPath } = sessionFiles(spec.agentId);
+  const specPath = path.join(getSessionsDir(), `${spec.agentId}.spec`);
+  const fullSpec: SessionHostSpec = { ...spec, socketPath, metaPath };
+  fs.writeFileSync(specPath, JSON.stringify(fullSpec), { mode: 0o600 });
+
+  const hostScript = path.join(path.dirname(fileURLToPath(import.meta.url)), 'session-host.js');
+  const child = spawn(process.execPath, [hostScript, specPath], {
+    detached: true,
+    stdio: 'ignore',
+    env: { ...process.env, ELECTRON_RUN_AS_NODE: '1' },
+  });
+  child.unref();
+
+  const sock = await connectWithRetry(socketPath, HOST_READY_TIMEOUT);
+  // The host writes its metadata before accepting connections
+  const meta = JSON.parse(fs.readFileSync(metaPath, 'utf8')) as DetachedSessionMeta;
+  return wrapSocket(sock, meta.pid, spec.cols);
+}
+
+/** List session hosts that are still running, removing stale metadata. */
+export function listDetachedSessions(): DetachedSessionMeta[] {
+  const dir = getSessionsDir();
+  const result: DetachedSessionMeta[] = [];
+  for (const name of fs.readdirSync(dir)) {
+    if (!name.endsWith('.json')) continue;
+    const metaPath = path.join(dir, name);
+    try {
+      const meta = JSON.parse(fs.readFileSync(metaPath, 'utf8')) as DetachedSessionMeta;
+      if (isProcessAlive(meta.hostPid)) {
+        result.push(meta);
+      } else {
+        fs.rmSync(metaPath, { force: true });
+        fs.rmSync(meta.socketPath, { force: true });
+      }
+    } catch {
+      /* ignore malformed metadata */
+    }
+  }
+  return result.sort((a, b) => a.startedAt - b.startedAt);
+}
+
+/** Connect to an existing session host by agent ID. */
+export async function connectDetachedPty(
+  agentId: string,
+  cols: number,
+): Promise<{ proc: DetachedPty; meta: DetachedSessionMeta }> {
+  const meta = listDetachedSessions().find((m) => m.agentId === agentId);
+  if (!meta) throw new Error(`Detached session not found: ${agentId}`);
+  const sock = await connectOnce(meta.socketPath);
+  return { proc: wrapSocket(sock, meta.pid, cols), meta };
+}
@@ -0,0 +1,66 @@
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { fetchAndCompare, type RemoteComparison } from './git.js';
+
+const MIN_FETCH_INTERVAL_SECS = 60;
+
+interface TrackedBranch {
+  worktreePath: string;
+  lastBehind: number | null;
+  timer: ReturnType<typeof setInterval> | null;
+}
+
+const tracked = new Map<string, TrackedBranch>();
+
+function getTracked(taskId: string, worktreePath: string): TrackedBranch {
+  let t = tracked.get(taskId);
+  if (!t || t.worktreePath !== worktreePath) {
+    if (t?.timer) clearInterval(t.timer);
+    t = { worktreePath, lastBehind: null, timer: null };
+    tracked.set(taskId, t);
+  }
+  return t;
+}
+
+/**
+ * Fetch and compare a task branch against the remote main. Emits
+ * `branch-diverged` whenever the behind count grows (or first becomes non-zero).
+ */
+export async function checkBranchDivergence(
+  win: BrowserWindow,
+  taskId: string,
+  worktreePath: string,
+): Promise<RemoteComparison> {
+  const t = getTracked(taskId, worktreePath);
+  const result = await fetchAndCompare(worktreePath);
+  const previous = t.lastBehind ?? 0;
+  t.lastBehind = result.behind;
+  if (result.behind > previous && !win.isDestroyed()) {
+    win.webContents.send(IPC.BranchDiverged, { task_id: taskId, ...result });
+  }
+  return result;
+}
+
+/** Start (or, with a null interval, stop) periodic background fetches for a task. */
+export function setBackgroundFetch(
+  win: BrowserWindow,
+  taskId: string,
+  worktreePath: string,
+  intervalSecs: number | null,
+): void {
+  if (intervalSecs === null) {
+    const t = tracked.get(taskId);
+    if (t?.timer) clearInterval(t.timer);
+    tracked.delete(taskId);
+    return;
+  }
+  if (!Number.isFinite(intervalSecs) || intervalSecs < MIN_FETCH_INTERVAL_SECS) {
+    throw new Error(`intervalSecs must be at least ${MIN_FETCH_INTERVAL_SECS}`);
+  }
+  const t = getTracked(taskId, worktreePath);
+  if (t.timer) clearInterval(t.timer);
+  t.timer = setInterval(() => {
+    // Offline or no remote — try again next tick
+    checkBranchDivergence(win, taskId, worktreePath).catch(() => {});
+  }, intervalSecs * 1000);
+}
@@ -0,0 +1,197 @@
+import { spawn } from 'child_process';
+import fs from 'fs';
+import path from 'path';
+import { shell } from 'electron';
+import { getStateDir } from './persistence.js';
+
+// Open a worktree, or a file at a line, in the user's editor. Known GUI
+// editors are detected on PATH; a custom command template overrides them.
+
+interface EditorDef {
+  id: string;
+  name: string;
+  bins: string[];
+  /** Arguments to open `file` at `line` inside the `dir` workspace. */
+  fileArgs: (dir: string, file: string, line: number) => string[];
+}
+
+const vscodeLike = (dir: string, file: string, line: number) => [dir, '--goto', `${file}:${line}`];
+const jetbrains = (dir: string, file: string, line: number) => [dir, '--line', String(line), file];
+
+// In order of preference when several are installed
+const EDITORS: EditorDef[] = [
+  { id: 'vscode', name: 'Visual Studio Code', bins: ['code'], fileArgs: vscodeLike },
+  { id: 'cursor', name: 'Cursor', bins: ['cursor'], fileArgs: vscodeLike },
+  { id: 'windsurf', name: 'Windsurf', bins: ['windsurf'], fileArgs: vscodeLike },
+  { id: 'zed', name: 'Zed', bins: ['zed', 'zeditor'], fileArgs: (d, f, l) => [d, `${f}:${l}`] },
+  {
+    id: 'jetbrains',
+    name: 'JetBrains IDE',
+    bins: ['idea', 'webstorm', 'pycharm', 'goland', 'rustrover', 'clion', 'phpstorm', 'rider'],
+    fileArgs: jetbrains,
+  },
+  { id: 'sublime', name: 'Sublime Text', bins: ['subl'], fileArgs: (d, f, l) => [d, `${f}:${l}`] },
+  // GUI wrappers around vim; terminal vim has no window to open in
+  {
+    id: 'vim',
+    name: 'Vim (GUI)',
+    bins: ['gvim', 'mvim', 'nvim-qt', 'neovide'],
+    fileArgs: (_d, f, l) => [`+${l}`, f],
+  },
+];
+
+export interface DetectedEditor {
+  id: string;
+  name: string;
+  command: string;
+}
+
+export interface EditorSettings {
+  /**
+   * Command line with {file}, {line} and {dir} placeholders, e.g.
+   * `code --goto {file}:{line}`. null uses the first detected editor.
+   */
+  command_template: string | null;
+}
+
+// --- Settings ---
+
+let cached: EditorSettings | null = null;
+
+function getSettingsPath(): string {
+  return path.join(getStateDir(), 'editor.json');
+}
+
+export function getEditorSettings(): EditorSettings {
+  if (cached) return cached;
+  try {
+    const parsed = JSON.parse(fs.readFileSync(getSettingsPath(), 'utf8')) as EditorSettings;
+    cached = { command_template: parsed.command_template || null };
+  } catch {
+    cached = { command_template: null };
+  }
+  return cached;
+}
+
+export function setEditorSettings(settings: EditorSettings): EditorSettings {
+  const template = settings.command_template?.trim() || null;
+  if (template && splitCommand(template).length === 0) {
+    throw new Error('Editor command must not be empty');
+  }
+  const next: EditorSettings = { command_template: template };
+  const settingsPath = getSettingsPath();
+  fs.mkdirSync(path.dirname(settingsPath), { recursive: true });
+  const tmpPath = settingsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, settingsPath);
+  cached = next;
+  return next;
+}
+
+// --- Detection ---
+
+function findOnPath(bin: string): string | null {
+  const exts =
+    process.platform === 'win32'
+      ? (process.env.PATHEXT ?? '.EXE;.CMD;.BAT').split(';').map((e) => e.toLowerCase())
+      : [''];
+  for (const dir of (process.env.PATH ?? '').split(path.delimiter)) {
+    if (!dir) continue;
+    for (const ext of exts) {
+      const candidate = path.join(dir, bin + ext);
+      try {
+        fs.accessSync(candidate, fs.constants.X_OK);
+        if (fs.statSync(candidate).isFile()) return candidate;
+      } catch {
+        /* not here */
+      }
+    }
+  }
+  return null;
+}
+
+export function detectEditors(): DetectedEditor[] {
+  const found: DetectedEditor[] = [];
+  for (const editor of EDITORS) {
+    for (const bin of editor.bins) {
+      const command = findOnPath(bin);
+      if (command) {
+        found.push({ id: editor.id, name: editor.name, command });
+        break;
+      }
+    }
+  }
+  return found;
+}
+
+// --- Launching ---
+
+/** Split a command template into words, honouring single and double quotes. */
+function splitCommand(template: string): string[] {
+  const words: string[] = [];
+  const re = /"([^"]*)"|'([^']*)'|(\S+)/g;
+  let m: RegExpExecArray | null;
+  while ((m = re.exec(template)) !== null) words.push(m[1] ?? m[2] ?? m[3]);
+  return words;
+}
+
+function launch(command: string, args: string[], cwd: string): Promise<void> {
+  return new Promise((resolve, reject) => {
+    // .cmd wrappers (code.cmd etc.) need a shell on Windows
+    const child = spawn(command, args, {
+      cwd,
+      detached: true,
+      stdio: 'ignore',
+      shell: process.platform === 'win32' && /\.(cmd|bat)$/i.test(command),
+    });
+    child.once('error', reject);
+    child.once('spawn', () => {
+      child.unref();
+      resolve();
+    });
+  });
+}
+
+/**
+ * Open `file` (relative to the worktree) at `line`, or the worktree itself
+ * when `file` is null. Falls back to the OS default handler if no editor is
+ * configured or detected.
+ */
+export async function openInEditor(
+  worktreePath: string,
+  file: string | null,
+  line: number | null,
+): Promise<{ editor: string }> {
+  const dir = path.resolve(worktreePath);
+  const target = file ? path.resolve(dir, file) : null;
+  if (target && path.relative(dir, target).startsWith('..')) {
+    throw new Error(`Path is outside the worktree: ${file}`);
+  }
+  const lineNo = Math.max(1, Math.floor(line ?? 1));
+
+  const template = getEditorSettings().command_template;
+  if (template) {
+    const vars: Record<string, string> = {
+      file: target ?? dir,
+      line: String(lineNo),
+      dir,
+    };
+    const words = splitCommand(template).map((w) =>
+      w.replace(/\{(file|line|dir)\}/g, (_m, key: string) => vars[key]),
+    );
+    await launch(words[0], words.slice(1), dir);
+    return { editor: 'custom' };
+  }
+
+  const detected = detectEditors()[0];
+  if (detected) {
+    const def = EDITORS.find((e) => e.id === detected.id);
+    const args = target && def ? def.fileArgs(dir, target, lineNo) : [dir];
+    await launch(detected.command, args, dir);
+    return { editor: detected.id };
+  }
+
+  const error = await shell.openPath(target ?? dir);
+  if (error) throw new Error(`Could not open ${target ?? dir}: ${error}`);
+  return { editor: 'system' };
+}
@@ -0,0 +1,167 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+import { onPtyEvent, type PtyEventSession } from './pty.js';
+
+// A structured log of what the backend did: agents starting and stopping,
+// tasks being created, merged, pushed and deleted, and failures along the way.
+// Events are appended as JSON lines to events.log, which is rotated by size,
+// and read back by `query_events` for task timelines and post-mortems.
+
+export const EVENT_KINDS = [
+  'agent_spawn',
+  'agent_exit',
+  'agent_kill',
+  'task_create',
+  'task_delete',
+  'merge',
+  'push',
+  'error',
+] as const;
+export type EventKind = (typeof EVENT_KINDS)[number];
+
+export interface AppEvent {
+  /** Unique and increasing within a log. */
+  id: string;
+  ts: number;
+  kind: EventKind;
+  task_id: string | null;
+  agent_id: string | null;
+  message: string;
+  data?: Record<string, unknown>;
+}
+
+export interface EventFilter {
+  task_id?: string;
+  agent_id?: string;
+  kinds?: EventKind[];
+}
+
+const MAX_LOG_BYTES = 2 * 1024 * 1024;
+/** Rotated files kept besides the current one; `.1` is the newest. */
+const ROTATED_LOGS = 3;
+const DEFAULT_LIMIT = 200;
+
+let sequence = 0;
+
+function getLogPath(index = 0): string {
+  const file = path.join(getStateDir(), 'events.log');
+  return index === 0 ? file : `${file}.${index}`;
+}
+
+function rotateIfFull(): void {
+  let size: number;
+  try {
+    size = fs.statSync(getLogPath()).size;
+  } catch {
+    return;
+  }
+  if (size < MAX_LOG_BYTES) return;
+  fs.rmSync(getLogPath(ROTATED_LOGS), { force: true });
+  for (let i = ROTATED_LOGS - 1; i >= 0; i--) {
+    if (fs.existsSync(getLogPath(i))) fs.renameSync(getLogPath(i), getLogPath(i + 1));
+  }
+}
+
+export function isEventKind(value: unknown): value is EventKind {
+  return EVENT_KINDS.includes(value as EventKind);
+}
+
+/** Append an event to the log. Never throws; a lost event isn't worth failing the action. */
+export function recordEvent(
+  kind: EventKind,
+  message: string,
+  opts: { taskId?: string | null; agentId?: string | null; data?: Record<string, unknown> } = {},
+): void {
+  const ts = Date.now();
+  const event: AppEvent = {
+    id: `${ts.toString(36)}-${(sequence++).toString(36)}`,
+    ts,
+    kind,
+    task_id: opts.taskId ?? null,
+    agent_id: opts.agentId ?? null,
+    message,
+    ...(opts.data ? { data: opts.data } : {}),
+  };
+  try {
+    fs.mkdirSync(getStateDir(), { recursive: true });
+    rotateIfFull();
+    fs.appendFileSync(getLogPath(), JSON.stringify(event) + '\n', 'utf8');
+  } catch (e) {
+    console.warn('Could not write event log:', e);
+  }
+}
+
+/** Record a failed action as an `error` event. */
+export function recordError(
+  action: string,
+  err: unknown,
+  opts: { taskId?: string | null; agentId?: string | null } = {},
+): void {
+  const reason = err instanceof Error ? err.message : String(err);
+  recordEvent('error', `${action} failed: ${reason}`, { ...opts, data: { action } });
+}
+
+function matches(event: AppEvent, filter: EventFilter): boolean {
+  if (filter.task_id && event.task_id !== filter.task_id) return false;
+  if (filter.agent_id && event.agent_id !== filter.agent_id) return false;
+  if (filter.kinds && filter.kinds.length > 0 && !filter.kinds.includes(event.kind)) return false;
+  return true;
+}
+
+/** Events matching `filter`, newest first, no older than `since` (ms since epoch). */
+export function queryEvents(
+  filter: EventFilter = {},
+  since = 0,
+  limit = DEFAULT_LIMIT,
+): AppEvent[] {
+  const result: AppEvent[] = [];
+  for (let index = 0; index <= ROTATED_LOGS; index++) {
+    let content: string;
+    try {
+      content = fs.readFileSync(getLogPath(index), 'utf8');
+    } catch {
+      continue;
+    }
+    const lines = content.split('\n');
+    for (let i = lines.length - 1; i >= 0; i--) {
+      if (!lines[i]) continue;
+      let event: AppEvent;
+      try {
+        event = JSON.parse(lines[i]) as AppEvent;
+      } catch {
+        continue; // a line cut short by a crash
+      }
+      if (event.ts < since) return result;
+      if (!matches(event, filter)) continue;
+      result.push(event);
+      if (result.length >= limit) return result;
+    }
+  }
+  return result;
+}
+
+/** Log agent starts and exits as they happen. */
+export function initEvents(): void {
+  onPtyEvent('spawn', (agentId, data) => {
+    const { taskId, queueable } = data as PtyEventSession;
+    recordEvent('agent_spawn', queueable ? 'Agent started' : 'Shell started', { taskId, agentId });
+  });
+  onPtyEvent('exit', (agentId, data) => {
+    const d = data as PtyEventSession & {
+      exitCode: number | null;
+      signal?: number;
+      exitReason: string;
+    };
+    const what = d.queueable ? 'Agent' : 'Shell';
+    const message =
+      d.exitCode === null
+        ? `${what} exited (${d.exitReason})`
+        : `${what} exited with code ${d.exitCode}`;
+    recordEvent('agent_exit', message, {
+      taskId: d.taskId,
+      agentId,
+      data: { exit_code: d.exitCode, signal: d.signal ?? null, exit_reason: d.exitReason },
+    });
+  });
+}
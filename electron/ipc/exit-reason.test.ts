@@ -0,0 +1,88 @@
+import { describe, it, expect } from 'vitest';
+import { classifyExit, windowsExitSignal, type ExitReason } from './exit-reason.js';
+
+// ---------------------------------------------------------------------------
+// classifyExit
+// ---------------------------------------------------------------------------
+describe('classifyExit', () => {
+  const cases: Array<{
+    name: string;
+    exitCode: number | null;
+    signal?: number | null;
+    killedByUser?: boolean;
+    lastOutput?: string[];
+    expected: ExitReason;
+  }> = [
+    { name: 'a clean exit', exitCode: 0, expected: 'normal' },
+    { name: 'a plain failure', exitCode: 1, expected: 'error' },
+    {
+      name: 'a user kill over anything else',
+      exitCode: 139,
+      killedByUser: true,
+      expected: 'killed_by_user',
+    },
+    { name: 'SIGSEGV', exitCode: null, signal: 11, expected: 'segfault' },
+    { name: 'a shell-reported SIGSEGV', exitCode: 139, expected: 'segfault' },
+    { name: 'SIGBUS', exitCode: null, signal: 7, expected: 'segfault' },
+    { name: 'a bare SIGKILL', exitCode: null, signal: 9, expected: 'signal' },
+    { name: 'a shell-reported SIGKILL', exitCode: 137, expected: 'signal' },
+    {
+      name: 'SIGKILL after an out-of-memory message',
+      exitCode: null,
+      signal: 9,
+      lastOutput: ['FATAL ERROR: Reached heap limit', 'JavaScript heap out of memory'],
+      expected: 'oom_killed',
+    },
+    {
+      name: 'a failure after an allocation error',
+      exitCode: 1,
+      lastOutput: ['fatal: Cannot allocate memory'],
+      expected: 'oom_killed',
+    },
+    {
+      name: 'a clean exit that mentions memory',
+      exitCode: 0,
+      lastOutput: ['retrying after out of memory'],
+      expected: 'normal',
+    },
+    { name: 'exit 127', exitCode: 127, expected: 'command_not_found' },
+    { name: 'cmd.exe exit 9009', exitCode: 9009, expected: 'command_not_found' },
+    {
+      name: 'a shell "command not found"',
+      exitCode: 1,
+      lastOutput: ['zsh: command not found: claude'],
+      expected: 'command_not_found',
+    },
+    { name: 'STATUS_NO_MEMORY', exitCode: 0xc0000017, expected: 'oom_killed' },
+    { name: 'STATUS_NO_MEMORY as a signed code', exitCode: -1073741801, expected: 'oom_killed' },
+    { name: 'SIGTERM', exitCode: null, signal: 15, expected: 'signal' },
+    { name: 'a zero signal', exitCode: 0, signal: 0, expected: 'normal' },
+  ];
+
+  it.each(cases)('classifies $name', (c) => {
+    expect(
+      classifyExit({
+        exitCode: c.exitCode,
+        signal: c.signal ?? null,
+        killedByUser: c.killedByUser ?? false,
+        lastOutput: c.lastOutput ?? [],
+      }),
+    ).toBe(c.expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// windowsExitSignal
+// ---------------------------------------------------------------------------
+describe('windowsExitSignal', () => {
+  it.each([
+    [0xc000013a, 2],
+    [0xc0000005, 11],
+    [-1073741819, 11],
+    [0xc00000fd, 11],
+    [1, null],
+    [null, null],
+  ])('maps %s to %s', (exitCode, signal) => {
+    expect(windowsExitSignal(exitCode)).toBe(signal);
+  });
+});
@@ -0,0 +1,88 @@
+/** Classified cause of a PTY exit, sent alongside the raw code/signal. */
+export type ExitReason =
+  | 'normal'
+  | 'error'
+  | 'killed_by_user'
+  | 'oom_killed'
+  | 'segfault'
+  | 'signal'
+  | 'command_not_found';
+
+const SIGKILL = 9;
+const SIGSEGV = 11;
+const SIGBUS = 7;
+const EXIT_COMMAND_NOT_FOUND = 127;
+
+// Windows has no signals: ConPTY children report NTSTATUS codes instead
+const STATUS_ACCESS_VIOLATION = 0xc0000005;
+const STATUS_STACK_OVERFLOW = 0xc00000fd;
+const STATUS_NO_MEMORY = 0xc0000017;
+const STATUS_CONTROL_C_EXIT = 0xc000013a;
+const EXIT_CMD_NOT_RECOGNIZED = 9009;
+const SIGINT = 2;
+
+const OOM_PATTERNS = [
+  /out of memory/i,
+  /heap out of memory/i,
+  /cannot allocate memory/i,
+  /oom-kill/i,
+];
+const NOT_FOUND_PATTERNS = [
+  /command not found/i,
+  /execvp\(3\) failed/i,
+  /is not recognized as an internal or external command/i,
+];
+
+/** Exit codes may arrive signed or unsigned depending on the runtime. */
+function ntStatus(exitCode: number | null): number | null {
+  return exitCode === null ? null : exitCode >>> 0;
+}
+
+/**
+ * Map a Windows exit code to the Unix signal it corresponds to, so the
+ * frontend sees the same `signal` field on every platform.
+ */
+export function windowsExitSignal(exitCode: number | null): number | null {
+  switch (ntStatus(exitCode)) {
+    case STATUS_CONTROL_C_EXIT:
+      return SIGINT;
+    case STATUS_ACCESS_VIOLATION:
+    case STATUS_STACK_OVERFLOW:
+      return SIGSEGV;
+    default:
+      return null;
+  }
+}
+
+function tailMatches(lines: string[], patterns: RegExp[]): boolean {
+  return lines.some((line) => patterns.some((re) => re.test(line)));
+}
+
+/**
+ * Derive an actionable exit reason from the exit code, terminating signal,
+ * and the last lines of output. Shells report signal deaths as 128 + N, so
+ * both forms are checked.
+ */
+export function classifyExit(opts: {
+  exitCode: number | null;
+  signal: number | null;
+  killedByUser: boolean;
+  lastOutput: string[];
+}): ExitReason {
+  const { exitCode, killedByUser, lastOutput } = opts;
+  const signal = opts.signal || null;
+  const signalled = (n: number) => signal === n || exitCode === 128 + n;
+
+  if (killedByUser) return 'killed_by_user';
+  if (ntStatus(exitCode) === STATUS_NO_MEMORY) return 'oom_killed';
+  if (signalled(SIGSEGV) || signalled(SIGBUS)) return 'segfault';
+  const failed = exitCode !== 0 || signal !== null;
+  if (failed && tailMatches(lastOutput, OOM_PATTERNS)) return 'oom_killed';
+  if (exitCode === EXIT_COMMAND_NOT_FOUND || exitCode === EXIT_CMD_NOT_RECOGNIZED) {
+    return 'command_not_found';
+  }
+  if (exitCode !== 0 && tailMatches(lastOutput, NOT_FOUND_PATTERNS)) return 'command_not_found';
+  // A bare SIGKILL may be the OOM killer, but also the orphan reaper or `kill -9`
+  if (signal !== null || signalled(SIGKILL)) return 'signal';
+  return exitCode === 0 ? 'normal' : 'error';
+}
@@ -0,0 +1,129 @@
+import { execFile, type ExecFileException } from 'child_process';
+
+// Pluggable git access. git.ts issues every git command through the active
+// backend, so an in-process implementation can replace the CLI without
+// touching call sites. git-inprocess.ts answers status and diff polling without
+// spawning git and hands everything else to the CLI backend.
+
+export interface GitRunOptions {
+  cwd?: string;
+  maxBuffer?: number;
+  env?: NodeJS.ProcessEnv;
+  timeout?: number;
+  /** Written to git's stdin (e.g. a patch for `git apply`). */
+  input?: string;
+  /** Kills git when aborted. */
+  signal?: AbortSignal;
+  /** Called with output as it arrives, e.g. to stream hook progress. */
+  onOutput?: (stream: 'stdout' | 'stderr', data: string) => void;
+}
+
+export interface GitResult {
+  stdout: string;
+  stderr: string;
+}
+
+export type GitErrorKind =
+  | 'not_a_repository'
+  | 'ref_not_found'
+  | 'conflict'
+  | 'dirty_worktree'
+  | 'locked'
+  | 'auth_failed'
+  | 'signing_failed'
+  | 'network'
+  | 'not_found'
+  | 'unknown';
+
+const ERROR_PATTERNS: Array<[GitErrorKind, RegExp]> = [
+  ['not_a_repository', /not a git repository/i],
+  ['signing_failed', /gpg failed to sign|failed to sign the data|ssh-keygen.*(?:failed|error)/i],
+  ['conflict', /CONFLICT|Merge conflict|could not apply/],
+  ['dirty_worktree', /would be overwritten|uncommitted changes|Please commit your changes/i],
+  ['locked', /index\.lock|Unable to create .*\.lock|is locked/i],
+  ['auth_failed', /Authentication failed|Permission denied \(publickey|could not read Username/i],
+  ['auth_failed', /could not read Password|terminal prompts disabled|Host key verification/i],
+  ['network', /Could not resolve host|Connection (?:refused|timed out)|unable to access/i],
+  ['ref_not_found', /unknown revision|bad revision|invalid reference|not a valid (?:object|ref)/i],
+  ['not_found', /not found|does not exist|did not match any/i],
+];
+
+/** Failed git invocation with the exit code, captured output and a coarse category. */
+export class GitError extends Error {
+  readonly kind: GitErrorKind;
+
+  constructor(
+    readonly args: string[],
+    readonly code: number | null,
+    readonly stdout: string,
+    readonly stderr: string,
+  ) {
+    // Same shape as execFile's message so existing String(e) parsing keeps working
+    super(`Command failed: git ${args.join(' ')}\n${stderr}`);
+    this.name = 'GitError';
+    this.kind = classifyGitError(`${stderr}\n${stdout}`);
+  }
+}
+
+export function classifyGitError(output: string): GitErrorKind {
+  for (const [kind, re] of ERROR_PATTERNS) {
+    if (re.test(output)) return kind;
+  }
+  return 'unknown';
+}
+
+export interface GitBackend {
+  readonly name: string;
+  run(args: string[], opts?: GitRunOptions): Promise<GitResult>;
+  /** Like run, but stdout is returned raw (for blobs that may not be text). */
+  runBuffer(args: string[], opts?: GitRunOptions): Promise<Buffer>;
+}
+
+export const cliGitBackend: GitBackend = {
+  name: 'cli',
+  run: (args, opts = {}) =>
+    new Promise((resolve, reject) => {
+      const { input, onOutput, ...execOpts } = opts;
+      const onDone = (err: ExecFileException | null, stdout: string, stderr: string) => {
+        if (!err) {
+          resolve({ stdout, stderr });
+          return;
+        }
+        const code = typeof err.code === 'number' ? err.code : null;
+        // Spawn failures (ENOENT etc.) have no git output to classify
+        if (code === null && !stderr) reject(err);
+        else reject(new GitError(args, code, stdout, stderr));
+      };
+      const child = execFile('git', args, { ...execOpts, encoding: 'utf8' }, onDone);
+      if (onOutput) {
+        child.stdout?.on('data', (d: string) => onOutput('stdout', d));
+        child.stderr?.on('data', (d: string) => onOutput('stderr', d));
+      }
+      if (input !== undefined) child.stdin?.end(input);
+    }),
+  runBuffer: (args, opts = {}) =>
+    new Promise((resolve, reject) => {
+      const { input, onOutput: _onOutput, ...execOpts } = opts;
+      const onDone = (err: ExecFileException | null, stdout: Buffer, stderr: Buffer) => {
+        if (!err) {
+          resolve(stdout);
+          return;
+        }
+        const code = typeof err.code === 'number' ? err.code : null;
+        if (code === null && stderr.length === 0) reject(err);
+        else reject(new GitError(args, code, '', stderr.toString('utf8')));
+      };
+      const child = execFile('git', args, { ...execOpts, encoding: 'buffer' }, onDone);
+      if (input !== undefined) child.stdin?.end(input);
+    }),
+};
+
+let activeBackend: GitBackend = cliGitBackend;
+
+export function getGitBackend(): GitBackend {
+  return activeBackend;
+}
+
+export function setGitBackend(backend: GitBackend): void {
+  activeBackend = backend;
+}
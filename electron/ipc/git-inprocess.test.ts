@@ -0,0 +1,151 @@
+import { execFileSync } from 'child_process';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+
+// Commands the in-process reader can't answer are logged and run through the
+// CLI; collect those so a test fails if its fixture wasn't read in-process.
+const fallbacks: string[] = [];
+vi.mock('./logs.js', () => ({
+  log: {
+    debug: (_target: string, message: string) => fallbacks.push(message),
+    info: () => {},
+    warn: (_target: string, message: string) => fallbacks.push(message),
+    error: () => {},
+  },
+}));
+
+import { inProcessGitBackend } from './git-inprocess.js';
+
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'git-inprocess-'));
+
+const IDENTITY = ['-c', 'user.name=Test', '-c', 'user.email=test@example.com'];
+
+function git(cwd: string, ...args: string[]): string {
+  return execFileSync('git', [...IDENTITY, ...args], {
+    cwd,
+    encoding: 'utf8',
+    stdio: ['ignore', 'pipe', 'pipe'],
+  });
+}
+
+function write(dir: string, files: Record<string, string>): void {
+  for (const [name, content] of Object.entries(files)) {
+    fs.mkdirSync(path.dirname(path.join(dir, name)), { recursive: true });
+    fs.writeFileSync(path.join(dir, name), content);
+  }
+}
+
+/** A repository with one commit of `files`; returns its path and that commit. */
+function createRepo(name: string, files: Record<string, string>): { dir: string; base: string } {
+  const dir = path.join(root, name);
+  fs.mkdirSync(dir);
+  git(dir, 'init', '-q', '-b', 'main');
+  write(dir, files);
+  git(dir, 'add', '-A');
+  git(dir, 'commit', '-qm', 'base');
+  return { dir, base: git(dir, 'rev-parse', 'HEAD').trim() };
+}
+
+const lines = (n: number, prefix = 'line') =>
+  Array.from({ length: n }, (_, i) => `${prefix} ${i}\n`).join('');
+
+/** Run the polled commands in-process and through git, and compare the output. */
+async function expectSameAsGit(dir: string, base: string): Promise<void> {
+  for (const args of [
+    ['status', '--porcelain', '-z'],
+    ['status', '--porcelain'],
+    ['diff', '--raw', '--numstat', '-z', '--no-abbrev', base],
+  ]) {
+    fallbacks.length = 0;
+    const { stdout } = await inProcessGitBackend.run(args, { cwd: dir });
+    expect(fallbacks).toEqual([]);
+    expect(stdout).toBe(git(dir, ...args));
+  }
+}
+
+beforeEach(() => {
+  fallbacks.length = 0;
+});
+
+// ---------------------------------------------------------------------------
+// inProcessGitBackend against git
+// ---------------------------------------------------------------------------
+describe('inProcessGitBackend', () => {
+  it('reads packed objects stored as deltas', async () => {
+    const { dir, base } = createRepo('packed', { 'src/big.txt': lines(400), 'README.md': 'hi\n' });
+    for (let i = 0; i < 5; i++) {
+      write(dir, { 'src/big.txt': lines(400) + `edit ${i}\n` });
+      git(dir, 'commit', '-qam', `edit ${i}`);
+    }
+    git(dir, 'gc', '-q', '--aggressive');
+    const packDir = path.join(dir, '.git', 'objects', 'pack');
+    const idx = fs.readdirSync(packDir).find((p) => p.endsWith('.idx')) ?? '';
+    const packStats = git(dir, 'verify-pack', '-v', path.join(packDir, idx));
+    expect(packStats).toMatch(/chain length = [1-9]/);
+
+    write(dir, { 'src/big.txt': lines(390) + 'changed\n', 'new.txt': 'untracked\n' });
+    fs.rmSync(path.join(dir, 'README.md'));
+    await expectSameAsGit(dir, base);
+  });
+
+  it('reads a version 4 index', async () => {
+    const { dir, base } = createRepo('index-v4', {
+      'lib/component/alpha.ts': 'a\n',
+      'lib/component/alphabet.ts': 'b\n',
+      'lib/component/beta.ts': 'c\n',
+      'lib/other.ts': 'd\n',
+    });
+    git(dir, 'update-index', '--index-version', '4');
+    write(dir, { 'lib/component/alphabet.ts': 'b\nb\n', 'lib/component/gamma.ts': 'e\n' });
+    git(dir, 'add', 'lib/component/gamma.ts');
+    write(dir, { 'lib/other.ts': 'd\nd\n' });
+    expect(fs.readFileSync(path.join(dir, '.git', 'index')).readUInt32BE(4)).toBe(4);
+    await expectSameAsGit(dir, base);
+  });
+
+  it('reads a linked worktree', async () => {
+    const { dir, base } = createRepo('main-checkout', { 'a.txt': lines(10), 'b.txt': 'b\n' });
+    const worktree = path.join(root, 'linked');
+    git(dir, 'worktree', 'add', '-q', '-b', 'task', worktree);
+    write(worktree, { 'a.txt': lines(12), 'c.txt': 'c\n' });
+    git(worktree, 'commit', '-qam', 'on task');
+    write(worktree, { 'b.txt': 'changed\n' });
+    git(worktree, 'add', 'c.txt');
+    expect(fs.statSync(path.join(worktree, '.git')).isFile()).toBe(true);
+    await expectSameAsGit(worktree, base);
+  });
+
+  it('reads objects through alternates', async () => {
+    const source = createRepo('alternates-source', { 'a.txt': lines(50), 'b.txt': 'b\n' });
+    git(source.dir, 'gc', '-q');
+    const clone = path.join(root, 'alternates-clone');
+    git(root, 'clone', '-q', '--shared', source.dir, clone);
+    expect(fs.existsSync(path.join(clone, '.git', 'objects', 'info', 'alternates'))).toBe(true);
+    write(clone, { 'a.txt': lines(45) + 'tail\n', 'd.txt': 'new\n' });
+    await expectSameAsGit(clone, source.base);
+  });
+
+  it('applies negated ignore patterns', async () => {
+    const { dir, base } = createRepo('ignore-negation', {
+      '.gitignore': '*.log\n!keep.log\nbuild/\n!build/keep.txt\n/tmp*\n!tmp-keep\n',
+      'sub/.gitignore': '!*.log\nsecret.*\n!secret.pub\n',
+      'tracked.txt': 't\n',
+    });
+    write(dir, {
+      'debug.log': 'x\n',
+      'keep.log': 'x\n',
+      'build/out.js': 'x\n',
+      'build/keep.txt': 'x\n',
+      'tmpfile': 'x\n',
+      'tmp-keep': 'x\n',
+      'nested/tmpfile': 'x\n',
+      'sub/trace.log': 'x\n',
+      'sub/secret.key': 'x\n',
+      'sub/secret.pub': 'x\n',
+      'tracked.txt': 'changed\n',
+    });
+    await expectSameAsGit(dir, base);
+  });
+});
@@ -0,0 +1,1076 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { cliGitBackend, type GitBackend, type GitRunOptions } from './git-backend.js';
+import {
+  commitTree,
+  findRepo,
+  hashBlob,
+  openObjectStore,
+  parseTree,
+  resolveRef,
+  resolveRevision,
+  Unsupported,
+  type ObjectStore,
+  type RepoLayout,
+} from './git-odb.js';
+import { log } from './logs.js';
+
+// In-process git backend for the commands polled on every task refresh:
+// `status --porcelain [-z]` and `diff --raw --numstat -z --no-abbrev <rev>`.
+// They are answered by reading the index, objects and working tree directly,
+// with output byte-for-byte what git prints. Anything outside the supported
+// subset (submodules, conflicts, sparse or split indexes, attributes, possible
+// renames, ...) throws Unsupported and the command runs through the CLI.
+
+/** Config is read through `git config` once per worktree per interval. */
+const CONFIG_TTL = 30_000; // 30s
+/** Changed files read for line counts at once. */
+const READ_CONCURRENCY = 64;
+/** Edit distance up to which a plain Myers diff matches git's line counts. */
+const MAX_DIFF_EDITS = 256;
+/** git treats files with a NUL in this many leading bytes as binary. */
+const BINARY_PROBE_BYTES = 8000;
+const NULL_OID = '0'.repeat(40);
+
+const S_IFMT = 0o170000;
+const MODE_TREE = 0o040000;
+const MODE_FILE = 0o100644;
+const MODE_EXEC = 0o100755;
+const MODE_SYMLINK = 0o120000;
+const MODE_GITLINK = 0o160000;
+
+// --- Config ---
+
+interface RepoSettings {
+  fileMode: boolean;
+  ignoreCase: boolean;
+  precompose: boolean;
+  quotePath: boolean;
+  /** Full stat comparison (core.checkStat=default) and ctime (core.trustCtime). */
+  checkStat: boolean;
+  trustCtime: boolean;
+  untracked: 'no' | 'normal' | 'all';
+  /** status.renames / diff.renames: detection would pair added and deleted paths. */
+  statusRenames: boolean;
+  diffRenames: boolean;
+  excludesFile: string;
+}
+
+const configCache = new Map<string, { config: Map<string, string>; expiresAt: number }>();
+
+async function readConfig(cwd: string): Promise<Map<string, string>> {
+  const cached = configCache.get(cwd);
+  if (cached && cached.expiresAt > Date.now()) return cached.config;
+  // git resolves system/global/worktree files and includes; keys come back lowercased
+  const { stdout } = await cliGitBackend.run(['config', '--list', '-z'], { cwd });
+  const config = new Map<string, string>();
+  for (const item of stdout.split('\0')) {
+    if (!item) continue;
+    const nl = item.indexOf('\n');
+    // A later definition overrides an earlier one, as for git's single-valued lookups
+    if (nl === -1) config.set(item, 'true');
+    else config.set(item.slice(0, nl), item.slice(nl + 1));
+  }
+  configCache.set(cwd, { config, expiresAt: Date.now() + CONFIG_TTL });
+  return config;
+}
+
+function parseBool(key: string, value: string): boolean {
+  const v = value.toLowerCase();
+  if (['true', 'yes', 'on', '1'].includes(v)) return true;
+  if (['false', 'no', 'off', '0', ''].includes(v)) return false;
+  throw new Unsupported(`${key}=${value}`);
+}
+
+function configBool(config: Map<string, string>, key: string, fallback: boolean): boolean {
+  const value = config.get(key);
+  return value === undefined ? fallback : parseBool(key, value);
+}
+
+/** Whether a renames setting (bool, "copies" or "copy") enables detection. */
+function renamesEnabled(key: string, value: string | undefined): boolean {
+  if (value === undefined) return true;
+  const v = value.toLowerCase();
+  return v === 'copies' || v === 'copy' || parseBool(key, v);
+}
+
+function expandHome(file: string): string {
+  return file === '~' || file.startsWith('~/') ? path.join(os.homedir(), file.slice(1)) : file;
+}
+
+async function loadSettings(repo: RepoLayout): Promise<RepoSettings> {
+  const config = await readConfig(repo.workTree);
+  const objectFormat = config.get('extensions.objectformat');
+  if (objectFormat && objectFormat !== 'sha1') throw new Unsupported(`${objectFormat} objects`);
+  const refStorage = config.get('extensions.refstorage');
+  if (refStorage && refStorage !== 'files') throw new Unsupported(`${refStorage} refs`);
+  for (const key of ['core.worktree', 'core.attributesfile', 'core.bigfilethreshold']) {
+    if (config.has(key)) throw new Unsupported(key);
+  }
+  if (config.has('diff.orderfile')) throw new Unsupported('diff.orderfile');
+  const algorithm = config.get('diff.algorithm')?.toLowerCase();
+  if (algorithm && algorithm !== 'myers' && algorithm !== 'default') {
+    throw new Unsupported(`diff.algorithm=${algorithm}`);
+  }
+  // Line ending conversion and attribute-driven filters change what gets hashed
+  if (configBool(config, 'core.autocrlf', false)) throw new Unsupported('core.autocrlf');
+  if (!configBool(config, 'core.symlinks', true)) throw new Unsupported('core.symlinks=false');
+  const xdgConfig = process.env.XDG_CONFIG_HOME || path.join(os.homedir(), '.config');
+  if (exists(path.join(xdgConfig, 'git', 'attributes'))) throw new Unsupported('global attributes');
+  if (exists(path.join(repo.commonDir, 'info', 'attributes'))) {
+    throw new Unsupported('info/attributes');
+  }
+
+  const untrackedValue = config.get('status.showuntrackedfiles')?.toLowerCase() ?? 'normal';
+  let untracked: RepoSettings['untracked'];
+  if (untrackedValue === 'normal' || untrackedValue === 'all' || untrackedValue === 'no') {
+    untracked = untrackedValue;
+  } else {
+    untracked = configBool(config, 'status.showuntrackedfiles', true) ? 'normal' : 'no';
+  }
+  const diffRenames = config.get('diff.renames');
+  const checkStat = config.get('core.checkstat')?.toLowerCase() ?? 'default';
+  if (checkStat !== 'default' && checkStat !== 'minimal') {
+    throw new Unsupported(`core.checkStat=${checkStat}`);
+  }
+  return {
+    fileMode: configBool(config, 'core.filemode', true),
+    ignoreCase: configBool(config, 'core.ignorecase', false),
+    precompose: configBool(config, 'core.precomposeunicode', false),
+    quotePath: configBool(config, 'core.quotepath', true),
+    checkStat: checkStat === 'default',
+    trustCtime: configBool(config, 'core.trustctime', true),
+    untracked,
+    statusRenames: renamesEnabled('status.renames', config.get('status.renames') ?? diffRenames),
+    diffRenames: renamesEnabled('diff.renames', diffRenames),
+    excludesFile: expandHome(
+      config.get('core.excludesfile') ?? path.join(xdgConfig, 'git', 'ignore'),
+    ),
+  };
+}
+
+// --- Index ---
+
+interface IndexEntry {
+  path: string;
+  mode: number;
+  oid: string;
+  ctimeS: number;
+  mtimeS: number;
+  ino: number;
+  uid: number;
+  gid: number;
+  size: number;
+}
+
+/** Cache-tree extension node: the tree id of a directory's index entries, if still valid. */
+interface CacheTree {
+  oid: string | null;
+  children: Map<string, CacheTree>;
+}
+
+interface GitIndex {
+  entries: IndexEntry[];
+  tree: CacheTree | null;
+  /** Index file mtime (s); entries modified in that second or later are racily clean. */
+  mtimeS: number;
+}
+
+function parseCacheTree(buf: Buffer, start: number): CacheTree {
+  let pos = start;
+  const node = (): [string, CacheTree] => {
+    const nul = buf.indexOf(0, pos);
+    const name = buf.toString('utf8', pos, nul);
+    const nl = buf.indexOf(0x0a, nul);
+    const [count, subtrees] = buf.toString('latin1', nul + 1, nl).split(' ').map(Number);
+    pos = nl + 1;
+    let oid: string | null = null;
+    // A negative entry count marks a directory invalidated since the last write-tree
+    if (count >= 0) {
+      oid = buf.toString('hex', pos, pos + 20);
+      pos += 20;
+    }
+    const children = new Map<string, CacheTree>();
+    for (let i = 0; i < subtrees; i++) {
+      const [childName, child] = node();
+      children.set(childName, child);
+    }
+    return [name, { oid, children }];
+  };
+  return node()[1];
+}
+
+async function readIndex(repo: RepoLayout): Promise<GitIndex> {
+  const file = path.join(repo.gitDir, 'index');
+  let buf: Buffer;
+  let mtimeS: number;
+  try {
+    [buf, mtimeS] = await Promise.all([
+      fs.promises.readFile(file),
+      fs.promises.stat(file).then((s) => Math.floor(s.mtimeMs / 1000)),
+    ]);
+  } catch (e) {
+    if ((e as NodeJS.ErrnoException).code === 'ENOENT') {
+      return { entries: [], tree: null, mtimeS: 0 };
+    }
+    throw e;
+  }
+  if (buf.toString('latin1', 0, 4) !== 'DIRC') throw new Unsupported('index signature');
+  const version = buf.readUInt32BE(4);
+  if (version < 2 || version > 4) throw new Unsupported(`index version ${version}`);
+  const count = buf.readUInt32BE(8);
+  const entries: IndexEntry[] = [];
+  let pos = 12;
+  let previous = Buffer.alloc(0);
+  for (let i = 0; i < count; i++) {
+    const start = pos;
+    const flags = buf.readUInt16BE(pos + 60);
+    pos += 62;
+    if (flags & 0x3000) throw new Unsupported('unmerged index entries');
+    if (flags & 0x8000) throw new Unsupported('assume-unchanged entries');
+    if (flags & 0x4000) {
+      // Extended flags: skip-worktree (sparse checkout) and intent-to-add
+      if (buf.readUInt16BE(pos) & 0x6000) throw new Unsupported('sparse or intent-to-add entries');
+      pos += 2;
+    }
+    let name: Buffer;
+    if (version === 4) {
+      // Path compression: how many bytes of the previous path to drop, then the rest
+      let c = buf[pos++];
+      let strip = c & 0x7f;
+      while (c & 0x80) {
+        c = buf[pos++];
+        strip = (strip + 1) * 128 + (c & 0x7f);
+      }
+      const nul = buf.indexOf(0, pos);
+      name = Buffer.concat([previous.subarray(0, previous.length - strip), buf.subarray(pos, nul)]);
+      pos = nul + 1;
+      previous = name;
+    } else {
+      const nul = buf.indexOf(0, pos);
+      name = buf.subarray(pos, nul);
+      // Entries are NUL-padded to a multiple of eight bytes
+      pos = start + ((pos - start + name.length + 8) & ~7);
+    }
+    const entryPath = name.toString('utf8');
+    if (entryPath.includes('\ufffd')) throw new Unsupported('path is not valid UTF-8');
+    const mode = buf.readUInt32BE(start + 24);
+    if (mode === MODE_GITLINK) throw new Unsupported('submodules');
+    entries.push({
+      path: entryPath,
+      mode,
+      oid: buf.toString('hex', start + 40, start + 60),
+      ctimeS: buf.readUInt32BE(start),
+      mtimeS: buf.readUInt32BE(start + 8),
+      ino: buf.readUInt32BE(start + 20),
+      uid: buf.readUInt32BE(start + 28),
+      gid: buf.readUInt32BE(start + 32),
+      size: buf.readUInt32BE(start + 36),
+    });
+  }
+  let tree: CacheTree | null = null;
+  // Extensions follow the entries, before the trailing checksum
+  while (pos + 8 <= buf.length - 20) {
+    const signature = buf.toString('latin1', pos, pos + 4);
+    const size = buf.readUInt32BE(pos + 4);
+    if (signature === 'TREE') tree = parseCacheTree(buf, pos + 8);
+    // Lowercase signatures mark extensions a reader must understand (split, sparse index)
+    else if (signature[0] >= 'a' && signature[0] <= 'z') {
+      throw new Unsupported(`index extension ${signature}`);
+    }
+    pos += 8 + size;
+  }
+  return { entries, tree, mtimeS };
+}
+
+// --- Trees ---
+
+interface BlobRef {
+  mode: number;
+  oid: string;
+}
+
+/** git's canonical form of a tree entry mode. */
+function canonMode(mode: number): number {
+  if ((mode & S_IFMT) !== 0o100000) return mode;
+  return mode & 0o100 ? MODE_EXEC : MODE_FILE;
+}
+
+/**
+ * Flatten a tree into blobs by path. Directories whose cache-tree id matches are
+ * added to `same` instead of being read: the index holds exactly their contents.
+ */
+async function flattenTree(
+  store: ObjectStore,
+  treeOid: string,
+  cacheTree: CacheTree | null,
+  prefix: string,
+  blobs: Map<string, BlobRef>,
+  same: Set<string>,
+): Promise<void> {
+  if (cacheTree?.oid === treeOid) {
+    same.add(prefix);
+    return;
+  }
+  const { type, data } = await store.read(treeOid);
+  if (type !== 'tree') throw new Unsupported(`${treeOid} is not a tree`);
+  for (const entry of parseTree(data)) {
+    const entryPath = prefix ? `${prefix}/${entry.name}` : entry.name;
+    if (entry.mode === MODE_TREE) {
+      const child = cacheTree?.children.get(entry.name) ?? null;
+      await flattenTree(store, entry.oid, child, entryPath, blobs, same);
+    } else if (entry.mode === MODE_GITLINK) {
+      throw new Unsupported('submodules');
+    } else {
+      blobs.set(entryPath, { mode: canonMode(entry.mode), oid: entry.oid });
+    }
+  }
+}
+
+/** A tree's blobs keyed by path, with index entries standing in for unchanged directories. */
+async function treeBlobs(
+  store: ObjectStore,
+  treeOid: string | null,
+  index: GitIndex,
+): Promise<Map<string, BlobRef>> {
+  const blobs = new Map<string, BlobRef>();
+  if (treeOid === null) return blobs;
+  const same = new Set<string>();
+  await flattenTree(store, treeOid, index.tree, '', blobs, same);
+  if (same.size === 0) return blobs;
+  for (const entry of index.entries) {
+    if (ancestors(entry.path).some((dir) => same.has(dir))) {
+      blobs.set(entry.path, { mode: entry.mode, oid: entry.oid });
+    }
+  }
+  return blobs;
+}
+
+/** Directories containing `p`, from the root ('') down. */
+function ancestors(p: string): string[] {
+  const dirs = [''];
+  for (let i = p.indexOf('/'); i !== -1; i = p.indexOf('/', i + 1)) dirs.push(p.slice(0, i));
+  return dirs;
+}
+
+// --- Working tree ---
+
+// The scan uses sync fs calls: a threadpool round trip per lstat costs far more
+// than the call itself. Work is split into batches that yield to the event loop
+// so a large checkout doesn't stall the main process.
+
+interface WorktreeState {
+  /** Gone, or replaced by a directory or something under a symlinked directory. */
+  missing: boolean;
+  /** The index entry's stat data still describes the file, so git reports its index id. */
+  statClean: boolean;
+  /** Content and mode match the index entry. */
+  matchesIndex: boolean;
+  typeChanged: boolean;
+  mode: number;
+  oid: string;
+}
+
+interface WorktreeCtx {
+  repo: RepoLayout;
+  settings: RepoSettings;
+  index: GitIndex;
+  /** lstat-is-a-real-directory results for leading path components. */
+  dirs: Map<string, boolean>;
+  /** Sync fs calls since the last yield. */
+  calls: number;
+}
+
+/** Paths checked or directories listed between yields to the event loop. */
+const YIELD_EVERY = 256;
+
+async function pause(ctx: WorktreeCtx): Promise<void> {
+  if (++ctx.calls < YIELD_EVERY) return;
+  ctx.calls = 0;
+  await new Promise((resolve) => setImmediate(resolve));
+}
+
+function exists(file: string): boolean {
+  return fs.lstatSync(file, { throwIfNoEntry: false }) !== undefined;
+}
+
+/** Whether each leading directory of a path is a real directory (not a symlink). */
+function realParents(ctx: WorktreeCtx, p: string): boolean {
+  for (const dir of ancestors(p).slice(1)) {
+    let real = ctx.dirs.get(dir);
+    if (real === undefined) {
+      const st = fs.lstatSync(path.join(ctx.repo.workTree, dir), { throwIfNoEntry: false });
+      real = st?.isDirectory() ?? false;
+      ctx.dirs.set(dir, real);
+    }
+    if (!real) return false;
+  }
+  return true;
+}
+
+function statMatches(ctx: WorktreeCtx, entry: IndexEntry, st: fs.BigIntStats): boolean {
+  const low32 = (n: bigint) => Number(n & 0xffffffffn);
+  const seconds = (ns: bigint) => low32(ns / 1_000_000_000n);
+  if (entry.mtimeS !== seconds(st.mtimeNs) || entry.size !== low32(st.size)) return false;
+  if (!ctx.settings.checkStat) return true;
+  if (ctx.settings.trustCtime && entry.ctimeS !== seconds(st.ctimeNs)) return false;
+  return entry.ino === low32(st.ino) && entry.uid === low32(st.uid) && entry.gid === low32(st.gid);
+}
+
+function readWorktreeFile(full: string, mode: number): Buffer {
+  if (mode === MODE_SYMLINK) return fs.readlinkSync(full, { encoding: 'buffer' });
+  return fs.readFileSync(full);
+}
+
+function worktreeState(ctx: WorktreeCtx, entry: IndexEntry): WorktreeState {
+  const full = path.join(ctx.repo.workTree, entry.path);
+  const gone: WorktreeState = {
+    missing: true,
+    statClean: false,
+    matchesIndex: false,
+    typeChanged: false,
+    mode: 0,
+    oid: NULL_OID,
+  };
+  if (!realParents(ctx, entry.path)) return gone;
+  let st: fs.BigIntStats | undefined;
+  try {
+    st = fs.lstatSync(full, { bigint: true, throwIfNoEntry: false });
+  } catch (e) {
+    // A leading component replaced by a file
+    if ((e as NodeJS.ErrnoException).code !== 'ENOTDIR') throw e;
+  }
+  if (!st) return gone;
+  if (st.isDirectory()) {
+    // A nested repository here would be reported as a submodule change
+    if (exists(path.join(full, '.git'))) throw new Unsupported('repository over tracked file');
+    return gone;
+  }
+  let mode: number;
+  if (st.isSymbolicLink()) mode = MODE_SYMLINK;
+  else if (!st.isFile()) throw new Unsupported(`special file ${entry.path}`);
+  else if (!ctx.settings.fileMode && entry.mode !== MODE_SYMLINK) mode = entry.mode;
+  else mode = st.mode & 0o100n ? MODE_EXEC : MODE_FILE;
+  const typeChanged = (mode & S_IFMT) !== (entry.mode & S_IFMT);
+  const statClean = !typeChanged && mode === entry.mode && statMatches(ctx, entry, st);
+  if (statClean && entry.mtimeS < ctx.index.mtimeS) {
+    return { missing: false, statClean, matchesIndex: true, typeChanged, mode, oid: entry.oid };
+  }
+  // Changed stat data or racily clean: compare content, as a git refresh would
+  const oid = hashBlob(readWorktreeFile(full, mode));
+  const matchesIndex = !typeChanged && mode === entry.mode && oid === entry.oid;
+  return {
+    missing: false,
+    statClean: statClean && matchesIndex,
+    matchesIndex,
+    typeChanged,
+    mode,
+    oid,
+  };
+}
+
+async function worktreeStates(ctx: WorktreeCtx): Promise<WorktreeState[]> {
+  const states: WorktreeState[] = [];
+  for (const entry of ctx.index.entries) {
+    await pause(ctx);
+    states.push(worktreeState(ctx, entry));
+  }
+  return states;
+}
+
+async function mapLimit<T, R>(
+  items: T[],
+  limit: number,
+  fn: (item: T) => Promise<R>,
+): Promise<R[]> {
+  const out = new Array<R>(items.length);
+  let next = 0;
+  const worker = async () => {
+    while (next < items.length) {
+      const i = next++;
+      out[i] = await fn(items[i]);
+    }
+  };
+  await Promise.all(Array.from({ length: Math.min(limit, items.length) }, worker));
+  return out;
+}
+
+/** Attributes can turn on filters, eol conversion or textconv; none are emulated. */
+async function assertNoAttributes(ctx: WorktreeCtx): Promise<void> {
+  const dirs = new Set<string>();
+  for (const entry of ctx.index.entries) {
+    if (path.posix.basename(entry.path) === '.gitattributes') {
+      throw new Unsupported('.gitattributes');
+    }
+    for (const dir of ancestors(entry.path)) dirs.add(dir);
+  }
+  for (const dir of dirs) {
+    await pause(ctx);
+    if (exists(path.join(ctx.repo.workTree, dir, '.gitattributes'))) {
+      throw new Unsupported('.gitattributes');
+    }
+  }
+}
+
+// --- Ignore rules ---
+
+interface IgnorePattern {
+  re: RegExp;
+  negate: boolean;
+  dirOnly: boolean;
+  /** No slash in the pattern: matched against the basename at any depth. */
+  basename: boolean;
+}
+
+interface IgnoreList {
+  /** Directory the patterns are relative to ('' for the top), as a byte string. */
+  base: string;
+  patterns: IgnorePattern[];
+}
+
+/**
+ * wildmatch compares bytes, so patterns and paths are matched as latin1 strings
+ * of their UTF-8 bytes (`?` is one byte), folding only ASCII case as git does.
+ */
+function byteString(s: string, ignoreCase: boolean): string {
+  const bytes = Buffer.from(s, 'utf8').toString('latin1');
+  return ignoreCase ? bytes.replace(/[A-Z]/g, (c) => c.toLowerCase()) : bytes;
+}
+
+const escapeRegExp = (s: string) => s.replace(/[.*+?^${}()|[\]\\/]/g, '\\$&');
+const escapeClass = (s: string) => s.replace(/[\\\]\[^-]/g, '\\$&');
+
+/** Translate a wildmatch pattern (as used by gitignore) to a regular expression. */
+export function globToRegExp(glob: string): RegExp {
+  let re = '';
+  for (let i = 0; i < glob.length; i++) {
+    const c = glob[i];
+    if (c === '*') {
+      let j = i;
+      while (glob[j] === '*') j++;
+      const whole = j - i >= 2 && (i === 0 || glob[i - 1] === '/');
+      if (whole && j === glob.length) {
+        // Trailing `**` matches everything inside
+        re += '.*';
+      } else if (whole && glob[j] === '/') {
+        // `**/` matches zero or more directories
+        re += '(?:.*/)?';
+        j++;
+      } else {
+        re += '[^/]*';
+      }
+      i = j - 1;
+    } else if (c === '?') {
+      re += '[^/]';
+    } else if (c === '[') {
+      let j = i + 1;
+      let negate = false;
+      if (glob[j] === '!' || glob[j] === '^') {
+        negate = true;
+        j++;
+      }
+      let cls = '';
+      for (let first = true; j < glob.length && (first || glob[j] !== ']'); j++, first = false) {
+        let ch = glob[j];
+        if (ch === '[' && glob[j + 1] === ':') throw new Unsupported('character class in pattern');
+        if (ch === '\\' && j + 1 < glob.length) ch = glob[++j];
+        if (glob[j + 1] === '-' && glob[j + 2] !== undefined && glob[j + 2] !== ']') {
+          let end = glob[j + 2];
+          j += 2;
+          if (end === '\\' && j + 1 < glob.length) end = glob[++j];
+          cls += `${escapeClass(ch)}-${escapeClass(end)}`;
+        } else {
+          cls += escapeClass(ch);
+        }
+      }
+      if (j >= glob.length) throw new Unsupported('unterminated bracket in pattern');
+      re += negate ? `[^/${cls}]` : `[${cls}]`;
+      i = j;
+    } else if (c === '\\') {
+      if (++i >= glob.length) throw new Unsupported('trailing backslash in pattern');
+      re += escapeRegExp(glob[i]);
+    } else {
+      re += escapeRegExp(c);
+    }
+  }
+  return new RegExp(`^${re}$`);
+}
+
+/** Parse one line of an ignore file, given as a byte string. */
+export function parseIgnoreLine(line: string, ignoreCase: boolean): IgnorePattern | null {
+  if (line.includes('\r')) throw new Unsupported('CR in ignore file');
+  // Trailing spaces are dropped unless escaped
+  let text = ignoreCase ? line.replace(/[A-Z]/g, (c) => c.toLowerCase()) : line;
+  while (text.endsWith(' ') && !text.endsWith('\\ ')) text = text.slice(0, -1);
+  if (!text || text.startsWith('#')) return null;
+  const negate = text.startsWith('!');
+  if (negate) text = text.slice(1);
+  const dirOnly = text.endsWith('/');
+  if (dirOnly) text = text.slice(0, -1);
+  if (!text) return null;
+  const basename = !text.includes('/');
+  if (text.startsWith('/')) text = text.slice(1);
+  return { re: globToRegExp(text), negate, dirOnly, basename };
+}
+
+function readIgnoreFile(
+  file: string,
+  base: string,
+  ignoreCase: boolean,
+  inTree: boolean,
+): IgnoreList | null {
+  const st = fs.lstatSync(file, { throwIfNoEntry: false });
+  if (!st) return null;
+  // git won't follow a symlinked .gitignore inside the working tree
+  if (inTree && st.isSymbolicLink()) throw new Unsupported('symlinked .gitignore');
+  const text = fs.readFileSync(file, 'latin1');
+  const patterns: IgnorePattern[] = [];
+  for (const line of text.split('\n')) {
+    const pattern = parseIgnoreLine(line, ignoreCase);
+    if (pattern) patterns.push(pattern);
+  }
+  return { base: byteString(base, ignoreCase), patterns };
+}
+
+/** Whether the last matching pattern, in precedence order, excludes the path. */
+export function isIgnored(
+  lists: IgnoreList[],
+  relPath: string,
+  isDir: boolean,
+  ignoreCase: boolean,
+): boolean {
+  const subject = byteString(relPath, ignoreCase);
+  for (let i = lists.length - 1; i >= 0; i--) {
+    const { base, patterns } = lists[i];
+    if (base && !subject.startsWith(`${base}/`)) continue;
+    const sub = base ? subject.slice(base.length + 1) : subject;
+    const name = sub.slice(sub.lastIndexOf('/') + 1);
+    for (let j = patterns.length - 1; j >= 0; j--) {
+      const pattern = patterns[j];
+      if (pattern.dirOnly && !isDir) continue;
+      if (pattern.re.test(pattern.basename ? name : sub)) return !pattern.negate;
+    }
+  }
+  return false;
+}
+
+// --- Untracked files ---
+
+interface UntrackedCtx extends WorktreeCtx {
+  /** Tracked file paths and their leading directories, case-folded with core.ignoreCase. */
+  files: Set<string>;
+  trackedDirs: Set<string>;
+}
+
+const foldCase = (ctx: UntrackedCtx, p: string) => (ctx.settings.ignoreCase ? p.toLowerCase() : p);
+
+function listDir(ctx: UntrackedCtx, dir: string, lists: IgnoreList[]) {
+  const full = path.join(ctx.repo.workTree, dir);
+  const dirents = fs.readdirSync(full, { withFileTypes: true });
+  const ignore = readIgnoreFile(path.join(full, '.gitignore'), dir, ctx.settings.ignoreCase, true);
+  const entries: Array<{ rel: string; isDir: boolean }> = [];
+  for (const dirent of dirents) {
+    let name = dirent.name;
+    if (name.includes('\ufffd')) throw new Unsupported('path is not valid UTF-8');
+    if (foldCase(ctx, name) === '.git') continue;
+    if (ctx.settings.precompose) name = name.normalize('NFC');
+    let isDir = dirent.isDirectory();
+    let isFile = dirent.isFile() || dirent.isSymbolicLink();
+    if (!isDir && !isFile) {
+      // Filesystems without d_type report everything as unknown
+      const st = fs.lstatSync(path.join(full, dirent.name));
+      isDir = st.isDirectory();
+      isFile = st.isFile() || st.isSymbolicLink();
+      if (!isDir && !isFile) continue;
+    }
+    entries.push({ rel: dir ? `${dir}/${name}` : name, isDir });
+  }
+  return { entries, lists: ignore ? [...lists, ignore] : lists };
+}
+
+/** How git shows an untracked nested repository: as a directory entry. */
+function isNestedRepo(ctx: UntrackedCtx, rel: string): boolean {
+  const dotGit = path.join(ctx.repo.workTree, rel, '.git');
+  const st = fs.lstatSync(dotGit, { throwIfNoEntry: false });
+  if (!st) return false;
+  if (st.isFile() || (st.isDirectory() && exists(path.join(dotGit, 'HEAD')))) return true;
+  throw new Unsupported(`unrecognised .git in ${rel}`);
+}
+
+/** Whether an untracked directory has anything git would list. */
+async function hasUntracked(ctx: UntrackedCtx, dir: string, lists: IgnoreList[]): Promise<boolean> {
+  if (isNestedRepo(ctx, dir)) return true;
+  await pause(ctx);
+  const listing = listDir(ctx, dir, lists);
+  for (const { rel, isDir } of listing.entries) {
+    if (isIgnored(listing.lists, rel, isDir, ctx.settings.ignoreCase)) continue;
+    if (!isDir || (await hasUntracked(ctx, rel, listing.lists))) return true;
+  }
+  return false;
+}
+
+async function walkUntracked(
+  ctx: UntrackedCtx,
+  dir: string,
+  lists: IgnoreList[],
+  ignoredDir: boolean,
+  out: string[],
+): Promise<void> {
+  await pause(ctx);
+  const listing = listDir(ctx, dir, lists);
+  for (const { rel, isDir } of listing.entries) {
+    const key = foldCase(ctx, rel);
+    // Everything below an ignored directory stays ignored, even partly tracked ones
+    const ignored = ignoredDir || isIgnored(listing.lists, rel, isDir, ctx.settings.ignoreCase);
+    if (!isDir) {
+      if (!ctx.files.has(key) && !ignored) out.push(rel);
+    } else if (ctx.trackedDirs.has(key)) {
+      await walkUntracked(ctx, rel, listing.lists, ignored, out);
+    } else if (ignored) {
+      continue;
+    } else if (ctx.settings.untracked === 'all' && !isNestedRepo(ctx, rel)) {
+      await walkUntracked(ctx, rel, listing.lists, false, out);
+    } else if (ctx.files.has(key)) {
+      // A directory listed as `dir/` is dropped when that path is still a tracked file
+      continue;
+    } else if (await hasUntracked(ctx, rel, listing.lists)) {
+      out.push(`${rel}/`);
+    }
+  }
+}
+
+async function listUntracked(ctx: WorktreeCtx): Promise<string[]> {
+  if (ctx.settings.untracked === 'no') return [];
+  const untrackedCtx: UntrackedCtx = { ...ctx, files: new Set(), trackedDirs: new Set() };
+  for (const entry of ctx.index.entries) {
+    untrackedCtx.files.add(foldCase(untrackedCtx, entry.path));
+    for (const dir of ancestors(entry.path).slice(1)) {
+      untrackedCtx.trackedDirs.add(foldCase(untrackedCtx, dir));
+    }
+  }
+  const { ignoreCase } = ctx.settings;
+  const lists: IgnoreList[] = [];
+  for (const file of [
+    ctx.settings.excludesFile,
+    path.join(ctx.repo.commonDir, 'info', 'exclude'),
+  ]) {
+    const list = readIgnoreFile(file, '', ignoreCase, false);
+    if (list) lists.push(list);
+  }
+  const out: string[] = [];
+  await walkUntracked(untrackedCtx, '', lists, false, out);
+  return out;
+}
+
+// --- Commands ---
+
+/** git orders paths by their bytes, not UTF-16 code units. */
+function sortByBytes(paths: string[]): string[] {
+  return paths
+    .map((p) => ({ p, key: Buffer.from(p) }))
+    .sort((a, b) => Buffer.compare(a.key, b.key))
+    .map(({ p }) => p);
+}
+
+interface Session {
+  ctx: WorktreeCtx;
+  store: ObjectStore;
+}
+
+async function withSession<T>(cwd: string, fn: (session: Session) => Promise<T>): Promise<T> {
+  const repo = await findRepo(cwd);
+  const settings = await loadSettings(repo);
+  const index = await readIndex(repo);
+  const ctx: WorktreeCtx = { repo, settings, index, dirs: new Map(), calls: 0 };
+  await assertNoAttributes(ctx);
+  const store = await openObjectStore(repo);
+  try {
+    return await fn({ ctx, store });
+  } finally {
+    await store.close();
+  }
+}
+
+/** `git status --porcelain [-z]`. */
+async function status(cwd: string, nulTerminated: boolean): Promise<string> {
+  return withSession(cwd, async ({ ctx, store }) => {
+    const head = await resolveRef(ctx.repo, 'HEAD');
+    const headTree = head ? await commitTree(store, head) : null;
+    const headBlobs = await treeBlobs(store, headTree, ctx.index);
+    const states = await worktreeStates(ctx);
+
+    const codes = new Map<string, string>();
+    ctx.index.entries.forEach((entry, i) => {
+      const base = headBlobs.get(entry.path);
+      let x = ' ';
+      if (!base) x = 'A';
+      else if ((base.mode & S_IFMT) !== (entry.mode & S_IFMT)) x = 'T';
+      else if (base.oid !== entry.oid || base.mode !== entry.mode) x = 'M';
+      const state = states[i];
+      let y = ' ';
+      if (state.missing) y = 'D';
+      else if (state.typeChanged) y = 'T';
+      else if (!state.matchesIndex) y = 'M';
+      if (x !== ' ' || y !== ' ') codes.set(entry.path, x + y);
+    });
+    const indexed = new Set(ctx.index.entries.map((e) => e.path));
+    for (const p of headBlobs.keys()) {
+      if (!indexed.has(p)) codes.set(p, 'D ');
+    }
+    const staged = [...codes.values()].map((xy) => xy[0]);
+    if (ctx.settings.statusRenames && staged.includes('A') && staged.includes('D')) {
+      throw new Unsupported('possible staged rename');
+    }
+
+    const lines = sortByBytes([...codes.keys()]).map((p) => `${codes.get(p)} ${p}`);
+    for (const p of sortByBytes(await listUntracked(ctx))) lines.push(`?? ${p}`);
+    if (nulTerminated) return lines.map((l) => `${l}\0`).join('');
+    // Without -z git quotes paths with spaces or unusual characters; leave those to the CLI
+    const needsQuoting = ctx.settings.quotePath ? /[\0-\x20"\\\x7f-\uffff]/ : /[\0-\x20"\\\x7f]/;
+    if (lines.some((l) => needsQuoting.test(l.slice(3)))) throw new Unsupported('quoted paths');
+    return lines.map((l) => `${l}\n`).join('');
+  });
+}
+
+function splitLines(data: Buffer): string[] {
+  const lines: string[] = [];
+  for (let start = 0; start < data.length; ) {
+    const nl = data.indexOf(0x0a, start);
+    const end = nl === -1 ? data.length : nl + 1;
+    lines.push(data.toString('latin1', start, end));
+    start = end;
+  }
+  return lines;
+}
+
+/** xdiff's integer square root estimate. */
+function bogoSqrt(n: number): number {
+  let i = 1;
+  for (let v = n; v > 0; v = Math.floor(v / 4)) i *= 2;
+  return i;
+}
+
+/**
+ * xdiff's test for discarding a line with many matches: only inside a run that
+ * is mostly lines with no match on the other side.
+ */
+function discardMultimatch(dis: Uint8Array, i: number, start: number, end: number): boolean {
+  const s = Math.max(start, i - 100);
+  const e = Math.min(end, i + 100);
+  let none = 0;
+  let many = 1;
+  for (let r = 1; i - r >= s; r++) {
+    if (dis[i - r] === 0) none++;
+    else if (dis[i - r] === 2) many++;
+    else break;
+  }
+  if (none === 0) return false;
+  let noneAfter = 0;
+  let manyAfter = 1;
+  for (let r = 1; i + r <= e; r++) {
+    if (dis[i + r] === 0) noneAfter++;
+    else if (dis[i + r] === 2) manyAfter++;
+    else break;
+  }
+  if (noneAfter === 0) return false;
+  none += noneAfter;
+  many += manyAfter;
+  return many * 4 < many + none;
+}
+
+/**
+ * Lines added and removed, as `git diff --numstat` counts them: xdiff's trimming
+ * and discarding of unmatched lines, then a minimal diff of what's left. Past
+ * MAX_DIFF_EDITS xdiff switches to heuristics, so larger diffs are Unsupported.
+ */
+export function countLineChanges(oldData: Buffer, newData: Buffer): [number, number] {
+  const ids = new Map<string, number>();
+  const toIds = (data: Buffer) =>
+    splitLines(data).map((line) => {
+      let id = ids.get(line);
+      if (id === undefined) {
+        id = ids.size;
+        ids.set(line, id);
+      }
+      return id;
+    });
+  const a = toIds(oldData);
+  const b = toIds(newData);
+  const occA = new Int32Array(ids.size);
+  const occB = new Int32Array(ids.size);
+  for (const id of a) occA[id]++;
+  for (const id of b) occB[id]++;
+
+  const lim = Math.min(a.length, b.length);
+  let start = 0;
+  while (start < lim && a[start] === b[start]) start++;
+  let tail = 0;
+  while (tail < lim - start && a[a.length - 1 - tail] === b[b.length - 1 - tail]) tail++;
+
+  const reduce = (seq: number[], occOther: Int32Array): [number[], number] => {
+    const end = seq.length - tail - 1;
+    const limit = Math.min(bogoSqrt(seq.length), 1024);
+    const dis = new Uint8Array(seq.length);
+    for (let i = start; i <= end; i++) {
+      const matches = occOther[seq[i]];
+      dis[i] = matches === 0 ? 0 : matches >= limit ? 2 : 1;
+    }
+    const kept: number[] = [];
+    let discarded = 0;
+    for (let i = start; i <= end; i++) {
+      if (dis[i] === 1 || (dis[i] === 2 && !discardMultimatch(dis, i, start, end))) {
+        kept.push(seq[i]);
+      } else {
+        discarded++;
+      }
+    }
+    return [kept, discarded];
+  };
+  const [keptA, removedOnly] = reduce(a, occB);
+  const [keptB, addedOnly] = reduce(b, occA);
+
+  // Myers' greedy forward search for the edit distance of what's left
+  const n = keptA.length;
+  const m = keptB.length;
+  const offset = MAX_DIFF_EDITS + 1;
+  const v = new Int32Array(2 * offset + 1);
+  for (let d = 0; d <= MAX_DIFF_EDITS; d++) {
+    for (let k = -d; k <= d; k += 2) {
+      let x =
+        k === -d || (k !== d && v[offset + k - 1] < v[offset + k + 1])
+          ? v[offset + k + 1]
+          : v[offset + k - 1] + 1;
+      let y = x - k;
+      while (x < n && y < m && keptA[x] === keptB[y]) {
+        x++;
+        y++;
+      }
+      v[offset + k] = x;
+      if (x >= n && y >= m) {
+        // d edits in total, and inserts minus deletes is m - n
+        return [addedOnly + (d + m - n) / 2, removedOnly + (d - m + n) / 2];
+      }
+    }
+  }
+  throw new Unsupported('large line diff');
+}
+
+const isBinary = (data: Buffer) => data.subarray(0, BINARY_PROBE_BYTES).includes(0);
+
+const countLines = (data: Buffer) => splitLines(data).length;
+
+interface Change {
+  status: 'A' | 'D' | 'M';
+  old: BlobRef | null;
+  newMode: number;
+  /** Shown id of the working tree side; all zeros unless it's the index entry's. */
+  newOid: string;
+  /** Whether there is a working tree file to read. */
+  present: boolean;
+}
+
+const deletion = (old: BlobRef): Change => ({
+  status: 'D',
+  old,
+  newMode: 0,
+  newOid: NULL_OID,
+  present: false,
+});
+
+/** `git diff --raw --numstat -z --no-abbrev <rev>`: the base commit against the working tree. */
+async function diffWorktree(cwd: string, rev: string): Promise<string> {
+  return withSession(cwd, async ({ ctx, store }) => {
+    const baseTree = await commitTree(store, await resolveRevision(ctx.repo, rev));
+    const baseBlobs = await treeBlobs(store, baseTree, ctx.index);
+    const states = await worktreeStates(ctx);
+
+    const changes = new Map<string, Change>();
+    ctx.index.entries.forEach((entry, i) => {
+      const state = states[i];
+      const base = baseBlobs.get(entry.path) ?? null;
+      if (state.missing) {
+        if (base) changes.set(entry.path, deletion(base));
+        return;
+      }
+      if (state.typeChanged || (base && (base.mode & S_IFMT) !== (state.mode & S_IFMT))) {
+        throw new Unsupported('type change');
+      }
+      if (base && base.oid === state.oid && base.mode === state.mode) return;
+      changes.set(entry.path, {
+        status: base ? 'M' : 'A',
+        old: base,
+        newMode: state.mode,
+        newOid: state.statClean ? entry.oid : NULL_OID,
+        present: true,
+      });
+    });
+    const indexed = new Set(ctx.index.entries.map((e) => e.path));
+    for (const [p, base] of baseBlobs) {
+      if (!indexed.has(p)) changes.set(p, deletion(base));
+    }
+    const statuses = [...changes.values()].map((c) => c.status);
+    if (ctx.settings.diffRenames && statuses.includes('A') && statuses.includes('D')) {
+      throw new Unsupported('possible rename');
+    }
+
+    const paths = sortByBytes([...changes.keys()]);
+    const numstat = await mapLimit(paths, READ_CONCURRENCY, async (p) => {
+      const change = changes.get(p) as Change;
+      const oldData = change.old ? (await store.read(change.old.oid)).data : Buffer.alloc(0);
+      const newData = change.present
+        ? readWorktreeFile(path.join(ctx.repo.workTree, p), change.newMode)
+        : Buffer.alloc(0);
+      if (isBinary(oldData) || isBinary(newData)) return `-\t-\t${p}\0`;
+      let counts: [number, number];
+      if (change.status === 'A') counts = [countLines(newData), 0];
+      else if (change.status === 'D') counts = [0, countLines(oldData)];
+      else counts = countLineChanges(oldData, newData);
+      return `${counts[0]}\t${counts[1]}\t${p}\0`;
+    });
+    const octal = (mode: number) => mode.toString(8).padStart(6, '0');
+    const raw = paths.map((p) => {
+      const c = changes.get(p) as Change;
+      const oldMode = c.old ? octal(c.old.mode) : '000000';
+      const oldOid = c.old ? c.old.oid : NULL_OID;
+      return `:${oldMode} ${octal(c.newMode)} ${oldOid} ${c.newOid} ${c.status}\0${p}\0`;
+    });
+    return raw.join('') + numstat.join('');
+  });
+}
+
+// --- Backend ---
+
+/** The in-process implementation of a command, or null to run it through the CLI. */
+function inProcessCommand(args: string[], opts: GitRunOptions): (() => Promise<string>) | null {
+  const { cwd } = opts;
+  // Anything that changes how git runs or consumes its output stays on the CLI
+  if (!cwd || opts.env || opts.input !== undefined || opts.signal || opts.onOutput) return null;
+  const command = args.join(' ');
+  if (command === 'status --porcelain') return () => status(cwd, false);
+  if (command === 'status --porcelain -z') return () => status(cwd, true);
+  const rev = args[5];
+  if (args.length === 6 && command.startsWith('diff --raw --numstat -z --no-abbrev ')) {
+    if (!rev.startsWith('-')) return () => diffWorktree(cwd, rev);
+  }
+  return null;
+}
+
+/** Answers status and working-tree diff polling in-process; everything else uses the CLI. */
+export const inProcessGitBackend: GitBackend = {
+  name: 'in-process',
+  run: async (args, opts = {}) => {
+    const command = inProcessCommand(args, opts);
+    if (command) {
+      try {
+        return { stdout: await command(), stderr: '' };
+      } catch (e) {
+        const message = `git ${args[0]} via CLI: ${e instanceof Error ? e.message : String(e)}`;
+        if (e instanceof Unsupported) log.debug('git', message);
+        else log.warn('git', message);
+      }
+    }
+    return cliGitBackend.run(args, opts);
+  },
+  runBuffer: (args, opts) => cliGitBackend.runBuffer(args, opts),
+};
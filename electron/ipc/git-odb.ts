@@ -0,0 +1,482 @@
+import { createHash } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+import { promisify } from 'util';
+import zlib from 'zlib';
+
+// Read-only access to a repository's on-disk format for the in-process git
+// backend (git-inprocess.ts): the layout of a checkout or linked worktree,
+// refs, and objects both loose and packed. Whatever falls outside that subset
+// throws Unsupported, and the command goes to the git CLI instead.
+
+const inflate = promisify(zlib.inflate);
+
+const OID_RE = /^[0-9a-f]{40}$/;
+/** Objects kept per store so delta chains and shared subtrees are read once. */
+const OBJECT_CACHE_BYTES = 32 * 1024 * 1024;
+const MAX_DELTA_DEPTH = 10_000;
+
+/** Thrown for repository features the in-process reader doesn't handle. */
+export class Unsupported extends Error {
+  constructor(reason: string) {
+    super(reason);
+    this.name = 'Unsupported';
+  }
+}
+
+export interface RepoLayout {
+  /** Top of the working tree. */
+  workTree: string;
+  /** Per-worktree git dir: HEAD, index. */
+  gitDir: string;
+  /** Shared git dir: objects, refs, config. */
+  commonDir: string;
+}
+
+export type ObjectType = 'commit' | 'tree' | 'blob' | 'tag';
+
+export interface GitObject {
+  type: ObjectType;
+  data: Buffer;
+}
+
+export interface TreeEntry {
+  name: string;
+  mode: number;
+  oid: string;
+}
+
+export interface ObjectStore {
+  read(oid: string): Promise<GitObject>;
+  /** Release open pack files. */
+  close(): Promise<void>;
+}
+
+// Variables that point git somewhere other than the checkout in cwd
+const LOCATION_ENV = [
+  'GIT_DIR',
+  'GIT_WORK_TREE',
+  'GIT_COMMON_DIR',
+  'GIT_INDEX_FILE',
+  'GIT_OBJECT_DIRECTORY',
+  'GIT_ALTERNATE_OBJECT_DIRECTORIES',
+  'GIT_NAMESPACE',
+];
+
+const isMissing = (e: unknown) => {
+  const code = (e as NodeJS.ErrnoException).code;
+  return code === 'ENOENT' || code === 'ENOTDIR';
+};
+
+async function readOptional(file: string): Promise<string | null> {
+  try {
+    return await fs.promises.readFile(file, 'utf8');
+  } catch (e) {
+    if (isMissing(e) || (e as NodeJS.ErrnoException).code === 'EISDIR') return null;
+    throw e;
+  }
+}
+
+/** Locate the git dirs of the checkout whose top level is `workTree`. */
+export async function findRepo(workTree: string): Promise<RepoLayout> {
+  const overridden = LOCATION_ENV.find((name) => process.env[name] !== undefined);
+  if (overridden) throw new Unsupported(`${overridden} is set`);
+  const dotGit = path.join(workTree, '.git');
+  let stat: fs.Stats;
+  try {
+    stat = await fs.promises.stat(dotGit);
+  } catch (e) {
+    if (isMissing(e)) throw new Unsupported('not the top level of a checkout');
+    throw e;
+  }
+  let gitDir = dotGit;
+  if (!stat.isDirectory()) {
+    // Linked worktrees and submodules have a `gitdir: <path>` file instead
+    const link = /^gitdir: (.+)$/m.exec(await fs.promises.readFile(dotGit, 'utf8'));
+    if (!link) throw new Unsupported('unreadable .git file');
+    gitDir = path.resolve(workTree, link[1].trim());
+  }
+  const common = await readOptional(path.join(gitDir, 'commondir'));
+  const commonDir = common ? path.resolve(gitDir, common.trim()) : gitDir;
+  return { workTree, gitDir, commonDir };
+}
+
+// --- Refs ---
+
+/** Refs stored per worktree rather than in the shared git dir. */
+const isPerWorktreeRef = (ref: string) =>
+  /^[A-Z_]+$/.test(ref) || /^refs\/(?:bisect|worktree|rewritten)\//.test(ref);
+
+const packedRefsCache = new Map<string, { mtimeMs: number; refs: Map<string, string> }>();
+
+async function readPackedRefs(repo: RepoLayout): Promise<Map<string, string>> {
+  const file = path.join(repo.commonDir, 'packed-refs');
+  let mtimeMs: number;
+  try {
+    mtimeMs = (await fs.promises.stat(file)).mtimeMs;
+  } catch (e) {
+    if (isMissing(e)) return new Map();
+    throw e;
+  }
+  const cached = packedRefsCache.get(file);
+  if (cached?.mtimeMs === mtimeMs) return cached.refs;
+  const refs = new Map<string, string>();
+  for (const line of (await fs.promises.readFile(file, 'utf8')).split('\n')) {
+    // Header comments and peeled `^<oid>` lines don't name refs
+    const m = /^([0-9a-f]{40}) (.+)$/.exec(line);
+    if (m) refs.set(m[2], m[1]);
+  }
+  packedRefsCache.set(file, { mtimeMs, refs });
+  return refs;
+}
+
+/** Object id a ref points at, following symbolic refs; null if it doesn't exist. */
+export async function resolveRef(repo: RepoLayout, name: string): Promise<string | null> {
+  let ref = name;
+  for (let depth = 0; depth < 5; depth++) {
+    const dir = isPerWorktreeRef(ref) ? repo.gitDir : repo.commonDir;
+    let content = (await readOptional(path.join(dir, ref)))?.trim() ?? null;
+    if (content === null) content = (await readPackedRefs(repo)).get(ref) ?? null;
+    if (content === null) return null;
+    if (content.startsWith('ref: ')) {
+      ref = content.slice(5).trim();
+      continue;
+    }
+    if (!OID_RE.test(content)) throw new Unsupported(`unreadable ref ${ref}`);
+    return content;
+  }
+  throw new Unsupported(`symbolic ref loop at ${name}`);
+}
+
+/** A full object id or ref name (with git's usual prefixes tried), as an object id. */
+export async function resolveRevision(repo: RepoLayout, rev: string): Promise<string> {
+  if (OID_RE.test(rev)) return rev;
+  // Abbreviated ids and revision syntax (`~`, `^`, `@{}`, `..`) are left to the CLI
+  if (!/^[\w/.-]+$/.test(rev) || rev.includes('..')) throw new Unsupported(`revision ${rev}`);
+  const candidates = rev.startsWith('refs/')
+    ? [rev]
+    : [
+        rev,
+        `refs/${rev}`,
+        `refs/tags/${rev}`,
+        `refs/heads/${rev}`,
+        `refs/remotes/${rev}`,
+        `refs/remotes/${rev}/HEAD`,
+      ];
+  for (const ref of candidates) {
+    const oid = await resolveRef(repo, ref);
+    if (oid) return oid;
+  }
+  throw new Unsupported(`unknown revision ${rev}`);
+}
+
+// --- Objects ---
+
+interface PackIndex {
+  packPath: string;
+  data: Buffer;
+  count: number;
+}
+
+/** Parsed pack indexes by path, reloaded when the file changes. */
+const packIndexCache = new Map<string, { mtimeMs: number; index: PackIndex }>();
+
+const OBJECT_TYPES: Record<number, ObjectType> = { 1: 'commit', 2: 'tree', 3: 'blob', 4: 'tag' };
+const OFS_DELTA = 6;
+const REF_DELTA = 7;
+/** Version 2 pack index: magic, version, 256-entry fanout, then the sorted ids. */
+const IDX_OIDS = 8 + 256 * 4;
+
+async function loadPackIndexes(objectsDir: string): Promise<PackIndex[]> {
+  const packDir = path.join(objectsDir, 'pack');
+  let names: string[];
+  try {
+    names = await fs.promises.readdir(packDir);
+  } catch (e) {
+    if (isMissing(e)) return [];
+    throw e;
+  }
+  const indexes: PackIndex[] = [];
+  for (const name of names.filter((n) => n.endsWith('.idx')).sort()) {
+    const idxPath = path.join(packDir, name);
+    const { mtimeMs } = await fs.promises.stat(idxPath);
+    const cached = packIndexCache.get(idxPath);
+    if (cached?.mtimeMs === mtimeMs) {
+      indexes.push(cached.index);
+      continue;
+    }
+    const data = await fs.promises.readFile(idxPath);
+    if (data.readUInt32BE(0) !== 0xff744f63 || data.readUInt32BE(4) !== 2) {
+      throw new Unsupported(`pack index version in ${name}`);
+    }
+    const index: PackIndex = {
+      packPath: idxPath.replace(/\.idx$/, '.pack'),
+      data,
+      count: data.readUInt32BE(IDX_OIDS - 4),
+    };
+    packIndexCache.set(idxPath, { mtimeMs, index });
+    indexes.push(index);
+  }
+  return indexes;
+}
+
+/** Offset of an object in the pack, or null if the pack doesn't have it. */
+function findPackOffset(index: PackIndex, oid: Buffer): number | null {
+  const { data, count } = index;
+  let lo = oid[0] === 0 ? 0 : data.readUInt32BE(8 + (oid[0] - 1) * 4);
+  let hi = data.readUInt32BE(8 + oid[0] * 4);
+  while (lo < hi) {
+    const mid = (lo + hi) >>> 1;
+    const at = IDX_OIDS + mid * 20;
+    const cmp = oid.compare(data, at, at + 20);
+    if (cmp < 0) {
+      hi = mid;
+    } else if (cmp > 0) {
+      lo = mid + 1;
+    } else {
+      const offsets = IDX_OIDS + count * 24;
+      const offset = data.readUInt32BE(offsets + mid * 4);
+      if (!(offset & 0x80000000)) return offset;
+      // The high bit points into the table of 8-byte offsets for packs over 2 GiB
+      const large = offsets + count * 4 + (offset & 0x7fffffff) * 8;
+      return Number(data.readBigUInt64BE(large));
+    }
+  }
+  return null;
+}
+
+function applyDelta(base: Buffer, delta: Buffer): Buffer {
+  let pos = 0;
+  const size = () => {
+    let value = 0;
+    let shift = 0;
+    let c: number;
+    do {
+      c = delta[pos++];
+      value += (c & 0x7f) * 2 ** shift;
+      shift += 7;
+    } while (c & 0x80);
+    return value;
+  };
+  if (size() !== base.length) throw new Unsupported('delta base size mismatch');
+  const out = Buffer.alloc(size());
+  let at = 0;
+  while (pos < delta.length) {
+    const op = delta[pos++];
+    if (op & 0x80) {
+      // Copy from the base: which offset and size bytes follow is flagged in op
+      let offset = 0;
+      let length = 0;
+      for (let bit = 0; bit < 4; bit++) {
+        if (op & (1 << bit)) offset += delta[pos++] * 2 ** (8 * bit);
+      }
+      for (let bit = 0; bit < 3; bit++) {
+        if (op & (0x10 << bit)) length += delta[pos++] * 2 ** (8 * bit);
+      }
+      at += base.copy(out, at, offset, offset + (length || 0x10000));
+    } else if (op > 0) {
+      at += delta.copy(out, at, pos, pos + op);
+      pos += op;
+    } else {
+      throw new Unsupported('invalid delta');
+    }
+  }
+  if (at !== out.length) throw new Unsupported('delta result size mismatch');
+  return out;
+}
+
+function parseLoose(raw: Buffer, oid: string): GitObject {
+  const nul = raw.indexOf(0);
+  const [type, size] = raw.toString('latin1', 0, nul).split(' ');
+  const data = raw.subarray(nul + 1);
+  if (!Object.values(OBJECT_TYPES).includes(type as ObjectType) || Number(size) !== data.length) {
+    throw new Unsupported(`corrupt loose object ${oid}`);
+  }
+  return { type: type as ObjectType, data };
+}
+
+async function objectDirs(objectsDir: string, depth = 0): Promise<string[]> {
+  const alternates = await readOptional(path.join(objectsDir, 'info', 'alternates'));
+  if (!alternates) return [objectsDir];
+  if (depth >= 5) throw new Unsupported('nested alternates');
+  const dirs = [objectsDir];
+  for (const line of alternates.split('\n')) {
+    const dir = line.trim();
+    if (!dir || dir.startsWith('#')) continue;
+    dirs.push(...(await objectDirs(path.resolve(objectsDir, dir), depth + 1)));
+  }
+  return dirs;
+}
+
+/** Object reader over the repository's loose objects, packs and alternates. */
+export async function openObjectStore(repo: RepoLayout): Promise<ObjectStore> {
+  const dirs = await objectDirs(path.join(repo.commonDir, 'objects'));
+  const packs = (await Promise.all(dirs.map(loadPackIndexes))).flat();
+  const handles = new Map<string, Promise<fs.promises.FileHandle>>();
+  // Insertion-ordered, so the first key is the least recently used
+  const cache = new Map<string, GitObject>();
+  let cachedBytes = 0;
+
+  const remember = (key: string, object: GitObject) => {
+    cache.set(key, object);
+    cachedBytes += object.data.length;
+    for (const [oldKey, old] of cache) {
+      if (cachedBytes <= OBJECT_CACHE_BYTES) break;
+      cache.delete(oldKey);
+      cachedBytes -= old.data.length;
+    }
+    return object;
+  };
+
+  const recall = (key: string) => {
+    const object = cache.get(key);
+    if (object) {
+      cache.delete(key);
+      cache.set(key, object);
+    }
+    return object;
+  };
+
+  const handle = (packPath: string) => {
+    let fh = handles.get(packPath);
+    if (!fh) {
+      fh = fs.promises.open(packPath, 'r');
+      handles.set(packPath, fh);
+    }
+    return fh;
+  };
+
+  /** Inflate a zlib stream of known output size starting at `pos`. */
+  const inflateAt = async (fh: fs.promises.FileHandle, pos: number, size: number) => {
+    // Deflate adds a few bytes per block; read more only when that wasn't enough
+    let length = size + 64 + Math.ceil(size / 1000);
+    for (;;) {
+      const buf = Buffer.alloc(length);
+      const { bytesRead } = await fh.read(buf, 0, length, pos);
+      try {
+        const out = await inflate(buf.subarray(0, bytesRead));
+        if (out.length !== size) throw new Unsupported('packed object size mismatch');
+        return out;
+      } catch (e) {
+        if ((e as NodeJS.ErrnoException).code !== 'Z_BUF_ERROR' || bytesRead < length) throw e;
+        length *= 2;
+      }
+    }
+  };
+
+  const readPacked = async (
+    packPath: string,
+    offset: number,
+    depth: number,
+  ): Promise<GitObject> => {
+    const key = `${packPath}:${offset}`;
+    const cached = recall(key);
+    if (cached) return cached;
+    if (depth > MAX_DELTA_DEPTH) throw new Unsupported('delta chain too deep');
+    const fh = await handle(packPath);
+    const head = Buffer.alloc(32);
+    await fh.read(head, 0, head.length, offset);
+    let pos = 0;
+    let c = head[pos++];
+    const typeNum = (c >> 4) & 7;
+    let size = c & 0x0f;
+    let shift = 4;
+    while (c & 0x80) {
+      c = head[pos++];
+      size += (c & 0x7f) * 2 ** shift;
+      shift += 7;
+    }
+    let base: (() => Promise<GitObject>) | null = null;
+    if (typeNum === OFS_DELTA) {
+      c = head[pos++];
+      let back = c & 0x7f;
+      while (c & 0x80) {
+        c = head[pos++];
+        back = (back + 1) * 128 + (c & 0x7f);
+      }
+      base = () => readPacked(packPath, offset - back, depth + 1);
+    } else if (typeNum === REF_DELTA) {
+      const baseOid = head.toString('hex', pos, pos + 20);
+      base = () => readObject(baseOid, depth + 1);
+      pos += 20;
+    }
+    const data = await inflateAt(fh, offset + pos, size);
+    if (base) {
+      const { type, data: baseData } = await base();
+      return remember(key, { type, data: applyDelta(baseData, data) });
+    }
+    const type = OBJECT_TYPES[typeNum];
+    if (!type) throw new Unsupported(`pack object type ${typeNum}`);
+    return remember(key, { type, data });
+  };
+
+  const readObject = async (oid: string, depth: number): Promise<GitObject> => {
+    if (!OID_RE.test(oid)) throw new Unsupported(`object id ${oid}`);
+    const cached = recall(oid);
+    if (cached) return cached;
+    const binary = Buffer.from(oid, 'hex');
+    for (const pack of packs) {
+      const offset = findPackOffset(pack, binary);
+      if (offset !== null) return readPacked(pack.packPath, offset, depth);
+    }
+    for (const dir of dirs) {
+      let raw: Buffer;
+      try {
+        raw = await fs.promises.readFile(path.join(dir, oid.slice(0, 2), oid.slice(2)));
+      } catch (e) {
+        if (isMissing(e)) continue;
+        throw e;
+      }
+      return remember(oid, parseLoose(await inflate(raw), oid));
+    }
+    // Possibly packed after the indexes were listed
+    throw new Unsupported(`object ${oid} not found`);
+  };
+
+  return {
+    read: (oid) => readObject(oid, 0),
+    close: async () => {
+      const open = [...handles.values()];
+      handles.clear();
+      await Promise.all(open.map((fh) => fh.then((h) => h.close()).catch(() => {})));
+    },
+  };
+}
+
+export function parseTree(data: Buffer): TreeEntry[] {
+  const entries: TreeEntry[] = [];
+  let pos = 0;
+  while (pos < data.length) {
+    const space = data.indexOf(0x20, pos);
+    const nul = data.indexOf(0, space);
+    if (space === -1 || nul === -1 || nul + 21 > data.length) throw new Unsupported('corrupt tree');
+    const name = data.toString('utf8', space + 1, nul);
+    if (name.includes('\ufffd')) throw new Unsupported('path is not valid UTF-8');
+    entries.push({
+      name,
+      mode: parseInt(data.toString('latin1', pos, space), 8),
+      oid: data.toString('hex', nul + 1, nul + 21),
+    });
+    pos = nul + 21;
+  }
+  return entries;
+}
+
+/** Tree id of a commit, peeling annotated tags on the way. */
+export async function commitTree(store: ObjectStore, oid: string): Promise<string> {
+  let current = oid;
+  for (let depth = 0; depth < 10; depth++) {
+    const { type, data } = await store.read(current);
+    const header = data.toString('latin1', 0, Math.min(data.length, 128));
+    const field = type === 'commit' ? 'tree' : type === 'tag' ? 'object' : null;
+    const m = field ? new RegExp(`^${field} ([0-9a-f]{40})$`, 'm').exec(header) : null;
+    if (!m) throw new Unsupported(`${oid} is not a commit`);
+    if (type === 'commit') return m[1];
+    current = m[1];
+  }
+  throw new Unsupported(`tag chain at ${oid}`);
+}
+
+export function hashBlob(content: Buffer): string {
+  return createHash('sha1').update(`blob ${content.length}\0`).update(content).digest('hex');
+}
@@ -0,0 +1,421 @@
+import { execFileSync } from 'child_process';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi } from 'vitest';
+
+// Per-repo settings are read from the state dir, which lives under a temp dir here
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'git-test-'));
+vi.mock('electron', () => ({ app: { getPath: () => root, isPackaged: true } }));
+
+import {
+  abortMerge,
+  continueMerge,
+  discardAllChanges,
+  discardFileChanges,
+  getFileHunks,
+  mergeTask,
+  parsePorcelainZ,
+  parseRawNumstatZ,
+  resolveConflict,
+  stageHunk,
+  unstageHunk,
+} from './git.js';
+
+const OID_A = 'a'.repeat(40);
+const OID_B = 'b'.repeat(40);
+const ZERO = '0'.repeat(40);
+
+/** One `--raw` record: the header field, then the path field(s). */
+const raw = (status: string, ...paths: string[]) =>
+  [`:100644 100644 ${OID_A} ${OID_B} ${status}`, ...paths].map((f) => `${f}\0`).join('');
+
+// ---------------------------------------------------------------------------
+// parsePorcelainZ
+// ---------------------------------------------------------------------------
+describe('parsePorcelainZ', () => {
+  it.each([
+    ['empty output', '', []],
+    ['a modified file', ' M src/a.ts\0', [{ xy: ' M', path: 'src/a.ts', origPath: null }]],
+    ['an untracked directory', '?? build/\0', [{ xy: '??', path: 'build/', origPath: null }]],
+    [
+      'paths with spaces and newlines verbatim',
+      'A  has space.txt\0?? line\nbreak\0',
+      [
+        { xy: 'A ', path: 'has space.txt', origPath: null },
+        { xy: '??', path: 'line\nbreak', origPath: null },
+      ],
+    ],
+    [
+      'a rename followed by its source',
+      'R  new.ts\0old.ts\0 M other.ts\0',
+      [
+        { xy: 'R ', path: 'new.ts', origPath: 'old.ts' },
+        { xy: ' M', path: 'other.ts', origPath: null },
+      ],
+    ],
+    [
+      'a copy with a worktree change',
+      'CM copy.ts\0orig.ts\0',
+      [{ xy: 'CM', path: 'copy.ts', origPath: 'orig.ts' }],
+    ],
+    [
+      'a path that looks like a status line',
+      'D  R  x\0',
+      [{ xy: 'D ', path: 'R  x', origPath: null }],
+    ],
+  ])('parses %s', (_name, out, expected) => {
+    expect(parsePorcelainZ(out)).toEqual(expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// parseRawNumstatZ
+// ---------------------------------------------------------------------------
+describe('parseRawNumstatZ', () => {
+  it.each([
+    {
+      name: 'a modification',
+      out: raw('M', 'src/a.ts') + '3\t1\tsrc/a.ts\0',
+      status: [['src/a.ts', 'M']],
+      orig: [],
+      numstat: [['src/a.ts', [3, 1]]],
+      binary: [],
+    },
+    {
+      name: 'an addition and a deletion',
+      out:
+        `:000000 100644 ${ZERO} ${OID_B} A\0new.ts\0` +
+        `:100644 000000 ${OID_A} ${ZERO} D\0gone.ts\0` +
+        '5\t0\tnew.ts\0' +
+        '0\t7\tgone.ts\0',
+      status: [
+        ['new.ts', 'A'],
+        ['gone.ts', 'D'],
+      ],
+      orig: [],
+      numstat: [
+        ['new.ts', [5, 0]],
+        ['gone.ts', [0, 7]],
+      ],
+      binary: [],
+    },
+    {
+      name: 'a rename with a similarity score',
+      out: raw('R087', 'old name.ts', 'new name.ts') + '2\t2\t\0old name.ts\0new name.ts\0',
+      status: [['new name.ts', 'R']],
+      orig: [['new name.ts', 'old name.ts']],
+      numstat: [['new name.ts', [2, 2]]],
+      binary: [],
+    },
+    {
+      name: 'a copy',
+      out: raw('C100', 'a.ts', 'b.ts') + '0\t0\t\0a.ts\0b.ts\0',
+      status: [['b.ts', 'C']],
+      orig: [['b.ts', 'a.ts']],
+      numstat: [['b.ts', [0, 0]]],
+      binary: [],
+    },
+    {
+      name: 'a binary file',
+      out: raw('M', 'logo.png') + '-\t-\tlogo.png\0',
+      status: [['logo.png', 'M']],
+      orig: [],
+      numstat: [],
+      binary: ['logo.png'],
+    },
+    {
+      name: 'a renamed binary file',
+      out: raw('R100', 'a.bin', 'b.bin') + '-\t-\t\0a.bin\0b.bin\0',
+      status: [['b.bin', 'R']],
+      orig: [['b.bin', 'a.bin']],
+      numstat: [],
+      binary: ['b.bin'],
+    },
+    {
+      name: 'a path containing a tab',
+      out: raw('M', 'a\tb') + '1\t0\ta\tb\0',
+      status: [['a\tb', 'M']],
+      orig: [],
+      numstat: [['a\tb', [1, 0]]],
+      binary: [],
+    },
+  ])('parses $name', ({ out, status, orig, numstat, binary }) => {
+    const result = parseRawNumstatZ(out);
+    expect([...result.statusMap]).toEqual(status);
+    expect([...result.origPaths]).toEqual(orig);
+    expect([...result.numstatMap]).toEqual(numstat);
+    expect([...result.binaryPaths]).toEqual(binary);
+  });
+
+  it('returns empty maps for empty output', () => {
+    const result = parseRawNumstatZ('');
+    expect(result.statusMap.size).toBe(0);
+    expect(result.numstatMap.size).toBe(0);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Temporary repositories
+// ---------------------------------------------------------------------------
+let repoCount = 0;
+
+function git(cwd: string, ...args: string[]): string {
+  return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] });
+}
+
+function write(dir: string, files: Record<string, string>): void {
+  for (const [name, content] of Object.entries(files)) {
+    fs.mkdirSync(path.dirname(path.join(dir, name)), { recursive: true });
+    fs.writeFileSync(path.join(dir, name), content);
+  }
+}
+
+function commit(dir: string, files: Record<string, string>, message: string): void {
+  write(dir, files);
+  git(dir, 'add', '-A');
+  git(dir, 'commit', '-qm', message);
+}
+
+/** A fresh repository on `main` with one commit of `files`. */
+function createRepo(files: Record<string, string>): string {
+  const dir = path.join(root, `repo-${++repoCount}`);
+  fs.mkdirSync(dir);
+  git(dir, 'init', '-q', '-b', 'main');
+  git(dir, 'config', 'user.name', 'Test');
+  git(dir, 'config', 'user.email', 'test@example.com');
+  git(dir, 'config', 'commit.gpgsign', 'false');
+  commit(dir, files, 'base');
+  return dir;
+}
+
+/** A repository whose `task` branch and `main` both changed `a.txt` and `b.txt`. */
+function createConflictingRepo(): string {
+  const dir = createRepo({ 'a.txt': 'one\ntwo\n', 'b.txt': 'b\n', 'c.txt': 'c\n' });
+  git(dir, 'checkout', '-qb', 'task');
+  commit(dir, { 'a.txt': 'one\ntask\n', 'b.txt': 'task b\n', 'c.txt': 'task c\n' }, 'task');
+  git(dir, 'checkout', '-q', 'main');
+  commit(dir, { 'a.txt': 'one\nmain\n', 'b.txt': 'main b\n' }, 'main');
+  return dir;
+}
+
+const show = (dir: string, spec: string) => git(dir, 'show', spec);
+
+// ---------------------------------------------------------------------------
+// Merge conflicts
+// ---------------------------------------------------------------------------
+describe('merge conflict resolution', () => {
+  it('reports conflicts with each side of the file', async () => {
+    const dir = createConflictingRepo();
+    const result = await mergeTask(dir, 'task', false, null, false, true);
+    expect(result.main_branch).toBe('main');
+    expect(result.conflict?.branch_name).toBe('task');
+    expect(result.conflict?.files).toEqual([
+      {
+        path: 'a.txt',
+        base: 'one\ntwo\n',
+        ours: 'one\nmain\n',
+        theirs: 'one\ntask\n',
+        binary: false,
+      },
+      { path: 'b.txt', base: 'b\n', ours: 'main b\n', theirs: 'task b\n', binary: false },
+    ]);
+    await abortMerge(dir);
+  });
+
+  it('commits the merge once every file is resolved', async () => {
+    const dir = createConflictingRepo();
+    await mergeTask(dir, 'task', false, null, false, true);
+    await expect(continueMerge(dir)).rejects.toThrow(/Unresolved conflicts remain: a.txt, b.txt/);
+
+    expect(await resolveConflict(dir, 'a.txt', { type: 'theirs' })).toEqual({
+      remaining: ['b.txt'],
+    });
+    const resolved = { type: 'content' as const, content: 'both b\n' };
+    expect(await resolveConflict(dir, 'b.txt', resolved)).toEqual({ remaining: [] });
+    await continueMerge(dir);
+
+    expect(show(dir, 'HEAD:a.txt')).toBe('one\ntask\n');
+    expect(show(dir, 'HEAD:b.txt')).toBe('both b\n');
+    expect(show(dir, 'HEAD:c.txt')).toBe('task c\n');
+    expect(git(dir, 'rev-list', '--parents', '-n1', 'HEAD').trim().split(' ')).toHaveLength(3);
+    expect(git(dir, 'status', '--porcelain')).toBe('');
+    await expect(continueMerge(dir)).rejects.toThrow(/No merge in progress/);
+  });
+
+  it('commits a squash merge as a single-parent commit', async () => {
+    const dir = createConflictingRepo();
+    const result = await mergeTask(dir, 'task', true, 'Squashed task', false, true);
+    expect(result.conflict?.files.map((f) => f.path)).toEqual(['a.txt', 'b.txt']);
+    await resolveConflict(dir, 'a.txt', { type: 'ours' });
+    await resolveConflict(dir, 'b.txt', { type: 'theirs' });
+    await continueMerge(dir);
+
+    expect(git(dir, 'log', '-1', '--format=%P').trim().split(' ')).toHaveLength(1);
+    expect(git(dir, 'log', '-1', '--format=%s')).toBe('Squashed task\n');
+    expect(show(dir, 'HEAD:a.txt')).toBe('one\nmain\n');
+    expect(show(dir, 'HEAD:b.txt')).toBe('task b\n');
+  });
+
+  it.each([false, true])('aborts the merge (squash: %s)', async (squash) => {
+    const dir = createConflictingRepo();
+    const before = git(dir, 'rev-parse', 'HEAD');
+    await mergeTask(dir, 'task', squash, null, false, true);
+    await expect(mergeTask(dir, 'task', squash, null, false, true)).rejects.toThrow(
+      /already in progress/,
+    );
+    await abortMerge(dir);
+
+    expect(git(dir, 'rev-parse', 'HEAD')).toBe(before);
+    expect(git(dir, 'status', '--porcelain')).toBe('');
+    expect(fs.existsSync(path.join(dir, '.git', 'MERGE_HEAD'))).toBe(false);
+    await expect(abortMerge(dir)).rejects.toThrow(/No merge in progress/);
+  });
+
+  it('aborts a conflicting merge and throws without keepConflicts', async () => {
+    const dir = createConflictingRepo();
+    await expect(mergeTask(dir, 'task', false, null, false)).rejects.toThrow(/Merge failed/);
+    expect(git(dir, 'status', '--porcelain')).toBe('');
+    await expect(continueMerge(dir)).rejects.toThrow(/No merge in progress/);
+  });
+
+  it('flags binary conflicts without their content', async () => {
+    const dir = createRepo({ 'img.bin': 'base\0' });
+    git(dir, 'checkout', '-qb', 'task');
+    commit(dir, { 'img.bin': 'task\0' }, 'task');
+    git(dir, 'checkout', '-q', 'main');
+    commit(dir, { 'img.bin': 'main\0' }, 'main');
+
+    const result = await mergeTask(dir, 'task', false, null, false, true);
+    expect(result.conflict?.files).toEqual([
+      { path: 'img.bin', base: null, ours: null, theirs: null, binary: true },
+    ]);
+    await abortMerge(dir);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Hunk staging
+// ---------------------------------------------------------------------------
+describe('hunk staging', () => {
+  const numbered = (n: number) => Array.from({ length: n }, (_, i) => `line ${i + 1}\n`).join('');
+
+  /** A repo where `file.txt` has an unstaged change near its start and one near its end. */
+  function createRepoWithTwoHunks(): string {
+    const dir = createRepo({ 'file.txt': numbered(30) });
+    const changed = numbered(30).replace('line 2\n', 'second\n').replace('line 29\n', '');
+    write(dir, { 'file.txt': changed });
+    return dir;
+  }
+
+  it('lists unstaged and staged hunks', async () => {
+    const dir = createRepoWithTwoHunks();
+    const { unstaged, staged } = await getFileHunks(dir, 'file.txt');
+    expect(staged).toEqual([]);
+    const ranges = unstaged.map((h) => [h.old_start, h.old_lines, h.new_start, h.new_lines]);
+    expect(ranges).toEqual([
+      [1, 5, 1, 5],
+      [26, 5, 26, 4],
+    ]);
+    expect(unstaged[0].lines.filter((l) => /^[-+]/.test(l))).toEqual(['-line 2', '+second']);
+  });
+
+  it('stages and unstages a single hunk', async () => {
+    const dir = createRepoWithTwoHunks();
+    const [first, second] = (await getFileHunks(dir, 'file.txt')).unstaged;
+
+    await stageHunk(dir, 'file.txt', first.header);
+    expect(show(dir, ':file.txt')).toBe(numbered(30).replace('line 2\n', 'second\n'));
+    const afterStage = await getFileHunks(dir, 'file.txt');
+    expect(afterStage.staged.map((h) => h.header)).toEqual([first.header]);
+    expect(afterStage.unstaged.map((h) => h.header)).toEqual([second.header]);
+
+    await unstageHunk(dir, 'file.txt', first.header);
+    expect(show(dir, ':file.txt')).toBe(numbered(30));
+    expect((await getFileHunks(dir, 'file.txt')).unstaged).toHaveLength(2);
+    // The working tree keeps both changes throughout
+    expect(fs.readFileSync(path.join(dir, 'file.txt'), 'utf8')).toContain('second\n');
+  });
+
+  it('stages a hunk at the end of a file without a final newline', async () => {
+    const dir = createRepo({ 'file.txt': 'a\nb\nc' });
+    write(dir, { 'file.txt': 'a\nb\nc\nd' });
+    const [hunk] = (await getFileHunks(dir, 'file.txt')).unstaged;
+    await stageHunk(dir, 'file.txt', hunk.header);
+    expect(show(dir, ':file.txt')).toBe('a\nb\nc\nd');
+  });
+
+  it('refuses a hunk that is no longer in the diff', async () => {
+    const dir = createRepoWithTwoHunks();
+    await expect(stageHunk(dir, 'file.txt', '@@ -9,3 +9,3 @@')).rejects.toThrow(/Hunk not found/);
+    await expect(unstageHunk(dir, 'file.txt', '@@ -1,5 +1,5 @@')).rejects.toThrow(
+      /Hunk not found/,
+    );
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Discarding changes
+// ---------------------------------------------------------------------------
+describe('discarding changes', () => {
+  /** Modified, staged, newly added, untracked and ignored files. */
+  function createDirtyRepo(): string {
+    const dir = createRepo({ '.gitignore': '*.log\n', 'a.txt': 'a\n', 'b.txt': 'b\n' });
+    write(dir, { 'a.txt': 'changed a\n', 'b.txt': 'staged b\n', 'added.txt': 'added\n' });
+    git(dir, 'add', 'b.txt', 'added.txt');
+    write(dir, { 'new/untracked.txt': 'u\n', 'debug.log': 'log\n' });
+    return dir;
+  }
+
+  const read = (dir: string, file: string) =>
+    fs.existsSync(path.join(dir, file)) ? fs.readFileSync(path.join(dir, file), 'utf8') : null;
+
+  it('reverts tracked files and deletes untracked ones', async () => {
+    const dir = createDirtyRepo();
+    await discardFileChanges(dir, ['b.txt', 'added.txt', 'new/untracked.txt']);
+
+    expect(read(dir, 'b.txt')).toBe('b\n');
+    expect(read(dir, 'added.txt')).toBeNull();
+    expect(read(dir, 'new/untracked.txt')).toBeNull();
+    // Paths not passed are left alone
+    expect(read(dir, 'a.txt')).toBe('changed a\n');
+    expect(read(dir, 'debug.log')).toBe('log\n');
+    expect(git(dir, 'status', '--porcelain')).toBe(' M a.txt\n');
+  });
+
+  it('does nothing for an empty list', async () => {
+    const dir = createDirtyRepo();
+    const before = git(dir, 'status', '--porcelain');
+    await discardFileChanges(dir, []);
+    expect(git(dir, 'status', '--porcelain')).toBe(before);
+  });
+
+  it('discards everything only with the token from a first call', async () => {
+    const dir = createDirtyRepo();
+    const first = await discardAllChanges(dir, null);
+    expect(first.discarded).toBe(false);
+    expect(first.files.sort()).toEqual(['a.txt', 'added.txt', 'b.txt', 'new/']);
+    expect(read(dir, 'a.txt')).toBe('changed a\n');
+
+    const wrong = await discardAllChanges(dir, 'not-the-token');
+    expect(wrong.discarded).toBe(false);
+    // A new report replaces the earlier token
+    expect(await discardAllChanges(dir, first.confirm_token)).toEqual(
+      expect.objectContaining({ discarded: false }),
+    );
+
+    const report = await discardAllChanges(dir, null);
+    expect(await discardAllChanges(dir, report.confirm_token)).toEqual({
+      discarded: true,
+      confirm_token: null,
+      files: [],
+    });
+    expect(git(dir, 'status', '--porcelain')).toBe('');
+    expect(read(dir, 'added.txt')).toBeNull();
+    // Ignored files are not part of the discard
+    expect(read(dir, 'debug.log')).toBe('log\n');
+
+    // Tokens are single-use
+    expect((await discardAllChanges(dir, report.confirm_token)).discarded).toBe(false);
+  });
+});
@@ -1,9 +1,23 @@
-import { execFile } from 'child_process';
-import { promisify } from 'util';
+import { randomUUID } from 'crypto';
 import fs from 'fs';
+import os from 'os';
 import path from 'path';
-
-const exec = promisify(execFile);
+import { getAskpassEnv } from './askpass.js';
+import { getGitBackend, GitError, type GitResult, type GitRunOptions } from './git-backend.js';
+import { mcpConfigFiles } from './mcp.js';
+import { getRepoSettings, updateRepoSettings, type RepoSettings } from './repo-settings.js';
+import {
+  getWorktreeLocation,
+  getWorktreeRoot,
+  resolveWorktreeRoot,
+  saveWorktreeLocation,
+  type WorktreeLocation,
+} from './worktree-location.js';
+
+// Every git invocation goes through the active backend (see git-backend.ts)
+function exec(_cmd: 'git', args: string[], opts?: GitRunOptions): Promise<GitResult> {
+  return getGitBackend().run(args, opts);
+}
 
 // --- TTL Caches ---
 
@@ -58,44 +72,53 @@ const SYMLINK_CANDIDATES = [
 
 // --- Internal helpers ---
 
+/** How the base branch was determined; `fallback` means none of the others matched. */
+export type MainBranchSource = 'override' | 'origin_head' | 'main' | 'master' | 'fallback';
+
 async function detectMainBranch(repoRoot: string): Promise<string> {
   const key = cacheKey(repoRoot);
   const cached = mainBranchCache.get(key);
   if (cached && cached.expiresAt > Date.now()) return cached.value;
 
-  const result = await detectMainBranchUncached(repoRoot);
+  const { branch: result } = await detectMainBranchUncached(repoRoot);
   mainBranchCache.set(key, { value: result, expiresAt: Date.now() + MAIN_BRANCH_TTL });
   return result;
 }
 
-async function detectMainBranchUncached(repoRoot: string): Promise<string> {
-  // Try remote HEAD reference first
+async function refExists(repoRoot: string, ref: string): Promise<boolean> {
+  try {
+    await exec('git', ['rev-parse', '--verify', '--quiet', `${ref}^{commit}`], { cwd: repoRoot });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+async function detectMainBranchUncached(
+  repoRoot: string,
+): Promise<{ branch: string; source: MainBranchSource }> {
+  // A per-repo override wins over any detection
+  const repoKey = await detectRepoLockKey(repoRoot).catch(() => repoRoot);
+  const override = getRepoSettings(repoKey).base_branch;
+  if (override) return { branch: override, source: 'override' };
+
+  // Then the remote's default branch, as recorded by clone or `remote set-head`
   try {
     const { stdout } = await exec('git', ['symbolic-ref', 'refs/remotes/origin/HEAD'], {
       cwd: repoRoot,
     });
     const refname = stdout.trim();
     const prefix = 'refs/remotes/origin/';
-    if (refname.startsWith(prefix)) return refname.slice(prefix.length);
-  } catch {
-    /* ignore */
-  }
-
-  // Check if 'main' exists
-  try {
-    await exec('git', ['rev-parse', '--verify', 'main'], { cwd: repoRoot });
-    return 'main';
+    if (refname.startsWith(prefix)) {
+      return { branch: refname.slice(prefix.length), source: 'origin_head' };
+    }
   } catch {
     /* ignore */
   }
 
-  // Fallback to 'master'
-  try {
-    await exec('git', ['rev-parse', '--verify', 'master'], { cwd: repoRoot });
-    return 'master';
-  } catch {
-    /* ignore */
-  }
+  // Check if 'main' exists, then fall back to 'master'
+  if (await refExists(repoRoot, 'main')) return { branch: 'main', source: 'main' };
+  if (await refExists(repoRoot, 'master')) return { branch: 'master', source: 'master' };
 
   // Empty repo (no commits yet) — use configured default branch or fall back to "main"
   try {
@@ -103,12 +126,12 @@ async function detectMainBranchUncached(repoRoot: string): Promise<string> {
       cwd: repoRoot,
     });
     const configured = stdout.trim();
-    if (configured) return configured;
+    if (configured) return { branch: configured, source: 'fallback' };
   } catch {
     /* ignore */
   }
 
-  return 'main';
+  return { branch: 'main', source: 'fallback' };
 }
 
 async function getCurrentBranchName(repoRoot: string): Promise<string> {
@@ -146,12 +169,106 @@ async function detectRepoLockKey(p: string): Promise<string> {
   }
 }
 
-function normalizeStatusPath(raw: string): string {
-  const trimmed = raw.trim();
-  if (!trimmed) return '';
-  // Handle rename/copy "old -> new"
-  const destination = trimmed.split(' -> ').pop()?.trim() ?? trimmed;
-  return destination.replace(/^"|"$/g, '');
+// --- Git hooks ---
+
+export type HookOperation = 'commit' | 'merge' | 'push';
+
+export interface GitHookOutput {
+  repo_path: string;
+  operation: HookOperation;
+  stream: 'stdout' | 'stderr';
+  data: string;
+}
+
+let hookOutputSink: ((output: GitHookOutput) => void) | null = null;
+
+/** Receive the output of commits, merges and pushes as it happens (hooks write to it). */
+export function setHookOutputSink(sink: ((output: GitHookOutput) => void) | null): void {
+  hookOutputSink = sink;
+}
+
+/**
+ * Extra arguments and run options for an operation that may trigger hooks:
+ * `--no-verify` when the repo has hooks turned off, otherwise live output.
+ */
+async function hookOptions(
+  repoPath: string,
+  operation: HookOperation,
+): Promise<{ args: string[]; opts: GitRunOptions }> {
+  const repoKey = await detectRepoLockKey(repoPath).catch(() => repoPath);
+  if (getRepoSettings(repoKey).run_hooks === false) return { args: ['--no-verify'], opts: {} };
+  return {
+    args: [],
+    opts: {
+      onOutput: (stream, data) =>
+        hookOutputSink?.({ repo_path: repoPath, operation, stream, data }),
+    },
+  };
+}
+
+// --- Commit signing ---
+
+export type SigningFormat = 'openpgp' | 'ssh' | 'x509';
+
+export interface SigningConfig {
+  /** commit.gpgsign is on, so the app's commits and merges are signed. */
+  enabled: boolean;
+  format: SigningFormat;
+  /** user.signingkey; null lets the signing program pick a default. */
+  key: string | null;
+  /** gpg.<format>.program (or gpg.program), when overridden. */
+  program: string | null;
+}
+
+/** Thrown when git couldn't sign a commit; the message says how to fix it. */
+export class SigningError extends Error {
+  constructor(
+    readonly format: SigningFormat,
+    readonly detail: string,
+  ) {
+    const hint =
+      format === 'ssh'
+        ? 'Make sure the signing key is loaded in ssh-agent (ssh-add) or has no passphrase.'
+        : 'Make sure gpg-agent is running with a graphical pinentry (e.g. pinentry-mac, ' +
+          'pinentry-gnome3); a terminal pinentry cannot prompt for the app.';
+    super(`Commit signing failed (${format}). ${hint}\n${detail}`);
+    this.name = 'SigningError';
+  }
+}
+
+async function getConfigValue(repoPath: string, key: string): Promise<string | null> {
+  try {
+    const { stdout } = await exec('git', ['config', '--get', key], { cwd: repoPath });
+    return stdout.trim() || null;
+  } catch {
+    return null; // unset
+  }
+}
+
+/** Read the repo's effective signing settings (local, global and system config). */
+export async function getSigningConfig(repoPath: string): Promise<SigningConfig> {
+  const [gpgsign, rawFormat, key] = await Promise.all([
+    getConfigValue(repoPath, 'commit.gpgsign'),
+    getConfigValue(repoPath, 'gpg.format'),
+    getConfigValue(repoPath, 'user.signingkey'),
+  ]);
+  const format: SigningFormat = rawFormat === 'ssh' || rawFormat === 'x509' ? rawFormat : 'openpgp';
+  const program =
+    (await getConfigValue(repoPath, `gpg.${format}.program`)) ??
+    (format === 'openpgp' ? await getConfigValue(repoPath, 'gpg.program') : null);
+  return {
+    enabled: ['true', 'yes', 'on', '1'].includes(gpgsign?.toLowerCase() ?? ''),
+    format,
+    key,
+    program,
+  };
+}
+
+/** Turn a signing failure into a SigningError; returns null for any other error. */
+async function asSigningError(repoPath: string, e: unknown): Promise<SigningError | null> {
+  if (!(e instanceof GitError) || e.kind !== 'signing_failed') return null;
+  const { format } = await getSigningConfig(repoPath).catch(() => ({ format: 'openpgp' as const }));
+  return new SigningError(format, e.stderr.trim());
 }
 
 function parseConflictPath(line: string): string | null {
@@ -182,6 +299,81 @@ function parseConflictPath(line: string): string | null {
   return candidate || null;
 }
 
+interface PorcelainEntry {
+  /** Two-letter XY status, e.g. " M", "A ", "??". */
+  xy: string;
+  path: string;
+  /** Source path for renames and copies. */
+  origPath: string | null;
+}
+
+/** Parse `git status --porcelain -z` output; paths are verbatim (no quoting). */
+export function parsePorcelainZ(out: string): PorcelainEntry[] {
+  const entries: PorcelainEntry[] = [];
+  const fields = out.split('\0');
+  for (let i = 0; i < fields.length; i++) {
+    const field = fields[i];
+    if (field.length < 4) continue;
+    const xy = field.slice(0, 2);
+    const entry: PorcelainEntry = { xy, path: field.slice(3), origPath: null };
+    // Renames/copies are followed by the original path as a separate field
+    if (xy[0] === 'R' || xy[0] === 'C') entry.origPath = fields[++i] ?? null;
+    entries.push(entry);
+  }
+  return entries;
+}
+
+interface RawNumstat {
+  /** Status letter per (destination) path. */
+  statusMap: Map<string, string>;
+  /** Source path for renames and copies. */
+  origPaths: Map<string, string>;
+  /** Line counts per path; binary files are left out. */
+  numstatMap: Map<string, [number, number]>;
+  binaryPaths: Set<string>;
+}
+
+/** Parse `git diff --raw --numstat -z` output; paths are verbatim (no quoting). */
+export function parseRawNumstatZ(out: string): RawNumstat {
+  const result: RawNumstat = {
+    statusMap: new Map(),
+    origPaths: new Map(),
+    numstatMap: new Map(),
+    binaryPaths: new Set(),
+  };
+  const tokens = out.split('\0');
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+    if (token.startsWith(':')) {
+      // --raw: ":<modes> <shas> <status>" then the path, or src and dst for renames/copies
+      const statusLetter = token.split(' ').pop()?.charAt(0) ?? 'M';
+      const renamed = statusLetter === 'R' || statusLetter === 'C';
+      const p = tokens[renamed ? i + 2 : i + 1];
+      if (p) {
+        result.statusMap.set(p, statusLetter);
+        if (renamed) result.origPaths.set(p, tokens[i + 1]);
+      }
+      i += renamed ? 2 : 1;
+      continue;
+    }
+    // --numstat: "<added>\t<removed>\t<path>"; renames leave the path empty, then src and dst
+    const m = /^(\d+|-)\t(\d+|-)\t(.*)$/s.exec(token);
+    if (!m) continue;
+    let p = m[3];
+    if (p === '') {
+      p = tokens[i + 2] ?? '';
+      i += 2;
+    }
+    if (!p) continue;
+    const added = parseInt(m[1], 10);
+    const removed = parseInt(m[2], 10);
+    // Binary files report "-"
+    if (isNaN(added) || isNaN(removed)) result.binaryPaths.add(p);
+    else result.numstatMap.set(p, [added, removed]);
+  }
+  return result;
+}
+
 async function computeBranchDiffStats(
   projectRoot: string,
   mainBranch: string,
@@ -204,20 +396,287 @@ async function computeBranchDiffStats(
 
 // --- Public functions (used by tasks.ts and register.ts) ---
 
+/** Resolve a branch, tag, or commit to a commit hash, or throw if it doesn't exist. */
+async function resolveBaseRef(repoRoot: string, baseRef: string): Promise<string> {
+  if (!baseRef || baseRef.startsWith('-')) throw new Error(`Invalid base ref: ${baseRef}`);
+  try {
+    const { stdout } = await exec(
+      'git',
+      ['rev-parse', '--verify', '--quiet', `${baseRef}^{commit}`],
+      { cwd: repoRoot },
+    );
+    return stdout.trim();
+  } catch {
+    throw new Error(`Base ref not found: ${baseRef}`);
+  }
+}
+
+/** How to populate submodules in a new worktree: skip, full clone, or depth-1 clone. */
+export type SubmoduleMode = 'none' | 'full' | 'shallow';
+
+async function initSubmodules(worktreePath: string, mode: SubmoduleMode): Promise<void> {
+  if (mode === 'none' || !fs.existsSync(path.join(worktreePath, '.gitmodules'))) return;
+  const args = ['submodule', 'update', '--init', '--recursive'];
+  if (mode === 'shallow') args.push('--depth', '1');
+  try {
+    await exec('git', args, {
+      cwd: worktreePath,
+      env: { ...process.env, GIT_TERMINAL_PROMPT: '0' },
+      maxBuffer: MAX_BUFFER,
+    });
+  } catch (e) {
+    // Keep the worktree; get_worktree_status reports submodules left uninitialized
+    console.warn('Submodule update failed:', e);
+  }
+}
+
+// --- Context files copied into new worktrees ---
+
+const COPY_WALK_SKIP = new Set(['.git', 'node_modules', '.worktrees']);
+
+function segmentToRegExp(segment: string): RegExp {
+  let body = '';
+  for (const c of segment) {
+    if (c === '*') body += '[^/]*';
+    else if (c === '?') body += '[^/]';
+    else body += c.replace(/[.+^${}()|[\]\\]/g, '\\$&');
+  }
+  return new RegExp(`^${body}$`);
+}
+
+/** Whether a relative path (forward slashes) matches a `*`, `?` and `**` glob. */
+export function matchesGlob(rel: string, pattern: string): boolean {
+  const match = (parts: string[], segments: string[]): boolean => {
+    if (segments.length === 0) return parts.length === 0;
+    const [head, ...rest] = segments;
+    if (head === '**') {
+      for (let i = 0; i <= parts.length; i++) if (match(parts.slice(i), rest)) return true;
+      return false;
+    }
+    return parts.length > 0 && segmentToRegExp(head).test(parts[0]) && match(parts.slice(1), rest);
+  };
+  return match(rel.split('/').filter(Boolean), pattern.split('/').filter(Boolean));
+}
+
+/**
+ * Matcher for a worktree's top-level .gitignore: whether a relative path or
+ * one of its directories is ignored. Nested .gitignore files aren't read.
+ */
+export function loadGitignore(root: string): (rel: string) => boolean {
+  let text: string;
+  try {
+    text = fs.readFileSync(path.join(root, '.gitignore'), 'utf8');
+  } catch {
+    return () => false;
+  }
+  const rules = text
+    .split(/\r?\n/)
+    .map((line) => line.trim())
+    .filter((line) => line && !line.startsWith('#'))
+    .map((line) => {
+      const negate = line.startsWith('!');
+      const body = negate ? line.slice(1) : line;
+      const pattern = body.replace(/\/+$/, '');
+      return {
+        negate,
+        dirOnly: pattern !== body,
+        // A slash anywhere but the end ties the pattern to the root
+        anchored: pattern.includes('/'),
+        pattern: pattern.replace(/^\//, ''),
+      };
+    });
+  return (rel) => {
+    const parts = rel.split('/').filter(Boolean);
+    let ignored = false;
+    for (const rule of rules) {
+      for (let i = 1; i <= parts.length; i++) {
+        if (rule.dirOnly && i === parts.length) break;
+        const hit = rule.anchored
+          ? matchesGlob(parts.slice(0, i).join('/'), rule.pattern)
+          : matchesGlob(parts[i - 1], rule.pattern);
+        if (hit) {
+          ignored = !rule.negate;
+          break;
+        }
+      }
+    }
+    return ignored;
+  };
+}
+
+/** Expand include patterns (`*`, `?` and `**` globs) to existing paths under `root`. */
+export async function expandCopyPatterns(root: string, patterns: string[]): Promise<string[]> {
+  const found = new Set<string>();
+
+  const walk = async (rel: string, segments: string[]): Promise<void> => {
+    if (segments.length === 0) {
+      if (rel && fs.existsSync(path.join(root, rel))) found.add(rel);
+      return;
+    }
+    const [head, ...rest] = segments;
+    if (!/[*?]/.test(head)) {
+      await walk(rel ? `${rel}/${head}` : head, rest);
+      return;
+    }
+    let entries: fs.Dirent[];
+    try {
+      entries = await fs.promises.readdir(path.join(root, rel), { withFileTypes: true });
+    } catch {
+      return;
+    }
+    if (head === '**') {
+      await walk(rel, rest);
+      for (const e of entries) {
+        if (e.isDirectory() && !COPY_WALK_SKIP.has(e.name)) {
+          await walk(rel ? `${rel}/${e.name}` : e.name, segments);
+        }
+      }
+      return;
+    }
+    const re = segmentToRegExp(head);
+    for (const e of entries) {
+      if (re.test(e.name) && !COPY_WALK_SKIP.has(e.name)) {
+        await walk(rel ? `${rel}/${e.name}` : e.name, rest);
+      }
+    }
+  };
+
+  for (const pattern of patterns) {
+    const segments = pattern.replace(/\\/g, '/').split('/').filter((p) => p && p !== '.');
+    if (segments.length === 0 || segments.includes('..')) continue;
+    await walk('', segments);
+  }
+  return [...found].sort();
+}
+
+/** The repo's configured context files (e.g. `.env`) present in the main checkout. */
+async function configuredContextFiles(repoRoot: string): Promise<string[]> {
+  const repoKey = await detectRepoLockKey(repoRoot).catch(() => repoRoot);
+  const patterns = getRepoSettings(repoKey).copy_files ?? [];
+  return patterns.length === 0 ? [] : expandCopyPatterns(repoRoot, patterns);
+}
+
+/** Whether a directory on the way to `rel` is a symlink, e.g. a shared `.claude`. */
+function throughSymlink(root: string, rel: string): boolean {
+  const dirs = rel.split('/').slice(0, -1);
+  for (let i = 1; i <= dirs.length; i++) {
+    const stat = fs.lstatSync(path.join(root, ...dirs.slice(0, i)), { throwIfNoEntry: false });
+    if (!stat) return false;
+    if (stat.isSymbolicLink()) return true;
+  }
+  return false;
+}
+
+/**
+ * Copy untracked or ignored files from the main checkout into a new worktree.
+ * Paths that already exist in the worktree, are missing from the checkout or
+ * lead through a symlinked directory (which would write outside the worktree)
+ * are left alone. Returns the copied paths.
+ */
+async function copyContextFiles(
+  repoRoot: string,
+  worktreePath: string,
+  rels: string[],
+): Promise<string[]> {
+  const copied: string[] = [];
+  for (const rel of rels) {
+    const target = path.join(worktreePath, rel);
+    if (!fs.existsSync(path.join(repoRoot, rel)) || fs.existsSync(target)) continue;
+    try {
+      if (throughSymlink(worktreePath, rel)) continue;
+      await fs.promises.mkdir(path.dirname(target), { recursive: true });
+      await fs.promises.cp(path.join(repoRoot, rel), target, {
+        recursive: true,
+        errorOnExist: false,
+        force: false,
+        verbatimSymlinks: true,
+      });
+      copied.push(rel);
+    } catch (e) {
+      console.warn(`Could not copy ${rel} into ${worktreePath}:`, e);
+    }
+  }
+  return copied;
+}
+
+export async function getWorktreeCopyFiles(projectRoot: string): Promise<string[]> {
+  return (await getRepoSettingsFor(projectRoot)).copy_files ?? [];
+}
+
+/** Set the paths or globs copied from the main checkout into every new worktree. */
+export async function setWorktreeCopyFiles(projectRoot: string, patterns: string[]): Promise<void> {
+  const cleaned = [...new Set(patterns.map((p) => p.trim()).filter(Boolean))];
+  const repoKey = await detectRepoLockKey(projectRoot);
+  updateRepoSettings(repoKey, { copy_files: cleaned.length > 0 ? cleaned : undefined });
+}
+
+export async function getWorktreeSetupCommand(projectRoot: string): Promise<string | null> {
+  return (await getRepoSettingsFor(projectRoot)).setup_command ?? null;
+}
+
+export async function setWorktreeSetupCommand(
+  projectRoot: string,
+  command: string | null,
+): Promise<void> {
+  const repoKey = await detectRepoLockKey(projectRoot);
+  updateRepoSettings(repoKey, { setup_command: command?.trim() || undefined });
+}
+
+export async function getProjectEnvProfile(projectRoot: string): Promise<string | null> {
+  return (await getRepoSettingsFor(projectRoot)).env_profile ?? null;
+}
+
+export async function setProjectEnvProfile(
+  projectRoot: string,
+  profileId: string | null,
+): Promise<void> {
+  const repoKey = await detectRepoLockKey(projectRoot);
+  updateRepoSettings(repoKey, { env_profile: profileId || undefined });
+}
+
 export async function createWorktree(
   repoRoot: string,
   branchName: string,
   symlinkDirs: string[],
+  baseRef?: string,
+  submodules: SubmoduleMode = 'full',
+  copyFiles = true,
 ): Promise<{ path: string; branch: string }> {
-  const worktreePath = `${repoRoot}/.worktrees/${branchName}`;
+  const worktreePath = `${getWorktreeRoot(repoRoot)}/${branchName}`;
+  const base = baseRef ? await resolveBaseRef(repoRoot, baseRef) : null;
 
   // Try -b first (new branch), fall back to existing branch
   try {
-    await exec('git', ['worktree', 'add', '-b', branchName, worktreePath], { cwd: repoRoot });
-  } catch {
+    const addArgs = ['worktree', 'add', '-b', branchName, worktreePath];
+    if (base) addArgs.push(base);
+    await exec('git', addArgs, { cwd: repoRoot });
+  } catch (e) {
+    // An existing branch only stands in for the requested base if it points there
+    if (base) {
+      const head = await exec(
+        'git',
+        ['rev-parse', '--verify', '--quiet', `refs/heads/${branchName}^{commit}`],
+        { cwd: repoRoot },
+      ).then(
+        ({ stdout }) => stdout.trim(),
+        () => null,
+      );
+      if (head === null) throw e;
+      if (head !== base) {
+        throw new Error(
+          `Branch ${branchName} already exists and does not point at ${baseRef}; ` +
+            'pick another branch name or base the task on that branch',
+        );
+      }
+    }
     await exec('git', ['worktree', 'add', worktreePath, branchName], { cwd: repoRoot });
   }
 
+  await initSubmodules(worktreePath, submodules);
+  if (copyFiles) {
+    await copyContextFiles(repoRoot, worktreePath, await configuredContextFiles(repoRoot));
+  }
+
   // Symlink selected directories
   for (const name of symlinkDirs) {
     // Reject names that could escape the worktree directory
@@ -232,6 +691,8 @@ export async function createWorktree(
       /* ignore */
     }
   }
+  // After symlinking, so a shared `.claude` directory isn't shadowed by a copy
+  if (copyFiles) await copyContextFiles(repoRoot, worktreePath, mcpConfigFiles());
 
   return { path: worktreePath, branch: branchName };
 }
@@ -241,10 +702,15 @@ export async function removeWorktree(
   branchName: string,
   deleteBranch: boolean,
 ): Promise<void> {
-  const worktreePath = `${repoRoot}/.worktrees/${branchName}`;
-
   if (!fs.existsSync(repoRoot)) return;
 
+  // Worktrees left behind by a location change (see setWorktreeLocation) live elsewhere
+  let worktreePath = `${getWorktreeRoot(repoRoot)}/${branchName}`;
+  if (!fs.existsSync(worktreePath)) {
+    const registered = await listWorktrees(repoRoot).catch(() => []);
+    worktreePath = registered.find((wt) => wt.branch === branchName)?.path ?? worktreePath;
+  }
+
   if (fs.existsSync(worktreePath)) {
     try {
       await exec('git', ['worktree', 'remove', '--force', worktreePath], { cwd: repoRoot });
@@ -271,106 +737,607 @@ export async function removeWorktree(
   }
 }
 
-// --- IPC command functions ---
+/** Directories under the worktree root that look like a worktree checkout (have a `.git` file). */
+function findWorktreeDirs(root: string, depth = 4): string[] {
+  const found: string[] = [];
+  let entries: fs.Dirent[];
+  try {
+    entries = fs.readdirSync(root, { withFileTypes: true });
+  } catch {
+    return found;
+  }
+  if (entries.some((e) => e.name === '.git')) return [root];
+  if (depth === 0) return found;
+  for (const e of entries) {
+    if (e.isDirectory()) found.push(...findWorktreeDirs(path.join(root, e.name), depth - 1));
+  }
+  return found;
+}
 
-export async function getGitIgnoredDirs(projectRoot: string): Promise<string[]> {
-  const results: string[] = [];
-  for (const name of SYMLINK_CANDIDATES) {
-    const dirPath = path.join(projectRoot, name);
-    try {
-      if (!fs.statSync(dirPath).isDirectory()) continue;
-    } catch {
-      continue;
-    }
-    try {
-      await exec('git', ['check-ignore', '-q', name], { cwd: projectRoot });
-      results.push(name);
-    } catch {
-      /* not ignored */
-    }
+function realpathOrSelf(p: string): string {
+  try {
+    return fs.realpathSync(p);
+  } catch {
+    return p;
   }
-  return results;
 }
 
-export async function getMainBranch(projectRoot: string): Promise<string> {
-  return detectMainBranch(projectRoot);
+export interface WorktreeInfo {
+  path: string;
+  head: string | null;
+  branch: string | null;
+  detached: boolean;
+  locked: boolean;
+  /** Registered with git but its directory is gone. */
+  prunable: boolean;
+  /** Present under the worktree root but not registered with git. */
+  orphaned: boolean;
 }
 
-export async function getCurrentBranch(projectRoot: string): Promise<string> {
-  return getCurrentBranchName(projectRoot);
+// --- IPC command functions ---
+
+export async function createTaskWorktree(
+  projectRoot: string,
+  branchName: string,
+  symlinkDirs: string[],
+  baseRef?: string,
+  submodules?: SubmoduleMode,
+  copyFiles?: boolean,
+): Promise<{ path: string; branch: string }> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+  return withWorktreeLock(lockKey, () =>
+    createWorktree(projectRoot, branchName, symlinkDirs, baseRef, submodules, copyFiles),
+  );
 }
 
-export async function getChangedFiles(worktreePath: string): Promise<
-  Array<{
-    path: string;
-    lines_added: number;
-    lines_removed: number;
-    status: string;
-    committed: boolean;
-  }>
-> {
-  const base = await detectMergeBase(worktreePath).catch(() => 'HEAD');
+export async function removeTaskWorktree(
+  projectRoot: string,
+  branchName: string,
+  deleteBranch: boolean,
+): Promise<void> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+  return withWorktreeLock(lockKey, () => removeWorktree(projectRoot, branchName, deleteBranch));
+}
 
-  // git diff --raw --numstat <base>
-  let diffStr = '';
-  try {
-    const { stdout } = await exec('git', ['diff', '--raw', '--numstat', base], {
-      cwd: worktreePath,
-      maxBuffer: MAX_BUFFER,
+export async function listWorktrees(projectRoot: string): Promise<WorktreeInfo[]> {
+  const { stdout } = await exec('git', ['worktree', 'list', '--porcelain'], {
+    cwd: projectRoot,
+    maxBuffer: MAX_BUFFER,
+  });
+
+  const worktrees: WorktreeInfo[] = [];
+  let current: WorktreeInfo | null = null;
+  for (const line of stdout.split('\n')) {
+    if (line.startsWith('worktree ')) {
+      current = {
+        path: line.slice('worktree '.length),
+        head: null,
+        branch: null,
+        detached: false,
+        locked: false,
+        prunable: false,
+        orphaned: false,
+      };
+      worktrees.push(current);
+    } else if (!current) {
+      continue;
+    } else if (line.startsWith('HEAD ')) {
+      current.head = line.slice('HEAD '.length);
+    } else if (line.startsWith('branch ')) {
+      current.branch = line.slice('branch '.length).replace(/^refs\/heads\//, '');
+    } else if (line === 'detached') {
+      current.detached = true;
+    } else if (line === 'locked' || line.startsWith('locked ')) {
+      current.locked = true;
+    } else if (line === 'prunable' || line.startsWith('prunable ')) {
+      current.prunable = true;
+    }
+  }
+
+  // Older git doesn't report "prunable"; a missing directory means the same
+  for (const wt of worktrees) {
+    if (!wt.prunable && !fs.existsSync(wt.path)) wt.prunable = true;
+  }
+
+  const registered = new Set(worktrees.map((wt) => realpathOrSelf(wt.path)));
+  for (const dir of findWorktreeDirs(getWorktreeRoot(projectRoot))) {
+    if (registered.has(realpathOrSelf(dir))) continue;
+    worktrees.push({
+      path: dir,
+      head: null,
+      branch: null,
+      detached: false,
+      locked: false,
+      prunable: false,
+      orphaned: true,
     });
-    diffStr = stdout;
-  } catch {
-    /* empty */
   }
 
-  const statusMap = new Map<string, string>();
-  const numstatMap = new Map<string, [number, number]>();
+  return worktrees;
+}
+
+/**
+ * Drop git's records of worktrees whose directories are gone and, if asked,
+ * delete orphaned directories under the worktree root that git no longer knows.
+ */
+export async function pruneWorktrees(
+  projectRoot: string,
+  removeOrphans: boolean,
+): Promise<{ pruned: string[]; removed_dirs: string[] }> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+
+  return withWorktreeLock(lockKey, async () => {
+    // `prune -v` reports each entry on stderr as "Removing worktrees/<name>: <reason>"
+    const { stdout, stderr } = await exec('git', ['worktree', 'prune', '-v'], {
+      cwd: projectRoot,
+    });
+    const pruned: string[] = [];
+    for (const line of `${stdout}\n${stderr}`.split('\n')) {
+      const m = /^Removing (?:worktrees\/)?(.+?):/.exec(line.trim());
+      if (m) pruned.push(m[1]);
+    }
 
-  for (const line of diffStr.split('\n')) {
-    if (line.startsWith(':')) {
-      // --raw format
-      const parts = line.split('\t');
-      if (parts.length >= 2) {
-        const statusLetter = parts[0].split(/\s+/).pop()?.charAt(0) ?? 'M';
-        const rawPath = parts[parts.length - 1];
-        const p = normalizeStatusPath(rawPath);
-        if (p) statusMap.set(p, statusLetter);
+    const removedDirs: string[] = [];
+    if (removeOrphans) {
+      const worktrees = await listWorktrees(projectRoot);
+      for (const wt of worktrees) {
+        if (!wt.orphaned) continue;
+        fs.rmSync(wt.path, { recursive: true, force: true });
+        removedDirs.push(wt.path);
       }
-      continue;
     }
-    const parts = line.split('\t');
-    if (parts.length >= 3) {
-      const added = parseInt(parts[0], 10);
-      const removed = parseInt(parts[1], 10);
-      if (!isNaN(added) && !isNaN(removed)) {
-        const rawPath = parts[parts.length - 1];
-        const p = normalizeStatusPath(rawPath);
-        if (p) numstatMap.set(p, [added, removed]);
+
+    return { pruned, removed_dirs: removedDirs };
+  });
+}
+
+/** Total size of a directory tree; symlinks (e.g. shared node_modules) are not followed. */
+async function dirSize(dir: string): Promise<number> {
+  let total = 0;
+  let entries: fs.Dirent[];
+  try {
+    entries = await fs.promises.readdir(dir, { withFileTypes: true });
+  } catch {
+    return 0;
+  }
+  for (const e of entries) {
+    const full = path.join(dir, e.name);
+    if (e.isDirectory()) {
+      total += await dirSize(full);
+    } else if (e.isFile()) {
+      try {
+        total += (await fs.promises.lstat(full)).size;
+      } catch {
+        /* removed mid-scan */
       }
     }
   }
+  return total;
+}
 
-  // git status --porcelain for uncommitted paths
-  let statusStr = '';
+export interface WorktreeDiskUsage {
+  path: string;
+  branch: string | null;
+  size_bytes: number;
+  /** Epoch ms the worktree directory was created. */
+  created_at: number | null;
+  /** Epoch ms of the latest commit or index update, whichever is newer. */
+  last_activity_at: number | null;
+  orphaned: boolean;
+}
+
+async function lastActivity(worktreePath: string): Promise<number | null> {
+  let latest: number | null = null;
+  try {
+    const { stdout } = await exec('git', ['log', '-1', '--format=%ct'], { cwd: worktreePath });
+    const secs = parseInt(stdout.trim(), 10);
+    if (secs) latest = secs * 1000;
+  } catch {
+    /* no commits */
+  }
   try {
-    const { stdout } = await exec('git', ['status', '--porcelain'], {
+    const { stdout } = await exec('git', ['rev-parse', '--git-path', 'index'], {
       cwd: worktreePath,
-      maxBuffer: MAX_BUFFER,
     });
-    statusStr = stdout;
+    const indexPath = path.resolve(worktreePath, stdout.trim());
+    const mtime = (await fs.promises.stat(indexPath)).mtimeMs;
+    latest = Math.max(latest ?? 0, mtime);
   } catch {
-    /* empty */
+    /* no index yet */
   }
+  return latest;
+}
 
-  const uncommittedPaths = new Set<string>();
-  for (const line of statusStr.split('\n')) {
-    if (line.length < 3) continue;
-    const p = normalizeStatusPath(line.slice(3));
-    if (!p) continue;
-    if (line.startsWith('??')) {
-      if (!statusMap.has(p)) statusMap.set(p, '?');
-    }
-    uncommittedPaths.add(p);
+/** Size and age of every task worktree under the worktree root (the main checkout is skipped). */
+export async function getWorktreesDiskUsage(projectRoot: string): Promise<WorktreeDiskUsage[]> {
+  const managedRoot = realpathOrSelf(getWorktreeRoot(projectRoot)) + path.sep;
+  const worktrees = (await listWorktrees(projectRoot)).filter(
+    (wt) => !wt.prunable && realpathOrSelf(wt.path).startsWith(managedRoot),
+  );
+  return Promise.all(
+    worktrees.map(async (wt) => {
+      let createdAt: number | null = null;
+      try {
+        const stat = await fs.promises.stat(wt.path);
+        createdAt = stat.birthtimeMs || stat.ctimeMs;
+      } catch {
+        /* ignore */
+      }
+      return {
+        path: wt.path,
+        branch: wt.branch,
+        size_bytes: await dirSize(wt.path),
+        created_at: createdAt,
+        last_activity_at: wt.orphaned ? createdAt : await lastActivity(wt.path),
+        orphaned: wt.orphaned,
+      };
+    }),
+  );
+}
+
+/**
+ * Remove task worktrees with no activity for `olderThanDays`. Worktrees in
+ * `keepPaths` (open tasks), locked ones, and ones with uncommitted changes are
+ * left alone. Branches are kept so no commits are lost.
+ */
+export async function cleanupStaleWorktrees(
+  projectRoot: string,
+  olderThanDays: number,
+  keepPaths: string[],
+  dryRun: boolean,
+): Promise<{ removed: string[]; skipped: Array<{ path: string; reason: string }> }> {
+  if (!Number.isFinite(olderThanDays) || olderThanDays < 1) {
+    throw new Error('olderThanDays must be at least 1');
+  }
+  const cutoff = Date.now() - olderThanDays * 86_400_000;
+  const keep = new Set(keepPaths.map(realpathOrSelf));
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+
+  return withWorktreeLock(lockKey, async () => {
+    const usage = await getWorktreesDiskUsage(projectRoot);
+    const locked = new Set(
+      (await listWorktrees(projectRoot)).filter((wt) => wt.locked).map((wt) => wt.path),
+    );
+    const removed: string[] = [];
+    const skipped: Array<{ path: string; reason: string }> = [];
+
+    for (const wt of usage) {
+      if ((wt.last_activity_at ?? 0) > cutoff) continue;
+      if (keep.has(realpathOrSelf(wt.path))) {
+        skipped.push({ path: wt.path, reason: 'task is open' });
+        continue;
+      }
+      if (locked.has(wt.path)) {
+        skipped.push({ path: wt.path, reason: 'locked' });
+        continue;
+      }
+      if (!wt.orphaned) {
+        const { stdout } = await exec('git', ['status', '--porcelain'], {
+          cwd: wt.path,
+          maxBuffer: MAX_BUFFER,
+        }).catch(() => ({ stdout: '?' }));
+        if (stdout.trim()) {
+          skipped.push({ path: wt.path, reason: 'uncommitted changes' });
+          continue;
+        }
+      }
+      if (!dryRun) {
+        if (wt.orphaned) {
+          fs.rmSync(wt.path, { recursive: true, force: true });
+        } else {
+          await exec('git', ['worktree', 'remove', '--force', wt.path], { cwd: projectRoot });
+        }
+      }
+      removed.push(wt.path);
+    }
+    return { removed, skipped };
+  });
+}
+
+export interface WorktreeRelocation {
+  root: string;
+  moved: Array<{ from: string; to: string }>;
+  failed: Array<{ path: string; error: string }>;
+}
+
+export function getWorktreeLocationInfo(projectRoot: string): {
+  location: WorktreeLocation;
+  root: string;
+} {
+  return { location: getWorktreeLocation(projectRoot), root: getWorktreeRoot(projectRoot) };
+}
+
+/**
+ * Change where new task worktrees are created. With `migrate`, existing
+ * worktrees under the old root are moved (`git worktree move`); ones that
+ * can't be moved (locked, containing submodules, another filesystem) stay put
+ * and keep working from their old path.
+ */
+export async function setWorktreeLocation(
+  projectRoot: string,
+  location: WorktreeLocation,
+  migrate: boolean,
+): Promise<WorktreeRelocation> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+
+  return withWorktreeLock(lockKey, async () => {
+    const oldRoot = getWorktreeRoot(projectRoot);
+    const newRoot = resolveWorktreeRoot(projectRoot, location);
+    const result: WorktreeRelocation = { root: newRoot, moved: [], failed: [] };
+
+    if (migrate && realpathOrSelf(oldRoot) !== realpathOrSelf(newRoot)) {
+      const oldPrefix = realpathOrSelf(oldRoot) + path.sep;
+      for (const wt of await listWorktrees(projectRoot)) {
+        const real = realpathOrSelf(wt.path);
+        if (wt.prunable || wt.orphaned || !real.startsWith(oldPrefix)) continue;
+        const dest = path.join(newRoot, real.slice(oldPrefix.length));
+        try {
+          fs.mkdirSync(path.dirname(dest), { recursive: true });
+          await exec('git', ['worktree', 'move', wt.path, dest], { cwd: projectRoot });
+          result.moved.push({ from: wt.path, to: dest });
+        } catch (e) {
+          result.failed.push({ path: wt.path, error: String(e) });
+        }
+      }
+    }
+
+    saveWorktreeLocation(projectRoot, location);
+    return result;
+  });
+}
+
+/** Subset of `relPaths` that git ignores (tracked paths never count as ignored). */
+async function filterIgnored(repoRoot: string, relPaths: string[]): Promise<Set<string>> {
+  if (relPaths.length === 0) return new Set();
+  try {
+    const { stdout } = await exec('git', ['check-ignore', '--stdin', '-z'], {
+      cwd: repoRoot,
+      input: relPaths.join('\0') + '\0',
+      maxBuffer: MAX_BUFFER,
+    });
+    return new Set(stdout.split('\0').filter(Boolean));
+  } catch (e) {
+    // Exit code 1: none of the paths are ignored
+    if (e instanceof GitError && e.code === 1) return new Set();
+    throw e;
+  }
+}
+
+export async function getGitIgnoredDirs(projectRoot: string): Promise<string[]> {
+  const dirs = SYMLINK_CANDIDATES.filter((name) => {
+    try {
+      return fs.statSync(path.join(projectRoot, name)).isDirectory();
+    } catch {
+      return false;
+    }
+  });
+  const ignored = await filterIgnored(projectRoot, dirs).catch(() => new Set<string>());
+  return dirs.filter((name) => ignored.has(name));
+}
+
+export interface TreeEntry {
+  name: string;
+  /** Relative to the worktree root, with forward slashes. */
+  path: string;
+  type: 'file' | 'dir' | 'symlink';
+  /** Directory contents; absent when the directory is beyond the requested depth. */
+  children?: TreeEntry[];
+}
+
+const MAX_TREE_DEPTH = 10;
+const MAX_TREE_ENTRIES = 5000;
+
+/**
+ * List a worktree directory `depth` levels deep, leaving out gitignored
+ * entries and `.git`. Stops adding entries past MAX_TREE_ENTRIES.
+ */
+export async function listWorktreeTree(
+  worktreePath: string,
+  subdir: string,
+  depth: number,
+): Promise<{ entries: TreeEntry[]; truncated: boolean }> {
+  const maxDepth = Math.min(Math.max(1, Math.floor(depth) || 1), MAX_TREE_DEPTH);
+  const rootRel = subdir.replace(/\\/g, '/').replace(/^\/+|\/+$/g, '');
+  const root: TreeEntry = { name: '', path: rootRel, type: 'dir', children: [] };
+  let level: TreeEntry[] = [root];
+  let count = 0;
+  let truncated = false;
+
+  // Level by level, so each level needs a single check-ignore call
+  for (let d = 0; d < maxDepth && level.length > 0 && !truncated; d++) {
+    const found: TreeEntry[] = [];
+    for (const dir of level) {
+      let dirents: fs.Dirent[];
+      try {
+        dirents = await fs.promises.readdir(path.join(worktreePath, dir.path), {
+          withFileTypes: true,
+        });
+      } catch {
+        continue;
+      }
+      dir.children = [];
+      for (const dirent of dirents) {
+        if (dirent.name === '.git') continue;
+        const entry: TreeEntry = {
+          name: dirent.name,
+          path: dir.path ? `${dir.path}/${dirent.name}` : dirent.name,
+          type: dirent.isSymbolicLink() ? 'symlink' : dirent.isDirectory() ? 'dir' : 'file',
+        };
+        dir.children.push(entry);
+        found.push(entry);
+      }
+    }
+
+    const ignored = await filterIgnored(worktreePath, found.map((e) => e.path));
+    const next: TreeEntry[] = [];
+    for (const dir of level) {
+      if (!dir.children) continue;
+      dir.children = dir.children
+        .filter((e) => !ignored.has(e.path))
+        .sort((a, b) => {
+          if ((a.type === 'dir') !== (b.type === 'dir')) return a.type === 'dir' ? -1 : 1;
+          return a.name.localeCompare(b.name);
+        });
+      if (count + dir.children.length > MAX_TREE_ENTRIES) {
+        dir.children = dir.children.slice(0, Math.max(0, MAX_TREE_ENTRIES - count));
+        truncated = true;
+      }
+      count += dir.children.length;
+      next.push(...dir.children.filter((e) => e.type === 'dir'));
+    }
+    level = next;
+  }
+  return { entries: root.children ?? [], truncated };
+}
+
+export async function branchExists(repoRoot: string, name: string): Promise<boolean> {
+  try {
+    await exec('git', ['show-ref', '--verify', '--quiet', `refs/heads/${name}`], { cwd: repoRoot });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+export async function isValidBranchName(repoRoot: string, name: string): Promise<boolean> {
+  if (name.startsWith('-')) return false;
+  try {
+    await exec('git', ['check-ref-format', '--branch', name], { cwd: repoRoot });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+export async function getGitUserName(repoRoot: string): Promise<string> {
+  const { stdout } = await exec('git', ['config', '--get', 'user.name'], { cwd: repoRoot });
+  return stdout.trim();
+}
+
+export async function getMainBranch(projectRoot: string): Promise<string> {
+  return detectMainBranch(projectRoot);
+}
+
+export interface MainBranchInfo {
+  branch: string;
+  source: MainBranchSource;
+  /** Whether `branch` resolves to a commit in this repo. */
+  exists: boolean;
+  /** Local branches, most recently committed first; offered when detection falls short. */
+  candidates: string[];
+}
+
+export async function getMainBranchInfo(projectRoot: string): Promise<MainBranchInfo> {
+  const { branch, source } = await detectMainBranchUncached(projectRoot);
+  const exists = await refExists(projectRoot, branch);
+  let candidates: string[] = [];
+  try {
+    const { stdout } = await exec(
+      'git',
+      ['for-each-ref', '--sort=-committerdate', '--format=%(refname:short)', 'refs/heads'],
+      { cwd: projectRoot },
+    );
+    candidates = stdout.split('\n').filter((b) => b.length > 0);
+  } catch {
+    /* ignore */
+  }
+  return { branch, source, exists, candidates };
+}
+
+/**
+ * Pin the branch that tasks in this repo fork from and merge into, or clear
+ * the override (`null`) to go back to auto-detection.
+ */
+export async function setTaskBaseBranch(projectRoot: string, branch: string | null): Promise<void> {
+  if (branch !== null && !(await refExists(projectRoot, branch))) {
+    throw new Error(`Branch "${branch}" does not exist`);
+  }
+  const repoKey = await detectRepoLockKey(projectRoot);
+  updateRepoSettings(repoKey, { base_branch: branch ?? undefined });
+  // Every worktree of the repo caches its own entry
+  mainBranchCache.clear();
+  invalidateMergeBaseCache();
+}
+
+export async function getRunGitHooks(projectRoot: string): Promise<boolean> {
+  const repoKey = await detectRepoLockKey(projectRoot);
+  return getRepoSettings(repoKey).run_hooks !== false;
+}
+
+/** Run (default) or skip (`--no-verify`) hooks for commits, merges and pushes in this repo. */
+export async function setRunGitHooks(projectRoot: string, enabled: boolean): Promise<void> {
+  const repoKey = await detectRepoLockKey(projectRoot);
+  updateRepoSettings(repoKey, { run_hooks: enabled ? undefined : false });
+}
+
+/** Top-level directory of the repository containing `dir`. */
+export async function getRepoRoot(dir: string): Promise<string> {
+  try {
+    const { stdout } = await exec('git', ['rev-parse', '--show-toplevel'], { cwd: dir });
+    return stdout.trim();
+  } catch {
+    throw new Error(`Not a git repository: ${dir}`);
+  }
+}
+
+export async function getRepoSettingsFor(projectRoot: string): Promise<RepoSettings> {
+  return getRepoSettings(await detectRepoLockKey(projectRoot));
+}
+
+/** Drop every per-repo setting (base branch, hooks, worktree location) for a repo. */
+export async function forgetRepoSettings(projectRoot: string): Promise<void> {
+  const repoKey = await detectRepoLockKey(projectRoot).catch(() => null);
+  if (repoKey) updateRepoSettings(repoKey, { base_branch: undefined, run_hooks: undefined });
+  saveWorktreeLocation(projectRoot, { mode: 'repo' });
+  mainBranchCache.clear();
+}
+
+export async function getCurrentBranch(projectRoot: string): Promise<string> {
+  return getCurrentBranchName(projectRoot);
+}
+
+export async function getChangedFiles(worktreePath: string): Promise<
+  Array<{
+    path: string;
+    lines_added: number;
+    lines_removed: number;
+    status: string;
+    committed: boolean;
+  }>
+> {
+  const base = await detectMergeBase(worktreePath).catch(() => 'HEAD');
+
+  // git diff --raw --numstat -z <base>: NUL-separated, paths verbatim
+  let diffStr = '';
+  try {
+    const diffArgs = ['diff', '--raw', '--numstat', '-z', '--no-abbrev', base];
+    const { stdout } = await exec('git', diffArgs, {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    });
+    diffStr = stdout;
+  } catch {
+    /* empty */
+  }
+
+  const { statusMap, numstatMap } = parseRawNumstatZ(diffStr);
+
+  // git status --porcelain -z for uncommitted paths
+  let statusStr = '';
+  try {
+    const { stdout } = await exec('git', ['status', '--porcelain', '-z'], {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    });
+    statusStr = stdout;
+  } catch {
+    /* empty */
+  }
+
+  const uncommittedPaths = new Set<string>();
+  for (const entry of parsePorcelainZ(statusStr)) {
+    if (entry.xy === '??' && !statusMap.has(entry.path)) statusMap.set(entry.path, '?');
+    uncommittedPaths.add(entry.path);
   }
 
   const files: Array<{
@@ -420,42 +1387,398 @@ export async function getChangedFiles(worktreePath: string): Promise<
   return files;
 }
 
-export async function getFileDiff(worktreePath: string, filePath: string): Promise<string> {
+const IMAGE_MIME: Record<string, string> = {
+  png: 'image/png',
+  jpg: 'image/jpeg',
+  jpeg: 'image/jpeg',
+  gif: 'image/gif',
+  webp: 'image/webp',
+  bmp: 'image/bmp',
+  ico: 'image/x-icon',
+  avif: 'image/avif',
+};
+const MAX_IMAGE_BYTES = 5 * 1024 * 1024; // 5MB per side
+
+export type FileDiff =
+  | { type: 'text'; diff: string }
+  | {
+      type: 'binary';
+      old_size: number | null;
+      new_size: number | null;
+      /** Both sides as base64 when the file is a (reasonably small) image. */
+      image: { mime: string; old_base64: string | null; new_base64: string | null } | null;
+    };
+
+function looksBinary(buf: Buffer): boolean {
+  return buf.subarray(0, 8000).includes(0);
+}
+
+async function readBlob(
+  worktreePath: string,
+  ref: string,
+  filePath: string,
+): Promise<Buffer | null> {
+  try {
+    return await getGitBackend().runBuffer(['show', `${ref}:${filePath}`], {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    });
+  } catch {
+    return null;
+  }
+}
+
+async function readWorktreeFile(fullPath: string): Promise<Buffer | null> {
+  try {
+    const stat = await fs.promises.stat(fullPath);
+    if (!stat.isFile() || stat.size >= MAX_BUFFER) return null;
+    return await fs.promises.readFile(fullPath);
+  } catch {
+    return null;
+  }
+}
+
+function binaryDiff(filePath: string, oldBlob: Buffer | null, newBlob: Buffer | null): FileDiff {
+  const mime = IMAGE_MIME[path.extname(filePath).slice(1).toLowerCase()];
+  const encode = (b: Buffer | null) =>
+    b && b.length <= MAX_IMAGE_BYTES ? b.toString('base64') : null;
+  return {
+    type: 'binary',
+    old_size: oldBlob?.length ?? null,
+    new_size: newBlob?.length ?? null,
+    image: mime ? { mime, old_base64: encode(oldBlob), new_base64: encode(newBlob) } : null,
+  };
+}
+
+/** Changed files of every worktree of a multi-repo task, grouped per worktree. */
+export async function getLinkedChangedFiles(
+  worktreePaths: string[],
+): Promise<Array<{ worktree_path: string; files: Awaited<ReturnType<typeof getChangedFiles>> }>> {
+  return Promise.all(
+    worktreePaths.map(async (p) => ({ worktree_path: p, files: await getChangedFiles(p) })),
+  );
+}
+
+export async function getFileDiff(worktreePath: string, filePath: string): Promise<FileDiff> {
   const base = await detectMergeBase(worktreePath).catch(() => 'HEAD');
+  const fullPath = path.join(worktreePath, filePath);
 
   try {
     const { stdout } = await exec('git', ['diff', base, '--', filePath], {
       cwd: worktreePath,
       maxBuffer: MAX_BUFFER,
     });
-    if (stdout.trim()) return stdout;
+    if (/^Binary files .* differ$/m.test(stdout)) {
+      const [oldBlob, newBlob] = await Promise.all([
+        readBlob(worktreePath, base, filePath),
+        readWorktreeFile(fullPath),
+      ]);
+      return binaryDiff(filePath, oldBlob, newBlob);
+    }
+    if (stdout.trim()) return { type: 'text', diff: stdout };
   } catch {
     /* empty */
   }
 
   // Untracked file — format as all-additions
-  const fullPath = path.join(worktreePath, filePath);
+  const contentBuf = await readWorktreeFile(fullPath);
+  if (contentBuf) {
+    if (looksBinary(contentBuf)) return binaryDiff(filePath, null, contentBuf);
+    const lines = contentBuf.toString('utf8').split('\n');
+    let pseudo = `--- /dev/null\n+++ b/${filePath}\n@@ -0,0 +1,${lines.length} @@\n`;
+    for (const line of lines) {
+      pseudo += `+${line}\n`;
+    }
+    return { type: 'text', diff: pseudo };
+  }
+
+  return { type: 'text', diff: '' };
+}
+
+function validateRef(ref: string, label: string): void {
+  if (!ref || ref.startsWith('-')) throw new Error(`Invalid ${label}: ${ref}`);
+}
+
+/** Diff one file between two refs; a null `toRef` compares against the working tree. */
+export async function getFileDiffBetween(
+  worktreePath: string,
+  filePath: string,
+  fromRef: string,
+  toRef: string | null,
+): Promise<string> {
+  validateRef(fromRef, 'fromRef');
+  if (toRef !== null) validateRef(toRef, 'toRef');
+  const refs = toRef === null ? [fromRef] : [fromRef, toRef];
+  const { stdout } = await exec('git', ['diff', '--no-color', ...refs, '--', filePath], {
+    cwd: worktreePath,
+    maxBuffer: MAX_BUFFER,
+  });
+  return stdout;
+}
+
+/**
+ * Committed changes on the task branch relative to where it forked from main
+ * (`main...HEAD`), ignoring uncommitted work. Limited to one file if given.
+ */
+export async function getTaskDiffVsBase(
+  worktreePath: string,
+  filePath: string | null,
+): Promise<string> {
+  const mainBranch = await detectMainBranch(worktreePath);
+  const args = ['diff', '--no-color', `${mainBranch}...HEAD`];
+  if (filePath) args.push('--', filePath);
+  const { stdout } = await exec('git', args, { cwd: worktreePath, maxBuffer: MAX_BUFFER });
+  return stdout;
+}
+
+export interface CommitFile {
+  path: string;
+  /** Source path for renames and copies. */
+  old_path: string | null;
+  status: string;
+  lines_added: number;
+  lines_removed: number;
+  binary: boolean;
+}
+
+export interface CommitDetail {
+  sha: string;
+  short_sha: string;
+  parents: string[];
+  author: string;
+  author_email: string;
+  /** Unix seconds. */
+  authored_at: number;
+  committer: string;
+  committed_at: number;
+  message: string;
+  /** Changes relative to the first parent (or the empty tree for a root commit). */
+  files: CommitFile[];
+}
+
+async function resolveCommit(worktreePath: string, sha: string): Promise<string> {
+  validateRef(sha, 'sha');
   try {
-    const stat = await fs.promises.stat(fullPath);
-    if (stat.isFile() && stat.size < MAX_BUFFER) {
-      const content = await fs.promises.readFile(fullPath, 'utf8');
-      const lines = content.split('\n');
-      let pseudo = `--- /dev/null\n+++ b/${filePath}\n@@ -0,0 +1,${lines.length} @@\n`;
-      for (const line of lines) {
-        pseudo += `+${line}\n`;
+    const { stdout } = await exec('git', ['rev-parse', '--verify', '--quiet', `${sha}^{commit}`], {
+      cwd: worktreePath,
+    });
+    return stdout.trim();
+  } catch {
+    throw new Error(`Commit not found: ${sha}`);
+  }
+}
+
+export async function getCommit(worktreePath: string, sha: string): Promise<CommitDetail> {
+  const commit = await resolveCommit(worktreePath, sha);
+  const { stdout: meta } = await exec(
+    'git',
+    ['show', '-s', '--format=%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%cn%x1f%ct%x1f%B', commit],
+    { cwd: worktreePath, maxBuffer: MAX_BUFFER },
+  );
+  const [full, short, parents, author, email, authoredAt, committer, committedAt, ...body] =
+    meta.split('\x1f');
+  const parentList = parents ? parents.split(' ') : [];
+
+  // First-parent diff keeps merge commits readable; root commits diff against the empty tree
+  const diffArgs =
+    parentList.length > 0
+      ? ['diff', '-M', '--raw', '--numstat', '-z', parentList[0], commit]
+      : ['diff-tree', '-r', '-M', '--root', '--no-commit-id', '--raw', '--numstat', '-z', commit];
+  const { stdout: diffOut } = await exec('git', diffArgs, {
+    cwd: worktreePath,
+    maxBuffer: MAX_BUFFER,
+  });
+  const { statusMap, origPaths, numstatMap, binaryPaths } = parseRawNumstatZ(diffOut);
+  const files: CommitFile[] = [];
+  for (const [p, status] of statusMap) {
+    const [added, removed] = numstatMap.get(p) ?? [0, 0];
+    files.push({
+      path: p,
+      old_path: origPaths.get(p) ?? null,
+      status,
+      lines_added: added,
+      lines_removed: removed,
+      binary: binaryPaths.has(p),
+    });
+  }
+  files.sort((a, b) => a.path.localeCompare(b.path));
+
+  return {
+    sha: full.trim(),
+    short_sha: short,
+    parents: parentList,
+    author,
+    author_email: email,
+    authored_at: parseInt(authoredAt, 10) || 0,
+    committer,
+    committed_at: parseInt(committedAt, 10) || 0,
+    message: body.join('\x1f').trimEnd(),
+    files,
+  };
+}
+
+/** Diff of one file as changed by a single commit (against its first parent). */
+export async function getCommitFileDiff(
+  worktreePath: string,
+  sha: string,
+  filePath: string,
+): Promise<FileDiff> {
+  const commit = await resolveCommit(worktreePath, sha);
+  const { stdout: parents } = await exec('git', ['rev-list', '--parents', '-n', '1', commit], {
+    cwd: worktreePath,
+  });
+  const parent = parents.trim().split(' ')[1] ?? null;
+
+  // --root makes a parentless commit show as all additions
+  const args = parent
+    ? ['diff', '--no-color', '-M', parent, commit, '--', filePath]
+    : ['diff-tree', '-p', '--no-color', '--root', '--no-commit-id', commit, '--', filePath];
+  const { stdout } = await exec('git', args, { cwd: worktreePath, maxBuffer: MAX_BUFFER });
+  if (/^Binary files .* differ$/m.test(stdout)) {
+    const [oldBlob, newBlob] = await Promise.all([
+      parent ? readBlob(worktreePath, parent, filePath) : Promise.resolve(null),
+      readBlob(worktreePath, commit, filePath),
+    ]);
+    return binaryDiff(filePath, oldBlob, newBlob);
+  }
+  return { type: 'text', diff: stdout };
+}
+
+export interface BlameLine {
+  /** 1-based line number in the blamed version. */
+  line: number;
+  /** All zeros for lines not committed yet. */
+  sha: string;
+  author: string;
+  authored_at: number;
+  summary: string;
+  /** Written on the task branch (or uncommitted) rather than inherited from main. */
+  in_task: boolean;
+  content: string;
+}
+
+const UNCOMMITTED_SHA = /^0+$/;
+
+/**
+ * Blame one file at `ref`, or in the working tree when `ref` is null (lines
+ * not committed yet then carry an all-zero sha).
+ */
+export async function getFileBlame(
+  worktreePath: string,
+  filePath: string,
+  ref: string | null,
+): Promise<BlameLine[]> {
+  if (ref !== null) validateRef(ref, 'ref');
+  const tip = ref ?? 'HEAD';
+  const mainBranch = await detectMainBranch(worktreePath);
+  const [{ stdout: blameOut }, { stdout: taskOut }] = await Promise.all([
+    exec('git', ['blame', '--porcelain', ...(ref ? [ref] : []), '--', filePath], {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    }),
+    exec('git', ['rev-list', tip, '--not', mainBranch, '--'], {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    }).catch(() => ({ stdout: '' })),
+  ]);
+  const taskCommits = new Set(taskOut.split('\n').filter(Boolean));
+
+  // Porcelain output repeats commit headers only on a commit's first group
+  const commits = new Map<string, { author: string; authored_at: number; summary: string }>();
+  const result: BlameLine[] = [];
+  let current: { sha: string; line: number } | null = null;
+  for (const raw of blameOut.split('\n')) {
+    if (raw.startsWith('\t')) {
+      if (!current) continue;
+      const info = commits.get(current.sha);
+      result.push({
+        line: current.line,
+        sha: current.sha,
+        author: info?.author ?? '',
+        authored_at: info?.authored_at ?? 0,
+        summary: info?.summary ?? '',
+        in_task: UNCOMMITTED_SHA.test(current.sha) || taskCommits.has(current.sha),
+        content: raw.slice(1),
+      });
+      current = null;
+      continue;
+    }
+    const header = /^([0-9a-f]{40,64}) \d+ (\d+)/.exec(raw);
+    if (header) {
+      current = { sha: header[1], line: parseInt(header[2], 10) };
+      if (!commits.has(current.sha)) {
+        commits.set(current.sha, { author: '', authored_at: 0, summary: '' });
       }
-      return pseudo;
+      continue;
+    }
+    const info = current && commits.get(current.sha);
+    if (!info) continue;
+    const space = raw.indexOf(' ');
+    const key = space === -1 ? raw : raw.slice(0, space);
+    const value = space === -1 ? '' : raw.slice(space + 1);
+    if (key === 'author') info.author = value;
+    else if (key === 'author-time') info.authored_at = parseInt(value, 10) || 0;
+    else if (key === 'summary') info.summary = value;
+  }
+  return result;
+}
+
+export interface SubmoduleStatus {
+  path: string;
+  /** Commit recorded in the superproject (or checked out, when modified). */
+  sha: string;
+  state: 'uninitialized' | 'up_to_date' | 'modified' | 'conflict';
+}
+
+const SUBMODULE_STATES: Record<string, SubmoduleStatus['state']> = {
+  ' ': 'up_to_date',
+  '-': 'uninitialized',
+  '+': 'modified',
+  U: 'conflict',
+};
+
+async function getSubmoduleStatus(worktreePath: string): Promise<SubmoduleStatus[]> {
+  if (!fs.existsSync(path.join(worktreePath, '.gitmodules'))) return [];
+  try {
+    const { stdout } = await exec('git', ['submodule', 'status', '--recursive'], {
+      cwd: worktreePath,
+      maxBuffer: MAX_BUFFER,
+    });
+    const result: SubmoduleStatus[] = [];
+    // "<flag><sha> <path>[ (<describe>)]"
+    for (const line of stdout.split('\n')) {
+      const m = /^([ +\-U])([0-9a-f]+) (.+?)(?: \(.*\))?$/.exec(line);
+      if (m) result.push({ path: m[3], sha: m[2], state: SUBMODULE_STATES[m[1]] });
     }
+    return result;
   } catch {
-    /* file doesn't exist or unreadable */
+    return [];
   }
+}
 
-  return '';
+export type WorktreeOperation = 'merge' | 'rebase' | 'cherry_pick' | 'revert';
+
+/** Which multi-step operation, if any, the worktree is stopped in the middle of. */
+async function getWorktreeOperation(worktreePath: string): Promise<WorktreeOperation | null> {
+  const { stdout } = await exec('git', ['rev-parse', '--absolute-git-dir'], { cwd: worktreePath });
+  const gitDir = stdout.trim();
+  const exists = (name: string) => fs.existsSync(path.join(gitDir, name));
+  // rebase-apply also covers `git am`, which continues and aborts the same way
+  if (exists('rebase-merge') || exists('rebase-apply')) return 'rebase';
+  if (exists('MERGE_HEAD')) return 'merge';
+  if (exists('CHERRY_PICK_HEAD')) return 'cherry_pick';
+  if (exists('REVERT_HEAD')) return 'revert';
+  return null;
 }
 
-export async function getWorktreeStatus(
-  worktreePath: string,
-): Promise<{ has_committed_changes: boolean; has_uncommitted_changes: boolean }> {
+export async function getWorktreeStatus(worktreePath: string): Promise<{
+  has_committed_changes: boolean;
+  has_uncommitted_changes: boolean;
+  submodules: SubmoduleStatus[];
+  /** Merge, rebase, cherry-pick or revert stopped part-way, e.g. on conflicts. */
+  operation: WorktreeOperation | null;
+  conflicted_files: number;
+}> {
   const { stdout: statusOut } = await exec('git', ['status', '--porcelain'], {
     cwd: worktreePath,
     maxBuffer: MAX_BUFFER,
@@ -473,23 +1796,73 @@ export async function getWorktreeStatus(
     /* ignore */
   }
 
+  const operation = await getWorktreeOperation(worktreePath).catch(() => null);
+  const conflicted = operation ? await listConflictedPaths(worktreePath).catch(() => []) : [];
+
   return {
     has_committed_changes: hasCommittedChanges,
     has_uncommitted_changes: hasUncommittedChanges,
+    submodules: await getSubmoduleStatus(worktreePath),
+    operation,
+    conflicted_files: conflicted.length,
   };
 }
 
-export async function checkMergeStatus(
-  worktreePath: string,
-): Promise<{ main_ahead_count: number; conflicting_files: string[] }> {
-  const mainBranch = await detectMainBranch(worktreePath);
+const OPERATION_COMMANDS: Record<Exclude<WorktreeOperation, 'merge'>, string> = {
+  rebase: 'rebase',
+  cherry_pick: 'cherry-pick',
+  revert: 'revert',
+};
 
-  let mainAheadCount = 0;
-  try {
-    const { stdout } = await exec('git', ['rev-list', '--count', `HEAD..${mainBranch}`], {
+/** Resume the worktree's stopped operation once its conflicts are resolved. */
+export async function continueWorktreeOperation(worktreePath: string): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const operation = await getWorktreeOperation(worktreePath);
+    if (!operation) throw new Error('No merge, rebase or cherry-pick in progress');
+    const remaining = await listConflictedPaths(worktreePath);
+    if (remaining.length > 0) {
+      throw new Error(`Unresolved conflicts remain: ${remaining.join(', ')}`);
+    }
+    const args =
+      operation === 'merge'
+        ? ['commit', '--no-edit']
+        : [OPERATION_COMMANDS[operation], '--continue'];
+    // Keep git's prepared messages instead of waiting on an editor
+    await exec('git', args, {
       cwd: worktreePath,
+      env: { ...process.env, GIT_EDITOR: 'true' },
+      maxBuffer: MAX_BUFFER,
     });
-    mainAheadCount = parseInt(stdout.trim(), 10) || 0;
+    invalidateMergeBaseCache();
+  });
+}
+
+/** Abandon the worktree's stopped operation and restore the state before it. */
+export async function abortWorktreeOperation(worktreePath: string): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const operation = await getWorktreeOperation(worktreePath);
+    if (!operation) throw new Error('No merge, rebase or cherry-pick in progress');
+    const command = operation === 'merge' ? 'merge' : OPERATION_COMMANDS[operation];
+    await exec('git', [command, '--abort'], { cwd: worktreePath });
+    invalidateMergeBaseCache();
+  });
+}
+
+export async function checkMergeStatus(
+  worktreePath: string,
+): Promise<{ main_ahead_count: number; conflicting_files: string[] }> {
+  const mainBranch = await detectMainBranch(worktreePath);
+
+  let mainAheadCount = 0;
+  try {
+    const { stdout } = await exec('git', ['rev-list', '--count', `HEAD..${mainBranch}`], {
+      cwd: worktreePath,
+    });
+    mainAheadCount = parseInt(stdout.trim(), 10) || 0;
   } catch {
     /* ignore */
   }
@@ -511,16 +1884,226 @@ export async function checkMergeStatus(
   return { main_ahead_count: mainAheadCount, conflicting_files: conflictingFiles };
 }
 
+export interface MergePreviewFile {
+  path: string;
+  lines_added: number;
+  lines_removed: number;
+  binary: boolean;
+}
+
+export interface MergePreview {
+  main_branch: string;
+  clean: boolean;
+  conflicting_files: string[];
+  /** Diffstat of what the merge would change on the main branch. */
+  files: MergePreviewFile[];
+  lines_added: number;
+  lines_removed: number;
+}
+
+/** Dry-run a merge of `branchName` into main with `git merge-tree`, touching no worktree. */
+export async function previewMerge(projectRoot: string, branchName: string): Promise<MergePreview> {
+  const mainBranch = await detectMainBranch(projectRoot);
+
+  // -z output: "<tree>\0<conflicted path>\0...\0\0<messages>"; exit 1 means conflicts
+  let out: string;
+  let clean = true;
+  try {
+    ({ stdout: out } = await exec(
+      'git',
+      ['merge-tree', '--write-tree', '--name-only', '-z', mainBranch, branchName],
+      { cwd: projectRoot, maxBuffer: MAX_BUFFER },
+    ));
+  } catch (e) {
+    if (!(e instanceof GitError) || e.code !== 1) throw e;
+    out = e.stdout;
+    clean = false;
+  }
+
+  const fields = out.split('\0');
+  const tree = fields[0].trim();
+  const conflicting = new Set<string>();
+  if (!clean) {
+    for (let i = 1; i < fields.length && fields[i] !== ''; i++) conflicting.add(fields[i]);
+  }
+
+  // The written tree still exists on conflict (with markers in conflicted files)
+  const { stdout: numstat } = await exec('git', ['diff', '--numstat', '-z', mainBranch, tree], {
+    cwd: projectRoot,
+    maxBuffer: MAX_BUFFER,
+  });
+  const files: MergePreviewFile[] = [];
+  let linesAdded = 0;
+  let linesRemoved = 0;
+  const tokens = numstat.split('\0');
+  for (let i = 0; i < tokens.length; i++) {
+    const m = /^(\d+|-)\t(\d+|-)\t(.*)$/s.exec(tokens[i]);
+    if (!m) continue;
+    let p = m[3];
+    // Renames leave the path empty, then list src and dst
+    if (p === '') {
+      p = tokens[i + 2] ?? '';
+      i += 2;
+    }
+    const binary = m[1] === '-';
+    const added = binary ? 0 : parseInt(m[1], 10);
+    const removed = binary ? 0 : parseInt(m[2], 10);
+    linesAdded += added;
+    linesRemoved += removed;
+    files.push({ path: p, lines_added: added, lines_removed: removed, binary });
+  }
+
+  return {
+    main_branch: mainBranch,
+    clean,
+    conflicting_files: [...conflicting],
+    files,
+    lines_added: linesAdded,
+    lines_removed: linesRemoved,
+  };
+}
+
+export interface ConflictFile {
+  path: string;
+  /** Base, ours (main) and theirs (task branch) versions; null if absent or too large. */
+  base: string | null;
+  ours: string | null;
+  theirs: string | null;
+  binary: boolean;
+}
+
+export interface MergeConflict {
+  project_root: string;
+  branch_name: string;
+  main_branch: string;
+  files: ConflictFile[];
+}
+
+export interface MergeResult {
+  main_branch: string;
+  lines_added: number;
+  lines_removed: number;
+  /** Set when the merge stopped on conflicts and is waiting for resolve/continue/abort. */
+  conflict?: MergeConflict;
+}
+
+interface PendingMerge {
+  branchName: string;
+  mainBranch: string;
+  originalBranch: string | null;
+  squash: boolean;
+  message: string | null;
+  cleanup: boolean;
+  linesAdded: number;
+  linesRemoved: number;
+}
+
+/** Merges stopped on conflicts, keyed by project root. */
+const pendingMerges = new Map<string, PendingMerge>();
+const MAX_CONFLICT_BLOB = 1024 * 1024; // 1MB
+
+async function restoreOriginalBranch(projectRoot: string, branch: string | null): Promise<void> {
+  if (!branch) return;
+  try {
+    await exec('git', ['checkout', branch], { cwd: projectRoot });
+  } catch {
+    /* ignore */
+  }
+}
+
+async function readIndexStage(
+  projectRoot: string,
+  stage: 1 | 2 | 3,
+  filePath: string,
+): Promise<{ content: string | null; binary: boolean }> {
+  try {
+    const { stdout } = await exec('git', ['show', `:${stage}:${filePath}`], {
+      cwd: projectRoot,
+      maxBuffer: MAX_BUFFER,
+    });
+    if (stdout.length > MAX_CONFLICT_BLOB) return { content: null, binary: false };
+    if (stdout.slice(0, 8000).includes('\0')) return { content: null, binary: true };
+    return { content: stdout, binary: false };
+  } catch {
+    return { content: null, binary: false };
+  }
+}
+
+async function listConflictedPaths(projectRoot: string): Promise<string[]> {
+  const { stdout } = await exec('git', ['diff', '--name-only', '--diff-filter=U', '-z'], {
+    cwd: projectRoot,
+    maxBuffer: MAX_BUFFER,
+  });
+  return stdout.split('\0').filter((p) => p.length > 0);
+}
+
+async function buildConflictReport(
+  projectRoot: string,
+  pending: PendingMerge,
+): Promise<MergeConflict> {
+  const files: ConflictFile[] = [];
+  for (const p of await listConflictedPaths(projectRoot)) {
+    const [base, ours, theirs] = await Promise.all([
+      readIndexStage(projectRoot, 1, p),
+      readIndexStage(projectRoot, 2, p),
+      readIndexStage(projectRoot, 3, p),
+    ]);
+    files.push({
+      path: p,
+      base: base.content,
+      ours: ours.content,
+      theirs: theirs.content,
+      binary: base.binary || ours.binary || theirs.binary,
+    });
+  }
+  return {
+    project_root: projectRoot,
+    branch_name: pending.branchName,
+    main_branch: pending.mainBranch,
+    files,
+  };
+}
+
+async function finishMerge(projectRoot: string, pending: PendingMerge): Promise<MergeResult> {
+  invalidateMergeBaseCache();
+  if (pending.cleanup) {
+    await removeWorktree(projectRoot, pending.branchName, true);
+  }
+  await restoreOriginalBranch(projectRoot, pending.originalBranch);
+  return {
+    main_branch: pending.mainBranch,
+    lines_added: pending.linesAdded,
+    lines_removed: pending.linesRemoved,
+  };
+}
+
+function getPendingMerge(projectRoot: string): PendingMerge {
+  const pending = pendingMerges.get(cacheKey(projectRoot));
+  if (!pending) throw new Error('No merge in progress for this project');
+  return pending;
+}
+
+/**
+ * Merge a task branch into main. With `keepConflicts`, a conflicting merge
+ * is left in progress and reported as `conflict` so the UI can drive
+ * resolveConflict/continueMerge/abortMerge; otherwise it is aborted and an
+ * error is thrown.
+ */
 export async function mergeTask(
   projectRoot: string,
   branchName: string,
   squash: boolean,
   message: string | null,
   cleanup: boolean,
-): Promise<{ main_branch: string; lines_added: number; lines_removed: number }> {
+  keepConflicts = false,
+): Promise<MergeResult> {
   const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
 
   return withWorktreeLock(lockKey, async () => {
+    if (pendingMerges.has(cacheKey(projectRoot))) {
+      throw new Error('A merge with conflicts is already in progress for this project.');
+    }
+
     const mainBranch = await detectMainBranch(projectRoot);
     const { linesAdded, linesRemoved } = await computeBranchDiffStats(
       projectRoot,
@@ -538,82 +2121,756 @@ export async function mergeTask(
       );
 
     const originalBranch = await getCurrentBranchName(projectRoot).catch(() => null);
+    const pending: PendingMerge = {
+      branchName,
+      mainBranch,
+      originalBranch,
+      squash,
+      message,
+      cleanup,
+      linesAdded,
+      linesRemoved,
+    };
 
     // Checkout main
     await exec('git', ['checkout', mainBranch], { cwd: projectRoot });
 
-    const restoreBranch = async () => {
-      if (originalBranch) {
-        try {
-          await exec('git', ['checkout', originalBranch], { cwd: projectRoot });
-        } catch {
-          /* ignore */
-        }
-      }
+    const restoreBranch = () => restoreOriginalBranch(projectRoot, originalBranch);
+
+    const stopOnConflict = async (e: unknown): Promise<MergeResult | null> => {
+      if (!keepConflicts || !(e instanceof GitError) || e.kind !== 'conflict') return null;
+      pendingMerges.set(cacheKey(projectRoot), pending);
+      return {
+        main_branch: mainBranch,
+        lines_added: linesAdded,
+        lines_removed: linesRemoved,
+        conflict: await buildConflictReport(projectRoot, pending),
+      };
     };
 
     if (squash) {
       try {
         await exec('git', ['merge', '--squash', '--', branchName], { cwd: projectRoot });
       } catch (e) {
+        const stopped = await stopOnConflict(e);
+        if (stopped) return stopped;
         await exec('git', ['reset', '--hard', 'HEAD'], { cwd: projectRoot }).catch(() => {});
         await restoreBranch();
         throw new Error(`Squash merge failed: ${e}`);
       }
       const msg = message ?? 'Squash merge';
+      const hooks = await hookOptions(projectRoot, 'commit');
       try {
-        await exec('git', ['commit', '-m', msg], { cwd: projectRoot });
+        await exec('git', ['commit', ...hooks.args, '-m', msg], {
+          ...hooks.opts,
+          cwd: projectRoot,
+        });
       } catch (e) {
         await exec('git', ['reset', '--hard', 'HEAD'], { cwd: projectRoot }).catch(() => {});
         await restoreBranch();
-        throw new Error(`Commit failed: ${e}`);
+        throw (await asSigningError(projectRoot, e)) ?? new Error(`Commit failed: ${e}`);
       }
     } else {
+      const hooks = await hookOptions(projectRoot, 'merge');
       try {
-        await exec('git', ['merge', '--', branchName], { cwd: projectRoot });
+        await exec('git', ['merge', ...hooks.args, '--', branchName], {
+          ...hooks.opts,
+          cwd: projectRoot,
+        });
       } catch (e) {
+        const stopped = await stopOnConflict(e);
+        if (stopped) return stopped;
         await exec('git', ['merge', '--abort'], { cwd: projectRoot }).catch(() => {});
         await restoreBranch();
-        throw new Error(`Merge failed: ${e}`);
+        throw (await asSigningError(projectRoot, e)) ?? new Error(`Merge failed: ${e}`);
+      }
+    }
+
+    return finishMerge(projectRoot, pending);
+  });
+}
+
+export interface LinkedMergeEntry {
+  projectRoot: string;
+  branchName: string;
+}
+
+/** Put a repo's main branch back to `sha` after a linked merge failed elsewhere. */
+async function rollbackMain(projectRoot: string, mainBranch: string, sha: string): Promise<void> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+  await withWorktreeLock(lockKey, async () => {
+    const current = await getCurrentBranchName(projectRoot).catch(() => null);
+    if (current === mainBranch) {
+      await exec('git', ['reset', '--keep', sha], { cwd: projectRoot });
+    } else {
+      await exec('git', ['update-ref', `refs/heads/${mainBranch}`, sha], { cwd: projectRoot });
+    }
+    invalidateMergeBaseCache();
+  });
+}
+
+/**
+ * Merge the branches of a task that spans several repositories, all or
+ * nothing: every merge is dry-run first, and if one still fails the mains
+ * that were already updated are reset to where they were. Worktrees are only
+ * cleaned up once every merge has landed.
+ */
+export async function mergeLinkedTasks(
+  entries: LinkedMergeEntry[],
+  squash: boolean,
+  message: string | null,
+  cleanup: boolean,
+): Promise<MergeResult[]> {
+  if (entries.length === 0) throw new Error('Nothing to merge');
+  const repoKeys = await Promise.all(
+    entries.map((e) => detectRepoLockKey(e.projectRoot).catch(() => e.projectRoot)),
+  );
+  if (new Set(repoKeys).size !== repoKeys.length) {
+    throw new Error('Each linked branch must belong to a different repository');
+  }
+
+  const previews = await Promise.all(entries.map((e) => previewMerge(e.projectRoot, e.branchName)));
+  const blocked: string[] = [];
+  previews.forEach((p, i) => {
+    if (!p.clean) blocked.push(`${entries[i].projectRoot}: ${p.conflicting_files.join(', ')}`);
+  });
+  if (blocked.length > 0) throw new Error(`Merge would conflict in ${blocked.join('; ')}`);
+
+  const before = await Promise.all(
+    entries.map(async (e, i) => {
+      const { stdout } = await exec('git', ['rev-parse', `refs/heads/${previews[i].main_branch}`], {
+        cwd: e.projectRoot,
+      });
+      return stdout.trim();
+    }),
+  );
+
+  const results: MergeResult[] = [];
+  for (const [i, e] of entries.entries()) {
+    try {
+      results.push(await mergeTask(e.projectRoot, e.branchName, squash, message, false));
+    } catch (err) {
+      const rollbackErrors: string[] = [];
+      for (let j = 0; j < i; j++) {
+        await rollbackMain(entries[j].projectRoot, previews[j].main_branch, before[j]).catch(
+          (re) => rollbackErrors.push(`${entries[j].projectRoot}: ${re}`),
+        );
       }
+      const suffix =
+        rollbackErrors.length > 0 ? ` Rolling back failed for ${rollbackErrors.join('; ')}` : '';
+      throw new Error(`Merge failed in ${e.projectRoot}: ${err}.${suffix}`);
+    }
+  }
+
+  if (cleanup) {
+    for (const e of entries) await removeWorktree(e.projectRoot, e.branchName, true);
+  }
+  return results;
+}
+
+/**
+ * Resolve one conflicted path of an in-progress merge: take main's version
+ * ("ours"), the task branch's ("theirs"), or explicit content.
+ */
+export async function resolveConflict(
+  projectRoot: string,
+  filePath: string,
+  resolution: { type: 'ours' | 'theirs' } | { type: 'content'; content: string },
+): Promise<{ remaining: string[] }> {
+  getPendingMerge(projectRoot);
+  if (resolution.type === 'content') {
+    await fs.promises.writeFile(path.join(projectRoot, filePath), resolution.content, 'utf8');
+  } else {
+    await exec('git', ['checkout', `--${resolution.type}`, '--', filePath], { cwd: projectRoot });
+  }
+  await exec('git', ['add', '--', filePath], { cwd: projectRoot });
+  return { remaining: await listConflictedPaths(projectRoot) };
+}
+
+/** Commit an in-progress merge once every conflict is resolved. */
+export async function continueMerge(projectRoot: string): Promise<MergeResult> {
+  const pending = getPendingMerge(projectRoot);
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+
+  return withWorktreeLock(lockKey, async () => {
+    const remaining = await listConflictedPaths(projectRoot);
+    if (remaining.length > 0) {
+      throw new Error(`Unresolved conflicts remain: ${remaining.join(', ')}`);
+    }
+    const hooks = await hookOptions(projectRoot, pending.squash ? 'commit' : 'merge');
+    const commitArgs = pending.squash
+      ? ['commit', ...hooks.args, '-m', pending.message ?? 'Squash merge']
+      : ['commit', ...hooks.args, '--no-edit'];
+    try {
+      await exec('git', commitArgs, { ...hooks.opts, cwd: projectRoot });
+    } catch (e) {
+      throw (await asSigningError(projectRoot, e)) ?? e;
+    }
+    pendingMerges.delete(cacheKey(projectRoot));
+    return finishMerge(projectRoot, pending);
+  });
+}
+
+/** Abandon an in-progress merge and return the project root to its original branch. */
+export async function abortMerge(projectRoot: string): Promise<void> {
+  const pending = getPendingMerge(projectRoot);
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+
+  return withWorktreeLock(lockKey, async () => {
+    // A squash merge leaves no MERGE_HEAD, so --abort doesn't apply
+    if (pending.squash) {
+      await exec('git', ['reset', '--hard', 'HEAD'], { cwd: projectRoot });
+    } else {
+      await exec('git', ['merge', '--abort'], { cwd: projectRoot });
+    }
+    pendingMerges.delete(cacheKey(projectRoot));
+    await restoreOriginalBranch(projectRoot, pending.originalBranch);
+  });
+}
+
+/**
+ * Stage `files` (or everything when omitted) and commit in a task worktree.
+ * Returns the new commit hash.
+ */
+export async function commitTask(
+  worktreePath: string,
+  message: string,
+  files: string[] | null,
+  amend: boolean,
+): Promise<{ commit: string }> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    if (files && files.length === 0) throw new Error('No files selected');
+    // -A so deletions of selected paths are staged too
+    await exec('git', ['add', '-A', '--', ...(files ?? ['.'])], { cwd: worktreePath });
+
+    if (!amend) {
+      try {
+        await exec('git', ['diff', '--cached', '--quiet', '--', ...(files ?? ['.'])], {
+          cwd: worktreePath,
+        });
+        throw new Error('Nothing to commit');
+      } catch (e) {
+        // Exit code 1 means there are staged changes
+        if (!(e instanceof GitError) || e.code !== 1) throw e;
+      }
+    }
+
+    const hooks = await hookOptions(worktreePath, 'commit');
+    const commitArgs = ['commit', ...hooks.args, '-m', message];
+    if (amend) commitArgs.push('--amend');
+    // Only commit the selected paths even if other changes were already staged
+    if (files) commitArgs.push('--', ...files);
+    try {
+      await exec('git', commitArgs, { ...hooks.opts, cwd: worktreePath, maxBuffer: MAX_BUFFER });
+    } catch (e) {
+      throw (await asSigningError(worktreePath, e)) ?? e;
     }
 
     invalidateMergeBaseCache();
+    const { stdout } = await exec('git', ['rev-parse', 'HEAD'], { cwd: worktreePath });
+    return { commit: stdout.trim() };
+  });
+}
 
-    if (cleanup) {
-      await removeWorktree(projectRoot, branchName, true);
+export interface DiffHunk {
+  /** The "@@ -a,b +c,d @@" line; identifies the hunk for stage/unstage. */
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: string[];
+}
+
+interface ParsedFileDiff {
+  /** diff --git / index / ---/+++ lines preceding the first hunk. */
+  header: string[];
+  hunks: DiffHunk[];
+}
+
+function parseUnifiedDiff(diff: string): ParsedFileDiff {
+  const result: ParsedFileDiff = { header: [], hunks: [] };
+  let current: DiffHunk | null = null;
+  const lines = diff.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  for (const line of lines) {
+    const m = /^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@/.exec(line);
+    if (m) {
+      current = {
+        header: line,
+        old_start: parseInt(m[1], 10),
+        old_lines: m[2] === undefined ? 1 : parseInt(m[2], 10),
+        new_start: parseInt(m[3], 10),
+        new_lines: m[4] === undefined ? 1 : parseInt(m[4], 10),
+        lines: [],
+      };
+      result.hunks.push(current);
+    } else if (current) {
+      current.lines.push(line);
+    } else {
+      result.header.push(line);
     }
+  }
+  return result;
+}
+
+async function readFileDiff(
+  worktreePath: string,
+  filePath: string,
+  staged: boolean,
+): Promise<ParsedFileDiff> {
+  const args = ['diff', '--no-color', '--no-ext-diff'];
+  if (staged) args.push('--cached');
+  const { stdout } = await exec('git', [...args, '--', filePath], {
+    cwd: worktreePath,
+    maxBuffer: MAX_BUFFER,
+  });
+  return parseUnifiedDiff(stdout);
+}
+
+/** Unstaged (worktree vs index) and staged (index vs HEAD) hunks for one file. */
+export async function getFileHunks(
+  worktreePath: string,
+  filePath: string,
+): Promise<{ unstaged: DiffHunk[]; staged: DiffHunk[] }> {
+  const [unstaged, staged] = await Promise.all([
+    readFileDiff(worktreePath, filePath, false),
+    readFileDiff(worktreePath, filePath, true),
+  ]);
+  return { unstaged: unstaged.hunks, staged: staged.hunks };
+}
+
+async function applyHunk(
+  worktreePath: string,
+  filePath: string,
+  hunkHeader: string,
+  unstage: boolean,
+): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const diff = await readFileDiff(worktreePath, filePath, unstage);
+    const hunk = diff.hunks.find((h) => h.header === hunkHeader);
+    if (!hunk) throw new Error('Hunk not found — the diff has changed, refresh and try again');
+    const patch = [...diff.header, hunk.header, ...hunk.lines].join('\n') + '\n';
+    const args = ['apply', '--cached', '--whitespace=nowarn'];
+    if (unstage) args.push('--reverse');
+    await exec('git', [...args, '-'], { cwd: worktreePath, input: patch });
+  });
+}
+
+/** Stage a single hunk from the worktree, identified by its @@ header. */
+export async function stageHunk(
+  worktreePath: string,
+  filePath: string,
+  hunkHeader: string,
+): Promise<void> {
+  return applyHunk(worktreePath, filePath, hunkHeader, false);
+}
+
+/** Remove a single staged hunk from the index, leaving the worktree untouched. */
+export async function unstageHunk(
+  worktreePath: string,
+  filePath: string,
+  hunkHeader: string,
+): Promise<void> {
+  return applyHunk(worktreePath, filePath, hunkHeader, true);
+}
 
-    await restoreBranch();
+async function listUntracked(worktreePath: string, paths: string[]): Promise<Set<string>> {
+  const { stdout } = await exec(
+    'git',
+    ['ls-files', '--others', '--exclude-standard', '-z', '--', ...paths],
+    { cwd: worktreePath, maxBuffer: MAX_BUFFER },
+  );
+  return new Set(stdout.split('\0').filter((p) => p.length > 0));
+}
 
-    return { main_branch: mainBranch, lines_added: linesAdded, lines_removed: linesRemoved };
+/** Throw away changes to paths: tracked files revert to HEAD, untracked ones are deleted. */
+export async function discardFileChanges(worktreePath: string, paths: string[]): Promise<void> {
+  if (paths.length === 0) return;
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const untracked = await listUntracked(worktreePath, paths);
+    const tracked = paths.filter((p) => !untracked.has(p));
+    if (tracked.length > 0) {
+      await exec(
+        'git',
+        ['restore', '--source=HEAD', '--staged', '--worktree', '--', ...tracked],
+        { cwd: worktreePath },
+      );
+    }
+    for (const p of untracked) {
+      await fs.promises.rm(path.join(worktreePath, p), { force: true });
+    }
   });
 }
 
-export async function getBranchLog(worktreePath: string): Promise<string> {
-  const mainBranch = await detectMainBranch(worktreePath).catch(() => 'HEAD');
-  try {
-    const { stdout } = await exec('git', ['log', `${mainBranch}..HEAD`, '--pretty=format:- %s'], {
+const DISCARD_TOKEN_TTL = 60_000; // 60s
+const discardTokens = new Map<string, { token: string; expiresAt: number }>();
+
+/**
+ * Reset a worktree to HEAD and delete untracked files. The first call (no
+ * token) only reports what would be lost and returns a single-use token;
+ * calling again with that token within a minute performs the discard.
+ */
+export async function discardAllChanges(
+  worktreePath: string,
+  confirmToken: string | null,
+): Promise<{ discarded: boolean; confirm_token: string | null; files: string[] }> {
+  const key = cacheKey(worktreePath);
+  const pending = discardTokens.get(key);
+
+  const confirmed =
+    !!confirmToken && pending?.token === confirmToken && pending.expiresAt >= Date.now();
+  if (!confirmed) {
+    const { stdout } = await exec('git', ['status', '--porcelain', '-z'], {
       cwd: worktreePath,
       maxBuffer: MAX_BUFFER,
     });
-    return stdout;
+    const files = parsePorcelainZ(stdout).map((e) => e.path);
+    const token = randomUUID();
+    discardTokens.set(key, { token, expiresAt: Date.now() + DISCARD_TOKEN_TTL });
+    return { discarded: false, confirm_token: token, files };
+  }
+
+  discardTokens.delete(key);
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+  return withWorktreeLock(lockKey, async () => {
+    await exec('git', ['reset', '--hard', 'HEAD'], { cwd: worktreePath });
+    await exec('git', ['clean', '-fd'], { cwd: worktreePath });
+    invalidateMergeBaseCache();
+    return { discarded: true, confirm_token: null, files: [] };
+  });
+}
+
+export interface StashEntry {
+  /** Stash commit hash — stable, unlike stash@{n} which shifts as stashes are added. */
+  id: string;
+  ref: string;
+  branch: string | null;
+  message: string;
+  created_at: number;
+}
+
+async function listAllStashes(worktreePath: string): Promise<StashEntry[]> {
+  const { stdout } = await exec('git', ['stash', 'list', '--format=%H%x00%gd%x00%gs%x00%ct'], {
+    cwd: worktreePath,
+    maxBuffer: MAX_BUFFER,
+  });
+  const entries: StashEntry[] = [];
+  for (const line of stdout.split('\n')) {
+    const [id, ref, subject, ct] = line.split('\0');
+    if (!id || !ref) continue;
+    // Subjects look like "On <branch>: msg" or "WIP on <branch>: <sha> msg"
+    const m = /^(?:WIP on|On) ([^:]+): (.*)$/.exec(subject ?? '');
+    entries.push({
+      id,
+      ref,
+      branch: m && m[1] !== '(no branch)' ? m[1] : null,
+      message: m ? m[2] : (subject ?? ''),
+      created_at: (parseInt(ct, 10) || 0) * 1000,
+    });
+  }
+  return entries;
+}
+
+async function findStashRef(worktreePath: string, id: string): Promise<string> {
+  const entry = (await listAllStashes(worktreePath)).find((e) => e.id === id);
+  if (!entry) throw new Error(`Stash not found: ${id}`);
+  return entry.ref;
+}
+
+/** Stash dirty work in a worktree. Returns the new stash, or null if there was nothing to stash. */
+export async function stashChanges(
+  worktreePath: string,
+  message: string | null,
+  includeUntracked: boolean,
+): Promise<StashEntry | null> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const before = (await listAllStashes(worktreePath))[0]?.id;
+    const args = ['stash', 'push'];
+    if (includeUntracked) args.push('--include-untracked');
+    if (message) args.push('-m', message);
+    await exec('git', args, { cwd: worktreePath });
+    const top = (await listAllStashes(worktreePath))[0];
+    return top && top.id !== before ? top : null;
+  });
+}
+
+/**
+ * Stashes created on the worktree's current branch. The stash list is shared
+ * by every worktree of a repository, so entries are scoped by branch.
+ */
+export async function listStashes(worktreePath: string): Promise<StashEntry[]> {
+  const branch = await getCurrentBranchName(worktreePath).catch(() => null);
+  const all = await listAllStashes(worktreePath);
+  return branch ? all.filter((e) => e.branch === branch) : all;
+}
+
+export async function applyStash(worktreePath: string, id: string, pop: boolean): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const ref = await findStashRef(worktreePath, id);
+    await exec('git', ['stash', pop ? 'pop' : 'apply', ref], { cwd: worktreePath });
+  });
+}
+
+export async function dropStash(worktreePath: string, id: string): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const ref = await findStashRef(worktreePath, id);
+    await exec('git', ['stash', 'drop', ref], { cwd: worktreePath });
+  });
+}
+
+export interface BranchLogEntry {
+  sha: string;
+  short_sha: string;
+  parents: string[];
+  /** Ref decorations, e.g. "HEAD -> task/foo", "tag: v1.2". */
+  refs: string[];
+  author: string;
+  /** Unix seconds. */
+  authored_at: number;
+  subject: string;
+}
+
+export interface BranchLog {
+  /** Newest first, in topological order. */
+  commits: BranchLogEntry[];
+  has_more: boolean;
+}
+
+const BRANCH_LOG_DEFAULT_LIMIT = 100;
+const BRANCH_LOG_MAX_LIMIT = 1000;
+
+export async function getBranchLog(
+  worktreePath: string,
+  skip = 0,
+  limit = BRANCH_LOG_DEFAULT_LIMIT,
+): Promise<BranchLog> {
+  if (!Number.isInteger(skip) || skip < 0) throw new Error('skip must be a non-negative integer');
+  if (!Number.isInteger(limit) || limit < 1 || limit > BRANCH_LOG_MAX_LIMIT) {
+    throw new Error(`limit must be an integer between 1 and ${BRANCH_LOG_MAX_LIMIT}`);
+  }
+  const mainBranch = await detectMainBranch(worktreePath).catch(() => 'HEAD');
+  let stdout: string;
+  try {
+    // One extra commit tells us whether another page exists
+    ({ stdout } = await exec(
+      'git',
+      [
+        'log',
+        '-z',
+        '--topo-order',
+        `--skip=${skip}`,
+        `--max-count=${limit + 1}`,
+        '--format=%H%x1f%h%x1f%P%x1f%D%x1f%an%x1f%at%x1f%s',
+        `${mainBranch}..HEAD`,
+      ],
+      { cwd: worktreePath, maxBuffer: MAX_BUFFER },
+    ));
   } catch {
-    return '';
+    return { commits: [], has_more: false };
   }
+
+  const commits: BranchLogEntry[] = [];
+  for (const record of stdout.split('\0')) {
+    const [sha, short, parents, refs, author, at, subject] = record.split('\x1f');
+    if (!sha?.trim()) continue;
+    commits.push({
+      sha: sha.trim(),
+      short_sha: short,
+      parents: parents ? parents.split(' ') : [],
+      refs: refs ? refs.split(', ') : [],
+      author,
+      authored_at: parseInt(at, 10) || 0,
+      subject: subject ?? '',
+    });
+  }
+  const hasMore = commits.length > limit;
+  return { commits: commits.slice(0, limit), has_more: hasMore };
+}
+
+export async function getRemoteUrl(repoPath: string, remote = 'origin'): Promise<string> {
+  try {
+    const { stdout } = await exec('git', ['remote', 'get-url', remote], { cwd: repoPath });
+    return stdout.trim();
+  } catch {
+    throw new Error(`No "${remote}" remote configured`);
+  }
+}
+
+// --- Remote credentials ---
+
+export interface CredentialStatus {
+  remote_url: string | null;
+  protocol: 'ssh' | 'https' | 'other' | null;
+  /** credential.helper, e.g. "osxkeychain" or "manager". */
+  credential_helper: string | null;
+  /** SSH_AUTH_SOCK is set and points at a live socket. */
+  ssh_agent: boolean;
+}
+
+/** Thrown when a remote rejects the app's credentials; `hints` says what to try. */
+export class AuthRequiredError extends Error {
+  constructor(
+    readonly status: CredentialStatus,
+    readonly hints: string[],
+    readonly detail: string,
+  ) {
+    super(`Authentication required for ${status.remote_url ?? 'the remote'}. ${hints.join(' ')}`);
+    this.name = 'AuthRequiredError';
+  }
+}
+
+function remoteProtocol(url: string): CredentialStatus['protocol'] {
+  if (/^https?:\/\//i.test(url)) return 'https';
+  // ssh://host/path or scp-like user@host:path
+  if (/^ssh:\/\//i.test(url) || /^[^/\\]+@[^/\\]+:/.test(url)) return 'ssh';
+  return 'other';
+}
+
+function hasSshAgent(): boolean {
+  const sock = process.env.SSH_AUTH_SOCK;
+  if (!sock) return false;
+  // On Windows the OpenSSH agent is a named pipe that stat can't see
+  if (process.platform === 'win32') return true;
+  try {
+    return fs.statSync(sock).isSocket();
+  } catch {
+    return false;
+  }
+}
+
+/** How git will authenticate against `remote`: agent, credential helper, or neither. */
+export async function getCredentialStatus(
+  repoPath: string,
+  remote = 'origin',
+): Promise<CredentialStatus> {
+  const [remoteUrl, helper] = await Promise.all([
+    getConfigValue(repoPath, `remote.${remote}.url`),
+    getConfigValue(repoPath, 'credential.helper'),
+  ]);
+  return {
+    remote_url: remoteUrl,
+    protocol: remoteUrl ? remoteProtocol(remoteUrl) : null,
+    credential_helper: helper,
+    ssh_agent: hasSshAgent(),
+  };
+}
+
+function authHints(status: CredentialStatus): string[] {
+  if (status.protocol === 'ssh') {
+    return status.ssh_agent
+      ? [
+          'Check that your key is loaded (ssh-add -l) and registered with the host,',
+          'and that the host key is in ~/.ssh/known_hosts.',
+        ]
+      : [
+          'No SSH agent was found (SSH_AUTH_SOCK is not set).',
+          'Start ssh-agent and run ssh-add, then restart the app from that session.',
+        ];
+  }
+  if (status.protocol === 'https') {
+    return status.credential_helper
+      ? [
+          `The credentials stored by "${status.credential_helper}" were rejected;`,
+          'update them, e.g. with a new personal access token.',
+        ]
+      : [
+          'No credential helper is configured.',
+          'Set one up (e.g. `git config --global credential.helper manager` or',
+          '`gh auth setup-git`) or enter credentials when prompted.',
+        ];
+  }
+  return ['Check the remote URL and your access to it.'];
+}
+
+/** Turn an authentication failure into an AuthRequiredError; null for other errors. */
+async function asAuthError(repoPath: string, e: unknown): Promise<AuthRequiredError | null> {
+  if (!(e instanceof GitError) || e.kind !== 'auth_failed') return null;
+  const status = await getCredentialStatus(repoPath).catch(
+    (): CredentialStatus => ({
+      remote_url: null,
+      protocol: null,
+      credential_helper: null,
+      ssh_agent: hasSshAgent(),
+    }),
+  );
+  return new AuthRequiredError(status, authHints(status), e.stderr.trim());
 }
 
 export async function pushTask(projectRoot: string, branchName: string): Promise<void> {
-  await exec('git', ['push', '-u', 'origin', '--', branchName], { cwd: projectRoot });
+  const hooks = await hookOptions(projectRoot, 'push');
+  try {
+    await exec('git', ['push', ...hooks.args, '-u', 'origin', '--', branchName], {
+      ...hooks.opts,
+      cwd: projectRoot,
+      env: getAskpassEnv(),
+    });
+  } catch (e) {
+    throw (await asAuthError(projectRoot, e)) ?? e;
+  }
+}
+
+export interface RemoteComparison {
+  main_branch: string;
+  /** e.g. "origin/main". */
+  remote_ref: string;
+  /** Commits on the task branch that the remote main lacks. */
+  ahead: number;
+  /** Commits on the remote main that the task branch lacks. */
+  behind: number;
+}
+
+const FETCH_TIMEOUT = 60_000; // ms
+
+/** Fetch origin's main branch and count commits ahead/behind it. */
+export async function fetchAndCompare(worktreePath: string): Promise<RemoteComparison> {
+  const mainBranch = await detectMainBranch(worktreePath);
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+  const remoteRef = `origin/${mainBranch}`;
+
+  await withWorktreeLock(lockKey, async () => {
+    try {
+      await exec('git', ['fetch', '--quiet', '--no-tags', 'origin', '--', mainBranch], {
+        cwd: worktreePath,
+        // Never block on a credential prompt in the background
+        env: { ...process.env, GIT_TERMINAL_PROMPT: '0' },
+        timeout: FETCH_TIMEOUT,
+      });
+    } catch (e) {
+      throw (await asAuthError(worktreePath, e)) ?? new Error(`Fetch failed: ${e}`);
+    }
+  });
+
+  const { stdout } = await exec(
+    'git',
+    ['rev-list', '--left-right', '--count', `HEAD...${remoteRef}`],
+    { cwd: worktreePath },
+  );
+  const [ahead, behind] = stdout.trim().split(/\s+/).map((n) => parseInt(n, 10) || 0);
+  return { main_branch: mainBranch, remote_ref: remoteRef, ahead: ahead ?? 0, behind: behind ?? 0 };
 }
 
-export async function rebaseTask(worktreePath: string): Promise<void> {
+export async function rebaseTask(worktreePath: string, autostash = false): Promise<void> {
   const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
 
   return withWorktreeLock(lockKey, async () => {
     const mainBranch = await detectMainBranch(worktreePath);
+    // --autostash stashes dirty work first and re-applies it after the rebase
+    const args = autostash ? ['rebase', '--autostash', mainBranch] : ['rebase', mainBranch];
     try {
-      await exec('git', ['rebase', mainBranch], { cwd: worktreePath });
+      await exec('git', args, { cwd: worktreePath });
     } catch (e) {
       await exec('git', ['rebase', '--abort'], { cwd: worktreePath }).catch(() => {});
       throw new Error(`Rebase failed: ${e}`);
@@ -621,3 +2878,352 @@ export async function rebaseTask(worktreePath: string): Promise<void> {
     invalidateMergeBaseCache();
   });
 }
+
+export type RebaseAction = 'pick' | 'reword' | 'squash' | 'fixup' | 'drop';
+
+export interface RebaseCommit {
+  sha: string;
+  short_sha: string;
+  author: string;
+  /** Unix seconds. */
+  authored_at: number;
+  subject: string;
+  message: string;
+}
+
+export interface RebasePlan {
+  /** Merge base with main; the plan rewrites everything after it. */
+  base: string;
+  head: string;
+  /** Oldest first, in the order they are applied. */
+  commits: RebaseCommit[];
+}
+
+export interface RebaseStep {
+  sha: string;
+  action: RebaseAction;
+  /** New message for reword, or the combined message for squash. */
+  message?: string;
+}
+
+const REBASE_ACTIONS: readonly RebaseAction[] = ['pick', 'reword', 'squash', 'fixup', 'drop'];
+
+export async function getRebasePlan(worktreePath: string): Promise<RebasePlan> {
+  const mainBranch = await detectMainBranch(worktreePath);
+  const { stdout: baseOut } = await exec('git', ['merge-base', mainBranch, 'HEAD'], {
+    cwd: worktreePath,
+  });
+  const base = baseOut.trim();
+  const { stdout: headOut } = await exec('git', ['rev-parse', 'HEAD'], { cwd: worktreePath });
+  const head = headOut.trim();
+
+  const { stdout: merges } = await exec('git', ['rev-list', '--merges', `${base}..${head}`], {
+    cwd: worktreePath,
+  });
+  if (merges.trim()) {
+    throw new Error('Branch contains merge commits; an interactive rebase would flatten them.');
+  }
+
+  const { stdout } = await exec(
+    'git',
+    ['log', '-z', '--reverse', '--format=%H%x1f%h%x1f%an%x1f%at%x1f%s%x1f%B', `${base}..${head}`],
+    { cwd: worktreePath, maxBuffer: MAX_BUFFER },
+  );
+  const commits: RebaseCommit[] = [];
+  for (const record of stdout.split('\0')) {
+    const [sha, short, author, at, subject, ...body] = record.split('\x1f');
+    if (!sha?.trim()) continue;
+    commits.push({
+      sha: sha.trim(),
+      short_sha: short,
+      author,
+      authored_at: parseInt(at, 10) || 0,
+      subject,
+      message: body.join('\x1f').trimEnd(),
+    });
+  }
+  return { base, head, commits };
+}
+
+function shellQuote(s: string): string {
+  return `'${s.replace(/'/g, `'\\''`)}'`;
+}
+
+/**
+ * Rewrite the branch according to `steps` (new order, oldest first) via
+ * `git rebase -i` with a generated todo list. Every commit from the plan must
+ * appear exactly once; `head` guards against commits made since the plan was read.
+ */
+export async function executeRebasePlan(
+  worktreePath: string,
+  base: string,
+  head: string,
+  steps: RebaseStep[],
+): Promise<void> {
+  const lockKey = await detectRepoLockKey(worktreePath).catch(() => worktreePath);
+
+  return withWorktreeLock(lockKey, async () => {
+    const plan = await getRebasePlan(worktreePath);
+    if (plan.base !== base || plan.head !== head) {
+      throw new Error('Branch changed since the rebase plan was loaded. Reload and try again.');
+    }
+
+    const expected = new Set(plan.commits.map((c) => c.sha));
+    const seen = new Set<string>();
+    for (const step of steps) {
+      if (!REBASE_ACTIONS.includes(step.action)) {
+        throw new Error(`Invalid rebase action: ${step.action}`);
+      }
+      if (!expected.has(step.sha) || seen.has(step.sha)) {
+        throw new Error(`Unknown or duplicate commit in rebase plan: ${step.sha}`);
+      }
+      seen.add(step.sha);
+    }
+    if (seen.size !== expected.size) {
+      throw new Error('Rebase plan must list every commit (use "drop" to remove one).');
+    }
+    const firstKept = steps.find((s) => s.action !== 'drop');
+    if (firstKept && (firstKept.action === 'squash' || firstKept.action === 'fixup')) {
+      throw new Error('The first kept commit cannot be squashed into a previous one.');
+    }
+
+    const tmpDir = await fs.promises.mkdtemp(path.join(os.tmpdir(), 'parallel-code-rebase-'));
+    try {
+      // Custom messages are applied by amending right after the commit is made
+      const todo: string[] = [];
+      for (const [i, step] of steps.entries()) {
+        const custom = step.action === 'reword' || step.action === 'squash';
+        todo.push(`${step.action === 'reword' ? 'pick' : step.action} ${step.sha}`);
+        if (custom && step.message?.trim()) {
+          const msgPath = path.join(tmpDir, `msg-${i}`);
+          await fs.promises.writeFile(msgPath, step.message);
+          todo.push(`exec git commit --amend --allow-empty --quiet -F ${shellQuote(msgPath)}`);
+        }
+      }
+      const todoPath = path.join(tmpDir, 'todo');
+      await fs.promises.writeFile(todoPath, todo.join('\n') + '\n');
+
+      const env = {
+        ...process.env,
+        // git runs the sequence editor with the todo path appended
+        GIT_SEQUENCE_EDITOR: `cp ${shellQuote(todoPath)}`,
+        // Squashes keep git's combined message unless one was supplied above
+        GIT_EDITOR: 'true',
+      };
+      try {
+        await exec('git', ['rebase', '-i', '--autostash', base], {
+          cwd: worktreePath,
+          env,
+          maxBuffer: MAX_BUFFER,
+        });
+      } catch (e) {
+        await exec('git', ['rebase', '--abort'], { cwd: worktreePath }).catch(() => {});
+        throw new Error(`Rebase failed: ${e}`);
+      }
+    } finally {
+      await fs.promises.rm(tmpDir, { recursive: true, force: true });
+    }
+    invalidateMergeBaseCache();
+  });
+}
+
+// --- Task archives ---
+
+export interface BranchSnapshot {
+  head: string;
+  main_branch: string;
+  merge_base: string;
+  /** Everything since the merge base, uncommitted changes and new files included. */
+  diff: string;
+  /** Uncommitted changes alone, re-applied when the task is restored. */
+  uncommitted: string;
+  /** False when the branch has no commits of its own, so no bundle was written. */
+  bundled: boolean;
+}
+
+/**
+ * Capture a task branch before its worktree goes away: commits since the base
+ * branch are written to a git bundle at `bundlePath`, changes are returned as
+ * binary patches.
+ */
+export async function snapshotTaskBranch(
+  projectRoot: string,
+  worktreePath: string,
+  branchName: string,
+  bundlePath: string,
+): Promise<BranchSnapshot> {
+  const mainBranch = await detectMainBranch(projectRoot);
+  const { stdout: headOut } = await exec('git', ['rev-parse', `refs/heads/${branchName}`], {
+    cwd: projectRoot,
+  });
+  const head = headOut.trim();
+  const mergeBase = await exec('git', ['merge-base', mainBranch, head], { cwd: projectRoot })
+    .then(({ stdout }) => stdout.trim() || mainBranch)
+    .catch(() => mainBranch);
+
+  let diff: string;
+  let uncommitted = '';
+  if (fs.existsSync(worktreePath)) {
+    // Intent-to-add makes new files show up in the diffs; the index is discarded with the worktree
+    await exec('git', ['add', '--intent-to-add', '.'], { cwd: worktreePath }).catch(() => {});
+    const opts = { cwd: worktreePath, maxBuffer: MAX_BUFFER };
+    diff = (await exec('git', ['diff', '--binary', mergeBase], opts)).stdout;
+    uncommitted = (await exec('git', ['diff', '--binary', 'HEAD'], opts)).stdout;
+  } else {
+    const opts = { cwd: projectRoot, maxBuffer: MAX_BUFFER };
+    diff = (await exec('git', ['diff', '--binary', mergeBase, head], opts)).stdout;
+  }
+
+  const bundled = head !== mergeBase;
+  if (bundled) {
+    const range = [`refs/heads/${branchName}`, `^${mergeBase}`];
+    await exec('git', ['bundle', 'create', bundlePath, ...range], { cwd: projectRoot });
+  }
+  return { head, main_branch: mainBranch, merge_base: mergeBase, diff, uncommitted, bundled };
+}
+
+/**
+ * Recreate an archived task branch — from its bundle, or at `head` when it had
+ * no commits of its own — and check it out in a new worktree. Uncommitted
+ * changes are re-applied from `patchPath`; `patch_applied` is false if they no
+ * longer apply.
+ */
+export async function restoreTaskBranch(
+  projectRoot: string,
+  branchName: string,
+  head: string,
+  bundlePath: string | null,
+  patchPath: string | null,
+  symlinkDirs: string[],
+): Promise<{ path: string; branch: string; patch_applied: boolean }> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+  return withWorktreeLock(lockKey, async () => {
+    if (await branchExists(projectRoot, branchName)) {
+      throw new Error(`Branch ${branchName} already exists — rename or delete it first`);
+    }
+    const ref = `refs/heads/${branchName}`;
+    if (bundlePath) {
+      await exec('git', ['fetch', '--no-tags', bundlePath, `${ref}:${ref}`], { cwd: projectRoot });
+    } else {
+      await exec('git', ['branch', branchName, head], { cwd: projectRoot });
+    }
+    const worktree = await createWorktree(projectRoot, branchName, symlinkDirs);
+
+    let patchApplied = true;
+    if (patchPath) {
+      try {
+        await exec('git', ['apply', '--binary', '--whitespace=nowarn', patchPath], {
+          cwd: worktree.path,
+        });
+      } catch {
+        patchApplied = false;
+      }
+    }
+    return { ...worktree, patch_applied: patchApplied };
+  });
+}
+
+// --- Task snapshots and forks ---
+
+/** Top-level directories a worktree symlinks from the main checkout. */
+function symlinkedDirs(repoRoot: string, worktreePath: string): string[] {
+  try {
+    return fs.readdirSync(worktreePath, { withFileTypes: true }).flatMap((entry) => {
+      if (!entry.isSymbolicLink()) return [];
+      const target = fs.readlinkSync(path.join(worktreePath, entry.name));
+      return target === path.join(repoRoot, entry.name) ? [entry.name] : [];
+    });
+  } catch {
+    return [];
+  }
+}
+
+/**
+ * Tree object of a worktree's files as they are on disk, untracked ones
+ * included. Staged in a throwaway index so the worktree's own index is left alone.
+ */
+async function snapshotWorktreeTree(worktreePath: string, head: string): Promise<string> {
+  const tmpIndex = path.join(os.tmpdir(), `parallel-code-snapshot-${randomUUID()}.index`);
+  const indexEnv = { ...process.env, GIT_INDEX_FILE: tmpIndex };
+  try {
+    await exec('git', ['read-tree', head], { cwd: worktreePath, env: indexEnv });
+    await exec('git', ['add', '-A'], { cwd: worktreePath, env: indexEnv });
+    const { stdout } = await exec('git', ['write-tree'], { cwd: worktreePath, env: indexEnv });
+    return stdout.trim();
+  } finally {
+    fs.rmSync(tmpIndex, { force: true });
+  }
+}
+
+/**
+ * Everything a task changed since it forked from main: commits, uncommitted
+ * edits and untracked files, as one diff (e.g. for a reviewer).
+ */
+export async function getTaskReviewDiff(worktreePath: string): Promise<string> {
+  const mainBranch = await detectMainBranch(worktreePath);
+  const { stdout: base } = await exec('git', ['merge-base', mainBranch, 'HEAD'], {
+    cwd: worktreePath,
+  });
+  const { stdout: head } = await exec('git', ['rev-parse', 'HEAD'], { cwd: worktreePath });
+  const tree = await snapshotWorktreeTree(worktreePath, head.trim());
+  const { stdout } = await exec('git', ['diff', '--no-color', base.trim(), tree], {
+    cwd: worktreePath,
+    maxBuffer: MAX_BUFFER,
+  });
+  return stdout;
+}
+
+/**
+ * Branch `newBranch` off a task's current state, uncommitted and untracked
+ * changes included, and check it out in a new worktree. The changes are
+ * carried over in a temporary commit that is reset away in the fork, so both
+ * branches start at the same commit with the same working tree.
+ */
+export async function forkTaskBranch(
+  projectRoot: string,
+  sourceWorktree: string,
+  newBranch: string,
+): Promise<{ path: string; branch: string; uncommitted_changes: boolean }> {
+  const lockKey = await detectRepoLockKey(projectRoot).catch(() => projectRoot);
+  return withWorktreeLock(lockKey, async () => {
+    if (await branchExists(projectRoot, newBranch)) {
+      throw new Error(`Branch ${newBranch} already exists`);
+    }
+    const { stdout: headOut } = await exec('git', ['rev-parse', 'HEAD'], { cwd: sourceWorktree });
+    const head = headOut.trim();
+    const tree = await snapshotWorktreeTree(sourceWorktree, head);
+    const { stdout: headTree } = await exec('git', ['rev-parse', `${head}^{tree}`], {
+      cwd: sourceWorktree,
+    });
+    const dirty = tree !== headTree.trim();
+
+    let start = head;
+    if (dirty) {
+      const { stdout } = await exec(
+        'git',
+        ['commit-tree', tree, '-p', head, '-m', `Fork snapshot of ${head}`],
+        {
+          cwd: sourceWorktree,
+          // Never kept, so it doesn't need the user's identity (which may be unset)
+          env: {
+            ...process.env,
+            GIT_AUTHOR_NAME: 'Parallel Code',
+            GIT_AUTHOR_EMAIL: 'parallel-code@localhost',
+            GIT_COMMITTER_NAME: 'Parallel Code',
+            GIT_COMMITTER_EMAIL: 'parallel-code@localhost',
+          },
+        },
+      );
+      start = stdout.trim();
+    }
+
+    await exec('git', ['branch', newBranch, start], { cwd: projectRoot });
+    const worktree = await createWorktree(
+      projectRoot,
+      newBranch,
+      symlinkedDirs(projectRoot, sourceWorktree),
+    );
+    if (dirty) await exec('git', ['reset', '-q', head], { cwd: worktree.path });
+    return { ...worktree, uncommitted_changes: dirty };
+  });
+}
@@ -0,0 +1,108 @@
+import fs from 'fs';
+import path from 'path';
+import { PLAN_PATTERNS } from './archive.js';
+import { expandCopyPatterns, getChangedFiles } from './git.js';
+import { stripAnsi } from './idle.js';
+import { getAgentMeta, getAgentScrollback, killAgent } from './pty.js';
+import { makeScratchDir } from './review.js';
+
+// Handing a task from one agent to another: what the outgoing agent leaves
+// behind (plan files, the end of its terminal output, the task's changes) is
+// written to HANDOFF.md in the worktree, the agent is stopped, and the
+// renderer starts the incoming agent with a prompt pointing at the file.
+
+const TERMINAL_TAIL_LINES = 120;
+const MAX_PLAN_CHARS = 20_000;
+
+export interface HandoffResult {
+  /** First prompt for the incoming agent. */
+  prompt: string;
+  /** HANDOFF.md, relative to the worktree. */
+  file: string;
+}
+
+function terminalTail(agentId: string): string {
+  const scrollback = getAgentScrollback(agentId);
+  if (!scrollback) return '';
+  return stripAnsi(Buffer.from(scrollback, 'base64').toString('utf8'))
+    .split(/\r?\n|\r/)
+    .map((l) => l.trimEnd())
+    .filter(Boolean)
+    .slice(-TERMINAL_TAIL_LINES)
+    .join('\n');
+}
+
+async function planSection(worktreePath: string): Promise<string> {
+  const files = await expandCopyPatterns(worktreePath, PLAN_PATTERNS).catch(() => []);
+  return files
+    .map((rel) => {
+      const text = fs.readFileSync(path.join(worktreePath, rel), 'utf8');
+      const clipped =
+        text.length > MAX_PLAN_CHARS ? text.slice(0, MAX_PLAN_CHARS) + '\n[…truncated]' : text;
+      return `### ${rel}\n\n${clipped.trim()}`;
+    })
+    .join('\n\n');
+}
+
+async function changesSection(worktreePath: string): Promise<string> {
+  const files = await getChangedFiles(worktreePath).catch(() => []);
+  return files
+    .map(
+      (f) =>
+        `- ${f.status} ${f.path} (+${f.lines_added} -${f.lines_removed}` +
+        `${f.committed ? '' : ', uncommitted'})`,
+    )
+    .join('\n');
+}
+
+/**
+ * Package the context of `fromAgentId` for `toAgentName` and stop the
+ * outgoing agent. Spawning the incoming one is up to the caller.
+ */
+export async function handoffTask(params: {
+  taskId: string;
+  fromAgentId: string;
+  fromAgentName: string;
+  toAgentName: string;
+  worktreePath: string;
+}): Promise<HandoffResult> {
+  const meta = getAgentMeta(params.fromAgentId);
+  if (meta && meta.taskId !== params.taskId) {
+    throw new Error('The agent does not belong to this task');
+  }
+
+  const [plans, changes] = await Promise.all([
+    planSection(params.worktreePath),
+    changesSection(params.worktreePath),
+  ]);
+  const tail = terminalTail(params.fromAgentId);
+  const doc = [
+    `# Handoff from ${params.fromAgentName} to ${params.toAgentName}`,
+    '',
+    `You are taking over this task from ${params.fromAgentName}. Below is what it`,
+    'left behind. Check the current state of the files before relying on it.',
+    '',
+    '## Plan',
+    '',
+    plans || 'No plan files were found in the worktree.',
+    '',
+    '## Changes so far',
+    '',
+    changes || 'No changes against the base branch yet.',
+    '',
+    `## End of ${params.fromAgentName}'s terminal`,
+    '',
+    tail ? '```\n' + tail + '\n```' : 'The terminal output is no longer available.',
+    '',
+  ].join('\n');
+
+  const dir = makeScratchDir(params.worktreePath, 'handoff');
+  fs.writeFileSync(path.join(dir, 'HANDOFF.md'), doc, 'utf8');
+  killAgent(params.fromAgentId, { graceful: true });
+
+  const file = path.join(path.relative(params.worktreePath, dir), 'HANDOFF.md');
+  return {
+    prompt: `Read ${file}, then continue the task where ${params.fromAgentName} left off.`,
+    file,
+  };
+}
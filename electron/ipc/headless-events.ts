@@ -0,0 +1,175 @@
+// Decoders for the JSON event streams agent CLIs print in non-interactive
+// mode. Each turns one parsed line into zero or more AgentEvents; fields the
+// app doesn't use are dropped, unknown event types are ignored.
+
+export type AgentEvent =
+  | { kind: 'message'; text: string }
+  | { kind: 'tool_call'; id: string | null; name: string; input: unknown }
+  | { kind: 'tool_result'; id: string | null; output: string; is_error: boolean }
+  | { kind: 'file_edit'; path: string; tool: string }
+  | { kind: 'completion'; success: boolean; result: string | null; cost_usd: number | null }
+  | { kind: 'error'; message: string };
+
+type Json = Record<string, unknown>;
+export type EventDecoder = (line: Json) => AgentEvent[];
+
+/** Claude Code tools whose `file_path` input is the file they change. */
+const CLAUDE_EDIT_TOOLS = new Set(['Edit', 'MultiEdit', 'Write', 'NotebookEdit']);
+
+const str = (v: unknown): string | null => (typeof v === 'string' ? v : null);
+
+/** Tool output can be a string or a list of content blocks. */
+function contentText(v: unknown): string {
+  if (typeof v === 'string') return v;
+  if (!Array.isArray(v)) return v === undefined ? '' : JSON.stringify(v);
+  return v.map((block) => str((block as Json)?.text) ?? '').join('');
+}
+
+/** `claude -p --output-format stream-json --verbose` */
+const decodeClaude: EventDecoder = (line) => {
+  const content = ((line.message as Json | undefined)?.content ?? []) as Json[];
+  switch (line.type) {
+    case 'assistant':
+      return content.flatMap((block): AgentEvent[] => {
+        if (block.type === 'text' && str(block.text)) {
+          return [{ kind: 'message', text: block.text as string }];
+        }
+        if (block.type !== 'tool_use') return [];
+        const name = str(block.name) ?? 'unknown';
+        const call: AgentEvent = { kind: 'tool_call', id: str(block.id), name, input: block.input };
+        const filePath = str((block.input as Json | undefined)?.file_path);
+        if (!CLAUDE_EDIT_TOOLS.has(name) || !filePath) return [call];
+        return [call, { kind: 'file_edit', path: filePath, tool: name }];
+      });
+    case 'user':
+      return content
+        .filter((block) => block.type === 'tool_result')
+        .map((block): AgentEvent => ({
+          kind: 'tool_result',
+          id: str(block.tool_use_id),
+          output: contentText(block.content),
+          is_error: block.is_error === true,
+        }));
+    case 'result':
+      return [
+        {
+          kind: 'completion',
+          success: line.is_error !== true && line.subtype === 'success',
+          result: str(line.result),
+          cost_usd: typeof line.total_cost_usd === 'number' ? line.total_cost_usd : null,
+        },
+      ];
+    default:
+      return [];
+  }
+};
+
+/** `codex exec --json` */
+const decodeCodex: EventDecoder = (line) => {
+  const item = (line.item ?? {}) as Json;
+  switch (line.type) {
+    case 'item.started':
+      if (item.type !== 'command_execution') return [];
+      return [{ kind: 'tool_call', id: str(item.id), name: 'shell', input: item.command }];
+    case 'item.completed':
+      switch (item.type) {
+        case 'agent_message':
+          return str(item.text) ? [{ kind: 'message', text: item.text as string }] : [];
+        case 'command_execution':
+          return [
+            {
+              kind: 'tool_result',
+              id: str(item.id),
+              output: str(item.aggregated_output) ?? '',
+              is_error: typeof item.exit_code === 'number' && item.exit_code !== 0,
+            },
+          ];
+        case 'file_change':
+          return ((item.changes ?? []) as Json[])
+            .map((change) => str(change.path))
+            .filter((path): path is string => path !== null)
+            .map((path): AgentEvent => ({ kind: 'file_edit', path, tool: 'patch' }));
+        default:
+          return [];
+      }
+    case 'turn.completed':
+      return [{ kind: 'completion', success: true, result: null, cost_usd: null }];
+    case 'turn.failed':
+      return [
+        {
+          kind: 'completion',
+          success: false,
+          result: str((line.error as Json | undefined)?.message),
+          cost_usd: null,
+        },
+      ];
+    case 'error':
+      return [{ kind: 'error', message: str(line.message) ?? 'Unknown error' }];
+    default:
+      return [];
+  }
+};
+
+/** Gemini CLI tools that write the file named by `file_path`. */
+const GEMINI_EDIT_TOOLS = new Set(['replace', 'write_file']);
+
+/** `gemini -p --output-format stream-json` */
+const decodeGemini: EventDecoder = (line) => {
+  switch (line.type) {
+    case 'message':
+      if (line.role !== 'assistant' || !str(line.content)) return [];
+      return [{ kind: 'message', text: line.content as string }];
+    case 'tool_use': {
+      const name = str(line.tool_name) ?? 'unknown';
+      const call: AgentEvent = {
+        kind: 'tool_call',
+        id: str(line.tool_id),
+        name,
+        input: line.parameters,
+      };
+      const filePath = str((line.parameters as Json | undefined)?.file_path);
+      if (!GEMINI_EDIT_TOOLS.has(name) || !filePath) return [call];
+      return [call, { kind: 'file_edit', path: filePath, tool: name }];
+    }
+    case 'tool_result':
+      return [
+        {
+          kind: 'tool_result',
+          id: str(line.tool_id),
+          output: contentText(line.output),
+          is_error: line.status === 'error',
+        },
+      ];
+    case 'result':
+      return [
+        { kind: 'completion', success: line.status === 'success', result: null, cost_usd: null },
+      ];
+    case 'error':
+      return [{ kind: 'error', message: str(line.message) ?? 'Unknown error' }];
+    default:
+      return [];
+  }
+};
+
+interface HeadlessMode {
+  /**
+   * Arguments for a one-shot run of `prompt` that streams JSON lines. The
+   * prompt must not be readable as an option, even when it starts with `-`.
+   */
+  args: (prompt: string) => string[];
+  decode: EventDecoder;
+}
+
+/** Agents that can run without a terminal, by agent id. */
+export const HEADLESS_MODES: Record<string, HeadlessMode> = {
+  'claude-code': {
+    args: (p) => ['-p', '--output-format', 'stream-json', '--verbose', '--', p],
+    decode: decodeClaude,
+  },
+  codex: { args: (p) => ['exec', '--json', '--', p], decode: decodeCodex },
+  // gemini takes the prompt as the value of --prompt; the joined form keeps it a value
+  gemini: {
+    args: (p) => [`--prompt=${p}`, '--output-format', 'stream-json'],
+    decode: decodeGemini,
+  },
+};
@@ -0,0 +1,135 @@
+import { spawn, type ChildProcess } from 'child_process';
+import { randomUUID } from 'crypto';
+import type { BrowserWindow } from 'electron';
+import { listAgents } from './agents.js';
+import { IPC } from './channels.js';
+import { HEADLESS_MODES, type AgentEvent } from './headless-events.js';
+import { buildSpawnEnv, resolveCommand } from './pty.js';
+
+// Agent runs without a terminal: the CLI's non-interactive mode is started
+// with JSON output, and each decoded event is sent to the renderer as an
+// `agent-event`. Every run ends with exactly one `completion` event.
+
+export type AgentEventMessage = AgentEvent & { run_id: string; task_id: string; at: number };
+
+interface HeadlessRun {
+  taskId: string;
+  child: ChildProcess;
+  completed: boolean;
+  cancelled: boolean;
+}
+
+const STDERR_TAIL_CHARS = 2000;
+
+const runs = new Map<string, HeadlessRun>();
+let mainWindow: BrowserWindow | null = null;
+const listeners = new Set<(event: AgentEventMessage) => void>();
+
+export function initHeadlessRuns(win: BrowserWindow): void {
+  mainWindow = win;
+}
+
+/** Follow events of every headless run, e.g. to collect one run's result. */
+export function onAgentEvent(listener: (event: AgentEventMessage) => void): () => void {
+  listeners.add(listener);
+  return () => listeners.delete(listener);
+}
+
+function emit(runId: string, run: HeadlessRun, event: AgentEvent): void {
+  if (run.completed) return;
+  if (event.kind === 'completion') run.completed = true;
+  const message: AgentEventMessage = {
+    ...event,
+    run_id: runId,
+    task_id: run.taskId,
+    at: Date.now(),
+  };
+  if (mainWindow && !mainWindow.isDestroyed()) {
+    mainWindow.webContents.send(IPC.AgentEvent, message);
+  }
+  for (const listener of listeners) listener(message);
+}
+
+export interface HeadlessRunParams {
+  taskId: string;
+  /** Agent definition id, e.g. `claude-code`. */
+  agentId: string;
+  prompt: string;
+  cwd: string;
+  env?: Record<string, string>;
+  /** Resolved environment profile, see shell-env.ts. */
+  profileEnv?: Record<string, string>;
+}
+
+/** Start `prompt` on a task's worktree without a terminal. Returns the run id. */
+export function runAgentHeadless(params: HeadlessRunParams): { run_id: string } {
+  const agent = listAgents().find((a) => a.id === params.agentId);
+  if (!agent) throw new Error(`Agent not found: ${params.agentId}`);
+  const mode = HEADLESS_MODES[agent.id];
+  if (!mode) throw new Error(`${agent.name} has no headless mode`);
+
+  const env = buildSpawnEnv({ ...agent.env, ...params.env }, params.profileEnv);
+  const child = spawn(resolveCommand(agent.command, env.PATH), mode.args(params.prompt), {
+    cwd: params.cwd,
+    env,
+    stdio: ['ignore', 'pipe', 'pipe'],
+  });
+  const runId = randomUUID();
+  const run: HeadlessRun = { taskId: params.taskId, child, completed: false, cancelled: false };
+  runs.set(runId, run);
+
+  const handleLine = (line: string) => {
+    if (!line.trim()) return;
+    let parsed: unknown;
+    try {
+      parsed = JSON.parse(line);
+    } catch {
+      return; // progress text some CLIs mix into stdout
+    }
+    if (parsed && typeof parsed === 'object') {
+      for (const event of mode.decode(parsed as Record<string, unknown>)) emit(runId, run, event);
+    }
+  };
+  let buffer = '';
+  child.stdout?.setEncoding('utf8');
+  child.stdout?.on('data', (chunk: string) => {
+    const lines = (buffer + chunk).split('\n');
+    buffer = lines.pop() ?? '';
+    lines.forEach(handleLine);
+  });
+  let stderr = '';
+  child.stderr?.setEncoding('utf8');
+  child.stderr?.on('data', (chunk: string) => {
+    stderr = (stderr + chunk).slice(-STDERR_TAIL_CHARS);
+  });
+
+  child.on('error', (err) => {
+    emit(runId, run, { kind: 'error', message: err.message });
+  });
+  child.on('close', (code) => {
+    runs.delete(runId);
+    // The last event may not end with a newline
+    handleLine(buffer);
+    buffer = '';
+    if (code !== 0 && !run.cancelled && stderr.trim()) {
+      emit(runId, run, { kind: 'error', message: stderr.trim() });
+    }
+    const result = run.cancelled ? 'Cancelled' : null;
+    emit(runId, run, { kind: 'completion', success: code === 0, result, cost_usd: null });
+  });
+  return { run_id: runId };
+}
+
+export function cancelHeadlessRun(runId: string): void {
+  const run = runs.get(runId);
+  if (!run) return;
+  run.cancelled = true;
+  run.child.kill('SIGTERM');
+}
+
+/** Stop a task's headless runs, e.g. when the task is deleted. */
+export function cancelTaskHeadlessRuns(taskId: string): void {
+  for (const [runId, run] of runs) {
+    if (run.taskId === taskId) cancelHeadlessRun(runId);
+  }
+}
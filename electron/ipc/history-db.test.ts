@@ -0,0 +1,192 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import type { TaskMetrics } from './task-metrics.js';
+
+let stateDir = '';
+vi.mock('electron', () => ({ app: { getPath: () => stateDir, isPackaged: true } }));
+
+// Only the lifecycle listeners matter; the tests fire the events themselves
+const ptyListeners = new Map<string, (agentId: string, data?: unknown) => void>();
+vi.mock('./pty.js', () => ({
+  onPtyEvent: (event: string, listener: (agentId: string, data?: unknown) => void) => {
+    ptyListeners.set(event, listener);
+    return () => {};
+  },
+}));
+
+let metrics: TaskMetrics[] = [];
+vi.mock('./task-metrics.js', () => ({
+  getMetricsReport: () => ({ tasks: metrics }),
+  getTaskMetrics: (taskId: string) => metrics.find((m) => m.task_id === taskId),
+}));
+
+// node:sqlite ships with Node 22.5 and later; without it the history is disabled
+const sqlite = await import('node:sqlite').catch(() => null);
+
+type HistoryModule = typeof import('./history-db.js');
+
+/** Open history.db in a fresh module, as on app start. */
+async function openHistory(): Promise<HistoryModule> {
+  vi.resetModules();
+  const history = await import('./history-db.js');
+  await history.initHistoryDb();
+  return history;
+}
+
+/** A separate connection to history.db. */
+function openDatabase() {
+  if (!sqlite) throw new Error('SQLite is unavailable');
+  return new sqlite.DatabaseSync(path.join(stateDir, 'history.db'));
+}
+
+function query(sql: string): Array<Record<string, unknown>> {
+  const database = openDatabase();
+  try {
+    return database.prepare(sql).all();
+  } finally {
+    database.close();
+  }
+}
+
+const task = (id: string, name: string) => ({
+  id,
+  name,
+  projectId: 'project-1',
+  branchName: `task/${id}`,
+});
+
+const stateJson = (...tasks: Array<{ id: string }>) => {
+  const byId = Object.fromEntries(tasks.map((t) => [t.id, t]));
+  return JSON.stringify({ projects: [], taskOrder: [], tasks: byId });
+};
+
+const taskMetrics = (taskId: string, costUsd: number): TaskMetrics => ({
+  task_id: taskId,
+  runtime_ms: 5000,
+  sessions: 1,
+  running: false,
+  input_tokens: 100,
+  output_tokens: 20,
+  cost_usd: costUsd,
+});
+
+beforeEach(() => {
+  stateDir = fs.mkdtempSync(path.join(os.tmpdir(), 'history-db-'));
+  ptyListeners.clear();
+  metrics = [];
+});
+
+// ---------------------------------------------------------------------------
+// Migrations
+// ---------------------------------------------------------------------------
+describe.skipIf(!sqlite)('history.db migrations', () => {
+  it('imports state.json, recordings and metrics into a new database', async () => {
+    fs.writeFileSync(
+      path.join(stateDir, 'state.json'),
+      stateJson(task('t1', 'Fix login'), { id: 'unnamed' }),
+    );
+    const recordings = path.join(stateDir, 'recordings', 't1');
+    fs.mkdirSync(recordings, { recursive: true });
+    fs.writeFileSync(path.join(recordings, 'agent-1-1700000000000.cast'), 'cast\n');
+    fs.writeFileSync(path.join(recordings, 'notes.txt'), 'not a recording\n');
+    metrics = [taskMetrics('t1', 0.5)];
+
+    const history = await openHistory();
+    expect(history.queryTaskHistory()).toEqual([
+      expect.objectContaining({
+        id: 't1',
+        name: 'Fix login',
+        project_id: 'project-1',
+        branch_name: 'task/t1',
+        closed_at: null,
+        sessions: 0,
+      }),
+    ]);
+    expect(query('PRAGMA user_version')).toEqual([{ user_version: 1 }]);
+    expect(query('SELECT task_id, agent_id, started_at, size FROM recordings')).toEqual([
+      { task_id: 't1', agent_id: 'agent-1', started_at: 1700000000000, size: 5 },
+    ]);
+    expect(query('SELECT task_id, runtime_ms, cost_usd FROM metrics')).toEqual([
+      { task_id: 't1', runtime_ms: 5000, cost_usd: 0.5 },
+    ]);
+  });
+
+  it('imports only once', async () => {
+    const statePath = path.join(stateDir, 'state.json');
+    fs.writeFileSync(statePath, stateJson(task('t1', 'Fix login')));
+    metrics = [taskMetrics('t1', 0.5)];
+    await openHistory();
+
+    fs.writeFileSync(statePath, stateJson(task('t1', 'Fix login'), task('t2', 'Add search')));
+    const history = await openHistory();
+    expect(history.queryTaskHistory().map((t) => t.id)).toEqual(['t1']);
+    expect(query('SELECT task_id FROM metrics')).toEqual([{ task_id: 't1' }]);
+  });
+
+  it('leaves a database from a newer version untouched', async () => {
+    const database = openDatabase();
+    database.exec('CREATE TABLE future (x INTEGER); PRAGMA user_version = 5;');
+    database.close();
+    fs.writeFileSync(path.join(stateDir, 'state.json'), stateJson(task('t1', 'Fix login')));
+
+    const history = await openHistory();
+    expect(query('PRAGMA user_version')).toEqual([{ user_version: 5 }]);
+    expect(query("SELECT name FROM sqlite_master WHERE type = 'table'")).toEqual([
+      { name: 'future' },
+    ]);
+    expect(() => history.queryTaskHistory()).toThrow(/unavailable/);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Task history
+// ---------------------------------------------------------------------------
+describe.skipIf(!sqlite)('task history', () => {
+  it('follows saved states and closes tasks that were removed', async () => {
+    fs.writeFileSync(
+      path.join(stateDir, 'state.json'),
+      stateJson(task('t1', 'Fix login'), task('t2', 'Add search')),
+    );
+    const history = await openHistory();
+
+    history.syncTaskHistory(stateJson(task('t1', 'Fix login flow')));
+    expect(history.queryTaskHistory().map((t) => [t.id, t.name])).toEqual([
+      ['t1', 'Fix login flow'],
+    ]);
+    const closed = history.queryTaskHistory({ include_closed: true }).find((t) => t.id === 't2');
+    expect(closed?.closed_at).toEqual(expect.any(Number));
+
+    history.syncTaskHistory(stateJson(task('t1', 'Fix login flow'), task('t2', 'Add search')));
+    expect(history.queryTaskHistory().map((t) => t.id).sort()).toEqual(['t1', 't2']);
+  });
+
+  it('matches search wildcards literally', async () => {
+    fs.writeFileSync(
+      path.join(stateDir, 'state.json'),
+      stateJson(task('t1', 'Reach 100% coverage'), task('t2', 'Reach 1000 users')),
+    );
+    const history = await openHistory();
+    expect(history.queryTaskHistory({ search: '100%' }).map((t) => t.id)).toEqual(['t1']);
+    expect(history.queryTaskHistory({ search: 'task/t' })).toHaveLength(2);
+    expect(history.queryTaskHistory({ search: 't_' })).toEqual([]);
+  });
+
+  it('records sessions and their metrics from PTY events', async () => {
+    fs.writeFileSync(path.join(stateDir, 'state.json'), stateJson(task('t1', 'Fix login')));
+    const history = await openHistory();
+    metrics = [taskMetrics('t1', 1.25)];
+
+    const session = { taskId: 't1', queueable: true };
+    ptyListeners.get('spawn')?.('agent-1', session);
+    ptyListeners.get('exit')?.('agent-1', { ...session, exitCode: 0, exitReason: 'exited' });
+
+    expect(history.listTaskSessions('t1')).toEqual([
+      expect.objectContaining({ agent_id: 'agent-1', kind: 'agent', exit_code: 0 }),
+    ]);
+    expect(history.listTaskSessions('t1')[0].ended_at).toEqual(expect.any(Number));
+    expect(history.queryTaskHistory()[0].sessions).toBe(1);
+    expect(query('SELECT cost_usd FROM metrics')).toEqual([{ cost_usd: 1.25 }]);
+  });
+});
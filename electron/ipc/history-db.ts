@@ -0,0 +1,353 @@
+import fs from 'fs';
+import path from 'path';
+import type { DatabaseSync } from 'node:sqlite';
+import { getStateDir, loadAppState } from './persistence.js';
+import { onPtyEvent, type PtyEventSession } from './pty.js';
+import { getMetricsReport, getTaskMetrics, type TaskMetrics } from './task-metrics.js';
+
+// Task history in an embedded SQLite database (history.db). state.json stays
+// the renderer's snapshot of what is open; this keeps what happened over time:
+// every task ever seen, each agent or shell session, recordings and metric
+// snapshots. Rows change one at a time instead of the whole file being
+// rewritten on each save.
+
+/**
+ * `MIGRATIONS[n]` moves a version-n database to version n + 1; the version is
+ * kept in `PRAGMA user_version`.
+ */
+const MIGRATIONS: Array<(db: DatabaseSync) => void> = [
+  (db) => {
+    db.exec(`
+      CREATE TABLE tasks (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        project_id TEXT,
+        branch_name TEXT,
+        worktree_path TEXT,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        closed_at INTEGER,
+        data TEXT NOT NULL
+      );
+      CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        task_id TEXT NOT NULL,
+        agent_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER,
+        exit_code INTEGER,
+        exit_reason TEXT
+      );
+      CREATE INDEX sessions_task ON sessions (task_id, started_at);
+      CREATE TABLE recordings (
+        path TEXT PRIMARY KEY,
+        task_id TEXT NOT NULL,
+        agent_id TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER,
+        size INTEGER
+      );
+      CREATE INDEX recordings_task ON recordings (task_id);
+      CREATE TABLE metrics (
+        task_id TEXT NOT NULL,
+        recorded_at INTEGER NOT NULL,
+        runtime_ms INTEGER NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        cost_usd REAL
+      );
+      CREATE INDEX metrics_task ON metrics (task_id, recorded_at);
+    `);
+    importExistingData(db);
+  },
+];
+
+let db: DatabaseSync | null = null;
+/** Serialized task rows as last written, to skip unchanged tasks on save. */
+const syncedTasks = new Map<string, string>();
+/** Open session row per running agent. */
+const liveSessions = new Map<string, number | bigint>();
+
+interface PersistedTaskLike {
+  id: string;
+  name: string;
+  projectId?: string;
+  branchName?: string;
+  worktreePath?: string;
+}
+
+function parseStateTasks(json: string): PersistedTaskLike[] {
+  const state = JSON.parse(json) as { tasks?: Record<string, PersistedTaskLike> };
+  return Object.values(state.tasks ?? {}).filter(
+    (t) => typeof t?.id === 'string' && typeof t.name === 'string',
+  );
+}
+
+function writeTask(
+  database: DatabaseSync,
+  task: PersistedTaskLike,
+  data: string,
+  now: number,
+): void {
+  database
+    .prepare(
+      `INSERT INTO tasks
+         (id, name, project_id, branch_name, worktree_path, created_at, updated_at, data)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+       ON CONFLICT (id) DO UPDATE SET name = excluded.name, project_id = excluded.project_id,
+         branch_name = excluded.branch_name, worktree_path = excluded.worktree_path,
+         updated_at = excluded.updated_at, closed_at = NULL, data = excluded.data`,
+    )
+    .run(
+      task.id,
+      task.name,
+      task.projectId ?? null,
+      task.branchName ?? null,
+      task.worktreePath ?? null,
+      now,
+      now,
+      data,
+    );
+}
+
+function insertMetrics(database: DatabaseSync, m: TaskMetrics, now: number): void {
+  database
+    .prepare(
+      `INSERT INTO metrics (task_id, recorded_at, runtime_ms, input_tokens, output_tokens, cost_usd)
+       VALUES (?, ?, ?, ?, ?, ?)`,
+    )
+    .run(m.task_id, now, m.runtime_ms, m.input_tokens, m.output_tokens, m.cost_usd);
+}
+
+/** Seed a new database from state.json, the recordings directory and task-metrics.json. */
+function importExistingData(database: DatabaseSync): void {
+  const now = Date.now();
+  try {
+    const json = loadAppState();
+    for (const task of json ? parseStateTasks(json) : []) {
+      writeTask(database, task, JSON.stringify(task), now);
+    }
+  } catch (e) {
+    console.warn('Could not import tasks from state.json:', e);
+  }
+
+  const recordingsDir = path.join(getStateDir(), 'recordings');
+  try {
+    const taskDirs = fs.existsSync(recordingsDir) ? fs.readdirSync(recordingsDir) : [];
+    for (const taskId of taskDirs) {
+      const dir = path.join(recordingsDir, taskId);
+      for (const name of fs.statSync(dir).isDirectory() ? fs.readdirSync(dir) : []) {
+        // Files are named `<agentId>-<startedAt>.cast`
+        const match = name.match(/^(.+)-(\d+)\.cast$/);
+        if (!match) continue;
+        const file = path.join(dir, name);
+        const stat = fs.statSync(file);
+        database
+          .prepare(
+            `INSERT OR IGNORE INTO recordings (path, task_id, agent_id, started_at, ended_at, size)
+             VALUES (?, ?, ?, ?, ?, ?)`,
+          )
+          .run(file, taskId, match[1], Number(match[2]), stat.mtimeMs, stat.size);
+      }
+    }
+  } catch (e) {
+    console.warn('Could not import recordings:', e);
+  }
+
+  for (const m of getMetricsReport().tasks) insertMetrics(database, m, now);
+}
+
+function transaction(database: DatabaseSync, fn: () => void): void {
+  database.exec('BEGIN');
+  try {
+    fn();
+    database.exec('COMMIT');
+  } catch (e) {
+    database.exec('ROLLBACK');
+    throw e;
+  }
+}
+
+function migrateDatabase(database: DatabaseSync): void {
+  const row = database.prepare('PRAGMA user_version').get() as { user_version: number };
+  const from = row.user_version;
+  if (from > MIGRATIONS.length) {
+    // A newer app's database: read what we understand, add nothing it doesn't expect
+    console.warn(`history.db is version ${from}, newer than this app knows`);
+    return;
+  }
+  for (let version = from; version < MIGRATIONS.length; version++) {
+    transaction(database, () => {
+      MIGRATIONS[version](database);
+      database.exec(`PRAGMA user_version = ${version + 1}`);
+    });
+  }
+}
+
+/** Record sessions and their metrics as agents start and stop. */
+function watchSessions(database: DatabaseSync): void {
+  onPtyEvent('spawn', (agentId, data) => {
+    const { taskId, queueable } = data as PtyEventSession;
+    const result = database
+      .prepare('INSERT INTO sessions (task_id, agent_id, kind, started_at) VALUES (?, ?, ?, ?)')
+      .run(taskId, agentId, queueable ? 'agent' : 'shell', Date.now());
+    liveSessions.set(agentId, result.lastInsertRowid);
+  });
+  onPtyEvent('exit', (agentId, data) => {
+    const d = data as PtyEventSession & { exitCode: number | null; exitReason: string };
+    const id = liveSessions.get(agentId);
+    liveSessions.delete(agentId);
+    const now = Date.now();
+    if (id !== undefined) {
+      database
+        .prepare('UPDATE sessions SET ended_at = ?, exit_code = ?, exit_reason = ? WHERE id = ?')
+        .run(now, d.exitCode, d.exitReason, id);
+    }
+    // Registered after the task metrics, so this session's runtime is included
+    if (d.queueable) insertMetrics(database, getTaskMetrics(d.taskId), now);
+  });
+}
+
+/**
+ * Open history.db, creating or migrating it. Without SQLite support in the
+ * runtime the history is simply not kept.
+ */
+export async function initHistoryDb(): Promise<void> {
+  let sqlite: { DatabaseSync: typeof DatabaseSync };
+  try {
+    sqlite = await import('node:sqlite');
+  } catch (e) {
+    console.warn('SQLite is unavailable, task history is disabled:', e);
+    return;
+  }
+  try {
+    fs.mkdirSync(getStateDir(), { recursive: true });
+    const database = new sqlite.DatabaseSync(path.join(getStateDir(), 'history.db'));
+    database.exec('PRAGMA journal_mode = WAL');
+    migrateDatabase(database);
+    const rows = database.prepare('SELECT id, data FROM tasks WHERE closed_at IS NULL').all();
+    for (const row of rows as Array<{ id: string; data: string }>) {
+      syncedTasks.set(row.id, row.data);
+    }
+    watchSessions(database);
+    db = database;
+  } catch (e) {
+    console.warn('Could not open history.db, task history is disabled:', e);
+  }
+}
+
+/**
+ * Bring the tasks table in line with a saved state: changed tasks are
+ * updated, tasks no longer in the state are marked closed.
+ */
+export function syncTaskHistory(json: string): void {
+  const database = db;
+  if (!database) return;
+  const now = Date.now();
+  const tasks = parseStateTasks(json);
+  const openIds = new Set(tasks.map((t) => t.id));
+  transaction(database, () => {
+    for (const task of tasks) {
+      const data = JSON.stringify(task);
+      if (syncedTasks.get(task.id) === data) continue;
+      writeTask(database, task, data, now);
+      syncedTasks.set(task.id, data);
+    }
+    for (const id of syncedTasks.keys()) {
+      if (openIds.has(id)) continue;
+      database.prepare('UPDATE tasks SET closed_at = ? WHERE id = ?').run(now, id);
+      syncedTasks.delete(id);
+    }
+  });
+}
+
+export function recordRecordingStarted(file: string, taskId: string, agentId: string): void {
+  if (!db) return;
+  db.prepare(
+    'INSERT OR REPLACE INTO recordings (path, task_id, agent_id, started_at) VALUES (?, ?, ?, ?)',
+  ).run(file, taskId, agentId, Date.now());
+}
+
+export function recordRecordingEnded(file: string, size: number): void {
+  if (!db) return;
+  db.prepare('UPDATE recordings SET ended_at = ?, size = ? WHERE path = ?').run(
+    Date.now(),
+    size,
+    file,
+  );
+}
+
+export interface TaskHistoryFilter {
+  project_id?: string;
+  /** Matched against task names and branches. */
+  search?: string;
+  include_closed?: boolean;
+  limit?: number;
+}
+
+export interface TaskHistoryEntry {
+  id: string;
+  name: string;
+  project_id: string | null;
+  branch_name: string | null;
+  created_at: number;
+  updated_at: number;
+  closed_at: number | null;
+  sessions: number;
+  /** Total time of finished sessions. */
+  session_ms: number;
+  last_session_at: number | null;
+}
+
+export interface SessionRecord {
+  id: number;
+  task_id: string;
+  agent_id: string;
+  kind: 'agent' | 'shell';
+  started_at: number;
+  ended_at: number | null;
+  exit_code: number | null;
+  exit_reason: string | null;
+}
+
+function requireDb(): DatabaseSync {
+  if (!db) throw new Error('Task history is unavailable');
+  return db;
+}
+
+/** Tasks from the history, most recently updated first. */
+export function queryTaskHistory(filter: TaskHistoryFilter = {}): TaskHistoryEntry[] {
+  const where: string[] = [];
+  const params: Array<string | number> = [];
+  if (!filter.include_closed) where.push('t.closed_at IS NULL');
+  if (filter.project_id) {
+    where.push('t.project_id = ?');
+    params.push(filter.project_id);
+  }
+  if (filter.search) {
+    where.push("(t.name LIKE ? ESCAPE '\\' OR t.branch_name LIKE ? ESCAPE '\\')");
+    const pattern = `%${filter.search.replace(/[\\%_]/g, (c) => `\\${c}`)}%`;
+    params.push(pattern, pattern);
+  }
+  const limit = filter.limit;
+  params.push(limit !== undefined && Number.isInteger(limit) && limit > 0 ? limit : 200);
+  const sql = `
+    SELECT t.id, t.name, t.project_id, t.branch_name, t.created_at, t.updated_at, t.closed_at,
+      COUNT(s.id) AS sessions,
+      COALESCE(SUM(s.ended_at - s.started_at), 0) AS session_ms,
+      MAX(s.started_at) AS last_session_at
+    FROM tasks t LEFT JOIN sessions s ON s.task_id = t.id
+    ${where.length > 0 ? `WHERE ${where.join(' AND ')}` : ''}
+    GROUP BY t.id
+    ORDER BY t.updated_at DESC
+    LIMIT ?`;
+  return requireDb().prepare(sql).all(...params) as unknown as TaskHistoryEntry[];
+}
+
+/** Sessions of one task, newest first. */
+export function listTaskSessions(taskId: string): SessionRecord[] {
+  return requireDb()
+    .prepare('SELECT * FROM sessions WHERE task_id = ? ORDER BY started_at DESC')
+    .all(taskId) as unknown as SessionRecord[];
+}
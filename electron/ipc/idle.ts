@@ -0,0 +1,76 @@
+// Backend-side idle detection: flags agents that stopped producing output
+// while showing something that looks like an interactive prompt.
+
+export const DEFAULT_IDLE_MS = 5_000;
+const TAIL_CHARS = 2048;
+
+/** Strip ANSI escape sequences (CSI, OSC, and single-char escapes). */
+export function stripAnsi(text: string): string {
+  return text.replace(
+    // eslint-disable-next-line no-control-regex
+    /[\u001b\u009b][[()#;?]*(?:[0-9]{1,4}(?:;[0-9]{0,4})*)?[0-9A-ORZcf-nq-uy=><~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)?/g,
+    '',
+  );
+}
+
+/**
+ * Patterns tested against the last non-empty line of stripped output.
+ * Mirrors the frontend's prompt detection in src/store/taskStatus.ts.
+ */
+const PROMPT_PATTERNS: RegExp[] = [
+  /❯\s*$/, // Claude Code prompt
+  /›\s*$/, // Codex CLI prompt
+  /(?:^|\s)[$%#>]\s*$/, // shell prompts
+  /\[y\/n\]\s*$/i, // Y/n confirmation
+  /\(y\/n\)\s*$/i,
+  /\?\s*$/, // open question
+  /press enter/i,
+];
+
+/** Returns the last non-empty line if the output tail looks like a prompt. */
+export function detectPrompt(tail: string): string | null {
+  const lines = stripAnsi(tail)
+    .split(/\r?\n|\r/)
+    .map((l) => l.trimEnd())
+    .filter((l) => l.length > 0);
+  const last = lines[lines.length - 1];
+  if (!last) return null;
+  return PROMPT_PATTERNS.some((re) => re.test(last)) ? last : null;
+}
+
+export interface IdleTracker {
+  /** Record new output; resets the idle timer. */
+  record: (data: string) => void;
+  dispose: () => void;
+}
+
+/**
+ * Fire `onIdle` once per quiet period when no output arrived for `idleMs`
+ * and the tail matches a prompt. `idleMs <= 0` disables tracking.
+ */
+export function createIdleTracker(
+  idleMs: number,
+  onIdle: (lastLine: string) => void,
+): IdleTracker {
+  let tail = '';
+  let timer: ReturnType<typeof setTimeout> | null = null;
+
+  const check = () => {
+    timer = null;
+    const line = detectPrompt(tail);
+    if (line !== null) onIdle(line);
+  };
+
+  return {
+    record: (data) => {
+      if (idleMs <= 0) return;
+      tail = (tail + data).slice(-TAIL_CHARS);
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(check, idleMs);
+    },
+    dispose: () => {
+      if (timer) clearTimeout(timer);
+      timer = null;
+    },
+  };
+}
@@ -0,0 +1,198 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+import { getSecret, hasSecret, setSecret } from './secrets.js';
+import { getMainBranch, getRemoteUrl, pushTask } from './git.js';
+
+// Code-hosting integrations: open pull/merge requests for task branches on
+// GitHub or GitLab (including self-hosted instances), using tokens kept in
+// the secrets store.
+
+export type Provider = 'github' | 'gitlab';
+
+export interface RemoteRepo {
+  provider: Provider;
+  host: string;
+  /** "owner/repo", or "group/subgroup/repo" on GitLab. */
+  repo: string;
+}
+
+export interface PullRequestRef extends RemoteRepo {
+  number: number;
+  url: string;
+  branch: string;
+  base: string;
+}
+
+const API_TIMEOUT = 30_000; // ms
+
+// --- Remote detection ---
+
+/** Parse scp-style, ssh:// and https:// remote URLs. */
+export function parseRemoteUrl(url: string): RemoteRepo | null {
+  let host: string;
+  let repoPath: string;
+  const scp = /^(?:[^@/]+@)?([^:/]+):(?!\/)(.+)$/.exec(url);
+  if (scp && !url.includes('://')) {
+    host = scp[1];
+    repoPath = scp[2];
+  } else {
+    try {
+      const u = new URL(url);
+      host = u.hostname;
+      repoPath = u.pathname;
+    } catch {
+      return null;
+    }
+  }
+  const repo = repoPath.replace(/^\/+/, '').replace(/\/+$/, '').replace(/\.git$/, '');
+  if (!repo.includes('/')) return null;
+  if (host === 'github.com' || host.includes('github')) return { provider: 'github', host, repo };
+  if (host === 'gitlab.com' || host.includes('gitlab')) return { provider: 'gitlab', host, repo };
+  return null;
+}
+
+function tokenName(provider: Provider, host: string): string {
+  return `${provider}-token.${host}`;
+}
+
+export function setIntegrationToken(provider: Provider, host: string, token: string | null): void {
+  setSecret(tokenName(provider, host), token);
+}
+
+export function hasIntegrationToken(provider: Provider, host: string): boolean {
+  return hasSecret(tokenName(provider, host));
+}
+
+function requireToken(remote: RemoteRepo): string {
+  const token = getSecret(tokenName(remote.provider, remote.host));
+  if (!token) throw new Error(`No ${remote.provider} token configured for ${remote.host}`);
+  return token;
+}
+
+function apiBase(remote: RemoteRepo): string {
+  if (remote.provider === 'github') {
+    return remote.host === 'github.com'
+      ? 'https://api.github.com'
+      : `https://${remote.host}/api/v3`;
+  }
+  return `https://${remote.host}/api/v4`;
+}
+
+export async function apiRequest<T>(
+  remote: RemoteRepo,
+  method: 'GET' | 'POST',
+  endpoint: string,
+  body?: unknown,
+): Promise<T> {
+  const token = requireToken(remote);
+  const headers: Record<string, string> =
+    remote.provider === 'github'
+      ? {
+          Authorization: `Bearer ${token}`,
+          Accept: 'application/vnd.github+json',
+          'User-Agent': 'parallel-code',
+        }
+      : { 'PRIVATE-TOKEN': token };
+  if (body !== undefined) headers['Content-Type'] = 'application/json';
+
+  const res = await fetch(`${apiBase(remote)}${endpoint}`, {
+    method,
+    headers,
+    body: body === undefined ? undefined : JSON.stringify(body),
+    signal: AbortSignal.timeout(API_TIMEOUT),
+  });
+  const text = await res.text();
+  if (!res.ok) {
+    let detail = text;
+    try {
+      const parsed = JSON.parse(text) as { message?: unknown; errors?: unknown };
+      detail = JSON.stringify(parsed.errors ?? parsed.message ?? parsed);
+    } catch {
+      /* keep raw body */
+    }
+    throw new Error(
+      `${remote.provider} API ${method} ${endpoint} failed (${res.status}): ${detail}`,
+    );
+  }
+  return (text ? JSON.parse(text) : null) as T;
+}
+
+// --- Pull request records (task id -> PR), persisted across restarts ---
+
+function getRecordsPath(): string {
+  return path.join(getStateDir(), 'pull-requests.json');
+}
+
+function readRecords(): Record<string, PullRequestRef> {
+  try {
+    return JSON.parse(fs.readFileSync(getRecordsPath(), 'utf8')) as Record<string, PullRequestRef>;
+  } catch {
+    return {};
+  }
+}
+
+function writeRecord(taskId: string, pr: PullRequestRef): void {
+  const records = readRecords();
+  records[taskId] = pr;
+  const recordsPath = getRecordsPath();
+  fs.mkdirSync(path.dirname(recordsPath), { recursive: true });
+  const tmpPath = recordsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(records, null, 2), 'utf8');
+  fs.renameSync(tmpPath, recordsPath);
+}
+
+export function getPullRequestRef(taskId: string): PullRequestRef | null {
+  return readRecords()[taskId] ?? null;
+}
+
+export function listPullRequestRefs(): Record<string, PullRequestRef> {
+  return readRecords();
+}
+
+// --- Commands ---
+
+export async function createPullRequest(
+  taskId: string,
+  worktreePath: string,
+  branchName: string,
+  title: string,
+  body: string,
+  draft: boolean,
+): Promise<PullRequestRef> {
+  if (!title.trim()) throw new Error('Pull request title must not be empty');
+  const remote = parseRemoteUrl(await getRemoteUrl(worktreePath));
+  if (!remote) throw new Error('The origin remote is not a recognised GitHub or GitLab URL');
+  requireToken(remote);
+
+  const base = await getMainBranch(worktreePath);
+  // No-op when the branch is already up to date on the remote
+  await pushTask(worktreePath, branchName);
+
+  let pr: PullRequestRef;
+  if (remote.provider === 'github') {
+    const res = await apiRequest<{ number: number; html_url: string }>(
+      remote,
+      'POST',
+      `/repos/${remote.repo}/pulls`,
+      { title, body, head: branchName, base, draft },
+    );
+    pr = { ...remote, number: res.number, url: res.html_url, branch: branchName, base };
+  } else {
+    const res = await apiRequest<{ iid: number; web_url: string }>(
+      remote,
+      'POST',
+      `/projects/${encodeURIComponent(remote.repo)}/merge_requests`,
+      {
+        source_branch: branchName,
+        target_branch: base,
+        title: draft ? `Draft: ${title}` : title,
+        description: body,
+      },
+    );
+    pr = { ...remote, number: res.iid, url: res.web_url, branch: branchName, base };
+  }
+
+  writeRecord(taskId, pr);
+  return pr;
+}
@@ -0,0 +1,69 @@
+// Named keys for send_key, mapped to xterm-256color byte sequences (the TERM
+// every agent is spawned with). Cursor keys depend on DECCKM: in application
+// cursor mode they are sent as SS3 (ESC O) instead of CSI (ESC [).
+
+export const KEY_NAMES = [
+  'enter',
+  'tab',
+  'shift_tab',
+  'backspace',
+  'escape',
+  'ctrl_c',
+  'ctrl_d',
+  'ctrl_z',
+  'ctrl_l',
+  'ctrl_r',
+  'up',
+  'down',
+  'right',
+  'left',
+  'home',
+  'end',
+  'page_up',
+  'page_down',
+  'insert',
+  'delete',
+] as const;
+
+export type KeyName = (typeof KEY_NAMES)[number];
+
+type CursorKey = 'up' | 'down' | 'right' | 'left' | 'home' | 'end';
+
+const FIXED: Record<Exclude<KeyName, CursorKey>, string> = {
+  enter: '\r',
+  tab: '\t',
+  shift_tab: '\x1b[Z',
+  backspace: '\x7f',
+  escape: '\x1b',
+  ctrl_c: '\x03',
+  ctrl_d: '\x04',
+  ctrl_z: '\x1a',
+  ctrl_l: '\x0c',
+  ctrl_r: '\x12',
+  page_up: '\x1b[5~',
+  page_down: '\x1b[6~',
+  insert: '\x1b[2~',
+  delete: '\x1b[3~',
+};
+
+const CURSOR: Record<CursorKey, string> = {
+  up: 'A',
+  down: 'B',
+  right: 'C',
+  left: 'D',
+  home: 'H',
+  end: 'F',
+};
+
+export function isKeyName(value: unknown): value is KeyName {
+  return typeof value === 'string' && (KEY_NAMES as readonly string[]).includes(value);
+}
+
+/** Byte sequence for a named key, honouring application cursor mode. */
+export function keySequence(key: KeyName, applicationCursor: boolean): string {
+  if (key in CURSOR) {
+    const final = CURSOR[key as CursorKey];
+    return (applicationCursor ? '\x1bO' : '\x1b[') + final;
+  }
+  return FIXED[key as Exclude<KeyName, CursorKey>];
+}
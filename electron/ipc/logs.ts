@@ -0,0 +1,148 @@
+import fs from 'fs';
+import path from 'path';
+import { format } from 'util';
+import { getStateDir } from './persistence.js';
+
+// Backend diagnostics. Everything written through console.* or `log` is kept
+// in a ring buffer and appended to backend.log, so a bug report can include
+// recent logs without restarting the app. The level filter is changed at run
+// time: a default level plus optional per-target overrides, e.g.
+// "warn,pty=debug". PARALLEL_CODE_LOG sets the filter at startup.
+
+export const LOG_LEVELS = ['error', 'warn', 'info', 'debug'] as const;
+export type LogLevel = (typeof LOG_LEVELS)[number];
+
+export interface LogEntry {
+  ts: number;
+  level: LogLevel;
+  /** Subsystem that logged it; null for plain console calls. */
+  target: string | null;
+  message: string;
+}
+
+const RING_SIZE = 2000;
+const MAX_LOG_BYTES = 1024 * 1024;
+const DEFAULT_FILTER = 'info';
+
+interface LogFilter {
+  level: LogLevel;
+  targets: Map<string, LogLevel>;
+}
+
+const ring: LogEntry[] = [];
+let filterText = DEFAULT_FILTER;
+let filter: LogFilter = { level: 'info', targets: new Map() };
+let stream: fs.WriteStream | null = null;
+let streamBytes = 0;
+let installed = false;
+
+/** The console methods as they were before capture, used for terminal output. */
+const original = {
+  error: console.error.bind(console),
+  warn: console.warn.bind(console),
+};
+
+export function isLogLevel(value: unknown): value is LogLevel {
+  return LOG_LEVELS.includes(value as LogLevel);
+}
+
+const severity = (level: LogLevel) => LOG_LEVELS.indexOf(level);
+
+function parseLogFilter(text: string): LogFilter {
+  const parsed: LogFilter = { level: 'info', targets: new Map() };
+  for (const part of text.split(',').map((p) => p.trim())) {
+    if (!part) continue;
+    const [target, level] = part.includes('=') ? part.split('=', 2) : [null, part];
+    if (!isLogLevel(level)) throw new Error(`Unknown log level: ${level}`);
+    if (target === null) parsed.level = level;
+    else if (/^[\w-]+$/.test(target)) parsed.targets.set(target, level);
+    else throw new Error(`Invalid log target: ${target}`);
+  }
+  return parsed;
+}
+
+function enabled(level: LogLevel, target: string | null): boolean {
+  const limit = (target !== null && filter.targets.get(target)) || filter.level;
+  return severity(level) <= severity(limit);
+}
+
+function getLogPath(): string {
+  return path.join(getStateDir(), 'backend.log');
+}
+
+/** Append to backend.log, keeping one rotated file. */
+function appendToFile(line: string): void {
+  try {
+    if (!stream) {
+      fs.mkdirSync(getStateDir(), { recursive: true });
+      streamBytes = fs.existsSync(getLogPath()) ? fs.statSync(getLogPath()).size : 0;
+      stream = fs.createWriteStream(getLogPath(), { flags: 'a' });
+    }
+    if (streamBytes > MAX_LOG_BYTES) {
+      stream.end();
+      fs.renameSync(getLogPath(), `${getLogPath()}.1`);
+      stream = fs.createWriteStream(getLogPath(), { flags: 'a' });
+      streamBytes = 0;
+    }
+    stream.write(line);
+    streamBytes += Buffer.byteLength(line);
+  } catch {
+    // Logging must never take the app down; the ring buffer still has it
+  }
+}
+
+function write(level: LogLevel, target: string | null, args: unknown[]): void {
+  if (!enabled(level, target)) return;
+  const entry: LogEntry = { ts: Date.now(), level, target, message: format(...args) };
+  ring.push(entry);
+  if (ring.length > RING_SIZE) ring.shift();
+
+  const prefix = `${new Date(entry.ts).toISOString()} ${level.toUpperCase()}`;
+  const line = `${prefix}${target ? ` [${target}]` : ''} ${entry.message}`;
+  appendToFile(line + '\n');
+  if (level === 'error') original.error(line);
+  else original.warn(line);
+}
+
+/** Logging for backend modules; `target` names the subsystem for per-target levels. */
+export const log = {
+  error: (target: string, ...args: unknown[]) => write('error', target, args),
+  warn: (target: string, ...args: unknown[]) => write('warn', target, args),
+  info: (target: string, ...args: unknown[]) => write('info', target, args),
+  debug: (target: string, ...args: unknown[]) => write('debug', target, args),
+};
+
+/** Route console output through the log. Call once, as early as possible. */
+export function installLogCapture(): void {
+  if (installed) return;
+  installed = true;
+  const initial = process.env.PARALLEL_CODE_LOG;
+  if (initial) {
+    try {
+      setLogLevel(initial);
+    } catch (e) {
+      original.warn(`Ignoring PARALLEL_CODE_LOG: ${String(e)}`);
+    }
+  }
+  console.error = (...args: unknown[]) => write('error', null, args);
+  console.warn = (...args: unknown[]) => write('warn', null, args);
+  console.info = (...args: unknown[]) => write('info', null, args);
+  console.log = (...args: unknown[]) => write('info', null, args);
+  console.debug = (...args: unknown[]) => write('debug', null, args);
+}
+
+/** Buffered entries at `level` or more severe, no older than `since`, oldest first. */
+export function getBackendLogs(level: LogLevel = 'debug', since = 0): LogEntry[] {
+  return ring.filter((e) => e.ts >= since && severity(e.level) <= severity(level));
+}
+
+export function getLogLevel(): string {
+  return filterText;
+}
+
+/** Replace the level filter, e.g. "debug" or "warn,pty=debug". Returns the new filter. */
+export function setLogLevel(text: string): string {
+  filter = parseLogFilter(text);
+  filterText = text.trim() || DEFAULT_FILTER;
+  return filterText;
+}
@@ -0,0 +1,221 @@
+import fs from 'fs';
+import path from 'path';
+
+// MCP servers of a project or worktree, kept in the files the agent CLIs read
+// themselves: Claude Code's `.mcp.json` (switched on and off in
+// `.claude/settings.local.json`) and Gemini CLI's `.gemini/settings.json`.
+// Other keys in those files are preserved.
+
+export type McpTransport = 'stdio' | 'http' | 'sse';
+
+export interface McpServer {
+  name: string;
+  transport: McpTransport;
+  /** stdio servers */
+  command?: string;
+  args?: string[];
+  env?: Record<string, string>;
+  /** http and sse servers */
+  url?: string;
+  enabled: boolean;
+  /** Ids of the agents whose config lists the server. */
+  agents: string[];
+}
+
+type Json = Record<string, unknown>;
+
+interface McpConfigFormat {
+  agentId: string;
+  /** Relative to the project or worktree. */
+  serversFile: string;
+  disabledFile: string;
+  disabledKey: string;
+  /** Servers the user approved; Claude Code asks about project servers not in it. */
+  enabledKey?: string;
+  toEntry: (server: McpServer) => Json;
+  fromEntry: (entry: Json) => Pick<McpServer, 'transport' | 'command' | 'args' | 'env' | 'url'>;
+}
+
+const str = (v: unknown): string | undefined => (typeof v === 'string' ? v : undefined);
+
+function stringArray(v: unknown): string[] {
+  return Array.isArray(v) ? v.filter((s): s is string => typeof s === 'string') : [];
+}
+
+function stdioFields(entry: Json): Pick<McpServer, 'command' | 'args' | 'env'> {
+  const env = entry.env && typeof entry.env === 'object' ? entry.env : {};
+  return {
+    command: str(entry.command),
+    args: stringArray(entry.args),
+    env: env as Record<string, string>,
+  };
+}
+
+const FORMATS: McpConfigFormat[] = [
+  {
+    agentId: 'claude-code',
+    serversFile: '.mcp.json',
+    disabledFile: '.claude/settings.local.json',
+    disabledKey: 'disabledMcpjsonServers',
+    enabledKey: 'enabledMcpjsonServers',
+    toEntry: (s) =>
+      s.transport === 'stdio'
+        ? { command: s.command, args: s.args ?? [], env: s.env ?? {} }
+        : { type: s.transport, url: s.url },
+    fromEntry: (e) =>
+      e.type === 'http' || e.type === 'sse'
+        ? { transport: e.type, url: str(e.url) }
+        : { transport: 'stdio', ...stdioFields(e) },
+  },
+  {
+    agentId: 'gemini',
+    serversFile: '.gemini/settings.json',
+    disabledFile: '.gemini/settings.json',
+    disabledKey: 'excludeMCPServers',
+    toEntry: (s) =>
+      s.transport === 'stdio'
+        ? { command: s.command, args: s.args ?? [], env: s.env ?? {} }
+        : s.transport === 'http'
+          ? { httpUrl: s.url }
+          : { url: s.url },
+    fromEntry: (e) =>
+      typeof e.httpUrl === 'string'
+        ? { transport: 'http', url: e.httpUrl }
+        : typeof e.url === 'string'
+          ? { transport: 'sse', url: e.url }
+          : { transport: 'stdio', ...stdioFields(e) },
+  },
+];
+
+function readJson(file: string): Json {
+  try {
+    const parsed: unknown = JSON.parse(fs.readFileSync(file, 'utf8'));
+    return parsed && typeof parsed === 'object' && !Array.isArray(parsed) ? (parsed as Json) : {};
+  } catch (e) {
+    if ((e as NodeJS.ErrnoException).code === 'ENOENT') return {};
+    throw new Error(`Could not read ${file}: ${e instanceof Error ? e.message : String(e)}`);
+  }
+}
+
+function writeJson(file: string, data: Json): void {
+  fs.mkdirSync(path.dirname(file), { recursive: true });
+  const tmpPath = file + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(data, null, 2) + '\n', 'utf8');
+  fs.renameSync(tmpPath, file);
+}
+
+function serversOf(dir: string, format: McpConfigFormat): Record<string, Json> {
+  const servers = readJson(path.join(dir, format.serversFile)).mcpServers;
+  return servers && typeof servers === 'object' ? (servers as Record<string, Json>) : {};
+}
+
+export function listMcpServers(dir: string): McpServer[] {
+  const byName = new Map<string, McpServer>();
+  for (const format of FORMATS) {
+    const disabled = stringArray(readJson(path.join(dir, format.disabledFile))[format.disabledKey]);
+    for (const [name, entry] of Object.entries(serversOf(dir, format))) {
+      const existing = byName.get(name);
+      if (existing) {
+        existing.agents.push(format.agentId);
+        existing.enabled &&= !disabled.includes(name);
+        continue;
+      }
+      byName.set(name, {
+        name,
+        ...format.fromEntry(entry ?? {}),
+        enabled: !disabled.includes(name),
+        agents: [format.agentId],
+      });
+    }
+  }
+  return [...byName.values()].sort((a, b) => a.name.localeCompare(b.name));
+}
+
+function validateServer(value: unknown): McpServer {
+  const s = value as Partial<Record<keyof McpServer, unknown>> | null;
+  if (typeof s?.name !== 'string' || !/^[A-Za-z0-9_.-]+$/.test(s.name)) {
+    throw new Error('name must be letters, digits, dots, dashes and underscores');
+  }
+  const transport = s.transport ?? 'stdio';
+  if (transport !== 'stdio' && transport !== 'http' && transport !== 'sse') {
+    throw new Error('transport must be stdio, http or sse');
+  }
+  if (transport === 'stdio') {
+    if (typeof s.command !== 'string' || !s.command.trim()) {
+      throw new Error('command must not be empty');
+    }
+    const args = s.args ?? [];
+    if (!Array.isArray(args) || !args.every((a) => typeof a === 'string')) {
+      throw new Error('args must be an array of strings');
+    }
+    const env = s.env ?? {};
+    if (typeof env !== 'object' || Object.values(env).some((v) => typeof v !== 'string')) {
+      throw new Error('env must map variable names to strings');
+    }
+    return {
+      name: s.name,
+      transport,
+      command: s.command.trim(),
+      args,
+      env: env as Record<string, string>,
+      enabled: true,
+      agents: [],
+    };
+  }
+  if (typeof s.url !== 'string' || !/^https?:\/\//.test(s.url)) {
+    throw new Error('url must be an http(s) URL');
+  }
+  return { name: s.name, transport, url: s.url, enabled: true, agents: [] };
+}
+
+function formatsFor(agentIds: string[] | undefined): McpConfigFormat[] {
+  if (!agentIds) return FORMATS;
+  const formats = FORMATS.filter((f) => agentIds.includes(f.agentId));
+  if (formats.length === 0) throw new Error('None of these agents has an MCP config');
+  return formats;
+}
+
+/** Add a server to the config of `agentIds` (default: every supported agent). */
+export function addMcpServer(dir: string, value: unknown, agentIds?: string[]): McpServer {
+  const server = validateServer(value);
+  const formats = formatsFor(agentIds);
+  for (const format of formats) {
+    if (serversOf(dir, format)[server.name]) {
+      throw new Error(`An MCP server named ${server.name} already exists`);
+    }
+  }
+  for (const format of formats) {
+    const file = path.join(dir, format.serversFile);
+    const config = readJson(file);
+    writeJson(file, {
+      ...config,
+      mcpServers: { ...serversOf(dir, format), [server.name]: format.toEntry(server) },
+    });
+  }
+  return { ...server, agents: formats.map((f) => f.agentId) };
+}
+
+/** Switch a server on or off for every agent whose config lists it. */
+export function toggleMcpServer(dir: string, name: string, enabled: boolean): void {
+  const formats = FORMATS.filter((f) => serversOf(dir, f)[name]);
+  if (formats.length === 0) throw new Error(`MCP server not found: ${name}`);
+  for (const format of formats) {
+    const file = path.join(dir, format.disabledFile);
+    const settings = readJson(file);
+    const disabled = stringArray(settings[format.disabledKey]).filter((n) => n !== name);
+    const update: Json = { [format.disabledKey]: enabled ? disabled : [...disabled, name] };
+    if (format.enabledKey) {
+      const approved = stringArray(settings[format.enabledKey]).filter((n) => n !== name);
+      update[format.enabledKey] = enabled ? [...approved, name] : approved;
+    }
+    writeJson(file, { ...settings, ...update });
+  }
+}
+
+/**
+ * MCP config files, relative to a checkout. New worktrees get copies of the
+ * untracked ones so tasks start with the project's servers.
+ */
+export function mcpConfigFiles(): string[] {
+  return [...new Set(FORMATS.flatMap((f) => [f.serversFile, f.disabledFile]))];
+}
@@ -0,0 +1,95 @@
+import { Notification, type BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { onPullRequestStatusChanged, type PullRequestStatus } from './pr-status.js';
+import { onPtyEvent, type PtyEventSession } from './pty.js';
+import {
+  getSettings,
+  updateSettings,
+  type NotificationSettings,
+  type NotificationType,
+} from './settings.js';
+import { getKnownTask } from './task-metadata.js';
+
+// Desktop notifications for things that need the user while the window is in
+// the background: agents exiting or waiting for input, merges stopping on
+// conflicts and pull request updates. Each kind can be switched off in the
+// settings.
+
+let mainWindow: BrowserWindow | null = null;
+
+export function getNotificationSettings(): NotificationSettings {
+  return getSettings().notifications;
+}
+
+/** Switch individual notification types on or off. */
+export function setNotificationSettings(patch: unknown): NotificationSettings {
+  return updateSettings({ notifications: patch }).notifications;
+}
+
+/** Show a notification; clicking it brings the window and the task to the front. */
+function show(title: string, body: string, taskId: string | null): void {
+  if (!Notification.isSupported()) return;
+  const notification = new Notification({ title, body });
+  notification.on('click', () => {
+    if (!mainWindow || mainWindow.isDestroyed()) return;
+    if (mainWindow.isMinimized()) mainWindow.restore();
+    mainWindow.show();
+    mainWindow.focus();
+    if (taskId) mainWindow.webContents.send(IPC.NotificationClicked, { task_id: taskId });
+  });
+  notification.show();
+}
+
+function notify(type: NotificationType, taskId: string, body: string): void {
+  if (!getNotificationSettings()[type]) return;
+  // The app shows all of this itself while it has focus
+  if (mainWindow && !mainWindow.isDestroyed() && mainWindow.isFocused()) return;
+  show(getKnownTask(taskId)?.name ?? 'Parallel Code', body, taskId);
+}
+
+export function notifyMergeConflict(taskId: string): void {
+  notify('merge_conflict', taskId, 'The merge stopped on conflicts');
+}
+
+function describePullRequest(status: PullRequestStatus, previous: PullRequestStatus): string {
+  if (status.state !== previous.state) return `Pull request ${status.state}`;
+  if (status.checks !== previous.checks && status.checks !== 'pending') {
+    return status.checks === 'success' ? 'Checks passed' : 'Checks failed';
+  }
+  if (status.review !== previous.review && status.review !== 'review_required') {
+    return status.review === 'approved' ? 'Pull request approved' : 'Changes requested';
+  }
+  return '';
+}
+
+/** Watch agents and pull requests for events worth a notification. */
+export function initNotifications(win: BrowserWindow): void {
+  mainWindow = win;
+  onPtyEvent('exit', (_agentId, data) => {
+    const d = data as PtyEventSession & { exitCode: number | null; exitReason: string };
+    if (!d.queueable || d.exitReason === 'killed_by_user') return;
+    notify(
+      'agent_exit',
+      d.taskId,
+      d.exitCode === 0 ? 'The agent finished' : `The agent exited with code ${d.exitCode}`,
+    );
+  });
+  onPtyEvent('idle', (_agentId, data) => {
+    const d = data as PtyEventSession & { lastLine: string };
+    if (!d.queueable) return;
+    notify('agent_idle', d.taskId, d.lastLine ? `Waiting: ${d.lastLine}` : 'Waiting for input');
+  });
+  onPullRequestStatusChanged((status, previous) => {
+    // The first poll after startup isn't a change
+    const body = previous ? describePullRequest(status, previous) : '';
+    if (body) notify('pr_status', status.task_id, body);
+  });
+}
+
+/** Show a sample notification regardless of settings and focus. */
+export function testNotification(): void {
+  if (!Notification.isSupported()) {
+    throw new Error('Desktop notifications are not supported on this system');
+  }
+  show('Parallel Code', 'Notifications are working', null);
+}
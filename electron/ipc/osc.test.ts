@@ -0,0 +1,86 @@
+import { describe, it, expect } from 'vitest';
+import {
+  createOscParser,
+  parseCwdSequence,
+  parseNotificationSequence,
+  parseTitleSequence,
+} from './osc.js';
+
+// ---------------------------------------------------------------------------
+// createOscParser
+// ---------------------------------------------------------------------------
+describe('createOscParser', () => {
+  it.each([
+    ['a BEL-terminated sequence', ['\x1b]0;title\x07'], [{ code: 0, payload: 'title' }]],
+    ['an ST-terminated sequence', ['\x1b]2;x\x1b\\'], [{ code: 2, payload: 'x' }]],
+    ['a sequence without payload', ['\x1b]104\x07'], [{ code: 104, payload: '' }]],
+    [
+      'a payload containing semicolons',
+      ['\x1b]777;notify;a;b\x07'],
+      [{ code: 777, payload: 'notify;a;b' }],
+    ],
+    [
+      'sequences mixed with text',
+      ['ab\x1b]0;one\x07cd\x1b]7;file:///tmp\x07ef'],
+      [
+        { code: 0, payload: 'one' },
+        { code: 7, payload: 'file:///tmp' },
+      ],
+    ],
+    ['a split sequence', ['\x1b]0;ti', 'tle\x07'], [{ code: 0, payload: 'title' }]],
+    ['a split introducer', ['text\x1b', ']0;t\x07'], [{ code: 0, payload: 't' }]],
+    ['a split ST terminator', ['\x1b]2;x\x1b', '\\'], [{ code: 2, payload: 'x' }]],
+    ['a non-numeric code', ['\x1b]abc;x\x07'], []],
+    ['an unterminated sequence', ['\x1b]0;never ends'], []],
+  ])('parses %s', (_name, chunks, expected) => {
+    const parser = createOscParser();
+    expect(chunks.flatMap((chunk) => parser.feed(chunk))).toEqual(expected);
+  });
+
+  it('gives up on a sequence that never terminates', () => {
+    const parser = createOscParser();
+    parser.feed('\x1b]0;' + 'x'.repeat(5000));
+    expect(parser.feed('\x07\x1b]2;next\x07')).toEqual([{ code: 2, payload: 'next' }]);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Payload parsers
+// ---------------------------------------------------------------------------
+describe('parseCwdSequence', () => {
+  it.each([
+    [7, 'file://host/home/me/my%20project', '/home/me/my project'],
+    [7, 'http://host/home', null],
+    [7, 'not a url', null],
+    [633, 'P;Cwd=/work', '/work'],
+    [633, 'A', null],
+    [1337, 'CurrentDir=/srv', '/srv'],
+    [1337, 'CurrentDir=', null],
+    [0, 'title', null],
+  ])('reads OSC %s %j', (code, payload, expected) => {
+    expect(parseCwdSequence({ code, payload })).toBe(expected);
+  });
+});
+
+describe('parseTitleSequence', () => {
+  it.each([
+    [0, 'window', 'window'],
+    [2, '', ''],
+    [1, 'icon', null],
+  ])('reads OSC %s %j', (code, payload, expected) => {
+    expect(parseTitleSequence({ code, payload })).toBe(expected);
+  });
+});
+
+describe('parseNotificationSequence', () => {
+  it.each([
+    [9, 'Build done', { title: '', body: 'Build done' }],
+    [9, '4;1;50', null],
+    [9, '', null],
+    [777, 'notify;Agent;Waiting for input', { title: 'Agent', body: 'Waiting for input' }],
+    [777, 'notify;Title only', { title: 'Title only', body: '' }],
+    [777, 'other;x', null],
+  ])('reads OSC %s %j', (code, payload, expected) => {
+    expect(parseNotificationSequence({ code, payload })).toEqual(expected);
+  });
+});
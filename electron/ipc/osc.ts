@@ -0,0 +1,100 @@
+// Incremental parser for OSC (Operating System Command) sequences in PTY
+// output: ESC ] <code> ; <payload> terminated by BEL or ESC \. Sequences may
+// be split across reads, so an unterminated tail is carried over.
+
+export interface OscSequence {
+  code: number;
+  payload: string;
+}
+
+const OSC_START = '\x1b]';
+const MAX_PENDING = 4096; // give up on sequences that never terminate
+
+export interface OscParser {
+  feed: (data: string) => OscSequence[];
+}
+
+export function createOscParser(): OscParser {
+  let pending = '';
+
+  return {
+    feed: (data) => {
+      const text = pending + data;
+      pending = '';
+      const result: OscSequence[] = [];
+      let idx = text.indexOf(OSC_START);
+      while (idx !== -1) {
+        const bel = text.indexOf('\x07', idx + 2);
+        const st = text.indexOf('\x1b\\', idx + 2);
+        const end = bel === -1 ? st : st === -1 ? bel : Math.min(bel, st);
+        if (end === -1) {
+          const tail = text.slice(idx);
+          if (tail.length <= MAX_PENDING) pending = tail;
+          break;
+        }
+        const body = text.slice(idx + 2, end);
+        const sep = body.indexOf(';');
+        const code = Number(sep === -1 ? body : body.slice(0, sep));
+        if (Number.isInteger(code)) {
+          result.push({ code, payload: sep === -1 ? '' : body.slice(sep + 1) });
+        }
+        idx = text.indexOf(OSC_START, end + 1);
+      }
+      // Keep a lone trailing ESC in case the next read starts with ']'
+      if (!pending && text.endsWith('\x1b')) pending = '\x1b';
+      return result;
+    },
+  };
+}
+
+/**
+ * Extract a working directory from a cwd-reporting sequence: OSC 7
+ * (file://host/path), VS Code's OSC 633 ;P;Cwd= and iTerm2's
+ * OSC 1337 ;CurrentDir=. Returns null for anything else.
+ */
+export function parseCwdSequence(seq: OscSequence): string | null {
+  try {
+    if (seq.code === 7) {
+      const url = new URL(seq.payload);
+      if (url.protocol !== 'file:') return null;
+      return decodeURIComponent(url.pathname) || null;
+    }
+    if (seq.code === 633 && seq.payload.startsWith('P;Cwd=')) {
+      return seq.payload.slice('P;Cwd='.length) || null;
+    }
+    if (seq.code === 1337 && seq.payload.startsWith('CurrentDir=')) {
+      return seq.payload.slice('CurrentDir='.length) || null;
+    }
+  } catch {
+    /* malformed URL */
+  }
+  return null;
+}
+
+/** Window/icon title from OSC 0 or OSC 2. */
+export function parseTitleSequence(seq: OscSequence): string | null {
+  if (seq.code !== 0 && seq.code !== 2) return null;
+  return seq.payload;
+}
+
+/**
+ * Desktop notification from OSC 9 (iTerm2: message only) or
+ * OSC 777 ;notify;title;body (rxvt/foot). ConEmu's OSC 9 ;<n>; subcommands
+ * (progress etc.) are ignored.
+ */
+export function parseNotificationSequence(
+  seq: OscSequence,
+): { title: string; body: string } | null {
+  if (seq.code === 9) {
+    if (!seq.payload || /^\d+;/.test(seq.payload)) return null;
+    return { title: '', body: seq.payload };
+  }
+  if (seq.code === 777 && seq.payload.startsWith('notify;')) {
+    const rest = seq.payload.slice('notify;'.length);
+    const sep = rest.indexOf(';');
+    return sep === -1
+      ? { title: rest, body: '' }
+      : { title: rest.slice(0, sep), body: rest.slice(sep + 1) };
+  }
+  return null;
+}
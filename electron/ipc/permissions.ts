@@ -0,0 +1,116 @@
+import { randomUUID } from 'crypto';
+import path from 'path';
+import { stripAnsi } from './idle.js';
+
+// Recognises the tool-permission dialogs of supported agent CLIs in their
+// terminal output and knows which keys answer them. A dialog is reported once;
+// it is resolved when answered through respond() or once its question has
+// scrolled out of the output tail (e.g. the user answered in the terminal).
+
+const TAIL_CHARS = 4096;
+const DETAIL_LINES = 3;
+
+export interface PermissionRequest {
+  request_id: string;
+  /** Agent CLI, e.g. `claude`. */
+  agent: string;
+  /** The dialog's question, e.g. `Do you want to proceed?` */
+  question: string;
+  /** Lines above the question, usually the command or file involved. */
+  detail: string;
+}
+
+interface Dialect {
+  agent: string;
+  question: RegExp;
+  /** Only on screen while the dialog waits for an answer. */
+  options: RegExp;
+  approve: string;
+  deny: string;
+}
+
+const DIALECTS: Record<string, Dialect> = {
+  claude: {
+    agent: 'claude',
+    question: /Do you want to [^?\n]+\?/g,
+    options: /1\.\s*Yes/,
+    approve: '\r', // first option is highlighted
+    deny: '\x1b',
+  },
+  codex: {
+    agent: 'codex',
+    question: /(?:Would you like to [^?\n]+|Allow command)\?/g,
+    options: /Yes, proceed/,
+    approve: 'y',
+    deny: '\x1b',
+  },
+  gemini: {
+    agent: 'gemini',
+    question: /(?:Allow execution of[^?\n]*|Apply this change|Do you want to proceed)\?/g,
+    options: /Yes, allow once/,
+    approve: '\r',
+    deny: '\x1b',
+  },
+};
+
+export type PermissionEvent =
+  | { kind: 'request'; request: PermissionRequest }
+  | { kind: 'resolved'; request_id: string };
+
+export interface PermissionDetector {
+  /** Feed raw PTY output; reports a dialog appearing or going away. */
+  feed: (data: string) => PermissionEvent | null;
+  /** Keys answering the pending dialog; throws if there is none. */
+  respond: (approve: boolean) => string;
+}
+
+/** Box-drawing borders and list markers around dialog text. */
+const cleanLine = (line: string) => line.replace(/^[\s│┃|╭╰─>❯›●]+|[\s│┃|╮╯─]+$/g, '');
+
+export function createPermissionDetector(command: string): PermissionDetector {
+  const dialect = DIALECTS[path.basename(command).replace(/\.(?:exe|cmd)$/i, '')];
+  let tail = '';
+  let pending: PermissionRequest | null = null;
+
+  const lastQuestion = (): RegExpExecArray | null => {
+    if (!dialect) return null;
+    let last: RegExpExecArray | null = null;
+    for (const m of tail.matchAll(dialect.question)) last = m;
+    return last;
+  };
+
+  return {
+    feed: (data) => {
+      if (!dialect) return null;
+      tail = (tail + stripAnsi(data)).slice(-TAIL_CHARS);
+      const question = lastQuestion();
+      if (pending) {
+        if (question) return null;
+        const requestId = pending.request_id;
+        pending = null;
+        return { kind: 'resolved', request_id: requestId };
+      }
+      if (!question || !dialect.options.test(tail.slice(question.index))) return null;
+      const detail = tail
+        .slice(0, question.index)
+        .split(/\r?\n|\r/)
+        .map(cleanLine)
+        .filter(Boolean)
+        .slice(-DETAIL_LINES)
+        .join('\n');
+      pending = {
+        request_id: randomUUID(),
+        agent: dialect.agent,
+        question: question[0],
+        detail,
+      };
+      return { kind: 'request', request: pending };
+    },
+    respond: (approve) => {
+      if (!dialect || !pending) throw new Error('No permission request is pending');
+      pending = null;
+      tail = '';
+      return approve ? dialect.approve : dialect.deny;
+    },
+  };
+}
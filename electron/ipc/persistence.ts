@@ -2,7 +2,12 @@ import { app } from 'electron';
 import fs from 'fs';
 import path from 'path';
 
-function getStateDir(): string {
+/** Rotating copies of state.json; `.bak.1` is the newest. */
+const STATE_BACKUPS = 5;
+/** A save rotates the backups only when the newest one is older than this. */
+const BACKUP_INTERVAL_MS = 10 * 60_000;
+
+export function getStateDir(): string {
   let dir = app.getPath('userData');
   // Use separate dir for dev mode
   if (!app.isPackaged) {
@@ -16,44 +21,183 @@ function getStatePath(): string {
   return path.join(getStateDir(), 'state.json');
 }
 
-export function saveAppState(json: string): void {
-  const statePath = getStatePath();
-  const dir = path.dirname(statePath);
-  fs.mkdirSync(dir, { recursive: true });
-
-  // Validate JSON before writing
-  JSON.parse(json);
+function getBackupPath(index: number): string {
+  return `${getStatePath()}.bak.${index}`;
+}
 
-  // Atomic write: write to temp, then rename
-  const tmpPath = statePath + '.tmp';
-  fs.writeFileSync(tmpPath, json, 'utf8');
+export interface DurableWriteOptions {
+  /** Defaults to `<file>.tmp`; writers that may race each other need their own. */
+  tmpPath?: string;
+  /** Runs once the data is flushed; throwing cancels the write. */
+  beforeRename?: () => void;
+}
 
-  // Keep one backup
-  if (fs.existsSync(statePath)) {
-    const bakPath = statePath.replace('.json', '.json.bak');
+/**
+ * Replace `file` so that a crash leaves either the old or the new content:
+ * write a temp file, flush it to disk, rename it over the target and flush
+ * the directory entry.
+ */
+export function writeFileDurable(
+  file: string,
+  data: string,
+  { tmpPath = file + '.tmp', beforeRename }: DurableWriteOptions = {},
+): void {
+  const fd = fs.openSync(tmpPath, 'w');
+  try {
+    fs.writeSync(fd, data, null, 'utf8');
+    fs.fsyncSync(fd);
+  } finally {
+    fs.closeSync(fd);
+  }
+  try {
+    beforeRename?.();
+    fs.renameSync(tmpPath, file);
+  } catch (e) {
+    fs.rmSync(tmpPath, { force: true });
+    throw e;
+  }
+  try {
+    const dirFd = fs.openSync(path.dirname(file), 'r');
     try {
-      fs.renameSync(statePath, bakPath);
-    } catch {
-      /* ignore */
+      fs.fsyncSync(dirFd);
+    } finally {
+      fs.closeSync(dirFd);
     }
+  } catch {
+    /* directories can't be opened or synced on Windows */
   }
+}
 
-  fs.renameSync(tmpPath, statePath);
+/** Move the previous single `.bak` into the rotation. */
+function migrateLegacyBackup(): void {
+  const legacy = getStatePath().replace('.json', '.json.bak');
+  if (fs.existsSync(legacy) && !fs.existsSync(getBackupPath(1))) {
+    fs.renameSync(legacy, getBackupPath(1));
+  }
 }
 
-export function loadAppState(): string | null {
+/** Copy the current state to `.bak.1`, shifting older backups and dropping the oldest. */
+function pushBackup(statePath: string): void {
+  fs.rmSync(getBackupPath(STATE_BACKUPS), { force: true });
+  for (let i = STATE_BACKUPS - 1; i >= 1; i--) {
+    if (fs.existsSync(getBackupPath(i))) fs.renameSync(getBackupPath(i), getBackupPath(i + 1));
+  }
+  fs.copyFileSync(statePath, getBackupPath(1));
+}
+
+function rotateBackups(statePath: string): void {
+  if (!fs.existsSync(statePath)) return;
+  migrateLegacyBackup();
+  try {
+    if (Date.now() - fs.statSync(getBackupPath(1)).mtimeMs < BACKUP_INTERVAL_MS) return;
+  } catch {
+    /* no backup yet */
+  }
+  pushBackup(statePath);
+}
+
+function parseState(json: string): Record<string, unknown> {
+  const parsed: unknown = JSON.parse(json);
+  if (!parsed || typeof parsed !== 'object' || Array.isArray(parsed)) {
+    throw new Error('App state must be a JSON object');
+  }
+  const version = (parsed as Record<string, unknown>).schemaVersion;
+  if (version !== undefined && !Number.isInteger(version)) {
+    throw new Error('schemaVersion must be an integer');
+  }
+  return parsed as Record<string, unknown>;
+}
+
+export function saveAppState(json: string): void {
   const statePath = getStatePath();
+  fs.mkdirSync(path.dirname(statePath), { recursive: true });
+
+  // Validate before touching anything on disk
+  parseState(json);
 
-  if (fs.existsSync(statePath)) {
-    const content = fs.readFileSync(statePath, 'utf8');
-    if (content.trim()) return content;
+  try {
+    rotateBackups(statePath);
+  } catch (e) {
+    console.warn('Could not rotate state backups:', e);
   }
+  writeFileDurable(statePath, json);
+}
 
-  const bakPath = statePath.replace('.json', '.json.bak');
-  if (fs.existsSync(bakPath)) {
-    const content = fs.readFileSync(bakPath, 'utf8');
-    if (content.trim()) return content;
+/** Content of `file` if it holds a valid state, otherwise null. */
+function readState(file: string): string | null {
+  try {
+    const content = fs.readFileSync(file, 'utf8');
+    if (!content.trim()) return null;
+    parseState(content);
+    return content;
+  } catch {
+    return null;
   }
+}
 
+/** The saved state, falling back to the newest readable backup. */
+export function loadAppState(): string | null {
+  migrateLegacyBackup();
+  const candidates = [getStatePath()];
+  for (let i = 1; i <= STATE_BACKUPS; i++) candidates.push(getBackupPath(i));
+  for (const file of candidates) {
+    const content = readState(file);
+    if (content) {
+      if (file !== getStatePath()) console.warn(`Loaded app state from backup ${file}`);
+      return content;
+    }
+  }
   return null;
 }
+
+export interface StateBackup {
+  /** 1 is the newest. */
+  index: number;
+  saved_at: number;
+  size: number;
+  schema_version: number | null;
+  /** False when the file can't be parsed. */
+  valid: boolean;
+}
+
+export function listStateBackups(): StateBackup[] {
+  migrateLegacyBackup();
+  const backups: StateBackup[] = [];
+  for (let index = 1; index <= STATE_BACKUPS; index++) {
+    const file = getBackupPath(index);
+    let stat: fs.Stats;
+    try {
+      stat = fs.statSync(file);
+    } catch {
+      continue;
+    }
+    const content = readState(file);
+    const version = content ? JSON.parse(content).schemaVersion : undefined;
+    backups.push({
+      index,
+      saved_at: stat.mtimeMs,
+      size: stat.size,
+      schema_version: typeof version === 'number' ? version : null,
+      valid: content !== null,
+    });
+  }
+  return backups;
+}
+
+/**
+ * Make backup `index` the current state. The state it replaces becomes the
+ * newest backup, so a restore can itself be undone. Returns the restored JSON.
+ */
+export function restoreStateBackup(index: number): string {
+  if (!Number.isInteger(index) || index < 1 || index > STATE_BACKUPS) {
+    throw new Error(`index must be between 1 and ${STATE_BACKUPS}`);
+  }
+  migrateLegacyBackup();
+  const content = readState(getBackupPath(index));
+  if (!content) throw new Error(`Backup ${index} is missing or unreadable`);
+
+  const statePath = getStatePath();
+  if (fs.existsSync(statePath)) pushBackup(statePath);
+  writeFileDurable(statePath, content);
+  return content;
+}
@@ -0,0 +1,132 @@
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { listAgents } from './agents.js';
+import type { KeyName } from './keys.js';
+import { isOwnPlanWrite, onPlanChanged } from './plans.js';
+import { listRunningAgents, sendKey, writeToAgent, type RunningAgentInfo } from './pty.js';
+import { advanceTaskStatus, listTaskAgents } from './tasks.js';
+
+// The plan → approve → execute loop. A plan showing up in a watched task puts
+// the task's approval in `pending`; approving answers the agent the way its
+// CLI expects and moves the task on to running, while requesting changes types
+// the feedback into the agent's terminal and waits for the next plan. State is
+// kept in memory only: after a restart the next plan change asks again.
+
+export type PlanApprovalState = 'pending' | 'approved' | 'changes_requested';
+
+export interface PlanApproval {
+  task_id: string;
+  /** Agent definition whose plan file changed. */
+  agent_id: string;
+  plan_path: string;
+  state: PlanApprovalState;
+  updated_at: number;
+  /** What was sent with request_plan_changes, while in that state. */
+  feedback: string | null;
+}
+
+interface ApprovalDialect {
+  /** Keys accepting the plan at the agent's own prompt; null to type APPROVAL_MESSAGE. */
+  approve: KeyName[] | null;
+  /** Keys leaving the agent's plan prompt before feedback is typed. */
+  beforeFeedback: KeyName[];
+}
+
+const DIALECTS: Record<string, ApprovalDialect> = {
+  // Plan mode ends in a "Would you like to proceed?" menu with "Yes" highlighted
+  claude: { approve: ['enter'], beforeFeedback: ['escape'] },
+};
+
+const DEFAULT_DIALECT: ApprovalDialect = { approve: null, beforeFeedback: [] };
+const APPROVAL_MESSAGE = 'The plan is approved. Go ahead and implement it.';
+
+const approvals = new Map<string, PlanApproval>();
+let mainWindow: BrowserWindow | null = null;
+
+const commandName = (command: string) => path.parse(command).name;
+
+function update(approval: PlanApproval): PlanApproval {
+  approvals.set(approval.task_id, approval);
+  if (mainWindow && !mainWindow.isDestroyed()) {
+    mainWindow.webContents.send(IPC.PlanApprovalChanged, approval);
+  }
+  return approval;
+}
+
+function pendingApproval(taskId: string): PlanApproval {
+  const approval = approvals.get(taskId);
+  if (approval?.state !== 'pending') throw new Error('No plan is waiting for approval');
+  return approval;
+}
+
+/** The task's live session of the agent that wrote the plan, else its latest agent session. */
+function planSession(approval: PlanApproval): RunningAgentInfo {
+  const live = new Set(
+    listTaskAgents(approval.task_id)
+      .filter((s) => s.kind === 'agent' && s.state !== 'queued')
+      .map((s) => s.agent_id),
+  );
+  const running = listRunningAgents().filter((s) => live.has(s.agent_id));
+  const def = listAgents().find((a) => a.id === approval.agent_id);
+  const name = def ? commandName(def.command) : null;
+  const session = running.filter((s) => commandName(s.command) === name).pop() ?? running.pop();
+  if (!session) throw new Error('No running agent to answer for this task');
+  return session;
+}
+
+async function submit(agentId: string, text: string): Promise<void> {
+  await writeToAgent(agentId, text, { paste: true });
+  sendKey(agentId, 'enter');
+}
+
+/** Answer the agent's plan with an approval and move the task on to running. */
+export async function approvePlan(taskId: string): Promise<PlanApproval> {
+  const approval = pendingApproval(taskId);
+  const session = planSession(approval);
+  const { approve } = DIALECTS[commandName(session.command)] ?? DEFAULT_DIALECT;
+  if (approve) approve.forEach((key) => sendKey(session.agent_id, key));
+  else await submit(session.agent_id, APPROVAL_MESSAGE);
+  advanceTaskStatus(taskId, 'running');
+  return update({ ...approval, state: 'approved', updated_at: Date.now(), feedback: null });
+}
+
+/** Send feedback on the plan to the agent; its next plan change asks for approval again. */
+export async function requestPlanChanges(taskId: string, feedback: string): Promise<PlanApproval> {
+  const text = feedback.trim();
+  if (!text) throw new Error('feedback must not be empty');
+  const approval = pendingApproval(taskId);
+  const session = planSession(approval);
+  const { beforeFeedback } = DIALECTS[commandName(session.command)] ?? DEFAULT_DIALECT;
+  beforeFeedback.forEach((key) => sendKey(session.agent_id, key));
+  await submit(session.agent_id, text);
+  const now = Date.now();
+  return update({ ...approval, state: 'changes_requested', updated_at: now, feedback: text });
+}
+
+export function getPlanApprovals(): PlanApproval[] {
+  return [...approvals.values()];
+}
+
+export function forgetPlanApproval(taskId: string): void {
+  approvals.delete(taskId);
+}
+
+export function initPlanApproval(win: BrowserWindow): void {
+  mainWindow = win;
+  onPlanChanged((event) => {
+    if (event.content === null) return;
+    // Saves from the plan editor or history aren't a new plan from the agent
+    if (isOwnPlanWrite(event)) return;
+    // Once approved, the agent ticking off items must not ask again
+    if (approvals.get(event.task_id)?.state === 'approved') return;
+    update({
+      task_id: event.task_id,
+      agent_id: event.agent_id,
+      plan_path: event.path,
+      state: 'pending',
+      updated_at: Date.now(),
+      feedback: null,
+    });
+  });
+}
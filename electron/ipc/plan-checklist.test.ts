@@ -0,0 +1,69 @@
+import { describe, it, expect, vi } from 'vitest';
+
+// plan-checklist.ts writes through persistence.ts, which imports Electron.
+vi.mock('electron', () => ({ app: {} }));
+
+import { parsePlanText } from './plan-checklist.js';
+
+const ids = (text: string) =>
+  parsePlanText('PLAN.md', text).sections.flatMap((s) => s.items.map((i) => i.id));
+
+// ---------------------------------------------------------------------------
+// parsePlanText
+// ---------------------------------------------------------------------------
+describe('parsePlanText', () => {
+  it('reads sections, items and progress', () => {
+    const plan = parsePlanText(
+      'PLAN.md',
+      [
+        '# Migrate auth',
+        '- [ ] loose item',
+        '## Backend',
+        '- [x] Add table',
+        '  - [ ] Nested step',
+        '1. [X] Numbered item',
+        '## Empty',
+        '```',
+        '- [ ] inside a fence',
+        '```',
+      ].join('\n'),
+    );
+    expect(plan.title).toBe('Migrate auth');
+    expect(plan.total).toBe(4);
+    expect(plan.done).toBe(2);
+    expect(plan.sections.map((s) => [s.title, s.level, s.items.length])).toEqual([
+      ['Migrate auth', 1, 1],
+      ['Backend', 2, 3],
+      ['Empty', 2, 0],
+    ]);
+    expect(plan.sections[1].items.map((i) => [i.text, i.done, i.depth, i.line])).toEqual([
+      ['Add table', true, 0, 3],
+      ['Nested step', false, 1, 4],
+      ['Numbered item', true, 0, 5],
+    ]);
+  });
+
+  it.each([
+    ['checking an item', '## A\n- [ ] one\n- [ ] two', '## A\n- [x] one\n- [ ] two'],
+    ['inserting lines above', '## A\n- [ ] one', '# Plan\n\nIntro\n## A\n- [ ] one'],
+    ['reordering items', '## A\n- [ ] one\n- [ ] two', '## A\n- [ ] two\n- [ ] one'],
+    ['changing indentation', '## A\n- [ ] one', '## A\n    - [ ] one'],
+    ['CRLF line endings', '## A\n- [ ] one', '## A\r\n- [ ] one\r\n'],
+  ])('keeps item ids stable when %s', (_name, before, after) => {
+    expect(new Set(ids(after))).toEqual(new Set(ids(before)));
+  });
+
+  it.each([
+    ['editing the text', '## A\n- [ ] one', '## A\n- [ ] one!'],
+    ['renaming the section', '## A\n- [ ] one', '## B\n- [ ] one'],
+  ])('gives an item a new id after %s', (_name, before, after) => {
+    expect(ids(after)).not.toEqual(ids(before));
+  });
+
+  it('tells identical items in one section apart by order', () => {
+    const [first, second, other] = ids('## A\n- [ ] same\n- [ ] same\n## B\n- [ ] same');
+    expect(second).toBe(`${first}-2`);
+    expect(other).not.toBe(first);
+    expect(first).toMatch(/^[0-9a-f]{12}$/);
+  });
+});
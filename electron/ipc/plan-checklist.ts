@@ -0,0 +1,117 @@
+import { createHash } from 'crypto';
+import fs from 'fs';
+import { writeFileDurable } from './persistence.js';
+
+// Markdown plans as structured checklists: headings become sections and
+// `- [ ]` / `- [x]` lines become items, so the UI can render a plan as an
+// interactive checklist. Item ids hash the section title and text, which keeps
+// them stable while the agent inserts or moves lines around them.
+
+const MAX_PLAN_BYTES = 512 * 1024;
+
+const HEADING_RE = /^(#{1,6})\s+(.*?)\s*#*\s*$/;
+const ITEM_RE = /^(\s*)(?:[-*+]|\d+[.)])\s+\[([ xX])\]\s?(.*)$/;
+const FENCE_RE = /^\s*(```|~~~)/;
+
+export interface PlanItem {
+  id: string;
+  text: string;
+  done: boolean;
+  /** Nesting level from the indentation, 0 for top-level items. */
+  depth: number;
+  /** Zero-based line in the file. */
+  line: number;
+}
+
+export interface PlanSection {
+  /** Empty for items before the first heading. */
+  title: string;
+  /** Heading level 1-6; 0 for items before the first heading. */
+  level: number;
+  items: PlanItem[];
+}
+
+export interface ParsedPlan {
+  path: string;
+  /** The first level-1 heading, if any. */
+  title: string | null;
+  sections: PlanSection[];
+  total: number;
+  done: number;
+}
+
+function readPlanText(planPath: string): string {
+  if (fs.statSync(planPath).size > MAX_PLAN_BYTES) throw new Error('Plan file is too large');
+  return fs.readFileSync(planPath, 'utf8');
+}
+
+export function parsePlanText(planPath: string, text: string): ParsedPlan {
+  const sections: PlanSection[] = [{ title: '', level: 0, items: [] }];
+  const seen = new Map<string, number>();
+  let title: string | null = null;
+  let fence: string | null = null;
+
+  text.split('\n').forEach((raw, line) => {
+    const content = raw.replace(/\r$/, '');
+    const fenceMatch = FENCE_RE.exec(content);
+    if (fenceMatch) {
+      if (fence === null) fence = fenceMatch[1];
+      else if (fence === fenceMatch[1]) fence = null;
+      return;
+    }
+    if (fence !== null) return;
+
+    const heading = HEADING_RE.exec(content);
+    if (heading) {
+      const level = heading[1].length;
+      if (level === 1 && title === null) title = heading[2];
+      sections.push({ title: heading[2], level, items: [] });
+      return;
+    }
+    const item = ITEM_RE.exec(content);
+    if (!item) return;
+    const section = sections[sections.length - 1];
+    const key = createHash('sha1')
+      .update(`${section.title}\n${item[3].trim()}`)
+      .digest('hex')
+      .slice(0, 12);
+    // Identical items in one section are told apart by their order
+    const count = (seen.get(key) ?? 0) + 1;
+    seen.set(key, count);
+    section.items.push({
+      id: count === 1 ? key : `${key}-${count}`,
+      text: item[3].trim(),
+      done: item[2] !== ' ',
+      depth: Math.floor(item[1].replace(/\t/g, '    ').length / 2),
+      line,
+    });
+  });
+
+  const kept = sections.filter((s) => s.level > 0 || s.items.length > 0);
+  const items = kept.flatMap((s) => s.items);
+  return {
+    path: planPath,
+    title,
+    sections: kept,
+    total: items.length,
+    done: items.filter((i) => i.done).length,
+  };
+}
+
+export function parsePlan(planPath: string): ParsedPlan {
+  return parsePlanText(planPath, readPlanText(planPath));
+}
+
+/** Flip an item's checkbox in the file and return the plan as it is now. */
+export function togglePlanItem(planPath: string, itemId: string): ParsedPlan {
+  const text = readPlanText(planPath);
+  const items = parsePlanText(planPath, text).sections.flatMap((s) => s.items);
+  const item = items.find((i) => i.id === itemId);
+  if (!item) throw new Error('Plan item not found; the plan may have changed');
+
+  const lines = text.split('\n');
+  lines[item.line] = lines[item.line].replace(/\[[ xX]\]/, item.done ? '[ ]' : '[x]');
+  const next = lines.join('\n');
+  writeFileDurable(planPath, next);
+  return parsePlanText(planPath, next);
+}
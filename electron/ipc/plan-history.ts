@@ -0,0 +1,191 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+// Every version of a task's plan files, one JSON file per task under app data,
+// so users can follow how an agent's plan evolved and go back to an earlier
+// one. Revisions are recorded by the plan watcher (plans.ts) whenever a plan's
+// content changes, including removals.
+
+/** Oldest revisions are dropped beyond this, per task. */
+const MAX_REVISIONS = 200;
+/** Unchanged lines shown around each change in a diff. */
+const DIFF_CONTEXT = 3;
+/** Above this many line pairs the diff just replaces everything, to bound the work. */
+const MAX_DIFF_CELLS = 4_000_000;
+
+export interface PlanRevision {
+  /** Increasing per task, starting at 1. */
+  rev: number;
+  /** Relative to the worktree, with forward slashes. */
+  path: string;
+  agent_id: string | null;
+  ts: number;
+  /** Null when the file was removed. */
+  content: string | null;
+}
+
+const cache = new Map<string, PlanRevision[]>();
+
+function getHistoryPath(taskId: string): string {
+  if (!/^[\w-]+$/.test(taskId)) throw new Error('taskId contains invalid characters');
+  return path.join(getStateDir(), 'plan-history', `${taskId}.json`);
+}
+
+function readHistory(taskId: string): PlanRevision[] {
+  const cached = cache.get(taskId);
+  if (cached) return cached;
+  let revisions: PlanRevision[] = [];
+  try {
+    revisions = JSON.parse(fs.readFileSync(getHistoryPath(taskId), 'utf8')) as PlanRevision[];
+  } catch {
+    // No history yet
+  }
+  cache.set(taskId, revisions);
+  return revisions;
+}
+
+function writeHistory(taskId: string, revisions: PlanRevision[]): void {
+  const historyPath = getHistoryPath(taskId);
+  fs.mkdirSync(path.dirname(historyPath), { recursive: true });
+  const tmpPath = historyPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(revisions), 'utf8');
+  fs.renameSync(tmpPath, historyPath);
+  cache.set(taskId, revisions);
+}
+
+/** Snapshot a plan file; does nothing when it matches the file's latest revision. */
+export function recordPlanRevision(
+  taskId: string,
+  planPath: string,
+  agentId: string | null,
+  content: string | null,
+): PlanRevision | null {
+  const revisions = readHistory(taskId);
+  const latest = [...revisions].reverse().find((r) => r.path === planPath);
+  if (latest ? latest.content === content : content === null) return null;
+  const revision: PlanRevision = {
+    rev: (revisions[revisions.length - 1]?.rev ?? 0) + 1,
+    path: planPath,
+    agent_id: agentId,
+    ts: Date.now(),
+    content,
+  };
+  writeHistory(taskId, [...revisions, revision].slice(-MAX_REVISIONS));
+  return revision;
+}
+
+/** A task's plan revisions, oldest first. */
+export function getPlanHistory(taskId: string): PlanRevision[] {
+  return readHistory(taskId);
+}
+
+function getRevision(taskId: string, rev: unknown): PlanRevision {
+  const revision = readHistory(taskId).find((r) => r.rev === rev);
+  if (!revision) throw new Error(`Plan revision not found: ${String(rev)}`);
+  return revision;
+}
+
+type DiffOp = { op: ' ' | '-' | '+'; line: string };
+
+function splitLines(content: string | null): string[] {
+  if (!content) return [];
+  const lines = content.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  return lines;
+}
+
+/** Line diff through the longest common subsequence, after trimming the common ends. */
+function diffLines(a: string[], b: string[]): DiffOp[] {
+  let head = 0;
+  while (head < a.length && head < b.length && a[head] === b[head]) head++;
+  let tail = 0;
+  while (
+    tail < a.length - head &&
+    tail < b.length - head &&
+    a[a.length - 1 - tail] === b[b.length - 1 - tail]
+  ) {
+    tail++;
+  }
+  const midA = a.slice(head, a.length - tail);
+  const midB = b.slice(head, b.length - tail);
+  const ops: DiffOp[] = a.slice(0, head).map((line): DiffOp => ({ op: ' ', line }));
+
+  if (midA.length * midB.length > MAX_DIFF_CELLS) {
+    ops.push(...midA.map((line): DiffOp => ({ op: '-', line })));
+    ops.push(...midB.map((line): DiffOp => ({ op: '+', line })));
+  } else {
+    const n = midA.length;
+    const m = midB.length;
+    const lcs = Array.from({ length: n + 1 }, () => new Uint32Array(m + 1));
+    for (let i = n - 1; i >= 0; i--) {
+      for (let j = m - 1; j >= 0; j--) {
+        lcs[i][j] =
+          midA[i] === midB[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+      }
+    }
+    let i = 0;
+    let j = 0;
+    while (i < n && j < m) {
+      if (midA[i] === midB[j]) {
+        ops.push({ op: ' ', line: midA[i] });
+        i++;
+        j++;
+      } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+        ops.push({ op: '-', line: midA[i++] });
+      } else {
+        ops.push({ op: '+', line: midB[j++] });
+      }
+    }
+    while (i < n) ops.push({ op: '-', line: midA[i++] });
+    while (j < m) ops.push({ op: '+', line: midB[j++] });
+  }
+
+  ops.push(...a.slice(a.length - tail).map((line): DiffOp => ({ op: ' ', line })));
+  return ops;
+}
+
+function unifiedDiff(a: PlanRevision, b: PlanRevision): string {
+  const ops = diffLines(splitLines(a.content), splitLines(b.content));
+  // Lines of each side before op k, for hunk headers
+  const oldAt = [0];
+  const newAt = [0];
+  for (const { op } of ops) {
+    oldAt.push(oldAt[oldAt.length - 1] + (op === '+' ? 0 : 1));
+    newAt.push(newAt[newAt.length - 1] + (op === '-' ? 0 : 1));
+  }
+  const range = (at: number[], start: number, end: number) => {
+    const count = at[end] - at[start];
+    return `${count === 0 ? at[start] : at[start] + 1},${count}`;
+  };
+
+  const out = [`--- a/${a.path}@${a.rev}`, `+++ b/${b.path}@${b.rev}`];
+  let k = 0;
+  while (k < ops.length) {
+    if (ops[k].op === ' ') {
+      k++;
+      continue;
+    }
+    // Changes closer than twice the context share a hunk
+    let last = k;
+    for (let x = k; x < ops.length && x - last <= 2 * DIFF_CONTEXT; x++) {
+      if (ops[x].op !== ' ') last = x;
+    }
+    const start = Math.max(0, k - DIFF_CONTEXT);
+    const end = Math.min(ops.length, last + DIFF_CONTEXT + 1);
+    out.push(`@@ -${range(oldAt, start, end)} +${range(newAt, start, end)} @@`);
+    for (let x = start; x < end; x++) out.push(ops[x].op + ops[x].line);
+    k = end;
+  }
+  return out.join('\n') + '\n';
+}
+
+/** Unified diff from revision `revA` to `revB` of a task's plans. */
+export function getPlanDiff(taskId: string, revA: unknown, revB: unknown): string {
+  return unifiedDiff(getRevision(taskId, revA), getRevision(taskId, revB));
+}
+
+export function deletePlanHistory(taskId: string): void {
+  cache.delete(taskId);
+  fs.rmSync(getHistoryPath(taskId), { force: true });
+}
@@ -0,0 +1,96 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'plans-'));
+vi.mock('electron', () => ({ app: { getPath: () => root, isPackaged: true } }));
+// archive.ts pulls in tasks and recordings (and with them the PTY layer)
+vi.mock('./archive.js', () => ({ PLAN_PATTERNS: ['PLAN.md'] }));
+
+import { hashPlan, PlanConflict, writePlanFile } from './plans.js';
+
+let worktree: string;
+
+beforeEach(() => {
+  worktree = fs.mkdtempSync(path.join(root, 'worktree-'));
+});
+
+const readPlan = (rel: string) => fs.readFileSync(path.join(worktree, rel), 'utf8');
+const tempFiles = (dir: string) => fs.readdirSync(dir).filter((name) => name.endsWith('.tmp'));
+
+/** The error writePlanFile throws, which the test expects to be a PlanConflict. */
+function conflictOf(write: () => void): PlanConflict {
+  try {
+    write();
+  } catch (e) {
+    if (e instanceof PlanConflict) return e;
+    throw e;
+  }
+  throw new Error('Expected a PlanConflict');
+}
+
+// ---------------------------------------------------------------------------
+// writePlanFile
+// ---------------------------------------------------------------------------
+describe('writePlanFile', () => {
+  it('writes through a temp file of its own and leaves none behind', () => {
+    const opened: string[] = [];
+    const openSync = fs.openSync;
+    const spy = vi.spyOn(fs, 'openSync').mockImplementation((file, flags) => {
+      opened.push(String(file));
+      return openSync(file, flags);
+    });
+    const first = writePlanFile(worktree, '.claude/plans/plan.md', '# one\n');
+    const second = writePlanFile(worktree, '.claude/plans/plan.md', '# two\n');
+    spy.mockRestore();
+
+    expect(first).toBe(hashPlan('# one\n'));
+    expect(second).toBe(hashPlan('# two\n'));
+    expect(readPlan('.claude/plans/plan.md')).toBe('# two\n');
+    const temps = opened.filter((file) => file.endsWith('.tmp'));
+    expect(temps).toHaveLength(2);
+    expect(temps[0]).not.toBe(temps[1]);
+    expect(temps[0]).toMatch(/plan\.md\.\d+\.[0-9a-f]{12}\.tmp$/);
+    expect(tempFiles(path.join(worktree, '.claude', 'plans'))).toEqual([]);
+  });
+
+  it('writes over the version the caller read', () => {
+    fs.writeFileSync(path.join(worktree, 'PLAN.md'), '# plan\n');
+    writePlanFile(worktree, 'PLAN.md', '# edited\n', hashPlan('# plan\n'));
+    expect(readPlan('PLAN.md')).toBe('# edited\n');
+  });
+
+  it.each([
+    ['the file changed', '# agent update\n', hashPlan('# plan\n')],
+    ['a new file already exists', '# agent update\n', null],
+    ['the file was removed', null, hashPlan('# plan\n')],
+  ])('refuses the write when %s', (_name, current, expectedHash) => {
+    if (current !== null) fs.writeFileSync(path.join(worktree, 'PLAN.md'), current);
+
+    const conflict = conflictOf(() => writePlanFile(worktree, 'PLAN.md', '# mine\n', expectedHash));
+    expect(conflict.path).toBe('PLAN.md');
+    expect(conflict.currentHash).toBe(current === null ? null : hashPlan(current));
+    expect(fs.existsSync(path.join(worktree, 'PLAN.md'))).toBe(current !== null);
+    if (current !== null) expect(readPlan('PLAN.md')).toBe(current);
+  });
+
+  it('checks again before replacing the file', () => {
+    const file = path.join(worktree, 'PLAN.md');
+    fs.writeFileSync(file, '# plan\n');
+    const fsyncSync = fs.fsyncSync;
+    const spy = vi.spyOn(fs, 'fsyncSync').mockImplementation((fd) => {
+      // The agent rewrites the plan while the new content is flushed
+      fs.writeFileSync(file, '# agent update\n');
+      fsyncSync(fd);
+    });
+    const conflict = conflictOf(() =>
+      writePlanFile(worktree, 'PLAN.md', '# mine\n', hashPlan('# plan\n')),
+    );
+    spy.mockRestore();
+
+    expect(conflict.currentHash).toBe(hashPlan('# agent update\n'));
+    expect(readPlan('PLAN.md')).toBe('# agent update\n');
+    expect(tempFiles(worktree)).toEqual([]);
+  });
+});
@@ -0,0 +1,367 @@
+import { createHash, randomBytes } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { listAgents, type AgentDef } from './agents.js';
+import { PLAN_PATTERNS } from './archive.js';
+import { expandCopyPatterns, loadGitignore, matchesGlob } from './git.js';
+import { recordPlanRevision } from './plan-history.js';
+import { writeFileDurable } from './persistence.js';
+import { subscribe, type ChangeKind } from './watcher.js';
+
+// Plan files agents write while they work. Every agent has its own convention
+// (Claude Code keeps plans under .claude/plans, Codex a PLANS.md at the root),
+// so a task watches the directories of each agent it runs and tags every
+// `plan-changed` event with the agent whose patterns matched the file. Each
+// change is also snapshotted into the task's plan history (plan-history.ts).
+// Paths are debounced one by one; glob matches anywhere in the tree skip
+// gitignored paths (node_modules, build output), while plans at a pattern's
+// fixed location are always kept. When the watcher may have missed events the
+// patterns are expanded again and compared with what was last seen.
+
+/** Built-in conventions, keyed by the agent's command; an agent's plan_patterns replace them. */
+const PLAN_CONVENTIONS: Record<string, string[]> = {
+  claude: ['.claude/plans/**/*.md'],
+  codex: ['PLANS.md', 'PLAN.md', '.codex/plans/**/*.md'],
+  aider: ['PLAN.md', '*.plan.md', 'plans/**/*.md'],
+};
+
+/** Plan files bigger than this are reported without their content. */
+const MAX_PLAN_BYTES = 512 * 1024;
+
+export interface PlanFile {
+  agent_id: string;
+  /** Relative to the worktree, with forward slashes. */
+  path: string;
+  /** Null when the file is gone, unreadable or too big. */
+  content: string | null;
+  /** SHA-256 of the content, to pass back as write_plan_file's expected hash. */
+  hash: string | null;
+}
+
+/** Thrown by writePlanFile when the file changed since the caller read it. */
+export class PlanConflict extends Error {
+  constructor(
+    readonly path: string,
+    readonly currentHash: string | null,
+  ) {
+    super(`${path} changed since it was read; reload it and apply the edit again`);
+    this.name = 'PlanConflict';
+  }
+}
+
+export interface PlanChangedEvent extends PlanFile {
+  task_id: string;
+  kind: ChangeKind;
+}
+
+interface PlanAgent {
+  id: string;
+  patterns: string[];
+}
+
+interface WatchRoot {
+  /** Relative to the worktree; empty for the worktree itself. */
+  dir: string;
+  recursive: boolean;
+}
+
+/** A task's plan watch, for list_active_watchers. */
+export interface PlanWatchInfo {
+  task_id: string;
+  worktree_path: string;
+  agent_ids: string[];
+  /** Directories watched for the task's agents. */
+  subscriptions: number;
+  plans: number;
+}
+
+interface PlanWatch {
+  taskId: string;
+  worktreePath: string;
+  agents: PlanAgent[];
+  ignored: (rel: string) => boolean;
+  /** Plans seen so far and their last content, to tell what a rescan changed. */
+  known: Map<string, { agentId: string; content: string | null }>;
+  unsubscribes: (() => void)[];
+}
+
+const watches = new Map<string, PlanWatch>();
+/** Hash writePlanFile last wrote, by file, until the file changes to something else. */
+const ownWrites = new Map<string, string>();
+const listeners = new Set<(event: PlanChangedEvent) => void>();
+let mainWindow: BrowserWindow | null = null;
+
+export function planPatternsFor(agent: AgentDef): string[] {
+  if (agent.plan_patterns && agent.plan_patterns.length > 0) return agent.plan_patterns;
+  return PLAN_CONVENTIONS[path.parse(agent.command).name] ?? PLAN_PATTERNS;
+}
+
+/** The directory a pattern's matches live under, falling back to an existing ancestor. */
+function watchRoot(worktreePath: string, pattern: string): WatchRoot {
+  const segments = pattern.split('/').filter(Boolean);
+  let fixed = 0;
+  while (fixed < segments.length - 1 && !/[*?]/.test(segments[fixed])) fixed++;
+  const rest = segments.slice(fixed);
+  let dir = segments.slice(0, fixed).join('/');
+  let recursive = rest.length > 1 || rest[0] === '**';
+  // The agent may create the directory later, so watch the nearest one that exists
+  while (dir && !fs.existsSync(path.join(worktreePath, dir))) {
+    dir = dir.includes('/') ? dir.slice(0, dir.lastIndexOf('/')) : '';
+    recursive = true;
+  }
+  return { dir, recursive };
+}
+
+/** Drop roots that another recursive root already sees. */
+function dedupeRoots(roots: WatchRoot[]): WatchRoot[] {
+  const covers = (a: WatchRoot, b: WatchRoot) =>
+    a.recursive && (a.dir === '' || b.dir === a.dir || b.dir.startsWith(a.dir + '/'));
+  const unique = [...new Map(roots.map((r) => [`${r.recursive}:${r.dir}`, r])).values()];
+  return unique.filter((r) => !unique.some((other) => other !== r && covers(other, r)));
+}
+
+/** Whether a pattern names its location, e.g. `PLAN.md` or `.claude/plans/*.md`. */
+const isAnchored = (pattern: string) => !/[*?]/.test(pattern.split('/')[0]);
+
+function agentFor(w: PlanWatch, rel: string): string | null {
+  const matches = (p: string) => matchesGlob(rel, p) && (isAnchored(p) || !w.ignored(rel));
+  return w.agents.find((a) => a.patterns.some(matches))?.id ?? null;
+}
+
+function readPlan(file: string): string | null {
+  try {
+    const stat = fs.statSync(file);
+    if (!stat.isFile() || stat.size > MAX_PLAN_BYTES) return null;
+    return fs.readFileSync(file, 'utf8');
+  } catch {
+    return null;
+  }
+}
+
+export function hashPlan(content: string): string {
+  return createHash('sha256').update(content).digest('hex');
+}
+
+function snapshot(taskId: string, rel: string, agentId: string, content: string | null): void {
+  try {
+    recordPlanRevision(taskId, rel, agentId, content);
+  } catch (e) {
+    console.warn('Could not record plan revision:', e);
+  }
+}
+
+function report(
+  w: PlanWatch,
+  rel: string,
+  agentId: string,
+  kind: ChangeKind,
+  content: string | null,
+): void {
+  if (kind === 'removed') w.known.delete(rel);
+  else w.known.set(rel, { agentId, content });
+  if (kind === 'removed' || content !== null) snapshot(w.taskId, rel, agentId, content);
+  const event: PlanChangedEvent = {
+    task_id: w.taskId,
+    agent_id: agentId,
+    path: rel,
+    kind,
+    content,
+    hash: content === null ? null : hashPlan(content),
+  };
+  const file = path.join(w.worktreePath, rel);
+  if (ownWrites.has(file) && ownWrites.get(file) !== event.hash) ownWrites.delete(file);
+  for (const listener of listeners) listener(event);
+  if (mainWindow && !mainWindow.isDestroyed()) {
+    mainWindow.webContents.send(IPC.PlanChanged, event);
+  }
+}
+
+/** Plan files in the worktree now, with the agent each belongs to. */
+async function findPlans(w: PlanWatch): Promise<Map<string, string>> {
+  const found = new Map<string, string>();
+  for (const agent of w.agents) {
+    for (const rel of await expandCopyPatterns(w.worktreePath, agent.patterns)) {
+      const agentId = agentFor(w, rel);
+      if (!found.has(rel) && agentId && isFile(path.join(w.worktreePath, rel))) {
+        found.set(rel, agentId);
+      }
+    }
+  }
+  return found;
+}
+
+function isFile(file: string): boolean {
+  try {
+    return fs.statSync(file).isFile();
+  } catch {
+    return false;
+  }
+}
+
+/** Report whatever differs from the last known state, after events may have been lost. */
+async function rescan(w: PlanWatch): Promise<void> {
+  const found = await findPlans(w);
+  if (watches.get(w.taskId) !== w) return;
+  for (const [rel, agentId] of found) {
+    const content = readPlan(path.join(w.worktreePath, rel));
+    const known = w.known.get(rel);
+    if (!known) report(w, rel, agentId, 'created', content);
+    else if (known.content !== content) report(w, rel, agentId, 'modified', content);
+  }
+  for (const [rel, known] of [...w.known]) {
+    if (!found.has(rel)) report(w, rel, known.agentId, 'removed', null);
+  }
+}
+
+function closeWatch(w: PlanWatch): void {
+  for (const unsubscribe of w.unsubscribes) unsubscribe();
+}
+
+/**
+ * Watch a task's worktree for the plan files of the given agents, replacing
+ * an existing watch for the task. Returns the plan files already there.
+ */
+export async function watchForPlans(
+  taskId: string,
+  worktreePath: string,
+  agentIds: string[],
+): Promise<PlanFile[]> {
+  unwatchPlans(taskId);
+  const defs = listAgents();
+  const agents: PlanAgent[] = [...new Set(agentIds)].map((id) => {
+    const def = defs.find((a) => a.id === id);
+    if (!def) throw new Error(`Agent not found: ${id}`);
+    return { id, patterns: planPatternsFor(def) };
+  });
+  const w: PlanWatch = {
+    taskId,
+    worktreePath,
+    agents,
+    ignored: loadGitignore(worktreePath),
+    known: new Map(),
+    unsubscribes: [],
+  };
+
+  const roots = dedupeRoots(
+    agents.flatMap((a) => a.patterns.map((p) => watchRoot(worktreePath, p))),
+  );
+  try {
+    for (const root of roots) {
+      const join = (rel: string) => (root.dir ? `${root.dir}/${rel}` : rel);
+      const unsubscribe = subscribe(
+        path.join(worktreePath, root.dir),
+        {
+          recursive: root.recursive,
+          perPath: true,
+          filter: (rel) => agentFor(w, join(rel)) !== null,
+          onRescan: () => void rescan(w),
+        },
+        (changes) => {
+          for (const change of changes) {
+            const rel = join(change.path);
+            const agentId = agentFor(w, rel);
+            if (!agentId) continue;
+            const content =
+              change.kind === 'removed' ? null : readPlan(path.join(worktreePath, rel));
+            report(w, rel, agentId, change.kind, content);
+          }
+        },
+      );
+      w.unsubscribes.push(unsubscribe);
+    }
+  } catch (e) {
+    closeWatch(w);
+    throw e;
+  }
+  watches.set(taskId, w);
+
+  const existing: PlanFile[] = [];
+  const found = await findPlans(w);
+  // A later call for the task may have replaced this watch during the scan
+  const current = watches.get(taskId) === w;
+  for (const [rel, agentId] of found) {
+    const content = readPlan(path.join(worktreePath, rel));
+    if (current) w.known.set(rel, { agentId, content });
+    if (current && content !== null) snapshot(taskId, rel, agentId, content);
+    const hash = content === null ? null : hashPlan(content);
+    existing.push({ agent_id: agentId, path: rel, content, hash });
+  }
+  return existing;
+}
+
+/**
+ * Replace a plan file atomically, e.g. with a revision from the plan history.
+ * With `expectedHash` (the hash the caller's edit started from, null for a new
+ * file) the write is refused with a PlanConflict if the file changed since, so
+ * a human edit and an agent update can't silently overwrite each other.
+ * Returns the new hash.
+ */
+export function writePlanFile(
+  worktreePath: string,
+  planPath: string,
+  content: string,
+  expectedHash?: string | null,
+): string {
+  const file = path.join(worktreePath, planPath);
+  const checkUnchanged = () => {
+    if (expectedHash === undefined) return;
+    let current: string | null = null;
+    try {
+      current = fs.readFileSync(file, 'utf8');
+    } catch (e) {
+      if ((e as NodeJS.ErrnoException).code !== 'ENOENT') throw e;
+    }
+    const currentHash = current === null ? null : hashPlan(current);
+    if (currentHash !== expectedHash) throw new PlanConflict(planPath, currentHash);
+  };
+  checkUnchanged();
+  fs.mkdirSync(path.dirname(file), { recursive: true });
+  const hash = hashPlan(content);
+  writeFileDurable(file, content, {
+    // Two saves of the same plan must not share a temp file
+    tmpPath: `${file}.${process.pid}.${randomBytes(6).toString('hex')}.tmp`,
+    beforeRename: () => {
+      // The agent may have rewritten the plan while the new content was flushed
+      checkUnchanged();
+      ownWrites.set(file, hash);
+    },
+  });
+  return hash;
+}
+
+/** Whether a plan change is writePlanFile's own write rather than the agent's. */
+export function isOwnPlanWrite(event: PlanChangedEvent): boolean {
+  const w = watches.get(event.task_id);
+  if (!w || event.hash === null) return false;
+  return ownWrites.get(path.join(w.worktreePath, event.path)) === event.hash;
+}
+
+export function unwatchPlans(taskId: string): void {
+  const w = watches.get(taskId);
+  if (w) closeWatch(w);
+  watches.delete(taskId);
+}
+
+export function listPlanWatches(): PlanWatchInfo[] {
+  return [...watches.values()].map((w) => ({
+    task_id: w.taskId,
+    worktree_path: w.worktreePath,
+    agent_ids: w.agents.map((a) => a.id),
+    subscriptions: w.unsubscribes.length,
+    plans: w.known.size,
+  }));
+}
+
+/** Subscribe to plan changes in-process. Returns an unsubscribe function. */
+export function onPlanChanged(listener: (event: PlanChangedEvent) => void): () => void {
+  listeners.add(listener);
+  return () => {
+    listeners.delete(listener);
+  };
+}
+
+export function initPlans(win: BrowserWindow): void {
+  mainWindow = win;
+}
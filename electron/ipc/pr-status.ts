@@ -0,0 +1,220 @@
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import {
+  apiRequest,
+  getPullRequestRef,
+  listPullRequestRefs,
+  type PullRequestRef,
+} from './integrations.js';
+
+const POLL_INTERVAL = 60_000; // ms
+
+export type CheckState = 'none' | 'pending' | 'success' | 'failure';
+export type ReviewState = 'none' | 'review_required' | 'approved' | 'changes_requested';
+
+export interface CheckItem {
+  name: string;
+  state: Exclude<CheckState, 'none'>;
+  url: string | null;
+}
+
+export interface PullRequestStatus {
+  task_id: string;
+  url: string;
+  state: 'open' | 'closed' | 'merged';
+  draft: boolean;
+  checks: CheckState;
+  check_items: CheckItem[];
+  review: ReviewState;
+  /** null while the host is still computing it. */
+  mergeable: boolean | null;
+}
+
+function summarizeChecks(items: CheckItem[]): CheckState {
+  if (items.length === 0) return 'none';
+  if (items.some((c) => c.state === 'failure')) return 'failure';
+  if (items.some((c) => c.state === 'pending')) return 'pending';
+  return 'success';
+}
+
+// --- GitHub ---
+
+interface GhPull {
+  state: 'open' | 'closed';
+  merged: boolean;
+  draft: boolean;
+  mergeable: boolean | null;
+  head: { sha: string };
+}
+
+interface GhCheckRun {
+  name: string;
+  status: string;
+  conclusion: string | null;
+  html_url: string | null;
+}
+
+interface GhStatus {
+  context: string;
+  state: 'error' | 'failure' | 'pending' | 'success';
+  target_url: string | null;
+}
+
+interface GhReview {
+  user: { login: string } | null;
+  state: string;
+}
+
+function checkRunState(run: GhCheckRun): CheckItem['state'] {
+  if (run.status !== 'completed') return 'pending';
+  return ['success', 'neutral', 'skipped'].includes(run.conclusion ?? '') ? 'success' : 'failure';
+}
+
+async function githubStatus(taskId: string, pr: PullRequestRef): Promise<PullRequestStatus> {
+  const base = `/repos/${pr.repo}`;
+  const pull = await apiRequest<GhPull>(pr, 'GET', `${base}/pulls/${pr.number}`);
+  const [runs, combined, reviews] = await Promise.all([
+    apiRequest<{ check_runs: GhCheckRun[] }>(
+      pr,
+      'GET',
+      `${base}/commits/${pull.head.sha}/check-runs?per_page=100`,
+    ),
+    apiRequest<{ statuses: GhStatus[] }>(pr, 'GET', `${base}/commits/${pull.head.sha}/status`),
+    apiRequest<GhReview[]>(pr, 'GET', `${base}/pulls/${pr.number}/reviews?per_page=100`),
+  ]);
+
+  const items: CheckItem[] = [
+    ...runs.check_runs.map((r) => ({ name: r.name, state: checkRunState(r), url: r.html_url })),
+    ...combined.statuses.map((s) => ({
+      name: s.context,
+      state: s.state === 'success' || s.state === 'pending' ? s.state : ('failure' as const),
+      url: s.target_url,
+    })),
+  ];
+
+  // Each reviewer's latest decisive review counts
+  const latest = new Map<string, string>();
+  for (const r of reviews) {
+    if (r.user && (r.state === 'APPROVED' || r.state === 'CHANGES_REQUESTED')) {
+      latest.set(r.user.login, r.state);
+    }
+  }
+  const decisions = [...latest.values()];
+  const review: ReviewState = decisions.includes('CHANGES_REQUESTED')
+    ? 'changes_requested'
+    : decisions.includes('APPROVED')
+      ? 'approved'
+      : 'review_required';
+
+  return {
+    task_id: taskId,
+    url: pr.url,
+    state: pull.merged ? 'merged' : pull.state,
+    draft: pull.draft,
+    checks: summarizeChecks(items),
+    check_items: items,
+    review,
+    mergeable: pull.mergeable,
+  };
+}
+
+// --- GitLab ---
+
+interface GlMergeRequest {
+  state: 'opened' | 'closed' | 'merged' | 'locked';
+  draft: boolean;
+  detailed_merge_status?: string;
+  merge_status?: string;
+  head_pipeline: { status: string; web_url: string } | null;
+}
+
+function pipelineState(status: string): CheckItem['state'] {
+  if (status === 'success') return 'success';
+  if (['failed', 'canceled'].includes(status)) return 'failure';
+  return 'pending';
+}
+
+async function gitlabStatus(taskId: string, pr: PullRequestRef): Promise<PullRequestStatus> {
+  const base = `/projects/${encodeURIComponent(pr.repo)}/merge_requests/${pr.number}`;
+  const [mr, approvals] = await Promise.all([
+    apiRequest<GlMergeRequest>(pr, 'GET', base),
+    apiRequest<{ approved?: boolean; approvals_left?: number }>(pr, 'GET', `${base}/approvals`),
+  ]);
+
+  const items: CheckItem[] = mr.head_pipeline
+    ? [
+        {
+          name: 'pipeline',
+          state: pipelineState(mr.head_pipeline.status),
+          url: mr.head_pipeline.web_url,
+        },
+      ]
+    : [];
+  const mergeStatus = mr.detailed_merge_status ?? mr.merge_status ?? '';
+  const computing = ['checking', 'unchecked', 'preparing', 'approvals_syncing'].includes(
+    mergeStatus,
+  );
+
+  return {
+    task_id: taskId,
+    url: pr.url,
+    state: mr.state === 'merged' ? 'merged' : mr.state === 'opened' ? 'open' : 'closed',
+    draft: mr.draft,
+    checks: summarizeChecks(items),
+    check_items: items,
+    review: approvals.approved || approvals.approvals_left === 0 ? 'approved' : 'review_required',
+    mergeable: computing ? null : mergeStatus === 'mergeable' || mergeStatus === 'can_be_merged',
+  };
+}
+
+// --- Commands ---
+
+function fetchStatus(taskId: string, pr: PullRequestRef): Promise<PullRequestStatus> {
+  return pr.provider === 'github' ? githubStatus(taskId, pr) : gitlabStatus(taskId, pr);
+}
+
+export async function getPullRequestStatus(taskId: string): Promise<PullRequestStatus | null> {
+  const pr = getPullRequestRef(taskId);
+  return pr ? fetchStatus(taskId, pr) : null;
+}
+
+// Last emitted status per task, to send pr-status-changed only on change
+const lastStatus = new Map<string, string>();
+let pollTimer: ReturnType<typeof setInterval> | null = null;
+
+type StatusListener = (status: PullRequestStatus, previous: PullRequestStatus | null) => void;
+const statusListeners = new Set<StatusListener>();
+
+/** Follow status changes found by polling. Returns an unsubscribe function. */
+export function onPullRequestStatusChanged(listener: StatusListener): () => void {
+  statusListeners.add(listener);
+  return () => {
+    statusListeners.delete(listener);
+  };
+}
+
+async function pollOnce(win: BrowserWindow): Promise<void> {
+  for (const [taskId, pr] of Object.entries(listPullRequestRefs())) {
+    const prev = lastStatus.get(taskId);
+    // Closed and merged PRs don't change any more
+    if (prev && JSON.parse(prev).state !== 'open') continue;
+    try {
+      const status = await fetchStatus(taskId, pr);
+      const serialized = JSON.stringify(status);
+      if (serialized === prev) continue;
+      lastStatus.set(taskId, serialized);
+      if (!win.isDestroyed()) win.webContents.send(IPC.PrStatusChanged, status);
+      const previous = prev ? (JSON.parse(prev) as PullRequestStatus) : null;
+      statusListeners.forEach((fn) => fn(status, previous));
+    } catch {
+      /* offline, token revoked or rate-limited — retry next tick */
+    }
+  }
+}
+
+/** Poll every recorded pull request and emit `pr-status-changed` on changes. */
+export function startPullRequestPolling(win: BrowserWindow): void {
+  if (pollTimer) return;
+  void pollOnce(win);
+  pollTimer = setInterval(() => void pollOnce(win), POLL_INTERVAL);
+}
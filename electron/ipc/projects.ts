@@ -0,0 +1,71 @@
+import path from 'path';
+import { forgetRepoSettings, getMainBranch, getRepoRoot, getRepoSettingsFor } from './git.js';
+import { loadAppState } from './persistence.js';
+import type { RepoSettings } from './repo-settings.js';
+import { getSettings } from './settings.js';
+import {
+  getWorktreeLocation,
+  hasWorktreeLocation,
+  saveWorktreeLocation,
+  type WorktreeLocation,
+} from './worktree-location.js';
+
+// Projects are registered repositories. The renderer owns the list (it's
+// part of the persisted app state); this module validates repositories as
+// they're added and exposes the list with each repo's backend settings.
+
+export interface ProjectInfo {
+  /** Repository top level, even when a subdirectory was picked. */
+  root: string;
+  name: string;
+  main_branch: string;
+}
+
+export interface RegisteredProject {
+  id: string;
+  name: string;
+  path: string;
+  settings: RepoSettings;
+  worktree_location: WorktreeLocation;
+}
+
+/**
+ * Check that `dir` is inside a git repository and describe it for
+ * registration. New repositories get the default worktree location.
+ */
+export async function addProject(dir: string): Promise<ProjectInfo> {
+  const root = await getRepoRoot(dir);
+  const { worktree_location } = getSettings();
+  if (!hasWorktreeLocation(root) && worktree_location.mode !== 'repo') {
+    saveWorktreeLocation(root, worktree_location);
+  }
+  return {
+    root,
+    name: path.basename(root) || root,
+    main_branch: await getMainBranch(root),
+  };
+}
+
+export async function listProjects(): Promise<RegisteredProject[]> {
+  let projects: Array<{ id: string; name: string; path: string }> = [];
+  try {
+    const state = JSON.parse(loadAppState() ?? '{}') as { projects?: typeof projects };
+    projects = Array.isArray(state.projects) ? state.projects : [];
+  } catch {
+    /* corrupt state — the renderer reports it on load */
+  }
+  return Promise.all(
+    projects.map(async (p) => ({
+      id: p.id,
+      name: p.name,
+      path: p.path,
+      settings: await getRepoSettingsFor(p.path).catch(() => ({})),
+      worktree_location: getWorktreeLocation(p.path),
+    })),
+  );
+}
+
+/** Forget the backend settings of a project that's being unregistered. */
+export async function removeProject(projectRoot: string): Promise<void> {
+  await forgetRepoSettings(projectRoot);
+}
@@ -0,0 +1,117 @@
+import { randomUUID } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+import { getChangedFiles } from './git.js';
+import { getStateDir } from './persistence.js';
+import { getAgentCwd, getAgentMeta, writeToAgent } from './pty.js';
+import { getKnownTask } from './task-metadata.js';
+
+// Saved prompts shared across tasks. `{{variables}}` are filled in when a
+// prompt is sent: the built-in ones describe the agent's task, anything else
+// comes from the caller.
+
+export interface PromptSnippet {
+  id: string;
+  name: string;
+  /** May contain {{title}}, {{branch}}, {{changed_files}} and custom {{variables}}. */
+  text: string;
+}
+
+/** Same pause as the renderer's sendPrompt between the text and Enter. */
+const ENTER_DELAY_MS = 50;
+
+let cached: PromptSnippet[] | null = null;
+
+function getSnippetsPath(): string {
+  return path.join(getStateDir(), 'prompt-library.json');
+}
+
+export function listPromptSnippets(): PromptSnippet[] {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getSnippetsPath(), 'utf8')) as PromptSnippet[];
+  } catch {
+    cached = [];
+  }
+  return cached;
+}
+
+function writeSnippets(snippets: PromptSnippet[]): void {
+  const snippetsPath = getSnippetsPath();
+  fs.mkdirSync(path.dirname(snippetsPath), { recursive: true });
+  const tmpPath = snippetsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(snippets, null, 2), 'utf8');
+  fs.renameSync(tmpPath, snippetsPath);
+  cached = snippets;
+}
+
+/** Create a snippet, or replace the one with the same id. New ones keep a given id. */
+export function savePromptSnippet(value: unknown): PromptSnippet {
+  const s = value as Partial<Record<keyof PromptSnippet, unknown>> | null;
+  if (typeof s?.name !== 'string' || !s.name.trim()) throw new Error('name must not be empty');
+  if (typeof s.text !== 'string' || !s.text.trim()) throw new Error('text must not be empty');
+  const snippets = [...listPromptSnippets()];
+  const givenId = typeof s.id === 'string' && s.id ? s.id : null;
+  const index = givenId ? snippets.findIndex((p) => p.id === givenId) : -1;
+  const snippet: PromptSnippet = {
+    id: givenId ?? randomUUID(),
+    name: s.name.trim(),
+    text: s.text,
+  };
+  if (index === -1) snippets.push(snippet);
+  else snippets[index] = snippet;
+  writeSnippets(snippets);
+  return snippet;
+}
+
+export function deletePromptSnippet(id: string): void {
+  writeSnippets(listPromptSnippets().filter((p) => p.id !== id));
+}
+
+/** Names of the {{variables}} in `text`, in order of first use. */
+export function promptVariables(text: string): string[] {
+  return [...new Set([...text.matchAll(/\{\{\s*([A-Za-z0-9_-]+)\s*\}\}/g)].map((m) => m[1]))];
+}
+
+/** Fill {{variables}}; unknown ones are left as typed. */
+export function renderPromptSnippet(text: string, values: Record<string, string>): string {
+  return text.replace(/\{\{\s*([A-Za-z0-9_-]+)\s*\}\}/g, (m, key: string) => values[key] ?? m);
+}
+
+/** Built-in variables for the task an agent works on; only computes what `text` uses. */
+async function taskVariables(agentId: string, text: string): Promise<Record<string, string>> {
+  const used = new Set(promptVariables(text));
+  const values: Record<string, string> = {};
+  const task = getKnownTask(getAgentMeta(agentId)?.taskId ?? '');
+  if (task) {
+    values.title = task.name;
+    if (task.branch_name) values.branch = task.branch_name;
+  }
+  if (used.has('changed_files')) {
+    const files = await getChangedFiles(getAgentCwd(agentId)).catch(() => []);
+    values.changed_files = files.map((f) => `- ${f.path}`).join('\n') || '(no changes yet)';
+  }
+  return values;
+}
+
+/**
+ * Send a saved prompt to an agent, followed by Enter. `vars` fill custom
+ * variables and override built-in ones. Returns the text that was sent.
+ */
+export async function sendPromptSnippet(
+  agentId: string,
+  promptId: string,
+  vars: Record<string, string> = {},
+): Promise<string> {
+  const snippet = listPromptSnippets().find((p) => p.id === promptId);
+  if (!snippet) throw new Error(`Prompt not found: ${promptId}`);
+  const text = renderPromptSnippet(snippet.text, {
+    ...(await taskVariables(agentId, snippet.text)),
+    ...vars,
+  });
+  // Text and Enter go separately so TUIs don't take the \r as part of a paste
+  await writeToAgent(agentId, text, { paste: true });
+  await new Promise((r) => setTimeout(r, ENTER_DELAY_MS));
+  await writeToAgent(agentId, '\r');
+  return text;
+}
@@ -1,22 +1,129 @@
 import * as pty from 'node-pty';
+import fs from 'fs';
 import type { BrowserWindow } from 'electron';
 import { RingBuffer } from '../remote/ring-buffer.js';
+import { incompleteUtf8Tail } from '../remote/utf8.js';
+import { spawnDetachedPty, connectDetachedPty, type DetachedPty } from './detached.js';
+import { classifyExit, windowsExitSignal } from './exit-reason.js';
+import { startRecording, type Recorder } from './recordings.js';
+import { createIdleTracker, DEFAULT_IDLE_MS, type IdleTracker } from './idle.js';
+import { createPatternMatcher, type PatternMatcher } from './triggers.js';
+import { createPermissionDetector, type PermissionDetector } from './permissions.js';
+import {
+  createOutputThrottle,
+  DEFAULT_THROTTLE,
+  mergeThrottleOptions,
+  validateThrottleOptions,
+  type OutputThrottle,
+  type ThrottleOptions,
+} from './throttle.js';
+import { isKeyName, keySequence } from './keys.js';
+import { checkSpawnPolicy } from './spawn-policy.js';
+import { log } from './logs.js';
+import { isSecretRef, resolveSecretRefs } from './secrets.js';
+import {
+  createOscParser,
+  parseCwdSequence,
+  parseNotificationSequence,
+  parseTitleSequence,
+  type OscParser,
+  type OscSequence,
+} from './osc.js';
+import { IPC } from './channels.js';
+
+/** The subset of node-pty's IPty used by sessions (also implemented by DetachedPty). */
+type PtyProcess = Pick<
+  pty.IPty,
+  'pid' | 'cols' | 'write' | 'resize' | 'pause' | 'resume' | 'kill' | 'onData' | 'onExit'
+>;
 
 interface PtySession {
-  proc: pty.IPty;
+  proc: PtyProcess;
+  /** Primary output channel; owns flow control and backpressure acks. */
   channelId: string;
+  /** Additional viewers attached via attachOutputChannel. */
+  extraChannels: Set<string>;
   taskId: string;
   agentId: string;
   flushTimer: ReturnType<typeof setTimeout> | null;
   subscribers: Set<(encoded: string) => void>;
   scrollback: RingBuffer;
+  /** Set for sessions owned by a session host; closes the connection without killing. */
+  detach: (() => void) | null;
+  /** How the app asked the process to stop, or null if it exited on its own. */
+  killPath: KillPath | null;
+  killTimer: ReturnType<typeof setTimeout> | null;
+  recorder: Recorder | null;
+  /** Process group stopped via SIGSTOP. */
+  suspended: boolean;
+  idle: IdleTracker;
+  triggers: PatternMatcher;
+  permissions: PermissionDetector;
+  /** Counts toward the concurrent agent limit (see spawn-queue.ts). */
+  queueable: boolean;
+  /** Whether the app enabled bracketed paste mode (DECSET 2004). */
+  bracketedPaste: boolean;
+  /** Whether the app enabled application cursor keys (DECCKM). */
+  applicationCursor: boolean;
+  /** Tail of ordered writes; chunked pastes append to it so input never interleaves. */
+  writeChain: Promise<void>;
+  pendingPastes: number;
+  /** Original spawn parameters (null for sessions reattached from a host). */
+  spawnArgs: SpawnAgentArgs | null;
+  /** Set by restartAgent; runs once the old process has exited. */
+  restart: (() => void) | null;
+  /** Effective environment the process was spawned with. */
+  env: Record<string, string> | null;
+  throttle: OutputThrottle;
+  tuning: OutputTuning;
+  command: string;
+  args: string[];
+  startedAt: number;
+  /** Last directory reported via OSC 7 (or similar); starts as the spawn cwd. */
+  cwd: string;
+  /** Last title set via OSC 0/2. */
+  title: string | null;
+  osc: OscParser;
+  /** Runs exit handling once, from onExit or from the reaper. */
+  finish: (exitCode: number | null, signal?: number) => void;
+  exited: boolean;
+  /** Renderer asked us to stop reading (xterm.js is behind). */
+  outputPaused: boolean;
+  /** Bytes sent to the renderer that it hasn't acknowledged yet. */
+  unackedBytes: number;
+  /** Reads paused because unackedBytes crossed the high-water mark. */
+  backpressured: boolean;
+}
+
+interface OrphanGroup {
+  /** pid -> start time, so a reused pid or group ID is never signalled. */
+  members: Map<number, string>;
+  attempts: number;
 }
 
+/**
+ * `immediate`: hard kill. `graceful`: SIGTERM was enough. `escalated`:
+ * SIGTERM timed out and SIGKILL followed.
+ */
+export type KillPath = 'immediate' | 'graceful' | 'escalated';
+
 const sessions = new Map<string, PtySession>();
+let defaultThrottle: ThrottleOptions = { ...DEFAULT_THROTTLE };
+/** Leftover members of exited agents' process groups, keyed by group ID. */
+const orphanGroups = new Map<number, OrphanGroup>();
+/** Sessions whose process was gone on the last reaper pass without an onExit. */
+const vanished = new WeakSet<PtySession>();
 
 // --- PTY event bus for spawn/exit notifications ---
 
-type PtyEventType = 'spawn' | 'exit' | 'list-changed';
+type PtyEventType = 'spawn' | 'exit' | 'idle' | 'output' | 'list-changed';
+
+/** Passed with spawn, exit, idle and output events, alongside event-specific fields. */
+export interface PtyEventSession {
+  taskId: string;
+  /** Agent rather than plain shell session. */
+  queueable: boolean;
+}
 type PtyEventListener = (agentId: string, data?: unknown) => void;
 const eventListeners = new Map<PtyEventType, Set<PtyEventListener>>();
 
@@ -38,36 +145,110 @@ export function notifyAgentListChanged(): void {
   emitPtyEvent('list-changed', '');
 }
 
-const BATCH_MAX = 64 * 1024;
-const BATCH_INTERVAL = 8; // ms
+const IS_WINDOWS = process.platform === 'win32';
+/**
+ * Output batching knobs. Reads are collected until `batchMax` bytes or
+ * `batchIntervalMs` pass; reads shorter than `smallFlush` bytes (likely an
+ * interactive prompt) are flushed immediately.
+ */
+export interface OutputTuning {
+  batchMax: number;
+  batchIntervalMs: number;
+  smallFlush: number;
+}
+
+const DEFAULT_TUNING: OutputTuning = {
+  batchMax: 64 * 1024,
+  batchIntervalMs: 8,
+  smallFlush: 1024,
+};
+
+function resolveTuning(base: OutputTuning, patch: Partial<OutputTuning> | undefined): OutputTuning {
+  const next = {
+    batchMax: patch?.batchMax ?? base.batchMax,
+    batchIntervalMs: patch?.batchIntervalMs ?? base.batchIntervalMs,
+    smallFlush: patch?.smallFlush ?? base.smallFlush,
+  };
+  if (!Number.isInteger(next.batchMax) || next.batchMax < 1024 || next.batchMax > 1024 * 1024) {
+    throw new Error('batchMax must be between 1024 and 1048576 bytes');
+  }
+  if (
+    !Number.isInteger(next.batchIntervalMs) ||
+    next.batchIntervalMs < 0 ||
+    next.batchIntervalMs > 1000
+  ) {
+    throw new Error('batchIntervalMs must be between 0 and 1000');
+  }
+  if (!Number.isInteger(next.smallFlush) || next.smallFlush < 0) {
+    throw new Error('smallFlush must be a non-negative integer');
+  }
+  return next;
+}
+
+const INITIAL_INPUT_SETTLE = 50; // ms after first output
+const INITIAL_INPUT_FALLBACK = 1_000; // ms if the child prints nothing
+const MAX_TIMER_MS = 2 ** 31 - 1;
 const TAIL_CAP = 8 * 1024;
 const MAX_LINES = 50;
+// Backpressure: stop reading once the renderer falls this far behind on
+// acknowledging output, so the kernel PTY buffer throttles the agent instead
+// of main-process memory growing without bound.
+const BACKPRESSURE_HIGH = 4 * 1024 * 1024;
+const BACKPRESSURE_LOW = 1024 * 1024;
+const DEFAULT_SCROLLBACK_BYTES = 64 * 1024;
+const MAX_SCROLLBACK_BYTES = 16 * 1024 * 1024;
 
-export function spawnAgent(
-  win: BrowserWindow,
-  args: {
-    taskId: string;
-    agentId: string;
-    command: string;
-    args: string[];
-    cwd: string;
-    env: Record<string, string>;
-    cols: number;
-    rows: number;
-    onOutput: { __CHANNEL_ID__: string };
-  },
-): void {
-  const channelId = args.onOutput.__CHANNEL_ID__;
-  const command = args.command || process.env.SHELL || '/bin/sh';
-  const cwd = args.cwd || process.env.HOME || '/';
+function clampScrollbackBytes(bytes: number | undefined): number {
+  if (bytes === undefined || !Number.isFinite(bytes)) return DEFAULT_SCROLLBACK_BYTES;
+  return Math.min(Math.max(Math.floor(bytes), 1024), MAX_SCROLLBACK_BYTES);
+}
+
+interface SpawnAgentArgs {
+  taskId: string;
+  agentId: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  env: Record<string, string>;
+  cols: number;
+  rows: number;
+  scrollbackBytes?: number;
+  detachable?: boolean;
+  /** Write an asciicast v2 recording of the session output. */
+  record?: boolean;
+  /** Quiet period before an `agent-idle` event; 0 disables. */
+  idleTimeoutMs?: number;
+  /** Agent (not plain shell) session subject to the concurrency limit. */
+  queueable?: boolean;
+  /** Written once the child is running (first output or a fallback delay). */
+  initialInput?: string;
+  /** Emit `agent-timeout` once the session has run this long. */
+  maxRuntimeSecs?: number;
+  /** What to do on timeout; defaults to a graceful kill. */
+  timeoutAction?: 'warn' | 'kill';
+  outputTuning?: Partial<OutputTuning>;
+  /** Resolved environment profile; may set PATH, unlike `env`. Filled in by the backend. */
+  profileEnv?: Record<string, string>;
+  onOutput: { __CHANNEL_ID__: string };
+}
+
+export function resolveCommand(command: string, searchPath?: string): string {
+  const resolved = command || process.env.SHELL || '/bin/sh';
 
   // Reject commands with shell metacharacters (node-pty uses execvp, but
   // guard against accidental misuse). Allow bare names (resolved via PATH)
   // and absolute paths.
-  if (/[;&|`$(){}\n]/.test(command)) {
-    throw new Error(`Command contains disallowed characters: ${command}`);
+  if (/[;&|`$(){}\n]/.test(resolved)) {
+    throw new Error(`Command contains disallowed characters: ${resolved}`);
   }
+  checkSpawnPolicy(resolved, searchPath);
+  return resolved;
+}
 
+export function buildSpawnEnv(
+  env: Record<string, string> | undefined,
+  profileEnv: Record<string, string> = {},
+): Record<string, string> {
   const filteredEnv: Record<string, string> = {};
   for (const [k, v] of Object.entries(process.env)) {
     if (v !== undefined) filteredEnv[k] = v;
@@ -87,15 +268,16 @@ export function spawnAgent(
     'ELECTRON_RUN_AS_NODE',
   ]);
   const safeEnvOverrides: Record<string, string> = {};
-  for (const [k, v] of Object.entries(args.env ?? {})) {
+  for (const [k, v] of Object.entries(env ?? {})) {
     if (!ENV_BLOCK_LIST.has(k)) safeEnvOverrides[k] = v;
   }
 
   const spawnEnv: Record<string, string> = {
     ...filteredEnv,
+    ...profileEnv,
     TERM: 'xterm-256color',
     COLORTERM: 'truecolor',
-    ...safeEnvOverrides,
+    ...resolveSecretRefs(safeEnvOverrides),
   };
 
   // Clear env vars that prevent nested agent sessions
@@ -103,22 +285,196 @@ export function spawnAgent(
   delete spawnEnv.CLAUDE_CODE_SESSION;
   delete spawnEnv.CLAUDE_CODE_ENTRYPOINT;
 
+  return spawnEnv;
+}
+
+export function spawnAgent(win: BrowserWindow, args: SpawnAgentArgs): void {
+  const env = buildSpawnEnv(args.env, args.profileEnv);
+  const command = resolveCommand(args.command, env.PATH);
+  const tuning = resolveTuning(DEFAULT_TUNING, args.outputTuning);
+  const cwd = args.cwd || process.env.HOME || '/';
+
   const proc = pty.spawn(command, args.args, {
     name: 'xterm-256color',
     cols: args.cols,
     rows: args.rows,
     cwd,
-    env: spawnEnv,
+    env,
   });
 
+  attachSession(win, proc, null, args, {
+    recorder: createRecorder(args, command),
+    spawnArgs: args,
+    env,
+    tuning,
+    command,
+    args: args.args,
+  });
+}
+
+/**
+ * Spawn an agent inside a session host process so it survives an app
+ * restart. Output is wired up exactly like a regular PTY session.
+ */
+export async function spawnDetachableAgent(
+  win: BrowserWindow,
+  args: SpawnAgentArgs,
+): Promise<void> {
+  const env = buildSpawnEnv(args.env, args.profileEnv);
+  const command = resolveCommand(args.command, env.PATH);
+  const tuning = resolveTuning(DEFAULT_TUNING, args.outputTuning);
+  const cwd = args.cwd || process.env.HOME || '/';
+
+  const proc = await spawnDetachedPty({
+    agentId: args.agentId,
+    taskId: args.taskId,
+    command,
+    args: args.args,
+    cwd,
+    env,
+    cols: args.cols,
+    rows: args.rows,
+    scrollbackBytes: clampScrollbackBytes(args.scrollbackBytes),
+  });
+
+  attachSession(win, proc, proc, args, {
+    recorder: createRecorder(args, command),
+    spawnArgs: args,
+    env,
+    tuning,
+    command,
+    args: args.args,
+  });
+}
+
+/**
+ * Reconnect the renderer to a running agent. If the session is still owned
+ * by this process (e.g. after a window reload) the output channel is swapped
+ * and scrollback replayed; otherwise a detached session host is looked up.
+ */
+export async function reattachAgent(
+  win: BrowserWindow,
+  args: { agentId: string; cols: number; onOutput: { __CHANNEL_ID__: string } },
+): Promise<{ taskId: string }> {
+  const existing = sessions.get(args.agentId);
+  if (existing) {
+    existing.channelId = args.onOutput.__CHANNEL_ID__;
+    // The old renderer will never ack what it was sent
+    existing.unackedBytes = 0;
+    existing.outputPaused = false;
+    if (existing.backpressured) {
+      existing.backpressured = false;
+      emitBackpressure(win, existing);
+    }
+    syncReadState(existing);
+    const replay = existing.scrollback.toBase64();
+    if (replay && !win.isDestroyed()) {
+      win.webContents.send(`channel:${existing.channelId}`, { type: 'Data', data: replay });
+    }
+    return { taskId: existing.taskId };
+  }
+
+  const { proc, meta } = await connectDetachedPty(args.agentId, args.cols);
+  attachSession(
+    win,
+    proc,
+    proc,
+    { taskId: meta.taskId, agentId: meta.agentId, cwd: meta.cwd, onOutput: args.onOutput },
+    { command: meta.command, args: meta.args, startedAt: meta.startedAt },
+  );
+  return { taskId: meta.taskId };
+}
+
+function createRecorder(args: SpawnAgentArgs, command: string): Recorder | null {
+  if (!args.record) return null;
+  return startRecording({
+    taskId: args.taskId,
+    agentId: args.agentId,
+    command: [command, ...args.args].join(' '),
+    cols: args.cols,
+    rows: args.rows,
+  });
+}
+
+function attachSession(
+  win: BrowserWindow,
+  proc: PtyProcess,
+  detachable: DetachedPty | null,
+  args: Pick<
+    SpawnAgentArgs,
+    | 'taskId'
+    | 'agentId'
+    | 'cwd'
+    | 'scrollbackBytes'
+    | 'idleTimeoutMs'
+    | 'queueable'
+    | 'initialInput'
+    | 'maxRuntimeSecs'
+    | 'timeoutAction'
+    | 'onOutput'
+  >,
+  opts: {
+    recorder?: Recorder | null;
+    spawnArgs?: SpawnAgentArgs;
+    env?: Record<string, string>;
+    tuning?: OutputTuning;
+    /** Resolved command line, for listRunningAgents. */
+    command?: string;
+    args?: string[];
+    startedAt?: number;
+  } = {},
+): void {
+  const eventSession: PtyEventSession = {
+    taskId: args.taskId,
+    queueable: args.queueable ?? false,
+  };
   const session: PtySession = {
     proc,
-    channelId,
+    channelId: args.onOutput.__CHANNEL_ID__,
+    extraChannels: new Set(),
     taskId: args.taskId,
     agentId: args.agentId,
     flushTimer: null,
     subscribers: new Set(),
-    scrollback: new RingBuffer(),
+    scrollback: new RingBuffer(clampScrollbackBytes(args.scrollbackBytes)),
+    detach: detachable ? () => detachable.detach() : null,
+    killPath: null,
+    killTimer: null,
+    recorder: opts.recorder ?? null,
+    spawnArgs: opts.spawnArgs ? { ...opts.spawnArgs } : null,
+    restart: null,
+    env: opts.env ?? null,
+    suspended: false,
+    idle: createIdleTracker(args.idleTimeoutMs ?? DEFAULT_IDLE_MS, (lastLine) => {
+      emitPtyEvent('idle', args.agentId, { ...eventSession, lastLine });
+      if (!win.isDestroyed()) {
+        win.webContents.send(IPC.AgentIdle, {
+          agent_id: args.agentId,
+          task_id: args.taskId,
+          last_line: lastLine,
+        });
+      }
+    }),
+    triggers: createPatternMatcher(),
+    permissions: createPermissionDetector(opts.command ?? ''),
+    throttle: createOutputThrottle(defaultThrottle),
+    tuning: opts.tuning ?? { ...DEFAULT_TUNING },
+    command: opts.command ?? '',
+    args: opts.args ?? [],
+    startedAt: opts.startedAt ?? Date.now(),
+    cwd: args.cwd || process.env.HOME || '/',
+    title: null,
+    osc: createOscParser(),
+    finish: () => {},
+    exited: false,
+    outputPaused: false,
+    unackedBytes: 0,
+    backpressured: false,
+    queueable: args.queueable ?? false,
+    bracketedPaste: false,
+    applicationCursor: false,
+    writeChain: Promise.resolve(),
+    pendingPastes: 0,
   };
   sessions.set(args.agentId, session);
 
@@ -126,59 +482,171 @@ export function spawnAgent(
   let batch = Buffer.alloc(0);
   let tailBuf = Buffer.alloc(0);
 
+  // Send initial input once the child shows signs of life, so it isn't
+  // swallowed before the program has set up its terminal
+  let initialInput = args.initialInput || null;
+  let initialInputTimer: ReturnType<typeof setTimeout> | null = null;
+  let sawOutput = false;
+  const sendInitialInput = () => {
+    if (initialInputTimer) clearTimeout(initialInputTimer);
+    initialInputTimer = null;
+    if (initialInput === null || session.exited) return;
+    const data = initialInput;
+    initialInput = null;
+    writeOrdered(session, data);
+  };
+  if (initialInput !== null) {
+    initialInputTimer = setTimeout(sendInitialInput, INITIAL_INPUT_FALLBACK);
+  }
+
+  let runtimeTimer: ReturnType<typeof setTimeout> | null = null;
+  const maxRuntimeSecs = args.maxRuntimeSecs;
+  if (maxRuntimeSecs !== undefined && Number.isFinite(maxRuntimeSecs) && maxRuntimeSecs > 0) {
+    const action = args.timeoutAction ?? 'kill';
+    // setTimeout overflows past ~24.8 days; treat longer limits as that
+    const ms = Math.min(maxRuntimeSecs * 1000, MAX_TIMER_MS);
+    runtimeTimer = setTimeout(() => {
+      runtimeTimer = null;
+      if (sessions.get(args.agentId) !== session) return;
+      if (!win.isDestroyed()) {
+        win.webContents.send(IPC.AgentTimeout, {
+          agent_id: args.agentId,
+          task_id: args.taskId,
+          max_runtime_secs: maxRuntimeSecs,
+          action,
+        });
+      }
+      if (action === 'kill') killAgent(args.agentId, { graceful: true });
+    }, ms);
+  }
+
+  // Read channelId from the session so reattachAgent can swap channels
   const send = (msg: unknown) => {
     if (!win.isDestroyed()) {
-      win.webContents.send(`channel:${channelId}`, msg);
+      win.webContents.send(`channel:${session.channelId}`, msg);
+      for (const id of session.extraChannels) {
+        win.webContents.send(`channel:${id}`, msg);
+      }
     }
   };
 
-  const flush = () => {
-    if (batch.length === 0) return;
-    const encoded = batch.toString('base64');
-    send({ type: 'Data', data: encoded });
-    session.scrollback.write(batch);
-    for (const sub of session.subscribers) {
-      sub(encoded);
-    }
-    batch = Buffer.alloc(0);
+  // Hold back a trailing partial UTF-8 sequence until the rest arrives,
+  // unless this is the final flush
+  const flush = (final = false) => {
     if (session.flushTimer) {
       clearTimeout(session.flushTimer);
       session.flushTimer = null;
     }
+    const held = final ? 0 : incompleteUtf8Tail(batch);
+    if (batch.length === held) return;
+    const out = held ? batch.subarray(0, batch.length - held) : batch;
+    batch = held ? Buffer.from(batch.subarray(batch.length - held)) : Buffer.alloc(0);
+
+    const encoded = out.toString('base64');
+    send({ type: 'Data', data: encoded });
+    session.unackedBytes += out.length;
+    if (!session.backpressured && session.unackedBytes > BACKPRESSURE_HIGH) {
+      session.backpressured = true;
+      syncReadState(session);
+      emitBackpressure(win, session);
+    }
+    session.scrollback.write(out);
+    for (const sub of session.subscribers) {
+      sub(encoded);
+    }
   };
 
+  // Scrollback replayed by a session host was already seen by the recorder,
+  // triggers and permission detector in an earlier run: only show it again
+  detachable?.onReplay((replay) => {
+    send({ type: 'Data', data: replay.toString('base64') });
+    session.scrollback.write(replay);
+  });
+
   proc.onData((data: string) => {
-    const chunk = Buffer.from(data, 'utf8');
+    let chunk = Buffer.from(data, 'utf8');
+    if (initialInput !== null && !sawOutput) {
+      if (initialInputTimer) clearTimeout(initialInputTimer);
+      initialInputTimer = setTimeout(sendInitialInput, INITIAL_INPUT_SETTLE);
+    }
+    sawOutput = true;
 
-    // Maintain tail buffer for exit diagnostics
+    // Tail buffer is kept even while flooding so exit diagnostics stay accurate
     tailBuf = Buffer.concat([tailBuf, chunk]);
     if (tailBuf.length > TAIL_CAP) {
       tailBuf = tailBuf.subarray(tailBuf.length - TAIL_CAP);
     }
 
+    const { pass, notice } = session.throttle.admit(chunk.length);
+    if (!pass) {
+      if (notice) {
+        batch = Buffer.concat([batch, Buffer.from(notice, 'utf8')]);
+        flush();
+      }
+      return;
+    }
+    if (notice) chunk = Buffer.concat([Buffer.from(notice, 'utf8'), chunk]);
+
+    session.recorder?.write(data);
+    session.idle.record(data);
+    emitPtyEvent('output', args.agentId, { ...eventSession, data });
+    trackTerminalModes(session, data);
+    for (const seq of session.osc.feed(data)) handleOsc(win, session, seq);
+    for (const match of session.triggers.feed(data)) {
+      if (!win.isDestroyed()) {
+        win.webContents.send(IPC.PatternMatched, { agent_id: args.agentId, ...match });
+      }
+    }
+    const permission = session.permissions.feed(data);
+    if (permission && !win.isDestroyed()) {
+      if (permission.kind === 'request') {
+        win.webContents.send(IPC.AgentPermissionRequest, {
+          agent_id: args.agentId,
+          task_id: args.taskId,
+          ...permission.request,
+        });
+      } else {
+        win.webContents.send(IPC.AgentPermissionResolved, {
+          agent_id: args.agentId,
+          request_id: permission.request_id,
+        });
+      }
+    }
+
     batch = Buffer.concat([batch, chunk]);
 
     // Flush large batches immediately
-    if (batch.length >= BATCH_MAX) {
+    if (batch.length >= session.tuning.batchMax) {
       flush();
       return;
     }
 
     // Small read = likely interactive prompt, flush immediately
-    if (chunk.length < 1024) {
+    if (chunk.length < session.tuning.smallFlush) {
       flush();
       return;
     }
 
     // Otherwise schedule flush on timer
     if (!session.flushTimer) {
-      session.flushTimer = setTimeout(flush, BATCH_INTERVAL);
+      session.flushTimer = setTimeout(() => flush(), session.tuning.batchIntervalMs);
     }
   });
 
-  proc.onExit(({ exitCode, signal }) => {
+  session.finish = (exitCode, rawSignal) => {
+    if (session.exited) return;
+    session.exited = true;
+    // ConPTY never reports a signal — derive one from the NTSTATUS exit code
+    const signal = IS_WINDOWS
+      ? (rawSignal ?? windowsExitSignal(exitCode) ?? undefined)
+      : rawSignal;
     // Flush any remaining buffered data
-    flush();
+    flush(true);
+    if (session.killTimer) clearTimeout(session.killTimer);
+    if (initialInputTimer) clearTimeout(initialInputTimer);
+    if (runtimeTimer) clearTimeout(runtimeTimer);
+    session.recorder?.close();
+    session.idle.dispose();
 
     // Parse tail buffer into last N lines for exit diagnostics
     const tailStr = tailBuf.toString('utf8');
@@ -188,47 +656,374 @@ export function spawnAgent(
       .filter((l) => l.length > 0)
       .slice(-MAX_LINES);
 
-    send({
-      type: 'Exit',
-      data: {
+    const exitReason = classifyExit({
+      exitCode,
+      signal: signal ?? null,
+      killedByUser: session.killPath !== null,
+      lastOutput: lines,
+    });
+
+    // A restart keeps the renderer's terminal alive, so it never sees this exit
+    if (!session.restart) {
+      send({
+        type: 'Exit',
+        data: {
+          exit_code: exitCode,
+          signal: signal !== undefined ? String(signal) : null,
+          last_output: lines,
+          exit_reason: exitReason,
+          kill_path: session.killPath,
+        },
+      });
+    }
+
+    if (!win.isDestroyed()) {
+      win.webContents.send(IPC.AgentExited, {
+        agent_id: args.agentId,
+        task_id: args.taskId,
         exit_code: exitCode,
         signal: signal !== undefined ? String(signal) : null,
-        last_output: lines,
-      },
-    });
+        exit_reason: exitReason,
+      });
+    }
+    log.info('pty', `${args.agentId} exited (${exitReason}, code ${exitCode})`);
+    emitPtyEvent('exit', args.agentId, { ...eventSession, exitCode, signal, exitReason });
+    if (sessions.get(args.agentId) === session) sessions.delete(args.agentId);
+    if (!IS_WINDOWS) trackOrphans(proc.pid);
+    session.restart?.();
+  };
+
+  proc.onExit(({ exitCode, signal }) => session.finish(exitCode, signal));
+
+  log.debug('pty', `${args.agentId} started: ${session.command} ${session.args.join(' ')}`);
+  emitPtyEvent('spawn', args.agentId, eventSession);
+}
+
+/**
+ * Kill an agent and respawn it with its original command, args, cwd, env
+ * and current size, streaming into the same output channel.
+ */
+export function restartAgent(win: BrowserWindow, agentId: string): Promise<void> {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  const spawnArgs = session.spawnArgs;
+  if (!spawnArgs) throw new Error(`Spawn parameters unavailable for agent: ${agentId}`);
+  if (session.restart) throw new Error(`Agent is already restarting: ${agentId}`);
 
-    emitPtyEvent('exit', args.agentId, { exitCode, signal });
-    sessions.delete(args.agentId);
+  return new Promise((resolve, reject) => {
+    session.restart = () => {
+      const args = { ...spawnArgs, onOutput: { __CHANNEL_ID__: session.channelId } };
+      try {
+        Promise.resolve(
+          args.detachable ? spawnDetachableAgent(win, args) : spawnAgent(win, args),
+        ).then(resolve, reject);
+      } catch (e) {
+        reject(e);
+      }
+    };
+    if (session.flushTimer) {
+      clearTimeout(session.flushTimer);
+      session.flushTimer = null;
+    }
+    if (session.suspended) signalProcessGroup(session, 'SIGCONT');
+    session.killPath = 'immediate';
+    session.proc.kill();
   });
+}
+
+const PASTE_START = '\x1b[200~';
+const PASTE_END = '\x1b[201~';
+const PASTE_CHUNK = 4096; // chars
+const PASTE_CHUNK_DELAY = 10; // ms
+
+function handleOsc(win: BrowserWindow, session: PtySession, seq: OscSequence): void {
+  if (win.isDestroyed()) return;
+  const agentId = session.agentId;
+
+  const cwd = parseCwdSequence(seq);
+  if (cwd && cwd !== session.cwd) {
+    session.cwd = cwd;
+    win.webContents.send(IPC.AgentCwdChanged, { agent_id: agentId, cwd });
+    return;
+  }
+
+  const title = parseTitleSequence(seq);
+  if (title !== null) {
+    if (title !== session.title) {
+      session.title = title;
+      win.webContents.send(IPC.AgentTitleChanged, { agent_id: agentId, title });
+    }
+    return;
+  }
+
+  const notification = parseNotificationSequence(seq);
+  if (notification) {
+    win.webContents.send(IPC.AgentNotification, {
+      agent_id: agentId,
+      task_id: session.taskId,
+      ...notification,
+    });
+  }
+}
+
+// Last toggle in the chunk wins; null if the mode isn't mentioned
+function lastModeToggle(data: string, mode: number): boolean | null {
+  const on = data.lastIndexOf(`\x1b[?${mode}h`);
+  const off = data.lastIndexOf(`\x1b[?${mode}l`);
+  if (on === -1 && off === -1) return null;
+  return on > off;
+}
+
+function trackTerminalModes(session: PtySession, data: string): void {
+  session.bracketedPaste = lastModeToggle(data, 2004) ?? session.bracketedPaste;
+  session.applicationCursor = lastModeToggle(data, 1) ?? session.applicationCursor;
+}
+
+/** Split into chunks without breaking UTF-16 surrogate pairs. */
+function chunkString(data: string, size: number): string[] {
+  const chunks: string[] = [];
+  let i = 0;
+  while (i < data.length) {
+    let end = Math.min(i + size, data.length);
+    const code = data.charCodeAt(end - 1);
+    if (end < data.length && code >= 0xd800 && code <= 0xdbff) end--;
+    chunks.push(data.slice(i, end));
+    i = end;
+  }
+  return chunks;
+}
+
+// Keep keystrokes behind any paste that is still being chunked
+function writeOrdered(session: PtySession, data: string | Buffer): void {
+  if (session.pendingPastes === 0) {
+    session.proc.write(data);
+  } else {
+    session.writeChain = session.writeChain.then(() => session.proc.write(data)).catch(() => {});
+  }
+}
+
+/**
+ * Write input to an agent. In paste mode the text is wrapped in bracketed
+ * paste markers (when the app enabled them) and written in small chunks
+ * with pauses, since some CLIs mangle multi-kilobyte single writes.
+ */
+export function writeToAgent(
+  agentId: string,
+  data: string,
+  opts: { paste?: boolean } = {},
+): Promise<void> | void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
 
-  emitPtyEvent('spawn', args.agentId);
+  if (!opts.paste) {
+    writeOrdered(session, data);
+    return;
+  }
+
+  // Strip end markers from the payload so it can't break out of the paste
+  const body = data.split(PASTE_END).join('');
+  const payload = session.bracketedPaste ? PASTE_START + body + PASTE_END : body;
+  session.pendingPastes++;
+  const run = session.writeChain
+    .then(async () => {
+      for (const chunk of chunkString(payload, PASTE_CHUNK)) {
+        if (sessions.get(agentId) !== session) return;
+        session.proc.write(chunk);
+        await new Promise((r) => setTimeout(r, PASTE_CHUNK_DELAY));
+      }
+    })
+    .finally(() => {
+      session.pendingPastes--;
+    });
+  // A failed write must not wedge later input
+  session.writeChain = run.catch(() => {});
+  return run;
+}
+
+/** Send a named key (see keys.ts) using the sequence the session currently expects. */
+export function sendKey(agentId: string, key: string): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  if (!isKeyName(key)) throw new Error(`Unknown key: ${key}`);
+  writeOrdered(session, keySequence(key, session.applicationCursor));
 }
 
-export function writeToAgent(agentId: string, data: string): void {
+/**
+ * Write raw bytes (base64-encoded) to an agent, for control sequences and
+ * non-UTF-8 input that would be corrupted by the string path.
+ */
+export function writeBytesToAgent(agentId: string, dataBase64: string): void {
   const session = sessions.get(agentId);
   if (!session) throw new Error(`Agent not found: ${agentId}`);
-  session.proc.write(data);
+  if (!/^[A-Za-z0-9+/]*={0,2}$/.test(dataBase64)) throw new Error('data must be base64');
+  writeOrdered(session, Buffer.from(dataBase64, 'base64'));
 }
 
 export function resizeAgent(agentId: string, cols: number, rows: number): void {
   const session = sessions.get(agentId);
   if (!session) throw new Error(`Agent not found: ${agentId}`);
   session.proc.resize(cols, rows);
+  session.recorder?.resize(cols, rows);
+  if (session.spawnArgs) {
+    session.spawnArgs.cols = cols;
+    session.spawnArgs.rows = rows;
+  }
 }
 
+/**
+ * Attach an extra output channel to a running agent (split view, log pane,
+ * ...). Scrollback is replayed to the new channel before live output. Extra
+ * channels are passive: they don't take part in flow control.
+ */
+export function attachOutputChannel(
+  win: BrowserWindow,
+  agentId: string,
+  onOutput: { __CHANNEL_ID__: string },
+): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  const id = onOutput.__CHANNEL_ID__;
+  if (id === session.channelId || session.extraChannels.has(id)) return;
+  const replay = session.scrollback.toBase64();
+  if (replay && !win.isDestroyed()) {
+    win.webContents.send(`channel:${id}`, { type: 'Data', data: replay });
+  }
+  session.extraChannels.add(id);
+}
+
+/** Stop sending output to a channel added with attachOutputChannel. */
+export function detachOutputChannel(agentId: string, channelId: string): void {
+  sessions.get(agentId)?.extraChannels.delete(channelId);
+}
+
+// Reads are paused while either the renderer or backpressure asks for it
+function syncReadState(session: PtySession): void {
+  if (session.outputPaused || session.backpressured) {
+    session.proc.pause();
+  } else {
+    session.proc.resume();
+  }
+}
+
+function emitBackpressure(win: BrowserWindow, session: PtySession): void {
+  if (win.isDestroyed()) return;
+  win.webContents.send(IPC.Backpressure, {
+    agent_id: session.agentId,
+    paused: session.backpressured,
+    buffered_bytes: session.unackedBytes,
+  });
+}
+
+/** Stop reading PTY output (flow control while the renderer catches up). */
+export function pauseAgentOutput(agentId: string): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  session.outputPaused = true;
+  syncReadState(session);
+}
+
+/** Resume reading PTY output after pauseAgentOutput. */
+export function resumeAgentOutput(agentId: string): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  session.outputPaused = false;
+  syncReadState(session);
+}
+
+/** Acknowledge output bytes received by the renderer, releasing backpressure. */
+export function ackAgentOutput(win: BrowserWindow, agentId: string, bytes: number): void {
+  const session = sessions.get(agentId);
+  if (!session) return;
+  if (!Number.isFinite(bytes) || bytes <= 0) return;
+  session.unackedBytes = Math.max(session.unackedBytes - bytes, 0);
+  if (session.backpressured && session.unackedBytes < BACKPRESSURE_LOW) {
+    session.backpressured = false;
+    syncReadState(session);
+    emitBackpressure(win, session);
+  }
+}
+
+// The PTY child is a session leader, so its PID is also its process group ID
+function signalProcessGroup(session: PtySession, signal: NodeJS.Signals): void {
+  if (IS_WINDOWS) throw new Error(`${signal} is not supported on Windows`);
+  try {
+    process.kill(-session.proc.pid, signal);
+  } catch {
+    process.kill(session.proc.pid, signal);
+  }
+}
+
+/** Freeze an agent's whole process group with SIGSTOP, keeping its session. */
 export function pauseAgent(agentId: string): void {
   const session = sessions.get(agentId);
   if (!session) throw new Error(`Agent not found: ${agentId}`);
-  session.proc.pause();
+  if (session.suspended) return;
+  signalProcessGroup(session, 'SIGSTOP');
+  session.suspended = true;
 }
 
+/** Continue a process group previously frozen by pauseAgent. */
 export function resumeAgent(agentId: string): void {
   const session = sessions.get(agentId);
   if (!session) throw new Error(`Agent not found: ${agentId}`);
-  session.proc.resume();
+  if (!session.suspended) return;
+  signalProcessGroup(session, 'SIGCONT');
+  session.suspended = false;
 }
 
-export function killAgent(agentId: string): void {
+/** Emit `pattern-matched` whenever a live output line matches `pattern`. */
+export function subscribeOutputPattern(agentId: string, pattern: string, triggerId: string): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  session.triggers.subscribe(triggerId, pattern);
+}
+
+/** Remove a trigger registered with subscribeOutputPattern. */
+export function unsubscribeOutputPattern(agentId: string, triggerId: string): boolean {
+  return sessions.get(agentId)?.triggers.unsubscribe(triggerId) ?? false;
+}
+
+/** Answer the agent's pending permission dialog with the keys its CLI expects. */
+export function respondToPermission(agentId: string, approve: boolean): void {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  writeOrdered(session, session.permissions.respond(approve));
+}
+
+/** Whether an agent is currently frozen by pauseAgent. */
+export function isAgentPaused(agentId: string): boolean {
+  return sessions.get(agentId)?.suspended ?? false;
+}
+
+/**
+ * Send a termination request. Windows has no SIGTERM: ConPTY turns a ^C on
+ * input into CTRL_C_EVENT for the console group, which is the closest
+ * graceful equivalent (CTRL_BREAK_EVENT has no input encoding). node-pty on
+ * Windows rejects signal names, so the hard kill is a plain kill().
+ */
+function terminateProcess(session: PtySession, signal: 'SIGTERM' | 'SIGKILL'): void {
+  if (!IS_WINDOWS) {
+    session.proc.kill(signal);
+  } else if (signal === 'SIGTERM') {
+    session.proc.write('\x03');
+  } else {
+    session.proc.kill();
+  }
+}
+
+const DEFAULT_GRACE_MS = 5_000;
+const MAX_GRACE_MS = 60_000;
+
+/**
+ * Stop an agent. In graceful mode a stopped process is woken with SIGCONT,
+ * then asked to terminate (SIGTERM, or ^C on Windows) so it can flush its own
+ * state, and hard-killed once the grace period (default 5s, at most 60s) runs
+ * out; killPath records which step ended it. Otherwise it is killed at once.
+ */
+export function killAgent(
+  agentId: string,
+  opts: { graceful?: boolean; graceMs?: number } = {},
+): void {
   const session = sessions.get(agentId);
   if (session) {
     if (session.flushTimer) {
@@ -239,7 +1034,29 @@ export function killAgent(agentId: string): void {
     // notify stale listeners. Let onExit handle sessions.delete
     // and emitPtyEvent to avoid the race condition.
     session.subscribers.clear();
-    session.proc.kill();
+    // A stopped process can't act on SIGTERM — wake it first
+    if (session.suspended) {
+      signalProcessGroup(session, 'SIGCONT');
+      session.suspended = false;
+    }
+
+    if (!opts.graceful) {
+      session.killPath = 'immediate';
+      session.proc.kill();
+      return;
+    }
+
+    // Already terminating gracefully — let the pending escalation run
+    if (session.killTimer) return;
+    const graceMs = Math.min(Math.max(opts.graceMs ?? DEFAULT_GRACE_MS, 0), MAX_GRACE_MS);
+    session.killPath = 'graceful';
+    terminateProcess(session, 'SIGTERM');
+    session.killTimer = setTimeout(() => {
+      session.killTimer = null;
+      if (sessions.get(agentId) !== session) return;
+      session.killPath = 'escalated';
+      terminateProcess(session, 'SIGKILL');
+    }, graceMs);
   }
 }
 
@@ -247,15 +1064,40 @@ export function countRunningAgents(): number {
   return sessions.size;
 }
 
+/** Number of running sessions that count toward the concurrency limit. */
+export function countQueueableAgents(): number {
+  let count = 0;
+  for (const s of sessions.values()) if (s.queueable) count++;
+  return count;
+}
+
 export function killAllAgents(): void {
   for (const [, session] of sessions) {
     if (session.flushTimer) clearTimeout(session.flushTimer);
     session.subscribers.clear();
+    session.killPath = 'immediate';
     session.proc.kill();
   }
   // Let onExit handlers clean up sessions individually
 }
 
+/**
+ * Tear down sessions on app quit: regular PTYs are killed, detachable ones
+ * are only disconnected so their session host keeps running.
+ */
+export function shutdownAgents(): void {
+  for (const [, session] of sessions) {
+    if (session.flushTimer) clearTimeout(session.flushTimer);
+    session.subscribers.clear();
+    if (session.detach) {
+      session.detach();
+    } else {
+      session.killPath = 'immediate';
+      session.proc.kill();
+    }
+  }
+}
+
 // --- Subscriber helpers for remote access ---
 
 /** Subscribe to live base64-encoded output from an agent. */
@@ -287,8 +1129,209 @@ export function getAgentMeta(agentId: string): { taskId: string; agentId: string
   return s ? { taskId: s.taskId, agentId: s.agentId } : null;
 }
 
+export interface RunningAgentInfo {
+  agent_id: string;
+  task_id: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  pid: number;
+  started_at: number;
+  alive: boolean;
+  detached: boolean;
+  suspended: boolean;
+}
+
+/** Describe every live session so the renderer can rebuild its view after a reload. */
+export function listRunningAgents(): RunningAgentInfo[] {
+  return [...sessions.values()]
+    .map((s) => ({
+      agent_id: s.agentId,
+      task_id: s.taskId,
+      command: s.command,
+      args: s.args,
+      cwd: s.cwd,
+      pid: s.proc.pid,
+      started_at: s.startedAt,
+      alive: isProcessAlive(s.proc.pid),
+      detached: s.detach !== null,
+      suspended: s.suspended,
+    }))
+    .sort((a, b) => a.started_at - b.started_at);
+}
+
 /** Return the current column width of an agent's PTY. */
 export function getAgentCols(agentId: string): number {
   const s = sessions.get(agentId);
   return s ? s.proc.cols : 80;
 }
+
+/**
+ * Return the effective environment an agent was spawned with (after login
+ * PATH resolution, renderer overrides and CLAUDE* removal), sorted by key.
+ * Variables filled from secrets show their `secret:<name>` reference.
+ */
+export function getAgentEnv(agentId: string): Record<string, string> | null {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  if (!session.env) return null;
+  const requested = session.spawnArgs?.env ?? {};
+  return Object.fromEntries(
+    Object.entries(session.env)
+      .map(([k, v]): [string, string] => [k, isSecretRef(requested[k]) ? requested[k] : v])
+      .sort(([a], [b]) => a.localeCompare(b)),
+  );
+}
+
+/**
+ * Tune or disable flood protection. With an agent ID only that session is
+ * changed; without one the new settings become the default and apply to all
+ * running sessions.
+ */
+export function setOutputThrottle(
+  agentId: string | undefined,
+  opts: Partial<ThrottleOptions>,
+): ThrottleOptions {
+  validateThrottleOptions(opts);
+  if (agentId) {
+    const session = sessions.get(agentId);
+    if (!session) throw new Error(`Agent not found: ${agentId}`);
+    session.throttle.configure(opts);
+    return session.throttle.options();
+  }
+  defaultThrottle = mergeThrottleOptions(defaultThrottle, opts);
+  for (const s of sessions.values()) s.throttle.configure(opts);
+  return { ...defaultThrottle };
+}
+
+const REAP_INTERVAL = 10_000; // ms
+
+/** Fields of /proc/<pid>/stat from field 3 (state) on, or null if unreadable. */
+function readProcStat(pid: number | string): string[] | null {
+  try {
+    const stat = fs.readFileSync(`/proc/${pid}/stat`, 'utf8');
+    // The command name is parenthesised and may itself contain spaces
+    return stat.slice(stat.lastIndexOf(')') + 2).split(' ');
+  } catch {
+    return null;
+  }
+}
+
+function processState(pid: number): 'running' | 'zombie' | 'gone' {
+  try {
+    process.kill(pid, 0);
+  } catch {
+    return 'gone';
+  }
+  if (process.platform !== 'linux') return 'running';
+  // An unreaped zombie still answers kill(0)
+  const stat = readProcStat(pid);
+  if (!stat) return 'gone';
+  return stat[0] === 'Z' ? 'zombie' : 'running';
+}
+
+function isProcessAlive(pid: number): boolean {
+  return processState(pid) === 'running';
+}
+
+/** Live members of a process group with their start times (Linux only). */
+function groupMembers(pgid: number): Map<number, string> {
+  const members = new Map<number, string>();
+  let entries: string[];
+  try {
+    entries = fs.readdirSync('/proc');
+  } catch {
+    return members;
+  }
+  for (const entry of entries) {
+    if (!/^\d+$/.test(entry)) continue;
+    const stat = readProcStat(entry);
+    // Field 5 is the process group, field 22 the start time
+    if (stat && stat[0] !== 'Z' && Number(stat[2]) === pgid) {
+      members.set(Number(entry), stat[19]);
+    }
+  }
+  return members;
+}
+
+/**
+ * Remember what is left of an exited agent's process group for the reaper.
+ * A group ID can be reused once its members are gone, so on Linux they are
+ * pinned by pid and start time; elsewhere there is no cheap way to tell, so
+ * the group only gets SIGTERM right after the exit, while the ID is still its.
+ */
+function trackOrphans(pgid: number): void {
+  if (process.platform !== 'linux') {
+    try {
+      process.kill(-pgid, 'SIGTERM');
+    } catch {
+      /* group already empty */
+    }
+    return;
+  }
+  const members = groupMembers(pgid);
+  if (members.size > 0) orphanGroups.set(pgid, { members, attempts: 0 });
+}
+
+/**
+ * One reaper pass: finish sessions whose process has been gone for two passes
+ * without an onExit (e.g. the node-pty reader went away), then terminate the
+ * remembered members of exited agents' process groups — SIGTERM first,
+ * SIGKILL on the next pass. Zombies are left to node-pty, whose onExit
+ * carries the real exit code and signal.
+ */
+function reapSessions(): void {
+  for (const session of [...sessions.values()]) {
+    if (processState(session.proc.pid) !== 'gone') {
+      vanished.delete(session);
+    } else if (vanished.has(session)) {
+      session.finish(null);
+    } else {
+      vanished.add(session);
+    }
+  }
+  for (const [pgid, group] of orphanGroups) {
+    const current = groupMembers(pgid);
+    const left = [...group.members.keys()].filter(
+      (pid) => current.get(pid) === group.members.get(pid),
+    );
+    for (const pid of left) {
+      try {
+        process.kill(pid, group.attempts === 0 ? 'SIGTERM' : 'SIGKILL');
+      } catch {
+        /* exited meanwhile */
+      }
+    }
+    if (left.length > 0 && group.attempts === 0) group.attempts = 1;
+    else orphanGroups.delete(pgid);
+  }
+}
+
+/** Periodically reap dead sessions and orphaned process groups. Returns a stop function. */
+export function startSessionReaper(): () => void {
+  const timer = setInterval(reapSessions, REAP_INTERVAL);
+  return () => clearInterval(timer);
+}
+
+/** Adjust output batching for a running agent; returns the effective settings. */
+export function tuneAgentOutput(agentId: string, opts: Partial<OutputTuning>): OutputTuning {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  session.tuning = resolveTuning(session.tuning, opts);
+  if (session.spawnArgs) session.spawnArgs.outputTuning = { ...session.tuning };
+  return { ...session.tuning };
+}
+
+/** Directory the agent's shell last reported, falling back to the spawn cwd. */
+export function getAgentCwd(agentId: string): string {
+  const session = sessions.get(agentId);
+  if (!session) throw new Error(`Agent not found: ${agentId}`);
+  return session.cwd;
+}
+
+/** Map of agent ID to the PID of its PTY child process. */
+export function getAgentPids(): Map<string, number> {
+  const pids = new Map<string, number>();
+  for (const [agentId, s] of sessions) pids.set(agentId, s.proc.pid);
+  return pids;
+}
@@ -0,0 +1,77 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi } from 'vitest';
+
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'recordings-'));
+vi.mock('electron', () => ({ app: { getPath: () => root, isPackaged: true } }));
+
+// history.db pulls in the PTY layer; only the bookkeeping calls matter here
+const recordRecordingStarted = vi.fn();
+const recordRecordingEnded = vi.fn();
+vi.mock('./history-db.js', () => ({
+  recordRecordingStarted: (...args: unknown[]) => recordRecordingStarted(...args),
+  recordRecordingEnded: (...args: unknown[]) => recordRecordingEnded(...args),
+}));
+
+import { exportRecording, listRecordings, startRecording } from './recordings.js';
+
+const waitForClose = () =>
+  new Promise<void>((resolve) => {
+    const poll = () => (recordRecordingEnded.mock.calls.length ? resolve() : setTimeout(poll, 5));
+    poll();
+  });
+
+// ---------------------------------------------------------------------------
+// startRecording
+// ---------------------------------------------------------------------------
+describe('startRecording', () => {
+  it('writes an asciicast v2 file the task lists and exports', async () => {
+    const recorder = startRecording({
+      taskId: 'task-1',
+      agentId: 'agent-1',
+      command: 'claude --verbose',
+      cols: 80,
+      rows: 24,
+    });
+    recorder.write('hello\r\n');
+    recorder.resize(120, 40);
+    recorder.close();
+    recorder.write('after close');
+    await waitForClose();
+
+    const [recording] = listRecordings('task-1');
+    expect(recording.name).toMatch(/^agent-1-\d+\.cast$/);
+    expect(recordRecordingStarted).toHaveBeenCalledWith(recording.path, 'task-1', 'agent-1');
+    expect(recordRecordingEnded).toHaveBeenCalledWith(recording.path, recording.size);
+
+    const text = fs.readFileSync(recording.path, 'utf8');
+    const lines = text.trimEnd().split('\n');
+    expect(lines).toHaveLength(3);
+    expect(JSON.parse(lines[0])).toEqual(
+      expect.objectContaining({ version: 2, width: 80, height: 24, title: 'claude --verbose' }),
+    );
+    // Events are [elapsed, kind, data]
+    expect(lines.slice(1).map((line) => JSON.parse(line).slice(1))).toEqual([
+      ['o', 'hello\r\n'],
+      ['r', '120x40'],
+    ]);
+
+    const dest = path.join(root, 'exported.cast');
+    exportRecording('task-1', recording.name, dest);
+    expect(fs.readFileSync(dest, 'utf8')).toBe(text);
+  });
+
+  it.each([
+    ['taskId', { taskId: '../task', agentId: 'agent' }],
+    ['agentId', { taskId: 'task', agentId: 'a/b' }],
+  ])('rejects a %s that is not a plain name', (label, ids) => {
+    expect(() => startRecording({ ...ids, command: 'sh', cols: 80, rows: 24 })).toThrow(
+      `${label} contains invalid characters`,
+    );
+  });
+
+  it('lists nothing for a task without recordings', () => {
+    expect(listRecordings('task-without-recordings')).toEqual([]);
+  });
+});
@@ -0,0 +1,99 @@
+import fs from 'fs';
+import path from 'path';
+import { recordRecordingEnded, recordRecordingStarted } from './history-db.js';
+import { getStateDir } from './persistence.js';
+
+/** Appends PTY output to an asciicast v2 file. */
+export interface Recorder {
+  write: (data: string) => void;
+  resize: (cols: number, rows: number) => void;
+  close: () => void;
+}
+
+export interface RecordingInfo {
+  name: string;
+  path: string;
+  size: number;
+  created_at: number;
+}
+
+function validateId(id: string, label: string): void {
+  if (!/^[\w-]+$/.test(id)) throw new Error(`${label} contains invalid characters`);
+}
+
+export function getRecordingsDir(taskId: string): string {
+  validateId(taskId, 'taskId');
+  return path.join(getStateDir(), 'recordings', taskId);
+}
+
+/** Start an asciicast v2 recording for an agent session. */
+export function startRecording(opts: {
+  taskId: string;
+  agentId: string;
+  command: string;
+  cols: number;
+  rows: number;
+}): Recorder {
+  validateId(opts.agentId, 'agentId');
+  const dir = getRecordingsDir(opts.taskId);
+  fs.mkdirSync(dir, { recursive: true });
+
+  const startedAt = Date.now();
+  const file = path.join(dir, `${opts.agentId}-${startedAt}.cast`);
+  const stream = fs.createWriteStream(file, { flags: 'a', mode: 0o600 });
+  const elapsed = () => (Date.now() - startedAt) / 1000;
+
+  const header = {
+    version: 2,
+    width: opts.cols,
+    height: opts.rows,
+    timestamp: Math.floor(startedAt / 1000),
+    title: opts.command,
+    env: { TERM: 'xterm-256color' },
+  };
+  stream.write(JSON.stringify(header) + '\n');
+  recordRecordingStarted(file, opts.taskId, opts.agentId);
+
+  let closed = false;
+  return {
+    write: (data) => {
+      if (!closed) stream.write(JSON.stringify([elapsed(), 'o', data]) + '\n');
+    },
+    resize: (cols, rows) => {
+      if (!closed) stream.write(JSON.stringify([elapsed(), 'r', `${cols}x${rows}`]) + '\n');
+    },
+    close: () => {
+      if (closed) return;
+      closed = true;
+      stream.end(() => recordRecordingEnded(file, stream.bytesWritten));
+    },
+  };
+}
+
+/** List recordings for a task, newest first. */
+export function listRecordings(taskId: string): RecordingInfo[] {
+  const dir = getRecordingsDir(taskId);
+  if (!fs.existsSync(dir)) return [];
+  const result: RecordingInfo[] = [];
+  for (const name of fs.readdirSync(dir)) {
+    if (!name.endsWith('.cast')) continue;
+    const full = path.join(dir, name);
+    try {
+      const stat = fs.statSync(full);
+      result.push({ name, path: full, size: stat.size, created_at: stat.birthtimeMs });
+    } catch {
+      /* ignore */
+    }
+  }
+  return result.sort((a, b) => b.created_at - a.created_at);
+}
+
+/** Copy a recording to a user-chosen destination. */
+export function exportRecording(taskId: string, name: string, destPath: string): void {
+  if (!name.endsWith('.cast') || name !== path.basename(name)) {
+    throw new Error('Invalid recording name');
+  }
+  const source = path.join(getRecordingsDir(taskId), name);
+  if (!fs.existsSync(source)) throw new Error(`Recording not found: ${name}`);
+  fs.copyFileSync(source, destPath);
+}
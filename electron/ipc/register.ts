@@ -3,14 +3,34 @@ import { fileURLToPath } from 'url';
 import { IPC } from './channels.js';
 import {
   spawnAgent,
+  spawnDetachableAgent,
+  reattachAgent,
+  restartAgent,
   writeToAgent,
+  writeBytesToAgent,
+  sendKey,
+  startSessionReaper,
   resizeAgent,
+  pauseAgentOutput,
+  resumeAgentOutput,
+  ackAgentOutput,
+  attachOutputChannel,
+  detachOutputChannel,
   pauseAgent,
   resumeAgent,
   killAgent,
   countRunningAgents,
   killAllAgents,
   getAgentMeta,
+  getAgentScrollback,
+  subscribeOutputPattern,
+  unsubscribeOutputPattern,
+  respondToPermission,
+  getAgentEnv,
+  listRunningAgents,
+  getAgentCwd,
+  setOutputThrottle,
+  tuneAgentOutput,
 } from './pty.js';
 import { startRemoteServer } from '../remote/server.js';
 import {
@@ -25,10 +45,214 @@ import {
   getBranchLog,
   pushTask,
   rebaseTask,
+  createTaskWorktree,
+  removeTaskWorktree,
+  listWorktrees,
+  pruneWorktrees,
+  resolveConflict,
+  continueMerge,
+  abortMerge,
+  commitTask,
+  getFileHunks,
+  stageHunk,
+  unstageHunk,
+  discardFileChanges,
+  discardAllChanges,
+  stashChanges,
+  listStashes,
+  applyStash,
+  dropStash,
+  getFileDiffBetween,
+  getTaskDiffVsBase,
+  previewMerge,
+  getRebasePlan,
+  executeRebasePlan,
+  getCommit,
+  getCommitFileDiff,
+  type SubmoduleMode,
+  getWorktreesDiskUsage,
+  cleanupStaleWorktrees,
+  getWorktreeLocationInfo,
+  setWorktreeLocation,
+  getMainBranchInfo,
+  setTaskBaseBranch,
+  getRunGitHooks,
+  setRunGitHooks,
+  setHookOutputSink,
+  getFileBlame,
+  listWorktreeTree,
+  getLinkedChangedFiles,
+  mergeLinkedTasks,
+  getWorktreeCopyFiles,
+  setWorktreeCopyFiles,
+  getWorktreeSetupCommand,
+  setWorktreeSetupCommand,
+  getProjectEnvProfile,
+  setProjectEnvProfile,
+  getSigningConfig,
+  getCredentialStatus,
+  continueWorktreeOperation,
+  abortWorktreeOperation,
 } from './git.js';
-import { createTask, deleteTask } from './tasks.js';
-import { listAgents } from './agents.js';
-import { saveAppState, loadAppState } from './persistence.js';
+import {
+  advanceTaskStatus,
+  assertTaskAcceptsAgents,
+  createTask,
+  deleteTask,
+  forgetTaskStatus,
+  forkTask,
+  getTaskStatuses,
+  initTaskStatus,
+  isTaskStatus,
+  listTaskAgents,
+  setTaskStatus,
+  startTaskLifecycle,
+} from './tasks.js';
+import { suggestBranchName } from './branch-name.js';
+import {
+  addAgentDef,
+  addAgentProfile,
+  deleteAgentDef,
+  deleteAgentProfile,
+  getAgentProfile,
+  listAgents,
+  updateAgentDef,
+  updateAgentProfile,
+  watchAgentConfig,
+} from './agents.js';
+import { listDetachedSessions } from './detached.js';
+import { getAgentStats, startAgentStatsPolling } from './stats.js';
+import { getSpawnPolicy, setSpawnPolicy } from './spawn-policy.js';
+import {
+  listWorktreeWatches,
+  stopWorktreeWatch,
+  watchWorktreeChanges,
+  unwatchWorktreeChanges,
+} from './worktree-watcher.js';
+import { checkBranchDivergence, setBackgroundFetch } from './divergence.js';
+import { answerAskpass, startAskpassServer } from './askpass.js';
+import {
+  createTaskFromTemplate,
+  deleteTaskTemplate,
+  listTaskTemplates,
+  saveTaskTemplate,
+} from './task-templates.js';
+import {
+  forgetTask,
+  getTaskDependencies,
+  initTaskDependencies,
+  markTaskLanded,
+  setTaskDependencies,
+} from './task-deps.js';
+import { archiveTask, listArchivedTasks, restoreTask } from './archive.js';
+import { suggestTaskName } from './task-name.js';
+import { getMetricsReport, getTaskMetrics, initTaskMetrics } from './task-metrics.js';
+import {
+  forgetTaskMetadata,
+  getTaskMetadata,
+  listTaskLabels,
+  queryTasks,
+  syncKnownTasks,
+  updateTaskMetadata,
+} from './task-metadata.js';
+import { deleteTaskNotes, getTaskNotes, updateTaskNotes } from './task-notes.js';
+import {
+  cancelHeadlessRun,
+  cancelTaskHeadlessRuns,
+  initHeadlessRuns,
+  runAgentHeadless,
+} from './headless.js';
+import { cancelSearch, searchWorktree } from './search.js';
+import { readWorktreeFile, writeWorktreeFile } from './worktree-files.js';
+import { detectEditors, getEditorSettings, openInEditor, setEditorSettings } from './editor.js';
+import { addProject, listProjects, removeProject } from './projects.js';
+import { addMcpServer, listMcpServers, toggleMcpServer } from './mcp.js';
+import { initReviews, startReviewAgent } from './review.js';
+import { handoffTask } from './handoff.js';
+import {
+  deletePromptSnippet,
+  listPromptSnippets,
+  savePromptSnippet,
+  sendPromptSnippet,
+} from './prompt-library.js';
+import { validateWorktreeLocation } from './worktree-location.js';
+import {
+  createPullRequest,
+  hasIntegrationToken,
+  setIntegrationToken,
+  type Provider,
+} from './integrations.js';
+import { getPullRequestStatus, startPullRequestPolling } from './pr-status.js';
+import { deleteSecret, getSecret, listSecrets, setSecret } from './secrets.js';
+import {
+  getSettings,
+  initSettings,
+  onSettingsChanged,
+  updateSettings,
+  type Settings,
+} from './settings.js';
+import { cliGitBackend, setGitBackend } from './git-backend.js';
+import { inProcessGitBackend } from './git-inprocess.js';
+import { exportWorkspace, importWorkspace } from './workspace.js';
+import { initEvents, isEventKind, queryEvents, recordError, recordEvent } from './events.js';
+import { getBackendLogs, getLogLevel, isLogLevel, setLogLevel } from './logs.js';
+import {
+  initWatcher,
+  listPathWatches,
+  listWatchHandles,
+  unwatch,
+  watchPath,
+} from './watcher.js';
+import {
+  initPlans,
+  listPlanWatches,
+  unwatchPlans,
+  watchForPlans,
+  writePlanFile,
+} from './plans.js';
+import { deletePlanHistory, getPlanDiff, getPlanHistory } from './plan-history.js';
+import { parsePlan, togglePlanItem } from './plan-checklist.js';
+import {
+  deleteEnvProfile,
+  listEnvProfiles,
+  profileEnvFor,
+  refreshLoginPath,
+  saveEnvProfile,
+} from './shell-env.js';
+import {
+  approvePlan,
+  forgetPlanApproval,
+  getPlanApprovals,
+  initPlanApproval,
+  requestPlanChanges,
+} from './plan-approval.js';
+import {
+  getNotificationSettings,
+  initNotifications,
+  notifyMergeConflict,
+  setNotificationSettings,
+  testNotification,
+} from './notifications.js';
+import { listRecordings, exportRecording } from './recordings.js';
+import {
+  initSpawnQueue,
+  requestSpawn,
+  cancelQueuedSpawn,
+  setAgentLimit,
+  getSpawnQueue,
+} from './spawn-queue.js';
+import {
+  saveAppState,
+  loadAppState,
+  listStateBackups,
+  restoreStateBackup,
+} from './persistence.js';
+import {
+  initHistoryDb,
+  listTaskSessions,
+  queryTaskHistory,
+  syncTaskHistory,
+} from './history-db.js';
 import path from 'path';
 
 /** Reject paths that are non-absolute or attempt directory traversal. */
@@ -50,54 +274,513 @@ function validateBranchName(name: unknown, label: string): void {
   if (name.startsWith('-')) throw new Error(`${label} must not start with "-"`);
 }
 
+/** Reject unknown submodule modes; undefined keeps the default. */
+function validateSubmoduleMode(mode: unknown): SubmoduleMode | undefined {
+  if (mode === undefined || mode === 'none' || mode === 'full' || mode === 'shallow') return mode;
+  throw new Error("submodules must be 'none', 'full' or 'shallow'");
+}
+
+/** Reject unknown code-hosting providers. */
+function validateProvider(p: unknown): Provider {
+  if (p !== 'github' && p !== 'gitlab') throw new Error("provider must be 'github' or 'gitlab'");
+  return p;
+}
+
+/** Reject anything but a list of non-empty glob strings. */
+function validateGlobs(globs: unknown, label: string): void {
+  if (!Array.isArray(globs) || !globs.every((g) => typeof g === 'string' && g.length > 0)) {
+    throw new Error(`${label} must be an array of non-empty strings`);
+  }
+}
+
 export function registerAllHandlers(win: BrowserWindow): void {
   // --- Remote access state ---
   let remoteServer: ReturnType<typeof startRemoteServer> | null = null;
   const taskNames = new Map<string, string>();
 
   // --- PTY commands ---
-  ipcMain.handle(IPC.SpawnAgent, (_e, args) => {
+  ipcMain.handle(IPC.SpawnAgent, async (_e, args) => {
     if (args.cwd) validatePath(args.cwd, 'cwd');
-    return spawnAgent(win, args);
+    assertTaskAcceptsAgents(args.taskId);
+    args = { ...args, profileEnv: await profileEnvFor(args.agentDefId, args.cwd) };
+    if (typeof args.profileId === 'string' && args.profileId) {
+      const profile = getAgentProfile(String(args.agentDefId), args.profileId);
+      args = {
+        ...args,
+        args: [...(args.args ?? []), ...profile.args],
+        env: { ...args.env, ...profile.env },
+      };
+    }
+    return requestSpawn(args, () =>
+      args.detachable ? spawnDetachableAgent(win, args) : spawnAgent(win, args),
+    );
+  });
+  ipcMain.handle(IPC.RefreshLoginPath, () => refreshLoginPath());
+  ipcMain.handle(IPC.ListEnvProfiles, () => listEnvProfiles());
+  ipcMain.handle(IPC.SaveEnvProfile, (_e, args) => saveEnvProfile(args.profile));
+  ipcMain.handle(IPC.DeleteEnvProfile, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    deleteEnvProfile(args.id);
   });
-  ipcMain.handle(IPC.WriteToAgent, (_e, args) => writeToAgent(args.agentId, args.data));
+  ipcMain.handle(IPC.WriteToAgent, (_e, args) =>
+    writeToAgent(args.agentId, args.data, { paste: args.paste }),
+  );
+  ipcMain.handle(IPC.SendKey, (_e, args) => sendKey(args.agentId, args.key));
+  ipcMain.handle(IPC.WriteBytesToAgent, (_e, args) =>
+    writeBytesToAgent(args.agentId, args.dataBase64),
+  );
   ipcMain.handle(IPC.ResizeAgent, (_e, args) => resizeAgent(args.agentId, args.cols, args.rows));
+  ipcMain.handle(IPC.PauseAgentOutput, (_e, args) => pauseAgentOutput(args.agentId));
+  ipcMain.handle(IPC.ResumeAgentOutput, (_e, args) => resumeAgentOutput(args.agentId));
+  ipcMain.handle(IPC.AttachOutputChannel, (_e, args) =>
+    attachOutputChannel(win, args.agentId, args.onOutput),
+  );
+  ipcMain.handle(IPC.DetachOutputChannel, (_e, args) =>
+    detachOutputChannel(args.agentId, args.channelId),
+  );
+  ipcMain.handle(IPC.AckAgentOutput, (_e, args) => ackAgentOutput(win, args.agentId, args.bytes));
   ipcMain.handle(IPC.PauseAgent, (_e, args) => pauseAgent(args.agentId));
   ipcMain.handle(IPC.ResumeAgent, (_e, args) => resumeAgent(args.agentId));
-  ipcMain.handle(IPC.KillAgent, (_e, args) => killAgent(args.agentId));
+  ipcMain.handle(IPC.KillAgent, (_e, args) => {
+    if (cancelQueuedSpawn(args.agentId)) return;
+    killAgent(args.agentId, { graceful: args.graceful, graceMs: args.graceMs });
+    recordEvent('agent_kill', args.graceful ? 'Agent stopped' : 'Agent killed', {
+      agentId: args.agentId,
+    });
+  });
   ipcMain.handle(IPC.CountRunningAgents, () => countRunningAgents());
   ipcMain.handle(IPC.KillAllAgents, () => killAllAgents());
+  ipcMain.handle(IPC.GetAgentScrollback, (_e, args) => getAgentScrollback(args.agentId));
+  ipcMain.handle(IPC.ListDetachedSessions, () => listDetachedSessions());
+  ipcMain.handle(IPC.ReattachAgent, (_e, args) => reattachAgent(win, args));
+  ipcMain.handle(IPC.RestartAgent, (_e, args) => restartAgent(win, args.agentId));
+  ipcMain.handle(IPC.GetAgentStats, (_e, args) => getAgentStats(args.agentId));
+  ipcMain.handle(IPC.ListRunningAgents, () => listRunningAgents());
+  ipcMain.handle(IPC.GetAgentEnv, (_e, args) => getAgentEnv(args.agentId));
+  ipcMain.handle(IPC.GetAgentCwd, (_e, args) => getAgentCwd(args.agentId));
+  ipcMain.handle(IPC.TuneAgentOutput, (_e, args) =>
+    tuneAgentOutput(args.agentId, {
+      batchMax: args.batchMax,
+      batchIntervalMs: args.batchIntervalMs,
+      smallFlush: args.smallFlush,
+    }),
+  );
+  ipcMain.handle(IPC.SetOutputThrottle, (_e, args) =>
+    setOutputThrottle(args.agentId, {
+      enabled: args.enabled,
+      maxBytesPerSec: args.maxBytesPerSec,
+      sustainSeconds: args.sustainSeconds,
+    }),
+  );
+  ipcMain.handle(IPC.SubscribeOutputPattern, (_e, args) =>
+    subscribeOutputPattern(args.agentId, args.pattern, args.triggerId),
+  );
+  ipcMain.handle(IPC.UnsubscribeOutputPattern, (_e, args) =>
+    unsubscribeOutputPattern(args.agentId, args.triggerId),
+  );
+  ipcMain.handle(IPC.RespondToPermission, (_e, args) => {
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    if (typeof args.approve !== 'boolean') throw new Error('approve must be a boolean');
+    respondToPermission(args.agentId, args.approve);
+  });
+  startAgentStatsPolling(win);
+  startPullRequestPolling(win);
+  initNotifications(win);
+  startAskpassServer(win);
+  startSessionReaper();
+  initSpawnQueue(win);
+  initSettings(win);
+  watchAgentConfig();
+  setAgentLimit(getSettings().agent_limit);
+  onSettingsChanged((settings) => setAgentLimit(settings.agent_limit));
+  const applyGitBackend = (settings: Settings) =>
+    setGitBackend(settings.git_backend === 'in-process' ? inProcessGitBackend : cliGitBackend);
+  applyGitBackend(getSettings());
+  onSettingsChanged(applyGitBackend);
+  ipcMain.handle(IPC.GetSpawnPolicy, () => getSpawnPolicy());
+  ipcMain.handle(IPC.SetSpawnPolicy, (_e, args) => setSpawnPolicy(args.policy));
+  ipcMain.handle(IPC.SetAgentLimit, (_e, args) => {
+    updateSettings({ agent_limit: args.limit });
+  });
+  ipcMain.handle(IPC.GetSpawnQueue, () => getSpawnQueue());
+  ipcMain.handle(IPC.ListRecordings, (_e, args) => listRecordings(args.taskId));
+  ipcMain.handle(IPC.ExportRecording, (_e, args) => {
+    validatePath(args.destPath, 'destPath');
+    return exportRecording(args.taskId, args.name, args.destPath);
+  });
 
   // --- Agent commands ---
   ipcMain.handle(IPC.ListAgents, () => listAgents());
+  ipcMain.handle(IPC.AddAgentDef, (_e, args) => addAgentDef(args.agent));
+  ipcMain.handle(IPC.UpdateAgentDef, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    return updateAgentDef(args.id, args.agent);
+  });
+  ipcMain.handle(IPC.DeleteAgentDef, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    deleteAgentDef(args.id);
+  });
+  ipcMain.handle(IPC.AddAgentProfile, (_e, args) => {
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    return addAgentProfile(args.agentId, args.profile);
+  });
+  ipcMain.handle(IPC.UpdateAgentProfile, (_e, args) => {
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    if (typeof args.profileId !== 'string') throw new Error('profileId must be a string');
+    return updateAgentProfile(args.agentId, args.profileId, args.profile);
+  });
+  ipcMain.handle(IPC.DeleteAgentProfile, (_e, args) => {
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    if (typeof args.profileId !== 'string') throw new Error('profileId must be a string');
+    deleteAgentProfile(args.agentId, args.profileId);
+  });
+  initHeadlessRuns(win);
+  ipcMain.handle(IPC.RunAgentHeadless, async (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    if (typeof args.prompt !== 'string' || !args.prompt.trim()) {
+      throw new Error('prompt must not be empty');
+    }
+    validatePath(args.cwd, 'cwd');
+    return runAgentHeadless({
+      taskId: args.taskId,
+      agentId: args.agentId,
+      prompt: args.prompt,
+      cwd: args.cwd,
+      env: args.env,
+      profileEnv: await profileEnvFor(args.agentId, args.cwd),
+    });
+  });
+  ipcMain.handle(IPC.CancelHeadlessRun, (_e, args) => {
+    if (typeof args.runId !== 'string') throw new Error('runId must be a string');
+    cancelHeadlessRun(args.runId);
+  });
 
   // --- Task commands ---
   ipcMain.handle(IPC.CreateTask, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
-    const result = createTask(args.name, args.projectRoot, args.symlinkDirs, args.branchPrefix);
-    result.then((r: { id: string }) => taskNames.set(r.id, args.name)).catch(() => {});
+    if (args.branchName !== undefined) validateBranchName(args.branchName, 'branchName');
+    const result = createTask(
+      args.name,
+      args.projectRoot,
+      args.symlinkDirs,
+      args.branchPrefix,
+      args.baseRef,
+      validateSubmoduleMode(args.submodules),
+      args.branchTemplate,
+      args.branchName,
+      args.copyFiles !== false,
+    );
+    result
+      .then((r: { id: string; branch_name: string }) => {
+        taskNames.set(r.id, args.name);
+        recordEvent('task_create', `Created task on ${r.branch_name}`, { taskId: r.id });
+      })
+      .catch((e) => recordError('Create task', e));
+    return result;
+  });
+  ipcMain.handle(IPC.ForkTask, async (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validatePath(args.worktreePath, 'worktreePath');
+    validateBranchName(args.newBranch, 'newBranch');
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    const forked = await forkTask(args.projectRoot, args.worktreePath, args.newBranch);
+    taskNames.set(forked.id, String(args.name ?? args.newBranch));
+    const { labels, priority } = getTaskMetadata(args.taskId);
+    if (labels.length > 0 || priority !== 'normal') {
+      updateTaskMetadata(forked.id, { labels, priority });
+    }
+    return forked;
+  });
+  ipcMain.handle(IPC.SuggestTaskName, (_e, args) => {
+    if (typeof args.prompt !== 'string') throw new Error('prompt must be a string');
+    return suggestTaskName(args.prompt, typeof args.agentId === 'string' ? args.agentId : null);
+  });
+  ipcMain.handle(IPC.ListTaskTemplates, () => listTaskTemplates());
+  ipcMain.handle(IPC.SaveTaskTemplate, (_e, args) => saveTaskTemplate(args.template));
+  ipcMain.handle(IPC.DeleteTaskTemplate, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    deleteTaskTemplate(args.id);
+  });
+  ipcMain.handle(IPC.CreateTaskFromTemplate, (_e, args) => {
+    if (typeof args.templateId !== 'string') throw new Error('templateId must be a string');
+    const params = args.params ?? {};
+    validatePath(params.projectRoot, 'projectRoot');
+    if (params.branchName !== undefined) validateBranchName(params.branchName, 'branchName');
+    const result = createTaskFromTemplate(args.templateId, params);
+    result.then((r) => taskNames.set(r.id, params.name)).catch(() => {});
     return result;
   });
-  ipcMain.handle(IPC.DeleteTask, (_e, args) => {
+  ipcMain.handle(IPC.DeleteTask, async (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateBranchName(args.branchName, 'branchName');
+    const taskId = typeof args.taskId === 'string' ? args.taskId : undefined;
+    if (taskId) {
+      cancelTaskHeadlessRuns(taskId);
+      unwatchPlans(taskId);
+      stopWorktreeWatch(taskId);
+    }
+    try {
+      await deleteTask(args.agentIds, args.branchName, args.deleteBranch, args.projectRoot, taskId);
+    } catch (e) {
+      recordError('Delete task', e, { taskId });
+      throw e;
+    }
+    recordEvent('task_delete', `Deleted task on ${args.branchName}`, { taskId });
+    if (taskId) {
+      forgetTask(taskId);
+      forgetTaskStatus(taskId);
+      forgetTaskMetadata(taskId);
+      deleteTaskNotes(taskId);
+      deletePlanHistory(taskId);
+      forgetPlanApproval(taskId);
+    }
+  });
+  initReviews(win);
+  ipcMain.handle(IPC.StartReviewAgent, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    if (typeof args.reviewerAgentId !== 'string') {
+      throw new Error('reviewerAgentId must be a string');
+    }
+    validatePath(args.worktreePath, 'worktreePath');
+    return startReviewAgent({
+      taskId: args.taskId,
+      reviewerAgentId: args.reviewerAgentId,
+      worktreePath: args.worktreePath,
+      env: args.env,
+    });
+  });
+  ipcMain.handle(IPC.HandoffTask, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    if (typeof args.fromAgentId !== 'string') throw new Error('fromAgentId must be a string');
+    if (typeof args.fromAgentName !== 'string') throw new Error('fromAgentName must be a string');
+    if (typeof args.toAgentName !== 'string') throw new Error('toAgentName must be a string');
+    validatePath(args.worktreePath, 'worktreePath');
+    return handoffTask({
+      taskId: args.taskId,
+      fromAgentId: args.fromAgentId,
+      fromAgentName: args.fromAgentName,
+      toAgentName: args.toAgentName,
+      worktreePath: args.worktreePath,
+    });
+  });
+  ipcMain.handle(IPC.ListTaskAgents, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return listTaskAgents(args.taskId);
+  });
+  ipcMain.handle(IPC.ArchiveTask, async (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
+    validatePath(args.worktreePath, 'worktreePath');
     validateBranchName(args.branchName, 'branchName');
-    return deleteTask(args.agentIds, args.branchName, args.deleteBranch, args.projectRoot);
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    unwatchPlans(args.taskId);
+    stopWorktreeWatch(args.taskId);
+    const archived = await archiveTask({
+      taskId: args.taskId,
+      name: String(args.name ?? ''),
+      projectRoot: args.projectRoot,
+      worktreePath: args.worktreePath,
+      branchName: args.branchName,
+      agentIds: args.agentIds ?? [],
+      agentId: typeof args.agentId === 'string' ? args.agentId : null,
+    });
+    forgetTask(args.taskId);
+    forgetTaskStatus(args.taskId);
+    return archived;
+  });
+  ipcMain.handle(IPC.ListArchivedTasks, () => listArchivedTasks());
+  ipcMain.handle(IPC.RestoreTask, async (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    const restored = await restoreTask(args.taskId, args.symlinkDirs ?? []);
+    taskNames.set(restored.id, restored.name);
+    startTaskLifecycle(restored.id);
+    return restored;
+  });
+  initTaskStatus(win);
+  ipcMain.handle(IPC.GetTaskStatuses, () => getTaskStatuses());
+  ipcMain.handle(IPC.SetTaskStatus, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    if (!isTaskStatus(args.status)) throw new Error(`Unknown task status: ${args.status}`);
+    return setTaskStatus(args.taskId, args.status);
+  });
+  initTaskMetrics();
+  initEvents();
+  ipcMain.handle(IPC.QueryEvents, (_e, args) => {
+    const filter = args?.filter ?? {};
+    if (filter.kinds !== undefined) {
+      if (!Array.isArray(filter.kinds) || !filter.kinds.every(isEventKind)) {
+        throw new Error('kinds must be a list of event kinds');
+      }
+    }
+    const since = typeof args?.since === 'number' ? args.since : 0;
+    const limit = Number.isInteger(args?.limit) && args.limit > 0 ? args.limit : undefined;
+    return queryEvents(filter, since, limit);
+  });
+  // After the task metrics, whose exit handling the history's snapshots rely on
+  void initHistoryDb();
+  ipcMain.handle(IPC.QueryTaskHistory, (_e, args) => queryTaskHistory(args?.filter ?? {}));
+  ipcMain.handle(IPC.ListTaskSessions, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return listTaskSessions(args.taskId);
+  });
+  ipcMain.handle(IPC.GetTaskMetrics, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return getTaskMetrics(args.taskId);
+  });
+  ipcMain.handle(IPC.GetMetricsReport, (_e, args) => {
+    const ids: unknown = args?.taskIds;
+    if (ids !== undefined && (!Array.isArray(ids) || !ids.every((id) => typeof id === 'string'))) {
+      throw new Error('taskIds must be an array of task ids');
+    }
+    return getMetricsReport(args?.taskIds);
+  });
+  ipcMain.handle(IPC.UpdateTaskMetadata, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return updateTaskMetadata(args.taskId, { labels: args.labels, priority: args.priority });
+  });
+  ipcMain.handle(IPC.GetTaskNotes, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return getTaskNotes(args.taskId);
+  });
+  ipcMain.handle(IPC.UpdateTaskNotes, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return updateTaskNotes(args.taskId, { notes: args.notes, checklist: args.checklist });
+  });
+  ipcMain.handle(IPC.QueryTasks, (_e, args) => queryTasks(args?.filter ?? {}));
+  ipcMain.handle(IPC.ListTaskLabels, () => listTaskLabels());
+  initTaskDependencies(win);
+  ipcMain.handle(IPC.SetTaskDependencies, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    const ids: unknown = args.dependsOn;
+    if (!Array.isArray(ids) || !ids.every((id) => typeof id === 'string')) {
+      throw new Error('dependsOn must be an array of task ids');
+    }
+    return setTaskDependencies(args.taskId, args.dependsOn);
   });
+  ipcMain.handle(IPC.GetTaskDependencies, (_e, args) => getTaskDependencies(args.taskId));
 
   // --- Git commands ---
   ipcMain.handle(IPC.GetChangedFiles, (_e, args) => {
     validatePath(args.worktreePath, 'worktreePath');
     return getChangedFiles(args.worktreePath);
   });
+  ipcMain.handle(IPC.GetLinkedChangedFiles, (_e, args) => {
+    if (!Array.isArray(args.worktreePaths)) throw new Error('worktreePaths must be an array');
+    args.worktreePaths.forEach((p: unknown, i: number) => validatePath(p, `worktreePaths[${i}]`));
+    return getLinkedChangedFiles(args.worktreePaths);
+  });
   ipcMain.handle(IPC.GetFileDiff, (_e, args) => {
     validatePath(args.worktreePath, 'worktreePath');
     validateRelativePath(args.filePath, 'filePath');
     return getFileDiff(args.worktreePath, args.filePath);
   });
+  ipcMain.handle(IPC.GetFileDiffBetween, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.filePath, 'filePath');
+    return getFileDiffBetween(args.worktreePath, args.filePath, args.fromRef, args.toRef ?? null);
+  });
+  ipcMain.handle(IPC.GetTaskDiffVsBase, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    if (args.filePath) validateRelativePath(args.filePath, 'filePath');
+    return getTaskDiffVsBase(args.worktreePath, args.filePath ?? null);
+  });
+  ipcMain.handle(IPC.WatchWorktreeChanges, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return watchWorktreeChanges(win, args.taskId, args.worktreePath);
+  });
+  ipcMain.handle(IPC.UnwatchWorktreeChanges, (_e, args) => unwatchWorktreeChanges(args.taskId));
+  initWatcher(win);
+  ipcMain.handle(IPC.WatchPath, (_e, args) => {
+    if (typeof args.id !== 'string' || !args.id) throw new Error('id must be a non-empty string');
+    validatePath(args.path, 'path');
+    watchPath(args.id, args.path, args.opts ?? {});
+  });
+  ipcMain.handle(IPC.Unwatch, (_e, args) => unwatch(args.id));
+  initPlans(win);
+  ipcMain.handle(IPC.WatchForPlans, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    if (!Array.isArray(args.agentIds)) throw new Error('agentIds must be an array');
+    return watchForPlans(args.taskId, args.worktreePath, args.agentIds);
+  });
+  ipcMain.handle(IPC.UnwatchPlans, (_e, args) => unwatchPlans(args.taskId));
+  ipcMain.handle(IPC.GetPlanHistory, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return getPlanHistory(args.taskId);
+  });
+  ipcMain.handle(IPC.GetPlanDiff, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return getPlanDiff(args.taskId, args.revA, args.revB);
+  });
+  ipcMain.handle(IPC.ParsePlan, (_e, args) => {
+    validatePath(args.path, 'path');
+    return parsePlan(args.path);
+  });
+  ipcMain.handle(IPC.TogglePlanItem, (_e, args) => {
+    validatePath(args.path, 'path');
+    if (typeof args.itemId !== 'string') throw new Error('itemId must be a string');
+    return togglePlanItem(args.path, args.itemId);
+  });
+  initPlanApproval(win);
+  ipcMain.handle(IPC.GetPlanApprovals, () => getPlanApprovals());
+  ipcMain.handle(IPC.ApprovePlan, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    return approvePlan(args.taskId);
+  });
+  ipcMain.handle(IPC.RequestPlanChanges, (_e, args) => {
+    if (typeof args.taskId !== 'string') throw new Error('taskId must be a string');
+    if (typeof args.feedback !== 'string') throw new Error('feedback must be a string');
+    return requestPlanChanges(args.taskId, args.feedback);
+  });
+  ipcMain.handle(IPC.WritePlanFile, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.path, 'path');
+    if (typeof args.content !== 'string') throw new Error('content must be a string');
+    const expected = args.expectedHash;
+    if (expected !== undefined && expected !== null && typeof expected !== 'string') {
+      throw new Error('expectedHash must be a string or null');
+    }
+    return writePlanFile(args.worktreePath, args.path, args.content, expected);
+  });
+  ipcMain.handle(IPC.SearchWorktree, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    if (typeof args.searchId !== 'string' || !args.searchId) {
+      throw new Error('searchId must be a non-empty string');
+    }
+    if (typeof args.query !== 'string' || !args.query) {
+      throw new Error('query must be a non-empty string');
+    }
+    const opts = args.opts ?? {};
+    if (opts.include !== undefined) validateGlobs(opts.include, 'opts.include');
+    if (opts.exclude !== undefined) validateGlobs(opts.exclude, 'opts.exclude');
+    return searchWorktree(win, args.searchId, args.worktreePath, args.query, opts);
+  });
+  ipcMain.handle(IPC.CancelSearch, (_e, args) => cancelSearch(args.searchId));
   ipcMain.handle(IPC.GetGitignoredDirs, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
     return getGitIgnoredDirs(args.projectRoot);
   });
+  ipcMain.handle(IPC.ListWorktreeTree, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    const subdir = args.subdir ?? '';
+    if (subdir) validateRelativePath(subdir, 'subdir');
+    return listWorktreeTree(args.worktreePath, subdir, args.depth ?? 1);
+  });
+  ipcMain.handle(IPC.ReadWorktreeFile, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.filePath, 'filePath');
+    return readWorktreeFile(args.worktreePath, args.filePath);
+  });
+  ipcMain.handle(IPC.WriteWorktreeFile, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.filePath, 'filePath');
+    if (typeof args.content !== 'string') throw new Error('content must be a string');
+    return writeWorktreeFile(
+      args.worktreePath,
+      args.filePath,
+      args.content,
+      args.expectedMtimeMs ?? null,
+    );
+  });
   ipcMain.handle(IPC.GetWorktreeStatus, (_e, args) => {
     validatePath(args.worktreePath, 'worktreePath');
     return getWorktreeStatus(args.worktreePath);
@@ -106,43 +789,456 @@ export function registerAllHandlers(win: BrowserWindow): void {
     validatePath(args.worktreePath, 'worktreePath');
     return checkMergeStatus(args.worktreePath);
   });
+  ipcMain.handle(IPC.PreviewMerge, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateBranchName(args.branchName, 'branchName');
+    return previewMerge(args.projectRoot, args.branchName);
+  });
+  ipcMain.handle(IPC.MergeLinkedTasks, (_e, args) => {
+    if (!Array.isArray(args.entries)) throw new Error('entries must be an array');
+    args.entries.forEach((e: { projectRoot: unknown; branchName: unknown }, i: number) => {
+      validatePath(e.projectRoot, `entries[${i}].projectRoot`);
+      validateBranchName(e.branchName, `entries[${i}].branchName`);
+    });
+    if (typeof args.taskId === 'string') advanceTaskStatus(args.taskId, 'merging');
+    const result = mergeLinkedTasks(args.entries, args.squash, args.message ?? null, args.cleanup);
+    if (typeof args.taskId === 'string') {
+      result
+        .then(() => {
+          markTaskLanded(args.taskId);
+          advanceTaskStatus(args.taskId, 'done');
+        })
+        .catch(() => advanceTaskStatus(args.taskId, 'review'));
+    }
+    return result;
+  });
   ipcMain.handle(IPC.MergeTask, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
     validateBranchName(args.branchName, 'branchName');
-    return mergeTask(args.projectRoot, args.branchName, args.squash, args.message, args.cleanup);
+    if (typeof args.taskId === 'string') advanceTaskStatus(args.taskId, 'merging');
+    const result = mergeTask(
+      args.projectRoot,
+      args.branchName,
+      args.squash,
+      args.message,
+      args.cleanup,
+      args.keepConflicts ?? false,
+    );
+    // A merge stopped on conflicts lands later, through continue_merge
+    if (typeof args.taskId === 'string') {
+      result
+        .then((r) => {
+          // Stays `merging` until the conflicts are resolved or the merge is aborted
+          if (r.conflict) {
+            notifyMergeConflict(args.taskId);
+            return;
+          }
+          markTaskLanded(args.taskId);
+          advanceTaskStatus(args.taskId, 'done');
+        })
+        .catch(() => advanceTaskStatus(args.taskId, 'review'));
+    }
+    const taskId = typeof args.taskId === 'string' ? args.taskId : null;
+    result
+      .then((r) => {
+        const outcome = r.conflict ? 'stopped on conflicts' : 'merged';
+        recordEvent('merge', `${args.branchName} ${outcome}`, {
+          taskId,
+          data: { into: r.main_branch, added: r.lines_added, removed: r.lines_removed },
+        });
+      })
+      .catch((e) => recordError(`Merge of ${args.branchName}`, e, { taskId }));
+    return result;
+  });
+  ipcMain.handle(IPC.ResolveConflict, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateRelativePath(args.path, 'path');
+    const type = args.resolution?.type;
+    if (type !== 'ours' && type !== 'theirs' && type !== 'content') {
+      throw new Error("resolution.type must be 'ours', 'theirs' or 'content'");
+    }
+    if (type === 'content' && typeof args.resolution.content !== 'string') {
+      throw new Error('resolution.content must be a string');
+    }
+    return resolveConflict(args.projectRoot, args.path, args.resolution);
+  });
+  ipcMain.handle(IPC.ContinueMerge, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    const result = continueMerge(args.projectRoot);
+    if (typeof args.taskId === 'string') {
+      result
+        .then(() => {
+          markTaskLanded(args.taskId);
+          advanceTaskStatus(args.taskId, 'done');
+        })
+        .catch(() => {});
+    }
+    return result;
+  });
+  ipcMain.handle(IPC.AbortMerge, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    const result = abortMerge(args.projectRoot);
+    if (typeof args.taskId === 'string') {
+      result.then(() => advanceTaskStatus(args.taskId, 'review')).catch(() => {});
+    }
+    return result;
   });
   ipcMain.handle(IPC.GetBranchLog, (_e, args) => {
     validatePath(args.worktreePath, 'worktreePath');
-    return getBranchLog(args.worktreePath);
+    return getBranchLog(args.worktreePath, args.skip, args.limit);
+  });
+  ipcMain.handle(IPC.GetCommit, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return getCommit(args.worktreePath, args.sha);
+  });
+  ipcMain.handle(IPC.GetCommitFileDiff, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.filePath, 'filePath');
+    return getCommitFileDiff(args.worktreePath, args.sha, args.filePath);
+  });
+  ipcMain.handle(IPC.GetFileBlame, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.filePath, 'filePath');
+    return getFileBlame(args.worktreePath, args.filePath, args.ref ?? null);
+  });
+  ipcMain.handle(IPC.CommitTask, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    if (typeof args.message !== 'string' || !args.message.trim()) {
+      throw new Error('message must be a non-empty string');
+    }
+    const files: string[] | null = args.files ?? null;
+    if (files) files.forEach((f, i) => validateRelativePath(f, `files[${i}]`));
+    return commitTask(args.worktreePath, args.message, files, args.amend ?? false);
+  });
+  ipcMain.handle(IPC.GetFileHunks, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.path, 'path');
+    return getFileHunks(args.worktreePath, args.path);
+  });
+  ipcMain.handle(IPC.StageHunk, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.path, 'path');
+    return stageHunk(args.worktreePath, args.path, args.hunkHeader);
+  });
+  ipcMain.handle(IPC.UnstageHunk, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateRelativePath(args.path, 'path');
+    return unstageHunk(args.worktreePath, args.path, args.hunkHeader);
+  });
+  ipcMain.handle(IPC.DiscardFileChanges, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    const paths: string[] = args.paths ?? [];
+    paths.forEach((p, i) => validateRelativePath(p, `paths[${i}]`));
+    return discardFileChanges(args.worktreePath, paths);
+  });
+  ipcMain.handle(IPC.DiscardAllChanges, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return discardAllChanges(args.worktreePath, args.confirmToken ?? null);
+  });
+  ipcMain.handle(IPC.StashChanges, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return stashChanges(args.worktreePath, args.message ?? null, args.includeUntracked ?? true);
+  });
+  ipcMain.handle(IPC.ListStashes, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return listStashes(args.worktreePath);
+  });
+  ipcMain.handle(IPC.ApplyStash, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return applyStash(args.worktreePath, args.id, args.pop ?? false);
+  });
+  ipcMain.handle(IPC.DropStash, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return dropStash(args.worktreePath, args.id);
   });
   ipcMain.handle(IPC.PushTask, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
     validateBranchName(args.branchName, 'branchName');
-    return pushTask(args.projectRoot, args.branchName);
+    const taskId = typeof args.taskId === 'string' ? args.taskId : null;
+    const result = pushTask(args.projectRoot, args.branchName);
+    result
+      .then(() => recordEvent('push', `Pushed ${args.branchName}`, { taskId }))
+      .catch((e) => recordError(`Push of ${args.branchName}`, e, { taskId }));
+    return result;
   });
   ipcMain.handle(IPC.RebaseTask, (_e, args) => {
     validatePath(args.worktreePath, 'worktreePath');
-    return rebaseTask(args.worktreePath);
+    return rebaseTask(args.worktreePath, args.autostash ?? false);
+  });
+  ipcMain.handle(IPC.ContinueWorktreeOperation, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return continueWorktreeOperation(args.worktreePath);
+  });
+  ipcMain.handle(IPC.AbortWorktreeOperation, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return abortWorktreeOperation(args.worktreePath);
+  });
+  ipcMain.handle(IPC.GetRebasePlan, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return getRebasePlan(args.worktreePath);
+  });
+  ipcMain.handle(IPC.ExecuteRebasePlan, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    if (!Array.isArray(args.steps)) throw new Error('steps must be an array');
+    return executeRebasePlan(args.worktreePath, args.base, args.head, args.steps);
+  });
+  ipcMain.handle(IPC.FetchAndCompare, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    return checkBranchDivergence(win, args.taskId, args.worktreePath);
+  });
+  ipcMain.handle(IPC.SetBackgroundFetch, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    setBackgroundFetch(win, args.taskId, args.worktreePath, args.intervalSecs ?? null);
   });
   ipcMain.handle(IPC.GetMainBranch, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
     return getMainBranch(args.projectRoot);
   });
+  ipcMain.handle(IPC.GetMainBranchInfo, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getMainBranchInfo(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetTaskBaseBranch, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    if (args.branch !== null) validateBranchName(args.branch, 'branch');
+    return setTaskBaseBranch(args.projectRoot, args.branch);
+  });
+  ipcMain.handle(IPC.GetSigningConfig, (_e, args) => {
+    validatePath(args.repoPath, 'repoPath');
+    return getSigningConfig(args.repoPath);
+  });
+  ipcMain.handle(IPC.GetCredentialStatus, (_e, args) => {
+    validatePath(args.repoPath, 'repoPath');
+    return getCredentialStatus(args.repoPath);
+  });
+  ipcMain.handle(IPC.AnswerAskpass, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    const answer = args.answer ?? null;
+    if (answer !== null && typeof answer !== 'string') {
+      throw new Error('answer must be a string or null');
+    }
+    answerAskpass(args.id, answer);
+  });
+  ipcMain.handle(IPC.GetRunGitHooks, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getRunGitHooks(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetRunGitHooks, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    if (typeof args.enabled !== 'boolean') throw new Error('enabled must be a boolean');
+    return setRunGitHooks(args.projectRoot, args.enabled);
+  });
+  ipcMain.handle(IPC.GetWorktreeCopyFiles, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getWorktreeCopyFiles(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetWorktreeCopyFiles, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateGlobs(args.patterns, 'patterns');
+    (args.patterns as string[]).forEach((p, i) => validateRelativePath(p, `patterns[${i}]`));
+    return setWorktreeCopyFiles(args.projectRoot, args.patterns);
+  });
+  ipcMain.handle(IPC.GetWorktreeSetupCommand, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getWorktreeSetupCommand(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetWorktreeSetupCommand, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    const command = args.command ?? null;
+    if (command !== null && typeof command !== 'string') {
+      throw new Error('command must be a string or null');
+    }
+    return setWorktreeSetupCommand(args.projectRoot, command);
+  });
+  setHookOutputSink((output) => {
+    if (!win.isDestroyed()) win.webContents.send(IPC.GitHookOutput, output);
+  });
   ipcMain.handle(IPC.GetCurrentBranch, (_e, args) => {
     validatePath(args.projectRoot, 'projectRoot');
     return getCurrentBranch(args.projectRoot);
   });
+  ipcMain.handle(IPC.CreateTaskWorktree, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateBranchName(args.branchName, 'branchName');
+    return createTaskWorktree(
+      args.projectRoot,
+      args.branchName,
+      args.symlinkDirs ?? [],
+      args.baseRef,
+      validateSubmoduleMode(args.submodules),
+      args.copyFiles !== false,
+    );
+  });
+  ipcMain.handle(IPC.RemoveTaskWorktree, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    validateBranchName(args.branchName, 'branchName');
+    return removeTaskWorktree(args.projectRoot, args.branchName, args.deleteBranch ?? false);
+  });
+  ipcMain.handle(IPC.ListWorktrees, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return listWorktrees(args.projectRoot);
+  });
+  ipcMain.handle(IPC.PruneWorktrees, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return pruneWorktrees(args.projectRoot, args.removeOrphans ?? false);
+  });
+  ipcMain.handle(IPC.SuggestBranchName, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return suggestBranchName(args.projectRoot, args.title, args.branchPrefix, args.branchTemplate);
+  });
+  ipcMain.handle(IPC.GetWorktreeLocation, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getWorktreeLocationInfo(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetWorktreeLocation, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return setWorktreeLocation(
+      args.projectRoot,
+      validateWorktreeLocation(args.location),
+      args.migrate ?? false,
+    );
+  });
+  ipcMain.handle(IPC.GetWorktreesDiskUsage, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getWorktreesDiskUsage(args.projectRoot);
+  });
+  ipcMain.handle(IPC.CleanupStaleWorktrees, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    const keepPaths: string[] = args.keepPaths ?? [];
+    keepPaths.forEach((p) => validatePath(p, 'keepPaths'));
+    return cleanupStaleWorktrees(
+      args.projectRoot,
+      args.olderThanDays,
+      keepPaths,
+      args.dryRun ?? false,
+    );
+  });
+
+  // --- Integrations ---
+  ipcMain.handle(IPC.CreatePullRequest, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    validateBranchName(args.branchName, 'branchName');
+    return createPullRequest(
+      args.taskId,
+      args.worktreePath,
+      args.branchName,
+      String(args.title ?? ''),
+      String(args.body ?? ''),
+      args.draft ?? false,
+    );
+  });
+  ipcMain.handle(IPC.SetIntegrationToken, (_e, args) =>
+    setIntegrationToken(validateProvider(args.provider), args.host, args.token ?? null),
+  );
+  ipcMain.handle(IPC.HasIntegrationToken, (_e, args) =>
+    hasIntegrationToken(validateProvider(args.provider), args.host),
+  );
+  ipcMain.handle(IPC.ListSecrets, () => listSecrets());
+  ipcMain.handle(IPC.SetSecret, (_e, args) => {
+    if (typeof args.value !== 'string' || !args.value) throw new Error('value must not be empty');
+    setSecret(args.name, args.value);
+  });
+  ipcMain.handle(IPC.GetSecret, (_e, args) => getSecret(args.name));
+  ipcMain.handle(IPC.DeleteSecret, (_e, args) => deleteSecret(args.name));
+  ipcMain.handle(IPC.GetPullRequestStatus, (_e, args) => getPullRequestStatus(args.taskId));
+  ipcMain.handle(IPC.OpenInEditor, (_e, args) => {
+    validatePath(args.worktreePath, 'worktreePath');
+    const file = args.file ?? null;
+    if (file !== null) validateRelativePath(file, 'file');
+    return openInEditor(args.worktreePath, file, args.line ?? null);
+  });
+  ipcMain.handle(IPC.DetectEditors, () => detectEditors());
+  ipcMain.handle(IPC.GetEditorSettings, () => getEditorSettings());
+  ipcMain.handle(IPC.SetEditorSettings, (_e, args) => {
+    const template = args.settings?.command_template ?? null;
+    if (template !== null && typeof template !== 'string') {
+      throw new Error('command_template must be a string or null');
+    }
+    return setEditorSettings({ command_template: template });
+  });
+
+  // --- Projects ---
+  ipcMain.handle(IPC.AddProject, (_e, args) => {
+    validatePath(args.path, 'path');
+    return addProject(args.path);
+  });
+  ipcMain.handle(IPC.ListProjects, () => listProjects());
+  ipcMain.handle(IPC.RemoveProject, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return removeProject(args.projectRoot);
+  });
+  ipcMain.handle(IPC.GetProjectEnvProfile, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    return getProjectEnvProfile(args.projectRoot);
+  });
+  ipcMain.handle(IPC.SetProjectEnvProfile, (_e, args) => {
+    validatePath(args.projectRoot, 'projectRoot');
+    const profileId = args.profileId ?? null;
+    if (profileId !== null && typeof profileId !== 'string') {
+      throw new Error('profileId must be a string or null');
+    }
+    return setProjectEnvProfile(args.projectRoot, profileId);
+  });
+  // MCP config lives in a project or worktree directory
+  ipcMain.handle(IPC.ListMcpServers, (_e, args) => {
+    validatePath(args.dir, 'dir');
+    return listMcpServers(args.dir);
+  });
+  ipcMain.handle(IPC.AddMcpServer, (_e, args) => {
+    validatePath(args.dir, 'dir');
+    const agentIds: unknown = args.agentIds;
+    if (
+      agentIds !== undefined &&
+      !(Array.isArray(agentIds) && agentIds.every((id) => typeof id === 'string'))
+    ) {
+      throw new Error('agentIds must be an array of strings');
+    }
+    return addMcpServer(args.dir, args.server, agentIds);
+  });
+  ipcMain.handle(IPC.ToggleMcpServer, (_e, args) => {
+    validatePath(args.dir, 'dir');
+    if (typeof args.name !== 'string') throw new Error('name must be a string');
+    if (typeof args.enabled !== 'boolean') throw new Error('enabled must be a boolean');
+    toggleMcpServer(args.dir, args.name, args.enabled);
+  });
+
+  // --- Prompts ---
+  ipcMain.handle(IPC.ListPromptSnippets, () => listPromptSnippets());
+  ipcMain.handle(IPC.SavePromptSnippet, (_e, args) => savePromptSnippet(args.prompt));
+  ipcMain.handle(IPC.DeletePromptSnippet, (_e, args) => {
+    if (typeof args.id !== 'string') throw new Error('id must be a string');
+    deletePromptSnippet(args.id);
+  });
+  ipcMain.handle(IPC.SendPrompt, (_e, args) => {
+    if (typeof args.agentId !== 'string') throw new Error('agentId must be a string');
+    if (typeof args.promptId !== 'string') throw new Error('promptId must be a string');
+    const vars: unknown = args.vars ?? {};
+    if (
+      typeof vars !== 'object' ||
+      vars === null ||
+      Object.values(vars).some((v) => typeof v !== 'string')
+    ) {
+      throw new Error('vars must map variable names to strings');
+    }
+    return sendPromptSnippet(args.agentId, args.promptId, vars as Record<string, string>);
+  });
 
   // --- Persistence ---
   // Extract task names from persisted state so the remote server can
-  // show them (taskNames is only populated on CreateTask otherwise).
+  // show them (taskNames is only populated on CreateTask otherwise), and
+  // so task queries know which tasks exist.
   function syncTaskNamesFromJson(json: string): void {
     try {
-      const state = JSON.parse(json) as { tasks?: Record<string, { id: string; name: string }> };
+      const state = JSON.parse(json) as {
+        tasks?: Record<
+          string,
+          { id: string; name: string; projectId?: string; branchName?: string }
+        >;
+      };
       if (state.tasks) {
-        for (const t of Object.values(state.tasks)) {
-          if (t.id && t.name) taskNames.set(t.id, t.name);
-        }
+        const tasks = Object.values(state.tasks).filter((t) => t.id && t.name);
+        for (const t of tasks) taskNames.set(t.id, t.name);
+        syncKnownTasks(tasks);
       }
     } catch {
       /* ignore malformed state */
@@ -150,16 +1246,41 @@ export function registerAllHandlers(win: BrowserWindow): void {
   }
   ipcMain.handle(IPC.SaveAppState, (_e, args) => {
     syncTaskNamesFromJson(args.json);
-    return saveAppState(args.json);
+    saveAppState(args.json);
+    try {
+      syncTaskHistory(args.json);
+    } catch (e) {
+      console.warn('Could not update task history:', e);
+    }
   });
   ipcMain.handle(IPC.LoadAppState, () => {
     const json = loadAppState();
     if (json) syncTaskNamesFromJson(json);
     return json;
   });
+  ipcMain.handle(IPC.ListStateBackups, () => listStateBackups());
+  ipcMain.handle(IPC.RestoreStateBackup, (_e, args) => {
+    if (typeof args.index !== 'number') throw new Error('index must be a number');
+    const json = restoreStateBackup(args.index);
+    syncTaskNamesFromJson(json);
+    return json;
+  });
+  ipcMain.handle(IPC.GetSettings, () => getSettings());
+  ipcMain.handle(IPC.UpdateSettings, (_e, args) => updateSettings(args.settings));
+  ipcMain.handle(IPC.ExportWorkspace, (_e, args) => {
+    validatePath(args.path, 'path');
+    exportWorkspace(args.path);
+  });
+  ipcMain.handle(IPC.ImportWorkspace, (_e, args) => {
+    validatePath(args.path, 'path');
+    return importWorkspace(args.path);
+  });
 
   // --- Window management ---
   ipcMain.handle(IPC.WindowIsFocused, () => win.isFocused());
+  ipcMain.handle(IPC.GetNotificationSettings, () => getNotificationSettings());
+  ipcMain.handle(IPC.SetNotificationSettings, (_e, args) => setNotificationSettings(args.settings));
+  ipcMain.handle(IPC.TestNotification, () => testNotification());
   ipcMain.handle(IPC.WindowIsMaximized, () => win.isMaximized());
   ipcMain.handle(IPC.WindowMinimize, () => win.minimize());
   ipcMain.handle(IPC.WindowToggleMaximize, () => {
@@ -205,6 +1326,29 @@ export function registerAllHandlers(win: BrowserWindow): void {
     return args?.multiple ? result.filePaths : (result.filePaths[0] ?? null);
   });
 
+  ipcMain.handle(IPC.DialogSave, async (_e, args) => {
+    const result = await dialog.showSaveDialog(win, { defaultPath: args?.defaultPath });
+    return result.canceled ? null : (result.filePath ?? null);
+  });
+
+  // --- Diagnostics ---
+  ipcMain.handle(IPC.GetBackendLogs, (_e, args) => {
+    const level = args?.level ?? 'debug';
+    if (!isLogLevel(level)) throw new Error(`Unknown log level: ${level}`);
+    return getBackendLogs(level, typeof args?.since === 'number' ? args.since : 0);
+  });
+  ipcMain.handle(IPC.GetLogLevel, () => getLogLevel());
+  ipcMain.handle(IPC.SetLogLevel, (_e, args) => {
+    if (typeof args.filter !== 'string') throw new Error('filter must be a string');
+    return setLogLevel(args.filter);
+  });
+  ipcMain.handle(IPC.ListActiveWatchers, () => ({
+    handles: listWatchHandles(),
+    paths: listPathWatches(),
+    worktrees: listWorktreeWatches(),
+    plans: listPlanWatches(),
+  }));
+
   // --- Shell/Opener ---
   ipcMain.handle(IPC.ShellReveal, (_e, args) => {
     validatePath(args.filePath, 'filePath');
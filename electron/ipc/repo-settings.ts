@@ -0,0 +1,58 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+// Per-repository git settings, keyed by the repo's git common dir so every
+// worktree of a repo shares them.
+
+export interface RepoSettings {
+  /** Branch tasks fork from and merge into, overriding detection. */
+  base_branch?: string;
+  /** Run git hooks for commits, merges and pushes made by the app (default true). */
+  run_hooks?: boolean;
+  /** Paths or globs copied from the main checkout into new worktrees (e.g. `.env*`). */
+  copy_files?: string[];
+  /** Shell command run in a new task's terminal once its worktree exists (e.g. `pnpm install`). */
+  setup_command?: string;
+  /** Environment profile (see shell-env.ts) for agents started in the repo. */
+  env_profile?: string;
+}
+
+type SettingsFile = Record<string, RepoSettings>;
+
+let cached: SettingsFile | null = null;
+
+function getSettingsPath(): string {
+  return path.join(getStateDir(), 'repo-settings.json');
+}
+
+function readSettings(): SettingsFile {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getSettingsPath(), 'utf8')) as SettingsFile;
+  } catch {
+    cached = {};
+  }
+  return cached;
+}
+
+export function getRepoSettings(repoKey: string): RepoSettings {
+  return readSettings()[repoKey] ?? {};
+}
+
+/** Merge `patch` into the repo's settings; `undefined` values reset to the default. */
+export function updateRepoSettings(repoKey: string, patch: RepoSettings): void {
+  const all = { ...readSettings() };
+  const next: RepoSettings = { ...all[repoKey], ...patch };
+  for (const key of Object.keys(next) as Array<keyof RepoSettings>) {
+    if (next[key] === undefined) delete next[key];
+  }
+  if (Object.keys(next).length === 0) delete all[repoKey];
+  else all[repoKey] = next;
+  const settingsPath = getSettingsPath();
+  fs.mkdirSync(path.dirname(settingsPath), { recursive: true });
+  const tmpPath = settingsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(all, null, 2), 'utf8');
+  fs.renameSync(tmpPath, settingsPath);
+  cached = all;
+}
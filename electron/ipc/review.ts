@@ -0,0 +1,170 @@
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getTaskReviewDiff } from './git.js';
+import { onAgentEvent, runAgentHeadless } from './headless.js';
+import { profileEnvFor } from './shell-env.js';
+
+// Automatic review of a task: its diff against the base and a review prompt
+// are written into the worktree, a reviewer agent reads them headlessly, and
+// the JSON verdict it ends with is emitted as `review-completed`.
+
+/** App scratch space inside a worktree; it ignores itself (see makeScratchDir). */
+const SCRATCH_DIR = '.parallel-code';
+const REVIEW_DIR = path.join(SCRATCH_DIR, 'review');
+
+export type ReviewVerdict = 'approve' | 'request_changes' | 'comment';
+
+export interface ReviewFinding {
+  file: string | null;
+  line: number | null;
+  severity: 'info' | 'warning' | 'error';
+  message: string;
+}
+
+export interface ReviewResult {
+  task_id: string;
+  run_id: string;
+  reviewer_agent_id: string;
+  /** False when the agent failed or its answer had no parsable verdict. */
+  success: boolean;
+  verdict: ReviewVerdict | null;
+  summary: string;
+  findings: ReviewFinding[];
+  at: number;
+}
+
+const REVIEW_PROMPT = `# Code review
+
+You are reviewing the changes of a task before it is merged. The complete
+diff against the base branch is in \`changes.diff\` next to this file; the
+rest of the repository is the working directory, for context.
+
+Look for bugs, missing error handling, security problems, unclear code and
+missing tests. Do not modify any files.
+
+End your answer with a single JSON object in a \`\`\`json fenced block:
+
+\`\`\`json
+{
+  "verdict": "approve" | "request_changes" | "comment",
+  "summary": "one or two sentences",
+  "findings": [
+    { "file": "src/x.ts", "line": 12, "severity": "info" | "warning" | "error", "message": "..." }
+  ]
+}
+\`\`\`
+`;
+
+let mainWindow: BrowserWindow | null = null;
+
+export function initReviews(win: BrowserWindow): void {
+  mainWindow = win;
+}
+
+/** Create `.parallel-code/<name>` in the worktree and return its absolute path. */
+export function makeScratchDir(worktreePath: string, name: string): string {
+  const dir = path.join(worktreePath, SCRATCH_DIR, name);
+  fs.mkdirSync(dir, { recursive: true });
+  // Keeps the files out of `git status` and out of the agent's commits
+  fs.writeFileSync(path.join(worktreePath, SCRATCH_DIR, '.gitignore'), '*\n', 'utf8');
+  return dir;
+}
+
+function writeReviewFiles(worktreePath: string, diff: string): string {
+  const dir = makeScratchDir(worktreePath, 'review');
+  fs.writeFileSync(path.join(dir, 'changes.diff'), diff, 'utf8');
+  fs.writeFileSync(path.join(dir, 'REVIEW.md'), REVIEW_PROMPT, 'utf8');
+  return dir;
+}
+
+const SEVERITIES = new Set(['info', 'warning', 'error']);
+const VERDICTS = new Set(['approve', 'request_changes', 'comment']);
+
+/** The last ```json block of the answer, or its last top-level `{...}`. */
+function parseReview(text: string): Pick<ReviewResult, 'verdict' | 'summary' | 'findings'> | null {
+  const fenced = [...text.matchAll(/```json\s*([\s\S]*?)```/g)].pop()?.[1];
+  const candidate = fenced ?? text.slice(text.indexOf('{'), text.lastIndexOf('}') + 1);
+  let parsed: Record<string, unknown> | null;
+  try {
+    parsed = JSON.parse(candidate) as Record<string, unknown> | null;
+  } catch {
+    return null;
+  }
+  if (typeof parsed?.verdict !== 'string' || !VERDICTS.has(parsed.verdict)) return null;
+  const findings = (Array.isArray(parsed.findings) ? parsed.findings : []).flatMap(
+    (f: Record<string, unknown>): ReviewFinding[] =>
+      f && typeof f.message === 'string'
+        ? [
+            {
+              file: typeof f.file === 'string' ? f.file : null,
+              line: typeof f.line === 'number' ? f.line : null,
+              severity: SEVERITIES.has(f.severity as string)
+                ? (f.severity as ReviewFinding['severity'])
+                : 'info',
+              message: f.message,
+            },
+          ]
+        : [],
+  );
+  return {
+    verdict: parsed.verdict as ReviewVerdict,
+    summary: typeof parsed.summary === 'string' ? parsed.summary : '',
+    findings,
+  };
+}
+
+/** Start a headless review of the task in `worktreePath`. Returns the run id. */
+export async function startReviewAgent(params: {
+  taskId: string;
+  reviewerAgentId: string;
+  worktreePath: string;
+  env?: Record<string, string>;
+}): Promise<{ run_id: string }> {
+  const diff = await getTaskReviewDiff(params.worktreePath);
+  if (!diff.trim()) throw new Error('The task has no changes to review');
+  const dir = writeReviewFiles(params.worktreePath, diff);
+
+  const profileEnv = await profileEnvFor(params.reviewerAgentId, params.worktreePath);
+  let run: { run_id: string };
+  try {
+    run = runAgentHeadless({
+      taskId: params.taskId,
+      agentId: params.reviewerAgentId,
+      prompt: `Read ${path.join(REVIEW_DIR, 'REVIEW.md')} and follow its instructions.`,
+      cwd: params.worktreePath,
+      env: params.env,
+      profileEnv,
+    });
+  } catch (e) {
+    fs.rmSync(dir, { recursive: true, force: true });
+    throw e;
+  }
+  const { run_id } = run;
+
+  const messages: string[] = [];
+  const off = onAgentEvent((event) => {
+    if (event.run_id !== run_id) return;
+    if (event.kind === 'message') messages.push(event.text);
+    if (event.kind !== 'completion') return;
+    off();
+    fs.rmSync(dir, { recursive: true, force: true });
+    const answer = event.result ?? messages.join('\n');
+    const review = event.success ? parseReview(answer) : null;
+    const result: ReviewResult = {
+      task_id: params.taskId,
+      run_id,
+      reviewer_agent_id: params.reviewerAgentId,
+      success: review !== null,
+      verdict: review?.verdict ?? null,
+      summary: review?.summary ?? (answer.trim() || 'The reviewer gave no answer'),
+      findings: review?.findings ?? [],
+      at: Date.now(),
+    };
+    if (mainWindow && !mainWindow.isDestroyed()) {
+      mainWindow.webContents.send(IPC.ReviewCompleted, result);
+    }
+  });
+  return { run_id };
+}
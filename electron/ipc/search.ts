@@ -0,0 +1,137 @@
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getGitBackend, GitError } from './git-backend.js';
+
+// Content search in a task worktree via `git grep`: tracked and untracked
+// files, skipping anything gitignored and binary. Matches are streamed to the
+// renderer in batches as git produces them.
+
+export interface SearchOptions {
+  /** Treat the query as a regular expression (POSIX extended) instead of a literal. */
+  regex?: boolean;
+  case_sensitive?: boolean;
+  whole_word?: boolean;
+  /** Glob pathspecs to search in, e.g. "src/**\/*.ts". */
+  include?: string[];
+  exclude?: string[];
+  max_results?: number;
+}
+
+export interface SearchMatch {
+  path: string;
+  line: number;
+  column: number;
+  text: string;
+}
+
+export interface SearchSummary {
+  search_id: string;
+  total: number;
+  /** Stopped early: max_results reached or cancelled. */
+  truncated: boolean;
+}
+
+const DEFAULT_MAX_RESULTS = 2000;
+const MAX_LINE_LENGTH = 500;
+
+const activeSearches = new Map<string, AbortController>();
+
+function parseMatch(line: string): SearchMatch | null {
+  // "<path>\0<line>\0<column>\0<text>" with --null
+  const m = /^([^\0]+)\0(\d+)\0(\d+)\0(.*)$/s.exec(line);
+  if (!m) return null;
+  return {
+    path: m[1],
+    line: parseInt(m[2], 10),
+    column: parseInt(m[3], 10),
+    text: m[4].length > MAX_LINE_LENGTH ? m[4].slice(0, MAX_LINE_LENGTH) : m[4],
+  };
+}
+
+function buildArgs(query: string, opts: SearchOptions): string[] {
+  const args = ['grep', '-n', '--column', '--null', '-I', '--no-color', '--untracked'];
+  args.push(opts.regex ? '-E' : '-F');
+  if (!opts.case_sensitive) args.push('-i');
+  if (opts.whole_word) args.push('-w');
+  args.push('-e', query, '--');
+  for (const glob of opts.include ?? []) args.push(`:(glob)${glob}`);
+  if ((opts.include ?? []).length === 0 && (opts.exclude ?? []).length > 0) args.push('.');
+  for (const glob of opts.exclude ?? []) args.push(`:(glob,exclude)${glob}`);
+  return args;
+}
+
+/**
+ * Search a worktree, emitting `search-results` batches tagged with
+ * `searchId`; resolves once git is done, the limit is hit or it's cancelled.
+ */
+export async function searchWorktree(
+  win: BrowserWindow,
+  searchId: string,
+  worktreePath: string,
+  query: string,
+  opts: SearchOptions = {},
+): Promise<SearchSummary> {
+  if (!query) throw new Error('query must not be empty');
+  cancelSearch(searchId);
+  const controller = new AbortController();
+  activeSearches.set(searchId, controller);
+
+  const maxResults = Math.max(1, opts.max_results ?? DEFAULT_MAX_RESULTS);
+  let total = 0;
+  let truncated = false;
+  let pending = '';
+
+  const send = (matches: SearchMatch[]) => {
+    if (matches.length > 0 && !win.isDestroyed()) {
+      win.webContents.send(IPC.SearchResults, { search_id: searchId, matches });
+    }
+  };
+
+  const consume = (chunk: string, flush: boolean) => {
+    const lines = (pending + chunk).split('\n');
+    pending = flush ? '' : (lines.pop() ?? '');
+    const batch: SearchMatch[] = [];
+    for (const line of lines) {
+      if (total >= maxResults) break;
+      const match = parseMatch(line);
+      if (!match) continue;
+      batch.push(match);
+      total++;
+    }
+    send(batch);
+    if (total >= maxResults && !truncated) {
+      truncated = true;
+      controller.abort();
+    }
+  };
+
+  try {
+    await getGitBackend().run(buildArgs(query, opts), {
+      cwd: worktreePath,
+      signal: controller.signal,
+      // Output is consumed as it streams; only the tail needs to fit
+      maxBuffer: 64 * 1024 * 1024,
+      onOutput: (stream, data) => {
+        if (stream === 'stdout' && !truncated) consume(data, false);
+      },
+    });
+    consume('', true);
+  } catch (e) {
+    if (controller.signal.aborted) {
+      truncated = true;
+    } else if (e instanceof GitError && e.code === 1 && !e.stderr.trim()) {
+      // Exit code 1 without an error message means no (more) matches
+      consume('', true);
+    } else {
+      throw e;
+    }
+  } finally {
+    if (activeSearches.get(searchId) === controller) activeSearches.delete(searchId);
+  }
+  return { search_id: searchId, total, truncated };
+}
+
+export function cancelSearch(searchId: string): void {
+  activeSearches.get(searchId)?.abort();
+  activeSearches.delete(searchId);
+}
@@ -0,0 +1,54 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'secrets-'));
+vi.mock('electron', () => ({
+  app: { getPath: () => root, isPackaged: true },
+  // Stand-in cipher: the tests only care about the file, not the encryption
+  safeStorage: {
+    isEncryptionAvailable: () => true,
+    getSelectedStorageBackend: () => 'gnome_libsecret',
+    encryptString: (value: string) => Buffer.from(value.split('').reverse().join('')),
+    decryptString: (buf: Buffer) => buf.toString().split('').reverse().join(''),
+  },
+}));
+
+import { getSecret, listSecrets, setSecret } from './secrets.js';
+
+const secretsPath = path.join(root, 'secrets.json');
+
+beforeEach(() => {
+  fs.rmSync(secretsPath, { force: true });
+});
+
+// ---------------------------------------------------------------------------
+// Secret storage
+// ---------------------------------------------------------------------------
+describe('secret storage', () => {
+  it('treats a missing file as no secrets', () => {
+    expect(listSecrets()).toEqual([]);
+    expect(getSecret('token')).toBeNull();
+  });
+
+  it('stores and reads back secrets', () => {
+    setSecret('token', 'abc');
+    setSecret('other', 'xyz');
+    expect(listSecrets()).toEqual(['other', 'token']);
+    expect(getSecret('token')).toBe('abc');
+    setSecret('other', null);
+    expect(listSecrets()).toEqual(['token']);
+  });
+
+  it.each([
+    ['invalid JSON', '{"token": '],
+    ['a JSON array', '["token"]'],
+    ['non-string values', '{"token": 42}'],
+  ])('refuses to overwrite a file with %s', (_name, content) => {
+    fs.writeFileSync(secretsPath, content);
+    expect(() => setSecret('new', 'value')).toThrow(/Could not read/);
+    expect(() => listSecrets()).toThrow(/Could not read/);
+    expect(fs.readFileSync(secretsPath, 'utf8')).toBe(content);
+  });
+});
@@ -0,0 +1,125 @@
+import { safeStorage } from 'electron';
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+// API tokens are encrypted with the OS-backed safeStorage key (Keychain on
+// macOS, DPAPI on Windows, the Secret Service or KWallet on Linux) and kept
+// out of state.json. Agent env vars can point at a secret with `secret:<name>`;
+// the value is filled in at spawn time so it never appears in saved tasks.
+
+/** An env value of exactly this form is replaced by the named secret. */
+const SECRET_REF = /^secret:([a-z0-9][a-z0-9._-]{0,63})$/i;
+
+type SecretFile = Record<string, string>; // name -> base64 ciphertext
+
+function getSecretsPath(): string {
+  return path.join(getStateDir(), 'secrets.json');
+}
+
+/**
+ * Read the stored secrets; a missing file is empty. A damaged one throws
+ * instead, since the next setSecret would otherwise replace it with just
+ * the new secret.
+ */
+function readSecrets(): SecretFile {
+  const secretsPath = getSecretsPath();
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(fs.readFileSync(secretsPath, 'utf8'));
+  } catch (e) {
+    if ((e as NodeJS.ErrnoException).code === 'ENOENT') return {};
+    throw new Error(`Could not read ${secretsPath}: ${e instanceof Error ? e.message : String(e)}`);
+  }
+  if (
+    !parsed ||
+    typeof parsed !== 'object' ||
+    Array.isArray(parsed) ||
+    !Object.values(parsed).every((v) => typeof v === 'string')
+  ) {
+    throw new Error(`Could not read ${secretsPath}: not a map of secret names to values`);
+  }
+  return parsed as SecretFile;
+}
+
+function writeSecrets(secrets: SecretFile): void {
+  const secretsPath = getSecretsPath();
+  fs.mkdirSync(path.dirname(secretsPath), { recursive: true });
+  const tmpPath = secretsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(secrets, null, 2), { encoding: 'utf8', mode: 0o600 });
+  fs.renameSync(tmpPath, secretsPath);
+}
+
+function validateSecretName(name: unknown): asserts name is string {
+  if (typeof name !== 'string' || !/^[a-z0-9][a-z0-9._-]{0,63}$/i.test(name)) {
+    throw new Error(`Invalid secret name: ${String(name)}`);
+  }
+}
+
+/** Refuse to store anything when safeStorage would fall back to a hardcoded key. */
+function requireKeychain(): void {
+  if (!safeStorage.isEncryptionAvailable()) {
+    throw new Error('Secure storage is not available on this system');
+  }
+  if (process.platform === 'linux' && safeStorage.getSelectedStorageBackend() === 'basic_text') {
+    throw new Error('No OS keychain found; install gnome-keyring or KWallet to store secrets');
+  }
+}
+
+export function getSecret(name: string): string | null {
+  validateSecretName(name);
+  const encrypted = readSecrets()[name];
+  if (!encrypted) return null;
+  try {
+    return safeStorage.decryptString(Buffer.from(encrypted, 'base64'));
+  } catch {
+    return null;
+  }
+}
+
+export function hasSecret(name: string): boolean {
+  validateSecretName(name);
+  return name in readSecrets();
+}
+
+/** Store a secret; an empty or null value deletes it. */
+export function setSecret(name: string, value: string | null): void {
+  validateSecretName(name);
+  const secrets = readSecrets();
+  if (!value) {
+    delete secrets[name];
+  } else {
+    requireKeychain();
+    secrets[name] = safeStorage.encryptString(value).toString('base64');
+  }
+  writeSecrets(secrets);
+}
+
+export function deleteSecret(name: string): void {
+  setSecret(name, null);
+}
+
+/** Names of all stored secrets, sorted. */
+export function listSecrets(): string[] {
+  return Object.keys(readSecrets()).sort();
+}
+
+export function isSecretRef(value: string | undefined): boolean {
+  return value !== undefined && SECRET_REF.test(value);
+}
+
+/** Replace `secret:<name>` values with the secrets they name. */
+export function resolveSecretRefs(env: Record<string, string>): Record<string, string> {
+  const resolved: Record<string, string> = {};
+  for (const [key, value] of Object.entries(env)) {
+    const match = value.match(SECRET_REF);
+    if (!match) {
+      resolved[key] = value;
+      continue;
+    }
+    const secret = getSecret(match[1]);
+    if (secret === null) throw new Error(`Secret "${match[1]}" for ${key} is not set`);
+    resolved[key] = secret;
+  }
+  return resolved;
+}
@@ -0,0 +1,115 @@
+// Standalone PTY host for detachable agent sessions.
+//
+// Launched by the main process with ELECTRON_RUN_AS_NODE=1 as a detached
+// process so the PTY outlives an app restart. The main process talks to it
+// over a Unix socket using newline-delimited JSON (see session-protocol.ts).
+// The host exits as soon as its PTY child exits.
+
+import * as pty from 'node-pty';
+import net from 'net';
+import fs from 'fs';
+import { RingBuffer } from '../remote/ring-buffer.js';
+import {
+  createLineParser,
+  type DetachedSessionMeta,
+  type HostCommand,
+  type HostMessage,
+  type SessionHostSpec,
+} from './session-protocol.js';
+
+const specPath = process.argv[2];
+if (!specPath) process.exit(1);
+
+// The spec contains the full spawn environment — read it once, then delete it.
+const spec = JSON.parse(fs.readFileSync(specPath, 'utf8')) as SessionHostSpec;
+fs.rmSync(specPath, { force: true });
+
+const proc = pty.spawn(spec.command, spec.args, {
+  name: 'xterm-256color',
+  cols: spec.cols,
+  rows: spec.rows,
+  cwd: spec.cwd,
+  env: spec.env,
+});
+
+const scrollback = new RingBuffer(spec.scrollbackBytes);
+const clients = new Set<net.Socket>();
+
+function send(sock: net.Socket, msg: HostMessage): void {
+  if (!sock.destroyed) sock.write(JSON.stringify(msg) + '\n');
+}
+
+function broadcast(msg: HostMessage): void {
+  for (const sock of clients) send(sock, msg);
+}
+
+function removeFiles(): void {
+  fs.rmSync(spec.metaPath, { force: true });
+  fs.rmSync(spec.socketPath, { force: true });
+}
+
+proc.onData((data) => {
+  scrollback.write(Buffer.from(data, 'utf8'));
+  broadcast({ type: 'data', data });
+});
+
+proc.onExit(({ exitCode, signal }) => {
+  broadcast({ type: 'exit', exitCode, signal: signal ?? null });
+  removeFiles();
+  for (const sock of clients) sock.end();
+  server.close();
+  // Give sockets a moment to drain the exit message
+  setTimeout(() => process.exit(0), 200);
+});
+
+function handleCommand(cmd: HostCommand): void {
+  switch (cmd.type) {
+    case 'write':
+      proc.write(cmd.data);
+      break;
+    case 'write_bytes':
+      proc.write(Buffer.from(cmd.data, 'base64'));
+      break;
+    case 'resize':
+      proc.resize(cmd.cols, cmd.rows);
+      break;
+    case 'pause':
+      proc.pause();
+      break;
+    case 'resume':
+      proc.resume();
+      break;
+    case 'kill':
+      proc.kill(cmd.signal);
+      break;
+  }
+}
+
+const server = net.createServer((sock) => {
+  // Replay history before adding to the live set so nothing is duplicated
+  send(sock, { type: 'scrollback', data: scrollback.toBase64() });
+  clients.add(sock);
+  sock.on('data', createLineParser<HostCommand>(handleCommand));
+  sock.on('close', () => clients.delete(sock));
+  sock.on('error', () => clients.delete(sock));
+});
+
+fs.rmSync(spec.socketPath, { force: true });
+server.listen(spec.socketPath, () => {
+  const meta: DetachedSessionMeta = {
+    agentId: spec.agentId,
+    taskId: spec.taskId,
+    command: spec.command,
+    args: spec.args,
+    cwd: spec.cwd,
+    pid: proc.pid,
+    hostPid: process.pid,
+    socketPath: spec.socketPath,
+    startedAt: Date.now(),
+  };
+  fs.writeFileSync(spec.metaPath, JSON.stringify(meta), { mode: 0o600 });
+});
+
+process.on('SIGTERM', () => {
+  proc.kill();
+});
@@ -0,0 +1,72 @@
+// Shared types for detachable PTY sessions. This module must stay free of
+// Electron imports because session-host.ts runs under ELECTRON_RUN_AS_NODE.
+
+import { StringDecoder } from 'string_decoder';
+
+/** Spawn parameters handed to the session host process (via a 0600 spec file). */
+export interface SessionHostSpec {
+  agentId: string;
+  taskId: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  env: Record<string, string>;
+  cols: number;
+  rows: number;
+  scrollbackBytes: number;
+  socketPath: string;
+  metaPath: string;
+}
+
+/** Metadata file written by a running session host. */
+export interface DetachedSessionMeta {
+  agentId: string;
+  taskId: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  pid: number;
+  hostPid: number;
+  socketPath: string;
+  startedAt: number;
+}
+
+// --- Host -> Client messages ---
+
+export type HostMessage =
+  | { type: 'scrollback'; data: string } // base64
+  | { type: 'data'; data: string }
+  | { type: 'exit'; exitCode: number; signal: number | null };
+
+// --- Client -> Host messages ---
+
+export type HostCommand =
+  | { type: 'write'; data: string }
+  | { type: 'write_bytes'; data: string } // base64
+  | { type: 'resize'; cols: number; rows: number }
+  | { type: 'pause' }
+  | { type: 'resume' }
+  | { type: 'kill'; signal?: string };
+
+/** Split a socket stream into newline-delimited JSON messages. */
+export function createLineParser<T>(onMessage: (msg: T) => void): (chunk: Buffer) => void {
+  // Keeps multi-byte characters split across chunks intact
+  const decoder = new StringDecoder('utf8');
+  let pending = '';
+  return (chunk) => {
+    pending += decoder.write(chunk);
+    let idx = pending.indexOf('\n');
+    while (idx !== -1) {
+      const line = pending.slice(0, idx);
+      pending = pending.slice(idx + 1);
+      if (line) {
+        try {
+          onMessage(JSON.parse(line) as T);
+        } catch {
+          /* ignore malformed line */
+        }
+      }
+      idx = pending.indexOf('\n');
+    }
+  };
+}
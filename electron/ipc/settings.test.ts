@@ -0,0 +1,132 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+
+let stateDir = '';
+vi.mock('electron', () => ({ app: { getPath: () => stateDir, isPackaged: true } }));
+
+type SettingsModule = typeof import('./settings.js');
+let settings: SettingsModule;
+
+beforeEach(async () => {
+  stateDir = fs.mkdtempSync(path.join(os.tmpdir(), 'settings-'));
+  // Settings are cached in module state — start each test with a fresh module
+  vi.resetModules();
+  settings = await import('./settings.js');
+});
+
+const settingsPath = () => path.join(stateDir, 'settings.json');
+const writeJson = (name: string, data: unknown) =>
+  fs.writeFileSync(path.join(stateDir, name), JSON.stringify(data));
+const readSaved = () => JSON.parse(fs.readFileSync(settingsPath(), 'utf8'));
+
+// ---------------------------------------------------------------------------
+// Migrations
+// ---------------------------------------------------------------------------
+describe('getSettings migrations', () => {
+  it('starts from defaults without any saved files', () => {
+    const s = settings.getSettings();
+    expect(s.version).toBe(settings.SETTINGS_VERSION);
+    expect(s.terminal_scrollback).toBe(3000);
+    expect(s.git_backend).toBe('cli');
+    expect(s.keep_agents_running).toBe(false);
+    expect(readSaved()).toEqual(s);
+  });
+
+  it('picks up values from where they lived before settings.json', () => {
+    writeJson('state.json', {
+      projects: [],
+      taskOrder: [],
+      terminalFont: 'Fira Code',
+      keepAgentsRunning: true,
+    });
+    writeJson('notifications.json', { agent_idle: false, some_old_type: true });
+
+    const s = settings.getSettings();
+    expect(s.terminal_font).toBe('Fira Code');
+    expect(s.keep_agents_running).toBe(true);
+    expect(s.notifications).toEqual({
+      agent_exit: true,
+      agent_idle: false,
+      merge_conflict: true,
+      pr_status: true,
+    });
+    expect(readSaved().version).toBe(settings.SETTINGS_VERSION);
+  });
+
+  it('runs only the migrations newer than the saved version', () => {
+    writeJson('state.json', { terminalFont: 'Fira Code', keepAgentsRunning: true });
+    writeJson('settings.json', { version: 1, terminal_font: 'Iosevka', agent_limit: 3 });
+
+    const s = settings.getSettings();
+    expect(s.terminal_font).toBe('Iosevka');
+    expect(s.agent_limit).toBe(3);
+    expect(s.keep_agents_running).toBe(true);
+  });
+
+  it('falls back to defaults for invalid saved values', () => {
+    writeJson('settings.json', {
+      version: settings.SETTINGS_VERSION,
+      terminal_scrollback: -5,
+      terminal_font: 'Iosevka',
+      git_backend: 'libgit2',
+      notifications: { agent_exit: 'yes', pr_status: false },
+    });
+
+    const s = settings.getSettings();
+    expect(s.terminal_scrollback).toBe(3000);
+    expect(s.terminal_font).toBe('Iosevka');
+    expect(s.git_backend).toBe('cli');
+    expect(s.notifications.pr_status).toBe(true);
+  });
+
+  it('reads a newer file without rewriting it', () => {
+    const newer = { version: settings.SETTINGS_VERSION + 1, agent_limit: 2, future_key: 'x' };
+    writeJson('settings.json', newer);
+
+    expect(settings.getSettings().agent_limit).toBe(2);
+    expect(readSaved()).toEqual(newer);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+describe('updateSettings', () => {
+  it.each([
+    ['a non-object', 'dark', /must be an object/],
+    ['an unknown key', { theme: 'dark' }, /Unknown setting: theme/],
+    ['the version', { version: 9 }, /Unknown setting: version/],
+    ['an empty font', { terminal_font: '  ' }, /terminal_font/],
+    ['fractional scrollback', { terminal_scrollback: 1.5 }, /terminal_scrollback/],
+    ['too much scrollback', { terminal_scrollback: 100_001 }, /terminal_scrollback/],
+    ['a numeric agent id', { default_agent_id: 7 }, /default_agent_id/],
+    ['a negative agent limit', { agent_limit: -1 }, /agent_limit/],
+    ['an unknown git backend', { git_backend: 'libgit2' }, /git_backend/],
+    ['a non-boolean keep_agents_running', { keep_agents_running: 1 }, /keep_agents_running/],
+    ['an unknown notification', { notifications: { digest: true } }, /Unknown notification/],
+    ['a non-boolean notification', { notifications: { agent_exit: 1 } }, /must be a boolean/],
+  ])('rejects %s', (_name, patch, error) => {
+    expect(() => settings.updateSettings(patch)).toThrow(error);
+    expect(settings.getSettings()).toEqual(readSaved());
+  });
+
+  it('saves a valid patch and tells listeners', () => {
+    const listener = vi.fn();
+    settings.onSettingsChanged(listener);
+    const previous = settings.getSettings();
+
+    const next = settings.updateSettings({
+      agent_limit: 4,
+      default_agent_id: '',
+      notifications: { agent_idle: false },
+    });
+    expect(next.agent_limit).toBe(4);
+    expect(next.default_agent_id).toBeNull();
+    expect(next.notifications.agent_idle).toBe(false);
+    expect(next.notifications.agent_exit).toBe(true);
+    expect(readSaved()).toEqual(next);
+    expect(listener).toHaveBeenCalledWith(next, previous);
+  });
+});
@@ -0,0 +1,276 @@
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getStateDir, loadAppState } from './persistence.js';
+import { subscribeFile } from './watcher.js';
+import { validateWorktreeLocation, type WorktreeLocation } from './worktree-location.js';
+
+// Application-wide settings, typed and validated here rather than buried in
+// the renderer's state.json. The file carries a schema version; older files
+// are migrated step by step when first read.
+
+export const NOTIFICATION_TYPES = [
+  'agent_exit',
+  'agent_idle',
+  'merge_conflict',
+  'pr_status',
+] as const;
+export type NotificationType = (typeof NOTIFICATION_TYPES)[number];
+
+export type NotificationSettings = Record<NotificationType, boolean>;
+
+const isNotificationType = (type: string): type is NotificationType =>
+  NOTIFICATION_TYPES.includes(type as NotificationType);
+
+export interface Settings {
+  version: number;
+  terminal_font: string;
+  /** Lines kept above the visible part of each terminal. */
+  terminal_scrollback: number;
+  /** Agent preselected for new tasks; null uses the last one picked. */
+  default_agent_id: string | null;
+  /** Where worktrees go for repositories added from now on. */
+  worktree_location: WorktreeLocation;
+  /** Agents running at once before new ones queue; 0 is unlimited. */
+  agent_limit: number;
+  /**
+   * How status and diff polling reads repositories. 'in-process' is opt-in and
+   * still hands anything it doesn't support to the CLI.
+   */
+  git_backend: 'in-process' | 'cli';
+  /** New agents run in a session host that outlives the app. */
+  keep_agents_running: boolean;
+  /** New agents write an asciicast recording of their output; see recordings.ts. */
+  record_sessions: boolean;
+  notifications: NotificationSettings;
+}
+
+export const SETTINGS_VERSION = 2;
+const MAX_SCROLLBACK = 100_000;
+
+const DEFAULT_SETTINGS: Settings = {
+  version: SETTINGS_VERSION,
+  terminal_font: 'JetBrains Mono',
+  terminal_scrollback: 3000,
+  default_agent_id: null,
+  worktree_location: { mode: 'repo' },
+  agent_limit: 0,
+  git_backend: 'cli',
+  keep_agents_running: false,
+  record_sessions: false,
+  notifications: { agent_exit: true, agent_idle: true, merge_conflict: true, pr_status: true },
+};
+
+type Json = Record<string, unknown>;
+
+function readJsonFile(file: string): Json {
+  try {
+    const parsed: unknown = JSON.parse(fs.readFileSync(file, 'utf8'));
+    return parsed && typeof parsed === 'object' ? (parsed as Json) : {};
+  } catch {
+    return {};
+  }
+}
+
+function readAppState(): Json {
+  try {
+    return JSON.parse(loadAppState() ?? '{}') as Json;
+  } catch {
+    return {}; // corrupt state — the renderer reports it on load
+  }
+}
+
+/**
+ * `MIGRATIONS[n]` turns a version-n file into version n + 1. Version 0 is
+ * "no settings file": values are picked up from where they used to live.
+ */
+const MIGRATIONS: Array<(raw: Json) => Json> = [
+  (raw) => ({
+    ...raw,
+    terminal_font: readAppState().terminalFont,
+    notifications: readJsonFile(path.join(getStateDir(), 'notifications.json')),
+  }),
+  // keep_agents_running used to be saved with the renderer state
+  (raw) => ({ ...raw, keep_agents_running: readAppState().keepAgentsRunning }),
+];
+
+let cached: Settings | null = null;
+let mainWindow: BrowserWindow | null = null;
+const listeners = new Set<(settings: Settings, previous: Settings) => void>();
+
+function getSettingsPath(): string {
+  return path.join(getStateDir(), 'settings.json');
+}
+
+function writeSettings(settings: Settings): void {
+  const settingsPath = getSettingsPath();
+  fs.mkdirSync(path.dirname(settingsPath), { recursive: true });
+  const tmpPath = settingsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(settings, null, 2), 'utf8');
+  fs.renameSync(tmpPath, settingsPath);
+  cached = settings;
+}
+
+/** Apply `patch` to `base`, throwing on the first invalid value. */
+function applyPatch(base: Settings, patch: unknown): Settings {
+  if (!patch || typeof patch !== 'object') throw new Error('settings must be an object');
+  const p = patch as Partial<Record<keyof Settings, unknown>>;
+  const next: Settings = { ...base, notifications: { ...base.notifications } };
+  for (const key of Object.keys(p)) {
+    if (!(key in DEFAULT_SETTINGS) || key === 'version') {
+      throw new Error(`Unknown setting: ${key}`);
+    }
+  }
+  if (p.terminal_font !== undefined) {
+    if (typeof p.terminal_font !== 'string' || !p.terminal_font.trim()) {
+      throw new Error('terminal_font must not be empty');
+    }
+    next.terminal_font = p.terminal_font;
+  }
+  if (p.terminal_scrollback !== undefined) {
+    const lines = p.terminal_scrollback as number;
+    if (!Number.isInteger(lines) || lines < 0 || lines > MAX_SCROLLBACK) {
+      throw new Error(`terminal_scrollback must be a whole number up to ${MAX_SCROLLBACK}`);
+    }
+    next.terminal_scrollback = lines;
+  }
+  if (p.default_agent_id !== undefined) {
+    if (p.default_agent_id !== null && typeof p.default_agent_id !== 'string') {
+      throw new Error('default_agent_id must be a string or null');
+    }
+    next.default_agent_id = p.default_agent_id || null;
+  }
+  if (p.worktree_location !== undefined) {
+    next.worktree_location = validateWorktreeLocation(p.worktree_location);
+  }
+  if (p.agent_limit !== undefined) {
+    if (!Number.isInteger(p.agent_limit) || (p.agent_limit as number) < 0) {
+      throw new Error('agent_limit must be a non-negative integer');
+    }
+    next.agent_limit = p.agent_limit as number;
+  }
+  if (p.git_backend !== undefined) {
+    if (p.git_backend !== 'in-process' && p.git_backend !== 'cli') {
+      throw new Error("git_backend must be 'in-process' or 'cli'");
+    }
+    next.git_backend = p.git_backend;
+  }
+  if (p.keep_agents_running !== undefined) {
+    if (typeof p.keep_agents_running !== 'boolean') {
+      throw new Error('keep_agents_running must be a boolean');
+    }
+    next.keep_agents_running = p.keep_agents_running;
+  }
+  if (p.record_sessions !== undefined) {
+    if (typeof p.record_sessions !== 'boolean') {
+      throw new Error('record_sessions must be a boolean');
+    }
+    next.record_sessions = p.record_sessions;
+  }
+  if (p.notifications !== undefined) {
+    if (!p.notifications || typeof p.notifications !== 'object') {
+      throw new Error('notifications must be an object');
+    }
+    for (const [type, enabled] of Object.entries(p.notifications)) {
+      if (!isNotificationType(type)) throw new Error(`Unknown notification type: ${type}`);
+      if (typeof enabled !== 'boolean') throw new Error(`notifications.${type} must be a boolean`);
+      next.notifications[type] = enabled;
+    }
+  }
+  return next;
+}
+
+/** Bring a file of any version up to date. Invalid values fall back to defaults. */
+function migrate(raw: Json, fromVersion: number): Settings {
+  let data = raw;
+  for (let version = fromVersion; version < SETTINGS_VERSION; version++) {
+    data = MIGRATIONS[version](data);
+  }
+
+  let settings = { ...DEFAULT_SETTINGS };
+  for (const key of Object.keys(DEFAULT_SETTINGS) as (keyof Settings)[]) {
+    if (key === 'version' || data[key] === undefined) continue;
+    const value = key === 'notifications' ? stripUnknownTypes(data[key]) : data[key];
+    try {
+      settings = applyPatch(settings, { [key]: value });
+    } catch (e) {
+      console.warn(`Ignoring saved setting ${key}:`, e);
+    }
+  }
+  return { ...settings, version: SETTINGS_VERSION };
+}
+
+function stripUnknownTypes(value: unknown): unknown {
+  if (!value || typeof value !== 'object') return value;
+  return Object.fromEntries(Object.entries(value).filter(([type]) => isNotificationType(type)));
+}
+
+export function getSettings(): Settings {
+  if (cached) return cached;
+  const settingsPath = getSettingsPath();
+  const raw = fs.existsSync(settingsPath) ? readJsonFile(settingsPath) : {};
+  const savedVersion = typeof raw.version === 'number' ? raw.version : 0;
+  const settings = migrate(raw, savedVersion);
+  // A newer app's file is read as far as understood but left alone
+  if (savedVersion < SETTINGS_VERSION) {
+    try {
+      writeSettings(settings);
+    } catch (e) {
+      console.warn('Could not save migrated settings:', e);
+    }
+  }
+  cached = settings;
+  return settings;
+}
+
+/** Validate and save a partial update, then announce it as `settings-changed`. */
+export function updateSettings(patch: unknown): Settings {
+  const previous = getSettings();
+  const next = applyPatch(previous, patch);
+  writeSettings(next);
+  announce(next, previous);
+  return next;
+}
+
+function announce(next: Settings, previous: Settings): void {
+  listeners.forEach((fn) => fn(next, previous));
+  if (mainWindow && !mainWindow.isDestroyed()) {
+    mainWindow.webContents.send(IPC.SettingsChanged, next);
+  }
+}
+
+/** Pick up edits made to settings.json outside the app. */
+function reloadFromDisk(): void {
+  try {
+    JSON.parse(fs.readFileSync(getSettingsPath(), 'utf8'));
+  } catch {
+    return; // half-written or broken by hand: keep what we have
+  }
+  const previous = getSettings();
+  cached = null;
+  const next = getSettings();
+  if (JSON.stringify(next) !== JSON.stringify(previous)) announce(next, previous);
+}
+
+/** Follow settings changes in the main process. Returns an unsubscribe function. */
+export function onSettingsChanged(
+  listener: (settings: Settings, previous: Settings) => void,
+): () => void {
+  listeners.add(listener);
+  return () => {
+    listeners.delete(listener);
+  };
+}
+
+export function initSettings(win: BrowserWindow): void {
+  mainWindow = win;
+  try {
+    fs.mkdirSync(getStateDir(), { recursive: true });
+    subscribeFile(getSettingsPath(), {}, (kind) => {
+      if (kind !== 'removed') reloadFromDisk();
+    });
+  } catch (e) {
+    console.warn('Could not watch settings.json:', e);
+  }
+}
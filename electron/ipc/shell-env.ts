@@ -0,0 +1,214 @@
+import { execFile, execFileSync } from 'child_process';
+import fs from 'fs';
+import path from 'path';
+import { promisify } from 'util';
+import { listAgents } from './agents.js';
+import { getRepoSettingsFor } from './git.js';
+import { getStateDir } from './persistence.js';
+
+// The environment agents run with. When launched from a .desktop file, PATH
+// is minimal (/usr/bin:/bin), so the user's full login-interactive shell PATH
+// is resolved so spawned PTYs can find CLI tools like claude, codex, gemini.
+//
+// Uses -ilc (interactive + login) to source both .zprofile/.profile AND
+// .zshrc/.bashrc, where version managers (nvm, volta, fnm) add to PATH.
+// Sentinel markers isolate PATH from noisy shell init output.
+//
+// Trade-off: -i (interactive) triggers .zshrc side effects (compinit, conda,
+// welcome messages). Login-only (-lc) would be quieter but would miss tools
+// that are only added to PATH in .bashrc/.zshrc (e.g. nvm). We accept the
+// side effects since the sentinel-based parsing discards all other output.
+//
+// Signing and SSH agent settings from the same shell are imported too, unless
+// the app already inherited them, so app-driven commits and pushes reach the
+// user's gpg-agent and ssh-agent.
+//
+// Named environment profiles add to that per agent or per project: extra PATH
+// entries, shell init run before PATH is read (e.g. `nvm use 20`, `eval
+// "$(pyenv init -)"`) and plain variables.
+
+const SHELL_ENV_VARS = ['PATH', 'GPG_TTY', 'GNUPGHOME', 'SSH_AUTH_SOCK'];
+const SENTINEL = '__PCODE_ENV__';
+const SHELL_TIMEOUT_MS = 5000;
+
+const execFileAsync = promisify(execFile);
+
+export interface EnvProfile {
+  id: string;
+  name: string;
+  /** Prepended to PATH, first entry first. */
+  path: string[];
+  /** Shell commands run in the login shell before PATH is read. */
+  init: string;
+  env?: Record<string, string>;
+}
+
+let cached: EnvProfile[] | null = null;
+/** Resolved environment per profile; cleared when PATH or profiles change. */
+const resolved = new Map<string, Promise<Record<string, string>>>();
+
+function loginShellArgs(init: string): [string, string[]] {
+  const format = SHELL_ENV_VARS.map(() => `${SENTINEL}%s`).join('') + SENTINEL;
+  const values = SHELL_ENV_VARS.map((name) => `"$${name}"`).join(' ');
+  const script = `${init ? `${init}\n` : ''}printf "${format}" ${values}`;
+  return [process.env.SHELL || '/bin/sh', ['-ilc', script]];
+}
+
+function parseLoginEnv(output: string): Record<string, string> {
+  const start = output.indexOf(SENTINEL);
+  if (start === -1) return {};
+  const parts = output.slice(start + SENTINEL.length).split(SENTINEL);
+  const env: Record<string, string> = {};
+  SHELL_ENV_VARS.forEach((name, i) => {
+    if (parts[i]) env[name] = parts[i];
+  });
+  return env;
+}
+
+function applyLoginEnv(env: Record<string, string>): void {
+  for (const [name, value] of Object.entries(env)) {
+    if (name === 'PATH') process.env.PATH = value;
+    // `export GPG_TTY=$(tty)` in a non-terminal shell yields "not a tty"
+    else if (!process.env[name] && value.startsWith('/')) process.env[name] = value;
+  }
+}
+
+async function readLoginEnv(init: string): Promise<Record<string, string>> {
+  if (process.platform === 'win32') return {};
+  const [shell, args] = loginShellArgs(init);
+  const { stdout } = await execFileAsync(shell, args, {
+    encoding: 'utf8',
+    timeout: SHELL_TIMEOUT_MS,
+  });
+  return parseLoginEnv(stdout);
+}
+
+/** Take PATH and friends from the login shell. Blocks; meant for startup. */
+export function loadLoginPath(): void {
+  if (process.platform === 'win32') return;
+  try {
+    const [shell, args] = loginShellArgs('');
+    const output = execFileSync(shell, args, { encoding: 'utf8', timeout: SHELL_TIMEOUT_MS });
+    applyLoginEnv(parseLoginEnv(output));
+  } catch {
+    // Keep existing environment if shell invocation fails
+  }
+}
+
+/** Read the login shell's PATH again, e.g. after installing a tool. Returns the new PATH. */
+export async function refreshLoginPath(): Promise<string> {
+  applyLoginEnv(await readLoginEnv(''));
+  resolved.clear();
+  return process.env.PATH ?? '';
+}
+
+function getProfilesPath(): string {
+  return path.join(getStateDir(), 'env-profiles.json');
+}
+
+export function listEnvProfiles(): EnvProfile[] {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getProfilesPath(), 'utf8')) as EnvProfile[];
+  } catch {
+    cached = [];
+  }
+  return cached;
+}
+
+function writeProfiles(profiles: EnvProfile[]): void {
+  const profilesPath = getProfilesPath();
+  fs.mkdirSync(path.dirname(profilesPath), { recursive: true });
+  const tmpPath = profilesPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(profiles, null, 2), 'utf8');
+  fs.renameSync(tmpPath, profilesPath);
+  cached = profiles;
+  resolved.clear();
+}
+
+function validateProfile(value: unknown): EnvProfile {
+  const p = value as Partial<Record<keyof EnvProfile, unknown>> | null;
+  if (typeof p?.id !== 'string' || !/^[a-z0-9][a-z0-9-]*$/.test(p.id)) {
+    throw new Error('id must be lowercase letters, digits and dashes');
+  }
+  if (typeof p.name !== 'string' || !p.name.trim()) throw new Error('name must not be empty');
+  const entries = p.path ?? [];
+  if (!Array.isArray(entries) || !entries.every((e) => typeof e === 'string')) {
+    throw new Error('path must be an array of strings');
+  }
+  if (entries.some((e) => !path.isAbsolute(e) || e.includes(path.delimiter))) {
+    throw new Error('path entries must be absolute directories');
+  }
+  if (p.init !== undefined && typeof p.init !== 'string') throw new Error('init must be a string');
+  const env = p.env ?? {};
+  const isEnvEntry = ([k, v]: [string, unknown]) =>
+    /^[A-Za-z_][A-Za-z0-9_]*$/.test(k) && k !== 'PATH' && typeof v === 'string';
+  if (typeof env !== 'object' || Array.isArray(env) || !Object.entries(env).every(isEnvEntry)) {
+    throw new Error('env must map variable names (other than PATH) to strings');
+  }
+  return {
+    id: p.id,
+    name: p.name.trim(),
+    path: entries,
+    init: p.init?.trim() ?? '',
+    env: env as Record<string, string>,
+  };
+}
+
+/** Add a profile or replace the one with the same id. */
+export function saveEnvProfile(value: unknown): EnvProfile {
+  const profile = validateProfile(value);
+  const others = listEnvProfiles().filter((p) => p.id !== profile.id);
+  writeProfiles([...others, profile]);
+  return profile;
+}
+
+export function deleteEnvProfile(id: string): void {
+  writeProfiles(listEnvProfiles().filter((p) => p.id !== id));
+}
+
+/** The variables a profile sets, PATH included; init runs once until something changes. */
+export function resolveEnvProfile(id: string): Promise<Record<string, string>> {
+  const profile = listEnvProfiles().find((p) => p.id === id);
+  if (!profile) return Promise.reject(new Error(`Environment profile not found: ${id}`));
+  let env = resolved.get(id);
+  if (!env) {
+    env = (async () => {
+      const base = profile.init
+        ? ((await readLoginEnv(profile.init)).PATH ?? process.env.PATH)
+        : process.env.PATH;
+      const entries = [...profile.path, ...(base ? [base] : [])];
+      return { ...profile.env, PATH: entries.join(path.delimiter) };
+    })();
+    // A failed init is retried on the next spawn
+    env.catch(() => resolved.delete(id));
+    resolved.set(id, env);
+  }
+  return env;
+}
+
+async function envProfileFor(agentDefId: unknown, cwd: unknown): Promise<string | null> {
+  const agentProfile = listAgents().find((a) => a.id === agentDefId)?.env_profile;
+  if (agentProfile) return agentProfile;
+  if (typeof cwd !== 'string' || !cwd) return null;
+  const settings = await getRepoSettingsFor(cwd).catch(() => null);
+  return settings?.env_profile ?? null;
+}
+
+/**
+ * Environment from the profile for a spawn: the agent's own, else the one set
+ * for the project in `cwd`. A missing or failing profile is skipped.
+ */
+export async function profileEnvFor(
+  agentDefId: unknown,
+  cwd: unknown,
+): Promise<Record<string, string> | undefined> {
+  const id = await envProfileFor(agentDefId, cwd);
+  if (!id) return undefined;
+  try {
+    return await resolveEnvProfile(id);
+  } catch (e) {
+    console.warn(`Skipping environment profile ${id}:`, e);
+    return undefined;
+  }
+}
@@ -0,0 +1,61 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, vi } from 'vitest';
+
+const root = fs.mkdtempSync(path.join(os.tmpdir(), 'spawn-policy-'));
+vi.mock('electron', () => ({ app: { getPath: () => root, isPackaged: true } }));
+
+import { checkSpawnPolicy, setSpawnPolicy } from './spawn-policy.js';
+
+function binDir(name: string, commands: string[]): string {
+  const dir = path.join(root, name);
+  fs.mkdirSync(dir);
+  for (const command of commands) {
+    fs.writeFileSync(path.join(dir, command), '#!/bin/sh\n', { mode: 0o755 });
+  }
+  return dir;
+}
+
+const trusted = binDir('trusted', ['agent', 'other']);
+const elsewhere = binDir('elsewhere', ['agent']);
+const searchPath = [trusted, elsewhere].join(path.delimiter);
+
+// ---------------------------------------------------------------------------
+// checkSpawnPolicy
+// ---------------------------------------------------------------------------
+describe('checkSpawnPolicy', () => {
+  it.each([
+    ['a bare command found on PATH', 'agent', true],
+    ['the same file by its full path', path.join(trusted, 'agent'), true],
+    ['a file with the same name elsewhere', path.join(elsewhere, 'agent'), false],
+    ['a relative path with the same name', './agent', false],
+    ['an unlisted command', 'other', false],
+    ['a command that is not on PATH', 'missing', false],
+  ])('in allow mode with a bare entry, judges %s', (_name, command, allowed) => {
+    setSpawnPolicy({ mode: 'allow', commands: ['agent'] });
+    const check = () => checkSpawnPolicy(command, searchPath);
+    if (allowed) expect(check).not.toThrow();
+    else expect(check).toThrow(/not permitted/);
+  });
+
+  it('compares absolute entries with the resolved command', () => {
+    setSpawnPolicy({ mode: 'allow', commands: [path.join(elsewhere, 'agent')] });
+    expect(() => checkSpawnPolicy('agent', elsewhere)).not.toThrow();
+    expect(() => checkSpawnPolicy('agent', searchPath)).toThrow(/not permitted/);
+  });
+
+  it('denies a bare name wherever it lives', () => {
+    setSpawnPolicy({ mode: 'deny', commands: ['agent'] });
+    expect(() => checkSpawnPolicy('agent', searchPath)).toThrow(/not permitted/);
+    expect(() => checkSpawnPolicy(path.join(elsewhere, 'agent'), searchPath)).toThrow(
+      /not permitted/,
+    );
+    expect(() => checkSpawnPolicy('other', searchPath)).not.toThrow();
+  });
+
+  it('allows everything when off', () => {
+    setSpawnPolicy({ mode: 'off', commands: [] });
+    expect(() => checkSpawnPolicy(path.join(elsewhere, 'agent'), searchPath)).not.toThrow();
+  });
+});
@@ -0,0 +1,92 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+/**
+ * Restricts which executables spawn_agent may launch. Commands and entries
+ * are compared as absolute paths, with bare names looked up on PATH first. A
+ * bare deny entry also blocks that name wherever else it lives.
+ */
+export interface SpawnPolicy {
+  mode: 'off' | 'allow' | 'deny';
+  commands: string[];
+}
+
+const DEFAULT_POLICY: SpawnPolicy = { mode: 'off', commands: [] };
+
+let cached: SpawnPolicy | null = null;
+
+function getPolicyPath(): string {
+  return path.join(getStateDir(), 'spawn-policy.json');
+}
+
+function validatePolicy(value: unknown): SpawnPolicy {
+  const p = value as Partial<SpawnPolicy> | null;
+  if (!p || (p.mode !== 'off' && p.mode !== 'allow' && p.mode !== 'deny')) {
+    throw new Error("mode must be 'off', 'allow' or 'deny'");
+  }
+  if (!Array.isArray(p.commands) || !p.commands.every((c) => typeof c === 'string' && c)) {
+    throw new Error('commands must be an array of non-empty strings');
+  }
+  return { mode: p.mode, commands: [...p.commands] };
+}
+
+export function getSpawnPolicy(): SpawnPolicy {
+  if (cached) return cached;
+  try {
+    cached = validatePolicy(JSON.parse(fs.readFileSync(getPolicyPath(), 'utf8')));
+  } catch {
+    cached = { ...DEFAULT_POLICY };
+  }
+  return cached;
+}
+
+export function setSpawnPolicy(policy: unknown): SpawnPolicy {
+  const next = validatePolicy(policy);
+  const policyPath = getPolicyPath();
+  fs.mkdirSync(path.dirname(policyPath), { recursive: true });
+  const tmpPath = policyPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, policyPath);
+  cached = next;
+  return next;
+}
+
+/** The file `name` runs as when looked up on `searchPath`, or null if there is none. */
+function findOnPath(name: string, searchPath: string): string | null {
+  for (const dir of searchPath.split(path.delimiter)) {
+    if (!dir) continue;
+    const candidate = path.resolve(dir, name);
+    try {
+      fs.accessSync(candidate, fs.constants.X_OK);
+      if (fs.statSync(candidate).isFile()) return candidate;
+    } catch {
+      /* not here */
+    }
+  }
+  return null;
+}
+
+function toAbsolute(command: string, searchPath: string): string | null {
+  return command.includes('/') ? path.resolve(command) : findOnPath(command, searchPath);
+}
+
+function matches(entry: string, command: string, deny: boolean, searchPath: string): boolean {
+  const resolved = toAbsolute(command, searchPath);
+  if (resolved !== null && resolved === toAbsolute(entry, searchPath)) return true;
+  return deny && !entry.includes('/') && path.basename(command) === entry;
+}
+
+/**
+ * Throw if the policy forbids launching `command`. Pass the PATH the command
+ * will be spawned with, so bare names resolve to what actually runs.
+ */
+export function checkSpawnPolicy(command: string, searchPath = process.env.PATH ?? ''): void {
+  const policy = getSpawnPolicy();
+  if (policy.mode === 'off') return;
+  const deny = policy.mode === 'deny';
+  const listed = policy.commands.some((entry) => matches(entry, command, deny, searchPath));
+  if (policy.mode === 'allow' ? !listed : listed) {
+    throw new Error(`Command not permitted by spawn policy: ${command}`);
+  }
+}
@@ -0,0 +1,125 @@
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import type { BrowserWindow } from 'electron';
+
+// Stand in for the PTY sessions: `running` is what countQueueableAgents reports
+let running = 0;
+const exitListeners: Array<() => void> = [];
+vi.mock('./pty.js', () => ({
+  countQueueableAgents: () => running,
+  onPtyEvent: (_event: string, listener: () => void) => exitListeners.push(listener),
+}));
+
+type SpawnQueue = typeof import('./spawn-queue.js');
+let spawnQueue: SpawnQueue;
+const send = vi.fn();
+
+beforeEach(async () => {
+  running = 0;
+  exitListeners.length = 0;
+  send.mockClear();
+  // The queue lives in module state — start each test with a fresh one
+  vi.resetModules();
+  spawnQueue = await import('./spawn-queue.js');
+  spawnQueue.initSpawnQueue({
+    isDestroyed: () => false,
+    webContents: { send },
+  } as unknown as BrowserWindow);
+});
+
+const flush = () => new Promise((resolve) => setTimeout(resolve, 0));
+
+/** A spawn that registers its session, or fails, only when told to. */
+function pendingSpawn(agentId: string) {
+  const spawn = {
+    started: false,
+    register: () => {},
+    fail: (_err: Error) => {},
+    request: () =>
+      spawnQueue.requestSpawn(
+        {
+          agentId,
+          taskId: 'task',
+          command: 'claude',
+          queueable: true,
+          onOutput: { __CHANNEL_ID__: agentId },
+        },
+        () =>
+          new Promise<void>((resolve, reject) => {
+            spawn.started = true;
+            spawn.register = () => {
+              running++;
+              resolve();
+            };
+            spawn.fail = reject;
+          }),
+      ),
+  };
+  return spawn;
+}
+
+// ---------------------------------------------------------------------------
+// requestSpawn
+// ---------------------------------------------------------------------------
+describe('requestSpawn', () => {
+  it('starts only as many queued spawns as the raised limit allows', async () => {
+    spawnQueue.setAgentLimit(1);
+    running = 1;
+    const spawns = ['a', 'b', 'c'].map(pendingSpawn);
+    const results = await Promise.all(spawns.map((s) => s.request()));
+    expect(results.map((r) => r.position)).toEqual([1, 2, 3]);
+
+    // None of the started spawns has registered a session yet
+    spawnQueue.setAgentLimit(3);
+    await flush();
+    expect(spawns.map((s) => s.started)).toEqual([true, true, false]);
+    expect(spawnQueue.getSpawnQueue().queue.map((q) => q.agent_id)).toEqual(['c']);
+
+    spawns[0].register();
+    spawns[1].register();
+    await flush();
+    expect(spawns[2].started).toBe(false);
+
+    // A running agent exits and frees its slot
+    running--;
+    exitListeners.forEach((fn) => fn());
+    await flush();
+    expect(spawns[2].started).toBe(true);
+  });
+
+  it('holds the slot of an immediate start until it registers', async () => {
+    spawnQueue.setAgentLimit(1);
+    const first = pendingSpawn('a');
+    const second = pendingSpawn('b');
+    const firstResult = first.request();
+    expect(await second.request()).toEqual({ queued: true, position: 1 });
+
+    first.register();
+    expect(await firstResult).toEqual({ queued: false, position: 0 });
+    expect(second.started).toBe(false);
+  });
+
+  it('frees the slot of a spawn that fails to start', async () => {
+    spawnQueue.setAgentLimit(1);
+    const spawns = ['a', 'b', 'c'].map(pendingSpawn);
+    const firstResult = spawns[0].request();
+    await spawns[1].request();
+    await spawns[2].request();
+
+    spawns[0].fail(new Error('no such command'));
+    await expect(firstResult).rejects.toThrow('no such command');
+    expect(spawns[1].started).toBe(true);
+    expect(spawns[2].started).toBe(false);
+
+    // A queued spawn that fails is reported on its output channel
+    spawns[1].fail(new Error('no such command'));
+    await flush();
+    expect(send).toHaveBeenCalledWith(
+      'channel:b',
+      expect.objectContaining({
+        type: 'Exit',
+        data: expect.objectContaining({ signal: 'spawn_failed' }),
+      }),
+    );
+    expect(spawns[2].started).toBe(true);
+  });
+});
@@ -0,0 +1,141 @@
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { onPtyEvent, countQueueableAgents } from './pty.js';
+
+interface QueuedSpawn {
+  agentId: string;
+  taskId: string;
+  command: string;
+  channelId: string;
+  queuedAt: number;
+  start: () => void | Promise<void>;
+}
+
+export interface SpawnQueueEntry {
+  agent_id: string;
+  task_id: string;
+  command: string;
+  queued_at: number;
+}
+
+// 0 = unlimited
+let agentLimit = 0;
+const queue: QueuedSpawn[] = [];
+// Spawns started but not yet registered as sessions; they hold a slot meanwhile
+let pendingStarts = 0;
+let mainWindow: BrowserWindow | null = null;
+
+function emit(channel: string, payload: unknown): void {
+  if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send(channel, payload);
+}
+
+function hasFreeSlot(): boolean {
+  return agentLimit === 0 || countQueueableAgents() + pendingStarts < agentLimit;
+}
+
+/** Run `start` holding a slot until it has registered its session or failed. */
+async function startReserved(start: () => void | Promise<void>): Promise<void> {
+  pendingStarts++;
+  try {
+    await start();
+  } finally {
+    pendingStarts--;
+    // A failed start frees its slot for the next queued spawn
+    drain();
+  }
+}
+
+function startQueued(entry: QueuedSpawn): void {
+  emit(IPC.AgentDequeued, { agent_id: entry.agentId, task_id: entry.taskId });
+  startReserved(entry.start).catch((err: unknown) => {
+    // The renderer is waiting on the output channel — report the failure there
+    emit(`channel:${entry.channelId}`, {
+      type: 'Exit',
+      data: {
+        exit_code: null,
+        signal: 'spawn_failed',
+        last_output: [`Failed to spawn: ${err}`],
+        exit_reason: 'error',
+      },
+    });
+  });
+}
+
+function drain(): void {
+  while (queue.length > 0 && hasFreeSlot()) {
+    const next = queue.shift();
+    if (next) startQueued(next);
+  }
+}
+
+/** Hook the queue up to PTY exits so freed slots are filled automatically. */
+export function initSpawnQueue(win: BrowserWindow): void {
+  mainWindow = win;
+  // Sessions are removed right after the exit event fires — drain afterwards
+  onPtyEvent('exit', () => setTimeout(drain, 0));
+}
+
+/**
+ * Start `start` now if a slot is free (or the spawn doesn't count toward
+ * the limit), otherwise queue it FIFO and emit `agent-queued`.
+ */
+export async function requestSpawn(
+  args: {
+    agentId: string;
+    taskId: string;
+    command: string;
+    queueable?: boolean;
+    onOutput: { __CHANNEL_ID__: string };
+  },
+  start: () => void | Promise<void>,
+): Promise<{ queued: boolean; position: number }> {
+  if (!args.queueable) {
+    await start();
+    return { queued: false, position: 0 };
+  }
+  if (queue.length === 0 && hasFreeSlot()) {
+    await startReserved(start);
+    return { queued: false, position: 0 };
+  }
+
+  queue.push({
+    agentId: args.agentId,
+    taskId: args.taskId,
+    command: args.command,
+    channelId: args.onOutput.__CHANNEL_ID__,
+    queuedAt: Date.now(),
+    start,
+  });
+  const position = queue.length;
+  emit(IPC.AgentQueued, { agent_id: args.agentId, task_id: args.taskId, position });
+  return { queued: true, position };
+}
+
+/** Drop a queued spawn (e.g. its task was closed). Returns true if it was queued. */
+export function cancelQueuedSpawn(agentId: string): boolean {
+  const idx = queue.findIndex((q) => q.agentId === agentId);
+  if (idx === -1) return false;
+  queue.splice(idx, 1);
+  emit(IPC.AgentDequeued, { agent_id: agentId, cancelled: true });
+  return true;
+}
+
+export function setAgentLimit(limit: number): void {
+  if (!Number.isInteger(limit) || limit < 0) {
+    throw new Error('limit must be a non-negative integer');
+  }
+  agentLimit = limit;
+  drain();
+}
+
+export function getSpawnQueue(): { limit: number; queue: SpawnQueueEntry[] } {
+  return {
+    limit: agentLimit,
+    queue: queue.map((q) => ({
+      agent_id: q.agentId,
+      task_id: q.taskId,
+      command: q.command,
+      queued_at: q.queuedAt,
+    })),
+  };
+}
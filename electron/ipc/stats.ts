@@ -0,0 +1,153 @@
+import { execFile } from 'child_process';
+import { promisify } from 'util';
+import fs from 'fs';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getAgentPids } from './pty.js';
+
+const exec = promisify(execFile);
+
+const STATS_INTERVAL = 5_000; // ms
+const CLK_TCK = 100; // Linux USER_HZ is 100 on all mainstream kernels
+
+export interface AgentStats {
+  agent_id: string;
+  pid: number;
+  rss_bytes: number;
+  cpu_percent: number;
+  process_count: number;
+}
+
+interface ProcInfo {
+  ppid: number;
+  rssBytes: number;
+  /** Cumulative CPU ticks on Linux, instantaneous %CPU elsewhere. */
+  cpu: number;
+}
+
+// Previous cumulative CPU samples per agent, for Linux delta computation
+const prevCpu = new Map<string, { ticks: number; at: number }>();
+
+async function readProcTableLinux(): Promise<Map<number, ProcInfo>> {
+  const table = new Map<number, ProcInfo>();
+  const entries = await fs.promises.readdir('/proc');
+  await Promise.all(
+    entries.map(async (name) => {
+      if (!/^\d+$/.test(name)) return;
+      try {
+        const stat = await fs.promises.readFile(`/proc/${name}/stat`, 'utf8');
+        // Fields after the last ')' — comm may contain spaces or parens
+        const fields = stat.slice(stat.lastIndexOf(')') + 2).split(' ');
+        const ppid = parseInt(fields[1], 10);
+        const ticks = parseInt(fields[11], 10) + parseInt(fields[12], 10);
+        const rssPages = parseInt(fields[21], 10);
+        table.set(parseInt(name, 10), { ppid, rssBytes: rssPages * 4096, cpu: ticks });
+      } catch {
+        /* process exited mid-scan */
+      }
+    }),
+  );
+  return table;
+}
+
+async function readProcTablePs(): Promise<Map<number, ProcInfo>> {
+  const table = new Map<number, ProcInfo>();
+  const { stdout } = await exec('ps', ['-axo', 'pid=,ppid=,rss=,pcpu=']);
+  for (const line of stdout.split('\n')) {
+    const [pid, ppid, rssKb, pcpu] = line.trim().split(/\s+/);
+    if (!pid) continue;
+    table.set(parseInt(pid, 10), {
+      ppid: parseInt(ppid, 10),
+      rssBytes: (parseInt(rssKb, 10) || 0) * 1024,
+      cpu: parseFloat(pcpu) || 0,
+    });
+  }
+  return table;
+}
+
+function collectTree(root: number, table: Map<number, ProcInfo>): number[] {
+  const children = new Map<number, number[]>();
+  for (const [pid, info] of table) {
+    const list = children.get(info.ppid);
+    if (list) list.push(pid);
+    else children.set(info.ppid, [pid]);
+  }
+  const result: number[] = [];
+  const stack = [root];
+  while (stack.length > 0) {
+    const pid = stack.pop() as number;
+    if (!table.has(pid)) continue;
+    result.push(pid);
+    stack.push(...(children.get(pid) ?? []));
+  }
+  return result;
+}
+
+async function sampleStats(agentPids: Map<string, number>): Promise<AgentStats[]> {
+  const isLinux = process.platform === 'linux';
+  const table = isLinux ? await readProcTableLinux() : await readProcTablePs();
+  const now = Date.now();
+  const results: AgentStats[] = [];
+
+  for (const [agentId, pid] of agentPids) {
+    const tree = collectTree(pid, table);
+    let rssBytes = 0;
+    let cpu = 0;
+    for (const p of tree) {
+      const info = table.get(p);
+      if (!info) continue;
+      rssBytes += info.rssBytes;
+      cpu += info.cpu;
+    }
+
+    let cpuPercent = cpu;
+    if (isLinux) {
+      const prev = prevCpu.get(agentId);
+      prevCpu.set(agentId, { ticks: cpu, at: now });
+      const elapsedSec = prev ? (now - prev.at) / 1000 : 0;
+      cpuPercent =
+        prev && elapsedSec > 0 ? Math.max(0, (cpu - prev.ticks) / CLK_TCK / elapsedSec) * 100 : 0;
+    }
+
+    results.push({
+      agent_id: agentId,
+      pid,
+      rss_bytes: rssBytes,
+      cpu_percent: Math.round(cpuPercent * 10) / 10,
+      process_count: tree.length,
+    });
+  }
+
+  // Forget agents that are gone
+  for (const agentId of prevCpu.keys()) {
+    if (!agentPids.has(agentId)) prevCpu.delete(agentId);
+  }
+  return results;
+}
+
+/** Resource usage of a single agent's process tree, or null if not running. */
+export async function getAgentStats(agentId: string): Promise<AgentStats | null> {
+  const pid = getAgentPids().get(agentId);
+  if (pid === undefined) return null;
+  const [stats] = await sampleStats(new Map([[agentId, pid]]));
+  return stats ?? null;
+}
+
+/** Periodically emit resource usage for all running agents. Returns a stop function. */
+export function startAgentStatsPolling(win: BrowserWindow): () => void {
+  let inFlight = false;
+  const timer = setInterval(() => {
+    const pids = getAgentPids();
+    if (inFlight || pids.size === 0 || win.isDestroyed()) return;
+    inFlight = true;
+    sampleStats(pids)
+      .then((stats) => {
+        if (!win.isDestroyed()) win.webContents.send(IPC.AgentStats, stats);
+      })
+      .catch(() => {})
+      .finally(() => {
+        inFlight = false;
+      });
+  }, STATS_INTERVAL);
+  return () => clearInterval(timer);
+}
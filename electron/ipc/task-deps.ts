@@ -0,0 +1,126 @@
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getStateDir } from './persistence.js';
+
+// "Start task B after task A lands": dependency edges between tasks. A task
+// lands when it is merged or closed; once everything a task waits on has
+// landed, `task-unblocked` tells the renderer to start its agent.
+
+interface DepsFile {
+  /** task id -> ids of the tasks it waits for */
+  dependencies: Record<string, string[]>;
+  /** Merged tasks, plus closed ones that a dependency list still mentions. */
+  landed: string[];
+}
+
+export interface TaskUnblocked {
+  task_id: string;
+  dependencies: string[];
+}
+
+let cached: DepsFile | null = null;
+let mainWindow: BrowserWindow | null = null;
+
+function getDepsPath(): string {
+  return path.join(getStateDir(), 'task-dependencies.json');
+}
+
+function readDeps(): DepsFile {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getDepsPath(), 'utf8')) as DepsFile;
+  } catch {
+    cached = { dependencies: {}, landed: [] };
+  }
+  return cached;
+}
+
+function writeDeps(next: DepsFile): void {
+  const depsPath = getDepsPath();
+  fs.mkdirSync(path.dirname(depsPath), { recursive: true });
+  const tmpPath = depsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, depsPath);
+  cached = next;
+}
+
+function waitingOn(deps: DepsFile, taskId: string): string[] {
+  const landed = new Set(deps.landed);
+  return (deps.dependencies[taskId] ?? []).filter((id) => !landed.has(id));
+}
+
+/** Whether `from` can reach `to` by following dependency edges. */
+function reaches(dependencies: Record<string, string[]>, from: string, to: string): boolean {
+  const seen = new Set<string>();
+  const stack = [from];
+  while (stack.length > 0) {
+    const id = stack.pop() as string;
+    if (id === to) return true;
+    if (seen.has(id)) continue;
+    seen.add(id);
+    stack.push(...(dependencies[id] ?? []));
+  }
+  return false;
+}
+
+export function initTaskDependencies(win: BrowserWindow): void {
+  mainWindow = win;
+}
+
+/**
+ * Replace the tasks `taskId` waits for. Returns the ones that haven't landed
+ * yet; the task may start right away when that list is empty.
+ */
+export function setTaskDependencies(taskId: string, dependsOn: string[]): { waiting_on: string[] } {
+  const deps = readDeps();
+  const unique = [...new Set(dependsOn)].filter((id) => id !== taskId);
+  for (const id of unique) {
+    if (reaches(deps.dependencies, id, taskId)) {
+      throw new Error('Task dependencies would form a cycle');
+    }
+  }
+  const dependencies = { ...deps.dependencies };
+  if (unique.length > 0) dependencies[taskId] = unique;
+  else delete dependencies[taskId];
+  writeDeps({ dependencies, landed: [...deps.landed] });
+  return { waiting_on: waitingOn(readDeps(), taskId) };
+}
+
+export function getTaskDependencies(taskId: string): {
+  depends_on: string[];
+  waiting_on: string[];
+} {
+  const deps = readDeps();
+  return { depends_on: deps.dependencies[taskId] ?? [], waiting_on: waitingOn(deps, taskId) };
+}
+
+/** Record that `taskId` was merged and emit `task-unblocked` for tasks it held back. */
+export function markTaskLanded(taskId: string): void {
+  const deps = readDeps();
+  if (deps.landed.includes(taskId)) return;
+  const next = { dependencies: deps.dependencies, landed: [...deps.landed, taskId] };
+  writeDeps(next);
+  for (const id of Object.keys(next.dependencies)) {
+    if (!next.dependencies[id].includes(taskId) || waitingOn(next, id).length > 0) continue;
+    const event: TaskUnblocked = { task_id: id, dependencies: next.dependencies[id] };
+    if (mainWindow && !mainWindow.isDestroyed()) {
+      mainWindow.webContents.send(IPC.TaskUnblocked, event);
+    }
+  }
+}
+
+/** A closed task counts as landed for its dependents and drops its own edges. */
+export function forgetTask(taskId: string): void {
+  markTaskLanded(taskId);
+  const deps = readDeps();
+  const dependencies = { ...deps.dependencies };
+  delete dependencies[taskId];
+  // Nothing can start depending on a closed task, so only keep it while referenced
+  const referenced = new Set(Object.values(dependencies).flat());
+  writeDeps({
+    dependencies,
+    landed: deps.landed.filter((id) => id !== taskId || referenced.has(id)),
+  });
+}
@@ -0,0 +1,208 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+import { getTaskStatus, type TaskStatus } from './tasks.js';
+
+// Labels and priority for tasks, and board queries over them. Names and
+// projects come from the renderer's saved state (see syncKnownTasks), so only
+// tasks that still exist are returned.
+
+export const TASK_PRIORITIES = ['low', 'normal', 'high', 'urgent'] as const;
+export type TaskPriority = (typeof TASK_PRIORITIES)[number];
+
+const MAX_LABELS = 20;
+const MAX_LABEL_LEN = 40;
+
+export interface TaskMetadata {
+  labels: string[];
+  priority: TaskPriority;
+  updated_at: number;
+}
+
+export interface TaskRecord extends TaskMetadata {
+  id: string;
+  name: string;
+  project_id: string | null;
+  branch_name: string | null;
+  status: TaskStatus | null;
+}
+
+export interface TaskFilter {
+  /** Tasks carrying every one of these labels. */
+  labels?: string[];
+  priorities?: TaskPriority[];
+  statuses?: TaskStatus[];
+  project_id?: string;
+  /** Case-insensitive match on name, branch and labels. */
+  text?: string;
+  /** `priority` (highest first, the default), `name` or `updated` (newest first). */
+  sort?: 'priority' | 'name' | 'updated';
+}
+
+export interface KnownTask {
+  name: string;
+  project_id: string | null;
+  branch_name: string | null;
+}
+
+const DEFAULT_METADATA: TaskMetadata = { labels: [], priority: 'normal', updated_at: 0 };
+
+let cached: Record<string, TaskMetadata> | null = null;
+let knownTasks = new Map<string, KnownTask>();
+
+function getMetadataPath(): string {
+  return path.join(getStateDir(), 'task-metadata.json');
+}
+
+function readMetadata(): Record<string, TaskMetadata> {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getMetadataPath(), 'utf8')) as Record<
+      string,
+      TaskMetadata
+    >;
+  } catch {
+    cached = {};
+  }
+  return cached;
+}
+
+function writeMetadata(next: Record<string, TaskMetadata>): void {
+  const metadataPath = getMetadataPath();
+  fs.mkdirSync(path.dirname(metadataPath), { recursive: true });
+  const tmpPath = metadataPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, metadataPath);
+  cached = next;
+}
+
+function normalizeLabels(value: unknown): string[] {
+  if (!Array.isArray(value) || !value.every((l) => typeof l === 'string')) {
+    throw new Error('labels must be an array of strings');
+  }
+  const labels = [...new Set(value.map((l: string) => l.trim().toLowerCase()).filter(Boolean))];
+  if (labels.length > MAX_LABELS) throw new Error(`At most ${MAX_LABELS} labels per task`);
+  if (labels.some((l) => l.length > MAX_LABEL_LEN)) {
+    throw new Error(`Labels must be at most ${MAX_LABEL_LEN} characters`);
+  }
+  return labels.sort();
+}
+
+export function isTaskPriority(value: unknown): value is TaskPriority {
+  return TASK_PRIORITIES.includes(value as TaskPriority);
+}
+
+/** Replace the set of existing tasks, from the renderer's saved state. */
+export function syncKnownTasks(
+  tasks: { id: string; name: string; projectId?: string; branchName?: string }[],
+): void {
+  knownTasks = new Map(
+    tasks.map((t) => [
+      t.id,
+      { name: t.name, project_id: t.projectId || null, branch_name: t.branchName || null },
+    ]),
+  );
+}
+
+/** Name and branch of a task as of the renderer's last save. */
+export function getKnownTask(taskId: string): KnownTask | null {
+  return knownTasks.get(taskId) ?? null;
+}
+
+export function getTaskMetadata(taskId: string): TaskMetadata {
+  return readMetadata()[taskId] ?? DEFAULT_METADATA;
+}
+
+/** Update labels and/or priority; fields left out keep their value. */
+export function updateTaskMetadata(
+  taskId: string,
+  patch: { labels?: unknown; priority?: unknown },
+): TaskMetadata {
+  const current = getTaskMetadata(taskId);
+  if (patch.priority !== undefined && !isTaskPriority(patch.priority)) {
+    throw new Error(`priority must be one of ${TASK_PRIORITIES.join(', ')}`);
+  }
+  const next: TaskMetadata = {
+    labels: patch.labels !== undefined ? normalizeLabels(patch.labels) : current.labels,
+    priority: (patch.priority as TaskPriority | undefined) ?? current.priority,
+    updated_at: Date.now(),
+  };
+  writeMetadata({ ...readMetadata(), [taskId]: next });
+  return next;
+}
+
+export function forgetTaskMetadata(taskId: string): void {
+  const { [taskId]: _removed, ...rest } = readMetadata();
+  writeMetadata(rest);
+}
+
+export function listAllTaskMetadata(): Record<string, TaskMetadata> {
+  return readMetadata();
+}
+
+/**
+ * Merge metadata from another machine; for a task known on both, the more
+ * recently updated entry wins. Returns how many tasks were taken over.
+ */
+export function mergeTaskMetadata(value: unknown): number {
+  if (!value || typeof value !== 'object') throw new Error('task metadata must be an object');
+  const current = readMetadata();
+  const next = { ...current };
+  let merged = 0;
+  for (const [taskId, raw] of Object.entries(value)) {
+    const m = raw as Partial<Record<keyof TaskMetadata, unknown>> | null;
+    if (!isTaskPriority(m?.priority) || typeof m.updated_at !== 'number') continue;
+    if (current[taskId] && current[taskId].updated_at >= m.updated_at) continue;
+    next[taskId] = {
+      labels: normalizeLabels(m.labels),
+      priority: m.priority,
+      updated_at: m.updated_at,
+    };
+    merged++;
+  }
+  if (merged > 0) writeMetadata(next);
+  return merged;
+}
+
+/** Every label in use, for filter pickers. */
+export function listTaskLabels(): string[] {
+  const labels = new Set<string>();
+  for (const [id, meta] of Object.entries(readMetadata())) {
+    if (knownTasks.has(id)) meta.labels.forEach((l) => labels.add(l));
+  }
+  return [...labels].sort();
+}
+
+export function queryTasks(filter: TaskFilter = {}): TaskRecord[] {
+  const metadata = readMetadata();
+  const wantLabels = filter.labels ? normalizeLabels(filter.labels) : [];
+  const text = filter.text?.trim().toLowerCase();
+
+  const records: TaskRecord[] = [...knownTasks].map(([id, task]) => ({
+    id,
+    ...task,
+    ...(metadata[id] ?? DEFAULT_METADATA),
+    status: getTaskStatus(id),
+  }));
+
+  const matches = records.filter(
+    (r) =>
+      wantLabels.every((l) => r.labels.includes(l)) &&
+      (!filter.priorities || filter.priorities.includes(r.priority)) &&
+      (!filter.statuses || (r.status !== null && filter.statuses.includes(r.status))) &&
+      (!filter.project_id || r.project_id === filter.project_id) &&
+      (!text ||
+        [r.name, r.branch_name ?? '', ...r.labels].some((s) => s.toLowerCase().includes(text))),
+  );
+
+  const rank = (p: TaskPriority) => TASK_PRIORITIES.indexOf(p);
+  const byName = (a: TaskRecord, b: TaskRecord) => a.name.localeCompare(b.name);
+  switch (filter.sort ?? 'priority') {
+    case 'name':
+      return matches.sort(byName);
+    case 'updated':
+      return matches.sort((a, b) => b.updated_at - a.updated_at || byName(a, b));
+    default:
+      return matches.sort((a, b) => rank(b.priority) - rank(a.priority) || byName(a, b));
+  }
+}
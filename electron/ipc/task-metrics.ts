@@ -0,0 +1,188 @@
+import fs from 'fs';
+import path from 'path';
+import { stripAnsi } from './idle.js';
+import { getStateDir } from './persistence.js';
+import { onPtyEvent, type PtyEventSession } from './pty.js';
+
+// Per-task time and spend. Runtime is the wall-clock time agent sessions were
+// alive; tokens and cost come from the usage summaries agent CLIs print (e.g.
+// Claude Code's "Total cost", Codex's "Token usage"). Shell sessions don't count.
+
+interface UsagePattern {
+  re: RegExp;
+  apply: (m: RegExpMatchArray, usage: AgentUsage) => void;
+}
+
+const toNumber = (s: string) => Number(s.replace(/,/g, ''));
+
+/** Session totals as printed by the CLIs; later lines replace earlier ones. */
+const USAGE_PATTERNS: UsagePattern[] = [
+  // Claude Code: "Total cost: $0.1234"
+  { re: /Total cost:\s*\$([\d.,]+)/i, apply: (m, u) => (u.cost_usd = toNumber(m[1])) },
+  // Codex CLI: "Token usage: total=12,345 input=10,000 (+ 2,000 cached) output=2,345"
+  {
+    re: /Token usage:.*?input=([\d,]+).*?output=([\d,]+)/i,
+    apply: (m, u) => {
+      u.input_tokens = toNumber(m[1]);
+      u.output_tokens = toNumber(m[2]);
+    },
+  },
+  // Claude Code and others: "1,234 input tokens, 567 output tokens" / "Usage: ..."
+  { re: /([\d,]+)\s+input tokens/i, apply: (m, u) => (u.input_tokens = toNumber(m[1])) },
+  { re: /([\d,]+)\s+output tokens/i, apply: (m, u) => (u.output_tokens = toNumber(m[1])) },
+  // Gemini CLI /stats: "Input Tokens   1,234" / "Output Tokens  567"
+  { re: /Input Tokens\s+([\d,]+)/, apply: (m, u) => (u.input_tokens = toNumber(m[1])) },
+  { re: /Output Tokens\s+([\d,]+)/, apply: (m, u) => (u.output_tokens = toNumber(m[1])) },
+];
+
+interface AgentUsage {
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number | null;
+}
+
+const EMPTY_USAGE: AgentUsage = { input_tokens: 0, output_tokens: 0, cost_usd: null };
+
+interface StoredMetrics {
+  /** Runtime of finished sessions; live ones are added on read. */
+  runtime_ms: number;
+  sessions: number;
+  /** Latest totals per agent session (`agentId:startedAt`), summed for the task. */
+  usage: Record<string, AgentUsage>;
+}
+
+type MetricsFile = Record<string, StoredMetrics>;
+
+export interface TaskMetrics {
+  task_id: string;
+  runtime_ms: number;
+  sessions: number;
+  running: boolean;
+  input_tokens: number;
+  output_tokens: number;
+  /** null until an agent reports a cost. */
+  cost_usd: number | null;
+}
+
+export interface MetricsReport {
+  tasks: TaskMetrics[];
+  totals: Omit<TaskMetrics, 'task_id' | 'running'> & { running_tasks: number };
+}
+
+const LINE_BUFFER_CHARS = 4096;
+
+let cached: MetricsFile | null = null;
+const live = new Map<string, { taskId: string; key: string; startedAt: number; line: string }>();
+
+function getMetricsPath(): string {
+  return path.join(getStateDir(), 'task-metrics.json');
+}
+
+function readMetrics(): MetricsFile {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getMetricsPath(), 'utf8')) as MetricsFile;
+  } catch {
+    cached = {};
+  }
+  return cached;
+}
+
+function writeMetrics(next: MetricsFile): void {
+  const metricsPath = getMetricsPath();
+  fs.mkdirSync(path.dirname(metricsPath), { recursive: true });
+  const tmpPath = metricsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, metricsPath);
+  cached = next;
+}
+
+function update(taskId: string, fn: (m: StoredMetrics) => StoredMetrics): void {
+  const all = readMetrics();
+  const current = all[taskId] ?? { runtime_ms: 0, sessions: 0, usage: {} };
+  writeMetrics({ ...all, [taskId]: fn(current) });
+}
+
+function parseLine(taskId: string, sessionKey: string, line: string): void {
+  const matches = USAGE_PATTERNS.map((p) => [p, line.match(p.re)] as const).filter(([, m]) => m);
+  if (matches.length === 0) return;
+  update(taskId, (m) => {
+    const usage = { ...(m.usage[sessionKey] ?? EMPTY_USAGE) };
+    for (const [pattern, match] of matches) pattern.apply(match as RegExpMatchArray, usage);
+    return { ...m, usage: { ...m.usage, [sessionKey]: usage } };
+  });
+}
+
+export function initTaskMetrics(): void {
+  onPtyEvent('spawn', (agentId, data) => {
+    const { taskId, queueable } = data as PtyEventSession;
+    if (!queueable) return;
+    // A restart reuses the agent id but its CLI counts from zero again
+    const startedAt = Date.now();
+    live.set(agentId, { taskId, key: `${agentId}:${startedAt}`, startedAt, line: '' });
+    update(taskId, (m) => ({ ...m, sessions: m.sessions + 1 }));
+  });
+  onPtyEvent('output', (agentId, data) => {
+    const session = live.get(agentId);
+    if (!session) return;
+    // Usage summaries are single lines; only complete lines are matched
+    const lines = (session.line + stripAnsi((data as { data: string }).data)).split(/\r?\n|\r/);
+    session.line = (lines.pop() ?? '').slice(-LINE_BUFFER_CHARS);
+    for (const line of lines) parseLine(session.taskId, session.key, line);
+  });
+  onPtyEvent('exit', (agentId) => {
+    const session = live.get(agentId);
+    if (!session) return;
+    live.delete(agentId);
+    if (session.line) parseLine(session.taskId, session.key, session.line);
+    const elapsed = Date.now() - session.startedAt;
+    update(session.taskId, (m) => ({ ...m, runtime_ms: m.runtime_ms + elapsed }));
+  });
+}
+
+function toTaskMetrics(taskId: string, stored: StoredMetrics | undefined): TaskMetrics {
+  const now = Date.now();
+  let runtime = stored?.runtime_ms ?? 0;
+  let running = false;
+  for (const session of live.values()) {
+    if (session.taskId !== taskId) continue;
+    runtime += now - session.startedAt;
+    running = true;
+  }
+  const usage = Object.values(stored?.usage ?? {});
+  const costs = usage.map((u) => u.cost_usd).filter((c): c is number => c !== null);
+  return {
+    task_id: taskId,
+    runtime_ms: runtime,
+    sessions: stored?.sessions ?? 0,
+    running,
+    input_tokens: usage.reduce((sum, u) => sum + u.input_tokens, 0),
+    output_tokens: usage.reduce((sum, u) => sum + u.output_tokens, 0),
+    cost_usd: costs.length > 0 ? costs.reduce((sum, c) => sum + c, 0) : null,
+  };
+}
+
+export function getTaskMetrics(taskId: string): TaskMetrics {
+  return toTaskMetrics(taskId, readMetrics()[taskId]);
+}
+
+/** Every tracked task, costliest first, with totals. Pass `taskIds` to restrict it. */
+export function getMetricsReport(taskIds?: string[]): MetricsReport {
+  const all = readMetrics();
+  const ids = taskIds ?? Object.keys(all);
+  const tasks = ids
+    .map((id) => toTaskMetrics(id, all[id]))
+    .sort((a, b) => (b.cost_usd ?? 0) - (a.cost_usd ?? 0) || b.runtime_ms - a.runtime_ms);
+  const costs = tasks.map((t) => t.cost_usd).filter((c): c is number => c !== null);
+  return {
+    tasks,
+    totals: {
+      runtime_ms: tasks.reduce((sum, t) => sum + t.runtime_ms, 0),
+      sessions: tasks.reduce((sum, t) => sum + t.sessions, 0),
+      running_tasks: tasks.filter((t) => t.running).length,
+      input_tokens: tasks.reduce((sum, t) => sum + t.input_tokens, 0),
+      output_tokens: tasks.reduce((sum, t) => sum + t.output_tokens, 0),
+      cost_usd: costs.length > 0 ? costs.reduce((sum, c) => sum + c, 0) : null,
+    },
+  };
+}
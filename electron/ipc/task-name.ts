@@ -0,0 +1,100 @@
+import { execFile } from 'child_process';
+import os from 'os';
+import { promisify } from 'util';
+import { listAgents } from './agents.js';
+import { slug } from './branch-name.js';
+import { checkSpawnPolicy } from './spawn-policy.js';
+
+const exec = promisify(execFile);
+
+// Names for tasks the user didn't title. The first line of the prompt is
+// cleaned up and shortened; optionally the agent CLI is asked for a title in
+// its non-interactive mode, falling back to the heuristic when that fails.
+
+const MAX_TITLE_LEN = 40;
+const MAX_PROMPT_CHARS = 4000;
+const SUMMARY_TIMEOUT_MS = 20_000;
+
+/** One-shot invocations that print an answer and exit, per agent id. */
+const SUMMARY_ARGS: Record<string, (instruction: string) => string[]> = {
+  'claude-code': (q) => ['-p', q],
+  codex: (q) => ['exec', q],
+  gemini: (q) => ['-p', q],
+};
+
+const FILLER =
+  /^(please|can you|could you|would you|we need to|we should|let's|i want to|help me)\s+/i;
+
+export interface TaskNameSuggestion {
+  title: string;
+  slug: string;
+  source: 'heuristic' | 'agent';
+}
+
+/** Cut at a word boundary so the title reads naturally. */
+function truncateTitle(text: string): string {
+  if (text.length <= MAX_TITLE_LEN) return text;
+  const cut = text.slice(0, MAX_TITLE_LEN);
+  return cut.replace(/\s+\S*$/, '') || cut;
+}
+
+function heuristicTitle(prompt: string): string {
+  const line = prompt
+    .split('\n')
+    .map((l) => l.trim())
+    .find(Boolean);
+  if (!line) return '';
+  let title = line
+    .replace(/https?:\/\/\S+/g, '')
+    .replace(/^[#>*\-\s]+/, '')
+    .trim();
+  let prev = '';
+  while (title !== prev) {
+    prev = title;
+    title = title.replace(FILLER, '');
+  }
+  title = title.replace(/[.!?:,;]+$/, '').trim();
+  return truncateTitle(title ? title[0].toUpperCase() + title.slice(1) : line);
+}
+
+async function agentTitle(agentId: string, prompt: string): Promise<string | null> {
+  const agent = listAgents().find((a) => a.id === agentId);
+  const buildArgs = SUMMARY_ARGS[agentId];
+  if (!agent || !buildArgs) return null;
+  checkSpawnPolicy(agent.command);
+
+  const instruction =
+    'Reply with only a short title (at most 6 words, no quotes, no trailing punctuation) ' +
+    'for this coding task:\n\n' +
+    prompt.slice(0, MAX_PROMPT_CHARS);
+  // Run outside any repo so agents that act on their cwd have nothing to touch
+  const { stdout } = await exec(agent.command, buildArgs(instruction), {
+    cwd: os.tmpdir(),
+    timeout: SUMMARY_TIMEOUT_MS,
+    maxBuffer: 1024 * 1024,
+  });
+  const line = stdout
+    .split('\n')
+    .map((l) => l.trim().replace(/^["'`*]+|["'`*.]+$/g, ''))
+    .find(Boolean);
+  return line ? truncateTitle(line) : null;
+}
+
+/**
+ * Suggest a title and branch slug for `prompt`. With `agentId`, that agent's
+ * CLI writes the title; errors and timeouts fall back to the heuristic.
+ */
+export async function suggestTaskName(
+  prompt: string,
+  agentId: string | null = null,
+): Promise<TaskNameSuggestion> {
+  if (agentId) {
+    const title = await agentTitle(agentId, prompt).catch((err) => {
+      console.warn('Agent task name suggestion failed:', err);
+      return null;
+    });
+    if (title) return { title, slug: slug(title), source: 'agent' };
+  }
+  const title = heuristicTitle(prompt);
+  return { title, slug: slug(title), source: 'heuristic' };
+}
@@ -0,0 +1,80 @@
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+// Review notes and a checklist per task, one JSON file each under app data so
+// they survive restarts and archiving (restored tasks keep their id).
+
+const MAX_NOTES_CHARS = 200_000;
+const MAX_CHECKLIST_ITEMS = 200;
+const MAX_ITEM_CHARS = 500;
+
+export interface ChecklistItem {
+  id: string;
+  text: string;
+  done: boolean;
+}
+
+export interface TaskNotes {
+  notes: string;
+  checklist: ChecklistItem[];
+  /** 0 when nothing was saved for the task yet. */
+  updated_at: number;
+}
+
+function getNotesPath(taskId: string): string {
+  if (!/^[\w-]+$/.test(taskId)) throw new Error('taskId contains invalid characters');
+  return path.join(getStateDir(), 'task-notes', `${taskId}.json`);
+}
+
+function validateChecklist(value: unknown): ChecklistItem[] {
+  if (!Array.isArray(value)) throw new Error('checklist must be an array');
+  if (value.length > MAX_CHECKLIST_ITEMS) {
+    throw new Error(`At most ${MAX_CHECKLIST_ITEMS} checklist items per task`);
+  }
+  return value.map((item: Partial<ChecklistItem>) => {
+    if (typeof item?.id !== 'string' || typeof item.text !== 'string') {
+      throw new Error('Checklist items need a string id and text');
+    }
+    return { id: item.id, text: item.text.slice(0, MAX_ITEM_CHARS), done: item.done === true };
+  });
+}
+
+export function getTaskNotes(taskId: string): TaskNotes {
+  try {
+    return JSON.parse(fs.readFileSync(getNotesPath(taskId), 'utf8')) as TaskNotes;
+  } catch {
+    return { notes: '', checklist: [], updated_at: 0 };
+  }
+}
+
+/** Replace the notes and/or checklist; fields left out keep their value. */
+export function updateTaskNotes(
+  taskId: string,
+  patch: { notes?: unknown; checklist?: unknown },
+): TaskNotes {
+  if (patch.notes !== undefined && typeof patch.notes !== 'string') {
+    throw new Error('notes must be a string');
+  }
+  if (typeof patch.notes === 'string' && patch.notes.length > MAX_NOTES_CHARS) {
+    throw new Error('Notes are too long');
+  }
+  const current = getTaskNotes(taskId);
+  const next: TaskNotes = {
+    notes: (patch.notes as string | undefined) ?? current.notes,
+    checklist:
+      patch.checklist !== undefined ? validateChecklist(patch.checklist) : current.checklist,
+    updated_at: Date.now(),
+  };
+
+  const notesPath = getNotesPath(taskId);
+  fs.mkdirSync(path.dirname(notesPath), { recursive: true });
+  const tmpPath = notesPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, notesPath);
+  return next;
+}
+
+export function deleteTaskNotes(taskId: string): void {
+  fs.rmSync(getNotesPath(taskId), { force: true });
+}
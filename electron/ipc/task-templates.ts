@@ -0,0 +1,147 @@
+import { randomUUID } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+import { createTask } from './tasks.js';
+
+// Reusable task presets (bug fix, dependency bump, test writing...): the agent
+// to run, its first prompt, and how the worktree is prepared.
+
+export interface TaskTemplate {
+  id: string;
+  name: string;
+  /** Agent to start; null uses whatever the user picked last. */
+  agent_id: string | null;
+  /** First prompt, with {name} and caller-supplied {placeholders}. */
+  prompt: string;
+  /** Branch the worktree starts from; null uses the project's base branch. */
+  base_branch: string | null;
+  branch_prefix: string | null;
+  /** Extra environment for the agent process. */
+  env: Record<string, string>;
+  /** Run in a shell in the new worktree, after the project's setup command. */
+  setup_commands: string[];
+}
+
+export interface TemplateTaskParams {
+  name: string;
+  projectRoot: string;
+  symlinkDirs?: string[];
+  branchPrefix: string;
+  branchTemplate?: string;
+  branchName?: string;
+  /** Replaces the template's prompt, e.g. after editing it in the dialog. */
+  prompt?: string;
+  values?: Record<string, string>;
+}
+
+let cached: TaskTemplate[] | null = null;
+
+function getTemplatesPath(): string {
+  return path.join(getStateDir(), 'task-templates.json');
+}
+
+export function listTaskTemplates(): TaskTemplate[] {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getTemplatesPath(), 'utf8')) as TaskTemplate[];
+  } catch {
+    cached = [];
+  }
+  return cached;
+}
+
+function writeTemplates(templates: TaskTemplate[]): void {
+  const templatesPath = getTemplatesPath();
+  fs.mkdirSync(path.dirname(templatesPath), { recursive: true });
+  const tmpPath = templatesPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(templates, null, 2), 'utf8');
+  fs.renameSync(tmpPath, templatesPath);
+  cached = templates;
+}
+
+function validateTemplate(value: unknown): Omit<TaskTemplate, 'id'> & { id?: string } {
+  const t = value as Partial<Record<keyof TaskTemplate, unknown>> | null;
+  if (typeof t?.name !== 'string' || !t.name.trim()) throw new Error('name must not be empty');
+  const optionalString = (v: unknown, label: string): string | null => {
+    if (v === undefined || v === null || v === '') return null;
+    if (typeof v !== 'string') throw new Error(`${label} must be a string or null`);
+    return v;
+  };
+  const env = t.env ?? {};
+  const isEnvEntry = ([k, v]: [string, unknown]) =>
+    /^[A-Za-z_][A-Za-z0-9_]*$/.test(k) && typeof v === 'string';
+  if (typeof env !== 'object' || Array.isArray(env) || !Object.entries(env).every(isEnvEntry)) {
+    throw new Error('env must map variable names to strings');
+  }
+  const setup = t.setup_commands ?? [];
+  if (!Array.isArray(setup) || !setup.every((c) => typeof c === 'string')) {
+    throw new Error('setup_commands must be an array of strings');
+  }
+  return {
+    id: typeof t.id === 'string' ? t.id : undefined,
+    name: t.name.trim(),
+    agent_id: optionalString(t.agent_id, 'agent_id'),
+    prompt: optionalString(t.prompt, 'prompt') ?? '',
+    base_branch: optionalString(t.base_branch, 'base_branch'),
+    branch_prefix: optionalString(t.branch_prefix, 'branch_prefix'),
+    env: env as Record<string, string>,
+    setup_commands: setup.map((c: string) => c.trim()).filter(Boolean),
+  };
+}
+
+/** Create a template, or replace the one with the same id. New ones keep a given id. */
+export function saveTaskTemplate(value: unknown): TaskTemplate {
+  const { id, ...fields } = validateTemplate(value);
+  const templates = [...listTaskTemplates()];
+  const index = id ? templates.findIndex((t) => t.id === id) : -1;
+  const template: TaskTemplate = {
+    id: index === -1 ? (id || randomUUID()) : templates[index].id,
+    ...fields,
+  };
+  if (index === -1) templates.push(template);
+  else templates[index] = template;
+  writeTemplates(templates);
+  return template;
+}
+
+export function deleteTaskTemplate(id: string): void {
+  writeTemplates(listTaskTemplates().filter((t) => t.id !== id));
+}
+
+/** Fill {placeholders}; unknown ones are left as typed. */
+export function renderTemplatePrompt(prompt: string, values: Record<string, string>): string {
+  return prompt.replace(/\{([A-Za-z0-9_-]+)\}/g, (m, key: string) => values[key] ?? m);
+}
+
+/**
+ * Create a task's worktree from a template. The renderer starts the agent
+ * with the returned agent, prompt and environment.
+ */
+export async function createTaskFromTemplate(templateId: string, params: TemplateTaskParams) {
+  const template = listTaskTemplates().find((t) => t.id === templateId);
+  if (!template) throw new Error(`Task template not found: ${templateId}`);
+
+  const result = await createTask(
+    params.name,
+    params.projectRoot,
+    params.symlinkDirs ?? [],
+    template.branch_prefix ?? params.branchPrefix,
+    template.base_branch ?? undefined,
+    undefined,
+    params.branchTemplate,
+    params.branchName,
+  );
+  const setup = [result.setup_command, ...template.setup_commands].filter((c): c is string => !!c);
+  return {
+    ...result,
+    setup_command: setup.length > 0 ? setup.join(' && ') : null,
+    template_id: template.id,
+    agent_id: template.agent_id,
+    prompt: renderTemplatePrompt(params.prompt ?? template.prompt, {
+      ...params.values,
+      name: params.name,
+    }),
+    env: template.env,
+  };
+}
@@ -1,62 +1,338 @@
 import { randomUUID } from 'crypto';
-import { createWorktree, removeWorktree } from './git.js';
-import { killAgent, notifyAgentListChanged } from './pty.js';
-
-const MAX_SLUG_LEN = 72;
-
-function slug(name: string): string {
-  let result = '';
-  let prevWasHyphen = false;
-  for (const c of name.toLowerCase()) {
-    if (result.length >= MAX_SLUG_LEN) break;
-    if (/[a-z0-9]/.test(c)) {
-      result += c;
-      prevWasHyphen = false;
-    } else if (!prevWasHyphen) {
-      result += '-';
-      prevWasHyphen = true;
-    }
-  }
-  return result.replace(/^-+|-+$/g, '');
-}
-
-function sanitizeBranchPrefix(prefix: string): string {
-  const parts = prefix
-    .split('/')
-    .map(slug)
-    .filter((p) => p.length > 0);
-  return parts.length === 0 ? 'task' : parts.join('/');
-}
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { buildBranchName } from './branch-name.js';
+import { IPC } from './channels.js';
+import {
+  createWorktree,
+  forkTaskBranch,
+  getWorktreeSetupCommand,
+  removeWorktree,
+  type SubmoduleMode,
+} from './git.js';
+import { getStateDir } from './persistence.js';
+import { killAgent, notifyAgentListChanged, onPtyEvent, type PtyEventSession } from './pty.js';
+import { cancelQueuedSpawn, getSpawnQueue } from './spawn-queue.js';
 
 export async function createTask(
   name: string,
   projectRoot: string,
   symlinkDirs: string[],
   branchPrefix: string,
-): Promise<{ id: string; branch_name: string; worktree_path: string }> {
-  const prefix = sanitizeBranchPrefix(branchPrefix);
-  const branchName = `${prefix}/${slug(name)}`;
-  const worktree = await createWorktree(projectRoot, branchName, symlinkDirs);
+  baseRef?: string,
+  submodules?: SubmoduleMode,
+  branchTemplate?: string,
+  branchNameOverride?: string,
+  copyFiles?: boolean,
+): Promise<{
+  id: string;
+  branch_name: string;
+  worktree_path: string;
+  setup_command: string | null;
+}> {
+  const branchName =
+    branchNameOverride ?? (await buildBranchName(projectRoot, name, branchPrefix, branchTemplate));
+  const worktree = await createWorktree(
+    projectRoot,
+    branchName,
+    symlinkDirs,
+    baseRef,
+    submodules,
+    copyFiles,
+  );
+  const id = randomUUID();
+  startTaskLifecycle(id);
+  return {
+    id,
+    branch_name: worktree.branch,
+    worktree_path: worktree.path,
+    // Run by the renderer in the task's terminal, where its output is visible
+    setup_command: await getWorktreeSetupCommand(projectRoot).catch(() => null),
+  };
+}
+
+/**
+ * Start a new task from another task's current state, uncommitted changes
+ * included, on branch `newBranch`. The source task is left untouched.
+ */
+export async function forkTask(
+  projectRoot: string,
+  sourceWorktree: string,
+  newBranch: string,
+): Promise<{
+  id: string;
+  branch_name: string;
+  worktree_path: string;
+  setup_command: string | null;
+  uncommitted_changes: boolean;
+}> {
+  const worktree = await forkTaskBranch(projectRoot, sourceWorktree, newBranch);
+  const id = randomUUID();
+  startTaskLifecycle(id);
   return {
-    id: randomUUID(),
+    id,
     branch_name: worktree.branch,
     worktree_path: worktree.path,
+    setup_command: await getWorktreeSetupCommand(projectRoot).catch(() => null),
+    uncommitted_changes: worktree.uncommitted_changes,
   };
 }
 
+/**
+ * Stop every session of the task, including ones the caller didn't list and
+ * queued spawns, then remove its worktree. No new session can join the task
+ * while this runs.
+ */
 export async function deleteTask(
   agentIds: string[],
   branchName: string,
   deleteBranch: boolean,
   projectRoot: string,
+  taskId?: string,
 ): Promise<void> {
-  for (const agentId of agentIds) {
-    try {
-      killAgent(agentId);
-    } catch {
-      /* already dead */
+  if (taskId) deletingTasks.add(taskId);
+  try {
+    const ids = new Set(agentIds);
+    if (taskId) {
+      for (const session of listTaskAgents(taskId)) ids.add(session.agent_id);
+    }
+    for (const agentId of ids) {
+      if (cancelQueuedSpawn(agentId)) continue;
+      try {
+        killAgent(agentId);
+      } catch {
+        /* already dead */
+      }
+    }
+    await removeWorktree(projectRoot, branchName, deleteBranch);
+  } finally {
+    if (taskId) {
+      deletingTasks.delete(taskId);
+      taskSessions.delete(taskId);
     }
   }
-  await removeWorktree(projectRoot, branchName, deleteBranch);
   notifyAgentListChanged();
 }
+
+// --- Agent sessions ---
+
+/** A terminal session owned by a task: one of its agents or a plain shell. */
+export interface TaskAgentSession {
+  agent_id: string;
+  task_id: string;
+  kind: 'agent' | 'shell';
+  /** `idle`: waiting at a prompt. `queued`: held back by the agent limit. */
+  state: 'queued' | 'running' | 'idle';
+  started_at: number;
+}
+
+const taskSessions = new Map<string, Map<string, TaskAgentSession>>();
+const deletingTasks = new Set<string>();
+
+/** Sessions of a task, live ones first in start order, then queued spawns. */
+export function listTaskAgents(taskId: string): TaskAgentSession[] {
+  const live = [...(taskSessions.get(taskId)?.values() ?? [])];
+  const queued = getSpawnQueue()
+    .queue.filter((q) => q.task_id === taskId)
+    .map(
+      (q): TaskAgentSession => ({
+        agent_id: q.agent_id,
+        task_id: taskId,
+        kind: 'agent',
+        state: 'queued',
+        started_at: q.queued_at,
+      }),
+    );
+  return [...live.sort((a, b) => a.started_at - b.started_at), ...queued];
+}
+
+/** Refuse new sessions for a task that is being deleted. */
+export function assertTaskAcceptsAgents(taskId: string): void {
+  if (deletingTasks.has(taskId)) throw new Error(`Task ${taskId} is being deleted`);
+}
+
+/** Whether another agent (not shell) of the task is still working. */
+function hasOtherBusyAgent(taskId: string, agentId: string): boolean {
+  for (const session of taskSessions.get(taskId)?.values() ?? []) {
+    if (session.agent_id !== agentId && session.kind === 'agent' && session.state === 'running') {
+      return true;
+    }
+  }
+  return false;
+}
+
+function setSessionState(taskId: string, agentId: string, state: TaskAgentSession['state']): void {
+  const session = taskSessions.get(taskId)?.get(agentId);
+  if (session) session.state = state;
+}
+
+// --- Status lifecycle ---
+
+export type TaskStatus = 'planning' | 'running' | 'review' | 'merging' | 'done' | 'failed';
+
+export interface TaskStatusChanged {
+  task_id: string;
+  /** null when the task (re)starts its lifecycle. */
+  from: TaskStatus | null;
+  to: TaskStatus;
+  at: number;
+}
+
+/** Allowed moves. `done` is final; a restored task starts over at `planning`. */
+const TRANSITIONS: Record<TaskStatus, readonly TaskStatus[]> = {
+  planning: ['running', 'review', 'failed', 'done'],
+  running: ['review', 'merging', 'failed', 'done'],
+  review: ['running', 'merging', 'failed', 'done'],
+  merging: ['review', 'done', 'failed'],
+  failed: ['planning', 'running', 'review', 'done'],
+  done: [],
+};
+
+type StatusFile = Record<string, { status: TaskStatus; updated_at: number }>;
+
+/** Finished tasks are forgotten after this; closed worktree tasks right away. */
+const DONE_RETENTION_MS = 7 * 24 * 60 * 60 * 1000;
+
+let statusCache: StatusFile | null = null;
+let statusWindow: BrowserWindow | null = null;
+const statusListeners = new Set<(change: TaskStatusChanged) => void>();
+
+function getStatusPath(): string {
+  return path.join(getStateDir(), 'task-status.json');
+}
+
+function readStatuses(): StatusFile {
+  if (statusCache) return statusCache;
+  try {
+    statusCache = JSON.parse(fs.readFileSync(getStatusPath(), 'utf8')) as StatusFile;
+  } catch {
+    statusCache = {};
+  }
+  return statusCache;
+}
+
+function writeStatuses(next: StatusFile): void {
+  const statusPath = getStatusPath();
+  fs.mkdirSync(path.dirname(statusPath), { recursive: true });
+  const tmpPath = statusPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(next, null, 2), 'utf8');
+  fs.renameSync(tmpPath, statusPath);
+  statusCache = next;
+}
+
+function recordStatus(taskId: string, from: TaskStatus | null, to: TaskStatus): TaskStatusChanged {
+  const change: TaskStatusChanged = { task_id: taskId, from, to, at: Date.now() };
+  writeStatuses({ ...readStatuses(), [taskId]: { status: to, updated_at: change.at } });
+  if (statusWindow && !statusWindow.isDestroyed()) {
+    statusWindow.webContents.send(IPC.TaskStatusChanged, change);
+  }
+  for (const listener of statusListeners) listener(change);
+  return change;
+}
+
+export function isTaskStatus(value: unknown): value is TaskStatus {
+  return typeof value === 'string' && value in TRANSITIONS;
+}
+
+export function getTaskStatus(taskId: string): TaskStatus | null {
+  return readStatuses()[taskId]?.status ?? null;
+}
+
+export function getTaskStatuses(): Record<string, TaskStatus> {
+  return Object.fromEntries(Object.entries(readStatuses()).map(([id, s]) => [id, s.status]));
+}
+
+/** Put a new or restored task at the start of its lifecycle. */
+export function startTaskLifecycle(taskId: string): void {
+  recordStatus(taskId, getTaskStatus(taskId), 'planning');
+}
+
+/** Move a task to `to`, rejecting transitions the lifecycle doesn't allow. */
+export function setTaskStatus(taskId: string, to: TaskStatus): TaskStatusChanged | null {
+  const from = getTaskStatus(taskId);
+  if (from === null) throw new Error(`Unknown task: ${taskId}`);
+  if (from === to) return null;
+  if (!TRANSITIONS[from].includes(to)) {
+    throw new Error(`Task cannot go from ${from} to ${to}`);
+  }
+  return recordStatus(taskId, from, to);
+}
+
+/** Like setTaskStatus, for automatic moves: a disallowed transition is skipped. */
+export function advanceTaskStatus(taskId: string, to: TaskStatus): void {
+  const from = getTaskStatus(taskId);
+  if (from !== null && from !== to && TRANSITIONS[from].includes(to)) {
+    recordStatus(taskId, from, to);
+  }
+}
+
+export function forgetTaskStatus(taskId: string): void {
+  const { [taskId]: _removed, ...rest } = readStatuses();
+  writeStatuses(rest);
+}
+
+/** Subscribe to status changes, e.g. from the scheduler. Returns an unsubscribe function. */
+export function onTaskStatusChanged(listener: (change: TaskStatusChanged) => void): () => void {
+  statusListeners.add(listener);
+  return () => {
+    statusListeners.delete(listener);
+  };
+}
+
+/**
+ * Track each task's sessions and drive statuses from them: an agent starting
+ * means `running`; once no agent of the task is working anymore, going idle
+ * at a prompt or exiting cleanly means `review` and crashing means `failed`.
+ * Plain shells don't count.
+ */
+export function initTaskStatus(win: BrowserWindow): void {
+  statusWindow = win;
+  const cutoff = Date.now() - DONE_RETENTION_MS;
+  const statuses = readStatuses();
+  writeStatuses(
+    Object.fromEntries(
+      Object.entries(statuses).filter(([, s]) => s.status !== 'done' || s.updated_at > cutoff),
+    ),
+  );
+
+  onPtyEvent('spawn', (agentId, data) => {
+    const session = data as PtyEventSession;
+    const sessions = taskSessions.get(session.taskId) ?? new Map<string, TaskAgentSession>();
+    sessions.set(agentId, {
+      agent_id: agentId,
+      task_id: session.taskId,
+      kind: session.queueable ? 'agent' : 'shell',
+      state: 'running',
+      started_at: Date.now(),
+    });
+    taskSessions.set(session.taskId, sessions);
+    if (!session.queueable) return;
+    // Direct-mode tasks, and ones created before statuses were tracked, start here
+    if (getTaskStatus(session.taskId) === null) recordStatus(session.taskId, null, 'running');
+    else advanceTaskStatus(session.taskId, 'running');
+  });
+  onPtyEvent('output', (agentId, data) => {
+    const { taskId } = data as PtyEventSession;
+    if (taskSessions.get(taskId)?.get(agentId)?.state === 'idle') {
+      setSessionState(taskId, agentId, 'running');
+    }
+  });
+  onPtyEvent('idle', (agentId, data) => {
+    const session = data as PtyEventSession;
+    setSessionState(session.taskId, agentId, 'idle');
+    if (session.queueable && !hasOtherBusyAgent(session.taskId, agentId)) {
+      advanceTaskStatus(session.taskId, 'review');
+    }
+  });
+  onPtyEvent('exit', (agentId, data) => {
+    const { taskId, queueable, exitReason } = data as PtyEventSession & { exitReason: string };
+    const sessions = taskSessions.get(taskId);
+    sessions?.delete(agentId);
+    if (sessions?.size === 0) taskSessions.delete(taskId);
+    if (!queueable || getTaskStatus(taskId) !== 'running') return;
+    if (hasOtherBusyAgent(taskId, agentId)) return;
+    const clean = exitReason === 'normal' || exitReason === 'killed_by_user';
+    advanceTaskStatus(taskId, clean ? 'review' : 'failed');
+  });
+}
@@ -0,0 +1,106 @@
+// Flood protection for PTY output: when an agent sustains more than
+// maxBytesPerSec for sustainSeconds (e.g. cat-ing a binary), output is
+// dropped and replaced by periodic "N bytes dropped" notices.
+
+export interface ThrottleOptions {
+  enabled: boolean;
+  maxBytesPerSec: number;
+  sustainSeconds: number;
+}
+
+export const DEFAULT_THROTTLE: ThrottleOptions = {
+  enabled: true,
+  maxBytesPerSec: 2 * 1024 * 1024,
+  sustainSeconds: 3,
+};
+
+const WINDOW_MS = 1_000;
+
+export interface OutputThrottle {
+  /** Account for a chunk. `pass` is false while dropping; `notice` is text to inject. */
+  admit: (bytes: number) => { pass: boolean; notice: string | null };
+  configure: (opts: Partial<ThrottleOptions>) => void;
+  options: () => ThrottleOptions;
+}
+
+function dropNotice(bytes: number, done: boolean): string {
+  const kb = Math.round(bytes / 1024);
+  const suffix = done ? 'output resumed' : 'output flood, still dropping';
+  return `\r\n\x1b[33m[${kb} KB dropped — ${suffix}]\x1b[0m\r\n`;
+}
+
+export function validateThrottleOptions(opts: Partial<ThrottleOptions>): void {
+  if (opts.enabled !== undefined && typeof opts.enabled !== 'boolean') {
+    throw new Error('enabled must be a boolean');
+  }
+  if (
+    opts.maxBytesPerSec !== undefined &&
+    (!Number.isFinite(opts.maxBytesPerSec) || opts.maxBytesPerSec < 1024)
+  ) {
+    throw new Error('maxBytesPerSec must be at least 1024');
+  }
+  if (
+    opts.sustainSeconds !== undefined &&
+    (!Number.isInteger(opts.sustainSeconds) || opts.sustainSeconds < 1)
+  ) {
+    throw new Error('sustainSeconds must be a positive integer');
+  }
+}
+
+/** Apply only the fields of `patch` that are set. */
+export function mergeThrottleOptions(
+  base: ThrottleOptions,
+  patch: Partial<ThrottleOptions>,
+): ThrottleOptions {
+  return {
+    enabled: patch.enabled ?? base.enabled,
+    maxBytesPerSec: patch.maxBytesPerSec ?? base.maxBytesPerSec,
+    sustainSeconds: patch.sustainSeconds ?? base.sustainSeconds,
+  };
+}
+
+export function createOutputThrottle(initial: ThrottleOptions): OutputThrottle {
+  let opts = { ...initial };
+  let windowStart = Date.now();
+  let windowBytes = 0;
+  let overLimitWindows = 0;
+  let dropping = false;
+  let droppedBytes = 0;
+
+  const rollWindow = (now: number): string | null => {
+    let notice: string | null = null;
+    const overLimit = windowBytes > opts.maxBytesPerSec;
+    overLimitWindows = overLimit ? overLimitWindows + 1 : 0;
+
+    if (dropping && droppedBytes > 0) {
+      notice = dropNotice(droppedBytes, !overLimit);
+      droppedBytes = 0;
+    }
+    if (dropping && !overLimit) dropping = false;
+    if (!dropping && overLimitWindows >= opts.sustainSeconds) dropping = true;
+
+    windowStart = now;
+    windowBytes = 0;
+    return notice;
+  };
+
+  return {
+    admit: (bytes) => {
+      if (!opts.enabled) return { pass: true, notice: null };
+      const now = Date.now();
+      const notice = now - windowStart >= WINDOW_MS ? rollWindow(now) : null;
+      windowBytes += bytes;
+      if (dropping) droppedBytes += bytes;
+      return { pass: !dropping, notice };
+    },
+    configure: (next) => {
+      opts = mergeThrottleOptions(opts, next);
+      if (!opts.enabled) {
+        dropping = false;
+        droppedBytes = 0;
+        overLimitWindows = 0;
+      }
+    },
+    options: () => ({ ...opts }),
+  };
+}
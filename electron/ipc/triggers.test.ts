@@ -0,0 +1,82 @@
+import { describe, it, expect } from 'vitest';
+import { createPatternMatcher } from './triggers.js';
+
+// ---------------------------------------------------------------------------
+// createPatternMatcher
+// ---------------------------------------------------------------------------
+describe('createPatternMatcher', () => {
+  it.each([
+    {
+      name: 'a complete line',
+      pattern: 'error: (\\w+)',
+      chunks: ['build error: E42 found\n'],
+      expected: [{ line: 'build error: E42 found', groups: ['E42'] }],
+    },
+    {
+      name: 'a line split across reads',
+      pattern: 'Done in (\\d+)s',
+      chunks: ['Do', 'ne in 1', '2s\r\n'],
+      expected: [{ line: 'Done in 12s', groups: ['12'] }],
+    },
+    {
+      name: 'colored output',
+      pattern: '^PASS (.*)$',
+      chunks: ['\x1b[32mPASS\x1b[0m src/a.test.ts\n'],
+      expected: [{ line: 'PASS src/a.test.ts', groups: ['src/a.test.ts'] }],
+    },
+    {
+      name: 'carriage-return progress lines',
+      pattern: '100%',
+      chunks: ['50%\r100%\r'],
+      expected: [{ line: '100%', groups: [] }],
+    },
+    {
+      name: 'an optional group that did not match',
+      pattern: 'v(\\d+)(-beta)?',
+      chunks: ['v3\n'],
+      expected: [{ line: 'v3', groups: ['3', ''] }],
+    },
+    {
+      name: 'an unterminated line',
+      pattern: 'prompt',
+      chunks: ['waiting at prompt'],
+      expected: [],
+    },
+  ])('matches $name', ({ pattern, chunks, expected }) => {
+    const matcher = createPatternMatcher();
+    matcher.subscribe('t', pattern);
+    const matches = chunks.flatMap((chunk) => matcher.feed(chunk));
+    expect(matches).toEqual(expected.map((m) => ({ trigger_id: 't', ...m })));
+  });
+
+  it('reports each subscribed trigger that matches a line', () => {
+    const matcher = createPatternMatcher();
+    matcher.subscribe('a', 'fail');
+    matcher.subscribe('b', 'test');
+    matcher.subscribe('c', 'pass');
+    expect(matcher.feed('test fail\n').map((m) => m.trigger_id)).toEqual(['a', 'b']);
+  });
+
+  it('stops matching after unsubscribe', () => {
+    const matcher = createPatternMatcher();
+    matcher.subscribe('t', 'x');
+    expect(matcher.unsubscribe('t')).toBe(true);
+    expect(matcher.unsubscribe('t')).toBe(false);
+    expect(matcher.feed('x\n')).toEqual([]);
+  });
+
+  it.each([
+    ['an invalid pattern', '(', /Invalid pattern/],
+    ['a pattern that is too long', 'x'.repeat(501), /too long/],
+  ])('rejects %s', (_name, pattern, error) => {
+    expect(() => createPatternMatcher().subscribe('t', pattern)).toThrow(error);
+  });
+
+  it('limits the number of triggers', () => {
+    const matcher = createPatternMatcher();
+    for (let i = 0; i < 32; i++) matcher.subscribe(`t${i}`, 'x');
+    expect(() => matcher.subscribe('one-more', 'x')).toThrow(/At most 32/);
+    // Replacing an existing trigger is still allowed
+    expect(() => matcher.subscribe('t0', 'y')).not.toThrow();
+  });
+});
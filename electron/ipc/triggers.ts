@@ -0,0 +1,58 @@
+import { stripAnsi } from './idle.js';
+
+const MAX_TRIGGERS = 32;
+const MAX_PATTERN_LEN = 500;
+const MAX_LINE_LEN = 4096;
+
+export interface PatternMatch {
+  trigger_id: string;
+  line: string;
+  groups: string[];
+}
+
+/** Per-session set of regex triggers evaluated against complete output lines. */
+export interface PatternMatcher {
+  subscribe: (triggerId: string, pattern: string) => void;
+  unsubscribe: (triggerId: string) => boolean;
+  /** Feed raw PTY output; returns matches for every line completed by it. */
+  feed: (data: string) => PatternMatch[];
+}
+
+export function createPatternMatcher(): PatternMatcher {
+  const triggers = new Map<string, RegExp>();
+  let partial = '';
+
+  const matchLine = (line: string, out: PatternMatch[]) => {
+    for (const [triggerId, re] of triggers) {
+      const m = re.exec(line);
+      if (m) out.push({ trigger_id: triggerId, line, groups: m.slice(1).map((g) => g ?? '') });
+    }
+  };
+
+  return {
+    subscribe: (triggerId, pattern) => {
+      if (pattern.length > MAX_PATTERN_LEN) throw new Error('Pattern is too long');
+      if (!triggers.has(triggerId) && triggers.size >= MAX_TRIGGERS) {
+        throw new Error(`At most ${MAX_TRIGGERS} patterns per agent`);
+      }
+      let re: RegExp;
+      try {
+        re = new RegExp(pattern);
+      } catch (e) {
+        throw new Error(`Invalid pattern: ${e instanceof Error ? e.message : e}`);
+      }
+      triggers.set(triggerId, re);
+    },
+    unsubscribe: (triggerId) => triggers.delete(triggerId),
+    feed: (data) => {
+      if (triggers.size === 0) return [];
+      const matches: PatternMatch[] = [];
+      const lines = (partial + stripAnsi(data)).split(/\r?\n|\r/);
+      partial = (lines.pop() ?? '').slice(-MAX_LINE_LEN);
+      for (const line of lines) {
+        if (line.trim()) matchLine(line, matches);
+      }
+      return matches;
+    },
+  };
+}
@@ -0,0 +1,254 @@
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+
+// One place for file watching. Subscriptions on the same directory share a
+// single fs.watch handle; each subscription has its own debounce and gets the
+// changes of a burst coalesced per path, with a kind worked out once the burst
+// settles. In-process users call `subscribe`; the renderer uses `watch_path`
+// and receives `path-changed` events. When the OS can't say what changed (an
+// event without a name, or the watch failing and being reopened), subscribers
+// are asked to rescan instead, so no change is silently lost.
+
+const DEFAULT_DEBOUNCE_MS = 200;
+/** Delay before reopening a watch that failed, e.g. on a full inotify queue. */
+const REOPEN_DELAY_MS = 1000;
+/** Renderer subscriptions open at once. */
+const MAX_IPC_WATCHES = 64;
+
+export type ChangeKind = 'created' | 'modified' | 'removed';
+
+export interface PathChange {
+  /** Relative to the watched directory, with forward slashes. */
+  path: string;
+  kind: ChangeKind;
+}
+
+export interface PathChangedEvent {
+  id: string;
+  root: string;
+  changes: PathChange[];
+  /** Changes were missed; the watcher should re-read the directory. */
+  rescan?: boolean;
+}
+
+export interface WatchOptions {
+  recursive?: boolean;
+  debounceMs?: number;
+  /** Return false to drop a change before it is queued. */
+  filter?: (relPath: string) => boolean;
+  /** Debounce every path on its own, so a busy file doesn't hold back others. */
+  perPath?: boolean;
+  /** Called (debounced) when changes may have been missed and the tree should be re-read. */
+  onRescan?: () => void;
+}
+
+/** An empty relPath means "something changed, name unknown". */
+type RawListener = (event: 'rename' | 'change', relPath: string) => void;
+
+interface SharedWatch {
+  root: string;
+  recursive: boolean;
+  watcher: fs.FSWatcher | null;
+  listeners: Set<RawListener>;
+}
+
+/** One open fs.watch handle, for list_active_watchers. */
+export interface WatchHandleInfo {
+  root: string;
+  recursive: boolean;
+  subscribers: number;
+  /** False while a failed watch waits to be reopened. */
+  open: boolean;
+}
+
+const shared = new Map<string, SharedWatch>();
+const ipcWatches = new Map<string, { root: string; stop: () => void }>();
+let mainWindow: BrowserWindow | null = null;
+
+function acquire(root: string, recursive: boolean, listener: RawListener): () => void {
+  const key = `${recursive ? 'r' : 'd'}:${root}`;
+  let entry = shared.get(key);
+  if (!entry) {
+    const created: SharedWatch = { root, recursive, watcher: null, listeners: new Set() };
+    const open = () => {
+      const watcher = fs.watch(root, { recursive }, (event, filename) => {
+        const rel = filename ? filename.toString().split(path.sep).join('/') : '';
+        for (const fn of created.listeners) fn(event, rel);
+      });
+      // A failed watch (e.g. an overflowed event queue) is reopened while the
+      // directory exists; subscribers rescan for what happened in between
+      watcher.on('error', () => {
+        watcher.close();
+        created.watcher = null;
+        setTimeout(() => {
+          if (shared.get(key) !== created || !fs.existsSync(root)) return;
+          try {
+            open();
+          } catch {
+            return;
+          }
+          for (const fn of created.listeners) fn('rename', '');
+        }, REOPEN_DELAY_MS);
+      });
+      created.watcher = watcher;
+    };
+    open();
+    entry = created;
+    shared.set(key, entry);
+  }
+  const current = entry;
+  current.listeners.add(listener);
+  return () => {
+    current.listeners.delete(listener);
+    if (current.listeners.size > 0 || shared.get(key) !== current) return;
+    current.watcher?.close();
+    shared.delete(key);
+  };
+}
+
+/**
+ * Watch a directory and get its changes in debounced batches. Throws if the
+ * directory can't be watched. Returns an unsubscribe function.
+ */
+export function subscribe(
+  root: string,
+  opts: WatchOptions,
+  listener: (changes: PathChange[]) => void,
+): () => void {
+  const debounceMs = opts.debounceMs ?? DEFAULT_DEBOUNCE_MS;
+  const pending = new Map<string, 'rename' | 'change'>();
+  // Keyed by path with perPath, else a single '' timer for the whole burst
+  const timers = new Map<string, ReturnType<typeof setTimeout>>();
+  let rescanTimer: ReturnType<typeof setTimeout> | null = null;
+  let closed = false;
+
+  const flush = (rels: string[]) => {
+    if (closed) return;
+    const changes: PathChange[] = [];
+    for (const rel of rels) {
+      const event = pending.get(rel);
+      if (!event) continue;
+      pending.delete(rel);
+      const exists = fs.existsSync(path.join(root, rel));
+      const kind: ChangeKind = !exists ? 'removed' : event === 'rename' ? 'created' : 'modified';
+      changes.push({ path: rel, kind });
+    }
+    if (changes.length > 0) listener(changes);
+  };
+
+  const schedule = (key: string, rels: () => string[]) => {
+    const previous = timers.get(key);
+    if (previous) clearTimeout(previous);
+    const timer = setTimeout(() => {
+      timers.delete(key);
+      flush(rels());
+    }, debounceMs);
+    timers.set(key, timer);
+  };
+
+  const release = acquire(root, opts.recursive ?? false, (event, rel) => {
+    if (!rel) {
+      const onRescan = opts.onRescan;
+      if (!onRescan) return;
+      if (rescanTimer) clearTimeout(rescanTimer);
+      rescanTimer = setTimeout(() => {
+        rescanTimer = null;
+        if (!closed) onRescan();
+      }, debounceMs);
+      return;
+    }
+    if (opts.filter && !opts.filter(rel)) return;
+    // A later 'change' doesn't hide that the file appeared in this burst
+    if (pending.get(rel) !== 'rename') pending.set(rel, event);
+    if (opts.perPath) schedule(rel, () => [rel]);
+    else schedule('', () => [...pending.keys()]);
+  });
+
+  return () => {
+    closed = true;
+    for (const timer of timers.values()) clearTimeout(timer);
+    if (rescanTimer) clearTimeout(rescanTimer);
+    release();
+  };
+}
+
+/** Watch a single file through its directory, so atomic replace-by-rename is seen. */
+export function subscribeFile(
+  file: string,
+  opts: Pick<WatchOptions, 'debounceMs'>,
+  listener: (kind: ChangeKind) => void,
+): () => void {
+  const name = path.basename(file);
+  return subscribe(
+    path.dirname(file),
+    {
+      ...opts,
+      filter: (rel) => rel === name,
+      onRescan: () => listener(fs.existsSync(file) ? 'modified' : 'removed'),
+    },
+    (changes) => listener(changes[changes.length - 1].kind),
+  );
+}
+
+/** Top-level names a renderer watch can skip, e.g. node_modules. */
+function ignoreFilter(ignore: unknown): ((rel: string) => boolean) | undefined {
+  if (ignore === undefined) return undefined;
+  if (!Array.isArray(ignore) || !ignore.every((n) => typeof n === 'string')) {
+    throw new Error('ignore must be an array of strings');
+  }
+  const names = new Set(ignore);
+  return (rel) => !names.has(rel.split('/')[0]);
+}
+
+/** Start a renderer watch; replaces an existing one with the same id. */
+export function watchPath(id: string, root: string, opts: Record<string, unknown> = {}): void {
+  if (!fs.statSync(root).isDirectory()) throw new Error(`Not a directory: ${root}`);
+  unwatch(id);
+  if (ipcWatches.size >= MAX_IPC_WATCHES) {
+    throw new Error(`At most ${MAX_IPC_WATCHES} paths can be watched at once`);
+  }
+  const debounceMs = opts.debounce_ms;
+  if (debounceMs !== undefined && (typeof debounceMs !== 'number' || debounceMs < 0)) {
+    throw new Error('debounce_ms must be a non-negative number');
+  }
+  const send = (event: PathChangedEvent) => {
+    if (!mainWindow || mainWindow.isDestroyed()) return;
+    mainWindow.webContents.send(IPC.PathChanged, event);
+  };
+  const stop = subscribe(
+    root,
+    {
+      recursive: opts.recursive === true,
+      debounceMs,
+      filter: ignoreFilter(opts.ignore),
+      onRescan: () => send({ id, root, changes: [], rescan: true }),
+    },
+    (changes) => send({ id, root, changes }),
+  );
+  ipcWatches.set(id, { root, stop });
+}
+
+export function unwatch(id: string): void {
+  ipcWatches.get(id)?.stop();
+  ipcWatches.delete(id);
+}
+
+export function listWatchHandles(): WatchHandleInfo[] {
+  return [...shared.values()].map((w) => ({
+    root: w.root,
+    recursive: w.recursive,
+    subscribers: w.listeners.size,
+    open: w.watcher !== null,
+  }));
+}
+
+/** Renderer watches started with watch_path. */
+export function listPathWatches(): { id: string; root: string }[] {
+  return [...ipcWatches].map(([id, w]) => ({ id, root: w.root }));
+}
+
+export function initWatcher(win: BrowserWindow): void {
+  mainWindow = win;
+}
@@ -0,0 +1,141 @@
+import fs from 'fs';
+import path from 'path';
+import {
+  addAgentDef,
+  addAgentProfile,
+  listAgents,
+  listUserAgentConfig,
+  updateAgentDef,
+  updateAgentProfile,
+} from './agents.js';
+import { listPromptSnippets, savePromptSnippet } from './prompt-library.js';
+import { getSettings, updateSettings } from './settings.js';
+import { listAllTaskMetadata, mergeTaskMetadata } from './task-metadata.js';
+import { listTaskTemplates, saveTaskTemplate } from './task-templates.js';
+
+// A workspace file carries the setup that isn't tied to one machine's
+// repositories: settings, custom agents and their profiles, task templates,
+// the prompt library and task labels/priorities. Secrets are never included.
+// Importing merges: entries with the same id are replaced, others are added.
+
+const WORKSPACE_FORMAT = 'parallel-code-workspace';
+const WORKSPACE_VERSION = 1;
+
+export interface WorkspaceImportResult {
+  settings: number;
+  agents: number;
+  profiles: number;
+  templates: number;
+  prompts: number;
+  task_metadata: number;
+  /** Entries that failed validation, with the reason. */
+  skipped: string[];
+}
+
+export function exportWorkspace(file: string): void {
+  const { version: _version, ...settings } = getSettings();
+  const { agents, profiles } = listUserAgentConfig();
+  const bundle = {
+    format: WORKSPACE_FORMAT,
+    version: WORKSPACE_VERSION,
+    exported_at: new Date().toISOString(),
+    settings,
+    agents,
+    agent_profiles: profiles,
+    task_templates: listTaskTemplates(),
+    prompt_library: listPromptSnippets(),
+    task_metadata: listAllTaskMetadata(),
+  };
+  fs.mkdirSync(path.dirname(file), { recursive: true });
+  const tmpPath = file + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(bundle, null, 2), 'utf8');
+  fs.renameSync(tmpPath, file);
+}
+
+function list(value: unknown): unknown[] {
+  return Array.isArray(value) ? value : [];
+}
+
+function record(value: unknown): Record<string, unknown> {
+  return value && typeof value === 'object' && !Array.isArray(value)
+    ? (value as Record<string, unknown>)
+    : {};
+}
+
+export function importWorkspace(file: string): WorkspaceImportResult {
+  let bundle: Record<string, unknown>;
+  try {
+    bundle = record(JSON.parse(fs.readFileSync(file, 'utf8')));
+  } catch (e) {
+    throw new Error(`Could not read workspace file: ${String(e)}`);
+  }
+  if (bundle.format !== WORKSPACE_FORMAT) throw new Error('Not a Parallel Code workspace file');
+  if (typeof bundle.version !== 'number' || bundle.version > WORKSPACE_VERSION) {
+    throw new Error('The workspace file is from a newer version of Parallel Code');
+  }
+
+  const result: WorkspaceImportResult = {
+    settings: 0,
+    agents: 0,
+    profiles: 0,
+    templates: 0,
+    prompts: 0,
+    task_metadata: 0,
+    skipped: [],
+  };
+  const attempt = (label: string, fn: () => void): boolean => {
+    try {
+      fn();
+      return true;
+    } catch (e) {
+      result.skipped.push(`${label}: ${e instanceof Error ? e.message : String(e)}`);
+      return false;
+    }
+  };
+
+  // One key at a time, so a path that doesn't exist here doesn't drop the rest
+  for (const [key, value] of Object.entries(record(bundle.settings))) {
+    if (attempt(`setting ${key}`, () => updateSettings({ [key]: value }))) result.settings++;
+  }
+
+  const customIds = new Set(listUserAgentConfig().agents.map((a) => a.id));
+  for (const def of list(bundle.agents)) {
+    const id = record(def).id;
+    const ok = attempt(`agent ${String(id)}`, () => {
+      if (typeof id === 'string' && customIds.has(id)) updateAgentDef(id, def);
+      else addAgentDef(def);
+    });
+    if (ok) result.agents++;
+  }
+
+  const knownAgents = new Set(listAgents().map((a) => a.id));
+  const userProfiles = listUserAgentConfig().profiles;
+  for (const [agentId, profiles] of Object.entries(record(bundle.agent_profiles))) {
+    for (const profile of list(profiles)) {
+      const id = record(profile).id;
+      const ok = attempt(`profile ${agentId}/${String(id)}`, () => {
+        if (!knownAgents.has(agentId)) throw new Error('agent not found');
+        const exists = (userProfiles[agentId] ?? []).some((p) => p.id === id);
+        if (exists && typeof id === 'string') updateAgentProfile(agentId, id, profile);
+        else addAgentProfile(agentId, profile);
+      });
+      if (ok) result.profiles++;
+    }
+  }
+
+  for (const template of list(bundle.task_templates)) {
+    const name = record(template).name;
+    if (attempt(`template ${String(name)}`, () => saveTaskTemplate(template))) result.templates++;
+  }
+
+  for (const snippet of list(bundle.prompt_library)) {
+    const name = record(snippet).name;
+    if (attempt(`prompt ${String(name)}`, () => savePromptSnippet(snippet))) result.prompts++;
+  }
+
+  attempt('task metadata', () => {
+    result.task_metadata = mergeTaskMetadata(record(bundle.task_metadata));
+  });
+
+  return result;
+}
@@ -0,0 +1,61 @@
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { describe, it, expect, beforeEach } from 'vitest';
+import { readWorktreeFile, writeWorktreeFile } from './worktree-files.js';
+
+let worktree: string;
+let outside: string;
+
+beforeEach(() => {
+  const root = fs.mkdtempSync(path.join(os.tmpdir(), 'worktree-files-'));
+  worktree = path.join(root, 'worktree');
+  outside = path.join(root, 'outside');
+  fs.mkdirSync(path.join(worktree, '.git'), { recursive: true });
+  fs.mkdirSync(outside);
+  fs.writeFileSync(path.join(worktree, '.git', 'config'), '[core]\n');
+  fs.writeFileSync(path.join(worktree, 'README.md'), 'hello\n');
+  fs.writeFileSync(path.join(outside, 'secret'), 'secret\n');
+  fs.symlinkSync(path.join(worktree, '.git'), path.join(worktree, 'git-link'));
+  fs.symlinkSync(path.join(worktree, '.git', 'config'), path.join(worktree, 'config-link'));
+  fs.symlinkSync(path.join(outside, 'secret'), path.join(worktree, 'secret-link'));
+});
+
+// ---------------------------------------------------------------------------
+// readWorktreeFile / writeWorktreeFile
+// ---------------------------------------------------------------------------
+describe('worktree file access', () => {
+  it.each([
+    ['a path under .git', '.git/config', /under \.git/],
+    ['a symlink to .git', 'git-link/config', /under \.git/],
+    ['a symlink to a file in .git', 'config-link', /under \.git/],
+    ['a symlink out of the worktree', 'secret-link', /outside the worktree/],
+    ['a relative path out of the worktree', '../outside/secret', /outside the worktree/],
+  ])('refuses %s', async (_name, filePath, error) => {
+    await expect(readWorktreeFile(worktree, filePath)).rejects.toThrow(error);
+    await expect(writeWorktreeFile(worktree, filePath, 'x', null)).rejects.toThrow(error);
+  });
+
+  it('writes back a file that is unchanged since it was read', async () => {
+    const file = await readWorktreeFile(worktree, 'README.md');
+    const written = await writeWorktreeFile(worktree, 'README.md', 'edited\n', file.mtime_ms);
+    expect(written.size).toBe(7);
+    expect(fs.readFileSync(path.join(worktree, 'README.md'), 'utf8')).toBe('edited\n');
+  });
+
+  it('refuses to overwrite a file changed since it was read', async () => {
+    const file = await readWorktreeFile(worktree, 'README.md');
+    const readme = path.join(worktree, 'README.md');
+    fs.writeFileSync(readme, 'changed by the agent\n');
+    fs.utimesSync(readme, new Date(), new Date(file.mtime_ms + 5000));
+    await expect(writeWorktreeFile(worktree, 'README.md', 'x', file.mtime_ms)).rejects.toThrow(
+      /changed on disk/,
+    );
+    expect(fs.readFileSync(readme, 'utf8')).toBe('changed by the agent\n');
+  });
+
+  it('creates a file that does not exist yet', async () => {
+    await writeWorktreeFile(worktree, 'notes.md', 'new\n', 123);
+    expect(fs.readFileSync(path.join(worktree, 'notes.md'), 'utf8')).toBe('new\n');
+  });
+});
@@ -0,0 +1,113 @@
+import fs from 'fs';
+import path from 'path';
+
+// Direct file access for quick edits from the app. Every path must resolve
+// (after following symlinks) inside the worktree, and `.git` is off limits.
+
+const MAX_EDITABLE_SIZE = 5 * 1024 * 1024; // 5MB
+const BINARY_SNIFF_BYTES = 8000;
+
+export interface WorktreeFile {
+  /** null for binary files, which the editor can't show. */
+  content: string | null;
+  binary: boolean;
+  size: number;
+  /** Pass back to writeWorktreeFile to detect concurrent changes. */
+  mtime_ms: number;
+}
+
+function isInside(root: string, target: string): boolean {
+  const rel = path.relative(root, target);
+  return rel === '' || (!rel.startsWith('..') && !path.isAbsolute(rel));
+}
+
+function isUnderGitDir(root: string, target: string): boolean {
+  // Lowercased for case-insensitive file systems, where .GIT is the same directory
+  return path.relative(root, target).split(path.sep)[0].toLowerCase() === '.git';
+}
+
+/**
+ * Resolve `filePath` against the worktree, rejecting anything that escapes it.
+ * A file that doesn't exist yet is checked through its parent directory.
+ */
+async function resolveInWorktree(worktreePath: string, filePath: string): Promise<string> {
+  const root = await fs.promises.realpath(worktreePath);
+  const target = path.resolve(root, filePath);
+  if (!isInside(root, target) || target === root) {
+    throw new Error(`Path is outside the worktree: ${filePath}`);
+  }
+  if (isUnderGitDir(root, target)) throw new Error('Files under .git cannot be accessed');
+  let real: string;
+  try {
+    real = await fs.promises.realpath(target);
+  } catch {
+    real = path.join(await fs.promises.realpath(path.dirname(target)), path.basename(target));
+  }
+  if (!isInside(root, real)) throw new Error(`Path is outside the worktree: ${filePath}`);
+  // A symlink inside the worktree may still point into .git
+  if (isUnderGitDir(root, real)) throw new Error('Files under .git cannot be accessed');
+  return real;
+}
+
+export async function readWorktreeFile(
+  worktreePath: string,
+  filePath: string,
+): Promise<WorktreeFile> {
+  const fullPath = await resolveInWorktree(worktreePath, filePath);
+  const stat = await fs.promises.stat(fullPath);
+  if (!stat.isFile()) throw new Error(`Not a file: ${filePath}`);
+  if (stat.size > MAX_EDITABLE_SIZE) {
+    throw new Error(`File is too large to edit (${stat.size} bytes): ${filePath}`);
+  }
+  const buf = await fs.promises.readFile(fullPath);
+  const binary = buf.subarray(0, BINARY_SNIFF_BYTES).includes(0);
+  return {
+    content: binary ? null : buf.toString('utf8'),
+    binary,
+    size: stat.size,
+    mtime_ms: stat.mtimeMs,
+  };
+}
+
+/**
+ * Write a text file in the worktree. With `expectedMtimeMs`, refuse to
+ * overwrite a file that changed since it was read (e.g. by the agent).
+ *
+ * The check and the write go through one file descriptor, opened without
+ * following symlinks, so the path can't be swapped in between. A write by
+ * someone else between the check and ours is still lost; that window is a
+ * single write long.
+ */
+export async function writeWorktreeFile(
+  worktreePath: string,
+  filePath: string,
+  content: string,
+  expectedMtimeMs: number | null,
+): Promise<{ size: number; mtime_ms: number }> {
+  const fullPath = await resolveInWorktree(worktreePath, filePath);
+  const { O_WRONLY, O_CREAT, O_EXCL, O_NOFOLLOW = 0 } = fs.constants;
+  let handle: fs.promises.FileHandle;
+  let created = false;
+  try {
+    handle = await fs.promises.open(fullPath, O_WRONLY | O_NOFOLLOW);
+  } catch (e) {
+    if ((e as NodeJS.ErrnoException).code !== 'ENOENT') throw e;
+    handle = await fs.promises.open(fullPath, O_WRONLY | O_CREAT | O_EXCL | O_NOFOLLOW, 0o666);
+    created = true;
+  }
+  try {
+    if (expectedMtimeMs !== null && !created) {
+      const current = await handle.stat();
+      if (current.mtimeMs !== expectedMtimeMs) {
+        throw new Error(`${filePath} was changed on disk since it was opened`);
+      }
+    }
+    // In place rather than rename, to keep the file's mode and any hard links
+    await handle.truncate(0);
+    await handle.writeFile(content, 'utf8');
+    const stat = await handle.stat();
+    return { size: stat.size, mtime_ms: stat.mtimeMs };
+  } finally {
+    await handle.close();
+  }
+}
@@ -0,0 +1,87 @@
+import { createHash } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+import { getStateDir } from './persistence.js';
+
+/**
+ * Where a repository's task worktrees live. `repo` keeps the historical
+ * `<repo>/.worktrees`; the others use a per-repo subdirectory of app data or
+ * of a user-chosen directory, so several repos can share one location.
+ */
+export type WorktreeLocation =
+  | { mode: 'repo' }
+  | { mode: 'app_data' }
+  | { mode: 'custom'; path: string };
+
+type LocationFile = Record<string, WorktreeLocation>; // repo realpath -> location
+
+let cached: LocationFile | null = null;
+
+function getLocationsPath(): string {
+  return path.join(getStateDir(), 'worktree-locations.json');
+}
+
+function repoKey(repoRoot: string): string {
+  try {
+    return fs.realpathSync(repoRoot);
+  } catch {
+    return path.resolve(repoRoot);
+  }
+}
+
+function readLocations(): LocationFile {
+  if (cached) return cached;
+  try {
+    cached = JSON.parse(fs.readFileSync(getLocationsPath(), 'utf8')) as LocationFile;
+  } catch {
+    cached = {};
+  }
+  return cached;
+}
+
+export function validateWorktreeLocation(value: unknown): WorktreeLocation {
+  const loc = value as Partial<{ mode: string; path: unknown }> | null;
+  if (loc?.mode === 'repo' || loc?.mode === 'app_data') return { mode: loc.mode };
+  if (loc?.mode === 'custom') {
+    if (typeof loc.path !== 'string' || !path.isAbsolute(loc.path) || loc.path.includes('..')) {
+      throw new Error('custom worktree location must be an absolute path');
+    }
+    return { mode: 'custom', path: loc.path };
+  }
+  throw new Error("mode must be 'repo', 'app_data' or 'custom'");
+}
+
+export function hasWorktreeLocation(repoRoot: string): boolean {
+  return repoKey(repoRoot) in readLocations();
+}
+
+export function getWorktreeLocation(repoRoot: string): WorktreeLocation {
+  return readLocations()[repoKey(repoRoot)] ?? { mode: 'repo' };
+}
+
+export function saveWorktreeLocation(repoRoot: string, location: WorktreeLocation): void {
+  const locations = { ...readLocations() };
+  if (location.mode === 'repo') delete locations[repoKey(repoRoot)];
+  else locations[repoKey(repoRoot)] = location;
+  const locationsPath = getLocationsPath();
+  fs.mkdirSync(path.dirname(locationsPath), { recursive: true });
+  const tmpPath = locationsPath + '.tmp';
+  fs.writeFileSync(tmpPath, JSON.stringify(locations, null, 2), 'utf8');
+  fs.renameSync(tmpPath, locationsPath);
+  cached = locations;
+}
+
+/** Directory that holds the task worktrees for `repoRoot` under `location`. */
+export function resolveWorktreeRoot(repoRoot: string, location: WorktreeLocation): string {
+  if (location.mode === 'repo') return `${repoRoot}/.worktrees`;
+  // e.g. "myapp-1a2b3c4d": readable, but unique per repository path
+  const key = repoKey(repoRoot);
+  const hash = createHash('sha1').update(key).digest('hex').slice(0, 8);
+  const dirName = `${path.basename(key)}-${hash}`;
+  const base = location.mode === 'app_data' ? path.join(getStateDir(), 'worktrees') : location.path;
+  return path.join(base, dirName);
+}
+
+export function getWorktreeRoot(repoRoot: string): string {
+  return resolveWorktreeRoot(repoRoot, getWorktreeLocation(repoRoot));
+}
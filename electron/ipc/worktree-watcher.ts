@@ -0,0 +1,205 @@
+import fs from 'fs';
+import path from 'path';
+import type { BrowserWindow } from 'electron';
+import { IPC } from './channels.js';
+import { getChangedFiles } from './git.js';
+import { subscribe } from './watcher.js';
+
+const DEBOUNCE_MS = 300;
+// Safety net for changes the watcher can't see (e.g. a ref updated through a packed-refs rewrite)
+const FALLBACK_REFRESH_MS = 30_000;
+
+/** Files in the git dir that change when commits, staging or checkouts happen. */
+const GIT_STATE_FILES = new Set(['HEAD', 'index', 'ORIG_HEAD']);
+
+type ChangedFile = Awaited<ReturnType<typeof getChangedFiles>>[number];
+
+interface WorktreeWatch {
+  worktreePath: string;
+  unsubscribes: (() => void)[];
+  /** Number of open watch_worktree_changes calls for this task. */
+  refs: number;
+  files: Map<string, ChangedFile>;
+  fallbackTimer: ReturnType<typeof setInterval>;
+  refreshing: boolean;
+  dirty: boolean;
+}
+
+const watches = new Map<string, WorktreeWatch>();
+
+function sameFile(a: ChangedFile, b: ChangedFile): boolean {
+  return (
+    a.status === b.status &&
+    a.lines_added === b.lines_added &&
+    a.lines_removed === b.lines_removed &&
+    a.committed === b.committed
+  );
+}
+
+/** Paths inside the worktree whose changes never affect `git status`. */
+function isIgnoredPath(rel: string): boolean {
+  const first = rel.split(/[\\/]/)[0];
+  return first === '.git' || first === 'node_modules';
+}
+
+/** The per-worktree git dir (`.git` is a file pointing at it for linked worktrees). */
+function resolveGitDir(worktreePath: string): string | null {
+  const dotGit = path.join(worktreePath, '.git');
+  try {
+    if (fs.statSync(dotGit).isDirectory()) return dotGit;
+    const m = /^gitdir:\s*(.+)$/m.exec(fs.readFileSync(dotGit, 'utf8'));
+    return m ? path.resolve(worktreePath, m[1].trim()) : null;
+  } catch {
+    return null;
+  }
+}
+
+async function refresh(win: BrowserWindow, taskId: string, w: WorktreeWatch): Promise<void> {
+  // Coalesce bursts: one refresh in flight, at most one queued behind it
+  if (w.refreshing) {
+    w.dirty = true;
+    return;
+  }
+  w.refreshing = true;
+  try {
+    const next = await getChangedFiles(w.worktreePath);
+    if (watches.get(taskId) !== w) return;
+
+    const changed: ChangedFile[] = [];
+    const removed: string[] = [];
+    const nextMap = new Map(next.map((f) => [f.path, f]));
+    for (const f of next) {
+      const prev = w.files.get(f.path);
+      if (!prev || !sameFile(prev, f)) changed.push(f);
+    }
+    for (const p of w.files.keys()) {
+      if (!nextMap.has(p)) removed.push(p);
+    }
+    w.files = nextMap;
+
+    if ((changed.length > 0 || removed.length > 0) && !win.isDestroyed()) {
+      win.webContents.send(IPC.ChangedFilesUpdated, {
+        task_id: taskId,
+        worktree_path: w.worktreePath,
+        changed,
+        removed,
+      });
+    }
+  } catch {
+    // Worktree may have been removed — the next event or fallback tick retries
+  } finally {
+    w.refreshing = false;
+    if (w.dirty && watches.get(taskId) === w) {
+      w.dirty = false;
+      void refresh(win, taskId, w);
+    }
+  }
+}
+
+function closeWatch(w: WorktreeWatch): void {
+  for (const unsubscribe of w.unsubscribes) unsubscribe();
+  clearInterval(w.fallbackTimer);
+}
+
+/**
+ * Start watching a task's worktree. Returns the current changed-file list;
+ * afterwards only deltas are sent as `changed-files-updated` events.
+ * Calls are reference-counted per task.
+ */
+export async function watchWorktreeChanges(
+  win: BrowserWindow,
+  taskId: string,
+  worktreePath: string,
+): Promise<ChangedFile[]> {
+  const existing = watches.get(taskId);
+  if (existing && existing.worktreePath === worktreePath) {
+    existing.refs++;
+    return [...existing.files.values()];
+  }
+  if (existing) {
+    closeWatch(existing);
+    watches.delete(taskId);
+  }
+
+  const files = await getChangedFiles(worktreePath);
+  // A concurrent call may have set up the watch while git was running
+  const raced = watches.get(taskId);
+  if (raced && raced.worktreePath === worktreePath) {
+    raced.refs++;
+    return [...raced.files.values()];
+  }
+
+  const w: WorktreeWatch = {
+    worktreePath,
+    unsubscribes: [],
+    refs: 1,
+    files: new Map(files.map((f) => [f.path, f])),
+    fallbackTimer: setInterval(() => void refresh(win, taskId, w), FALLBACK_REFRESH_MS),
+    refreshing: false,
+    dirty: false,
+  };
+
+  const onChange = () => void refresh(win, taskId, w);
+  try {
+    w.unsubscribes.push(
+      subscribe(
+        worktreePath,
+        {
+          recursive: true,
+          debounceMs: DEBOUNCE_MS,
+          filter: (rel) => !isIgnoredPath(rel),
+          onRescan: onChange,
+        },
+        onChange,
+      ),
+    );
+  } catch (e) {
+    closeWatch(w);
+    throw e;
+  }
+  // Commits, staging and checkouts only touch the git dir
+  const gitDir = resolveGitDir(worktreePath);
+  if (gitDir) {
+    try {
+      w.unsubscribes.push(
+        subscribe(
+          gitDir,
+          { debounceMs: DEBOUNCE_MS, filter: (name) => GIT_STATE_FILES.has(name) },
+          onChange,
+        ),
+      );
+    } catch {
+      /* fall back to the periodic refresh */
+    }
+  }
+
+  const previous = watches.get(taskId);
+  if (previous) closeWatch(previous);
+  watches.set(taskId, w);
+  return files;
+}
+
+/** Release one watch_worktree_changes reference; the watcher stops at zero. */
+export function unwatchWorktreeChanges(taskId: string): void {
+  const w = watches.get(taskId);
+  if (!w) return;
+  if (--w.refs > 0) return;
+  closeWatch(w);
+  watches.delete(taskId);
+}
+
+/** Stop a task's watch whatever its reference count, e.g. when the task is deleted. */
+export function stopWorktreeWatch(taskId: string): void {
+  const w = watches.get(taskId);
+  if (!w) return;
+  closeWatch(w);
+  watches.delete(taskId);
+}
+
+export function listWorktreeWatches(): { task_id: string; worktree_path: string; refs: number }[] {
+  return [...watches].map(([taskId, w]) => ({
+    task_id: taskId,
+    worktree_path: w.worktreePath,
+    refs: w.refs,
+  }));
+}
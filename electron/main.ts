@@ -2,45 +2,19 @@ import { app, BrowserWindow, shell } from 'electron';
 import path from 'path';
 import fs from 'fs';
 import { fileURLToPath } from 'url';
-import { execFileSync } from 'child_process';
 import { registerAllHandlers } from './ipc/register.js';
-import { killAllAgents } from './ipc/pty.js';
+import { installLogCapture } from './ipc/logs.js';
+import { loadLoginPath } from './ipc/shell-env.js';
+import { shutdownAgents } from './ipc/pty.js';
 import { IPC } from './ipc/channels.js';
 
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = path.dirname(__filename);
 
-// When launched from a .desktop file, PATH is minimal (/usr/bin:/bin).
-// Resolve the user's full login-interactive shell PATH so spawned PTYs
-// can find CLI tools like claude, codex, gemini, etc.
-//
-// Uses -ilc (interactive + login) to source both .zprofile/.profile AND
-// .zshrc/.bashrc, where version managers (nvm, volta, fnm) add to PATH.
-// Sentinel markers isolate PATH from noisy shell init output.
-//
-// Trade-off: -i (interactive) triggers .zshrc side effects (compinit, conda,
-// welcome messages). Login-only (-lc) would be quieter but would miss tools
-// that are only added to PATH in .bashrc/.zshrc (e.g. nvm). We accept the
-// side effects since the sentinel-based parsing discards all other output.
-function fixPath(): void {
-  if (process.platform === 'win32') return;
-  try {
-    const loginShell = process.env.SHELL || '/bin/sh';
-    const sentinel = '__PCODE_PATH__';
-    const result = execFileSync(loginShell, ['-ilc', `printf "${sentinel}%s${sentinel}" "$PATH"`], {
-      encoding: 'utf8',
-      timeout: 5000,
-    });
-    const match = result.match(new RegExp(`${sentinel}(.+?)${sentinel}`));
-    if (match?.[1]) {
-      process.env.PATH = match[1];
-    }
-  } catch {
-    // Keep existing PATH if shell invocation fails
-  }
-}
+installLogCapture();
 
-fixPath();
+// Agents need the user's full shell PATH; see shell-env.ts
+loadLoginPath();
 
 // Verify that preload.cjs ALLOWED_CHANNELS stays in sync with the IPC enum.
 // Logs a warning in dev if they drift — catches mismatches before they hit users.
@@ -140,7 +114,7 @@ function createWindow() {
 app.whenReady().then(createWindow);
 
 app.on('before-quit', () => {
-  killAllAgents();
+  shutdownAgents();
 });
 
 app.on('window-all-closed', () => {
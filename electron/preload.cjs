@@ -8,15 +8,111 @@ const ALLOWED_CHANNELS = new Set([
   'spawn_agent',
   'write_to_agent',
   'resize_agent',
+  'pause_agent_output',
+  'resume_agent_output',
   'pause_agent',
   'resume_agent',
   'kill_agent',
   'count_running_agents',
   'kill_all_agents',
   'list_agents',
+  'get_agent_scrollback',
+  'list_detached_sessions',
+  'reattach_agent',
+  'get_agent_stats',
+  'agent-stats',
+  'list_recordings',
+  'export_recording',
+  'agent-idle',
+  'subscribe_output_pattern',
+  'unsubscribe_output_pattern',
+  'pattern-matched',
+  'set_agent_limit',
+  'get_spawn_queue',
+  'agent-queued',
+  'agent-dequeued',
+  'write_bytes_to_agent',
+  'restart_agent',
+  'get_agent_env',
+  'set_output_throttle',
+  'ack_agent_output',
+  'backpressure',
+  'attach_output_channel',
+  'detach_output_channel',
+  'send_key',
+  'agent-exited',
+  'get_spawn_policy',
+  'set_spawn_policy',
+  'get_agent_cwd',
+  'agent-cwd-changed',
+  'agent-title-changed',
+  'agent-notification',
+  'agent-timeout',
+  'list_running_agents',
+  'tune_agent_output',
+  'add_agent_def',
+  'update_agent_def',
+  'delete_agent_def',
+  'add_agent_profile',
+  'update_agent_profile',
+  'delete_agent_profile',
+  'run_agent_headless',
+  'cancel_headless_run',
+  'agent-event',
+  'respond_to_permission',
+  'agent-permission-request',
+  'agent-permission-resolved',
+  'refresh_login_path',
+  'list_env_profiles',
+  'save_env_profile',
+  'delete_env_profile',
   // Task
   'create_task',
   'delete_task',
+  'list_task_templates',
+  'save_task_template',
+  'delete_task_template',
+  'create_task_from_template',
+  'set_task_dependencies',
+  'get_task_dependencies',
+  'task-unblocked',
+  'archive_task',
+  'list_archived_tasks',
+  'restore_task',
+  'get_task_statuses',
+  'set_task_status',
+  'task-status-changed',
+  'suggest_task_name',
+  'get_task_metrics',
+  'get_metrics_report',
+  'update_task_metadata',
+  'query_tasks',
+  'list_task_labels',
+  'fork_task',
+  'get_task_notes',
+  'update_task_notes',
+  'list_task_agents',
+  'start_review_agent',
+  'review-completed',
+  'handoff_task',
+  'query_task_history',
+  'list_task_sessions',
+  'query_events',
+  'watch_path',
+  'unwatch',
+  'path-changed',
+  'watch_for_plans',
+  'unwatch_plans',
+  'plan-changed',
+  'get_plan_history',
+  'get_plan_diff',
+  'write_plan_file',
+  'parse_plan',
+  'toggle_plan_item',
+  'approve_plan',
+  'request_plan_changes',
+  'get_plan_approvals',
+  'plan-approval-changed',
   // Git
   'get_changed_files',
   'get_file_diff',
@@ -29,9 +125,76 @@ const ALLOWED_CHANNELS = new Set([
   'rebase_task',
   'get_main_branch',
   'get_current_branch',
+  'create_task_worktree',
+  'remove_task_worktree',
+  'list_worktrees',
+  'prune_worktrees',
+  'resolve_conflict',
+  'continue_merge',
+  'abort_merge',
+  'commit_task',
+  'get_file_hunks',
+  'stage_hunk',
+  'unstage_hunk',
+  'discard_file_changes',
+  'discard_all_changes',
+  'stash_changes',
+  'list_stashes',
+  'apply_stash',
+  'drop_stash',
+  'get_file_diff_between',
+  'get_task_diff_vs_base',
+  'watch_worktree_changes',
+  'unwatch_worktree_changes',
+  'changed-files-updated',
+  'preview_merge',
+  'get_rebase_plan',
+  'execute_rebase_plan',
+  'get_commit',
+  'get_commit_file_diff',
+  'fetch_and_compare',
+  'set_background_fetch',
+  'branch-diverged',
+  'get_worktrees_disk_usage',
+  'cleanup_stale_worktrees',
+  'get_worktree_location',
+  'set_worktree_location',
+  'suggest_branch_name',
+  'get_main_branch_info',
+  'set_task_base_branch',
+  'get_run_git_hooks',
+  'set_run_git_hooks',
+  'git-hook-output',
+  'get_file_blame',
+  'search_worktree',
+  'cancel_search',
+  'search-results',
+  'list_worktree_tree',
+  'read_worktree_file',
+  'write_worktree_file',
+  'get_linked_changed_files',
+  'merge_linked_tasks',
+  'get_worktree_copy_files',
+  'set_worktree_copy_files',
+  'get_worktree_setup_command',
+  'set_worktree_setup_command',
+  'get_signing_config',
+  'get_credential_status',
+  'answer_askpass',
+  'askpass-prompt',
+  'askpass-cancelled',
+  'continue_worktree_operation',
+  'abort_worktree_operation',
   // Persistence
   'save_app_state',
   'load_app_state',
+  'get_settings',
+  'update_settings',
+  'settings-changed',
+  'list_state_backups',
+  'restore_state_backup',
+  'export_workspace',
+  'import_workspace',
   // Window
   '__window_is_focused',
   '__window_is_maximized',
@@ -51,15 +214,53 @@ const ALLOWED_CHANNELS = new Set([
   '__window_resized',
   '__window_moved',
   '__window_close_requested',
+  'get_notification_settings',
+  'set_notification_settings',
+  'test_notification',
+  'notification-clicked',
   // Dialog
   '__dialog_confirm',
   '__dialog_open',
+  '__dialog_save',
   // Shell
   '__shell_reveal',
   // Remote access
   'start_remote_server',
   'stop_remote_server',
   'get_remote_status',
+  // Integrations
+  'create_pull_request',
+  'set_integration_token',
+  'has_integration_token',
+  'get_pull_request_status',
+  'pr-status-changed',
+  'open_in_editor',
+  'detect_editors',
+  'get_editor_settings',
+  'set_editor_settings',
+  'set_secret',
+  'get_secret',
+  'delete_secret',
+  'list_secrets',
+  // Projects
+  'add_project',
+  'list_projects',
+  'remove_project',
+  'list_mcp_servers',
+  'add_mcp_server',
+  'toggle_mcp_server',
+  'get_project_env_profile',
+  'set_project_env_profile',
+  // Prompts
+  'list_prompt_snippets',
+  'save_prompt_snippet',
+  'delete_prompt_snippet',
+  'send_prompt',
+  // Diagnostics
+  'get_backend_logs',
+  'get_log_level',
+  'set_log_level',
+  'list_active_watchers',
 ]);
 
 function isAllowedChannel(channel) {
@@ -1,3 +1,5 @@
+import { leadingUtf8Continuation } from './utf8.js';
+
 /** Fixed-capacity ring buffer for terminal scrollback replay. */
 export class RingBuffer {
   private buf: Buffer;
@@ -30,10 +32,15 @@ export class RingBuffer {
     if (!this.full && this.pos < data.length) this.full = true;
   }
 
-  /** Read all buffered data in chronological order (returns a copy). */
+  /**
+   * Read all buffered data in chronological order (returns a copy). Once the
+   * buffer has wrapped, the oldest bytes may be the tail of a multi-byte
+   * UTF-8 sequence; those are dropped.
+   */
   read(): Buffer {
     if (!this.full) return Buffer.from(this.buf.subarray(0, this.pos));
-    return Buffer.concat([this.buf.subarray(this.pos), this.buf.subarray(0, this.pos)]);
+    const data = Buffer.concat([this.buf.subarray(this.pos), this.buf.subarray(0, this.pos)]);
+    return data.subarray(leadingUtf8Continuation(data));
   }
 
   /** Return buffered data as a base64 string. */
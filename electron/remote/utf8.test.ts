@@ -0,0 +1,99 @@
+import { describe, it, expect } from 'vitest';
+import { incompleteUtf8Tail, leadingUtf8Continuation } from './utf8.js';
+import { RingBuffer } from './ring-buffer.js';
+
+const bytes = (...b: number[]) => Buffer.from(b);
+
+// ---------------------------------------------------------------------------
+// incompleteUtf8Tail
+// ---------------------------------------------------------------------------
+describe('incompleteUtf8Tail', () => {
+  it.each([
+    ['empty input', bytes(), 0],
+    ['ASCII', Buffer.from('abc'), 0],
+    ['a complete 2-byte sequence', Buffer.from('é'), 0],
+    ['a complete 4-byte sequence', Buffer.from('a😀'), 0],
+    ['a lone 2-byte lead', bytes(0x61, 0xc3), 1],
+    ['1 of 3 bytes', bytes(0x61, 0xe2), 1],
+    ['2 of 3 bytes', bytes(0xe2, 0x82), 2],
+    ['3 of 4 bytes', bytes(0x61, 0xf0, 0x9f, 0x98), 3],
+    ['stray continuation bytes', bytes(0x80, 0x80, 0x80, 0x80), 0],
+  ])('reports %s', (_name, buf, expected) => {
+    expect(incompleteUtf8Tail(buf)).toBe(expected);
+  });
+
+  it('splits every prefix of a multi-byte string where it can be decoded', () => {
+    const text = 'añ€😀b';
+    const buf = Buffer.from(text);
+    for (let end = 0; end <= buf.length; end++) {
+      const cut = end - incompleteUtf8Tail(buf.subarray(0, end));
+      const head = buf.subarray(0, cut).toString('utf8');
+      expect(head).not.toContain('\ufffd');
+      expect(text.startsWith(head)).toBe(true);
+    }
+  });
+});
+
+// ---------------------------------------------------------------------------
+// leadingUtf8Continuation
+// ---------------------------------------------------------------------------
+describe('leadingUtf8Continuation', () => {
+  it.each([
+    ['empty input', bytes(), 0],
+    ['ASCII', Buffer.from('abc'), 0],
+    ['a lead byte', Buffer.from('€'), 0],
+    ['the tail of a 3-byte sequence', bytes(0x82, 0xac, 0x61), 2],
+    ['more than 3 continuation bytes', bytes(0x80, 0x80, 0x80, 0x80), 3],
+  ])('counts %s', (_name, buf, expected) => {
+    expect(leadingUtf8Continuation(buf)).toBe(expected);
+  });
+});
+
+// ---------------------------------------------------------------------------
+// RingBuffer
+// ---------------------------------------------------------------------------
+describe('RingBuffer', () => {
+  it('returns what was written before it fills up', () => {
+    const ring = new RingBuffer(8);
+    ring.write(Buffer.from('abc'));
+    ring.write(Buffer.from('de'));
+    expect(ring.read().toString()).toBe('abcde');
+    expect(ring.length).toBe(5);
+  });
+
+  it.each([
+    ['a write that wraps', ['abcdef', 'ghij'], 'cdefghij'],
+    ['a write that exactly fills it', ['abcdefgh'], 'abcdefgh'],
+    ['a write larger than the capacity', ['0123456789'], '23456789'],
+    ['many small writes', ['ab', 'cd', 'ef', 'gh', 'ij', 'k'], 'defghijk'],
+  ])('keeps the newest bytes after %s', (_name, writes, expected) => {
+    const ring = new RingBuffer(8);
+    for (const w of writes) ring.write(Buffer.from(w));
+    expect(ring.read().toString()).toBe(expected);
+    expect(ring.length).toBe(8);
+  });
+
+  it('drops a multi-byte sequence cut by the wrap point', () => {
+    const ring = new RingBuffer(8);
+    // '€' is 3 bytes; only its last two survive the overwrite
+    ring.write(Buffer.from('ab€'));
+    ring.write(Buffer.from('cdefgh'));
+    expect(ring.read().toString()).toBe('cdefgh');
+  });
+
+  it('keeps a multi-byte sequence that starts at the oldest byte', () => {
+    const ring = new RingBuffer(8);
+    ring.write(Buffer.from('abc'));
+    ring.write(Buffer.from('€defgh'));
+    expect(ring.read().toString()).toBe('€defgh');
+  });
+
+  it('starts over after clear', () => {
+    const ring = new RingBuffer(4);
+    ring.write(Buffer.from('abcdef'));
+    ring.clear();
+    ring.write(Buffer.from('x'));
+    expect(ring.read().toString()).toBe('x');
+    expect(ring.toBase64()).toBe(Buffer.from('x').toString('base64'));
+  });
+});
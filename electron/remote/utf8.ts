@@ -0,0 +1,24 @@
+// Helpers for cutting byte streams without splitting multi-byte UTF-8
+// sequences, which renderers would otherwise show as U+FFFD.
+
+/** Length of an incomplete UTF-8 sequence at the end of `buf` (0 if none). */
+export function incompleteUtf8Tail(buf: Buffer): number {
+  // A sequence is at most 4 bytes, so only the last 3 can be a partial one
+  for (let i = 1; i <= Math.min(3, buf.length); i++) {
+    const byte = buf[buf.length - i];
+    if ((byte & 0xc0) === 0x80) continue; // continuation byte
+    let need = 1;
+    if ((byte & 0xe0) === 0xc0) need = 2;
+    else if ((byte & 0xf0) === 0xe0) need = 3;
+    else if ((byte & 0xf8) === 0xf0) need = 4;
+    return need > i ? i : 0;
+  }
+  return 0;
+}
+
+/** Number of stray continuation bytes at the start of `buf` (at most 3). */
+export function leadingUtf8Continuation(buf: Buffer): number {
+  let n = 0;
+  while (n < Math.min(3, buf.length) && (buf[n] & 0xc0) === 0x80) n++;
+  return n;
+}
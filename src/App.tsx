@@ -10,12 +10,17 @@ import { TilingLayout } from './components/TilingLayout';
 import { NewTaskDialog } from './components/NewTaskDialog';
 import { HelpDialog } from './components/HelpDialog';
 import { SettingsDialog } from './components/SettingsDialog';
+import { AskpassDialog } from './components/AskpassDialog';
+import { PermissionRequests } from './components/PermissionRequests';
 import { WindowTitleBar } from './components/WindowTitleBar';
 import { WindowResizeHandles } from './components/WindowResizeHandles';
 import { theme } from './lib/theme';
 import {
   store,
   loadAgents,
+  loadPromptSnippets,
+  loadSettings,
+  listenForSettingsChanges,
   loadState,
   saveState,
   toggleNewTaskDialog,
@@ -27,6 +32,12 @@ import {
   resetFontScale,
   startTaskStatusPolling,
   stopTaskStatusPolling,
+  listenForUnblockedTasks,
+  listenForTaskStatusChanges,
+  listenForReviews,
+  listenForNotificationClicks,
+  loadTaskMetadata,
+  loadTaskNotes,
   navigateRow,
   navigateColumn,
   setPendingAction,
@@ -278,10 +289,19 @@ function App() {
 
     await loadAgents();
     await loadState();
+    await loadSettings().catch((err) => console.error('Failed to load settings:', err));
     await restoreWindowState();
     await captureWindowState();
     setupAutosave();
     startTaskStatusPolling();
+    const unlistenUnblocked = listenForUnblockedTasks();
+    const unlistenTaskStatus = listenForTaskStatusChanges();
+    const unlistenReviews = listenForReviews();
+    const unlistenNotificationClicks = listenForNotificationClicks();
+    const unlistenSettings = listenForSettingsChanges();
+    loadTaskMetadata().catch((err) => console.error('Failed to load task metadata:', err));
+    loadTaskNotes().catch((err) => console.error('Failed to load task notes:', err));
+    loadPromptSnippets().catch((err) => console.error('Failed to load prompt library:', err));
 
     const handlePaste = (e: ClipboardEvent) => {
       if (store.showNewTaskDialog || store.showHelpDialog || store.showSettingsDialog) return;
@@ -535,6 +555,11 @@ function App() {
       unlistenCloseRequested();
       cleanupShortcuts();
       stopTaskStatusPolling();
+      unlistenUnblocked();
+      unlistenTaskStatus();
+      unlistenReviews();
+      unlistenNotificationClicks();
+      unlistenSettings();
       unlistenFocusChanged?.();
       unlistenResized?.();
       unlistenMoved?.();
@@ -669,6 +694,8 @@ function App() {
           open={store.showSettingsDialog}
           onClose={() => toggleSettingsDialog(false)}
         />
+        <AskpassDialog />
+        <PermissionRequests />
         <Show when={showDropOverlay()}>
           <DropOverlay />
         </Show>
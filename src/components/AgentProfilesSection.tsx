@@ -0,0 +1,200 @@
+import { For, Show, createSignal } from 'solid-js';
+import { theme } from '../lib/theme';
+import { parseEnvLines } from '../lib/agent-args';
+import { store, saveAgentProfile, deleteAgentProfile } from '../store/store';
+import { inputStyle, primaryButton, secondaryButton, splitArgs } from './CustomAgentsSection';
+import type { AgentProfile } from '../ipc/types';
+
+/** Settings section for named flag sets (model, permission mode...) per agent. */
+export function AgentProfilesSection() {
+  // null: form closed; otherwise the agent and, when editing, the profile id
+  const [editing, setEditing] = createSignal<{ agentId: string; profileId?: string } | null>(null);
+  const [id, setId] = createSignal('');
+  const [name, setName] = createSignal('');
+  const [args, setArgs] = createSignal('');
+  const [env, setEnv] = createSignal('');
+  const [error, setError] = createSignal('');
+
+  function openForm(forAgent: string, profile?: AgentProfile) {
+    setEditing({ agentId: forAgent, profileId: profile?.id });
+    setId(profile?.id ?? '');
+    setName(profile?.name ?? '');
+    setArgs(profile?.args.join(' ') ?? '');
+    setEnv(
+      Object.entries(profile?.env ?? {})
+        .map(([k, v]) => `${k}=${v}`)
+        .join('\n'),
+    );
+    setError('');
+  }
+
+  async function save() {
+    const target = editing();
+    if (!target) return;
+    setError('');
+    try {
+      const profile: AgentProfile = {
+        id: id().trim(),
+        name: name(),
+        args: splitArgs(args()),
+        env: parseEnvLines(env()),
+      };
+      await saveAgentProfile(target.agentId, profile, target.profileId);
+      setEditing(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        Agent Profiles
+      </div>
+      <For each={store.availableAgents}>
+        {(agent) => (
+          <div
+            style={{
+              display: 'flex',
+              'flex-direction': 'column',
+              gap: '6px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+              <span style={{ 'font-size': '13px', color: theme.fg, flex: '1' }}>{agent.name}</span>
+              <button
+                type="button"
+                class="btn-secondary"
+                style={secondaryButton}
+                onClick={() => openForm(agent.id)}
+              >
+                Add profile
+              </button>
+            </div>
+            <For each={agent.profiles ?? []}>
+              {(profile) => (
+                <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+                  <span style={{ 'font-size': '12px', color: theme.fg, flex: '1' }}>
+                    {profile.name}
+                    <span
+                      style={{ 'font-size': '11px', color: theme.fgSubtle, 'margin-left': '8px' }}
+                    >
+                      {profile.args.join(' ')}
+                    </span>
+                  </span>
+                  <Show when={!profile.builtin}>
+                    <button
+                      type="button"
+                      class="btn-secondary"
+                      style={secondaryButton}
+                      onClick={() => openForm(agent.id, profile)}
+                    >
+                      Edit
+                    </button>
+                    <button
+                      type="button"
+                      class="btn-secondary"
+                      style={secondaryButton}
+                      onClick={() =>
+                        deleteAgentProfile(agent.id, profile.id).catch((err) =>
+                          setError(String(err)),
+                        )
+                      }
+                    >
+                      Delete
+                    </button>
+                  </Show>
+                </div>
+              )}
+            </For>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <Show when={editing()}>
+        <div
+          style={{
+            display: 'grid',
+            'grid-template-columns': '1fr 1fr',
+            gap: '8px',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <label style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>Id</span>
+            <input
+              style={inputStyle}
+              value={id()}
+              disabled={!!editing()?.profileId}
+              placeholder="opus"
+              onInput={(e) => setId(e.currentTarget.value)}
+            />
+          </label>
+          <label style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>Name</span>
+            <input
+              style={inputStyle}
+              value={name()}
+              placeholder="Opus model"
+              onInput={(e) => setName(e.currentTarget.value)}
+            />
+          </label>
+          <label style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>Extra arguments</span>
+            <input
+              style={inputStyle}
+              value={args()}
+              placeholder="--model opus"
+              onInput={(e) => setArgs(e.currentTarget.value)}
+            />
+          </label>
+          <label style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+              Environment (KEY=VALUE per line)
+            </span>
+            <textarea
+              style={{ ...inputStyle, resize: 'vertical' }}
+              rows={2}
+              value={env()}
+              onInput={(e) => setEnv(e.currentTarget.value)}
+            />
+          </label>
+          <div style={{ 'grid-column': '1 / -1', display: 'flex', gap: '8px' }}>
+            <button
+              type="button"
+              class="btn-primary"
+              style={primaryButton}
+              onClick={() => void save()}
+            >
+              {editing()?.profileId ? 'Save' : 'Add'}
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => setEditing(null)}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </Show>
+    </div>
+  );
+}
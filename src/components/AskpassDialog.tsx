@@ -0,0 +1,83 @@
+import { createEffect, createSignal, onCleanup, onMount } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { ConfirmDialog } from './ConfirmDialog';
+import { theme } from '../lib/theme';
+import type { AskpassPrompt } from '../ipc/types';
+
+/** Answers credential prompts from git and ssh (usernames, passwords, passphrases). */
+export function AskpassDialog() {
+  const [queue, setQueue] = createSignal<AskpassPrompt[]>([]);
+  const [answer, setAnswer] = createSignal('');
+  let inputRef: HTMLInputElement | undefined;
+
+  const current = () => queue()[0];
+
+  onMount(() => {
+    const offPrompt = window.electron.ipcRenderer.on(IPC.AskpassPrompt, (msg: unknown) => {
+      setQueue((q) => [...q, msg as AskpassPrompt]);
+    });
+    const offCancelled = window.electron.ipcRenderer.on(IPC.AskpassCancelled, (msg: unknown) => {
+      const { id } = msg as { id: string };
+      setQueue((q) => q.filter((p) => p.id !== id));
+    });
+    onCleanup(() => {
+      offPrompt();
+      offCancelled();
+    });
+  });
+
+  // Fresh, focused input for each prompt
+  createEffect(() => {
+    if (!current()) return;
+    setAnswer('');
+    requestAnimationFrame(() => inputRef?.focus());
+  });
+
+  function respond(value: string | null) {
+    const prompt = current();
+    if (!prompt) return;
+    setQueue((q) => q.slice(1));
+    invoke(IPC.AnswerAskpass, { id: prompt.id, answer: value }).catch((err) =>
+      console.error('Failed to answer credential prompt:', err),
+    );
+  }
+
+  return (
+    <ConfirmDialog
+      open={current() !== undefined}
+      title="Credentials required"
+      autoFocusCancel={false}
+      message={
+        <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+          <div style={{ 'white-space': 'pre-wrap', 'word-break': 'break-word' }}>
+            {current()?.prompt}
+          </div>
+          <input
+            ref={inputRef}
+            class="input-field"
+            type={current()?.secret ? 'password' : 'text'}
+            value={answer()}
+            onInput={(e) => setAnswer(e.currentTarget.value)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') respond(answer());
+            }}
+            autocomplete="off"
+            style={{
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+              'border-radius': '8px',
+              padding: '10px 14px',
+              color: theme.fg,
+              'font-size': '13px',
+              outline: 'none',
+            }}
+          />
+        </div>
+      }
+      confirmLabel="Continue"
+      onConfirm={() => respond(answer())}
+      onCancel={() => respond(null)}
+    />
+  );
+}
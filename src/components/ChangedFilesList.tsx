@@ -4,9 +4,10 @@ import { IPC } from '../../electron/ipc/channels';
 import { theme } from '../lib/theme';
 import { sf } from '../lib/fontScale';
 import { getStatusColor } from '../lib/status-colors';
-import type { ChangedFile } from '../ipc/types';
+import type { ChangedFile, ChangedFilesUpdatedEvent } from '../ipc/types';
 
 interface ChangedFilesListProps {
+  taskId: string;
   worktreePath: string;
   isActive?: boolean;
   onFileClick?: (file: ChangedFile) => void;
@@ -36,33 +37,39 @@ export function ChangedFilesList(props: ChangedFilesListProps) {
     }
   }
 
-  // Poll every 5s, matching the git status polling interval
+  // Seed from the watcher, then apply the deltas it pushes
   createEffect(() => {
+    const taskId = props.taskId;
     const path = props.worktreePath;
-    if (!props.isActive) return;
+    if (!props.isActive || !path) return;
     let cancelled = false;
-    let inFlight = false;
 
-    async function refresh() {
-      if (!path || inFlight) return;
-      inFlight = true;
-      try {
-        const result = await invoke<ChangedFile[]>(IPC.GetChangedFiles, {
-          worktreePath: path,
-        });
+    const off = window.electron.ipcRenderer.on(IPC.ChangedFilesUpdated, (msg: unknown) => {
+      const update = msg as ChangedFilesUpdatedEvent;
+      if (cancelled || update.task_id !== taskId) return;
+      const byPath = new Map(files().map((f) => [f.path, f]));
+      for (const p of update.removed) byPath.delete(p);
+      for (const f of update.changed) byPath.set(f.path, f);
+      setFiles(
+        [...byPath.values()].sort((a, b) => {
+          if (a.committed !== b.committed) return a.committed ? -1 : 1;
+          return a.path.localeCompare(b.path);
+        }),
+      );
+    });
+
+    invoke<ChangedFile[]>(IPC.WatchWorktreeChanges, { taskId, worktreePath: path })
+      .then((result) => {
         if (!cancelled) setFiles(result);
-      } catch {
+      })
+      .catch(() => {
         // Silently ignore — worktree may not exist yet
-      } finally {
-        inFlight = false;
-      }
-    }
+      });
 
-    refresh();
-    const timer = setInterval(refresh, 5000);
     onCleanup(() => {
       cancelled = true;
-      clearInterval(timer);
+      off();
+      invoke(IPC.UnwatchWorktreeChanges, { taskId }).catch(() => {});
     });
   });
 
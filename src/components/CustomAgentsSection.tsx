@@ -0,0 +1,284 @@
+import { For, Show, createSignal, type JSX } from 'solid-js';
+import { theme } from '../lib/theme';
+import { parseEnvLines } from '../lib/agent-args';
+import { store, saveCustomAgent, deleteCustomAgent } from '../store/store';
+import type { AgentDef } from '../ipc/types';
+
+export const inputStyle = {
+  width: '100%',
+  background: theme.bgInput,
+  border: `1px solid ${theme.border}`,
+  'border-radius': '6px',
+  padding: '6px 8px',
+  color: theme.fg,
+  'font-size': '12px',
+  'font-family': "'JetBrains Mono', monospace",
+  outline: 'none',
+  'box-sizing': 'border-box',
+} as const;
+
+export const secondaryButton = {
+  padding: '5px 12px',
+  background: theme.bgInput,
+  border: `1px solid ${theme.border}`,
+  'border-radius': '6px',
+  color: theme.fgMuted,
+  cursor: 'pointer',
+  'font-size': '12px',
+} as const;
+
+export const primaryButton = {
+  ...secondaryButton,
+  background: theme.accent,
+  border: 'none',
+  color: theme.accentText,
+  'font-weight': '500',
+} as const;
+
+export const splitArgs = (text: string) => text.split(/\s+/).filter(Boolean);
+
+/** Settings section for registering agent CLIs beyond the built-in ones. */
+export function CustomAgentsSection() {
+  // null: form closed; '': adding; otherwise the id being edited
+  const [editing, setEditing] = createSignal<string | null>(null);
+  const [id, setId] = createSignal('');
+  const [name, setName] = createSignal('');
+  const [icon, setIcon] = createSignal('');
+  const [command, setCommand] = createSignal('');
+  const [args, setArgs] = createSignal('');
+  const [resumeArgs, setResumeArgs] = createSignal('');
+  const [skipArgs, setSkipArgs] = createSignal('');
+  const [env, setEnv] = createSignal('');
+  const [planPatterns, setPlanPatterns] = createSignal('');
+  const [envProfile, setEnvProfile] = createSignal('');
+  const [error, setError] = createSignal('');
+
+  const customAgents = () => store.availableAgents.filter((a) => a.custom);
+
+  function openForm(agent?: AgentDef) {
+    setEditing(agent?.id ?? '');
+    setId(agent?.id ?? '');
+    setName(agent?.name ?? '');
+    setIcon(agent?.icon ?? '');
+    setCommand(agent?.command ?? '');
+    setArgs(agent?.args.join(' ') ?? '');
+    setResumeArgs(agent?.resume_args?.join(' ') ?? '');
+    setSkipArgs(agent?.skip_permissions_args?.join(' ') ?? '');
+    setPlanPatterns(agent?.plan_patterns?.join(' ') ?? '');
+    setEnvProfile(agent?.env_profile ?? '');
+    setEnv(
+      Object.entries(agent?.env ?? {})
+        .map(([k, v]) => `${k}=${v}`)
+        .join('\n'),
+    );
+    setError('');
+  }
+
+  async function save() {
+    setError('');
+    try {
+      const def: AgentDef = {
+        id: id().trim(),
+        name: name(),
+        icon: icon() || undefined,
+        command: command(),
+        args: splitArgs(args()),
+        resume_args: splitArgs(resumeArgs()),
+        skip_permissions_args: splitArgs(skipArgs()),
+        description: '',
+        env: parseEnvLines(env()),
+        plan_patterns: splitArgs(planPatterns()),
+        env_profile: envProfile().trim() || undefined,
+      };
+      await saveCustomAgent(def, editing() || undefined);
+      setEditing(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  const field = (label: string, input: () => JSX.Element) => (
+    <label style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+      <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>{label}</span>
+      {input()}
+    </label>
+  );
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        Custom Agents
+      </div>
+      <For each={customAgents()}>
+        {(agent) => (
+          <div
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '10px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <span style={{ 'font-size': '13px', color: theme.fg, flex: '1' }}>
+              {agent.icon ? `${agent.icon} ${agent.name}` : agent.name}
+              <span style={{ 'font-size': '11px', color: theme.fgSubtle, 'margin-left': '8px' }}>
+                {[agent.command, ...agent.args].join(' ')}
+              </span>
+            </span>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => openForm(agent)}
+            >
+              Edit
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => deleteCustomAgent(agent.id).catch((err) => setError(String(err)))}
+            >
+              Delete
+            </button>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <Show
+        when={editing() !== null}
+        fallback={
+          <button
+            type="button"
+            class="btn-secondary"
+            style={{ ...secondaryButton, 'align-self': 'flex-start' }}
+            onClick={() => openForm()}
+          >
+            Add agent
+          </button>
+        }
+      >
+        <div
+          style={{
+            display: 'grid',
+            'grid-template-columns': '1fr 1fr',
+            gap: '8px',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          {field('Id', () => (
+            <input
+              style={inputStyle}
+              value={id()}
+              disabled={!!editing()}
+              placeholder="aider"
+              onInput={(e) => setId(e.currentTarget.value)}
+            />
+          ))}
+          {field('Name', () => (
+            <input
+              style={inputStyle}
+              value={name()}
+              placeholder="Aider"
+              onInput={(e) => setName(e.currentTarget.value)}
+            />
+          ))}
+          {field('Command', () => (
+            <input
+              style={inputStyle}
+              value={command()}
+              placeholder="aider"
+              onInput={(e) => setCommand(e.currentTarget.value)}
+            />
+          ))}
+          {field('Icon', () => (
+            <input
+              style={inputStyle}
+              value={icon()}
+              placeholder="Emoji or short text"
+              onInput={(e) => setIcon(e.currentTarget.value)}
+            />
+          ))}
+          {field('Arguments ({worktree}, {branch}, {task_name})', () => (
+            <input
+              style={inputStyle}
+              value={args()}
+              onInput={(e) => setArgs(e.currentTarget.value)}
+            />
+          ))}
+          {field('Resume arguments', () => (
+            <input
+              style={inputStyle}
+              value={resumeArgs()}
+              onInput={(e) => setResumeArgs(e.currentTarget.value)}
+            />
+          ))}
+          {field('Skip-permissions arguments', () => (
+            <input
+              style={inputStyle}
+              value={skipArgs()}
+              onInput={(e) => setSkipArgs(e.currentTarget.value)}
+            />
+          ))}
+          {field('Plan files (globs)', () => (
+            <input
+              style={inputStyle}
+              value={planPatterns()}
+              placeholder="PLAN.md plans/**/*.md"
+              onInput={(e) => setPlanPatterns(e.currentTarget.value)}
+            />
+          ))}
+          {field('Environment profile (id)', () => (
+            <input
+              style={inputStyle}
+              value={envProfile()}
+              placeholder="Project default"
+              onInput={(e) => setEnvProfile(e.currentTarget.value)}
+            />
+          ))}
+          {field('Environment (KEY=VALUE per line)', () => (
+            <textarea
+              style={{ ...inputStyle, resize: 'vertical' }}
+              rows={2}
+              value={env()}
+              onInput={(e) => setEnv(e.currentTarget.value)}
+            />
+          ))}
+          <div style={{ 'grid-column': '1 / -1', display: 'flex', gap: '8px' }}>
+            <button
+              type="button"
+              class="btn-primary"
+              style={primaryButton}
+              onClick={() => void save()}
+            >
+              {editing() ? 'Save' : 'Add'}
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => setEditing(null)}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </Show>
+    </div>
+  );
+}
@@ -0,0 +1,138 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { theme } from '../lib/theme';
+import { store, loadSettings, updateSettings, type SettingsPatch } from '../store/store';
+import { inputStyle } from './CustomAgentsSection';
+
+/** Settings section for defaults kept by the backend: agent, limits, worktree location. */
+export function DefaultsSection() {
+  const [error, setError] = createSignal('');
+  const [settings, { mutate }] = createResource(() =>
+    loadSettings().catch((err) => {
+      setError(String(err));
+      return null;
+    }),
+  );
+
+  async function save(patch: SettingsPatch) {
+    setError('');
+    try {
+      mutate(await updateSettings(patch));
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+    }
+  }
+
+  const row = {
+    display: 'flex',
+    'align-items': 'center',
+    gap: '10px',
+    'font-size': '13px',
+    color: theme.fg,
+  } as const;
+  const field = { ...inputStyle, width: '180px', 'margin-left': 'auto' } as const;
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        Defaults
+      </div>
+      <Show when={settings()}>
+        {(s) => (
+          <div
+            style={{
+              display: 'flex',
+              'flex-direction': 'column',
+              gap: '8px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <label style={row}>
+              Agent for new tasks
+              <select
+                style={field}
+                value={s().default_agent_id ?? ''}
+                onChange={(e) => void save({ default_agent_id: e.currentTarget.value || null })}
+              >
+                <option value="">Last used</option>
+                <For each={store.availableAgents}>
+                  {(agent) => <option value={agent.id}>{agent.name}</option>}
+                </For>
+              </select>
+            </label>
+            <label style={row}>
+              Agents running at once (0 = no limit)
+              <input
+                type="number"
+                min="0"
+                style={field}
+                value={s().agent_limit}
+                onChange={(e) => void save({ agent_limit: Number(e.currentTarget.value) })}
+              />
+            </label>
+            <label style={row}>
+              Read git status
+              <select
+                style={field}
+                value={s().git_backend}
+                onChange={(e) =>
+                  void save({ git_backend: e.currentTarget.value as 'in-process' | 'cli' })
+                }
+              >
+                <option value="cli">With the git command</option>
+                <option value="in-process">In-process (experimental, falls back to git)</option>
+              </select>
+            </label>
+            <label style={row}>
+              Terminal scrollback lines
+              <input
+                type="number"
+                min="0"
+                step="1000"
+                style={field}
+                value={s().terminal_scrollback}
+                onChange={(e) => void save({ terminal_scrollback: Number(e.currentTarget.value) })}
+              />
+            </label>
+            <label style={row}>
+              Worktrees of new projects
+              <select
+                style={field}
+                value={s().worktree_location.mode}
+                onChange={(e) =>
+                  void save({
+                    worktree_location: { mode: e.currentTarget.value as 'repo' | 'app_data' },
+                  })
+                }
+              >
+                <option value="repo">Inside the repository</option>
+                <option value="app_data">In app data</option>
+                <Show when={s().worktree_location.mode === 'custom'}>
+                  <option value="custom" disabled>
+                    Custom directory
+                  </option>
+                </Show>
+              </select>
+            </label>
+          </div>
+        )}
+      </Show>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        Scrollback applies to terminals opened from now on
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,79 @@
+import { Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import { inputStyle, secondaryButton } from './CustomAgentsSection';
+import type { LogEntry } from '../ipc/types';
+
+function formatEntry(e: LogEntry): string {
+  const target = e.target ? ` [${e.target}]` : '';
+  return `${new Date(e.ts).toISOString()} ${e.level.toUpperCase()}${target} ${e.message}`;
+}
+
+/** Settings section to change the backend log level and copy recent logs for a bug report. */
+export function DiagnosticsSection() {
+  const [error, setError] = createSignal('');
+  const [copied, setCopied] = createSignal(false);
+  const [filter, { mutate }] = createResource(() =>
+    invoke<string>(IPC.GetLogLevel).catch(() => 'info'),
+  );
+
+  async function applyFilter(text: string) {
+    setError('');
+    try {
+      mutate(await invoke<string>(IPC.SetLogLevel, { filter: text }));
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+    }
+  }
+
+  async function copyLogs() {
+    setError('');
+    try {
+      const entries = await invoke<LogEntry[]>(IPC.GetBackendLogs, { level: 'debug' });
+      await navigator.clipboard.writeText(entries.map(formatEntry).join('\n'));
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        Diagnostics
+      </div>
+      <div style={{ display: 'flex', 'align-items': 'center', gap: '8px' }}>
+        <input
+          style={{ ...inputStyle, flex: '1' }}
+          value={filter() ?? ''}
+          placeholder="info"
+          onChange={(e) => void applyFilter(e.currentTarget.value)}
+        />
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => void copyLogs()}
+        >
+          {copied() ? 'Copied' : 'Copy logs'}
+        </button>
+      </div>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        Log level: error, warn, info or debug, optionally per subsystem (warn,pty=debug)
+      </div>
+    </div>
+  );
+}
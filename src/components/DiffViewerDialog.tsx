@@ -1,13 +1,12 @@
-import { Show, createSignal, createEffect } from 'solid-js';
+import { For, Show, createSignal, createEffect, createResource } from 'solid-js';
 import { Dialog } from './Dialog';
 import { invoke } from '../lib/ipc';
 import { IPC } from '../../electron/ipc/channels';
 import { DiffView, DiffModeEnum } from '@git-diff-view/solid';
 import '@git-diff-view/solid/styles/diff-view.css';
 import { theme } from '../lib/theme';
-import { isBinaryDiff } from '../lib/diff-parser';
 import { getStatusColor } from '../lib/status-colors';
-import type { ChangedFile } from '../ipc/types';
+import type { BinaryFileDiff, BlameLine, ChangedFile, FileDiff } from '../ipc/types';
 
 interface DiffViewerDialogProps {
   file: ChangedFile | null;
@@ -58,6 +57,13 @@ const EXT_TO_LANG: Record<string, string> = {
   hpp: 'cpp',
 };
 
+function formatSize(bytes: number | null): string {
+  if (bytes === null) return '—';
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 function detectLang(filePath: string): string {
   const ext = filePath.split('.').pop()?.toLowerCase() ?? '';
   const basename = filePath.split('/').pop()?.toLowerCase() ?? '';
@@ -66,12 +72,38 @@ function detectLang(filePath: string): string {
   return EXT_TO_LANG[ext] ?? 'plaintext';
 }
 
+function formatAge(unixSeconds: number): string {
+  const secs = Math.max(0, Date.now() / 1000 - unixSeconds);
+  if (secs < 3600) return `${Math.floor(secs / 60)}m`;
+  if (secs < 86400) return `${Math.floor(secs / 3600)}h`;
+  if (secs < 86400 * 365) return `${Math.floor(secs / 86400)}d`;
+  return `${Math.floor(secs / (86400 * 365))}y`;
+}
+
+function firstChangedLine(diff: string): number {
+  const m = /^@@ -\d+(?:,\d+)? \+(\d+)/m.exec(diff);
+  return m ? parseInt(m[1], 10) : 1;
+}
+
+function blameLabel(l: BlameLine): string {
+  if (/^0+$/.test(l.sha)) return 'uncommitted';
+  return `${l.sha.slice(0, 7)} ${formatAge(l.authored_at).padStart(3)} ${l.author}`;
+}
+
 export function DiffViewerDialog(props: DiffViewerDialogProps) {
   const [rawDiff, setRawDiff] = createSignal('');
   const [loading, setLoading] = createSignal(false);
   const [error, setError] = createSignal('');
-  const [binary, setBinary] = createSignal(false);
+  const [binary, setBinary] = createSignal<BinaryFileDiff | null>(null);
   const [viewMode, setViewMode] = createSignal(DiffModeEnum.Split);
+  const [showBlame, setShowBlame] = createSignal(false);
+
+  // Deleted files have nothing left to blame in the working tree
+  const [blame] = createResource(
+    () => (showBlame() && props.file && props.file.status !== 'D' ? props.file.path : null),
+    (filePath) =>
+      invoke<BlameLine[]>(IPC.GetFileBlame, { worktreePath: props.worktreePath, filePath }),
+  );
 
   createEffect(() => {
     const file = props.file;
@@ -79,18 +111,18 @@ export function DiffViewerDialog(props: DiffViewerDialogProps) {
 
     setLoading(true);
     setError('');
-    setBinary(false);
+    setBinary(null);
     setRawDiff('');
 
-    invoke<string>(IPC.GetFileDiff, {
+    invoke<FileDiff>(IPC.GetFileDiff, {
       worktreePath: props.worktreePath,
       filePath: file.path,
     })
-      .then((raw) => {
-        if (isBinaryDiff(raw)) {
-          setBinary(true);
+      .then((result) => {
+        if (result.type === 'binary') {
+          setBinary(result);
         } else {
-          setRawDiff(raw);
+          setRawDiff(result.diff);
         }
       })
       .catch((err) => setError(String(err)))
@@ -196,6 +228,45 @@ export function DiffViewerDialog(props: DiffViewerDialogProps) {
                 </button>
               </div>
 
+              <button
+                onClick={() => {
+                  invoke(IPC.OpenInEditor, {
+                    worktreePath: props.worktreePath,
+                    file: file().path,
+                    line: firstChangedLine(rawDiff()),
+                  }).catch((err) => console.error('Failed to open editor:', err));
+                }}
+                title="Open in editor at the first change"
+                style={{
+                  background: 'rgba(255,255,255,0.04)',
+                  border: 'none',
+                  color: theme.fgMuted,
+                  'font-size': '11px',
+                  padding: '5px 10px',
+                  'border-radius': '6px',
+                  cursor: 'pointer',
+                  'font-family': 'inherit',
+                }}
+              >
+                Open
+              </button>
+              <button
+                onClick={() => setShowBlame(!showBlame())}
+                title="Show who last changed each line; lines from this task are highlighted"
+                style={{
+                  background: showBlame() ? 'rgba(255,255,255,0.10)' : 'rgba(255,255,255,0.04)',
+                  border: 'none',
+                  color: showBlame() ? theme.fg : theme.fgMuted,
+                  'font-size': '11px',
+                  padding: '5px 10px',
+                  'border-radius': '6px',
+                  cursor: 'pointer',
+                  'font-family': 'inherit',
+                }}
+              >
+                Blame
+              </button>
+
               <button
                 onClick={() => props.onClose()}
                 style={{
@@ -236,18 +307,105 @@ export function DiffViewerDialog(props: DiffViewerDialogProps) {
               </Show>
 
               <Show when={binary()}>
-                <div style={{ padding: '40px', 'text-align': 'center', color: theme.fgMuted }}>
-                  Binary file — cannot display diff
-                </div>
+                {(bin) => (
+                  <div style={{ padding: '24px', 'text-align': 'center', color: theme.fgMuted }}>
+                    <div style={{ 'margin-bottom': '16px' }}>
+                      Binary file — {formatSize(bin().old_size)} → {formatSize(bin().new_size)}
+                    </div>
+                    <Show when={bin().image}>
+                      {(img) => (
+                        <div style={{ display: 'flex', gap: '16px', 'justify-content': 'center' }}>
+                          <For each={[img().old_base64, img().new_base64]}>
+                            {(data, i) => (
+                              <div style={{ flex: '1', 'max-width': '50%' }}>
+                                <div style={{ 'margin-bottom': '8px', 'font-size': '12px' }}>
+                                  {i() === 0 ? 'Before' : 'After'}
+                                </div>
+                                <Show when={data} fallback={<div>—</div>}>
+                                  <img
+                                    src={`data:${img().mime};base64,${data}`}
+                                    style={{
+                                      'max-width': '100%',
+                                      border: `1px solid ${theme.border}`,
+                                    }}
+                                  />
+                                </Show>
+                              </div>
+                            )}
+                          </For>
+                        </div>
+                      )}
+                    </Show>
+                  </div>
+                )}
+              </Show>
+
+              <Show when={showBlame()}>
+                <Show when={blame.error}>
+                  <div style={{ padding: '40px', 'text-align': 'center', color: theme.error }}>
+                    {String(blame.error)}
+                  </div>
+                </Show>
+                <Show when={blame()}>
+                  {(lines) => (
+                    <div
+                      style={{
+                        'font-family': "'JetBrains Mono', monospace",
+                        'font-size': '12px',
+                        'white-space': 'pre',
+                      }}
+                    >
+                      <For each={lines()}>
+                        {(l) => (
+                          <div
+                            style={{
+                              display: 'flex',
+                              background: l.in_task
+                                ? `color-mix(in srgb, ${theme.success} 10%, transparent)`
+                                : 'transparent',
+                            }}
+                          >
+                            <span
+                              title={l.summary}
+                              style={{
+                                width: '260px',
+                                'flex-shrink': '0',
+                                padding: '0 8px',
+                                color: theme.fgMuted,
+                                overflow: 'hidden',
+                                'text-overflow': 'ellipsis',
+                                'border-right': `1px solid ${theme.border}`,
+                              }}
+                            >
+                              {blameLabel(l)}
+                            </span>
+                            <span
+                              style={{
+                                width: '48px',
+                                'flex-shrink': '0',
+                                padding: '0 8px',
+                                'text-align': 'right',
+                                color: theme.fgSubtle,
+                              }}
+                            >
+                              {l.line}
+                            </span>
+                            <span style={{ color: theme.fg }}>{l.content}</span>
+                          </div>
+                        )}
+                      </For>
+                    </div>
+                  )}
+                </Show>
               </Show>
 
-              <Show when={!loading() && !error() && !binary() && !rawDiff()}>
+              <Show when={!showBlame() && !loading() && !error() && !binary() && !rawDiff()}>
                 <div style={{ padding: '40px', 'text-align': 'center', color: theme.fgMuted }}>
                   No changes
                 </div>
               </Show>
 
-              <Show when={!loading() && !error() && !binary() && rawDiff()}>
+              <Show when={!showBlame() && !loading() && !error() && !binary() && rawDiff()}>
                 <DiffView
                   data={{
                     oldFile: { fileName: file().path, fileLang: detectLang(file().path) },
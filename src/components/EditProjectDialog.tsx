@@ -1,9 +1,13 @@
-import { createSignal, createEffect, For, Show } from 'solid-js';
+import { createSignal, createEffect, createResource, For, Show } from 'solid-js';
 import { Dialog } from './Dialog';
-import { updateProject, PASTEL_HUES } from '../store/store';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { updateProject, restoreArchivedTask, PASTEL_HUES } from '../store/store';
 import { sanitizeBranchPrefix, toBranchName } from '../lib/branch-name';
 import { theme } from '../lib/theme';
+import { McpServersSection } from './McpServersSection';
 import type { Project, TerminalBookmark } from '../store/types';
+import type { ArchivedTask, MainBranchInfo } from '../ipc/types';
 
 interface EditProjectDialogProps {
   project: Project | null;
@@ -19,11 +23,71 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
   const [name, setName] = createSignal('');
   const [selectedHue, setSelectedHue] = createSignal(0);
   const [branchPrefix, setBranchPrefix] = createSignal('task');
+  const [branchTemplate, setBranchTemplate] = createSignal('');
+  const [baseBranch, setBaseBranch] = createSignal('');
+  const [runGitHooks, setRunGitHooks] = createSignal(true);
+  const [copyFiles, setCopyFiles] = createSignal('');
+  const [setupCommand, setSetupCommand] = createSignal('');
   const [deleteBranchOnClose, setDeleteBranchOnClose] = createSignal(true);
   const [bookmarks, setBookmarks] = createSignal<TerminalBookmark[]>([]);
   const [newCommand, setNewCommand] = createSignal('');
   let nameRef!: HTMLInputElement;
 
+  const [baseBranchInfo] = createResource(
+    () => props.project?.path,
+    (projectRoot) =>
+      invoke<MainBranchInfo>(IPC.GetMainBranchInfo, { projectRoot }).catch(() => null),
+  );
+  const initialBaseBranch = () => {
+    const info = baseBranchInfo();
+    return info?.source === 'override' ? info.branch : '';
+  };
+  createEffect(() => setBaseBranch(initialBaseBranch()));
+  const [savedRunGitHooks] = createResource(
+    () => props.project?.path,
+    (projectRoot) => invoke<boolean>(IPC.GetRunGitHooks, { projectRoot }).catch(() => true),
+  );
+  createEffect(() => setRunGitHooks(savedRunGitHooks() ?? true));
+  const [savedCopyFiles] = createResource(
+    () => props.project?.path,
+    (projectRoot) =>
+      invoke<string[]>(IPC.GetWorktreeCopyFiles, { projectRoot }).catch(() => [] as string[]),
+  );
+  createEffect(() => setCopyFiles((savedCopyFiles() ?? []).join(', ')));
+  const [savedSetupCommand] = createResource(
+    () => props.project?.path,
+    (projectRoot) =>
+      invoke<string | null>(IPC.GetWorktreeSetupCommand, { projectRoot }).catch(() => null),
+  );
+  createEffect(() => setSetupCommand(savedSetupCommand() ?? ''));
+  const [archivedTasks, { refetch: refetchArchivedTasks }] = createResource(
+    () => props.project?.path,
+    (projectRoot) =>
+      invoke<ArchivedTask[]>(IPC.ListArchivedTasks)
+        .then((all) => all.filter((a) => a.project_root === projectRoot))
+        .catch(() => [] as ArchivedTask[]),
+  );
+  const [restoringId, setRestoringId] = createSignal<string | null>(null);
+  const [restoreError, setRestoreError] = createSignal('');
+
+  async function handleRestore(archived: ArchivedTask) {
+    setRestoringId(archived.id);
+    setRestoreError('');
+    try {
+      const restored = await restoreArchivedTask(archived);
+      if (!restored.patch_applied) {
+        setRestoreError(
+          `Uncommitted changes of "${archived.name}" no longer apply; see ${archived.path}`,
+        );
+      }
+      refetchArchivedTasks();
+    } catch (err) {
+      setRestoreError(String(err));
+    } finally {
+      setRestoringId(null);
+    }
+  }
+
   // Sync signals when project prop changes
   createEffect(() => {
     const p = props.project;
@@ -31,6 +95,7 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
     setName(p.name);
     setSelectedHue(hueFromColor(p.color));
     setBranchPrefix(sanitizeBranchPrefix(p.branchPrefix ?? 'task'));
+    setBranchTemplate(p.branchTemplate ?? '');
     setDeleteBranchOnClose(p.deleteBranchOnClose ?? true);
     setBookmarks(p.terminalBookmarks ? [...p.terminalBookmarks] : []);
     setNewCommand('');
@@ -62,9 +127,39 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
       name: name().trim(),
       color: `hsl(${selectedHue()}, 70%, 75%)`,
       branchPrefix: sanitizedPrefix,
+      branchTemplate: branchTemplate(),
       deleteBranchOnClose: deleteBranchOnClose(),
       terminalBookmarks: bookmarks(),
     });
+    const base = baseBranch().trim();
+    if (!baseBranchInfo.loading && base !== initialBaseBranch()) {
+      invoke(IPC.SetTaskBaseBranch, {
+        projectRoot: props.project.path,
+        branch: base || null,
+      }).catch((err) => console.error('Failed to set base branch:', err));
+    }
+    if (!savedRunGitHooks.loading && runGitHooks() !== savedRunGitHooks()) {
+      invoke(IPC.SetRunGitHooks, {
+        projectRoot: props.project.path,
+        enabled: runGitHooks(),
+      }).catch((err) => console.error('Failed to set git hook preference:', err));
+    }
+    const patterns = copyFiles()
+      .split(',')
+      .map((p) => p.trim())
+      .filter(Boolean);
+    if (!savedCopyFiles.loading && patterns.join(',') !== (savedCopyFiles() ?? []).join(',')) {
+      invoke(IPC.SetWorktreeCopyFiles, { projectRoot: props.project.path, patterns }).catch((err) =>
+        console.error('Failed to set worktree copy files:', err),
+      );
+    }
+    const command = setupCommand().trim();
+    if (!savedSetupCommand.loading && command !== (savedSetupCommand() ?? '')) {
+      invoke(IPC.SetWorktreeSetupCommand, {
+        projectRoot: props.project.path,
+        command: command || null,
+      }).catch((err) => console.error('Failed to set setup command:', err));
+    }
     props.onClose();
   }
 
@@ -191,6 +286,168 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
               </Show>
             </div>
 
+            {/* Branch template */}
+            <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+              <label
+                style={{
+                  'font-size': '11px',
+                  color: theme.fgMuted,
+                  'text-transform': 'uppercase',
+                  'letter-spacing': '0.05em',
+                }}
+              >
+                Branch template
+              </label>
+              <input
+                class="input-field"
+                type="text"
+                value={branchTemplate()}
+                onInput={(e) => setBranchTemplate(e.currentTarget.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter' && canSave()) handleSave();
+                }}
+                placeholder="{prefix}/{slug}"
+                style={{
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  padding: '10px 14px',
+                  color: theme.fg,
+                  'font-size': '13px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  outline: 'none',
+                }}
+              />
+              <div style={{ 'font-size': '11px', color: theme.fgSubtle, padding: '2px 2px 0' }}>
+                Placeholders: {'{prefix}'}, {'{slug}'}, {'{user}'}, {'{date}'}
+              </div>
+            </div>
+
+            {/* Base branch */}
+            <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+              <label
+                style={{
+                  'font-size': '11px',
+                  color: theme.fgMuted,
+                  'text-transform': 'uppercase',
+                  'letter-spacing': '0.05em',
+                }}
+              >
+                Base branch
+              </label>
+              <input
+                class="input-field"
+                type="text"
+                list="base-branch-candidates"
+                value={baseBranch()}
+                onInput={(e) => setBaseBranch(e.currentTarget.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter' && canSave()) handleSave();
+                }}
+                placeholder={
+                  baseBranchInfo()?.source === 'override'
+                    ? 'auto-detect'
+                    : `auto-detect (${baseBranchInfo()?.branch ?? 'main'})`
+                }
+                style={{
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  padding: '10px 14px',
+                  color: theme.fg,
+                  'font-size': '13px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  outline: 'none',
+                }}
+              />
+              <datalist id="base-branch-candidates">
+                <For each={baseBranchInfo()?.candidates ?? []}>
+                  {(branch) => <option value={branch} />}
+                </For>
+              </datalist>
+              <Show when={baseBranchInfo() && !baseBranchInfo()?.exists && !baseBranch()}>
+                <div style={{ 'font-size': '11px', color: theme.warning, padding: '2px 2px 0' }}>
+                  No main or master branch found — pick the branch tasks should merge into.
+                </div>
+              </Show>
+            </div>
+
+            {/* Context files */}
+            <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+              <label
+                style={{
+                  'font-size': '11px',
+                  color: theme.fgMuted,
+                  'text-transform': 'uppercase',
+                  'letter-spacing': '0.05em',
+                }}
+              >
+                Copy into new worktrees
+              </label>
+              <input
+                class="input-field"
+                type="text"
+                value={copyFiles()}
+                onInput={(e) => setCopyFiles(e.currentTarget.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter' && canSave()) handleSave();
+                }}
+                placeholder=".env, .env.local, config/*.local.json"
+                style={{
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  padding: '10px 14px',
+                  color: theme.fg,
+                  'font-size': '13px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  outline: 'none',
+                }}
+              />
+              <div style={{ 'font-size': '11px', color: theme.fgSubtle, padding: '2px 2px 0' }}>
+                Untracked or ignored files copied from the main checkout, comma-separated
+              </div>
+            </div>
+
+            {/* Setup command */}
+            <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+              <label
+                style={{
+                  'font-size': '11px',
+                  color: theme.fgMuted,
+                  'text-transform': 'uppercase',
+                  'letter-spacing': '0.05em',
+                }}
+              >
+                Setup command
+              </label>
+              <input
+                class="input-field"
+                type="text"
+                value={setupCommand()}
+                onInput={(e) => setSetupCommand(e.currentTarget.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter' && canSave()) handleSave();
+                }}
+                placeholder="pnpm install"
+                style={{
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  padding: '10px 14px',
+                  color: theme.fg,
+                  'font-size': '13px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  outline: 'none',
+                }}
+              />
+              <div style={{ 'font-size': '11px', color: theme.fgSubtle, padding: '2px 2px 0' }}>
+                Runs in a shell of each new task worktree, e.g. to install dependencies
+              </div>
+            </div>
+
+            <McpServersSection projectRoot={project().path} />
+
             {/* Color palette */}
             <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
               <label
@@ -252,6 +509,26 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
               Always delete branch and worklog on merge
             </label>
 
+            {/* Git hooks */}
+            <label
+              style={{
+                display: 'flex',
+                'align-items': 'center',
+                gap: '8px',
+                cursor: 'pointer',
+                'font-size': '13px',
+                color: theme.fg,
+              }}
+            >
+              <input
+                type="checkbox"
+                checked={runGitHooks()}
+                onChange={(e) => setRunGitHooks(e.currentTarget.checked)}
+                style={{ cursor: 'pointer' }}
+              />
+              Run git hooks on commit, merge and push
+            </label>
+
             {/* Command Bookmarks */}
             <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
               <label
@@ -360,6 +637,77 @@ export function EditProjectDialog(props: EditProjectDialogProps) {
               </div>
             </div>
 
+            {/* Archived tasks */}
+            <Show when={(archivedTasks() ?? []).length > 0}>
+              <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+                <label
+                  style={{
+                    'font-size': '11px',
+                    color: theme.fgMuted,
+                    'text-transform': 'uppercase',
+                    'letter-spacing': '0.05em',
+                  }}
+                >
+                  Archived tasks
+                </label>
+                <div style={{ display: 'flex', 'flex-direction': 'column', gap: '4px' }}>
+                  <For each={archivedTasks()}>
+                    {(archived) => (
+                      <div
+                        style={{
+                          display: 'flex',
+                          'align-items': 'center',
+                          gap: '8px',
+                          padding: '4px 8px',
+                          background: theme.bgInput,
+                          'border-radius': '6px',
+                          border: `1px solid ${theme.border}`,
+                        }}
+                      >
+                        <span
+                          style={{
+                            flex: '1',
+                            'font-size': '12px',
+                            color: theme.fg,
+                            overflow: 'hidden',
+                            'text-overflow': 'ellipsis',
+                            'white-space': 'nowrap',
+                          }}
+                          title={`${archived.branch_name} — ${archived.path}`}
+                        >
+                          {archived.name}
+                          <span style={{ color: theme.fgSubtle }}>
+                            {' '}
+                            · {new Date(archived.archived_at).toLocaleDateString()}
+                          </span>
+                        </span>
+                        <button
+                          type="button"
+                          disabled={restoringId() !== null}
+                          onClick={() => void handleRestore(archived)}
+                          style={{
+                            padding: '4px 10px',
+                            background: 'transparent',
+                            border: `1px solid ${theme.border}`,
+                            'border-radius': '6px',
+                            color: theme.fg,
+                            cursor: restoringId() !== null ? 'not-allowed' : 'pointer',
+                            'font-size': '11px',
+                            'flex-shrink': '0',
+                          }}
+                        >
+                          {restoringId() === archived.id ? 'Restoring…' : 'Restore'}
+                        </button>
+                      </div>
+                    )}
+                  </For>
+                </div>
+                <Show when={restoreError()}>
+                  <div style={{ 'font-size': '11px', color: theme.error }}>{restoreError()}</div>
+                </Show>
+              </div>
+            </Show>
+
             {/* Buttons */}
             <div
               style={{
@@ -0,0 +1,214 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import type { EnvProfile } from '../ipc/types';
+import { parseEnvLines } from '../lib/agent-args';
+import { inputStyle, primaryButton, secondaryButton } from './CustomAgentsSection';
+
+/** Settings section for environment profiles agents run with, plus a login PATH refresh. */
+export function EnvProfilesSection() {
+  const [adding, setAdding] = createSignal(false);
+  const [id, setId] = createSignal('');
+  const [name, setName] = createSignal('');
+  const [pathEntries, setPathEntries] = createSignal('');
+  const [init, setInit] = createSignal('');
+  const [env, setEnv] = createSignal('');
+  const [error, setError] = createSignal('');
+  const [notice, setNotice] = createSignal('');
+  const [profiles, { refetch }] = createResource(() =>
+    invoke<EnvProfile[]>(IPC.ListEnvProfiles).catch((err) => {
+      setError(String(err));
+      return [];
+    }),
+  );
+
+  async function run(action: () => Promise<unknown>): Promise<boolean> {
+    setError('');
+    setNotice('');
+    try {
+      await action();
+      await refetch();
+      return true;
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+      return false;
+    }
+  }
+
+  function edit(profile?: EnvProfile) {
+    setId(profile?.id ?? '');
+    setName(profile?.name ?? '');
+    setPathEntries(profile?.path.join('\n') ?? '');
+    setInit(profile?.init ?? '');
+    setEnv(
+      Object.entries(profile?.env ?? {})
+        .map(([k, v]) => `${k}=${v}`)
+        .join('\n'),
+    );
+    setAdding(true);
+  }
+
+  async function save() {
+    const profile: EnvProfile = {
+      id: id().trim(),
+      name: name().trim(),
+      path: pathEntries()
+        .split('\n')
+        .map((p) => p.trim())
+        .filter(Boolean),
+      init: init(),
+      env: parseEnvLines(env()),
+    };
+    if (await run(() => invoke(IPC.SaveEnvProfile, { profile }))) setAdding(false);
+  }
+
+  async function refreshPath() {
+    await run(async () => {
+      const path = await invoke<string>(IPC.RefreshLoginPath);
+      setNotice(`PATH reloaded from the login shell: ${path}`);
+    });
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+        <div
+          style={{
+            flex: '1',
+            'font-size': '11px',
+            color: theme.fgMuted,
+            'text-transform': 'uppercase',
+            'letter-spacing': '0.05em',
+            'font-weight': '600',
+          }}
+        >
+          Environment profiles
+        </div>
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => void refreshPath()}
+        >
+          Refresh PATH
+        </button>
+        <button type="button" class="btn-secondary" style={secondaryButton} onClick={() => edit()}>
+          Add profile
+        </button>
+      </div>
+      <For each={profiles()}>
+        {(profile) => (
+          <div
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '10px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <span style={{ flex: '1', 'font-size': '13px', color: theme.fg }}>
+              {profile.name}
+              <span style={{ 'font-size': '11px', color: theme.fgSubtle }}> {profile.id}</span>
+            </span>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => edit(profile)}
+            >
+              Edit
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => void run(() => invoke(IPC.DeleteEnvProfile, { id: profile.id }))}
+            >
+              Delete
+            </button>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <Show when={notice()}>
+        <div style={{ 'font-size': '12px', color: theme.fgSubtle, 'word-break': 'break-all' }}>
+          {notice()}
+        </div>
+      </Show>
+      <Show when={adding()}>
+        <div
+          style={{
+            display: 'flex',
+            'flex-direction': 'column',
+            gap: '8px',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            style={inputStyle}
+            value={id()}
+            placeholder="node-20"
+            onInput={(e) => setId(e.currentTarget.value)}
+          />
+          <input
+            style={inputStyle}
+            value={name()}
+            placeholder="Name"
+            onInput={(e) => setName(e.currentTarget.value)}
+          />
+          <textarea
+            style={{ ...inputStyle, resize: 'vertical' }}
+            rows={2}
+            value={pathEntries()}
+            placeholder="Extra PATH directories, one per line"
+            onInput={(e) => setPathEntries(e.currentTarget.value)}
+          />
+          <textarea
+            style={{ ...inputStyle, resize: 'vertical' }}
+            rows={2}
+            value={init()}
+            placeholder="Shell init, e.g. nvm use 20"
+            onInput={(e) => setInit(e.currentTarget.value)}
+          />
+          <textarea
+            style={{ ...inputStyle, resize: 'vertical' }}
+            rows={2}
+            value={env()}
+            placeholder="KEY=VALUE per line"
+            onInput={(e) => setEnv(e.currentTarget.value)}
+          />
+          <div style={{ display: 'flex', gap: '8px' }}>
+            <button
+              type="button"
+              class="btn-primary"
+              style={primaryButton}
+              disabled={!id().trim() || !name().trim()}
+              onClick={() => void save()}
+            >
+              Save
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => setAdding(false)}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        Set a profile on an agent, or per project in its repo settings
+      </div>
+    </div>
+  );
+}
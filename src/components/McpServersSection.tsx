@@ -0,0 +1,141 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import { inputStyle, secondaryButton, splitArgs } from './CustomAgentsSection';
+import type { McpServer } from '../ipc/types';
+
+interface McpServersSectionProps {
+  projectRoot: string;
+}
+
+/** MCP servers in the project's agent configs; new worktrees start with the same set. */
+export function McpServersSection(props: McpServersSectionProps) {
+  const [error, setError] = createSignal('');
+  const [servers, { refetch }] = createResource(
+    () => props.projectRoot,
+    (dir) =>
+      invoke<McpServer[]>(IPC.ListMcpServers, { dir }).catch((err) => {
+        setError(String(err));
+        return [] as McpServer[];
+      }),
+  );
+  const [name, setName] = createSignal('');
+  const [target, setTarget] = createSignal('');
+
+  async function add() {
+    setError('');
+    const words = splitArgs(target());
+    const server = /^https?:\/\//.test(target().trim())
+      ? { name: name().trim(), transport: 'http', url: target().trim() }
+      : { name: name().trim(), transport: 'stdio', command: words[0], args: words.slice(1) };
+    try {
+      await invoke(IPC.AddMcpServer, { dir: props.projectRoot, server });
+      setName('');
+      setTarget('');
+      refetch();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function toggle(server: McpServer, enabled: boolean) {
+    setError('');
+    try {
+      await invoke(IPC.ToggleMcpServer, { dir: props.projectRoot, name: server.name, enabled });
+    } catch (err) {
+      setError(String(err));
+    }
+    refetch();
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}>
+      <label
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+        }}
+      >
+        MCP servers
+      </label>
+      <For each={servers() ?? []}>
+        {(server) => (
+          <label
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '8px',
+              padding: '4px 8px',
+              background: theme.bgInput,
+              'border-radius': '6px',
+              border: `1px solid ${theme.border}`,
+              'font-size': '12px',
+              color: theme.fg,
+              cursor: 'pointer',
+            }}
+            title={`Configured for ${server.agents.join(', ')}`}
+          >
+            <input
+              type="checkbox"
+              checked={server.enabled}
+              onChange={(e) => void toggle(server, e.currentTarget.checked)}
+              style={{ cursor: 'pointer' }}
+            />
+            <span style={{ 'flex-shrink': '0' }}>{server.name}</span>
+            <span
+              style={{
+                flex: '1',
+                'font-size': '11px',
+                'font-family': "'JetBrains Mono', monospace",
+                color: theme.fgSubtle,
+                overflow: 'hidden',
+                'text-overflow': 'ellipsis',
+                'white-space': 'nowrap',
+              }}
+            >
+              {server.url ?? [server.command, ...(server.args ?? [])].join(' ')}
+            </span>
+          </label>
+        )}
+      </For>
+      <div style={{ display: 'flex', gap: '6px' }}>
+        <input
+          style={{ ...inputStyle, width: '30%' }}
+          value={name()}
+          placeholder="name"
+          onInput={(e) => setName(e.currentTarget.value)}
+        />
+        <input
+          style={{ ...inputStyle, flex: '1' }}
+          value={target()}
+          placeholder="npx -y @modelcontextprotocol/server-github or https://…"
+          onInput={(e) => setTarget(e.currentTarget.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') {
+              e.preventDefault();
+              void add();
+            }
+          }}
+        />
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          disabled={!name().trim() || !target().trim()}
+          onClick={() => void add()}
+        >
+          Add
+        </button>
+      </div>
+      <Show when={error()}>
+        <div style={{ 'font-size': '11px', color: theme.error }}>{error()}</div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle, padding: '2px 2px 0' }}>
+        Written to .mcp.json and .gemini/settings.json in the main checkout
+      </div>
+    </div>
+  );
+}
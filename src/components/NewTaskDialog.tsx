@@ -1,10 +1,11 @@
-import { createSignal, createEffect, For, Show, onCleanup } from 'solid-js';
+import { createSignal, createEffect, createResource, For, Show, onCleanup } from 'solid-js';
 import { Dialog } from './Dialog';
 import { invoke } from '../lib/ipc';
 import { IPC } from '../../electron/ipc/channels';
 import {
   store,
   createTask,
+  createTaskFromTemplate,
   createDirectTask,
   toggleNewTaskDialog,
   loadAgents,
@@ -20,7 +21,15 @@ import { toBranchName, sanitizeBranchPrefix } from '../lib/branch-name';
 import { cleanTaskName } from '../lib/clean-task-name';
 import { extractGitHubUrl } from '../lib/github-url';
 import { theme } from '../lib/theme';
-import type { AgentDef } from '../ipc/types';
+import type {
+  AgentDef,
+  BranchNameSuggestion,
+  TaskNameSuggestion,
+  TaskTemplate,
+  WorktreeLocationInfo,
+} from '../ipc/types';
+
+const NAME_SUGGESTION_DELAY_MS = 800;
 
 interface NewTaskDialogProps {
   open: boolean;
@@ -40,7 +49,10 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
   const [selectedDirs, setSelectedDirs] = createSignal<Set<string>>(new Set());
   const [directMode, setDirectMode] = createSignal(false);
   const [skipPermissions, setSkipPermissions] = createSignal(false);
+  const [profileId, setProfileId] = createSignal<string | undefined>(undefined);
   const [branchPrefix, setBranchPrefix] = createSignal('');
+  const [templateId, setTemplateId] = createSignal<string | null>(null);
+  const [startAfter, setStartAfter] = createSignal<string | null>(null);
   let projectMenuRef!: HTMLDivElement;
   let promptRef!: HTMLTextAreaElement;
   let formRef!: HTMLFormElement;
@@ -171,15 +183,18 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
     setProjectMenuOpen(false);
     setDirectMode(false);
     setSkipPermissions(false);
+    setTemplateId(null);
+    setStartAfter(null);
 
     void (async () => {
       if (store.availableAgents.length === 0) {
         await loadAgents();
       }
-      const lastAgent = store.lastAgentId
-        ? (store.availableAgents.find((a) => a.id === store.lastAgentId) ?? null)
+      const preferredId = store.defaultAgentId ?? store.lastAgentId;
+      const preferred = preferredId
+        ? (store.availableAgents.find((a) => a.id === preferredId) ?? null)
         : null;
-      setSelectedAgent(lastAgent ?? store.availableAgents[0] ?? null);
+      setSelectedAgent(preferred ?? store.availableAgents[0] ?? null);
 
       // Pre-fill from drop data if present
       const dropUrl = store.newTaskDropUrl;
@@ -265,9 +280,33 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
     if (directModeDisabled()) setDirectMode(false);
   });
 
+  // Profiles belong to one agent; drop the choice when the agent changes
+  createEffect(() => {
+    const profiles = selectedAgent()?.profiles ?? [];
+    if (!profiles.some((p) => p.id === profileId())) setProfileId(undefined);
+  });
+
+  // Agent-written title for an untitled prompt, requested once typing pauses
+  const [suggestedName, setSuggestedName] = createSignal('');
+  createEffect(() => {
+    const p = prompt().trim();
+    const agent = selectedAgent();
+    setSuggestedName('');
+    if (!store.agentTaskNames || !p || !agent || name().trim()) return;
+    const timer = setTimeout(() => {
+      invoke<TaskNameSuggestion>(IPC.SuggestTaskName, { prompt: p, agentId: agent.id })
+        .then((s) => {
+          if (prompt().trim() === p) setSuggestedName(s.title);
+        })
+        .catch(() => {});
+    }, NAME_SUGGESTION_DELAY_MS);
+    onCleanup(() => clearTimeout(timer));
+  });
+
   const effectiveName = () => {
     const n = name().trim();
     if (n) return n;
+    if (suggestedName()) return suggestedName();
     const p = prompt().trim();
     if (!p) return '';
     // Use first line, clean filler phrases, truncate at ~40 chars on word boundary
@@ -276,7 +315,10 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
     return firstLine.slice(0, 40).replace(/\s+\S*$/, '') || firstLine.slice(0, 40);
   };
 
+  // Local approximation until the backend suggestion (template + collisions) arrives
   const branchPreview = () => {
+    const suggested = branchSuggestion()?.branch_name;
+    if (suggested) return suggested;
     const n = effectiveName();
     const prefix = sanitizeBranchPrefix(branchPrefix());
     return n ? `${prefix}/${toBranchName(n)}` : '';
@@ -287,11 +329,86 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
     return pid ? getProjectPath(pid) : undefined;
   };
 
+  const [worktreeLocation] = createResource(selectedProjectPath, (projectRoot) =>
+    invoke<WorktreeLocationInfo>(IPC.GetWorktreeLocation, { projectRoot }),
+  );
+
   const selectedProject = () => {
     const pid = selectedProjectId();
     return pid ? getProject(pid) : undefined;
   };
 
+  const [branchSuggestion] = createResource(
+    () => {
+      const projectRoot = selectedProjectPath();
+      const title = effectiveName();
+      if (!projectRoot || !title || directMode()) return null;
+      return {
+        projectRoot,
+        title,
+        branchPrefix: sanitizeBranchPrefix(branchPrefix()),
+        branchTemplate: selectedProject()?.branchTemplate,
+      };
+    },
+    (args) => invoke<BranchNameSuggestion>(IPC.SuggestBranchName, args).catch(() => null),
+  );
+
+  const [templates, { refetch: refetchTemplates }] = createResource(
+    () => props.open,
+    () => invoke<TaskTemplate[]>(IPC.ListTaskTemplates).catch(() => [] as TaskTemplate[]),
+  );
+
+  function applyTemplate(id: string | null) {
+    setTemplateId(id);
+    const template = templates()?.find((t) => t.id === id);
+    if (!template) return;
+    const agent = store.availableAgents.find((a) => a.id === template.agent_id);
+    if (agent) setSelectedAgent(agent);
+    setPrompt(template.prompt);
+    if (template.branch_prefix) setBranchPrefix(template.branch_prefix);
+  }
+
+  async function saveAsTemplate() {
+    const existing = templates()?.find((t) => t.id === templateId());
+    try {
+      const saved = await invoke<TaskTemplate>(IPC.SaveTaskTemplate, {
+        template: {
+          ...existing,
+          name: existing?.name ?? effectiveName(),
+          agent_id: selectedAgent()?.id ?? null,
+          prompt: prompt(),
+          branch_prefix: sanitizeBranchPrefix(branchPrefix()),
+        },
+      });
+      await refetchTemplates();
+      setTemplateId(saved.id);
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function deleteTemplate() {
+    const id = templateId();
+    if (!id) return;
+    try {
+      await invoke(IPC.DeleteTaskTemplate, { id });
+      setTemplateId(null);
+      await refetchTemplates();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  // Worktree tasks a new task can wait for
+  const prerequisiteTasks = () =>
+    store.taskOrder
+      .map((id) => store.tasks[id])
+      .filter((t) => t && !t.directMode && !t.closingStatus);
+  const dependsOn = () => {
+    const id = startAfter();
+    return id ? [id] : undefined;
+  };
+
   const directModeDisabled = () => {
     const pid = selectedProjectId();
     return pid ? hasDirectModeTask(pid) : false;
@@ -360,7 +477,18 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
           isFromDrop ? undefined : p,
           ghUrl,
           agentSupportsSkipPermissions() && skipPermissions(),
+          profileId(),
         );
+      } else if (templateId()) {
+        taskId = await createTaskFromTemplate(templateId() as string, n, agent, projectId, {
+          symlinkDirs: [...selectedDirs()],
+          prompt: isFromDrop ? '' : (p ?? ''),
+          branchPrefix: prefix,
+          branchName: branchSuggestion.loading ? undefined : branchSuggestion()?.branch_name,
+          skipPermissions: agentSupportsSkipPermissions() && skipPermissions(),
+          dependsOn: dependsOn(),
+          agentProfileId: profileId(),
+        });
       } else {
         taskId = await createTask(
           n,
@@ -371,6 +499,9 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
           prefix,
           ghUrl,
           agentSupportsSkipPermissions() && skipPermissions(),
+          branchSuggestion.loading ? undefined : branchSuggestion()?.branch_name,
+          dependsOn(),
+          profileId(),
         );
       }
       // Drop flow: prefill prompt without auto-sending
@@ -416,6 +547,127 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
           </p>
         </div>
 
+        {/* Task template */}
+        <Show when={!directMode()}>
+          <div
+            data-nav-field="template"
+            style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}
+          >
+            <label
+              style={{
+                'font-size': '11px',
+                color: theme.fgMuted,
+                'text-transform': 'uppercase',
+                'letter-spacing': '0.05em',
+              }}
+            >
+              Template
+            </label>
+            <div style={{ display: 'flex', gap: '8px' }}>
+              <select
+                class="input-field"
+                value={templateId() ?? ''}
+                onChange={(e) => applyTemplate(e.currentTarget.value || null)}
+                style={{
+                  flex: '1',
+                  'min-width': '0',
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  padding: '6px 10px',
+                  color: theme.fg,
+                  'font-size': '13px',
+                  outline: 'none',
+                }}
+              >
+                <option value="">None</option>
+                <For each={templates() ?? []}>
+                  {(t) => <option value={t.id}>{t.name}</option>}
+                </For>
+              </select>
+              <button
+                type="button"
+                disabled={!templateId() && !effectiveName()}
+                onClick={() => void saveAsTemplate()}
+                title={
+                  templateId()
+                    ? 'Update the template with the agent, prompt and branch prefix below'
+                    : 'Save the agent, prompt and branch prefix below as a template'
+                }
+                style={{
+                  padding: '6px 10px',
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  'border-radius': '8px',
+                  color: theme.fgMuted,
+                  cursor: 'pointer',
+                  'font-size': '12px',
+                  'white-space': 'nowrap',
+                }}
+              >
+                {templateId() ? 'Update' : 'Save as template'}
+              </button>
+              <Show when={templateId()}>
+                <button
+                  type="button"
+                  onClick={() => void deleteTemplate()}
+                  title="Delete this template"
+                  style={{
+                    padding: '6px 10px',
+                    background: theme.bgInput,
+                    border: `1px solid ${theme.border}`,
+                    'border-radius': '8px',
+                    color: theme.fgMuted,
+                    cursor: 'pointer',
+                    'font-size': '12px',
+                    'white-space': 'nowrap',
+                  }}
+                >
+                  Delete
+                </button>
+              </Show>
+            </div>
+          </div>
+        </Show>
+
+        {/* Start after another task */}
+        <Show when={!directMode() && prerequisiteTasks().length > 0}>
+          <div
+            data-nav-field="start-after"
+            style={{ display: 'flex', 'flex-direction': 'column', gap: '8px' }}
+          >
+            <label
+              style={{
+                'font-size': '11px',
+                color: theme.fgMuted,
+                'text-transform': 'uppercase',
+                'letter-spacing': '0.05em',
+              }}
+            >
+              Start after
+            </label>
+            <select
+              class="input-field"
+              value={startAfter() ?? ''}
+              onChange={(e) => setStartAfter(e.currentTarget.value || null)}
+              style={{
+                background: theme.bgInput,
+                border: `1px solid ${theme.border}`,
+                'border-radius': '8px',
+                padding: '6px 10px',
+                color: theme.fg,
+                'font-size': '13px',
+                outline: 'none',
+              }}
+            >
+              <option value="">Start immediately</option>
+              <For each={prerequisiteTasks()}>
+                {(t) => <option value={t.id}>{t.name} lands</option>}
+              </For>
+            </select>
+          </div>
+        </Show>
+
         {/* Prompt input (optional) */}
         <div
           data-nav-field="prompt"
@@ -596,7 +848,8 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
                   >
                     <path d="M1.75 1A1.75 1.75 0 0 0 0 2.75v10.5C0 14.216.784 15 1.75 15h12.5A1.75 1.75 0 0 0 16 13.25v-8.5A1.75 1.75 0 0 0 14.25 3H7.5a.25.25 0 0 1-.2-.1l-.9-1.2C6.07 1.26 5.55 1 5 1H1.75Z" />
                   </svg>
-                  {selectedProjectPath()}/.worktrees/{branchPreview()}
+                  {worktreeLocation()?.root ?? `${selectedProjectPath()}/.worktrees`}/
+                  {branchPreview()}
                 </span>
               </div>
             </Show>
@@ -800,7 +1053,7 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
           >
             Agent
           </label>
-          <div style={{ display: 'flex', gap: '8px' }}>
+          <div style={{ display: 'flex', 'flex-wrap': 'wrap', gap: '8px' }}>
             <For each={store.availableAgents}>
               {(agent) => {
                 const isSelected = () => selectedAgent()?.id === agent.id;
@@ -827,13 +1080,37 @@ export function NewTaskDialog(props: NewTaskDialogProps) {
                       'font-weight': isSelected() ? '500' : '400',
                       'text-align': 'center',
                     }}
+                    title={agent.description || undefined}
                   >
-                    {agent.name}
+                    {agent.icon ? `${agent.icon} ${agent.name}` : agent.name}
                   </button>
                 );
               }}
             </For>
           </div>
+          <Show when={selectedAgent()?.profiles?.length}>
+            <select
+              value={profileId() ?? ''}
+              onChange={(e) => setProfileId(e.currentTarget.value || undefined)}
+              style={{
+                background: theme.bgInput,
+                border: `1px solid ${theme.border}`,
+                'border-radius': '8px',
+                padding: '6px 8px',
+                color: theme.fg,
+                'font-size': '12px',
+              }}
+            >
+              <option value="">Default flags</option>
+              <For each={selectedAgent()?.profiles ?? []}>
+                {(profile) => (
+                  <option value={profile.id} title={profile.args.join(' ')}>
+                    {profile.name}
+                  </option>
+                )}
+              </For>
+            </select>
+          </Show>
         </div>
 
         {/* Direct mode toggle */}
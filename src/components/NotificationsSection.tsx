@@ -0,0 +1,102 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import { secondaryButton } from './CustomAgentsSection';
+import type { NotificationSettings } from '../ipc/types';
+
+const NOTIFICATION_LABELS: { type: keyof NotificationSettings; label: string }[] = [
+  { type: 'agent_exit', label: 'An agent exits' },
+  { type: 'agent_idle', label: 'An agent waits for input' },
+  { type: 'merge_conflict', label: 'A merge stops on conflicts' },
+  { type: 'pr_status', label: 'A pull request is merged, reviewed or its checks finish' },
+];
+
+/** Settings section for desktop notifications shown while the window is in the background. */
+export function NotificationsSection() {
+  const [error, setError] = createSignal('');
+  const [settings, { mutate }] = createResource(() =>
+    invoke<NotificationSettings>(IPC.GetNotificationSettings).catch((err) => {
+      setError(String(err));
+      return null;
+    }),
+  );
+
+  async function toggle(type: keyof NotificationSettings, enabled: boolean) {
+    setError('');
+    try {
+      mutate(
+        await invoke<NotificationSettings>(IPC.SetNotificationSettings, {
+          settings: { [type]: enabled },
+        }),
+      );
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  function sendTest() {
+    setError('');
+    invoke(IPC.TestNotification).catch((err) => setError(String(err)));
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+        <div
+          style={{
+            flex: '1',
+            'font-size': '11px',
+            color: theme.fgMuted,
+            'text-transform': 'uppercase',
+            'letter-spacing': '0.05em',
+            'font-weight': '600',
+          }}
+        >
+          Notifications
+        </div>
+        <button type="button" class="btn-secondary" style={secondaryButton} onClick={sendTest}>
+          Send test
+        </button>
+      </div>
+      <div
+        style={{
+          display: 'flex',
+          'flex-direction': 'column',
+          gap: '6px',
+          padding: '8px 12px',
+          'border-radius': '8px',
+          background: theme.bgInput,
+          border: `1px solid ${theme.border}`,
+        }}
+      >
+        <For each={NOTIFICATION_LABELS}>
+          {(item) => (
+            <label
+              style={{
+                display: 'flex',
+                'align-items': 'center',
+                gap: '10px',
+                cursor: 'pointer',
+                'font-size': '13px',
+                color: theme.fg,
+              }}
+            >
+              <input
+                type="checkbox"
+                checked={settings()?.[item.type] ?? true}
+                disabled={!settings()}
+                onChange={(e) => void toggle(item.type, e.currentTarget.checked)}
+                style={{ 'accent-color': theme.accent, cursor: 'pointer' }}
+              />
+              {item.label}
+            </label>
+          )}
+        </For>
+      </div>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+    </div>
+  );
+}
@@ -0,0 +1,136 @@
+import { For, createSignal, onCleanup, onMount } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { store, setActiveTask } from '../store/store';
+import { theme } from '../lib/theme';
+import type { PermissionRequestEvent } from '../ipc/types';
+
+/** Approve or deny agents' tool-permission dialogs without switching to their terminal. */
+export function PermissionRequests() {
+  const [requests, setRequests] = createSignal<PermissionRequestEvent[]>([]);
+
+  const drop = (requestId: string) =>
+    setRequests((list) => list.filter((r) => r.request_id !== requestId));
+
+  onMount(() => {
+    const offRequest = window.electron.ipcRenderer.on(IPC.AgentPermissionRequest, (msg) => {
+      const request = msg as PermissionRequestEvent;
+      // One dialog per agent at a time; a newer one replaces the old
+      setRequests((list) => [...list.filter((r) => r.agent_id !== request.agent_id), request]);
+    });
+    const offResolved = window.electron.ipcRenderer.on(IPC.AgentPermissionResolved, (msg) => {
+      drop((msg as { request_id: string }).request_id);
+    });
+    onCleanup(() => {
+      offRequest();
+      offResolved();
+    });
+  });
+
+  // Agents that exited can't answer anymore
+  const visible = () => requests().filter((r) => store.agents[r.agent_id]?.status === 'running');
+
+  function respond(request: PermissionRequestEvent, approve: boolean) {
+    drop(request.request_id);
+    invoke(IPC.RespondToPermission, { agentId: request.agent_id, approve }).catch((err) =>
+      console.error('Failed to answer permission request:', err),
+    );
+  }
+
+  const button = {
+    padding: '4px 12px',
+    'border-radius': '6px',
+    cursor: 'pointer',
+    'font-size': '12px',
+  } as const;
+
+  return (
+    <div
+      style={{
+        position: 'fixed',
+        right: '16px',
+        bottom: '16px',
+        display: 'flex',
+        'flex-direction': 'column',
+        gap: '8px',
+        width: '340px',
+        'z-index': '1500',
+      }}
+    >
+      <For each={visible()}>
+        {(request) => (
+          <div
+            style={{
+              display: 'flex',
+              'flex-direction': 'column',
+              gap: '6px',
+              background: theme.islandBg,
+              border: `1px solid ${theme.border}`,
+              'border-radius': '8px',
+              padding: '10px 12px',
+              'box-shadow': '0 4px 24px rgba(0,0,0,0.4)',
+              'font-size': '12px',
+              color: theme.fg,
+            }}
+          >
+            <button
+              type="button"
+              onClick={() => setActiveTask(request.task_id)}
+              style={{
+                background: 'transparent',
+                border: 'none',
+                padding: '0',
+                color: theme.fgMuted,
+                cursor: 'pointer',
+                'font-size': '11px',
+                'text-align': 'left',
+              }}
+            >
+              {store.tasks[request.task_id]?.name ?? request.agent} asks:
+            </button>
+            <div style={{ 'font-weight': '500' }}>{request.question}</div>
+            <div
+              style={{
+                'font-family': "'JetBrains Mono', monospace",
+                'font-size': '11px',
+                color: theme.fgSubtle,
+                'white-space': 'pre-wrap',
+                'word-break': 'break-word',
+              }}
+            >
+              {request.detail}
+            </div>
+            <div style={{ display: 'flex', gap: '6px', 'justify-content': 'flex-end' }}>
+              <button
+                type="button"
+                class="btn-secondary"
+                onClick={() => respond(request, false)}
+                style={{
+                  ...button,
+                  background: theme.bgInput,
+                  border: `1px solid ${theme.border}`,
+                  color: theme.fgMuted,
+                }}
+              >
+                Deny
+              </button>
+              <button
+                type="button"
+                class="btn-primary"
+                onClick={() => respond(request, true)}
+                style={{
+                  ...button,
+                  background: theme.accent,
+                  border: 'none',
+                  color: theme.accentText,
+                }}
+              >
+                Approve
+              </button>
+            </div>
+          </div>
+        )}
+      </For>
+    </div>
+  );
+}
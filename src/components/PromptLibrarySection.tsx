@@ -0,0 +1,156 @@
+import { For, Show, createSignal } from 'solid-js';
+import { theme } from '../lib/theme';
+import { store, savePromptSnippet, deletePromptSnippet } from '../store/store';
+import { inputStyle, primaryButton, secondaryButton } from './CustomAgentsSection';
+import type { PromptSnippet } from '../ipc/types';
+
+/** Settings section for saved prompts that can be sent to any task's agent. */
+export function PromptLibrarySection() {
+  // undefined: form closed; null: new snippet; otherwise the id being edited
+  const [editing, setEditing] = createSignal<string | null | undefined>(undefined);
+  const [name, setName] = createSignal('');
+  const [text, setText] = createSignal('');
+  const [error, setError] = createSignal('');
+
+  function openForm(snippet?: PromptSnippet) {
+    setEditing(snippet?.id ?? null);
+    setName(snippet?.name ?? '');
+    setText(snippet?.text ?? '');
+    setError('');
+  }
+
+  async function save() {
+    setError('');
+    try {
+      await savePromptSnippet({ id: editing() ?? undefined, name: name(), text: text() });
+      setEditing(undefined);
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+        <div
+          style={{
+            flex: '1',
+            'font-size': '11px',
+            color: theme.fgMuted,
+            'text-transform': 'uppercase',
+            'letter-spacing': '0.05em',
+            'font-weight': '600',
+          }}
+        >
+          Prompt Library
+        </div>
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => openForm()}
+        >
+          Add prompt
+        </button>
+      </div>
+      <For each={store.promptSnippets}>
+        {(snippet) => (
+          <div
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '10px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <span style={{ 'font-size': '13px', color: theme.fg, 'flex-shrink': '0' }}>
+              {snippet.name}
+            </span>
+            <span
+              style={{
+                flex: '1',
+                'font-size': '11px',
+                color: theme.fgSubtle,
+                overflow: 'hidden',
+                'text-overflow': 'ellipsis',
+                'white-space': 'nowrap',
+              }}
+            >
+              {snippet.text}
+            </span>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => openForm(snippet)}
+            >
+              Edit
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => deletePromptSnippet(snippet.id).catch((err) => setError(String(err)))}
+            >
+              Delete
+            </button>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <Show when={editing() !== undefined}>
+        <div
+          style={{
+            display: 'flex',
+            'flex-direction': 'column',
+            gap: '8px',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            style={inputStyle}
+            value={name()}
+            placeholder="Write tests"
+            onInput={(e) => setName(e.currentTarget.value)}
+          />
+          <textarea
+            style={{ ...inputStyle, resize: 'vertical' }}
+            rows={4}
+            value={text()}
+            placeholder="Add tests for the files changed in {{branch}}:&#10;{{changed_files}}"
+            onInput={(e) => setText(e.currentTarget.value)}
+          />
+          <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+            {'{{title}}, {{branch}} and {{changed_files}} are filled in from the task; ' +
+              'prompts with other {{variables}} open in the prompt box to be completed'}
+          </span>
+          <div style={{ display: 'flex', gap: '8px' }}>
+            <button
+              type="button"
+              class="btn-primary"
+              style={primaryButton}
+              onClick={() => void save()}
+            >
+              {editing() ? 'Save' : 'Add'}
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => setEditing(undefined)}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </Show>
+    </div>
+  );
+}
@@ -0,0 +1,142 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import { inputStyle, primaryButton, secondaryButton } from './CustomAgentsSection';
+
+/** Settings section for API tokens kept in the OS keychain; values are write-only here. */
+export function SecretsSection() {
+  const [adding, setAdding] = createSignal(false);
+  const [name, setName] = createSignal('');
+  const [value, setValue] = createSignal('');
+  const [error, setError] = createSignal('');
+  const [names, { refetch }] = createResource(() =>
+    invoke<string[]>(IPC.ListSecrets).catch((err) => {
+      setError(String(err));
+      return [];
+    }),
+  );
+
+  async function run(action: () => Promise<unknown>): Promise<boolean> {
+    setError('');
+    try {
+      await action();
+      await refetch();
+      return true;
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+      return false;
+    }
+  }
+
+  async function save() {
+    const ok = await run(() => invoke(IPC.SetSecret, { name: name().trim(), value: value() }));
+    if (!ok) return;
+    setAdding(false);
+    setName('');
+    setValue('');
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div style={{ display: 'flex', 'align-items': 'center', gap: '10px' }}>
+        <div
+          style={{
+            flex: '1',
+            'font-size': '11px',
+            color: theme.fgMuted,
+            'text-transform': 'uppercase',
+            'letter-spacing': '0.05em',
+            'font-weight': '600',
+          }}
+        >
+          Secrets
+        </div>
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => setAdding(true)}
+        >
+          Add secret
+        </button>
+      </div>
+      <For each={names()}>
+        {(secret) => (
+          <div
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '10px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <span style={{ flex: '1', 'font-size': '13px', color: theme.fg }}>{secret}</span>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => void run(() => invoke(IPC.DeleteSecret, { name: secret }))}
+            >
+              Delete
+            </button>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <Show when={adding()}>
+        <div
+          style={{
+            display: 'flex',
+            'flex-direction': 'column',
+            gap: '8px',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            style={inputStyle}
+            value={name()}
+            placeholder="anthropic-api-key"
+            onInput={(e) => setName(e.currentTarget.value)}
+          />
+          <input
+            type="password"
+            style={inputStyle}
+            value={value()}
+            placeholder="Value"
+            onInput={(e) => setValue(e.currentTarget.value)}
+          />
+          <div style={{ display: 'flex', gap: '8px' }}>
+            <button
+              type="button"
+              class="btn-primary"
+              style={primaryButton}
+              disabled={!name().trim() || !value()}
+              onClick={() => void save()}
+            >
+              Save
+            </button>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              onClick={() => setAdding(false)}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        Use secret:&lt;name&gt; as a task or agent env value to pass a secret to the agent
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,15 @@
 import { For, Show, createMemo } from 'solid-js';
 import { Dialog } from './Dialog';
+import { CustomAgentsSection } from './CustomAgentsSection';
+import { DefaultsSection } from './DefaultsSection';
+import { AgentProfilesSection } from './AgentProfilesSection';
+import { PromptLibrarySection } from './PromptLibrarySection';
+import { NotificationsSection } from './NotificationsSection';
+import { SecretsSection } from './SecretsSection';
+import { EnvProfilesSection } from './EnvProfilesSection';
+import { StateBackupsSection } from './StateBackupsSection';
+import { DiagnosticsSection } from './DiagnosticsSection';
+import { WorkspaceSection } from './WorkspaceSection';
 import { getAvailableTerminalFonts, getTerminalFontFamily, LIGATURE_FONTS } from '../lib/fonts';
 import { LOOK_PRESETS } from '../lib/look';
 import { theme } from '../lib/theme';
@@ -8,6 +18,9 @@ import {
   setTerminalFont,
   setThemePreset,
   setAutoTrustFolders,
+  setAgentTaskNames,
+  setKeepAgentsRunning,
+  setRecordSessions,
   setInactiveColumnOpacity,
 } from '../store/store';
 import { mod } from '../lib/platform';
@@ -149,8 +162,105 @@ export function SettingsDialog(props: SettingsDialogProps) {
             </span>
           </div>
         </label>
+        <label
+          style={{
+            display: 'flex',
+            'align-items': 'center',
+            gap: '10px',
+            cursor: 'pointer',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            background: theme.bgInput,
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            type="checkbox"
+            checked={store.agentTaskNames}
+            onChange={(e) => setAgentTaskNames(e.currentTarget.checked)}
+            style={{ 'accent-color': theme.accent, cursor: 'pointer' }}
+          />
+          <div style={{ display: 'flex', 'flex-direction': 'column', gap: '2px' }}>
+            <span style={{ 'font-size': '13px', color: theme.fg }}>Agent-written task names</span>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+              Ask the selected agent to title untitled tasks from their prompt
+            </span>
+          </div>
+        </label>
+        <label
+          style={{
+            display: 'flex',
+            'align-items': 'center',
+            gap: '10px',
+            cursor: 'pointer',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            background: theme.bgInput,
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            type="checkbox"
+            checked={store.keepAgentsRunning}
+            onChange={(e) => setKeepAgentsRunning(e.currentTarget.checked)}
+            style={{ 'accent-color': theme.accent, cursor: 'pointer' }}
+          />
+          <div style={{ display: 'flex', 'flex-direction': 'column', gap: '2px' }}>
+            <span style={{ 'font-size': '13px', color: theme.fg }}>
+              Keep agents running on quit
+            </span>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+              New agents outlive the app and are reconnected when it starts again
+            </span>
+          </div>
+        </label>
+        <label
+          style={{
+            display: 'flex',
+            'align-items': 'center',
+            gap: '10px',
+            cursor: 'pointer',
+            padding: '8px 12px',
+            'border-radius': '8px',
+            background: theme.bgInput,
+            border: `1px solid ${theme.border}`,
+          }}
+        >
+          <input
+            type="checkbox"
+            checked={store.recordSessions}
+            onChange={(e) => setRecordSessions(e.currentTarget.checked)}
+            style={{ 'accent-color': theme.accent, cursor: 'pointer' }}
+          />
+          <div style={{ display: 'flex', 'flex-direction': 'column', gap: '2px' }}>
+            <span style={{ 'font-size': '13px', color: theme.fg }}>Record agent sessions</span>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+              Save the terminal output of new agents so it can be replayed or exported
+            </span>
+          </div>
+        </label>
       </div>
 
+      <DefaultsSection />
+
+      <CustomAgentsSection />
+
+      <AgentProfilesSection />
+
+      <PromptLibrarySection />
+
+      <NotificationsSection />
+
+      <SecretsSection />
+
+      <EnvProfilesSection />
+
+      <WorkspaceSection />
+
+      <StateBackupsSection />
+
+      <DiagnosticsSection />
+
       <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
         <div
           style={{
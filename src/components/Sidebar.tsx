@@ -745,7 +745,38 @@ function TaskRow(props: TaskRowProps) {
                 {t().branchName}
               </span>
             </Show>
-            <span style={{ overflow: 'hidden', 'text-overflow': 'ellipsis' }}>{t().name}</span>
+            <Show when={t().priority === 'high' || t().priority === 'urgent'}>
+              <span
+                title={`${t().priority} priority`}
+                style={{
+                  'font-size': sf(10),
+                  'font-weight': '700',
+                  color: t().priority === 'urgent' ? theme.error : theme.warning,
+                  'flex-shrink': '0',
+                }}
+              >
+                {t().priority === 'urgent' ? '!!' : '!'}
+              </span>
+            </Show>
+            <span
+              title={t().labels?.length ? t().labels?.join(', ') : undefined}
+              style={{ overflow: 'hidden', 'text-overflow': 'ellipsis' }}
+            >
+              {t().name}
+            </span>
+            {/* The dot already shows a running agent */}
+            <Show when={t().lifecycle !== undefined && t().lifecycle !== 'running'}>
+              <span
+                style={{
+                  'margin-left': 'auto',
+                  'font-size': sf(10),
+                  color: t().lifecycle === 'failed' ? theme.error : theme.fgSubtle,
+                  'flex-shrink': '0',
+                }}
+              >
+                {t().lifecycle}
+              </span>
+            </Show>
           </div>
         </>
       )}
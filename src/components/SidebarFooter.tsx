@@ -1,12 +1,33 @@
-import { createMemo } from 'solid-js';
-import { getCompletedTasksTodayCount, getMergedLineTotals, toggleHelpDialog } from '../store/store';
+import { Show, createMemo, createResource, onCleanup } from 'solid-js';
+import {
+  store,
+  getCompletedTasksTodayCount,
+  getMergedLineTotals,
+  toggleHelpDialog,
+} from '../store/store';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { formatMetrics } from '../lib/metrics';
 import { theme } from '../lib/theme';
 import { sf } from '../lib/fontScale';
 import { alt, mod } from '../lib/platform';
+import type { MetricsReport } from '../ipc/types';
+
+const REPORT_REFRESH_MS = 60_000;
 
 export function SidebarFooter() {
   const completedTasksToday = createMemo(() => getCompletedTasksTodayCount());
   const mergedLines = createMemo(() => getMergedLineTotals());
+  // Time and spend across the open tasks
+  const [report, { refetch: refetchReport }] = createResource(
+    () => store.taskOrder.join(','),
+    () =>
+      invoke<MetricsReport>(IPC.GetMetricsReport, { taskIds: [...store.taskOrder] }).catch(
+        () => null,
+      ),
+  );
+  const reportTimer = setInterval(() => refetchReport(), REPORT_REFRESH_MS);
+  onCleanup(() => clearInterval(reportTimer));
 
   return (
     <>
@@ -82,6 +103,34 @@ export function SidebarFooter() {
             <span style={{ color: theme.error }}>-{mergedLines().removed.toLocaleString()}</span>
           </span>
         </div>
+        <Show when={report()?.totals.sessions ? report()?.totals : null}>
+          {(totals) => (
+            <div
+              style={{
+                display: 'flex',
+                'align-items': 'center',
+                'justify-content': 'space-between',
+                background: theme.bgInput,
+                border: `1px solid ${theme.border}`,
+                'border-radius': '8px',
+                padding: '8px 10px',
+                'font-size': sf(11),
+                color: theme.fgMuted,
+              }}
+            >
+              <span>Agent time</span>
+              <span
+                style={{
+                  color: theme.fg,
+                  'font-weight': '600',
+                  'font-variant-numeric': 'tabular-nums',
+                }}
+              >
+                {formatMetrics(totals())}
+              </span>
+            </div>
+          )}
+        </Show>
       </div>
 
       {/* Tips */}
@@ -0,0 +1,87 @@
+import { For, Show, createResource, createSignal } from 'solid-js';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { theme } from '../lib/theme';
+import { restoreStateBackup } from '../store/store';
+import { secondaryButton } from './CustomAgentsSection';
+import type { StateBackup } from '../ipc/types';
+
+/** Settings section listing saved copies of the app state, each restorable. */
+export function StateBackupsSection() {
+  const [error, setError] = createSignal('');
+  const [backups] = createResource(() =>
+    invoke<StateBackup[]>(IPC.ListStateBackups).catch((err) => {
+      setError(String(err));
+      return [];
+    }),
+  );
+
+  async function restore(backup: StateBackup) {
+    const when = new Date(backup.saved_at).toLocaleString();
+    if (!window.confirm(`Restore the tasks and layout saved at ${when}? The app will reload.`)) {
+      return;
+    }
+    setError('');
+    try {
+      await restoreStateBackup(backup.index);
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+    }
+  }
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        State Backups
+      </div>
+      <For
+        each={backups()}
+        fallback={<div style={{ 'font-size': '12px', color: theme.fgSubtle }}>No backups yet</div>}
+      >
+        {(backup) => (
+          <div
+            style={{
+              display: 'flex',
+              'align-items': 'center',
+              gap: '10px',
+              padding: '8px 12px',
+              'border-radius': '8px',
+              background: theme.bgInput,
+              border: `1px solid ${theme.border}`,
+            }}
+          >
+            <span style={{ flex: '1', 'font-size': '13px', color: theme.fg }}>
+              {new Date(backup.saved_at).toLocaleString()}
+            </span>
+            <span style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+              {backup.valid ? `${Math.ceil(backup.size / 1024)} KB` : 'unreadable'}
+            </span>
+            <button
+              type="button"
+              class="btn-secondary"
+              style={secondaryButton}
+              disabled={!backup.valid}
+              onClick={() => void restore(backup)}
+            >
+              Restore
+            </button>
+          </div>
+        )}
+      </For>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        A copy is kept at most every 10 minutes; restoring keeps the current state as a backup
+      </div>
+    </div>
+  );
+}
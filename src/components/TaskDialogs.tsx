@@ -1,14 +1,21 @@
-import { Show, For, createSignal, createResource, createEffect } from 'solid-js';
+import { Show, For, createSignal, createResource, createEffect, onCleanup } from 'solid-js';
 import { invoke } from '../lib/ipc';
 import { IPC } from '../../electron/ipc/channels';
-import { store, closeTask, mergeTask, pushTask, getProject } from '../store/store';
+import { store, closeTask, forkTask, mergeTask, pushTask, getProject } from '../store/store';
 import { sendPrompt } from '../store/tasks';
 import { ConfirmDialog } from './ConfirmDialog';
 import { ChangedFilesList } from './ChangedFilesList';
 import { DiffViewerDialog } from './DiffViewerDialog';
 import { theme } from '../lib/theme';
 import type { Task } from '../store/types';
-import type { ChangedFile, MergeStatus, WorktreeStatus } from '../ipc/types';
+import type {
+  BranchLog,
+  ChangedFile,
+  GitHookOutputEvent,
+  MergeStatus,
+  SigningConfig,
+  WorktreeStatus,
+} from '../ipc/types';
 
 interface TaskDialogsProps {
   task: Task;
@@ -20,11 +27,17 @@ interface TaskDialogsProps {
   showPushConfirm: boolean;
   onPushStart: () => void;
   onPushConfirmDone: (success: boolean) => void;
+  showForkConfirm: boolean;
+  onForkConfirmDone: () => void;
   diffFile: ChangedFile | null;
+  /** Worktree the diff file belongs to; defaults to the task's own. */
+  diffWorktreePath?: string;
   onDiffClose: () => void;
   onDiffFileClick: (file: ChangedFile) => void;
 }
 
+const HOOK_OUTPUT_LIMIT = 8000; // chars
+
 export function TaskDialogs(props: TaskDialogsProps) {
   // --- Merge state ---
   const [mergeError, setMergeError] = createSignal('');
@@ -35,17 +48,30 @@ export function TaskDialogs(props: TaskDialogsProps) {
   const [rebasing, setRebasing] = createSignal(false);
   const [rebaseError, setRebaseError] = createSignal('');
   const [rebaseSuccess, setRebaseSuccess] = createSignal(false);
+  const [operationBusy, setOperationBusy] = createSignal(false);
+  const [operationError, setOperationError] = createSignal('');
+
+  // --- Close state ---
+  const [archiveOnClose, setArchiveOnClose] = createSignal(false);
 
   // --- Push state ---
   const [pushError, setPushError] = createSignal('');
   const [pushing, setPushing] = createSignal(false);
 
+  // --- Fork state ---
+  const [forkBranch, setForkBranch] = createSignal('');
+  const [forkError, setForkError] = createSignal('');
+  const [forking, setForking] = createSignal(false);
+
+  // --- Hook output (streamed while a merge or push runs) ---
+  const [hookOutput, setHookOutput] = createSignal('');
+
   // --- Resources ---
   const [branchLog] = createResource(
     () => (props.showMergeConfirm ? props.task.worktreePath : null),
-    (path) => invoke<string>(IPC.GetBranchLog, { worktreePath: path }),
+    (path) => invoke<BranchLog>(IPC.GetBranchLog, { worktreePath: path, limit: 1000 }),
   );
-  const [worktreeStatus] = createResource(
+  const [worktreeStatus, { refetch: refetchWorktreeStatus }] = createResource(
     () =>
       props.showMergeConfirm || (props.showCloseConfirm && !props.task.directMode)
         ? props.task.worktreePath
@@ -56,9 +82,50 @@ export function TaskDialogs(props: TaskDialogsProps) {
     () => (props.showMergeConfirm ? props.task.worktreePath : null),
     (path) => invoke<MergeStatus>(IPC.CheckMergeStatus, { worktreePath: path }),
   );
+  const [signing] = createResource(
+    () => (props.showMergeConfirm ? getProject(props.task.projectId)?.path : undefined),
+    (repoPath) => invoke<SigningConfig>(IPC.GetSigningConfig, { repoPath }).catch(() => null),
+  );
+
+  createEffect(() => {
+    if (props.showPushConfirm) setHookOutput('');
+  });
+
+  createEffect(() => {
+    if (!props.showForkConfirm) return;
+    setForkBranch(`${props.task.branchName}-fork`);
+    setForkError('');
+  });
+
+  // Slow hooks would otherwise leave the dialog spinning without feedback
+  createEffect(() => {
+    if (!merging() && !pushing()) return;
+    const projectRoot = getProject(props.task.projectId)?.path;
+    const off = window.electron.ipcRenderer.on(IPC.GitHookOutput, (msg: unknown) => {
+      const output = msg as GitHookOutputEvent;
+      if (output.repo_path !== projectRoot) return;
+      setHookOutput((prev) => (prev + output.data).slice(-HOOK_OUTPUT_LIMIT));
+    });
+    onCleanup(off);
+  });
 
   const hasConflicts = () => (mergeStatus()?.conflicting_files.length ?? 0) > 0;
   const hasCommittedChangesToMerge = () => worktreeStatus()?.has_committed_changes ?? false;
+  const operationInProgress = () => worktreeStatus()?.operation ?? null;
+
+  async function resolveOperation(channel: IPC) {
+    setOperationBusy(true);
+    setOperationError('');
+    try {
+      await invoke(channel, { worktreePath: props.task.worktreePath });
+      refetchWorktreeStatus();
+      refetchMergeStatus();
+    } catch (err) {
+      setOperationError(String(err));
+    } finally {
+      setOperationBusy(false);
+    }
+  }
 
   // Reset all merge-related state when the dialog opens
   createEffect(() => {
@@ -67,8 +134,10 @@ export function TaskDialogs(props: TaskDialogsProps) {
       setSquash(false);
       setSquashMessage('');
       setMergeError('');
+      setHookOutput('');
       setRebaseError('');
       setRebaseSuccess(false);
+      setOperationError('');
       setMerging(false);
       setRebasing(false);
     }
@@ -171,14 +240,33 @@ export function TaskDialogs(props: TaskDialogsProps) {
                   </>
                 );
               })()}
+              <label
+                style={{
+                  display: 'flex',
+                  'align-items': 'center',
+                  gap: '8px',
+                  'margin-top': '12px',
+                  cursor: 'pointer',
+                  'font-size': '13px',
+                  color: theme.fg,
+                }}
+              >
+                <input
+                  type="checkbox"
+                  checked={archiveOnClose()}
+                  onChange={(e) => setArchiveOnClose(e.currentTarget.checked)}
+                  style={{ cursor: 'pointer' }}
+                />
+                Archive the branch, diff, recordings and plans first
+              </label>
             </Show>
           </div>
         }
-        confirmLabel={props.task.directMode ? 'Close' : 'Delete'}
-        danger={!props.task.directMode}
+        confirmLabel={props.task.directMode ? 'Close' : archiveOnClose() ? 'Archive' : 'Delete'}
+        danger={!props.task.directMode && !archiveOnClose()}
         onConfirm={() => {
           props.onCloseConfirmDone();
-          closeTask(props.task.id);
+          closeTask(props.task.id, { archive: !props.task.directMode && archiveOnClose() });
         }}
         onCancel={() => props.onCloseConfirmDone()}
       />
@@ -191,6 +279,63 @@ export function TaskDialogs(props: TaskDialogsProps) {
         autoFocusCancel
         message={
           <div>
+            <Show when={operationInProgress()}>
+              {(operation) => (
+                <div
+                  style={{
+                    'margin-bottom': '12px',
+                    'font-size': '12px',
+                    color: theme.error,
+                    background: `color-mix(in srgb, ${theme.error} 8%, transparent)`,
+                    padding: '8px 12px',
+                    'border-radius': '8px',
+                    border: `1px solid color-mix(in srgb, ${theme.error} 20%, transparent)`,
+                  }}
+                >
+                  <div style={{ 'font-weight': '600' }}>
+                    This worktree is in the middle of a {operation().replace('_', '-')}
+                    <Show when={(worktreeStatus()?.conflicted_files ?? 0) > 0}>
+                      {' '}with {worktreeStatus()?.conflicted_files} conflicted file
+                      {worktreeStatus()?.conflicted_files === 1 ? '' : 's'}
+                    </Show>
+                    . Finish or abort it before merging.
+                  </div>
+                  <div style={{ display: 'flex', gap: '8px', 'margin-top': '8px' }}>
+                    <For
+                      each={[
+                        { label: 'Continue', channel: IPC.ContinueWorktreeOperation },
+                        { label: 'Abort', channel: IPC.AbortWorktreeOperation },
+                      ]}
+                    >
+                      {(action) => (
+                        <button
+                          type="button"
+                          disabled={operationBusy()}
+                          onClick={() => resolveOperation(action.channel)}
+                          style={{
+                            padding: '4px 12px',
+                            background: theme.bgInput,
+                            border: `1px solid ${theme.border}`,
+                            'border-radius': '6px',
+                            color: theme.fg,
+                            cursor: operationBusy() ? 'not-allowed' : 'pointer',
+                            'font-size': '12px',
+                            opacity: operationBusy() ? '0.5' : '1',
+                          }}
+                        >
+                          {action.label}
+                        </button>
+                      )}
+                    </For>
+                  </div>
+                  <Show when={operationError()}>
+                    <div style={{ 'margin-top': '6px', 'word-break': 'break-word' }}>
+                      {operationError()}
+                    </div>
+                  </Show>
+                </div>
+              )}
+            </Show>
             <Show when={worktreeStatus()?.has_uncommitted_changes}>
               <div
                 style={{
@@ -372,13 +517,20 @@ export function TaskDialogs(props: TaskDialogsProps) {
             <p style={{ margin: '0 0 12px' }}>
               Merge <strong>{props.task.branchName}</strong> into main:
             </p>
-            <Show when={!branchLog.loading && branchLog()}>
+            <Show when={(props.task.linkedWorktrees?.length ?? 0) > 0}>
+              <p style={{ margin: '0 0 12px', 'font-size': '12px', color: theme.fgMuted }}>
+                Also lands the branch in{' '}
+                {(props.task.linkedWorktrees ?? [])
+                  .map((l) => getProject(l.projectId)?.name ?? l.projectId)
+                  .join(', ')}
+                . If any repository fails to merge, none are changed.
+              </p>
+            </Show>
+            <Show
+              when={!branchLog.loading && (branchLog()?.commits.length ?? 0) > 0 && branchLog()}
+            >
               {(log) => {
-                const commits = () =>
-                  log()
-                    .split('\n')
-                    .filter((l: string) => l.trim())
-                    .map((l: string) => l.replace(/^- /, ''));
+                const commits = () => log().commits.map((c) => c.subject);
                 return (
                   <div
                     style={{
@@ -449,6 +601,7 @@ export function TaskDialogs(props: TaskDialogsProps) {
               }}
             >
               <ChangedFilesList
+                taskId={props.task.id}
                 worktreePath={props.task.worktreePath}
                 isActive={props.showMergeConfirm}
                 onFileClick={props.onDiffFileClick}
@@ -491,7 +644,9 @@ export function TaskDialogs(props: TaskDialogsProps) {
                   const checked = e.currentTarget.checked;
                   setSquash(checked);
                   if (checked && !squashMessage()) {
-                    setSquashMessage(branchLog() ?? '');
+                    setSquashMessage(
+                      (branchLog()?.commits ?? []).map((c) => `- ${c.subject}`).join('\n'),
+                    );
                   }
                 }}
                 style={{ cursor: 'pointer' }}
@@ -520,6 +675,31 @@ export function TaskDialogs(props: TaskDialogsProps) {
                 }}
               />
             </Show>
+            <Show when={signing()?.enabled}>
+              <div style={{ 'margin-top': '12px', 'font-size': '12px', color: theme.fgMuted }}>
+                Merge commits are signed ({signing()?.format === 'ssh' ? 'SSH' : 'GPG'}
+                {signing()?.key ? ` key ${signing()?.key}` : ''}). Confirm any passphrase prompt
+                that appears.
+              </div>
+            </Show>
+            <Show when={hookOutput()}>
+              <pre
+                style={{
+                  'margin-top': '12px',
+                  'max-height': '160px',
+                  overflow: 'auto',
+                  'font-size': '11px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  color: theme.fgMuted,
+                  background: theme.bgInput,
+                  padding: '8px 12px',
+                  'border-radius': '8px',
+                  'white-space': 'pre-wrap',
+                }}
+              >
+                {hookOutput()}
+              </pre>
+            </Show>
             <Show when={mergeError()}>
               <div
                 style={{
@@ -537,13 +717,19 @@ export function TaskDialogs(props: TaskDialogsProps) {
             </Show>
           </div>
         }
-        confirmDisabled={merging() || hasConflicts() || !hasCommittedChangesToMerge()}
+        confirmDisabled={
+          merging() ||
+          hasConflicts() ||
+          !hasCommittedChangesToMerge() ||
+          operationInProgress() !== null
+        }
         confirmLoading={merging()}
         confirmLabel={merging() ? 'Merging...' : squash() ? 'Squash Merge' : 'Merge'}
         onConfirm={() => {
           const taskId = props.task.id;
           const onDone = props.onMergeConfirmDone;
           setMergeError('');
+          setHookOutput('');
           setMerging(true);
           void mergeTask(taskId, {
             squash: squash(),
@@ -580,6 +766,24 @@ export function TaskDialogs(props: TaskDialogsProps) {
             <p style={{ margin: '0 0 8px' }}>
               Push branch <strong>{props.task.branchName}</strong> to remote?
             </p>
+            <Show when={hookOutput()}>
+              <pre
+                style={{
+                  'margin-top': '12px',
+                  'max-height': '160px',
+                  overflow: 'auto',
+                  'font-size': '11px',
+                  'font-family': "'JetBrains Mono', monospace",
+                  color: theme.fgMuted,
+                  background: theme.bgInput,
+                  padding: '8px 12px',
+                  'border-radius': '8px',
+                  'white-space': 'pre-wrap',
+                }}
+              >
+                {hookOutput()}
+              </pre>
+            </Show>
             <Show when={pushError()}>
               <div
                 style={{
@@ -603,6 +807,7 @@ export function TaskDialogs(props: TaskDialogsProps) {
           const onStart = props.onPushStart;
           const onDone = props.onPushConfirmDone;
           setPushError('');
+          setHookOutput('');
           setPushing(true);
           onStart();
           void pushTask(taskId)
@@ -623,10 +828,73 @@ export function TaskDialogs(props: TaskDialogsProps) {
         }}
       />
 
+      {/* Fork Dialog */}
+      <ConfirmDialog
+        open={props.showForkConfirm}
+        title="Fork Task"
+        message={
+          <div>
+            <p style={{ margin: '0 0 8px' }}>
+              Start a new task from the current state of <strong>{props.task.branchName}</strong>,
+              uncommitted changes included. This task is left as it is.
+            </p>
+            <input
+              type="text"
+              value={forkBranch()}
+              onInput={(e) => setForkBranch(e.currentTarget.value)}
+              placeholder="New branch name"
+              style={{
+                width: '100%',
+                background: theme.bgInput,
+                border: `1px solid ${theme.border}`,
+                'border-radius': '8px',
+                padding: '8px 10px',
+                color: theme.fg,
+                'font-size': '12px',
+                'font-family': "'JetBrains Mono', monospace",
+                outline: 'none',
+                'box-sizing': 'border-box',
+              }}
+            />
+            <Show when={forkError()}>
+              <div
+                style={{
+                  'margin-top': '12px',
+                  'font-size': '12px',
+                  color: theme.error,
+                  background: `color-mix(in srgb, ${theme.error} 8%, transparent)`,
+                  padding: '8px 12px',
+                  'border-radius': '8px',
+                  border: `1px solid color-mix(in srgb, ${theme.error} 20%, transparent)`,
+                }}
+              >
+                {forkError()}
+              </div>
+            </Show>
+          </div>
+        }
+        confirmDisabled={forking() || !forkBranch().trim()}
+        confirmLoading={forking()}
+        confirmLabel={forking() ? 'Forking...' : 'Fork'}
+        onConfirm={() => {
+          const onDone = props.onForkConfirmDone;
+          setForkError('');
+          setForking(true);
+          void forkTask(props.task.id, forkBranch().trim())
+            .then(() => onDone())
+            .catch((err) => setForkError(String(err)))
+            .finally(() => setForking(false));
+        }}
+        onCancel={() => {
+          props.onForkConfirmDone();
+          setForkError('');
+        }}
+      />
+
       {/* Diff Viewer */}
       <DiffViewerDialog
         file={props.diffFile}
-        worktreePath={props.task.worktreePath}
+        worktreePath={props.diffWorktreePath ?? props.task.worktreePath}
         onClose={props.onDiffClose}
       />
     </>
@@ -1,5 +1,15 @@
-import { Show, For, createSignal, createEffect, onMount, onCleanup } from 'solid-js';
+import {
+  Show,
+  For,
+  createSignal,
+  createEffect,
+  createResource,
+  onMount,
+  onCleanup,
+} from 'solid-js';
 import { createStore } from 'solid-js/store';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
 import { revealItemInDir } from '../lib/shell';
 import {
   store,
@@ -9,6 +19,9 @@ import {
   restartAgent,
   updateTaskName,
   updateTaskNotes,
+  addChecklistItem,
+  toggleChecklistItem,
+  removeChecklistItem,
   spawnShellForTask,
   closeShell,
   setLastPrompt,
@@ -24,6 +37,14 @@ import {
   setTaskFocusedPanel,
   triggerFocus,
   clearPendingAction,
+  linkTaskToProject,
+  setTaskDependencies,
+  updateTaskMetadata,
+  startReview,
+  handoffTask,
+  sendPromptSnippet,
+  setPrefillPrompt,
+  showNotification,
 } from '../store/store';
 import { ResizablePanel, type PanelChild } from './ResizablePanel';
 import { EditableText, type EditableTextHandle } from './EditableText';
@@ -38,11 +59,26 @@ import { TaskDialogs } from './TaskDialogs';
 import { EditProjectDialog } from './EditProjectDialog';
 import { theme } from '../lib/theme';
 import { sf } from '../lib/fontScale';
+import { describeExit } from '../lib/exit-reason';
 import { mod } from '../lib/platform';
 import { extractLabel, consumePendingShellCommand } from '../lib/bookmarks';
 import { handleDragReorder } from '../lib/dragReorder';
+import { formatMetrics } from '../lib/metrics';
+import { expandAgentArgs } from '../lib/agent-args';
 import type { Task } from '../store/types';
-import type { ChangedFile } from '../ipc/types';
+import type { ChangedFile, TaskMetrics, TaskPriority } from '../ipc/types';
+
+const METRICS_REFRESH_MS = 30_000;
+
+const REVIEW_VERDICTS: Record<string, string> = {
+  approve: 'Approved',
+  request_changes: 'Changes requested',
+  comment: 'Reviewed',
+  none: 'Review failed',
+};
+
+/** Prompt-library variables the backend fills in from the task. */
+const BUILTIN_PROMPT_VARS = ['title', 'branch', 'changed_files'];
 
 interface TaskPanelProps {
   task: Task;
@@ -53,12 +89,27 @@ export function TaskPanel(props: TaskPanelProps) {
   const [showCloseConfirm, setShowCloseConfirm] = createSignal(false);
   const [showMergeConfirm, setShowMergeConfirm] = createSignal(false);
   const [showPushConfirm, setShowPushConfirm] = createSignal(false);
+  const [showForkConfirm, setShowForkConfirm] = createSignal(false);
   const [pushSuccess, setPushSuccess] = createSignal(false);
   const [pushing, setPushing] = createSignal(false);
   let pushSuccessTimer: ReturnType<typeof setTimeout> | undefined;
   onCleanup(() => clearTimeout(pushSuccessTimer));
   const [diffFile, setDiffFile] = createSignal<ChangedFile | null>(null);
+  const [diffWorktreePath, setDiffWorktreePath] = createSignal<string | undefined>();
+  const [showLinkMenu, setShowLinkMenu] = createSignal(false);
+  const linkableProjects = () =>
+    store.projects.filter(
+      (p) =>
+        p.id !== props.task.projectId &&
+        !(props.task.linkedWorktrees ?? []).some((l) => l.projectId === p.id),
+    );
   const [editingProjectId, setEditingProjectId] = createSignal<string | null>(null);
+  const [metrics, { refetch: refetchMetrics }] = createResource(
+    () => props.task.id,
+    (taskId) => invoke<TaskMetrics>(IPC.GetTaskMetrics, { taskId }).catch(() => null),
+  );
+  const metricsTimer = setInterval(() => refetchMetrics(), METRICS_REFRESH_MS);
+  onCleanup(() => clearInterval(metricsTimer));
   const [shellExits, setShellExits] = createStore<
     Record<string, { exitCode: number | null; signal: string | null }>
   >({});
@@ -151,6 +202,40 @@ export function TaskPanel(props: TaskPanelProps) {
 
   const firstAgentId = () => props.task.agentIds[0] ?? '';
 
+  const reviewStatus = () => {
+    if (props.task.reviewRunId) return 'Reviewing…';
+    const review = props.task.review;
+    return review ? `${REVIEW_VERDICTS[review.verdict ?? 'none']}: ${review.summary}` : '';
+  };
+
+  function requestReview() {
+    startReview(props.task.id, firstAgent()?.def.id ?? 'claude-code').catch((err) =>
+      showNotification(String(err).replace(/^Error: /, '')),
+    );
+  }
+
+  function handoffTo(defId: string) {
+    const def = store.availableAgents.find((d) => d.id === defId);
+    if (!def) return;
+    handoffTask(props.task.id, def).catch((err) =>
+      showNotification(String(err).replace(/^Error: /, '')),
+    );
+  }
+
+  function pickPromptSnippet(id: string) {
+    const snippet = store.promptSnippets.find((p) => p.id === id);
+    if (!snippet) return;
+    const vars = [...snippet.text.matchAll(/\{\{\s*([A-Za-z0-9_-]+)\s*\}\}/g)].map((m) => m[1]);
+    // Variables only the user can fill in: let them edit the prompt first
+    if (vars.some((v) => !BUILTIN_PROMPT_VARS.includes(v))) {
+      setPrefillPrompt(props.task.id, snippet.text);
+      return;
+    }
+    sendPromptSnippet(props.task.id, firstAgentId(), id).catch((err) =>
+      showNotification(String(err).replace(/^Error: /, '')),
+    );
+  }
+
   function handleTitleMouseDown(e: MouseEvent) {
     handleDragReorder(e, {
       itemId: props.task.id,
@@ -228,6 +313,15 @@ export function TaskPanel(props: TaskPanelProps) {
                 onClick={openMergeConfirm}
                 title="Merge into main"
               />
+              <IconButton
+                icon={
+                  <svg width="16" height="16" viewBox="0 0 16 16" fill="currentColor">
+                    <path d="M5 5.372v.878c0 .414.336.75.75.75h4.5a.75.75 0 0 0 .75-.75v-.878a2.25 2.25 0 1 1 1.5 0v.878a2.25 2.25 0 0 1-2.25 2.25h-1.5v2.128a2.251 2.251 0 1 1-1.5 0V8.5h-1.5A2.25 2.25 0 0 1 3.5 6.25v-.878a2.25 2.25 0 1 1 1.5 0ZM5 3.25a.75.75 0 1 0-1.5 0 .75.75 0 0 0 1.5 0Zm6.75.75a.75.75 0 1 0 0-1.5.75.75 0 0 0 0 1.5Zm-3 8.75a.75.75 0 1 0-1.5 0 .75.75 0 0 0 1.5 0Z" />
+                  </svg>
+                }
+                onClick={() => setShowForkConfirm(true)}
+                title="Fork task"
+              />
               <div style={{ position: 'relative', display: 'inline-flex' }}>
                 <Show
                   when={!pushing()}
@@ -418,6 +512,122 @@ export function TaskPanel(props: TaskPanelProps) {
               </span>
             </Show>
           </span>
+          <Show when={metrics()?.sessions ? metrics() : null}>
+            {(m) => (
+              <span
+                title={`${m().sessions} agent sessions, ${m().input_tokens} input tokens`}
+                style={{ 'margin-right': '12px' }}
+              >
+                {formatMetrics(m())}
+              </span>
+            )}
+          </Show>
+          <select
+            value={props.task.priority ?? 'normal'}
+            title="Priority"
+            onClick={(e) => e.stopPropagation()}
+            onChange={(e) =>
+              updateTaskMetadata(props.task.id, {
+                priority: e.currentTarget.value as TaskPriority,
+              }).catch((err) => console.error('Failed to set task priority:', err))
+            }
+            style={{
+              'margin-right': '12px',
+              background: 'transparent',
+              border: 'none',
+              color:
+                props.task.priority === 'urgent' || props.task.priority === 'high'
+                  ? theme.warning
+                  : 'inherit',
+              'font-family': 'inherit',
+              'font-size': 'inherit',
+              cursor: 'pointer',
+            }}
+          >
+            <option value="low">low</option>
+            <option value="normal">normal</option>
+            <option value="high">high</option>
+            <option value="urgent">urgent</option>
+          </select>
+          <Show when={firstAgent()}>
+            {(a) => (
+              <select
+                value=""
+                title="Stop the agent and let another one take over the task"
+                onClick={(e) => e.stopPropagation()}
+                onChange={(e) => {
+                  handoffTo(e.currentTarget.value);
+                  e.currentTarget.value = '';
+                }}
+                style={{
+                  'margin-right': '12px',
+                  background: 'transparent',
+                  border: 'none',
+                  color: 'inherit',
+                  'font-family': 'inherit',
+                  'font-size': 'inherit',
+                  cursor: 'pointer',
+                }}
+              >
+                <option value="">hand off…</option>
+                <For each={store.availableAgents.filter((d) => d.id !== a().def.id)}>
+                  {(def) => <option value={def.id}>{def.name}</option>}
+                </For>
+              </select>
+            )}
+          </Show>
+          <Show when={firstAgent() && store.promptSnippets.length > 0}>
+            <select
+              value=""
+              title="Send a prompt from the library"
+              onClick={(e) => e.stopPropagation()}
+              onChange={(e) => {
+                pickPromptSnippet(e.currentTarget.value);
+                e.currentTarget.value = '';
+              }}
+              style={{
+                'margin-right': '12px',
+                background: 'transparent',
+                border: 'none',
+                color: 'inherit',
+                'font-family': 'inherit',
+                'font-size': 'inherit',
+                cursor: 'pointer',
+              }}
+            >
+              <option value="">prompts…</option>
+              <For each={store.promptSnippets}>
+                {(snippet) => <option value={snippet.id}>{snippet.name}</option>}
+              </For>
+            </select>
+          </Show>
+          <input
+            type="text"
+            value={(props.task.labels ?? []).join(', ')}
+            placeholder="labels"
+            title="Labels, comma separated"
+            onClick={(e) => e.stopPropagation()}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') e.currentTarget.blur();
+            }}
+            onChange={(e) => {
+              const input = e.currentTarget;
+              const labels = input.value.split(',');
+              updateTaskMetadata(props.task.id, { labels }).catch((err) => {
+                input.value = (props.task.labels ?? []).join(', ');
+                console.error('Failed to set task labels:', err);
+              });
+            }}
+            style={{
+              width: '120px',
+              'margin-right': '12px',
+              background: 'transparent',
+              border: 'none',
+              color: 'inherit',
+              'font-family': 'inherit',
+              'font-size': 'inherit',
+            }}
+          />
           <span
             style={{ display: 'inline-flex', 'align-items': 'center', gap: '4px', opacity: 0.6 }}
           >
@@ -455,7 +665,13 @@ export function TaskPanel(props: TaskPanelProps) {
                 <ScalablePanel panelId={`${props.task.id}:notes`}>
                   <div
                     class="focusable-panel"
-                    style={{ width: '100%', height: '100%' }}
+                    style={{
+                      width: '100%',
+                      height: '100%',
+                      display: 'flex',
+                      'flex-direction': 'column',
+                      background: theme.taskPanelBg,
+                    }}
                     onClick={() => setTaskFocusedPanel(props.task.id, 'notes')}
                   >
                     <textarea
@@ -465,7 +681,8 @@ export function TaskPanel(props: TaskPanelProps) {
                       placeholder="Notes..."
                       style={{
                         width: '100%',
-                        height: '100%',
+                        flex: '1',
+                        'min-height': '0',
                         background: theme.taskPanelBg,
                         border: 'none',
                         padding: '6px 8px',
@@ -476,6 +693,110 @@ export function TaskPanel(props: TaskPanelProps) {
                         outline: 'none',
                       }}
                     />
+                    <div
+                      style={{
+                        'flex-shrink': '0',
+                        'max-height': '50%',
+                        overflow: 'auto',
+                        padding: '4px 8px',
+                        'border-top': `1px solid ${theme.border}`,
+                        'font-size': sf(11),
+                        color: theme.fg,
+                      }}
+                    >
+                      <div style={{ display: 'flex', 'align-items': 'center', gap: '6px' }}>
+                        <span
+                          style={{
+                            flex: '1',
+                            color: theme.fgSubtle,
+                            overflow: 'hidden',
+                            'text-overflow': 'ellipsis',
+                            'white-space': 'nowrap',
+                          }}
+                          title={props.task.review?.summary}
+                        >
+                          {reviewStatus()}
+                        </span>
+                        <button
+                          type="button"
+                          disabled={!!props.task.reviewRunId}
+                          onClick={requestReview}
+                          style={{
+                            background: 'transparent',
+                            border: 'none',
+                            color: theme.accent,
+                            cursor: props.task.reviewRunId ? 'default' : 'pointer',
+                            padding: '0',
+                            'font-size': sf(11),
+                          }}
+                          title="Have the task's agent review the diff and add its findings below"
+                        >
+                          Review changes
+                        </button>
+                      </div>
+                      <For each={props.task.checklist ?? []}>
+                        {(item) => (
+                          <label
+                            style={{
+                              display: 'flex',
+                              'align-items': 'center',
+                              gap: '6px',
+                              cursor: 'pointer',
+                            }}
+                          >
+                            <input
+                              type="checkbox"
+                              checked={item.done}
+                              onChange={() => toggleChecklistItem(props.task.id, item.id)}
+                            />
+                            <span
+                              style={{
+                                flex: '1',
+                                color: item.done ? theme.fgSubtle : theme.fg,
+                                'text-decoration': item.done ? 'line-through' : 'none',
+                              }}
+                            >
+                              {item.text}
+                            </span>
+                            <button
+                              type="button"
+                              title="Remove item"
+                              onClick={(e) => {
+                                e.preventDefault();
+                                removeChecklistItem(props.task.id, item.id);
+                              }}
+                              style={{
+                                background: 'transparent',
+                                border: 'none',
+                                color: theme.fgSubtle,
+                                cursor: 'pointer',
+                                padding: '0 2px',
+                              }}
+                            >
+                              &times;
+                            </button>
+                          </label>
+                        )}
+                      </For>
+                      <input
+                        type="text"
+                        placeholder="+ Review checklist item"
+                        onKeyDown={(e) => {
+                          if (e.key !== 'Enter') return;
+                          addChecklistItem(props.task.id, e.currentTarget.value);
+                          e.currentTarget.value = '';
+                        }}
+                        style={{
+                          width: '100%',
+                          background: 'transparent',
+                          border: 'none',
+                          padding: '2px 0',
+                          color: theme.fg,
+                          'font-size': sf(11),
+                          outline: 'none',
+                        }}
+                      />
+                    </div>
                   </div>
                 </ScalablePanel>
               ),
@@ -508,14 +829,113 @@ export function TaskPanel(props: TaskPanelProps) {
                       }}
                     >
                       Changed Files
+                      <Show when={!props.task.directMode && linkableProjects().length > 0}>
+                        <span style={{ position: 'relative', float: 'right' }}>
+                          <button
+                            onClick={(e) => {
+                              e.stopPropagation();
+                              setShowLinkMenu(!showLinkMenu());
+                            }}
+                            title="Give this task a worktree in another project"
+                            style={{
+                              background: 'transparent',
+                              border: 'none',
+                              color: theme.fgMuted,
+                              cursor: 'pointer',
+                              'font-size': sf(10),
+                              padding: '0',
+                            }}
+                          >
+                            + repo
+                          </button>
+                          <Show when={showLinkMenu()}>
+                            <div
+                              style={{
+                                position: 'absolute',
+                                right: '0',
+                                top: '100%',
+                                'z-index': '10',
+                                background: theme.bgElevated,
+                                border: `1px solid ${theme.border}`,
+                                'border-radius': '6px',
+                                padding: '4px',
+                                'text-transform': 'none',
+                                'letter-spacing': 'normal',
+                              }}
+                            >
+                              <For each={linkableProjects()}>
+                                {(project) => (
+                                  <button
+                                    onClick={(e) => {
+                                      e.stopPropagation();
+                                      setShowLinkMenu(false);
+                                      linkTaskToProject(props.task.id, project.id).catch((err) =>
+                                        console.error('Failed to link project:', err),
+                                      );
+                                    }}
+                                    style={{
+                                      display: 'block',
+                                      width: '100%',
+                                      'text-align': 'left',
+                                      background: 'transparent',
+                                      border: 'none',
+                                      color: theme.fg,
+                                      cursor: 'pointer',
+                                      'font-size': sf(11),
+                                      padding: '4px 8px',
+                                      'white-space': 'nowrap',
+                                    }}
+                                  >
+                                    {project.name}
+                                  </button>
+                                )}
+                              </For>
+                            </div>
+                          </Show>
+                        </span>
+                      </Show>
                     </div>
-                    <div style={{ flex: '1', overflow: 'hidden' }}>
+                    <div
+                      style={{
+                        flex: '1',
+                        overflow: props.task.linkedWorktrees?.length ? 'auto' : 'hidden',
+                      }}
+                    >
                       <ChangedFilesList
+                        taskId={props.task.id}
                         worktreePath={props.task.worktreePath}
                         isActive={props.isActive}
-                        onFileClick={setDiffFile}
+                        onFileClick={(file) => {
+                          setDiffWorktreePath(undefined);
+                          setDiffFile(file);
+                        }}
                         ref={(el) => (changedFilesRef = el)}
                       />
+                      <For each={props.task.linkedWorktrees ?? []}>
+                        {(linked) => (
+                          <>
+                            <div
+                              style={{
+                                padding: '4px 8px',
+                                'font-size': sf(10),
+                                color: theme.fgMuted,
+                                'border-top': `1px solid ${theme.border}`,
+                              }}
+                            >
+                              {getProject(linked.projectId)?.name ?? linked.projectId}
+                            </div>
+                            <ChangedFilesList
+                              taskId={`${props.task.id}:${linked.projectId}`}
+                              worktreePath={linked.worktreePath}
+                              isActive={props.isActive}
+                              onFileClick={(file) => {
+                                setDiffWorktreePath(linked.worktreePath);
+                                setDiffFile(file);
+                              }}
+                            />
+                          </>
+                        )}
+                      </For>
                     </div>
                   </div>
                 </ScalablePanel>
@@ -818,7 +1238,7 @@ export function TaskPanel(props: TaskPanelProps) {
                           gap: '8px',
                         }}
                       >
-                        <span>Process exited ({a().exitCode ?? '?'})</span>
+                        <span>{describeExit(a().exitReason, a().exitCode)}</span>
                         <button
                           onClick={(e) => {
                             e.stopPropagation();
@@ -857,31 +1277,87 @@ export function TaskPanel(props: TaskPanelProps) {
                         </Show>
                       </div>
                     </Show>
-                    <Show when={`${a().id}:${a().generation}`} keyed>
-                      <TerminalView
-                        taskId={props.task.id}
-                        agentId={a().id}
-                        isFocused={
-                          props.isActive && store.focusedPanel[props.task.id] === 'ai-terminal'
-                        }
-                        command={a().def.command}
-                        args={[
-                          ...(a().resumed && a().def.resume_args?.length
-                            ? (a().def.resume_args ?? [])
-                            : a().def.args),
-                          ...(props.task.skipPermissions && a().def.skip_permissions_args?.length
-                            ? (a().def.skip_permissions_args ?? [])
-                            : []),
-                        ]}
-                        cwd={props.task.worktreePath}
-                        onExit={(code) => markAgentExited(a().id, code)}
-                        onData={(data) => markAgentOutput(a().id, data, props.task.id)}
-                        onPromptDetected={(text) => setLastPrompt(props.task.id, text)}
-                        onReady={(focusFn) =>
-                          registerFocusFn(`${props.task.id}:ai-terminal`, focusFn)
-                        }
-                        fontSize={Math.round(13 * getFontScale(`${props.task.id}:ai-terminal`))}
-                      />
+                    <Show
+                      when={!props.task.waitingOn?.length}
+                      fallback={
+                        <div
+                          style={{
+                            height: '100%',
+                            display: 'flex',
+                            'flex-direction': 'column',
+                            'align-items': 'center',
+                            'justify-content': 'center',
+                            gap: '10px',
+                            color: theme.fgMuted,
+                            'font-size': sf(12),
+                          }}
+                        >
+                          <span>
+                            Starts after{' '}
+                            {(props.task.waitingOn ?? [])
+                              .map((id) => store.tasks[id]?.name ?? 'a closed task')
+                              .join(', ')}{' '}
+                            lands
+                          </span>
+                          <button
+                            type="button"
+                            onClick={() =>
+                              setTaskDependencies(props.task.id, []).catch((err) =>
+                                console.error('Failed to clear task dependencies:', err),
+                              )
+                            }
+                            style={{
+                              padding: '4px 12px',
+                              background: theme.bgInput,
+                              border: `1px solid ${theme.border}`,
+                              'border-radius': '6px',
+                              color: theme.fg,
+                              cursor: 'pointer',
+                              'font-size': sf(11),
+                            }}
+                          >
+                            Start now
+                          </button>
+                        </div>
+                      }
+                    >
+                      <Show when={`${a().id}:${a().generation}`} keyed>
+                        <TerminalView
+                          taskId={props.task.id}
+                          agentId={a().id}
+                          isFocused={
+                            props.isActive && store.focusedPanel[props.task.id] === 'ai-terminal'
+                          }
+                          command={a().def.command}
+                          args={expandAgentArgs(
+                            [
+                              ...(a().resumed && a().def.resume_args?.length
+                                ? (a().def.resume_args ?? [])
+                                : a().def.args),
+                              ...(props.task.skipPermissions &&
+                              a().def.skip_permissions_args?.length
+                                ? (a().def.skip_permissions_args ?? [])
+                                : []),
+                            ],
+                            props.task,
+                          )}
+                          cwd={props.task.worktreePath}
+                          env={{ ...a().def.env, ...props.task.env }}
+                          agentDefId={a().def.id}
+                          profileId={props.task.agentProfileId}
+                          queueable
+                          detachable={store.keepAgentsRunning}
+                          record={store.recordSessions}
+                          reattach={a().reattach}
+                          onExit={(code) => markAgentExited(a().id, code)}
+                          onData={(data) => markAgentOutput(a().id, data, props.task.id)}
+                          onPromptDetected={(text) => setLastPrompt(props.task.id, text)}
+                          onReady={(focusFn) =>
+                            registerFocusFn(`${props.task.id}:ai-terminal`, focusFn)
+                          }
+                          fontSize={Math.round(13 * getFontScale(`${props.task.id}:ai-terminal`))}
+                        />
+                      </Show>
                     </Show>
                   </>
                 )}
@@ -1027,9 +1503,15 @@ export function TaskPanel(props: TaskPanelProps) {
             pushSuccessTimer = setTimeout(() => setPushSuccess(false), 3000);
           }
         }}
+        showForkConfirm={showForkConfirm()}
+        onForkConfirmDone={() => setShowForkConfirm(false)}
         diffFile={diffFile()}
+        diffWorktreePath={diffWorktreePath()}
         onDiffClose={() => setDiffFile(null)}
-        onDiffFileClick={setDiffFile}
+        onDiffFileClick={(file) => {
+          setDiffWorktreePath(undefined);
+          setDiffFile(file);
+        }}
       />
       <EditProjectDialog project={editingProject()} onClose={() => setEditingProjectId(null)} />
     </div>
@@ -11,7 +11,7 @@ import { matchesGlobalShortcut } from '../lib/shortcuts';
 import { isMac } from '../lib/platform';
 import { store } from '../store/store';
 import { registerTerminal, unregisterTerminal, markDirty } from '../lib/terminalFitManager';
-import type { PtyOutput } from '../ipc/types';
+import type { ExitInfo, PtyOutput } from '../ipc/types';
 
 // Pre-computed base64 lookup table — avoids atob() intermediate string allocation.
 const B64_LOOKUP = new Uint8Array(128);
@@ -44,11 +44,7 @@ interface TerminalViewProps {
   args: string[];
   cwd: string;
   env?: Record<string, string>;
-  onExit?: (exitInfo: {
-    exit_code: number | null;
-    signal: string | null;
-    last_output: string[];
-  }) => void;
+  onExit?: (exitInfo: ExitInfo) => void;
   onData?: (data: Uint8Array) => void;
   onPromptDetected?: (text: string) => void;
   onReady?: (focusFn: () => void) => void;
@@ -56,6 +52,17 @@ interface TerminalViewProps {
   autoFocus?: boolean;
   initialCommand?: string;
   isFocused?: boolean;
+  /** Counts toward the backend's concurrent agent limit. */
+  queueable?: boolean;
+  /** Agent definition and profile whose flags the backend appends to `args`. */
+  agentDefId?: string;
+  profileId?: string;
+  /** Run in a session host so the process survives an app restart. */
+  detachable?: boolean;
+  /** Write an asciicast recording of the session, listed with the task's recordings. */
+  record?: boolean;
+  /** Reconnect to the session host already running `agentId`, spawning if it is gone. */
+  reattach?: boolean;
 }
 
 // Status parsing only needs recent output. Capping forwarded bytes avoids
@@ -80,7 +87,7 @@ export function TerminalView(props: TerminalViewProps) {
       fontFamily: getTerminalFontFamily(store.terminalFont),
       theme: getTerminalTheme(store.themePreset),
       allowProposedApi: true,
-      scrollback: 3000,
+      scrollback: store.terminalScrollback,
     });
 
     fitAddon = new FitAddon();
@@ -111,7 +118,9 @@ export function TerminalView(props: TerminalViewProps) {
 
       if (isPaste) {
         navigator.clipboard.readText().then((text) => {
-          if (text) enqueueInput(text);
+          if (!text) return;
+          flushPendingInput();
+          invoke(IPC.WriteToAgent, { agentId, data: text, paste: true });
         });
         return false;
       }
@@ -134,17 +143,11 @@ export function TerminalView(props: TerminalViewProps) {
     let ptyPaused = false;
     const FLOW_HIGH = 256 * 1024; // 256KB — pause PTY reader
     const FLOW_LOW = 32 * 1024; // 32KB — resume PTY reader
-    let pendingExitPayload: {
-      exit_code: number | null;
-      signal: string | null;
-      last_output: string[];
-    } | null = null;
-
-    function emitExit(payload: {
-      exit_code: number | null;
-      signal: string | null;
-      last_output: string[];
-    }) {
+    const ACK_BYTES = 64 * 1024; // acknowledge received output to the backend in chunks
+    let unackedBytes = 0;
+    let pendingExitPayload: ExitInfo | null = null;
+
+    function emitExit(payload: ExitInfo) {
       if (!term) return;
       term.write('\r\n\x1b[90m[Process exited]\x1b[0m\r\n');
       props.onExit?.(payload);
@@ -183,7 +186,7 @@ export function TerminalView(props: TerminalViewProps) {
         // Resume PTY reader when xterm.js has caught up
         if (watermark < FLOW_LOW && ptyPaused) {
           ptyPaused = false;
-          invoke(IPC.ResumeAgent, { agentId }).catch(() => {
+          invoke(IPC.ResumeAgentOutput, { agentId }).catch(() => {
             ptyPaused = false;
           });
         }
@@ -217,7 +220,7 @@ export function TerminalView(props: TerminalViewProps) {
       // Pause PTY reader when xterm.js falls behind
       if (watermark > FLOW_HIGH && !ptyPaused) {
         ptyPaused = true;
-        invoke(IPC.PauseAgent, { agentId }).catch(() => {
+        invoke(IPC.PauseAgentOutput, { agentId }).catch(() => {
           ptyPaused = false;
         });
       }
@@ -231,14 +234,16 @@ export function TerminalView(props: TerminalViewProps) {
     }
 
     const onOutput = new Channel<PtyOutput>();
-    let initialCommandSent = false;
     onOutput.onmessage = (msg) => {
       if (msg.type === 'Data') {
-        enqueueOutput(base64ToUint8Array(msg.data));
-        if (!initialCommandSent && props.initialCommand) {
-          initialCommandSent = true;
-          setTimeout(() => enqueueInput(props.initialCommand! + '\r'), 50);
+        const chunk = base64ToUint8Array(msg.data);
+        unackedBytes += chunk.length;
+        if (unackedBytes >= ACK_BYTES) {
+          const bytes = unackedBytes;
+          unackedBytes = 0;
+          invoke(IPC.AckAgentOutput, { agentId, bytes }).catch(() => {});
         }
+        enqueueOutput(chunk);
       } else if (msg.type === 'Exit') {
         pendingExitPayload = msg.data;
         flushOutputQueue();
@@ -344,27 +349,45 @@ export function TerminalView(props: TerminalViewProps) {
       // WebGL2 not supported — DOM renderer used automatically
     }
 
-    invoke(IPC.SpawnAgent, {
-      taskId,
-      agentId,
-      command: props.command,
-      args: props.args,
-      cwd: props.cwd,
-      env: props.env ?? {},
-      cols: term.cols,
-      rows: term.rows,
-      onOutput,
-    }).catch((err) => {
-      // Strip control/escape characters to prevent terminal escape injection
-      // eslint-disable-next-line no-control-regex -- intentionally stripping control/escape chars to prevent terminal injection
-      const safeErr = String(err).replace(/[\x00-\x1f\x7f]/g, '');
-      term!.write(`\x1b[31mFailed to spawn: ${safeErr}\x1b[0m\r\n`);
-      props.onExit?.({
-        exit_code: null,
-        signal: 'spawn_failed',
-        last_output: [`Failed to spawn: ${safeErr}`],
+    const spawn = () =>
+      invoke(IPC.SpawnAgent, {
+        taskId,
+        agentId,
+        command: props.command,
+        args: props.args,
+        cwd: props.cwd,
+        env: props.env ?? {},
+        cols: term!.cols,
+        rows: term!.rows,
+        queueable: props.queueable ?? false,
+        detachable: props.detachable ?? false,
+        record: props.record ?? false,
+        agentDefId: props.agentDefId,
+        profileId: props.profileId,
+        initialInput: props.initialCommand ? props.initialCommand + '\r' : undefined,
+        onOutput,
+      }).catch((err) => {
+        // Strip control/escape characters to prevent terminal escape injection
+        // eslint-disable-next-line no-control-regex -- intentionally stripping control/escape chars to prevent terminal injection
+        const safeErr = String(err).replace(/[\x00-\x1f\x7f]/g, '');
+        term!.write(`\x1b[31mFailed to spawn: ${safeErr}\x1b[0m\r\n`);
+        props.onExit?.({
+          exit_code: null,
+          signal: 'spawn_failed',
+          last_output: [`Failed to spawn: ${safeErr}`],
+          exit_reason: 'error',
+        });
       });
-    });
+
+    if (props.reattach) {
+      invoke(IPC.ReattachAgent, { agentId, cols: term.cols, onOutput }).then(
+        () => invoke(IPC.ResizeAgent, { agentId, cols: term!.cols, rows: term!.rows }),
+        // The agent exited while the app was closed
+        () => spawn(),
+      );
+    } else {
+      spawn();
+    }
 
     onCleanup(() => {
       flushPendingInput();
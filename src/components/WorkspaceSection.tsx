@@ -0,0 +1,81 @@
+import { Show, createSignal } from 'solid-js';
+import { theme } from '../lib/theme';
+import { exportWorkspace, importWorkspace } from '../store/store';
+import { secondaryButton } from './CustomAgentsSection';
+
+/** Settings section to move settings, agents, templates and prompts between machines. */
+export function WorkspaceSection() {
+  const [message, setMessage] = createSignal('');
+  const [error, setError] = createSignal('');
+
+  async function run(action: () => Promise<string | null>) {
+    setMessage('');
+    setError('');
+    try {
+      setMessage((await action()) ?? '');
+    } catch (err) {
+      setError(String(err).replace(/^Error: /, ''));
+    }
+  }
+
+  const doExport = () => run(async () => ((await exportWorkspace()) ? 'Workspace exported' : null));
+
+  const doImport = () =>
+    run(async () => {
+      const r = await importWorkspace();
+      if (!r) return null;
+      const counts = [
+        `${r.settings} settings`,
+        `${r.agents} agents`,
+        `${r.profiles} profiles`,
+        `${r.templates} templates`,
+        `${r.prompts} prompts`,
+        `${r.task_metadata} task labels`,
+      ].join(', ');
+      const skipped = r.skipped.length > 0 ? `; skipped ${r.skipped.join('; ')}` : '';
+      return `Imported ${counts}${skipped}`;
+    });
+
+  return (
+    <div style={{ display: 'flex', 'flex-direction': 'column', gap: '10px' }}>
+      <div
+        style={{
+          'font-size': '11px',
+          color: theme.fgMuted,
+          'text-transform': 'uppercase',
+          'letter-spacing': '0.05em',
+          'font-weight': '600',
+        }}
+      >
+        Workspace
+      </div>
+      <div style={{ display: 'flex', gap: '8px' }}>
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => void doExport()}
+        >
+          Export…
+        </button>
+        <button
+          type="button"
+          class="btn-secondary"
+          style={secondaryButton}
+          onClick={() => void doImport()}
+        >
+          Import…
+        </button>
+      </div>
+      <Show when={message()}>
+        <div style={{ 'font-size': '12px', color: theme.fg }}>{message()}</div>
+      </Show>
+      <Show when={error()}>
+        <div style={{ 'font-size': '12px', color: theme.error }}>{error()}</div>
+      </Show>
+      <div style={{ 'font-size': '11px', color: theme.fgSubtle }}>
+        Settings, custom agents, profiles, templates, prompts and task labels; secrets stay here
+      </div>
+    </div>
+  );
+}
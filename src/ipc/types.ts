@@ -1,9 +1,23 @@
+export type ExitReason =
+  | 'normal'
+  | 'error'
+  | 'killed_by_user'
+  | 'oom_killed'
+  | 'segfault'
+  | 'signal'
+  | 'command_not_found';
+
+export interface ExitInfo {
+  exit_code: number | null;
+  signal: string | null;
+  last_output: string[];
+  exit_reason?: ExitReason;
+  kill_path?: 'immediate' | 'graceful' | 'escalated' | null;
+}
+
 export type PtyOutput =
   | { type: 'Data'; data: string } // base64-encoded
-  | {
-      type: 'Exit';
-      data: { exit_code: number | null; signal: string | null; last_output: string[] };
-    };
+  | { type: 'Exit'; data: ExitInfo };
 
 export interface AgentDef {
   id: string;
@@ -13,12 +27,353 @@ export interface AgentDef {
   resume_args?: string[];
   skip_permissions_args?: string[];
   description: string;
+  env?: Record<string, string>;
+  icon?: string;
+  plan_patterns?: string[];
+  /** Environment profile id; overrides the project's. */
+  env_profile?: string;
+  /** User-defined; see electron/ipc/agents.ts. */
+  custom?: boolean;
+  profiles?: AgentProfile[];
+}
+
+/** Extra PATH entries, shell init and variables for agents; see electron/ipc/shell-env.ts. */
+export interface EnvProfile {
+  id: string;
+  name: string;
+  path: string[];
+  init: string;
+  env?: Record<string, string>;
+}
+
+/** Named extra flags for an agent, picked per task. */
+export interface AgentProfile {
+  id: string;
+  name: string;
+  args: string[];
+  env?: Record<string, string>;
+  builtin?: boolean;
 }
 
+/** A decoded event from an agent run without a terminal (`agent-event`). */
+export type AgentEvent =
+  | { kind: 'message'; text: string }
+  | { kind: 'tool_call'; id: string | null; name: string; input: unknown }
+  | { kind: 'tool_result'; id: string | null; output: string; is_error: boolean }
+  | { kind: 'file_edit'; path: string; tool: string }
+  | { kind: 'completion'; success: boolean; result: string | null; cost_usd: number | null }
+  | { kind: 'error'; message: string };
+
+export type AgentEventMessage = AgentEvent & { run_id: string; task_id: string; at: number };
+
 export interface CreateTaskResult {
   id: string;
   branch_name: string;
   worktree_path: string;
+  /** The project's post-create setup command, if one is configured. */
+  setup_command: string | null;
+}
+
+export interface ForkTaskResult extends CreateTaskResult {
+  /** Whether uncommitted changes were carried over from the source task. */
+  uncommitted_changes: boolean;
+}
+
+/** Backend-owned task lifecycle; see electron/ipc/tasks.ts for allowed transitions. */
+export type TaskLifecycleStatus = 'planning' | 'running' | 'review' | 'merging' | 'done' | 'failed';
+
+export interface TaskStatusChangedEvent {
+  task_id: string;
+  from: TaskLifecycleStatus | null;
+  to: TaskLifecycleStatus;
+  at: number;
+}
+
+export interface ChecklistItem {
+  id: string;
+  text: string;
+  done: boolean;
+}
+
+/** Review notes stored per task by the backend; see electron/ipc/task-notes.ts. */
+export interface TaskNotes {
+  notes: string;
+  checklist: ChecklistItem[];
+  updated_at: number;
+}
+
+export type TaskPriority = 'low' | 'normal' | 'high' | 'urgent';
+
+export interface TaskMetadata {
+  labels: string[];
+  priority: TaskPriority;
+  updated_at: number;
+}
+
+/** A task as returned by `query_tasks`; see electron/ipc/task-metadata.ts. */
+export interface TaskRecord extends TaskMetadata {
+  id: string;
+  name: string;
+  project_id: string | null;
+  branch_name: string | null;
+  status: TaskLifecycleStatus | null;
+}
+
+export interface ReviewFinding {
+  file: string | null;
+  line: number | null;
+  severity: 'info' | 'warning' | 'error';
+  message: string;
+}
+
+/** Outcome of `start_review_agent`, delivered as `review-completed`. */
+export interface ReviewResult {
+  task_id: string;
+  run_id: string;
+  reviewer_agent_id: string;
+  /** False when the agent failed or its answer had no parsable verdict. */
+  success: boolean;
+  verdict: 'approve' | 'request_changes' | 'comment' | null;
+  summary: string;
+  findings: ReviewFinding[];
+  at: number;
+}
+
+/** A live or queued terminal session of a task; see `list_task_agents`. */
+export interface TaskAgentSession {
+  agent_id: string;
+  task_id: string;
+  kind: 'agent' | 'shell';
+  state: 'queued' | 'running' | 'idle';
+  started_at: number;
+}
+
+export interface TaskFilter {
+  labels?: string[];
+  priorities?: TaskPriority[];
+  statuses?: TaskLifecycleStatus[];
+  project_id?: string;
+  text?: string;
+  sort?: 'priority' | 'name' | 'updated';
+}
+
+export interface TaskHistoryFilter {
+  project_id?: string;
+  search?: string;
+  include_closed?: boolean;
+  limit?: number;
+}
+
+/** A task from history.db; see electron/ipc/history-db.ts. */
+export interface TaskHistoryEntry {
+  id: string;
+  name: string;
+  project_id: string | null;
+  branch_name: string | null;
+  created_at: number;
+  updated_at: number;
+  closed_at: number | null;
+  sessions: number;
+  session_ms: number;
+  last_session_at: number | null;
+}
+
+export interface SessionRecord {
+  id: number;
+  task_id: string;
+  agent_id: string;
+  kind: 'agent' | 'shell';
+  started_at: number;
+  ended_at: number | null;
+  exit_code: number | null;
+  exit_reason: string | null;
+}
+
+export type EventKind =
+  | 'agent_spawn'
+  | 'agent_exit'
+  | 'agent_kill'
+  | 'task_create'
+  | 'task_delete'
+  | 'merge'
+  | 'push'
+  | 'error';
+
+/** An entry of the backend's event log; see electron/ipc/events.ts. */
+export interface AppEvent {
+  id: string;
+  ts: number;
+  kind: EventKind;
+  task_id: string | null;
+  agent_id: string | null;
+  message: string;
+  data?: Record<string, unknown>;
+}
+
+export interface EventFilter {
+  task_id?: string;
+  agent_id?: string;
+  kinds?: EventKind[];
+}
+
+export interface LogEntry {
+  ts: number;
+  level: 'error' | 'warn' | 'info' | 'debug';
+  target: string | null;
+  message: string;
+}
+
+/** Everything the backend is watching, from `list_active_watchers`. */
+export interface ActiveWatchers {
+  handles: { root: string; recursive: boolean; subscribers: number; open: boolean }[];
+  paths: { id: string; root: string }[];
+  worktrees: { task_id: string; worktree_path: string; refs: number }[];
+  plans: {
+    task_id: string;
+    worktree_path: string;
+    agent_ids: string[];
+    subscriptions: number;
+    plans: number;
+  }[];
+}
+
+/** Payload of `path-changed`, for a directory watched with `watch_path`. */
+export interface PathChangedEvent {
+  id: string;
+  root: string;
+  changes: { path: string; kind: 'created' | 'modified' | 'removed' }[];
+  /** Events were lost; re-read the directory instead of applying `changes`. */
+  rescan?: boolean;
+}
+
+/** A plan file an agent keeps in the worktree; see `watch_for_plans`. */
+export interface PlanFile {
+  agent_id: string;
+  path: string;
+  content: string | null;
+  /** Pass back as `expectedHash` to write_plan_file; a stale one fails with PlanConflict. */
+  hash: string | null;
+}
+
+/** Payload of `plan-changed`. */
+export interface PlanChangedEvent extends PlanFile {
+  task_id: string;
+  kind: 'created' | 'modified' | 'removed';
+}
+
+/** A snapshot from `get_plan_history`; content is null for a removal. */
+export interface PlanRevision {
+  rev: number;
+  path: string;
+  agent_id: string | null;
+  ts: number;
+  content: string | null;
+}
+
+export interface PlanItem {
+  id: string;
+  text: string;
+  done: boolean;
+  depth: number;
+  line: number;
+}
+
+/** A markdown plan as checklist sections, from `parse_plan`. */
+export interface ParsedPlan {
+  path: string;
+  title: string | null;
+  sections: { title: string; level: number; items: PlanItem[] }[];
+  total: number;
+  done: number;
+}
+
+/** Approval state of a task's plan; payload of `plan-approval-changed`. */
+export interface PlanApproval {
+  task_id: string;
+  agent_id: string;
+  plan_path: string;
+  state: 'pending' | 'approved' | 'changes_requested';
+  updated_at: number;
+  feedback: string | null;
+}
+
+export interface TaskUnblockedEvent {
+  task_id: string;
+  dependencies: string[];
+}
+
+export interface TaskTemplate {
+  id: string;
+  name: string;
+  agent_id: string | null;
+  prompt: string;
+  base_branch: string | null;
+  branch_prefix: string | null;
+  env: Record<string, string>;
+  setup_commands: string[];
+}
+
+export interface PromptSnippet {
+  id: string;
+  name: string;
+  /** May contain {{title}}, {{branch}}, {{changed_files}} and custom {{variables}}. */
+  text: string;
+}
+
+export interface TaskMetrics {
+  task_id: string;
+  /** Wall-clock time agent sessions were alive, including running ones. */
+  runtime_ms: number;
+  sessions: number;
+  running: boolean;
+  input_tokens: number;
+  output_tokens: number;
+  /** null until an agent CLI reports a cost. */
+  cost_usd: number | null;
+}
+
+export interface MetricsReport {
+  tasks: TaskMetrics[];
+  totals: Omit<TaskMetrics, 'task_id' | 'running'> & { running_tasks: number };
+}
+
+export interface TaskNameSuggestion {
+  title: string;
+  slug: string;
+  /** `agent` when the agent CLI wrote the title, `heuristic` otherwise. */
+  source: 'heuristic' | 'agent';
+}
+
+export interface TemplateTaskResult extends CreateTaskResult {
+  template_id: string;
+  agent_id: string | null;
+  /** The template's prompt with placeholders filled in. */
+  prompt: string;
+  env: Record<string, string>;
+}
+
+export interface ArchivedTask {
+  id: string;
+  name: string;
+  project_root: string;
+  branch_name: string;
+  main_branch: string;
+  head: string;
+  merge_base: string;
+  agent_id: string | null;
+  archived_at: number;
+  /** Directory holding the bundle, final.diff, recordings/ and plans/. */
+  path: string;
+  has_bundle: boolean;
+  has_uncommitted_changes: boolean;
+  recordings: string[];
+  plan_files: string[];
+}
+
+export interface RestoredTask extends ArchivedTask {
+  worktree_path: string;
+  /** False when uncommitted changes no longer applied; the archive is kept then. */
+  patch_applied: boolean;
 }
 
 export interface TaskInfo {
@@ -38,9 +393,28 @@ export interface ChangedFile {
   committed: boolean;
 }
 
+export interface ChangedFilesUpdatedEvent {
+  task_id: string;
+  worktree_path: string;
+  /** Files that are new or whose status or line counts changed. */
+  changed: ChangedFile[];
+  removed: string[];
+}
+
+export interface SubmoduleStatus {
+  path: string;
+  sha: string;
+  state: 'uninitialized' | 'up_to_date' | 'modified' | 'conflict';
+}
+
+export type WorktreeOperation = 'merge' | 'rebase' | 'cherry_pick' | 'revert';
+
 export interface WorktreeStatus {
   has_committed_changes: boolean;
   has_uncommitted_changes: boolean;
+  submodules: SubmoduleStatus[];
+  operation: WorktreeOperation | null;
+  conflicted_files: number;
 }
 
 export interface MergeStatus {
@@ -48,8 +422,523 @@ export interface MergeStatus {
   conflicting_files: string[];
 }
 
+export interface ConflictFile {
+  path: string;
+  base: string | null;
+  ours: string | null;
+  theirs: string | null;
+  binary: boolean;
+}
+
+export interface MergeConflict {
+  project_root: string;
+  branch_name: string;
+  main_branch: string;
+  files: ConflictFile[];
+}
+
+export interface MergePreviewFile {
+  path: string;
+  lines_added: number;
+  lines_removed: number;
+  binary: boolean;
+}
+
+export interface MergePreview {
+  main_branch: string;
+  clean: boolean;
+  conflicting_files: string[];
+  files: MergePreviewFile[];
+  lines_added: number;
+  lines_removed: number;
+}
+
 export interface MergeResult {
   main_branch: string;
   lines_added: number;
   lines_removed: number;
+  conflict?: MergeConflict;
+}
+
+export interface DetachedSession {
+  agentId: string;
+  taskId: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  pid: number;
+  hostPid: number;
+  socketPath: string;
+  startedAt: number;
+}
+
+export interface AgentStats {
+  agent_id: string;
+  pid: number;
+  rss_bytes: number;
+  cpu_percent: number;
+  process_count: number;
+}
+
+export interface RecordingInfo {
+  name: string;
+  path: string;
+  size: number;
+  created_at: number;
+}
+
+export interface AgentIdleEvent {
+  agent_id: string;
+  task_id: string;
+  last_line: string;
+}
+
+export interface PatternMatchedEvent {
+  agent_id: string;
+  trigger_id: string;
+  line: string;
+  groups: string[];
+}
+
+/** A tool-permission dialog spotted in an agent's terminal (`agent-permission-request`). */
+export interface PermissionRequestEvent {
+  agent_id: string;
+  task_id: string;
+  request_id: string;
+  agent: string;
+  question: string;
+  detail: string;
+}
+
+export interface SpawnPolicy {
+  mode: 'off' | 'allow' | 'deny';
+  commands: string[];
+}
+
+export interface RunningAgentInfo {
+  agent_id: string;
+  task_id: string;
+  command: string;
+  args: string[];
+  cwd: string;
+  pid: number;
+  started_at: number;
+  alive: boolean;
+  detached: boolean;
+  suspended: boolean;
+}
+
+export interface AgentExitedEvent {
+  agent_id: string;
+  task_id: string;
+  exit_code: number | null;
+  signal: string | null;
+  exit_reason: ExitReason;
+}
+
+export interface AgentCwdChangedEvent {
+  agent_id: string;
+  cwd: string;
+}
+
+export interface AgentTitleChangedEvent {
+  agent_id: string;
+  title: string;
+}
+
+export interface NotificationSettings {
+  agent_exit: boolean;
+  agent_idle: boolean;
+  merge_conflict: boolean;
+  pr_status: boolean;
+}
+
+export interface Settings {
+  version: number;
+  terminal_font: string;
+  terminal_scrollback: number;
+  default_agent_id: string | null;
+  /** Where worktrees go for repositories added from now on. */
+  worktree_location: WorktreeLocation;
+  /** Agents running at once before new ones queue; 0 is unlimited. */
+  agent_limit: number;
+  /** How status and diff polling reads repositories; in-process is opt-in. */
+  git_backend: 'in-process' | 'cli';
+  /** New agents run in a session host that outlives the app. */
+  keep_agents_running: boolean;
+  /** New agents write an asciicast recording of their output. */
+  record_sessions: boolean;
+  notifications: NotificationSettings;
+}
+
+/** Counts of what `import_workspace` took over; see electron/ipc/workspace.ts. */
+export interface WorkspaceImportResult {
+  settings: number;
+  agents: number;
+  profiles: number;
+  templates: number;
+  prompts: number;
+  task_metadata: number;
+  skipped: string[];
+}
+
+export interface StateBackup {
+  /** 1 is the newest. */
+  index: number;
+  saved_at: number;
+  size: number;
+  schema_version: number | null;
+  valid: boolean;
+}
+
+export interface AgentNotificationEvent {
+  agent_id: string;
+  task_id: string;
+  title: string;
+  body: string;
+}
+
+export interface AgentTimeoutEvent {
+  agent_id: string;
+  task_id: string;
+  max_runtime_secs: number;
+  action: 'warn' | 'kill';
+}
+
+export interface BackpressureEvent {
+  agent_id: string;
+  paused: boolean;
+  buffered_bytes: number;
+}
+
+/** Named keys accepted by send_key (mirrors electron/ipc/keys.ts). */
+export type KeyName =
+  | 'enter'
+  | 'tab'
+  | 'shift_tab'
+  | 'backspace'
+  | 'escape'
+  | 'ctrl_c'
+  | 'ctrl_d'
+  | 'ctrl_z'
+  | 'ctrl_l'
+  | 'ctrl_r'
+  | 'up'
+  | 'down'
+  | 'right'
+  | 'left'
+  | 'home'
+  | 'end'
+  | 'page_up'
+  | 'page_down'
+  | 'insert'
+  | 'delete';
+
+export interface WorktreeInfo {
+  path: string;
+  head: string | null;
+  branch: string | null;
+  detached: boolean;
+  locked: boolean;
+  prunable: boolean;
+  orphaned: boolean;
+}
+
+export interface DiffHunk {
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: string[];
+}
+
+export interface StashEntry {
+  id: string;
+  ref: string;
+  branch: string | null;
+  message: string;
+  created_at: number;
+}
+
+export interface BinaryFileDiff {
+  type: 'binary';
+  old_size: number | null;
+  new_size: number | null;
+  image: { mime: string; old_base64: string | null; new_base64: string | null } | null;
+}
+
+export type FileDiff = { type: 'text'; diff: string } | BinaryFileDiff;
+
+export type RebaseAction = 'pick' | 'reword' | 'squash' | 'fixup' | 'drop';
+
+export interface RebaseCommit {
+  sha: string;
+  short_sha: string;
+  author: string;
+  authored_at: number;
+  subject: string;
+  message: string;
+}
+
+export interface RebasePlan {
+  base: string;
+  head: string;
+  commits: RebaseCommit[];
+}
+
+export interface RebaseStep {
+  sha: string;
+  action: RebaseAction;
+  message?: string;
+}
+
+export interface BranchLogEntry {
+  sha: string;
+  short_sha: string;
+  parents: string[];
+  refs: string[];
+  author: string;
+  authored_at: number;
+  subject: string;
+}
+
+export interface BranchLog {
+  commits: BranchLogEntry[];
+  has_more: boolean;
+}
+
+export interface CommitFile {
+  path: string;
+  old_path: string | null;
+  status: string;
+  lines_added: number;
+  lines_removed: number;
+  binary: boolean;
+}
+
+export interface CommitDetail {
+  sha: string;
+  short_sha: string;
+  parents: string[];
+  author: string;
+  author_email: string;
+  authored_at: number;
+  committer: string;
+  committed_at: number;
+  message: string;
+  files: CommitFile[];
+}
+
+export interface RemoteComparison {
+  main_branch: string;
+  remote_ref: string;
+  ahead: number;
+  behind: number;
+}
+
+export interface BranchDivergedEvent extends RemoteComparison {
+  task_id: string;
+}
+
+export type IntegrationProvider = 'github' | 'gitlab';
+
+export interface PullRequestRef {
+  provider: IntegrationProvider;
+  host: string;
+  repo: string;
+  number: number;
+  url: string;
+  branch: string;
+  base: string;
+}
+
+export type CheckState = 'none' | 'pending' | 'success' | 'failure';
+export type ReviewState = 'none' | 'review_required' | 'approved' | 'changes_requested';
+
+export interface CheckItem {
+  name: string;
+  state: Exclude<CheckState, 'none'>;
+  url: string | null;
+}
+
+export interface PullRequestStatus {
+  task_id: string;
+  url: string;
+  state: 'open' | 'closed' | 'merged';
+  draft: boolean;
+  checks: CheckState;
+  check_items: CheckItem[];
+  review: ReviewState;
+  mergeable: boolean | null;
+}
+
+export interface WorktreeDiskUsage {
+  path: string;
+  branch: string | null;
+  size_bytes: number;
+  created_at: number | null;
+  last_activity_at: number | null;
+  orphaned: boolean;
+}
+
+export interface StaleWorktreeCleanup {
+  removed: string[];
+  skipped: Array<{ path: string; reason: string }>;
+}
+
+export type WorktreeLocation =
+  | { mode: 'repo' }
+  | { mode: 'app_data' }
+  | { mode: 'custom'; path: string };
+
+export interface WorktreeLocationInfo {
+  location: WorktreeLocation;
+  root: string;
+}
+
+export interface WorktreeRelocation {
+  root: string;
+  moved: Array<{ from: string; to: string }>;
+  failed: Array<{ path: string; error: string }>;
+}
+
+export interface BranchNameSuggestion {
+  branch_name: string;
+  /** The templated name before any collision suffix. */
+  base_name: string;
+  collision: boolean;
+}
+
+export interface MainBranchInfo {
+  branch: string;
+  source: 'override' | 'origin_head' | 'main' | 'master' | 'fallback';
+  /** Whether `branch` resolves to a commit in this repo. */
+  exists: boolean;
+  /** Local branches, most recently committed first. */
+  candidates: string[];
+}
+
+export interface SigningConfig {
+  enabled: boolean;
+  format: 'openpgp' | 'ssh' | 'x509';
+  key: string | null;
+  program: string | null;
+}
+
+export interface CredentialStatus {
+  remote_url: string | null;
+  protocol: 'ssh' | 'https' | 'other' | null;
+  credential_helper: string | null;
+  ssh_agent: boolean;
+}
+
+export interface AskpassPrompt {
+  id: string;
+  prompt: string;
+  secret: boolean;
+}
+
+export interface GitHookOutputEvent {
+  repo_path: string;
+  operation: 'commit' | 'merge' | 'push';
+  stream: 'stdout' | 'stderr';
+  data: string;
+}
+
+export interface BlameLine {
+  line: number;
+  /** All zeros for lines not committed yet. */
+  sha: string;
+  author: string;
+  authored_at: number;
+  summary: string;
+  /** Written on the task branch (or uncommitted) rather than inherited from main. */
+  in_task: boolean;
+  content: string;
+}
+
+export interface SearchOptions {
+  regex?: boolean;
+  case_sensitive?: boolean;
+  whole_word?: boolean;
+  include?: string[];
+  exclude?: string[];
+  max_results?: number;
+}
+
+export interface SearchMatch {
+  path: string;
+  line: number;
+  column: number;
+  text: string;
+}
+
+export interface SearchResultsEvent {
+  search_id: string;
+  matches: SearchMatch[];
+}
+
+export interface SearchSummary {
+  search_id: string;
+  total: number;
+  /** Stopped early: max_results reached or cancelled. */
+  truncated: boolean;
+}
+
+export interface TreeEntry {
+  name: string;
+  /** Relative to the worktree root. */
+  path: string;
+  type: 'file' | 'dir' | 'symlink';
+  /** Absent when the directory is beyond the requested depth. */
+  children?: TreeEntry[];
+}
+
+export interface WorktreeTree {
+  entries: TreeEntry[];
+  truncated: boolean;
+}
+
+export interface WorktreeFile {
+  /** null for binary files. */
+  content: string | null;
+  binary: boolean;
+  size: number;
+  mtime_ms: number;
+}
+
+export interface DetectedEditor {
+  id: string;
+  name: string;
+  command: string;
+}
+
+export interface EditorSettings {
+  /** Command with {file}, {line} and {dir} placeholders; null uses the detected editor. */
+  command_template: string | null;
+}
+
+export interface ProjectInfo {
+  /** Repository top level, even when a subdirectory was picked. */
+  root: string;
+  name: string;
+  main_branch: string;
+}
+
+export interface McpServer {
+  name: string;
+  transport: 'stdio' | 'http' | 'sse';
+  command?: string;
+  args?: string[];
+  env?: Record<string, string>;
+  url?: string;
+  enabled: boolean;
+  /** Ids of the agents whose config lists the server. */
+  agents: string[];
+}
+
+export interface LinkedChangedFiles {
+  worktree_path: string;
+  files: ChangedFile[];
 }
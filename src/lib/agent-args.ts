@@ -0,0 +1,24 @@
+import type { Task } from '../store/types';
+
+/** Fill {worktree}, {branch} and {task_name} in a custom agent's arguments. */
+export function expandAgentArgs(args: string[], task: Task): string[] {
+  const values: Record<string, string> = {
+    worktree: task.worktreePath,
+    branch: task.branchName,
+    task_name: task.name,
+  };
+  return args.map((arg) => arg.replace(/\{(\w+)\}/g, (m, key: string) => values[key] ?? m));
+}
+
+/** Parse KEY=VALUE lines; blank lines and # comments are skipped. */
+export function parseEnvLines(text: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const trimmed = line.trim();
+    if (!trimmed || trimmed.startsWith('#')) continue;
+    const eq = trimmed.indexOf('=');
+    if (eq <= 0) throw new Error(`Expected KEY=VALUE: ${trimmed}`);
+    env[trimmed.slice(0, eq).trim()] = trimmed.slice(eq + 1).trim();
+  }
+  return env;
+}
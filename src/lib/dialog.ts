@@ -26,3 +26,11 @@ export async function openDialog(options?: OpenDialogOptions): Promise<string |
     string | string[] | null
   >;
 }
+
+interface SaveDialogOptions {
+  defaultPath?: string;
+}
+
+export async function saveDialog(options?: SaveDialogOptions): Promise<string | null> {
+  return window.electron.ipcRenderer.invoke(IPC.DialogSave, options) as Promise<string | null>;
+}
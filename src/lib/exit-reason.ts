@@ -0,0 +1,19 @@
+import type { ExitReason } from '../ipc/types';
+
+/** Human-readable exit badge text; falls back to the raw code for plain exits. */
+export function describeExit(reason: ExitReason | null, exitCode: number | null): string {
+  switch (reason) {
+    case 'killed_by_user':
+      return 'Stopped by user';
+    case 'oom_killed':
+      return 'Killed — likely out of memory';
+    case 'segfault':
+      return 'Crashed (segmentation fault)';
+    case 'command_not_found':
+      return 'Command not found — is the CLI installed and on PATH?';
+    case 'signal':
+      return 'Terminated by signal';
+    default:
+      return `Process exited (${exitCode ?? '?'})`;
+  }
+}
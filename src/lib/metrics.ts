@@ -0,0 +1,19 @@
+import type { TaskMetrics } from '../ipc/types';
+
+/** "2h 05m", "12m" or "<1m". */
+export function formatRuntime(ms: number): string {
+  const minutes = Math.floor(ms / 60_000);
+  if (minutes >= 60) {
+    return `${Math.floor(minutes / 60)}h ${String(minutes % 60).padStart(2, '0')}m`;
+  }
+  return minutes > 0 ? `${minutes}m` : '<1m';
+}
+
+/** Runtime, plus cost and tokens when an agent reported them. */
+export function formatMetrics(m: Omit<TaskMetrics, 'task_id' | 'running'>): string {
+  const parts = [formatRuntime(m.runtime_ms)];
+  if (m.cost_usd !== null) parts.push(`$${m.cost_usd.toFixed(2)}`);
+  const tokens = m.input_tokens + m.output_tokens;
+  if (tokens > 0) parts.push(`${(tokens / 1000).toFixed(1)}k tok`);
+  return parts.join(' · ');
+}
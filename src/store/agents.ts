@@ -2,7 +2,7 @@ import { produce } from 'solid-js/store';
 import { invoke } from '../lib/ipc';
 import { IPC } from '../../electron/ipc/channels';
 import { store, setStore } from './core';
-import type { AgentDef } from '../ipc/types';
+import type { AgentDef, AgentEventMessage, AgentProfile, ExitInfo } from '../ipc/types';
 import type { Agent } from './types';
 import { refreshTaskStatus, clearAgentActivity, markAgentSpawned } from './taskStatus';
 
@@ -11,6 +11,69 @@ export async function loadAgents(): Promise<void> {
   setStore('availableAgents', agents);
 }
 
+/** Add a custom agent, or replace the one with id `replaceId`, and reload the list. */
+export async function saveCustomAgent(def: AgentDef, replaceId?: string): Promise<void> {
+  if (replaceId) await invoke(IPC.UpdateAgentDef, { id: replaceId, agent: def });
+  else await invoke(IPC.AddAgentDef, { agent: def });
+  await loadAgents();
+}
+
+export async function deleteCustomAgent(id: string): Promise<void> {
+  await invoke(IPC.DeleteAgentDef, { id });
+  await loadAgents();
+}
+
+/** Add a profile to an agent, or replace its profile `replaceId`, and reload the list. */
+export async function saveAgentProfile(
+  agentId: string,
+  profile: AgentProfile,
+  replaceId?: string,
+): Promise<void> {
+  if (replaceId) {
+    await invoke(IPC.UpdateAgentProfile, { agentId, profileId: replaceId, profile });
+  } else {
+    await invoke(IPC.AddAgentProfile, { agentId, profile });
+  }
+  await loadAgents();
+}
+
+export async function deleteAgentProfile(agentId: string, profileId: string): Promise<void> {
+  await invoke(IPC.DeleteAgentProfile, { agentId, profileId });
+  await loadAgents();
+}
+
+/**
+ * Run `prompt` with an agent in a task's worktree without a terminal. Progress
+ * arrives as `agent-event`s tagged with the returned run id.
+ */
+export async function runAgentHeadless(
+  taskId: string,
+  agentDefId: string,
+  prompt: string,
+): Promise<string> {
+  const task = store.tasks[taskId];
+  if (!task) throw new Error(`Task not found: ${taskId}`);
+  const { run_id } = await invoke<{ run_id: string }>(IPC.RunAgentHeadless, {
+    taskId,
+    agentId: agentDefId,
+    prompt,
+    cwd: task.worktreePath,
+    env: task.env,
+  });
+  return run_id;
+}
+
+export async function cancelHeadlessRun(runId: string): Promise<void> {
+  await invoke(IPC.CancelHeadlessRun, { runId });
+}
+
+/** Follow events of all headless runs. Returns an unsubscribe function. */
+export function onAgentEvent(listener: (event: AgentEventMessage) => void): () => void {
+  return window.electron.ipcRenderer.on(IPC.AgentEvent, (msg: unknown) =>
+    listener(msg as AgentEventMessage),
+  );
+}
+
 export async function addAgentToTask(taskId: string, agentDef: AgentDef): Promise<void> {
   const task = store.tasks[taskId];
   if (!task) return;
@@ -25,6 +88,7 @@ export async function addAgentToTask(taskId: string, agentDef: AgentDef): Promis
     exitCode: null,
     signal: null,
     lastOutput: [],
+    exitReason: null,
     generation: 0,
   };
 
@@ -40,10 +104,51 @@ export async function addAgentToTask(taskId: string, agentDef: AgentDef): Promis
   markAgentSpawned(agentId);
 }
 
-export function markAgentExited(
-  agentId: string,
-  exitInfo: { exit_code: number | null; signal: string | null; last_output: string[] },
-): void {
+/**
+ * Replace the task's agent with one running `toDef`. The backend packages what
+ * the current agent leaves behind and stops it; the new agent is started with
+ * a prompt to pick up from there.
+ */
+export async function handoffTask(taskId: string, toDef: AgentDef): Promise<void> {
+  const task = store.tasks[taskId];
+  const fromId = task?.agentIds[0];
+  const from = fromId ? store.agents[fromId] : undefined;
+  if (!task || !fromId || !from) throw new Error('The task has no agent to hand off from');
+
+  const { prompt } = await invoke<{ prompt: string; file: string }>(IPC.HandoffTask, {
+    taskId,
+    fromAgentId: fromId,
+    fromAgentName: from.def.name,
+    toAgentName: toDef.name,
+    worktreePath: task.worktreePath,
+  });
+
+  const agentId = crypto.randomUUID();
+  setStore(
+    produce((s) => {
+      s.agents[agentId] = {
+        id: agentId,
+        taskId,
+        def: toDef,
+        resumed: false,
+        status: 'running',
+        exitCode: null,
+        signal: null,
+        lastOutput: [],
+        exitReason: null,
+        generation: 0,
+      };
+      s.tasks[taskId].agentIds[0] = agentId;
+      s.tasks[taskId].initialPrompt = prompt;
+      delete s.agents[fromId];
+      if (s.activeAgentId === fromId) s.activeAgentId = agentId;
+    }),
+  );
+  clearAgentActivity(fromId);
+  markAgentSpawned(agentId);
+}
+
+export function markAgentExited(agentId: string, exitInfo: ExitInfo): void {
   const agent = store.agents[agentId];
   setStore(
     produce((s) => {
@@ -52,6 +157,7 @@ export function markAgentExited(
         s.agents[agentId].exitCode = exitInfo.exit_code;
         s.agents[agentId].signal = exitInfo.signal;
         s.agents[agentId].lastOutput = exitInfo.last_output;
+        s.agents[agentId].exitReason = exitInfo.exit_reason ?? null;
       }
     }),
   );
@@ -69,8 +175,10 @@ export function restartAgent(agentId: string, useResumeArgs: boolean): void {
         s.agents[agentId].exitCode = null;
         s.agents[agentId].signal = null;
         s.agents[agentId].lastOutput = [];
+        s.agents[agentId].exitReason = null;
         s.agents[agentId].resumed = useResumeArgs;
         s.agents[agentId].generation += 1;
+        s.agents[agentId].reattach = false;
       }
     }),
   );
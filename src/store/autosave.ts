@@ -20,10 +20,10 @@ function persistedSnapshot(): string {
     completedTaskCount: store.completedTaskCount,
     mergedLinesAdded: store.mergedLinesAdded,
     mergedLinesRemoved: store.mergedLinesRemoved,
-    terminalFont: store.terminalFont,
     themePreset: store.themePreset,
     windowState: store.windowState,
     autoTrustFolders: store.autoTrustFolders,
+    agentTaskNames: store.agentTaskNames,
     tasks: Object.fromEntries(
       store.taskOrder
         .filter((id) => store.tasks[id])
@@ -14,6 +14,7 @@ export const [store, setStore] = createStore<AppStore>({
   activeTaskId: null,
   activeAgentId: null,
   availableAgents: [],
+  promptSnippets: [],
   showNewTaskDialog: false,
   sidebarVisible: true,
   fontScales: {},
@@ -35,9 +36,14 @@ export const [store, setStore] = createStore<AppStore>({
   mergedLinesAdded: 0,
   mergedLinesRemoved: 0,
   terminalFont: DEFAULT_TERMINAL_FONT,
+  terminalScrollback: 3000,
+  defaultAgentId: null,
   themePreset: 'minimal',
   windowState: null,
   autoTrustFolders: false,
+  agentTaskNames: false,
+  keepAgentsRunning: false,
+  recordSessions: false,
   inactiveColumnOpacity: 0.6,
   newTaskDropUrl: null,
   remoteAccess: {
@@ -1,3 +1,4 @@
+import { IPC } from '../../electron/ipc/channels';
 import { store, setStore, updateWindowTitle } from './core';
 import { showNotification } from './notification';
 import { pickAndAddProject } from './projects';
@@ -12,6 +13,13 @@ export function setActiveTask(id: string): void {
   updateWindowTitle(task?.name ?? terminal?.name);
 }
 
+/** Show the task of a clicked desktop notification. Returns an unsubscribe function. */
+export function listenForNotificationClicks(): () => void {
+  return window.electron.ipcRenderer.on(IPC.NotificationClicked, (msg: unknown) => {
+    setActiveTask((msg as { task_id: string }).task_id);
+  });
+}
+
 export function setActiveAgent(agentId: string): void {
   setStore('activeAgentId', agentId);
 }
@@ -13,12 +13,20 @@ import type {
   PersistedWindowState,
   Project,
 } from './types';
-import { DEFAULT_TERMINAL_FONT, isTerminalFont } from '../lib/fonts';
+import type { DetachedSession } from '../ipc/types';
 import { isLookPreset } from '../lib/look';
 import { syncTerminalCounter } from './terminals';
 
+/** Bump when the shape of PersistedState changes in a way loadState must know about. */
+const STATE_SCHEMA_VERSION = 1;
+
+// Set while a backup is being restored so the outgoing store can't overwrite it
+let savesSuspended = false;
+
 export async function saveState(): Promise<void> {
+  if (savesSuspended) return;
   const persisted: PersistedState = {
+    schemaVersion: STATE_SCHEMA_VERSION,
     projects: store.projects.map((p) => ({ ...p })),
     lastProjectId: store.lastProjectId,
     lastAgentId: store.lastAgentId,
@@ -33,10 +41,10 @@ export async function saveState(): Promise<void> {
     completedTaskCount: store.completedTaskCount,
     mergedLinesAdded: store.mergedLinesAdded,
     mergedLinesRemoved: store.mergedLinesRemoved,
-    terminalFont: store.terminalFont,
     themePreset: store.themePreset,
     windowState: store.windowState ? { ...store.windowState } : undefined,
     autoTrustFolders: store.autoTrustFolders,
+    agentTaskNames: store.agentTaskNames,
     inactiveColumnOpacity: store.inactiveColumnOpacity,
   };
 
@@ -60,6 +68,11 @@ export async function saveState(): Promise<void> {
       skipPermissions: task.skipPermissions,
       githubUrl: task.githubUrl,
       savedInitialPrompt: task.savedInitialPrompt,
+      linkedWorktrees: task.linkedWorktrees,
+      env: task.env,
+      dependsOn: task.dependsOn,
+      waitingOn: task.waitingOn,
+      agentProfileId: task.agentProfileId,
     };
   }
 
@@ -118,6 +131,7 @@ function parsePersistedWindowState(v: unknown): PersistedWindowState | null {
 }
 
 interface LegacyPersistedState {
+  schemaVersion?: number;
   projectRoot?: string;
   projects?: Project[];
   lastProjectId?: string | null;
@@ -150,6 +164,9 @@ export async function loadState(): Promise<void> {
     console.warn('Invalid persisted state structure, skipping load');
     return;
   }
+  if ((raw.schemaVersion ?? 0) > STATE_SCHEMA_VERSION) {
+    console.warn(`State is from a newer version (schema ${raw.schemaVersion}), loading anyway`);
+  }
 
   // Migrate from old format if needed
   let projects: Project[] = raw.projects ?? [];
@@ -179,6 +196,12 @@ export async function loadState(): Promise<void> {
 
   const restoredRunningAgentIds: string[] = [];
   const today = getLocalDateKey();
+  // Agents left running in session hosts are reconnected instead of respawned
+  const detached = await invoke<DetachedSession[]>(IPC.ListDetachedSessions).catch(() => []);
+  const detachedByTask = new Map<string, string[]>();
+  for (const d of detached) {
+    detachedByTask.set(d.taskId, [...(detachedByTask.get(d.taskId) ?? []), d.agentId]);
+  }
 
   setStore(
     produce((s) => {
@@ -216,13 +239,11 @@ export async function loadState(): Promise<void> {
         typeof mergedLinesRemovedRaw === 'number' && Number.isFinite(mergedLinesRemovedRaw)
           ? Math.max(0, Math.floor(mergedLinesRemovedRaw))
           : 0;
-      s.terminalFont = isTerminalFont(rawAny.terminalFont)
-        ? rawAny.terminalFont
-        : DEFAULT_TERMINAL_FONT;
       s.themePreset = isLookPreset(rawAny.themePreset) ? rawAny.themePreset : 'minimal';
       s.windowState = parsePersistedWindowState(rawAny.windowState);
       s.autoTrustFolders =
         typeof rawAny.autoTrustFolders === 'boolean' ? rawAny.autoTrustFolders : false;
+      s.agentTaskNames = rawAny.agentTaskNames === true;
       const rawOpacity = rawAny.inactiveColumnOpacity;
       s.inactiveColumnOpacity =
         typeof rawOpacity === 'number' &&
@@ -236,7 +257,10 @@ export async function loadState(): Promise<void> {
         const pt = raw.tasks[taskId];
         if (!pt) continue;
 
-        const agentId = crypto.randomUUID();
+        // A task can have several agents left running; reattach all of them
+        const detachedAgentIds = detachedByTask.get(taskId) ?? [];
+        const reattach = detachedAgentIds.length > 0;
+        const taskAgentIds = reattach ? detachedAgentIds : [crypto.randomUUID()];
         const agentDef = pt.agentDef;
 
         // Enrich with resume_args/skip_permissions_args from fresh defaults (handles old state files)
@@ -260,7 +284,7 @@ export async function loadState(): Promise<void> {
           projectId: pt.projectId ?? '',
           branchName: pt.branchName,
           worktreePath: pt.worktreePath,
-          agentIds: agentDef ? [agentId] : [],
+          agentIds: agentDef ? taskAgentIds : [],
           shellAgentIds,
           notes: pt.notes,
           lastPrompt: pt.lastPrompt,
@@ -268,24 +292,33 @@ export async function loadState(): Promise<void> {
           skipPermissions: pt.skipPermissions === true,
           githubUrl: pt.githubUrl,
           savedInitialPrompt: pt.savedInitialPrompt,
+          linkedWorktrees: pt.linkedWorktrees,
+          env: pt.env,
+          dependsOn: pt.dependsOn,
+          waitingOn: pt.waitingOn,
+          agentProfileId: pt.agentProfileId,
         };
 
         s.tasks[taskId] = task;
 
         if (agentDef) {
-          const agent: Agent = {
-            id: agentId,
-            taskId,
-            def: agentDef,
-            resumed: true,
-            status: 'running',
-            exitCode: null,
-            signal: null,
-            lastOutput: [],
-            generation: 0,
-          };
-          s.agents[agentId] = agent;
-          restoredRunningAgentIds.push(agentId);
+          for (const agentId of taskAgentIds) {
+            const agent: Agent = {
+              id: agentId,
+              taskId,
+              def: agentDef,
+              resumed: true,
+              status: 'running',
+              exitCode: null,
+              signal: null,
+              lastOutput: [],
+              exitReason: null,
+              generation: 0,
+              reattach,
+            };
+            s.agents[agentId] = agent;
+            restoredRunningAgentIds.push(agentId);
+          }
         }
       }
 
@@ -315,3 +348,15 @@ export async function loadState(): Promise<void> {
 
   syncTerminalCounter();
 }
+
+/** Replace the current state with backup `index` and reload the window to pick it up. */
+export async function restoreStateBackup(index: number): Promise<void> {
+  savesSuspended = true;
+  try {
+    await invoke(IPC.RestoreStateBackup, { index });
+  } catch (e) {
+    savesSuspended = false;
+    throw e;
+  }
+  window.location.reload();
+}
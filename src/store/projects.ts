@@ -1,8 +1,12 @@
 import { produce } from 'solid-js/store';
 import { openDialog } from '../lib/dialog';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
 import { store, setStore } from './core';
 import { closeTask } from './tasks';
+import { showNotification } from './notification';
 import type { Project } from './types';
+import type { ProjectInfo, WorktreeLocation, WorktreeRelocation } from '../ipc/types';
 import { sanitizeBranchPrefix } from '../lib/branch-name';
 
 export const PASTEL_HUES = [0, 30, 60, 120, 180, 210, 260, 300, 330];
@@ -43,7 +47,15 @@ export function removeProject(projectId: string): void {
 export function updateProject(
   projectId: string,
   updates: Partial<
-    Pick<Project, 'name' | 'color' | 'branchPrefix' | 'deleteBranchOnClose' | 'terminalBookmarks'>
+    Pick<
+      Project,
+      | 'name'
+      | 'color'
+      | 'branchPrefix'
+      | 'branchTemplate'
+      | 'deleteBranchOnClose'
+      | 'terminalBookmarks'
+    >
   >,
 ): void {
   setStore(
@@ -54,6 +66,8 @@ export function updateProject(
       if (updates.color !== undefined) s.projects[idx].color = updates.color;
       if (updates.branchPrefix !== undefined)
         s.projects[idx].branchPrefix = sanitizeBranchPrefix(updates.branchPrefix);
+      if (updates.branchTemplate !== undefined)
+        s.projects[idx].branchTemplate = updates.branchTemplate.trim() || undefined;
       if (updates.deleteBranchOnClose !== undefined)
         s.projects[idx].deleteBranchOnClose = updates.deleteBranchOnClose;
       if (updates.terminalBookmarks !== undefined)
@@ -62,6 +76,31 @@ export function updateProject(
   );
 }
 
+/** Change where a project's worktrees live, pointing tasks at any that were moved. */
+export async function setProjectWorktreeLocation(
+  projectId: string,
+  location: WorktreeLocation,
+  migrate: boolean,
+): Promise<WorktreeRelocation> {
+  const projectRoot = getProjectPath(projectId);
+  if (!projectRoot) throw new Error('Project not found');
+  const result = await invoke<WorktreeRelocation>(IPC.SetWorktreeLocation, {
+    projectRoot,
+    location,
+    migrate,
+  });
+  const movedTo = new Map(result.moved.map((m) => [m.from, m.to]));
+  setStore(
+    produce((s) => {
+      for (const task of Object.values(s.tasks)) {
+        const to = task.projectId === projectId ? movedTo.get(task.worktreePath) : undefined;
+        if (to) task.worktreePath = to;
+      }
+    }),
+  );
+  return result;
+}
+
 export function getProjectBranchPrefix(projectId: string): string {
   const raw = store.projects.find((p) => p.id === projectId)?.branchPrefix ?? 'task';
   return sanitizeBranchPrefix(raw);
@@ -86,15 +125,30 @@ export async function removeProjectWithTasks(projectId: string): Promise<void> {
   const hasRemainingTasks = taskIds.some((tid) => store.tasks[tid]?.projectId === projectId);
   if (hasRemainingTasks) return;
 
-  // Now remove the project itself
+  // Now remove the project itself, and the repo's backend settings unless
+  // another project still points at it
+  const projectRoot = getProjectPath(projectId);
   removeProject(projectId);
+  if (projectRoot && !store.projects.some((p) => p.path === projectRoot)) {
+    invoke(IPC.RemoveProject, { projectRoot }).catch(() => {});
+  }
 }
 
 export async function pickAndAddProject(): Promise<string | null> {
   const selected = await openDialog({ directory: true, multiple: false });
   if (!selected) return null;
-  const path = selected as string;
-  const segments = path.split('/');
-  const name = segments[segments.length - 1] || path;
-  return addProject(name, path);
+  let info: ProjectInfo;
+  try {
+    info = await invoke<ProjectInfo>(IPC.AddProject, { path: selected as string });
+  } catch (err) {
+    showNotification(String(err).replace(/^Error: /, ''));
+    return null;
+  }
+  // Picking a subdirectory, or a repo that's already registered, selects the existing project
+  const existing = store.projects.find((p) => p.path === info.root);
+  if (existing) {
+    setStore('lastProjectId', existing.id);
+    return existing.id;
+  }
+  return addProject(info.name, info.root);
 }
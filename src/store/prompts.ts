@@ -0,0 +1,34 @@
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { setStore } from './core';
+import type { PromptSnippet } from '../ipc/types';
+
+export async function loadPromptSnippets(): Promise<void> {
+  const snippets = await invoke<PromptSnippet[]>(IPC.ListPromptSnippets);
+  setStore('promptSnippets', snippets);
+}
+
+/** Add a snippet, or replace the one with the same id, and reload the library. */
+export async function savePromptSnippet(snippet: Partial<PromptSnippet>): Promise<void> {
+  await invoke(IPC.SavePromptSnippet, { prompt: snippet });
+  await loadPromptSnippets();
+}
+
+export async function deletePromptSnippet(id: string): Promise<void> {
+  await invoke(IPC.DeletePromptSnippet, { id });
+  await loadPromptSnippets();
+}
+
+/**
+ * Send a saved prompt to an agent; the backend fills its {{variables}} from
+ * the agent's task and `vars`.
+ */
+export async function sendPromptSnippet(
+  taskId: string,
+  agentId: string,
+  promptId: string,
+  vars: Record<string, string> = {},
+): Promise<void> {
+  const text = await invoke<string>(IPC.SendPrompt, { agentId, promptId, vars });
+  setStore('tasks', taskId, 'lastPrompt', text);
+}
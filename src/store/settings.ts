@@ -0,0 +1,67 @@
+import { produce } from 'solid-js/store';
+import { invoke } from '../lib/ipc';
+import { IPC } from '../../electron/ipc/channels';
+import { openDialog, saveDialog } from '../lib/dialog';
+import { DEFAULT_TERMINAL_FONT, isTerminalFont } from '../lib/fonts';
+import { setStore } from './core';
+import { loadAgents } from './agents';
+import { loadPromptSnippets } from './prompts';
+import { loadTaskMetadata } from './tasks';
+import type { NotificationSettings, Settings, WorkspaceImportResult } from '../ipc/types';
+
+export type SettingsPatch = Partial<Omit<Settings, 'version' | 'notifications'>> & {
+  notifications?: Partial<NotificationSettings>;
+};
+
+/** Mirror the backend settings the renderer reads into the store. */
+function applySettings(settings: Settings): void {
+  setStore(
+    produce((s) => {
+      s.terminalFont = isTerminalFont(settings.terminal_font)
+        ? settings.terminal_font
+        : DEFAULT_TERMINAL_FONT;
+      s.terminalScrollback = settings.terminal_scrollback;
+      s.defaultAgentId = settings.default_agent_id;
+      s.keepAgentsRunning = settings.keep_agents_running;
+      s.recordSessions = settings.record_sessions;
+    }),
+  );
+}
+
+export async function loadSettings(): Promise<Settings> {
+  const settings = await invoke<Settings>(IPC.GetSettings);
+  applySettings(settings);
+  return settings;
+}
+
+/** Validate and save a partial update in the backend; rejects on invalid values. */
+export async function updateSettings(patch: SettingsPatch): Promise<Settings> {
+  const settings = await invoke<Settings>(IPC.UpdateSettings, { settings: patch });
+  applySettings(settings);
+  return settings;
+}
+
+/** Follow settings changed elsewhere (e.g. by a command). Returns an unsubscribe function. */
+export function listenForSettingsChanges(): () => void {
+  return window.electron.ipcRenderer.on(IPC.SettingsChanged, (msg: unknown) =>
+    applySettings(msg as Settings),
+  );
+}
+
+/** Save settings, agents, templates and prompts to a file the user picks. False if cancelled. */
+export async function exportWorkspace(): Promise<boolean> {
+  const file = await saveDialog({ defaultPath: 'parallel-code-workspace.json' });
+  if (!file) return false;
+  await invoke(IPC.ExportWorkspace, { path: file });
+  return true;
+}
+
+/** Merge a workspace file into this setup and reload what changed. Null if cancelled. */
+export async function importWorkspace(): Promise<WorkspaceImportResult | null> {
+  const file = await openDialog({ directory: false, multiple: false });
+  if (typeof file !== 'string') return null;
+  const result = await invoke<WorkspaceImportResult>(IPC.ImportWorkspace, { path: file });
+  // Settings arrive through settings-changed
+  await Promise.all([loadAgents(), loadPromptSnippets(), loadTaskMetadata()]);
+  return result;
+}
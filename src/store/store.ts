@@ -11,16 +11,49 @@ export {
   pickAndAddProject,
   PASTEL_HUES,
 } from './projects';
-export { loadAgents, addAgentToTask, markAgentExited, restartAgent } from './agents';
+export {
+  loadAgents,
+  saveCustomAgent,
+  deleteCustomAgent,
+  saveAgentProfile,
+  deleteAgentProfile,
+  runAgentHeadless,
+  cancelHeadlessRun,
+  onAgentEvent,
+  addAgentToTask,
+  handoffTask,
+  markAgentExited,
+  restartAgent,
+} from './agents';
 export {
   createTask,
+  createTaskFromTemplate,
   createDirectTask,
   closeTask,
+  restoreArchivedTask,
+  forkTask,
   retryCloseTask,
   mergeTask,
+  linkTaskToProject,
+  setTaskDependencies,
+  listenForUnblockedTasks,
+  listenForTaskStatusChanges,
+  loadTaskMetadata,
+  updateTaskMetadata,
+  queryTasks,
+  queryTaskHistory,
+  queryEvents,
+  listTaskSessions,
+  listTaskAgents,
+  startReview,
+  listenForReviews,
   pushTask,
   updateTaskName,
   updateTaskNotes,
+  loadTaskNotes,
+  addChecklistItem,
+  toggleChecklistItem,
+  removeChecklistItem,
   sendPrompt,
   setLastPrompt,
   clearInitialPrompt,
@@ -35,6 +68,7 @@ export {
 } from './tasks';
 export {
   setActiveTask,
+  listenForNotificationClicks,
   setActiveAgent,
   navigateTask,
   navigateAgent,
@@ -63,7 +97,7 @@ export {
   setSidebarFocusedProjectId,
 } from './focus';
 export type { PanelId, PendingAction } from './types';
-export { saveState, loadState } from './persistence';
+export { saveState, loadState, restoreStateBackup } from './persistence';
 export {
   getFontScale,
   adjustFontScale,
@@ -77,6 +111,9 @@ export {
   setTerminalFont,
   setThemePreset,
   setAutoTrustFolders,
+  setAgentTaskNames,
+  setKeepAgentsRunning,
+  setRecordSessions,
   setInactiveColumnOpacity,
   setWindowState,
 } from './ui';
@@ -108,3 +145,17 @@ export {
   syncTerminalCounter,
 } from './terminals';
 export { startRemoteAccess, stopRemoteAccess, refreshRemoteStatus } from './remote';
+export {
+  loadSettings,
+  updateSettings,
+  listenForSettingsChanges,
+  exportWorkspace,
+  importWorkspace,
+} from './settings';
+export type { SettingsPatch } from './settings';
+export {
+  loadPromptSnippets,
+  savePromptSnippet,
+  deletePromptSnippet,
+  sendPromptSnippet,
+} from './prompts';
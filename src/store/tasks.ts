@@ -7,12 +7,37 @@ import { getProject, getProjectPath, getProjectBranchPrefix } from './projects';
 import { setPendingShellCommand } from '../lib/bookmarks';
 import { markAgentSpawned, clearAgentActivity, rescheduleTaskStatusPolling } from './taskStatus';
 import { recordMergedLines, recordTaskCompleted } from './completion';
-import type { AgentDef, CreateTaskResult, MergeResult } from '../ipc/types';
+import type {
+  AgentDef,
+  AppEvent,
+  ArchivedTask,
+  ChecklistItem,
+  CreateTaskResult,
+  EventFilter,
+  ForkTaskResult,
+  MergeResult,
+  RestoredTask,
+  ReviewResult,
+  SessionRecord,
+  TaskAgentSession,
+  TaskFilter,
+  TaskHistoryEntry,
+  TaskHistoryFilter,
+  TaskLifecycleStatus,
+  TaskMetadata,
+  TaskNotes,
+  TaskPriority,
+  TaskRecord,
+  TaskStatusChangedEvent,
+  TaskUnblockedEvent,
+  TemplateTaskResult,
+} from '../ipc/types';
 import { parseGitHubUrl, taskNameFromGitHubUrl } from '../lib/github-url';
-import type { Agent, Task } from './types';
+import type { Agent, LinkedWorktree, Task } from './types';
 
 const AGENT_WRITE_READY_TIMEOUT_MS = 8_000;
 const AGENT_WRITE_RETRY_MS = 50;
+const NOTES_SAVE_DELAY_MS = 500;
 
 function sleep(ms: number): Promise<void> {
   return new Promise((resolve) => setTimeout(resolve, ms));
@@ -42,6 +67,21 @@ async function writeToAgentWhenReady(agentId: string, data: string): Promise<voi
   throw lastErr ?? new Error(`Timed out waiting for agent ${agentId} to become writable`);
 }
 
+/**
+ * Wrap a project's setup command with start/finish markers so the setup phase
+ * stands out in the shell's scrollback. Windows shells get the bare command.
+ */
+function setupShellCommand(command: string): string {
+  if (navigator.userAgent.includes('Windows')) return command;
+  const quoted = `'${command.replace(/'/g, "'\\''")}'`;
+  const marker = (color: number, text: string) =>
+    `printf '\\033[1;${color}m==> %s\\033[0m\\n' ${text}`;
+  return (
+    `${marker(36, `"Setup: "${quoted}`)} && ${command} && ${marker(32, "'Setup complete'")}` +
+    ` || ${marker(31, "'Setup failed'")}`
+  );
+}
+
 export async function createTask(
   name: string,
   agentDef: AgentDef,
@@ -51,6 +91,9 @@ export async function createTask(
   branchPrefixOverride?: string,
   githubUrl?: string,
   skipPermissions?: boolean,
+  branchName?: string,
+  dependsOn?: string[],
+  agentProfileId?: string,
 ): Promise<string> {
   const projectRoot = getProjectPath(projectId);
   if (!projectRoot) throw new Error('Project not found');
@@ -61,8 +104,82 @@ export async function createTask(
     projectRoot,
     symlinkDirs,
     branchPrefix,
+    branchTemplate: getProject(projectId)?.branchTemplate,
+    branchName,
   });
 
+  return addCreatedTask(result, name, agentDef, projectId, {
+    initialPrompt,
+    githubUrl,
+    skipPermissions,
+    dependsOn,
+    agentProfileId,
+  });
+}
+
+/**
+ * Create a task from a saved template. The template's agent wins over
+ * `agentDef` when it's still available; `prompt` replaces the template's.
+ */
+export async function createTaskFromTemplate(
+  templateId: string,
+  name: string,
+  agentDef: AgentDef,
+  projectId: string,
+  opts: {
+    symlinkDirs?: string[];
+    prompt?: string;
+    branchPrefix?: string;
+    branchName?: string;
+    values?: Record<string, string>;
+    skipPermissions?: boolean;
+    dependsOn?: string[];
+    agentProfileId?: string;
+  } = {},
+): Promise<string> {
+  const projectRoot = getProjectPath(projectId);
+  if (!projectRoot) throw new Error('Project not found');
+
+  const result = await invoke<TemplateTaskResult>(IPC.CreateTaskFromTemplate, {
+    templateId,
+    params: {
+      name,
+      projectRoot,
+      symlinkDirs: opts.symlinkDirs ?? [],
+      branchPrefix: opts.branchPrefix ?? getProjectBranchPrefix(projectId),
+      branchTemplate: getProject(projectId)?.branchTemplate,
+      branchName: opts.branchName,
+      prompt: opts.prompt,
+      values: opts.values,
+    },
+  });
+  const templateAgent = store.availableAgents.find((a) => a.id === result.agent_id);
+  return addCreatedTask(result, name, templateAgent ?? agentDef, projectId, {
+    initialPrompt: result.prompt.trim() || undefined,
+    skipPermissions: opts.skipPermissions,
+    env: Object.keys(result.env).length > 0 ? result.env : undefined,
+    dependsOn: opts.dependsOn,
+    agentProfileId: opts.agentProfileId,
+  });
+}
+
+/** Add a task whose worktree was just created and start its agent. */
+function addCreatedTask(
+  result: CreateTaskResult,
+  name: string,
+  agentDef: AgentDef,
+  projectId: string,
+  opts: {
+    initialPrompt?: string;
+    githubUrl?: string;
+    skipPermissions?: boolean;
+    env?: Record<string, string>;
+    dependsOn?: string[];
+    agentProfileId?: string;
+  },
+): string {
+  const { initialPrompt, githubUrl, skipPermissions, env } = opts;
+  const dependsOn = opts.dependsOn?.length ? opts.dependsOn : undefined;
   const agentId = crypto.randomUUID();
   const task: Task = {
     id: result.id,
@@ -78,6 +195,16 @@ export async function createTask(
     skipPermissions: skipPermissions || undefined,
     githubUrl,
     savedInitialPrompt: initialPrompt || undefined,
+    env,
+    dependsOn,
+    // Held back until the backend confirms which prerequisites are still open
+    waitingOn: dependsOn,
+    // The backend's task-status-changed for this arrived before the task existed here
+    lifecycle: 'planning',
+    // A template may pick a different agent that lacks the profile
+    agentProfileId: agentDef.profiles?.some((p) => p.id === opts.agentProfileId)
+      ? opts.agentProfileId
+      : undefined,
   };
 
   const agent: Agent = {
@@ -89,6 +216,7 @@ export async function createTask(
     exitCode: null,
     signal: null,
     lastOutput: [],
+    exitReason: null,
     generation: 0,
   };
 
@@ -104,13 +232,119 @@ export async function createTask(
     }),
   );
 
-  // Mark as busy immediately; terminal output may arrive later.
-  markAgentSpawned(agentId);
+  // Run the project's setup step in its own shell so the agent isn't blocked by it
+  if (result.setup_command) {
+    spawnShellForTask(result.id, setupShellCommand(result.setup_command));
+  }
+
+  if (dependsOn) {
+    setTaskDependencies(result.id, dependsOn).catch((err) => {
+      console.error('Failed to set task dependencies:', err);
+      setStore('tasks', result.id, 'waitingOn', undefined);
+    });
+  } else {
+    // Mark as busy immediately; terminal output may arrive later.
+    markAgentSpawned(agentId);
+  }
   rescheduleTaskStatusPolling();
   updateWindowTitle(name);
   return result.id;
 }
 
+/**
+ * Make a task wait for others to land (merge or close) before its agent
+ * starts. An empty list starts it now.
+ */
+export async function setTaskDependencies(taskId: string, dependsOn: string[]): Promise<void> {
+  const { waiting_on } = await invoke<{ waiting_on: string[] }>(IPC.SetTaskDependencies, {
+    taskId,
+    dependsOn,
+  });
+  const task = store.tasks[taskId];
+  if (!task) return;
+  const wasWaiting = (task.waitingOn?.length ?? 0) > 0;
+  setStore('tasks', taskId, {
+    dependsOn: dependsOn.length > 0 ? dependsOn : undefined,
+    waitingOn: waiting_on.length > 0 ? waiting_on : undefined,
+  });
+  if (wasWaiting && waiting_on.length === 0 && task.agentIds[0]) {
+    markAgentSpawned(task.agentIds[0]);
+  }
+}
+
+/** Start agents whose prerequisite tasks have all landed. Returns an unsubscribe function. */
+export function listenForUnblockedTasks(): () => void {
+  return window.electron.ipcRenderer.on(IPC.TaskUnblocked, (msg: unknown) => {
+    const { task_id } = msg as TaskUnblockedEvent;
+    const task = store.tasks[task_id];
+    if (!task?.waitingOn?.length) return;
+    setStore('tasks', task_id, 'waitingOn', undefined);
+    if (task.agentIds[0]) markAgentSpawned(task.agentIds[0]);
+  });
+}
+
+/**
+ * Mirror the backend's task statuses into the store: load them once, then
+ * follow `task-status-changed`. Returns an unsubscribe function.
+ */
+export function listenForTaskStatusChanges(): () => void {
+  invoke<Record<string, TaskLifecycleStatus>>(IPC.GetTaskStatuses)
+    .then((statuses) => {
+      for (const [taskId, status] of Object.entries(statuses)) {
+        if (store.tasks[taskId]) setStore('tasks', taskId, 'lifecycle', status);
+      }
+    })
+    .catch((err) => console.error('Failed to load task statuses:', err));
+  return window.electron.ipcRenderer.on(IPC.TaskStatusChanged, (msg: unknown) => {
+    const { task_id, to } = msg as TaskStatusChangedEvent;
+    if (store.tasks[task_id]) setStore('tasks', task_id, 'lifecycle', to);
+  });
+}
+
+/** The task's sessions as the backend sees them, e.g. after a reload. */
+export function listTaskAgents(taskId: string): Promise<TaskAgentSession[]> {
+  return invoke<TaskAgentSession[]>(IPC.ListTaskAgents, { taskId });
+}
+
+/** Tasks matching `filter`, filtered and sorted by the backend. */
+export function queryTasks(filter: TaskFilter = {}): Promise<TaskRecord[]> {
+  return invoke<TaskRecord[]>(IPC.QueryTasks, { filter });
+}
+
+/** Open and, with `include_closed`, closed tasks from the backend's task history. */
+export function queryTaskHistory(filter: TaskHistoryFilter = {}): Promise<TaskHistoryEntry[]> {
+  return invoke<TaskHistoryEntry[]>(IPC.QueryTaskHistory, { filter });
+}
+
+/** Logged backend events, newest first; pass `task_id` for a task's timeline. */
+export function queryEvents(filter: EventFilter = {}, since = 0, limit = 200): Promise<AppEvent[]> {
+  return invoke<AppEvent[]>(IPC.QueryEvents, { filter, since, limit });
+}
+
+/** Every agent and shell session a task has run, newest first. */
+export function listTaskSessions(taskId: string): Promise<SessionRecord[]> {
+  return invoke<SessionRecord[]>(IPC.ListTaskSessions, { taskId });
+}
+
+/** Load labels and priorities for the tasks in the store. */
+export async function loadTaskMetadata(): Promise<void> {
+  const records = await queryTasks();
+  for (const r of records) {
+    if (!store.tasks[r.id]) continue;
+    setStore('tasks', r.id, { labels: r.labels, priority: r.priority });
+  }
+}
+
+export async function updateTaskMetadata(
+  taskId: string,
+  patch: { labels?: string[]; priority?: TaskPriority },
+): Promise<void> {
+  const meta = await invoke<TaskMetadata>(IPC.UpdateTaskMetadata, { taskId, ...patch });
+  if (store.tasks[taskId]) {
+    setStore('tasks', taskId, { labels: meta.labels, priority: meta.priority });
+  }
+}
+
 export async function createDirectTask(
   name: string,
   agentDef: AgentDef,
@@ -119,6 +353,7 @@ export async function createDirectTask(
   initialPrompt?: string,
   githubUrl?: string,
   skipPermissions?: boolean,
+  agentProfileId?: string,
 ): Promise<string> {
   if (hasDirectModeTask(projectId)) {
     throw new Error('A direct-mode task already exists for this project');
@@ -144,6 +379,7 @@ export async function createDirectTask(
     directMode: true,
     skipPermissions: skipPermissions || undefined,
     githubUrl,
+    agentProfileId,
   };
 
   const agent: Agent = {
@@ -155,6 +391,7 @@ export async function createDirectTask(
     exitCode: null,
     signal: null,
     lastOutput: [],
+    exitReason: null,
     generation: 0,
   };
 
@@ -176,7 +413,12 @@ export async function createDirectTask(
   return id;
 }
 
-export async function closeTask(taskId: string): Promise<void> {
+/**
+ * Stop a task's agents and remove its worktree. With `archive`, the branch,
+ * final diff, recordings and plan files are kept in app data first (see
+ * restoreArchivedTask).
+ */
+export async function closeTask(taskId: string, opts: { archive?: boolean } = {}): Promise<void> {
   const task = store.tasks[taskId];
   if (!task || task.closingStatus === 'closing' || task.closingStatus === 'removing') return;
 
@@ -199,10 +441,23 @@ export async function closeTask(taskId: string): Promise<void> {
       await invoke(IPC.KillAgent, { agentId: shellId }).catch(console.error);
     }
 
-    // Skip git cleanup for direct mode (no worktree/branch to remove)
-    if (!task.directMode) {
+    // Direct mode has no worktree/branch to remove; the task just ends
+    if (task.directMode) {
+      await invoke(IPC.SetTaskStatus, { taskId, status: 'done' }).catch(() => {});
+    } else if (opts.archive) {
+      await invoke<ArchivedTask>(IPC.ArchiveTask, {
+        taskId,
+        name: task.name,
+        agentIds: [...agentIds, ...shellAgentIds],
+        agentId: store.agents[agentIds[0]]?.def.id ?? null,
+        branchName,
+        worktreePath: task.worktreePath,
+        projectRoot,
+      });
+    } else {
       // Remove worktree + branch
       await invoke(IPC.DeleteTask, {
+        taskId,
         agentIds: [...agentIds, ...shellAgentIds],
         branchName,
         deleteBranch,
@@ -210,6 +465,17 @@ export async function closeTask(taskId: string): Promise<void> {
       });
     }
 
+    // Worktrees the task owns in other projects
+    for (const linked of task.linkedWorktrees ?? []) {
+      const linkedRoot = getProjectPath(linked.projectId);
+      if (!linkedRoot) continue;
+      await invoke(IPC.RemoveTaskWorktree, {
+        projectRoot: linkedRoot,
+        branchName: linked.branchName,
+        deleteBranch: getProject(linked.projectId)?.deleteBranchOnClose ?? true,
+      });
+    }
+
     // Backend cleanup succeeded — remove from UI
     removeTaskFromStore(taskId, [...agentIds, ...shellAgentIds]);
   } catch (err) {
@@ -220,6 +486,65 @@ export async function closeTask(taskId: string): Promise<void> {
   }
 }
 
+/** Check an archived task out again and reopen it with a fresh agent. */
+export async function restoreArchivedTask(archived: ArchivedTask): Promise<RestoredTask> {
+  const project = store.projects.find((p) => p.path === archived.project_root);
+  if (!project) throw new Error(`Project not found: ${archived.project_root}`);
+  const agentDef =
+    store.availableAgents.find((a) => a.id === archived.agent_id) ??
+    store.availableAgents.find((a) => a.id === store.lastAgentId) ??
+    store.availableAgents[0];
+  if (!agentDef) throw new Error('No agent available to restore the task with');
+
+  const restored = await invoke<RestoredTask>(IPC.RestoreTask, { taskId: archived.id });
+  addCreatedTask(
+    {
+      id: restored.id,
+      branch_name: restored.branch_name,
+      worktree_path: restored.worktree_path,
+      setup_command: await invoke<string | null>(IPC.GetWorktreeSetupCommand, {
+        projectRoot: project.path,
+      }).catch(() => null),
+    },
+    restored.name,
+    agentDef,
+    project.id,
+    {},
+  );
+  return restored;
+}
+
+/**
+ * Start a new task on `newBranch` from another task's current state, its
+ * uncommitted changes included, with a fresh agent of the same kind.
+ */
+export async function forkTask(taskId: string, newBranch: string): Promise<string> {
+  const source = store.tasks[taskId];
+  if (!source) throw new Error('Task not found');
+  const projectRoot = getProjectPath(source.projectId);
+  if (!projectRoot) throw new Error('Project not found');
+  const agentDef = store.agents[source.agentIds[0]]?.def ?? store.availableAgents[0];
+  if (!agentDef) throw new Error('No agent available to fork the task with');
+
+  const name = `${source.name} (fork)`;
+  const result = await invoke<ForkTaskResult>(IPC.ForkTask, {
+    taskId,
+    name,
+    projectRoot,
+    worktreePath: source.worktreePath,
+    newBranch,
+  });
+  const id = addCreatedTask(result, name, agentDef, source.projectId, {
+    githubUrl: source.githubUrl,
+    skipPermissions: source.skipPermissions,
+    env: source.env,
+    agentProfileId: source.agentProfileId,
+  });
+  // The backend copied these over with the fork
+  setStore('tasks', id, { labels: source.labels, priority: source.priority });
+  return id;
+}
+
 export function retryCloseTask(taskId: string): void {
   setStore('tasks', taskId, 'closingStatus', undefined);
   setStore('tasks', taskId, 'closingError', undefined);
@@ -306,15 +631,33 @@ export async function mergeTask(
     }
   }
 
-  // Merge branch into main. Cleanup is optional.
-  const mergeResult = await invoke<MergeResult>(IPC.MergeTask, {
-    projectRoot,
-    branchName,
-    squash: options?.squash ?? false,
-    message: options?.message,
-    cleanup,
+  const linked = (task.linkedWorktrees ?? []).flatMap((l) => {
+    const root = getProjectPath(l.projectId);
+    return root ? [{ projectRoot: root, branchName: l.branchName }] : [];
   });
-  recordMergedLines(mergeResult.lines_added, mergeResult.lines_removed);
+
+  if (linked.length > 0) {
+    // Multi-repo task: land every branch or none
+    const results = await invoke<MergeResult[]>(IPC.MergeLinkedTasks, {
+      taskId,
+      entries: [{ projectRoot, branchName }, ...linked],
+      squash: options?.squash ?? false,
+      message: options?.message,
+      cleanup,
+    });
+    for (const r of results) recordMergedLines(r.lines_added, r.lines_removed);
+  } else {
+    // Merge branch into main. Cleanup is optional.
+    const mergeResult = await invoke<MergeResult>(IPC.MergeTask, {
+      taskId,
+      projectRoot,
+      branchName,
+      squash: options?.squash ?? false,
+      message: options?.message,
+      cleanup,
+    });
+    recordMergedLines(mergeResult.lines_added, mergeResult.lines_removed);
+  }
 
   if (cleanup) {
     // Remove task UI only when branch/worktree were cleaned up.
@@ -322,6 +665,34 @@ export async function mergeTask(
   }
 }
 
+/**
+ * Give a task a worktree in another project, on a branch of the same name,
+ * for changes that span repositories. Returns the new worktree path.
+ */
+export async function linkTaskToProject(taskId: string, projectId: string): Promise<string> {
+  const task = store.tasks[taskId];
+  if (!task || task.directMode) throw new Error('Only worktree tasks can span projects');
+  const linkedIds = (task.linkedWorktrees ?? []).map((l) => l.projectId);
+  if (projectId === task.projectId || linkedIds.includes(projectId)) {
+    throw new Error('Task already has a worktree in this project');
+  }
+  const projectRoot = getProjectPath(projectId);
+  if (!projectRoot) throw new Error('Project not found');
+
+  const result = await invoke<{ path: string; branch: string }>(IPC.CreateTaskWorktree, {
+    projectRoot,
+    branchName: task.branchName,
+    symlinkDirs: [],
+  });
+  const linked: LinkedWorktree = {
+    projectId,
+    branchName: result.branch,
+    worktreePath: result.path,
+  };
+  setStore('tasks', taskId, 'linkedWorktrees', [...(task.linkedWorktrees ?? []), linked]);
+  return result.path;
+}
+
 export async function pushTask(taskId: string): Promise<void> {
   const task = store.tasks[taskId];
   if (!task || task.directMode) return;
@@ -332,6 +703,7 @@ export async function pushTask(taskId: string): Promise<void> {
   await invoke(IPC.PushTask, {
     projectRoot,
     branchName: task.branchName,
+    taskId,
   });
 }
 
@@ -342,8 +714,101 @@ export function updateTaskName(taskId: string, name: string): void {
   }
 }
 
+const notesSaveTimers = new Map<string, ReturnType<typeof setTimeout>>();
+
+function saveTaskNotes(taskId: string): void {
+  clearTimeout(notesSaveTimers.get(taskId));
+  notesSaveTimers.delete(taskId);
+  const task = store.tasks[taskId];
+  if (!task) return;
+  invoke(IPC.UpdateTaskNotes, {
+    taskId,
+    notes: task.notes,
+    checklist: task.checklist ?? [],
+  }).catch((err) => console.error('Failed to save task notes:', err));
+}
+
 export function updateTaskNotes(taskId: string, notes: string): void {
   setStore('tasks', taskId, 'notes', notes);
+  clearTimeout(notesSaveTimers.get(taskId));
+  notesSaveTimers.set(taskId, setTimeout(() => saveTaskNotes(taskId), NOTES_SAVE_DELAY_MS));
+}
+
+/**
+ * Load notes and checklists from the backend. Notes that only exist in the
+ * saved app state (from before notes were stored per task) are moved over.
+ */
+export async function loadTaskNotes(): Promise<void> {
+  await Promise.all(
+    store.taskOrder.map(async (taskId) => {
+      if (!store.tasks[taskId]) return;
+      const saved = await invoke<TaskNotes>(IPC.GetTaskNotes, { taskId });
+      if (!store.tasks[taskId]) return;
+      if (saved.updated_at === 0) {
+        if (store.tasks[taskId].notes) saveTaskNotes(taskId);
+        return;
+      }
+      setStore('tasks', taskId, { notes: saved.notes, checklist: saved.checklist });
+    }),
+  );
+}
+
+/** Have `reviewerAgentId` review the task's changes headlessly; see listenForReviews. */
+export async function startReview(taskId: string, reviewerAgentId: string): Promise<void> {
+  const task = store.tasks[taskId];
+  if (!task || task.reviewRunId) return;
+  const { run_id } = await invoke<{ run_id: string }>(IPC.StartReviewAgent, {
+    taskId,
+    reviewerAgentId,
+    worktreePath: task.worktreePath,
+    env: task.env,
+  });
+  setStore('tasks', taskId, 'reviewRunId', run_id);
+}
+
+/**
+ * Store finished reviews on their task and add the findings to its review
+ * checklist. Returns an unsubscribe function.
+ */
+export function listenForReviews(): () => void {
+  return window.electron.ipcRenderer.on(IPC.ReviewCompleted, (msg: unknown) => {
+    const review = msg as ReviewResult;
+    const task = store.tasks[review.task_id];
+    if (!task) return;
+    setStore('tasks', review.task_id, { review, reviewRunId: undefined });
+    for (const finding of review.findings) {
+      const line = finding.line ? `:${finding.line}` : '';
+      const where = finding.file ? `${finding.file}${line} ` : '';
+      addChecklistItem(review.task_id, `${where}${finding.message}`);
+    }
+  });
+}
+
+function setChecklist(taskId: string, checklist: ChecklistItem[]): void {
+  setStore('tasks', taskId, 'checklist', checklist);
+  saveTaskNotes(taskId);
+}
+
+export function addChecklistItem(taskId: string, text: string): void {
+  const task = store.tasks[taskId];
+  if (!task || !text.trim()) return;
+  const item = { id: crypto.randomUUID(), text: text.trim(), done: false };
+  setChecklist(taskId, [...(task.checklist ?? []), item]);
+}
+
+export function toggleChecklistItem(taskId: string, itemId: string): void {
+  const task = store.tasks[taskId];
+  if (!task) return;
+  setChecklist(
+    taskId,
+    (task.checklist ?? []).map((i) => (i.id === itemId ? { ...i, done: !i.done } : i)),
+  );
+}
+
+export function removeChecklistItem(taskId: string, itemId: string): void {
+  const task = store.tasks[taskId];
+  if (!task) return;
+  setChecklist(taskId, (task.checklist ?? []).filter((i) => i.id !== itemId));
 }
 
 export async function sendPrompt(taskId: string, agentId: string, text: string): Promise<void> {
@@ -1,4 +1,13 @@
-import type { AgentDef, WorktreeStatus } from '../ipc/types';
+import type {
+  AgentDef,
+  ChecklistItem,
+  ExitReason,
+  PromptSnippet,
+  ReviewResult,
+  TaskLifecycleStatus,
+  TaskPriority,
+  WorktreeStatus,
+} from '../ipc/types';
 import type { TerminalFont } from '../lib/fonts';
 import type { LookPreset } from '../lib/look';
 
@@ -13,6 +22,7 @@ export interface Project {
   path: string;
   color: string;
   branchPrefix?: string; // default "task" if unset
+  branchTemplate?: string; // default "{prefix}/{slug}" if unset
   deleteBranchOnClose?: boolean; // default true if unset
   terminalBookmarks?: TerminalBookmark[];
 }
@@ -26,7 +36,10 @@ export interface Agent {
   exitCode: number | null;
   signal: string | null;
   lastOutput: string[];
+  exitReason: ExitReason | null;
   generation: number;
+  /** Reconnect to a session host left running by the previous app instance. */
+  reattach?: boolean;
 }
 
 export interface Task {
@@ -47,6 +60,23 @@ export interface Task {
   directMode?: boolean;
   skipPermissions?: boolean;
   githubUrl?: string;
+  linkedWorktrees?: LinkedWorktree[]; // worktrees in other projects for multi-repo tasks
+  env?: Record<string, string>; // extra agent environment, e.g. from a task template
+  dependsOn?: string[]; // tasks that must land before this one's agent starts
+  waitingOn?: string[]; // the subset of dependsOn that hasn't landed yet
+  lifecycle?: TaskLifecycleStatus; // owned by the backend, kept in sync by task-status-changed
+  labels?: string[]; // owned by the backend, see updateTaskMetadata
+  priority?: TaskPriority;
+  checklist?: ChecklistItem[]; // review checklist, stored by the backend with the notes
+  agentProfileId?: string; // profile of the task's agent def, applied by the backend on spawn
+  reviewRunId?: string; // headless reviewer currently running, see startReview
+  review?: ReviewResult; // last automatic review; not persisted
+}
+
+export interface LinkedWorktree {
+  projectId: string;
+  branchName: string;
+  worktreePath: string;
 }
 
 export interface Terminal {
@@ -70,6 +100,11 @@ export interface PersistedTask {
   skipPermissions?: boolean;
   githubUrl?: string;
   savedInitialPrompt?: string;
+  linkedWorktrees?: LinkedWorktree[];
+  env?: Record<string, string>;
+  dependsOn?: string[];
+  waitingOn?: string[];
+  agentProfileId?: string;
 }
 
 export interface PersistedTerminal {
@@ -86,6 +121,7 @@ export interface PersistedWindowState {
 }
 
 export interface PersistedState {
+  schemaVersion?: number;
   projects: Project[];
   lastProjectId: string | null;
   lastAgentId: string | null;
@@ -101,10 +137,10 @@ export interface PersistedState {
   completedTaskCount?: number;
   mergedLinesAdded?: number;
   mergedLinesRemoved?: number;
-  terminalFont?: TerminalFont;
   themePreset?: LookPreset;
   windowState?: PersistedWindowState;
   autoTrustFolders?: boolean;
+  agentTaskNames?: boolean;
   inactiveColumnOpacity?: number;
 }
 
@@ -138,6 +174,7 @@ export interface AppStore {
   activeTaskId: string | null;
   activeAgentId: string | null;
   availableAgents: AgentDef[];
+  promptSnippets: PromptSnippet[];
   showNewTaskDialog: boolean;
   sidebarVisible: boolean;
   fontScales: Record<string, number>;
@@ -159,9 +196,17 @@ export interface AppStore {
   mergedLinesAdded: number;
   mergedLinesRemoved: number;
   terminalFont: TerminalFont;
+  terminalScrollback: number;
+  /** Agent preselected for new tasks; null uses the last one picked. */
+  defaultAgentId: string | null;
   themePreset: LookPreset;
   windowState: PersistedWindowState | null;
   autoTrustFolders: boolean;
+  agentTaskNames: boolean;
+  /** Spawn agents in session hosts that outlive the app. */
+  keepAgentsRunning: boolean;
+  /** Record the output of new agents to asciicast files. */
+  recordSessions: boolean;
   inactiveColumnOpacity: number;
   newTaskDropUrl: string | null;
   remoteAccess: RemoteAccess;
@@ -1,5 +1,6 @@
 import { produce } from 'solid-js/store';
 import { store, setStore } from './core';
+import { updateSettings } from './settings';
 import type { TerminalFont } from '../lib/fonts';
 import type { LookPreset } from '../lib/look';
 import type { PersistedWindowState } from './types';
@@ -73,6 +74,9 @@ export function toggleSidebar(): void {
 
 export function setTerminalFont(terminalFont: TerminalFont): void {
   setStore('terminalFont', terminalFont);
+  updateSettings({ terminal_font: terminalFont }).catch((err) =>
+    console.error('Failed to save terminal font:', err),
+  );
 }
 
 export function setThemePreset(themePreset: LookPreset): void {
@@ -83,6 +87,24 @@ export function setAutoTrustFolders(autoTrustFolders: boolean): void {
   setStore('autoTrustFolders', autoTrustFolders);
 }
 
+export function setAgentTaskNames(agentTaskNames: boolean): void {
+  setStore('agentTaskNames', agentTaskNames);
+}
+
+export function setKeepAgentsRunning(keepAgentsRunning: boolean): void {
+  setStore('keepAgentsRunning', keepAgentsRunning);
+  updateSettings({ keep_agents_running: keepAgentsRunning }).catch((err) =>
+    console.error('Failed to save keep-agents-running setting:', err),
+  );
+}
+
+export function setRecordSessions(recordSessions: boolean): void {
+  setStore('recordSessions', recordSessions);
+  updateSettings({ record_sessions: recordSessions }).catch((err) =>
+    console.error('Failed to save record-sessions setting:', err),
+  );
+}
+
 export function setInactiveColumnOpacity(opacity: number): void {
   setStore('inactiveColumnOpacity', Math.round(Math.max(0.3, Math.min(1.0, opacity)) * 100) / 100);
 }